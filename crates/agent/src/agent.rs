@@ -6,6 +6,7 @@ mod native_agent_server;
 pub mod outline;
 mod templates;
 mod thread;
+mod thread_export;
 mod tool_schema;
 mod tools;
 
@@ -17,6 +18,7 @@ pub use history_store::*;
 pub use native_agent_server::NativeAgentServer;
 pub use templates::*;
 pub use thread::*;
+pub use thread_export::*;
 pub use tools::*;
 
 use acp_thread::{AcpThread, AgentModelSelector};
@@ -384,7 +386,8 @@ impl NativeAgent {
         Ok(())
     }
 
-    fn build_project_context(
+    /// Builds the context describing the project's worktrees and rules for the system prompt.
+    pub fn build_project_context(
         project: &Entity<Project>,
         prompt_store: Option<&Entity<PromptStore>>,
         cx: &mut App,
@@ -55,6 +55,21 @@ pub struct DbThread {
 impl DbThread {
     pub const VERSION: &'static str = "0.3.0";
 
+    /// Serializes the thread along with the version of its format.
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct SerializedThread<'a> {
+            #[serde(flatten)]
+            thread: &'a DbThread,
+            version: &'static str,
+        }
+
+        Ok(serde_json::to_string(&SerializedThread {
+            thread: self,
+            version: Self::VERSION,
+        })?)
+    }
+
     pub fn from_json(json: &[u8]) -> Result<Self> {
         let saved_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
         match saved_thread_json.get("version") {
@@ -319,19 +334,9 @@ impl ThreadsDatabase {
     ) -> Result<()> {
         const COMPRESSION_LEVEL: i32 = 3;
 
-        #[derive(Serialize)]
-        struct SerializedThread {
-            #[serde(flatten)]
-            thread: DbThread,
-            version: &'static str,
-        }
-
         let title = thread.title.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
-        let json_data = serde_json::to_string(&SerializedThread {
-            thread,
-            version: DbThread::VERSION,
-        })?;
+        let json_data = thread.to_json()?;
 
         let connection = connection.lock();
 
@@ -188,6 +188,26 @@ impl HistoryStore {
         })
     }
 
+    /// Saves an imported thread to the database under a new ID, so that it can be resumed.
+    pub fn import_thread(
+        &mut self,
+        thread: DbThread,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DbThreadMetadata>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let metadata = DbThreadMetadata {
+                id: acp::SessionId(uuid::Uuid::new_v4().to_string().into()),
+                title: thread.title.clone(),
+                updated_at: thread.updated_at,
+            };
+            database.save_thread(metadata.id.clone(), thread).await?;
+            this.update(cx, |this, cx| this.reload(cx))?;
+            Ok(metadata)
+        })
+    }
+
     pub fn delete_text_thread(
         &mut self,
         path: Arc<Path>,
@@ -425,6 +425,23 @@ impl AgentMessage {
                 }
             }
 
+            if let Some(diff) = tool_result
+                .output
+                .as_ref()
+                .and_then(|output| output.get("diff")?.as_str())
+                .filter(|diff| !diff.is_empty())
+            {
+                writeln!(
+                    markdown,
+                    "**Diff**:\n\n{}\n",
+                    MarkdownCodeBlock {
+                        tag: "diff",
+                        text: diff
+                    }
+                )
+                .ok();
+            }
+
             if let Some(output) = tool_result.output.as_ref() {
                 writeln!(
                     markdown,
@@ -0,0 +1,214 @@
+use crate::{DbThread, Message};
+use agent_client_protocol as acp;
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use language_model::TokenUsage;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// A thread exported to share it outside of Zed, for example to hand an agent session over to a
+/// teammate or to attach it to a bug report.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThreadExport {
+    /// The version of the export format, as opposed to the version of the thread format, which
+    /// is stored in the thread itself.
+    pub version: u32,
+    pub id: acp::SessionId,
+    pub exported_at: DateTime<Utc>,
+    pub thread: serde_json::Value,
+}
+
+impl ThreadExport {
+    pub const VERSION: u32 = 1;
+
+    pub fn new(id: acp::SessionId, thread: &DbThread) -> Result<Self> {
+        Ok(Self {
+            version: Self::VERSION,
+            id,
+            exported_at: Utc::now(),
+            thread: serde_json::from_str(&thread.to_json()?)?,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parses an exported thread, upgrading it to the current thread format.
+    pub fn from_json(json: &[u8]) -> Result<DbThread> {
+        let export = serde_json::from_slice::<Self>(json).context("invalid thread export")?;
+        anyhow::ensure!(
+            export.version <= Self::VERSION,
+            "the thread was exported by a newer version of Zed (export format {})",
+            export.version
+        );
+        DbThread::from_json(&serde_json::to_vec(&export.thread)?)
+    }
+}
+
+/// Renders a thread as Markdown, including its tool calls, diffs and token usage.
+pub fn thread_to_markdown(thread: &DbThread) -> String {
+    let mut markdown = String::new();
+    let title = if thread.title.is_empty() {
+        "Untitled Thread"
+    } else {
+        thread.title.as_ref()
+    };
+    writeln!(markdown, "# {title}\n").ok();
+    if let Some(model) = &thread.model {
+        writeln!(markdown, "- **Model**: {}/{}", model.provider, model.model).ok();
+    }
+    if let Some(profile) = &thread.profile {
+        writeln!(markdown, "- **Profile**: {}", profile.as_str()).ok();
+    }
+    writeln!(
+        markdown,
+        "- **Updated**: {}",
+        thread.updated_at.to_rfc3339()
+    )
+    .ok();
+    writeln!(
+        markdown,
+        "- **Token Usage**: {}",
+        format_token_usage(&thread.cumulative_token_usage)
+    )
+    .ok();
+    if let Some(summary) = &thread.detailed_summary {
+        writeln!(markdown, "\n{summary}").ok();
+    }
+
+    for message in &thread.messages {
+        markdown.push('\n');
+        markdown.push_str(&message.to_markdown());
+        if let Message::User(message) = message
+            && let Some(usage) = thread.request_token_usage.get(&message.id)
+        {
+            writeln!(markdown, "\n_Token Usage: {}_", format_token_usage(usage)).ok();
+        }
+    }
+
+    markdown
+}
+
+fn format_token_usage(usage: &TokenUsage) -> String {
+    let mut text = format!(
+        "{} input, {} output",
+        usage.input_tokens, usage.output_tokens
+    );
+    if usage.cache_read_input_tokens > 0 || usage.cache_creation_input_tokens > 0 {
+        write!(
+            text,
+            ", {} cache read, {} cache write",
+            usage.cache_read_input_tokens, usage.cache_creation_input_tokens
+        )
+        .ok();
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AgentMessage, AgentMessageContent, DbLanguageModel, UserMessage, UserMessageContent,
+    };
+    use acp_thread::UserMessageId;
+    use collections::{HashMap, IndexMap};
+    use language_model::{
+        LanguageModelToolResult, LanguageModelToolResultContent, LanguageModelToolUse,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn test_thread() -> DbThread {
+        let user_message_id = UserMessageId::new();
+        let tool_use_id = language_model::LanguageModelToolUseId::from("tool_1");
+        let mut tool_results = IndexMap::default();
+        tool_results.insert(
+            tool_use_id.clone(),
+            LanguageModelToolResult {
+                tool_use_id: tool_use_id.clone(),
+                tool_name: "edit_file".into(),
+                is_error: false,
+                content: LanguageModelToolResultContent::Text("Edited src/main.rs".into()),
+                output: Some(json!({"diff": "-old\n+new"})),
+            },
+        );
+        let mut request_token_usage = HashMap::default();
+        request_token_usage.insert(
+            user_message_id.clone(),
+            TokenUsage {
+                input_tokens: 100,
+                output_tokens: 20,
+                ..Default::default()
+            },
+        );
+
+        DbThread {
+            title: "Fix main".into(),
+            messages: vec![
+                Message::User(UserMessage {
+                    id: user_message_id,
+                    content: vec![UserMessageContent::Text("Fix main".into())],
+                }),
+                Message::Agent(AgentMessage {
+                    content: vec![AgentMessageContent::ToolUse(LanguageModelToolUse {
+                        id: tool_use_id,
+                        name: "edit_file".into(),
+                        raw_input: "{}".into(),
+                        input: json!({}),
+                        is_input_complete: true,
+                    })],
+                    tool_results,
+                }),
+            ],
+            updated_at: DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            detailed_summary: None,
+            initial_project_snapshot: None,
+            cumulative_token_usage: TokenUsage {
+                input_tokens: 100,
+                output_tokens: 20,
+                ..Default::default()
+            },
+            request_token_usage,
+            model: Some(DbLanguageModel {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+            }),
+            completion_mode: None,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn test_export_json_round_trip() {
+        let thread = test_thread();
+        let export = ThreadExport::new(acp::SessionId("thread-1".into()), &thread).unwrap();
+        assert_eq!(export.thread["version"], json!(DbThread::VERSION));
+
+        let imported = ThreadExport::from_json(export.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(imported.title, thread.title);
+        assert_eq!(imported.messages, thread.messages);
+        assert_eq!(imported.request_token_usage, thread.request_token_usage);
+        assert_eq!(
+            imported.cumulative_token_usage,
+            thread.cumulative_token_usage
+        );
+
+        let mut newer_export = serde_json::to_value(&export).unwrap();
+        newer_export["version"] = json!(ThreadExport::VERSION + 1);
+        assert!(ThreadExport::from_json(newer_export.to_string().as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        let markdown = thread_to_markdown(&test_thread());
+        assert!(markdown.starts_with("# Fix main\n\n- **Model**: anthropic/claude-sonnet-4\n"));
+        assert!(markdown.contains("- **Token Usage**: 100 input, 20 output\n"));
+        assert!(markdown.contains("## User\n\nFix main\n\n_Token Usage: 100 input, 20 output_\n"));
+        assert!(markdown.contains("**Tool Use**: edit_file (ID: tool_1)\n"));
+        assert!(markdown.contains("**Diff**:\n\n```diff\n-old\n+new\n```\n"));
+    }
+}
@@ -119,6 +119,7 @@ impl ContextServerRegistry {
                     }
                 }
             }
+            _ => {}
         }
     }
 }
@@ -0,0 +1,345 @@
+mod approval;
+mod environment;
+mod output;
+
+use acp_thread::UserMessageId;
+use agent::{
+    ContextServerRegistry, NativeAgent, Templates, Thread, ThreadEvent, UserMessageContent,
+};
+use agent_client_protocol as acp;
+use agent_settings::{AgentProfileId, AgentSettings};
+use anyhow::{Context as _, Result};
+use approval::ToolApproval;
+use clap::Parser;
+use collections::HashMap;
+use environment::CliThreadEnvironment;
+use futures::{StreamExt as _, future};
+use gpui::{App, AppContext as _, Application, AsyncApp, UpdateGlobal as _};
+use headless_app::HeadlessAppState;
+use language_model::LanguageModelRegistry;
+use output::OutputEvent;
+use project::Project;
+use reqwest_client::ReqwestClient;
+use settings::{Settings as _, SettingsStore};
+use std::io::Read as _;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Runs an agent thread to completion without any UI, printing its events to stdout as JSON lines.
+///
+/// Exits with 0 when the agent ended its turn, 2 when it stopped for another reason (such as
+/// reaching the token limit or refusing), and 1 when the run failed.
+#[derive(Parser, Debug)]
+#[command(name = "zed --agent")]
+struct RunArgs {
+    /// The prompt to send to the agent. Read from stdin when omitted.
+    #[arg(long)]
+    prompt: Option<String>,
+    /// The directory of the project the agent works in.
+    #[arg(long, default_value = ".")]
+    project: PathBuf,
+    /// provider/model to use, instead of the `agent.default_model` setting.
+    #[arg(long)]
+    model: Option<String>,
+    /// The agent profile, which determines the available tools, instead of the
+    /// `agent.default_profile` setting.
+    #[arg(long)]
+    profile: Option<String>,
+    /// Approves the tool calls that the agent asks permission for, by tool name. Can be repeated,
+    /// and a trailing `*` matches any tool name with that prefix. Other tool calls are denied.
+    #[arg(long = "allow", value_name = "TOOL")]
+    allow: Vec<String>,
+    /// A settings file to use instead of the user's settings file.
+    #[arg(long)]
+    settings: Option<PathBuf>,
+}
+
+/// Runs `zed --agent <args>`, and exits once the thread completes.
+pub fn main(args: Vec<String>) {
+    let mut args = RunArgs::parse_from(std::iter::once("zed --agent".to_string()).chain(args));
+    zlog::init();
+    zlog::init_output_stderr();
+
+    let prompt = match args.prompt.take() {
+        Some(prompt) => prompt,
+        None => {
+            let mut prompt = String::new();
+            if let Err(error) = std::io::stdin().read_to_string(&mut prompt) {
+                eprintln!("Failed to read the prompt from stdin: {error}");
+                std::process::exit(1);
+            }
+            prompt
+        }
+    };
+    if prompt.trim().is_empty() {
+        eprintln!("No prompt given, pass one with --prompt or on stdin");
+        std::process::exit(1);
+    }
+
+    let http_client = Arc::new(ReqwestClient::new());
+    let app = Application::headless().with_http_client(http_client);
+
+    app.run(move |cx| {
+        let app_state = headless_app::init("Zed Agent CLI", cx);
+        agent_settings::init(cx);
+        cx.spawn(async move |cx| {
+            let stop_reason = match run_thread(args, prompt, app_state, cx).await {
+                Ok(stop_reason) => Some(stop_reason),
+                Err(error) => {
+                    OutputEvent::Error {
+                        message: format!("{error:#}"),
+                    }
+                    .print();
+                    None
+                }
+            };
+            std::process::exit(output::exit_code(stop_reason));
+        })
+        .detach();
+    });
+}
+
+async fn run_thread(
+    args: RunArgs,
+    prompt: String,
+    app_state: HeadlessAppState,
+    cx: &mut AsyncApp,
+) -> Result<acp::StopReason> {
+    let RunArgs {
+        prompt: _,
+        project: project_path,
+        model,
+        profile,
+        allow,
+        settings,
+    } = args;
+
+    let project_path = project_path
+        .canonicalize()
+        .with_context(|| format!("invalid project directory {project_path:?}"))?;
+    let user_settings = match settings {
+        Some(path) => Some(
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read settings file {path:?}"))?,
+        ),
+        None => std::fs::read_to_string(paths::settings_file()).ok(),
+    };
+
+    cx.update(|cx| load_settings(user_settings.as_deref(), cx))??;
+
+    let authenticate = cx.update(|cx| {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry
+                .providers()
+                .iter()
+                .map(|provider| provider.authenticate(cx))
+                .collect::<Vec<_>>()
+        })
+    })?;
+    future::join_all(authenticate).await;
+
+    let (model, profile_id) = cx.update(|cx| -> Result<_> {
+        let settings = AgentSettings::get_global(cx);
+        let model_name = match model {
+            Some(model_name) => model_name,
+            None => {
+                let selection = settings
+                    .default_model
+                    .as_ref()
+                    .context("no model passed with --model and no `agent.default_model` set")?;
+                format!("{}/{}", selection.provider.0, selection.model)
+            }
+        };
+        let model = LanguageModelRegistry::read_global(cx).find_model(&model_name, cx)?;
+
+        let profile_id = profile
+            .map(|profile| AgentProfileId(profile.into()))
+            .unwrap_or_else(|| settings.default_profile.clone());
+        anyhow::ensure!(
+            settings.profiles.contains_key(&profile_id),
+            "no agent profile with ID {}. Available profiles: {}",
+            profile_id.as_str(),
+            settings
+                .profiles
+                .keys()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Ok((model, profile_id))
+    })??;
+
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            cx,
+        )
+    })?;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.create_worktree(&project_path, true, cx)
+        })?
+        .await?;
+    worktree
+        .update(cx, |worktree, _cx| {
+            worktree.as_local().map(|worktree| worktree.scan_complete())
+        })?
+        .context("project directory is not local")?
+        .await;
+
+    let project_context = cx
+        .update(|cx| NativeAgent::build_project_context(&project, None, cx))?
+        .await;
+    let thread = cx.update(|cx| {
+        let project_context = cx.new(|_cx| project_context);
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        cx.new(|cx| {
+            let mut thread = Thread::new(
+                project.clone(),
+                project_context,
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            );
+            thread.add_default_tools(
+                Rc::new(CliThreadEnvironment {
+                    project: project.clone(),
+                }),
+                cx,
+            );
+            thread.set_profile(profile_id.clone());
+            thread
+        })
+    })?;
+
+    OutputEvent::Started {
+        project: project_path.to_string_lossy().into_owned(),
+        model: format!("{}/{}", model.provider_id().0, model.id().0),
+        profile: profile_id.as_str().to_string(),
+    }
+    .print();
+
+    let mut events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), [UserMessageContent::Text(prompt)], cx)
+    })??;
+
+    let approval = ToolApproval::new(allow);
+    let mut tool_names = HashMap::default();
+    let mut stop_reason = None;
+    while let Some(event) = events.next().await {
+        match event? {
+            ThreadEvent::UserMessage(_) => {}
+            ThreadEvent::AgentText(text) => OutputEvent::Text { text }.print(),
+            ThreadEvent::AgentThinking(text) => OutputEvent::Thinking { text }.print(),
+            ThreadEvent::ToolCall(tool_call) => {
+                let tool_name = tool_call
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.get("tool_name"))
+                    .and_then(|tool_name| tool_name.as_str())
+                    .map(|tool_name| tool_name.to_string());
+                if let Some(tool_name) = &tool_name {
+                    tool_names.insert(tool_call.id.clone(), tool_name.clone());
+                }
+                OutputEvent::ToolCall {
+                    tool_name,
+                    tool_call,
+                }
+                .print();
+            }
+            ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update)) => {
+                OutputEvent::ToolCallUpdate {
+                    tool_call_update: update,
+                }
+                .print();
+            }
+            // Diffs and terminals are entities; their contents are reported in the tool call's
+            // content and output once it completes.
+            ThreadEvent::ToolCallUpdate(_) => {}
+            ThreadEvent::ToolCallAuthorization(authorization) => {
+                let id = authorization.tool_call.id.clone();
+                let tool_name = tool_names.get(&id).cloned();
+                let approved = tool_name
+                    .as_deref()
+                    .is_some_and(|tool_name| approval.is_approved(tool_name));
+                OutputEvent::ToolCallAuthorization {
+                    id,
+                    tool_name,
+                    title: authorization.tool_call.fields.title.clone(),
+                    approved,
+                }
+                .print();
+
+                let option = authorization.options.iter().find(|option| {
+                    if approved {
+                        matches!(option.kind, acp::PermissionOptionKind::AllowOnce)
+                    } else {
+                        matches!(option.kind, acp::PermissionOptionKind::RejectOnce)
+                    }
+                });
+                if let Some(option) = option {
+                    authorization.response.send(option.id.clone()).ok();
+                }
+            }
+            ThreadEvent::Retry(status) => OutputEvent::Retry {
+                attempt: status.attempt,
+                max_attempts: status.max_attempts,
+                error: status.last_error.to_string(),
+            }
+            .print(),
+            ThreadEvent::Stop(reason) => {
+                OutputEvent::Stop { reason }.print();
+                stop_reason = Some(reason);
+            }
+        }
+    }
+
+    stop_reason.context("the thread ended without stopping")
+}
+
+fn load_settings(user_settings: Option<&str>, cx: &mut App) -> Result<()> {
+    if let Some(user_settings) = user_settings {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_user_settings(user_settings, cx).result()
+        })?;
+    }
+
+    // Tool calls are only approved by the `--allow` rules, so that a run doesn't depend on
+    // whether the user chose to always allow tool actions in Zed.
+    let mut agent_settings = AgentSettings::get_global(cx).clone();
+    agent_settings.always_allow_tool_actions = false;
+    AgentSettings::override_global(agent_settings, cx);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_args() {
+        let args = RunArgs::try_parse_from([
+            "zed --agent",
+            "--prompt",
+            "Fix the tests",
+            "--allow",
+            "terminal",
+            "--allow",
+            "edit_file",
+        ])
+        .unwrap();
+        assert_eq!(args.prompt.as_deref(), Some("Fix the tests"));
+        assert_eq!(args.project, PathBuf::from("."));
+        assert_eq!(args.allow, ["terminal", "edit_file"]);
+
+        assert!(RunArgs::try_parse_from(["zed --agent", "run"]).is_err());
+    }
+}
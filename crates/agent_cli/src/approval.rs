@@ -0,0 +1,43 @@
+/// Decides which tool calls are approved when the agent asks for permission.
+///
+/// Each rule is a tool name, such as `terminal` or `edit_file`, or a prefix ending in `*`,
+/// such as `mcp_*`. A lone `*` approves every tool call.
+#[derive(Debug, Default, Clone)]
+pub struct ToolApproval {
+    rules: Vec<String>,
+}
+
+impl ToolApproval {
+    pub fn new(rules: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            rules: rules.into_iter().collect(),
+        }
+    }
+
+    pub fn is_approved(&self, tool_name: &str) -> bool {
+        self.rules.iter().any(|rule| match rule.strip_suffix('*') {
+            Some(prefix) => tool_name.starts_with(prefix),
+            None => rule == tool_name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_approval() {
+        let approval = ToolApproval::new(["terminal".into(), "mcp_*".into()]);
+        assert!(approval.is_approved("terminal"));
+        assert!(approval.is_approved("mcp_github"));
+        assert!(!approval.is_approved("terminal_2"));
+        assert!(!approval.is_approved("edit_file"));
+
+        let approval = ToolApproval::new(["*".into()]);
+        assert!(approval.is_approved("edit_file"));
+
+        let approval = ToolApproval::default();
+        assert!(!approval.is_approved("terminal"));
+    }
+}
@@ -0,0 +1,116 @@
+use agent_client_protocol as acp;
+use serde::Serialize;
+use std::io::Write as _;
+
+/// An event of a headless run, printed to stdout as a line of JSON.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputEvent {
+    Started {
+        project: String,
+        model: String,
+        profile: String,
+    },
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolCall {
+        tool_name: Option<String>,
+        tool_call: acp::ToolCall,
+    },
+    ToolCallUpdate {
+        tool_call_update: acp::ToolCallUpdate,
+    },
+    ToolCallAuthorization {
+        id: acp::ToolCallId,
+        tool_name: Option<String>,
+        title: Option<String>,
+        approved: bool,
+    },
+    Retry {
+        attempt: usize,
+        max_attempts: usize,
+        error: String,
+    },
+    Stop {
+        reason: acp::StopReason,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl OutputEvent {
+    pub fn print(&self) {
+        let mut stdout = std::io::stdout().lock();
+        match serde_json::to_string(self) {
+            Ok(line) => {
+                writeln!(stdout, "{line}").ok();
+                stdout.flush().ok();
+            }
+            Err(error) => log::error!("failed to serialize {self:?}: {error}"),
+        }
+    }
+}
+
+/// The exit code of a run that stopped for the given reason.
+pub fn exit_code(stop_reason: Option<acp::StopReason>) -> i32 {
+    match stop_reason {
+        Some(acp::StopReason::EndTurn) => 0,
+        Some(_) => 2,
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn test_output_event_serialization() {
+        let events = [
+            OutputEvent::Text {
+                text: "Fixed the test.".into(),
+            },
+            OutputEvent::ToolCallAuthorization {
+                id: acp::ToolCallId("tool_1".into()),
+                tool_name: Some("terminal".into()),
+                title: Some("Run `cargo test`".into()),
+                approved: true,
+            },
+            OutputEvent::Stop {
+                reason: acp::StopReason::EndTurn,
+            },
+        ];
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| serde_json::to_value(event).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                json!({"type": "text", "text": "Fixed the test."}),
+                json!({
+                    "type": "tool_call_authorization",
+                    "id": "tool_1",
+                    "tool_name": "terminal",
+                    "title": "Run `cargo test`",
+                    "approved": true,
+                }),
+                json!({"type": "stop", "reason": "end_turn"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(Some(acp::StopReason::EndTurn)), 0);
+        assert_eq!(exit_code(Some(acp::StopReason::MaxTokens)), 2);
+        assert_eq!(exit_code(Some(acp::StopReason::Refusal)), 2);
+        assert_eq!(exit_code(None), 1);
+    }
+}
@@ -244,7 +244,8 @@ impl AgentConnection for AcpConnection {
                 .iter()
                 .filter_map(|id| {
                     let configuration = context_server_store.configuration_for_server(id)?;
-                    let command = configuration.command();
+                    // Servers reached over HTTP are only connected to by Zed itself.
+                    let command = configuration.command()?;
                     Some(acp::McpServer::Stdio {
                         name: id.0.to_string(),
                         command: command.path.clone(),
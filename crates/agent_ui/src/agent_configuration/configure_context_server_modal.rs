@@ -4,7 +4,7 @@ use std::{
 };
 
 use anyhow::{Context as _, Result};
-use context_server::{ContextServerCommand, ContextServerId};
+use context_server::{ContextServerCommand, ContextServerEndpoint, ContextServerId};
 use editor::{Editor, EditorElement, EditorStyle};
 use gpui::{
     AsyncWindowContext, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Task,
@@ -36,6 +36,10 @@ enum ConfigurationTarget {
         id: ContextServerId,
         command: ContextServerCommand,
     },
+    ExistingHttp {
+        id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         id: ContextServerId,
         repository_url: Option<SharedString>,
@@ -105,6 +109,14 @@ impl ConfigurationSource {
                     cx,
                 ),
             },
+            ConfigurationTarget::ExistingHttp { id, endpoint } => ConfigurationSource::Existing {
+                editor: create_editor(
+                    context_server_http_input(id, endpoint),
+                    jsonc_language,
+                    window,
+                    cx,
+                ),
+            },
             ConfigurationTarget::Extension {
                 id,
                 repository_url,
@@ -141,15 +153,7 @@ impl ConfigurationSource {
     fn output(&self, cx: &mut App) -> Result<(ContextServerId, ContextServerSettings)> {
         match self {
             ConfigurationSource::New { editor } | ConfigurationSource::Existing { editor } => {
-                parse_input(&editor.read(cx).text(cx)).map(|(id, command)| {
-                    (
-                        id,
-                        ContextServerSettings::Custom {
-                            enabled: true,
-                            command,
-                        },
-                    )
-                })
+                parse_input(&editor.read(cx).text(cx))
             }
             ConfigurationSource::Extension {
                 id,
@@ -211,6 +215,37 @@ fn context_server_input(existing: Option<(ContextServerId, ContextServerCommand)
     )
 }
 
+fn context_server_http_input(id: ContextServerId, endpoint: ContextServerEndpoint) -> String {
+    let url = serde_json::to_string(&endpoint.url).unwrap();
+    let headers = serde_json::to_string(&endpoint.headers.unwrap_or_default()).unwrap();
+    let mut optional_fields = String::new();
+    if let Some(transport) = endpoint.transport {
+        optional_fields.push_str(&format!(
+            ",\n    /// The HTTP transport the MCP server speaks\n    \"transport\": {}",
+            serde_json::to_string(&transport).unwrap()
+        ));
+    }
+    if let Some(bearer_token) = endpoint.bearer_token {
+        optional_fields.push_str(&format!(
+            ",\n    /// The token to authenticate with\n    \"bearer_token\": {}",
+            serde_json::to_string(&bearer_token).unwrap()
+        ));
+    }
+
+    format!(
+        r#"{{
+  /// The name of your MCP server
+  "{}": {{
+    /// The URL of the MCP server
+    "url": {url},
+    /// The headers to send with every request
+    "headers": {headers}{optional_fields}
+  }}
+}}"#,
+        id.0
+    )
+}
+
 fn resolve_context_server_extension(
     id: ContextServerId,
     worktree_store: Entity<WorktreeStore>,
@@ -310,6 +345,13 @@ impl ConfigureContextServerModal {
                     id: server_id,
                     command,
                 }),
+                ContextServerSettings::Http {
+                    enabled: _,
+                    endpoint,
+                } => Some(ConfigurationTarget::ExistingHttp {
+                    id: server_id,
+                    endpoint,
+                }),
                 ContextServerSettings::Extension { .. } => {
                     match workspace
                         .update(cx, |workspace, cx| {
@@ -350,7 +392,8 @@ impl ConfigureContextServerModal {
                     workspace: workspace_handle,
                     state: State::Idle,
                     original_server_id: match &target {
-                        ConfigurationTarget::Existing { id, .. } => Some(id.clone()),
+                        ConfigurationTarget::Existing { id, .. }
+                        | ConfigurationTarget::ExistingHttp { id, .. } => Some(id.clone()),
                         ConfigurationTarget::Extension { id, .. } => Some(id.clone()),
                         ConfigurationTarget::New => None,
                     },
@@ -464,13 +507,26 @@ impl ConfigureContextServerModal {
     }
 }
 
-fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerCommand)> {
+fn parse_input(text: &str) -> Result<(ContextServerId, ContextServerSettings)> {
     let value: serde_json::Value = serde_json_lenient::from_str(text)?;
     let object = value.as_object().context("Expected object")?;
     anyhow::ensure!(object.len() == 1, "Expected exactly one key-value pair");
     let (context_server_name, value) = object.into_iter().next().unwrap();
-    let command: ContextServerCommand = serde_json::from_value(value.clone())?;
-    Ok((ContextServerId(context_server_name.clone().into()), command))
+    let settings = if value.get("url").is_some() {
+        ContextServerSettings::Http {
+            enabled: true,
+            endpoint: serde_json::from_value(value.clone())?,
+        }
+    } else {
+        ContextServerSettings::Custom {
+            enabled: true,
+            command: serde_json::from_value(value.clone())?,
+        }
+    };
+    Ok((
+        ContextServerId(context_server_name.clone().into()),
+        settings,
+    ))
 }
 
 impl ModalView for ConfigureContextServerModal {}
@@ -748,6 +804,7 @@ fn wait_for_context_server(
                 _ => {}
             }
         }
+        _ => {}
     });
 
     cx.spawn(async move |_cx| {
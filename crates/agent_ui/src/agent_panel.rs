@@ -4,7 +4,7 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use acp_thread::AcpThread;
-use agent::{ContextServerRegistry, DbThreadMetadata, HistoryEntry, HistoryStore};
+use agent::{ContextServerRegistry, DbThreadMetadata, HistoryEntry, HistoryStore, ThreadExport};
 use db::kvp::{Dismissable, KEY_VALUE_STORE};
 use project::{
     ExternalAgentServerName,
@@ -21,10 +21,10 @@ use zed_actions::agent::{OpenClaudeCodeOnboardingModal, ReauthenticateAgent};
 
 use crate::ui::{AcpOnboardingModal, ClaudeCodeOnboardingModal};
 use crate::{
-    AddContextServer, AgentDiffPane, DeleteRecentlyOpenThread, Follow, InlineAssistant,
-    NewTextThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory,
-    ResetTrialEndUpsell, ResetTrialUpsell, ToggleNavigationMenu, ToggleNewThreadMenu,
-    ToggleOptionsMenu,
+    AddContextServer, AgentDiffPane, DeleteRecentlyOpenThread, ExportThreadAsJson,
+    ExportThreadAsMarkdown, Follow, ImportThread, InlineAssistant, NewTextThread, NewThread,
+    OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell,
+    ToggleNavigationMenu, ToggleNewThreadMenu, ToggleOptionsMenu,
     acp::AcpThreadView,
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     slash_command::SlashCommandCompletionProvider,
@@ -52,12 +52,12 @@ use extension_host::ExtensionStore;
 use fs::Fs;
 use gpui::{
     Action, AnyElement, App, AsyncWindowContext, Corner, DismissEvent, Entity, EventEmitter,
-    ExternalPaths, FocusHandle, Focusable, KeyContext, Pixels, Subscription, Task, UpdateGlobal,
-    WeakEntity, prelude::*,
+    ExternalPaths, FocusHandle, Focusable, KeyContext, PathPromptOptions, Pixels, Subscription,
+    Task, UpdateGlobal, WeakEntity, prelude::*,
 };
 use language::LanguageRegistry;
 use language_model::{ConfigurationError, LanguageModelRegistry};
-use project::{Project, ProjectPath, Worktree};
+use project::{DirectoryLister, Project, ProjectPath, Worktree};
 use prompt_store::{PromptBuilder, PromptStore, UserPromptId};
 use rules_library::{RulesLibrary, open_rules_library};
 use search::{BufferSearchBar, buffer_search};
@@ -72,6 +72,7 @@ use util::ResultExt as _;
 use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr as _,
 };
 use zed_actions::{
     DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize,
@@ -1216,6 +1217,113 @@ impl AgentPanel {
         }
     }
 
+    fn export_active_thread_as_markdown(
+        &mut self,
+        _: &ExportThreadAsMarkdown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_active_thread(false, window, cx);
+    }
+
+    fn export_active_thread_as_json(
+        &mut self,
+        _: &ExportThreadAsJson,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_active_thread(true, window, cx);
+    }
+
+    /// Saves the active thread to a file, either as Markdown for people to read or as JSON that
+    /// can be imported again.
+    fn export_active_thread(&mut self, as_json: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let Some(thread) = self.active_native_agent_thread(cx) else {
+            return;
+        };
+
+        let (id, db_thread, title) = {
+            let thread = thread.read(cx);
+            (thread.id().clone(), thread.to_db(cx), thread.title())
+        };
+        let extension = if as_json { "json" } else { "md" };
+        let file_stem = title
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+        let file_stem = file_stem.trim_matches('-');
+        let suggested_name = if file_stem.is_empty() {
+            format!("thread.{extension}")
+        } else {
+            format!("{file_stem}.{extension}")
+        };
+
+        let path = workspace.update(cx, |workspace, cx| {
+            workspace.prompt_for_new_path(
+                DirectoryLister::Local(workspace.project().clone(), self.fs.clone()),
+                Some(suggested_name),
+                window,
+                cx,
+            )
+        });
+        let fs = self.fs.clone();
+        cx.spawn_in(window, async move |_, _| {
+            let Some(path) = path.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            let db_thread = db_thread.await;
+            let content = if as_json {
+                ThreadExport::new(id, &db_thread)?.to_json()?
+            } else {
+                agent::thread_to_markdown(&db_thread)
+            };
+            fs.atomic_write(path, content).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    /// Imports a thread exported as JSON into the history, and opens it.
+    fn import_thread(&mut self, _: &ImportThread, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let paths = workspace.update(cx, |workspace, cx| {
+            workspace.prompt_for_open_path(
+                PathPromptOptions {
+                    files: true,
+                    directories: false,
+                    multiple: false,
+                    prompt: None,
+                },
+                DirectoryLister::Local(workspace.project().clone(), self.fs.clone()),
+                window,
+                cx,
+            )
+        });
+        let fs = self.fs.clone();
+        let history_store = self.history_store.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await.ok().flatten().into_iter().flatten().next() else {
+                return Ok(());
+            };
+            let json = fs.load_bytes(&path).await?;
+            let thread = ThreadExport::from_json(&json)?;
+            let metadata = history_store
+                .update(cx, |history_store, cx| {
+                    history_store.import_thread(thread, cx)
+                })?
+                .await?;
+            this.update_in(cx, |this, window, cx| {
+                this.load_agent_thread(metadata, window, cx)
+            })
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1708,6 +1816,7 @@ impl AgentPanel {
         };
 
         let selected_agent = self.selected_agent.clone();
+        let has_native_thread = self.active_native_agent_thread(cx).is_some();
 
         PopoverMenu::new("agent-options-menu")
             .trigger_with_tooltip(
@@ -1768,7 +1877,15 @@ impl AgentPanel {
                                 .separator()
                         }
 
+                        menu = menu.header("Threads");
+                        if has_native_thread {
+                            menu = menu
+                                .action("Export as Markdown…", Box::new(ExportThreadAsMarkdown))
+                                .action("Export as JSON…", Box::new(ExportThreadAsJson));
+                        }
                         menu = menu
+                            .action("Import Thread…", Box::new(ImportThread))
+                            .separator()
                             .header("MCP Servers")
                             .action(
                                 "View Server Extensions",
@@ -2564,6 +2681,9 @@ impl Render for AgentPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_active_thread_as_json))
+            .on_action(cx.listener(Self::import_thread))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::go_back))
             .on_action(cx.listener(Self::toggle_navigation_menu))
@@ -7,6 +7,7 @@ mod buffer_codegen;
 mod context;
 mod context_picker;
 mod context_server_configuration;
+mod context_server_requests;
 mod context_store;
 mod context_strip;
 mod inline_assistant;
@@ -99,6 +100,12 @@ actions!(
         AcceptSuggestedContext,
         /// Opens the active thread as a markdown file.
         OpenActiveThreadAsMarkdown,
+        /// Saves the active thread to a Markdown file.
+        ExportThreadAsMarkdown,
+        /// Saves the active thread to a JSON file, which can be imported again.
+        ExportThreadAsJson,
+        /// Imports a thread from a JSON file exported by Zed.
+        ImportThread,
         /// Opens the agent diff view to review changes.
         OpenAgentDiff,
         /// Keeps the current suggestion or change.
@@ -260,6 +267,7 @@ pub fn init(
     assistant_slash_command::init(cx);
     agent_panel::init(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
+    context_server_requests::init(cx);
     TextThreadEditor::init(cx);
 
     register_slash_commands(cx);
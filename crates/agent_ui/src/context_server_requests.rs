@@ -0,0 +1,849 @@
+//! Modals for the requests that context servers send to Zed: generating messages with a
+//! language model (sampling) and asking the user for information (elicitation).
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow, bail};
+use context_server::types::{
+    self, ElicitAction, ElicitResult, ElicitationProperty, ElicitationPropertyType,
+};
+use futures::{
+    StreamExt as _,
+    channel::{mpsc, oneshot},
+};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render};
+use language_model::{
+    LanguageModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage, Role,
+};
+use project::context_server_store::{
+    ContextServerElicitationRequest, ContextServerSamplingRequest, Event,
+};
+use ui::{
+    Banner, Checkbox, KeyBinding, Modal, ModalFooter, ModalHeader, Section, TintColor, ToggleState,
+    prelude::*,
+};
+use ui_input::InputField;
+use workspace::{ModalView, Workspace};
+
+enum ContextServerRequest {
+    Sampling(ContextServerSamplingRequest),
+    Elicitation(ContextServerElicitationRequest),
+}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+
+        // Requests are shown one at a time, in the order in which they were received.
+        let (requests_tx, mut requests_rx) = mpsc::unbounded();
+        let context_server_store = workspace.project().read(cx).context_server_store();
+        cx.subscribe_in(
+            &context_server_store,
+            window,
+            move |_, _, event, _, _| match event {
+                Event::SamplingRequested(request) => {
+                    requests_tx
+                        .unbounded_send(ContextServerRequest::Sampling(request.clone()))
+                        .ok();
+                }
+                Event::ElicitationRequested(request) => {
+                    requests_tx
+                        .unbounded_send(ContextServerRequest::Elicitation(request.clone()))
+                        .ok();
+                }
+                _ => {}
+            },
+        )
+        .detach();
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            while let Some(request) = requests_rx.next().await {
+                let (done_tx, done_rx) = oneshot::channel();
+                workspace.update_in(cx, |workspace, window, cx| match request {
+                    ContextServerRequest::Sampling(request) => {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            SamplingModal::new(request, done_tx, window, cx)
+                        })
+                    }
+                    ContextServerRequest::Elicitation(request) => {
+                        workspace.toggle_modal(window, cx, |window, cx| {
+                            ElicitationModal::new(request, done_tx, window, cx)
+                        })
+                    }
+                })?;
+                // Resolves once the modal is dropped.
+                done_rx.await.ok();
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    })
+    .detach();
+}
+
+/// Asks the user to approve a context server's request to generate a message with a language
+/// model. Dismissing the modal rejects the request.
+struct SamplingModal {
+    request: Option<ContextServerSamplingRequest>,
+    model: Option<Arc<dyn LanguageModel>>,
+    focus_handle: FocusHandle,
+    _done: oneshot::Sender<()>,
+}
+
+impl SamplingModal {
+    fn new(
+        request: ContextServerSamplingRequest,
+        done: oneshot::Sender<()>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let model = sampling_model(request.params.model_preferences.as_ref(), cx);
+        Self {
+            request: Some(request),
+            model,
+            focus_handle: cx.focus_handle(),
+            _done: done,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(model) = self.model.clone() else {
+            return;
+        };
+        let Some(request) = self.request.take() else {
+            return;
+        };
+        cx.spawn(async move |_, cx| {
+            let result = create_message(&request.params, model, cx).await;
+            request.respond(result);
+        })
+        .detach();
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Picks the first available model matching one of the server's hints, falling back to the
+/// default model.
+fn sampling_model(
+    preferences: Option<&types::ModelPreferences>,
+    cx: &App,
+) -> Option<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+    for hint in hints {
+        if let Some(model) = registry
+            .available_models(cx)
+            .find(|model| model.id().0.contains(hint))
+        {
+            return Some(model);
+        }
+    }
+    registry.default_model().map(|configured| configured.model)
+}
+
+/// Typical number of string bytes per token, used to estimate the length of a generated message
+/// when the provider doesn't report its usage while streaming. This is intentionally low to err
+/// on the side of stopping early.
+const BYTES_PER_TOKEN_GUESS: usize = 3;
+
+async fn create_message(
+    params: &types::CreateMessageRequest,
+    model: Arc<dyn LanguageModel>,
+    cx: &gpui::AsyncApp,
+) -> Result<types::CreateMessageResult> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+        });
+    }
+    for message in &params.messages {
+        let types::MessageContent::Text { text, .. } = &message.content else {
+            bail!("Only text messages are supported for sampling");
+        };
+        messages.push(LanguageModelRequestMessage {
+            role: match message.role {
+                types::Role::User => Role::User,
+                types::Role::Assistant => Role::Assistant,
+            },
+            content: vec![text.clone().into()],
+            cache: false,
+        });
+    }
+
+    let request = LanguageModelRequest {
+        thread_id: None,
+        prompt_id: None,
+        intent: None,
+        mode: None,
+        messages,
+        tools: Vec::new(),
+        tool_choice: None,
+        // Stop sequences are applied while streaming rather than by the provider, so that the
+        // server can be told whether one of them ended the message.
+        stop: Vec::new(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        thinking_allowed: false,
+    };
+
+    let stop_sequences = params
+        .stop_sequences
+        .iter()
+        .flatten()
+        .filter(|stop_sequence| !stop_sequence.is_empty())
+        .collect::<Vec<_>>();
+    let longest_stop_sequence = stop_sequences
+        .iter()
+        .map(|stop_sequence| stop_sequence.len())
+        .max()
+        .unwrap_or_default();
+    let max_tokens = u64::from(params.max_tokens);
+
+    let completion = model.stream_completion_text(request, cx).await?;
+    let mut stream = completion.stream;
+    let mut text = String::new();
+    let mut stop_reason = "endTurn";
+    while let Some(chunk) = stream.next().await {
+        // A stop sequence may start in a previous chunk.
+        let mut search_start = text.len().saturating_sub(longest_stop_sequence);
+        while !text.is_char_boundary(search_start) {
+            search_start -= 1;
+        }
+        text.push_str(&chunk?);
+
+        if let Some(stop_ix) = stop_sequences
+            .iter()
+            .filter_map(|stop_sequence| text[search_start..].find(stop_sequence.as_str()))
+            .min()
+        {
+            text.truncate(search_start + stop_ix);
+            stop_reason = "stopSequence";
+            break;
+        }
+
+        let reported_tokens = completion.last_token_usage.lock().output_tokens;
+        let estimated_tokens = (text.len() / BYTES_PER_TOKEN_GUESS) as u64;
+        if reported_tokens.max(estimated_tokens) >= max_tokens {
+            stop_reason = "maxTokens";
+            break;
+        }
+    }
+
+    Ok(types::CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some(stop_reason.into()),
+    })
+}
+
+impl EventEmitter<DismissEvent> for SamplingModal {}
+
+impl Focusable for SamplingModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for SamplingModal {}
+
+impl Render for SamplingModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let Some(request) = self.request.as_ref() else {
+            return div().into_any_element();
+        };
+        let params = &request.params;
+
+        let description = match &self.model {
+            Some(model) => format!(
+                "The {} server wants to generate a message with {}, using at most {} tokens.",
+                request.server_id,
+                model.name().0,
+                params.max_tokens
+            ),
+            None => format!(
+                "The {} server wants to generate a message, but no language model is configured.",
+                request.server_id
+            ),
+        };
+
+        div()
+            .id("context-server-sampling-modal")
+            .key_context("ContextServerSamplingModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .capture_any_mouse_down(cx.listener(|this, _, window, cx| {
+                this.focus_handle(cx).focus(window);
+            }))
+            .child(
+                Modal::new("context-server-sampling", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline("Context Server Sampling Request")
+                            .description(description),
+                    )
+                    .child(
+                        v_flex()
+                            .id("sampling-messages")
+                            .size_full()
+                            .max_h_128()
+                            .overflow_y_scroll()
+                            .px(DynamicSpacing::Base12.rems(cx))
+                            .gap(DynamicSpacing::Base08.rems(cx))
+                            .when_some(params.system_prompt.clone(), |this, system_prompt| {
+                                this.child(render_message("System", system_prompt))
+                            })
+                            .children(params.messages.iter().map(|message| {
+                                let role = match message.role {
+                                    types::Role::User => "User",
+                                    types::Role::Assistant => "Assistant",
+                                };
+                                let content = match &message.content {
+                                    types::MessageContent::Text { text, .. } => text.clone(),
+                                    types::MessageContent::Image { mime_type, .. } => {
+                                        format!("[{mime_type} image]")
+                                    }
+                                    types::MessageContent::Resource { .. } => "[resource]".into(),
+                                };
+                                render_message(role, content)
+                            })),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("deny", "Deny")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(
+                                    Button::new("allow", "Allow")
+                                        .disabled(self.model.is_none())
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+fn render_message(role: &'static str, content: String) -> impl IntoElement {
+    v_flex()
+        .gap_0p5()
+        .child(Label::new(role).size(LabelSize::Small).color(Color::Muted))
+        .child(Label::new(content))
+}
+
+enum FieldInput {
+    Text(Entity<InputField>),
+    Boolean(ToggleState),
+    Enum(Option<usize>),
+}
+
+struct ElicitationField {
+    name: String,
+    input: FieldInput,
+}
+
+/// Shows the form that a context server requested, sending the entered values back to it.
+/// Dismissing the modal cancels the request.
+struct ElicitationModal {
+    request: Option<ContextServerElicitationRequest>,
+    fields: Vec<ElicitationField>,
+    focus_handle: FocusHandle,
+    last_error: Option<SharedString>,
+    _done: oneshot::Sender<()>,
+}
+
+impl ElicitationModal {
+    fn new(
+        request: ContextServerElicitationRequest,
+        done: oneshot::Sender<()>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let fields = request
+            .params
+            .requested_schema
+            .properties
+            .iter()
+            .map(|(name, property)| ElicitationField {
+                name: name.clone(),
+                input: field_input(name, property, window, cx),
+            })
+            .collect();
+        Self {
+            request: Some(request),
+            fields,
+            focus_handle: cx.focus_handle(),
+            last_error: None,
+            _done: done,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        match self.content(cx) {
+            Ok(content) => self.respond(ElicitAction::Accept, Some(content), cx),
+            Err(error) => {
+                self.last_error = Some(error);
+                cx.notify();
+            }
+        }
+    }
+
+    fn decline(&mut self, cx: &mut Context<Self>) {
+        self.respond(ElicitAction::Decline, None, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn respond(
+        &mut self,
+        action: ElicitAction,
+        content: Option<Vec<(String, serde_json::Value)>>,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(request) = self.request.take() {
+            request.respond(ElicitResult {
+                action,
+                content: content.map(|content| content.into_iter().collect()),
+                meta: None,
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn content(&self, cx: &App) -> Result<Vec<(String, serde_json::Value)>, SharedString> {
+        let Some(request) = self.request.as_ref() else {
+            return Ok(Vec::new());
+        };
+        let schema = &request.params.requested_schema;
+        let mut content = Vec::new();
+        for field in &self.fields {
+            let Some(property) = schema.properties.get(&field.name) else {
+                continue;
+            };
+            let value = match &field.input {
+                FieldInput::Text(input) => parse_field_value(property, &input.read(cx).text(cx))
+                    .map_err(|error| format!("{}: {error}", field_label(&field.name, property)))?,
+                FieldInput::Boolean(state) => Some(serde_json::Value::Bool(state.selected())),
+                FieldInput::Enum(selected) => selected
+                    .and_then(|ix| property.enum_values.as_ref()?.get(ix))
+                    .map(|value| serde_json::Value::String(value.clone())),
+            };
+            match value {
+                Some(value) => content.push((field.name.clone(), value)),
+                None if schema.required.contains(&field.name) => {
+                    return Err(
+                        format!("{} is required", field_label(&field.name, property)).into(),
+                    );
+                }
+                None => {}
+            }
+        }
+        Ok(content)
+    }
+
+    fn render_field(
+        &self,
+        ix: usize,
+        field: &ElicitationField,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let request = self.request.as_ref()?;
+        let property = request
+            .params
+            .requested_schema
+            .properties
+            .get(&field.name)?;
+        let label = field_label(&field.name, property);
+        let element = match &field.input {
+            FieldInput::Text(input) => input.clone().into_any_element(),
+            FieldInput::Boolean(state) => Checkbox::new(("elicitation-field", ix), *state)
+                .label(label)
+                .on_click(cx.listener(move |this, checked, _window, cx| {
+                    this.fields[ix].input = FieldInput::Boolean(*checked);
+                    cx.notify();
+                }))
+                .into_any_element(),
+            FieldInput::Enum(selected) => {
+                let values = property.enum_values.clone().unwrap_or_default();
+                let names = property.enum_names.clone().unwrap_or_default();
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(label).size(LabelSize::Small))
+                    .child(h_flex().flex_wrap().gap_1().children(
+                        values.into_iter().enumerate().map(|(value_ix, value)| {
+                            let name = names.get(value_ix).cloned().unwrap_or(value);
+                            Button::new(("elicitation-option", ix * 1000 + value_ix), name)
+                                .style(ButtonStyle::Outlined)
+                                .selected_style(ButtonStyle::Tinted(TintColor::Accent))
+                                .toggle_state(*selected == Some(value_ix))
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.fields[ix].input = FieldInput::Enum(Some(value_ix));
+                                    cx.notify();
+                                }))
+                        }),
+                    ))
+                    .into_any_element()
+            }
+        };
+        Some(
+            v_flex()
+                .gap_0p5()
+                .child(element)
+                .when_some(property.description.clone(), |this, description| {
+                    this.child(
+                        Label::new(description)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+}
+
+fn field_label(name: &str, property: &ElicitationProperty) -> String {
+    property.title.clone().unwrap_or_else(|| name.to_string())
+}
+
+fn field_input(
+    name: &str,
+    property: &ElicitationProperty,
+    window: &mut Window,
+    cx: &mut App,
+) -> FieldInput {
+    if let Some(values) = &property.enum_values {
+        let selected = property
+            .default
+            .as_ref()
+            .and_then(|default| default.as_str())
+            .and_then(|default| values.iter().position(|value| value == default));
+        return FieldInput::Enum(selected);
+    }
+
+    match property.ty {
+        ElicitationPropertyType::Boolean => FieldInput::Boolean(
+            property
+                .default
+                .as_ref()
+                .and_then(|default| default.as_bool())
+                .unwrap_or(false)
+                .into(),
+        ),
+        ElicitationPropertyType::String
+        | ElicitationPropertyType::Number
+        | ElicitationPropertyType::Integer => {
+            let placeholder = property
+                .format
+                .clone()
+                .unwrap_or_else(|| match property.ty {
+                    ElicitationPropertyType::Number => "number".into(),
+                    ElicitationPropertyType::Integer => "integer".into(),
+                    _ => String::new(),
+                });
+            let default = property.default.as_ref().map(|default| match default {
+                serde_json::Value::String(default) => default.clone(),
+                default => default.to_string(),
+            });
+            FieldInput::Text(cx.new(|cx| {
+                let input =
+                    InputField::new(window, cx, placeholder).label(field_label(name, property));
+                if let Some(default) = default {
+                    input.set_text(default, window, cx);
+                }
+                input
+            }))
+        }
+    }
+}
+
+/// Parses the text entered for a string, number or integer property, returning `None` when
+/// it is empty.
+fn parse_field_value(
+    property: &ElicitationProperty,
+    text: &str,
+) -> Result<Option<serde_json::Value>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    let value = match property.ty {
+        ElicitationPropertyType::String => {
+            let length = text.chars().count();
+            if let Some(min_length) = property.min_length
+                && length < min_length
+            {
+                bail!("must be at least {min_length} characters long");
+            }
+            if let Some(max_length) = property.max_length
+                && length > max_length
+            {
+                bail!("must be at most {max_length} characters long");
+            }
+            serde_json::Value::String(text.to_string())
+        }
+        ElicitationPropertyType::Number | ElicitationPropertyType::Integer => {
+            let (number, value) = if property.ty == ElicitationPropertyType::Integer {
+                let integer = text
+                    .parse::<i64>()
+                    .map_err(|_| anyhow!("must be an integer"))?;
+                (integer as f64, serde_json::Value::from(integer))
+            } else {
+                let number = text
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(|| anyhow!("must be a number"))?;
+                (number, serde_json::Value::from(number))
+            };
+            if let Some(minimum) = property.minimum
+                && number < minimum
+            {
+                bail!("must be at least {minimum}");
+            }
+            if let Some(maximum) = property.maximum
+                && number > maximum
+            {
+                bail!("must be at most {maximum}");
+            }
+            value
+        }
+        ElicitationPropertyType::Boolean => match text {
+            "true" => serde_json::Value::Bool(true),
+            "false" => serde_json::Value::Bool(false),
+            _ => bail!("must be true or false"),
+        },
+    };
+    Ok(Some(value))
+}
+
+impl EventEmitter<DismissEvent> for ElicitationModal {}
+
+impl Focusable for ElicitationModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for ElicitationModal {}
+
+impl Render for ElicitationModal {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle(cx);
+        let Some(request) = self.request.as_ref() else {
+            return div().into_any_element();
+        };
+        let description = format!(
+            "The {} server asks: {}",
+            request.server_id, request.params.message
+        );
+        let fields = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, field)| self.render_field(ix, field, cx))
+            .collect::<Vec<_>>();
+
+        div()
+            .id("context-server-elicitation-modal")
+            .key_context("ContextServerElicitationModal")
+            .w(rems(34.))
+            .elevation_3(cx)
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .capture_any_mouse_down(cx.listener(|this, _, window, cx| {
+                this.focus_handle(cx).focus(window);
+            }))
+            .child(
+                Modal::new("context-server-elicitation", None)
+                    .header(
+                        ModalHeader::new()
+                            .headline("Context Server Request")
+                            .description(description),
+                    )
+                    .when_some(self.last_error.clone(), |this, error| {
+                        this.section(
+                            Section::new().child(
+                                Banner::new()
+                                    .severity(Severity::Warning)
+                                    .child(div().text_xs().child(error)),
+                            ),
+                        )
+                    })
+                    .child(
+                        v_flex()
+                            .id("elicitation-fields")
+                            .size_full()
+                            .max_h_128()
+                            .overflow_y_scroll()
+                            .px(DynamicSpacing::Base12.rems(cx))
+                            .gap(DynamicSpacing::Base08.rems(cx))
+                            .children(fields),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("cancel", "Cancel")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(Button::new("decline", "Decline").on_click(
+                                    cx.listener(|this, _event, _window, cx| this.decline(cx)),
+                                ))
+                                .child(
+                                    Button::new("submit", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _event, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language_model::fake_provider::FakeLanguageModel;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_create_message_stop_reasons(cx: &mut TestAppContext) {
+        let model = Arc::new(FakeLanguageModel::default());
+        let generate = |max_tokens: u32, chunks: &[&str], cx: &mut TestAppContext| {
+            let params: types::CreateMessageRequest = serde_json::from_value(json!({
+                "messages": [{"role": "user", "content": {"type": "text", "text": "Hi"}}],
+                "maxTokens": max_tokens,
+                "stopSequences": ["END"],
+            }))
+            .unwrap();
+            let task = cx.spawn({
+                let model = model.clone();
+                |cx| async move { create_message(&params, model, &cx).await }
+            });
+            cx.run_until_parked();
+            for chunk in chunks {
+                model.send_last_completion_stream_text_chunk(*chunk);
+                cx.run_until_parked();
+            }
+            if !model.pending_completions().is_empty() {
+                model.end_last_completion_stream();
+            }
+            task
+        };
+        let text_and_stop_reason = |result: types::CreateMessageResult| {
+            let types::MessageContent::Text { text, .. } = result.content else {
+                panic!("expected a text message");
+            };
+            (text, result.stop_reason.unwrap())
+        };
+
+        let result = generate(100, &["Hello", " world"], cx).await.unwrap();
+        assert_eq!(
+            text_and_stop_reason(result),
+            ("Hello world".into(), "endTurn".into())
+        );
+
+        // The stop sequence is split across chunks, and left out of the message.
+        let result = generate(100, &["Hello E", "ND world"], cx).await.unwrap();
+        assert_eq!(
+            text_and_stop_reason(result),
+            ("Hello ".into(), "stopSequence".into())
+        );
+
+        let result = generate(4, &["Hello ", "there, world"], cx).await.unwrap();
+        assert_eq!(
+            text_and_stop_reason(result),
+            ("Hello there, world".into(), "maxTokens".into())
+        );
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        let property = |value: serde_json::Value| -> ElicitationProperty {
+            serde_json::from_value(value).unwrap()
+        };
+
+        let name = property(json!({"type": "string", "minLength": 2, "maxLength": 4}));
+        assert_eq!(parse_field_value(&name, " ").unwrap(), None);
+        assert_eq!(parse_field_value(&name, "abc").unwrap(), Some(json!("abc")));
+        assert!(parse_field_value(&name, "a").is_err());
+        assert!(parse_field_value(&name, "abcde").is_err());
+
+        let count = property(json!({"type": "integer", "minimum": 1, "maximum": 10}));
+        assert_eq!(parse_field_value(&count, "3").unwrap(), Some(json!(3)));
+        assert!(parse_field_value(&count, "3.5").is_err());
+        assert!(parse_field_value(&count, "11").is_err());
+
+        let ratio = property(json!({"type": "number", "maximum": 1.0}));
+        assert_eq!(parse_field_value(&ratio, "0.5").unwrap(), Some(json!(0.5)));
+        assert!(parse_field_value(&ratio, "NaN").is_err());
+        assert!(parse_field_value(&ratio, "1.5").is_err());
+    }
+}
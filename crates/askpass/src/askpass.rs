@@ -1,6 +1,8 @@
 mod encrypted_password;
+mod sequence_editor;
 
 pub use encrypted_password::{EncryptedPassword, IKnowWhatIAmDoingAndIHaveReadTheDocs};
+pub use sequence_editor::SequenceEditorSession;
 
 use net::async_net::UnixListener;
 use smol::lock::Mutex;
@@ -0,0 +1,141 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use futures::{AsyncBufReadExt as _, AsyncWriteExt as _, io::BufReader};
+use gpui::{BackgroundExecutor, Task};
+use net::async_net::UnixListener;
+use smol::fs;
+use util::{ResultExt as _, fs::make_file_executable, maybe, paths::PathExt, shell::ShellKind};
+
+use crate::ASKPASS_PROGRAM;
+
+#[cfg(not(target_os = "windows"))]
+const SEQUENCE_EDITOR_SCRIPT_NAME: &str = "sequence-editor.sh";
+#[cfg(target_os = "windows")]
+const SEQUENCE_EDITOR_SCRIPT_NAME: &str = "sequence-editor.ps1";
+
+/// The response sent back to the script when the todo list was edited.
+const ACCEPTED: &str = "ok";
+
+/// Serves as `GIT_SEQUENCE_EDITOR`, handing the todo list of an interactive rebase to Zed.
+///
+/// Like askpass, git runs a script that forwards the path of the todo list to this process
+/// over a socket, and waits for it to be edited. The rebase is aborted when the edit is
+/// rejected. The session must be retained until git exits.
+pub struct SequenceEditorSession {
+    _task: Task<()>,
+    #[cfg(not(target_os = "windows"))]
+    script_path: PathBuf,
+    #[cfg(target_os = "windows")]
+    editor_command: String,
+}
+
+impl SequenceEditorSession {
+    /// `edit_todo` is called with the path of the todo list as passed by git, and resolves to
+    /// whether the rebase should go on.
+    pub async fn new(
+        executor: &BackgroundExecutor,
+        mut edit_todo: impl FnMut(PathBuf) -> Task<bool> + 'static + Send + Sync,
+    ) -> Result<Self> {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("zed-sequence-editor")
+            .tempdir()?;
+        let socket_path = temp_dir.path().join("sequence-editor.sock");
+        let script_path = temp_dir.path().join(SEQUENCE_EDITOR_SCRIPT_NAME);
+        let current_exec =
+            std::env::current_exe().context("Failed to determine current zed executable path.")?;
+
+        let shell_kind = if cfg!(windows) {
+            ShellKind::PowerShell
+        } else {
+            ShellKind::Posix
+        };
+        let program = ASKPASS_PROGRAM
+            .get_or_init(|| current_exec)
+            .try_shell_safe(shell_kind)
+            .context("Failed to shell-escape sequence editor program path.")?
+            .to_string();
+        let script = generate_sequence_editor_script(&program, &socket_path);
+
+        let _task = executor.spawn(async move {
+            maybe!(async move {
+                let listener = UnixListener::bind(&socket_path)
+                    .context("creating sequence editor socket")?;
+
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let mut buffer = Vec::new();
+                    let mut reader = BufReader::new(&mut stream);
+                    if reader.read_until(b'\0', &mut buffer).await.is_err() {
+                        continue;
+                    }
+                    if buffer.last() == Some(&b'\0') {
+                        buffer.pop();
+                    }
+                    let todo_path = PathBuf::from(String::from_utf8_lossy(&buffer).into_owned());
+                    if edit_todo(todo_path).await {
+                        stream.write_all(ACCEPTED.as_bytes()).await.log_err();
+                    }
+                }
+                drop(temp_dir);
+                Result::<_, anyhow::Error>::Ok(())
+            })
+            .await
+            .log_err();
+        });
+
+        fs::write(&script_path, script)
+            .await
+            .with_context(|| format!("creating sequence editor script at {script_path:?}"))?;
+        make_file_executable(&script_path).await?;
+        #[cfg(target_os = "windows")]
+        let editor_command = format!(
+            "powershell.exe -ExecutionPolicy Bypass -File {}",
+            script_path.display()
+        );
+
+        Ok(Self {
+            _task,
+            #[cfg(not(target_os = "windows"))]
+            script_path,
+            #[cfg(target_os = "windows")]
+            editor_command,
+        })
+    }
+
+    /// The command to set as `GIT_SEQUENCE_EDITOR`.
+    pub fn script_path(&self) -> impl AsRef<OsStr> {
+        #[cfg(not(target_os = "windows"))]
+        {
+            &self.script_path
+        }
+        #[cfg(target_os = "windows")]
+        {
+            &self.editor_command
+        }
+    }
+}
+
+#[inline]
+#[cfg(not(target_os = "windows"))]
+fn generate_sequence_editor_script(program: &str, socket_path: &std::path::Path) -> String {
+    format!(
+        "{shebang}\n[ \"$({print_path} | {program} --askpass={socket_path} 2> /dev/null)\" = {ACCEPTED} ]\n",
+        socket_path = socket_path.display(),
+        print_path = "printf '%s\\0' \"$1\"",
+        shebang = "#!/bin/sh",
+    )
+}
+
+#[inline]
+#[cfg(target_os = "windows")]
+fn generate_sequence_editor_script(program: &str, socket_path: &std::path::Path) -> String {
+    format!(
+        r#"
+        $ErrorActionPreference = 'Stop';
+        $response = ($args[0] + [char]0) | & {program} --askpass={socket_path} 2> $null
+        if ($response -ne '{ACCEPTED}') {{ exit 1 }}
+        "#,
+        socket_path = socket_path.display(),
+    )
+}
@@ -864,6 +864,7 @@ impl TextThreadStore {
                     _ => {}
                 }
             }
+            _ => {}
         }
     }
 
@@ -127,6 +127,17 @@ struct Args {
     /// by having Zed act like netcat communicating over a Unix socket.
     #[arg(long, hide = true)]
     askpass: Option<String>,
+
+    /// Runs an agent thread without any UI, printing its events to stdout. The arguments that
+    /// follow are the agent's, see `zed --agent --help`.
+    #[arg(
+        long,
+        num_args = 0..,
+        allow_hyphen_values = true,
+        value_name = "AGENT_ARGS",
+        conflicts_with = "paths_with_position"
+    )]
+    agent: Option<Vec<String>>,
 }
 
 fn parse_path_with_position(argument_str: &str) -> anyhow::Result<String> {
@@ -245,6 +256,20 @@ fn main() -> Result<()> {
         anyhow::bail!(msg.join("\n"));
     }
 
+    // `zed --agent` runs in the Zed binary, which prints the agent's events to our stdout
+    if let Some(agent_args) = &args.agent {
+        let mut command = std::process::Command::new(app.path());
+        if let Some(dir) = &user_data_dir {
+            command.arg("--user-data-dir").arg(dir);
+        }
+        let status = command
+            .arg("--agent")
+            .args(agent_args)
+            .status()
+            .context("Failed to run the Zed binary")?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
         not(feature = "no-bundled-uninstall")
@@ -1074,3 +1099,25 @@ mod mac_os {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_args() {
+        // A path named `agent` is still opened.
+        let args = Args::try_parse_from(["zed", "agent"]).unwrap();
+        assert_eq!(args.paths_with_position, ["agent"]);
+        assert_eq!(args.agent, None);
+
+        let args = Args::try_parse_from(["zed", "--agent", "--prompt", "Fix it", "agent"]).unwrap();
+        assert!(args.paths_with_position.is_empty());
+        assert_eq!(args.agent.unwrap(), ["--prompt", "Fix it", "agent"]);
+
+        let args = Args::try_parse_from(["zed", "--agent"]).unwrap();
+        assert_eq!(args.agent.unwrap(), Vec::<String>::new());
+
+        assert!(Args::try_parse_from(["zed", "file.rs", "--agent"]).is_err());
+    }
+}
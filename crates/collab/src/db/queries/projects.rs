@@ -1005,6 +1005,8 @@ impl Database {
                         is_last_update: true,
                         merge_message: db_repository_entry.merge_message,
                         stash_entries: Vec::new(),
+                        in_progress_operation: None,
+                        rebase_progress: None,
                     });
                 }
             }
@@ -796,6 +796,8 @@ impl Database {
                             is_last_update: true,
                             merge_message: db_repository.merge_message,
                             stash_entries: Vec::new(),
+                            in_progress_operation: None,
+                            rebase_progress: None,
                         });
                     }
                 }
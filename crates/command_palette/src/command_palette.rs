@@ -109,6 +109,7 @@ impl CommandPalette {
                 Some(Command {
                     name: humanize_action_name(action.name()),
                     action,
+                    intercepted: false,
                 })
             })
             .collect();
@@ -169,6 +170,8 @@ pub struct CommandPaletteDelegate {
 struct Command {
     name: String,
     action: Box<dyn Action>,
+    /// Whether the command was produced by the [`GlobalCommandPaletteInterceptor`].
+    intercepted: bool,
 }
 
 impl Clone for Command {
@@ -176,6 +179,7 @@ impl Clone for Command {
         Self {
             name: self.name.clone(),
             action: self.action.boxed_clone(),
+            intercepted: self.intercepted,
         }
     }
 }
@@ -228,6 +232,7 @@ impl CommandPaletteDelegate {
             commands.push(Command {
                 name: string.clone(),
                 action,
+                intercepted: true,
             });
             new_matches.push(StringMatch {
                 candidate_id: commands.len() - 1,
@@ -457,6 +462,9 @@ impl PickerDelegate for CommandPaletteDelegate {
                 .await
         })
         .detach_and_log_err(cx);
+        if command.intercepted {
+            GlobalCommandPaletteInterceptor::confirmed(&self.latest_query, cx);
+        }
         let action = command.action;
         window.focus(&self.previous_focus_handle);
         self.dismissed(window, cx);
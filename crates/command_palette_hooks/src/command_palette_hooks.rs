@@ -115,9 +115,10 @@ pub struct CommandInterceptResult {
 
 /// An interceptor for the command palette.
 #[derive(Clone)]
-pub struct GlobalCommandPaletteInterceptor(
-    Rc<dyn Fn(&str, WeakEntity<Workspace>, &mut App) -> Task<CommandInterceptResult>>,
-);
+pub struct GlobalCommandPaletteInterceptor {
+    intercept: Rc<dyn Fn(&str, WeakEntity<Workspace>, &mut App) -> Task<CommandInterceptResult>>,
+    on_confirm: Option<Rc<dyn Fn(&str, &mut App)>>,
+}
 
 impl Global for GlobalCommandPaletteInterceptor {}
 
@@ -130,7 +131,20 @@ impl GlobalCommandPaletteInterceptor {
         interceptor: impl Fn(&str, WeakEntity<Workspace>, &mut App) -> Task<CommandInterceptResult>
         + 'static,
     ) {
-        cx.set_global(Self(Rc::new(interceptor)));
+        cx.set_global(Self {
+            intercept: Rc::new(interceptor),
+            on_confirm: None,
+        });
+    }
+
+    /// Sets a callback that is invoked with the query whenever one of the intercepted commands
+    /// is confirmed, for example to keep a history of them.
+    ///
+    /// Does nothing if no interceptor is set.
+    pub fn on_confirm(cx: &mut App, on_confirm: impl Fn(&str, &mut App) + 'static) {
+        if cx.has_global::<Self>() {
+            cx.global_mut::<Self>().on_confirm = Some(Rc::new(on_confirm));
+        }
     }
 
     /// Clears the global interceptor.
@@ -147,7 +161,17 @@ impl GlobalCommandPaletteInterceptor {
         cx: &mut App,
     ) -> Option<Task<CommandInterceptResult>> {
         let interceptor = cx.try_global::<Self>()?;
-        let handler = interceptor.0.clone();
+        let handler = interceptor.intercept.clone();
         Some(handler(query, workspace, cx))
     }
+
+    /// Notifies the interceptor that one of its commands was confirmed for the given query.
+    pub fn confirmed(query: &str, cx: &mut App) {
+        if let Some(on_confirm) = cx
+            .try_global::<Self>()
+            .and_then(|interceptor| interceptor.on_confirm.clone())
+        {
+            on_confirm(query, cx);
+        }
+    }
 }
@@ -51,6 +51,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -100,7 +101,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -113,7 +113,6 @@ pub(crate) struct Response<T> {
 pub(crate) enum CspResult<T> {
     #[serde(rename = "result")]
     Ok(Option<T>),
-    #[allow(dead_code)]
     Error(Option<Error>),
 }
 
@@ -202,12 +201,14 @@ impl Client {
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     notification_handlers,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -237,6 +238,7 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -252,14 +254,16 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches them
+    /// to the appropriate handlers. It processes requests (which are answered by registered
+    /// handlers), responses (which are matched to pending requests) and notifications
+    /// (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
         request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -274,6 +278,15 @@ impl Client {
                         request.params.unwrap_or(RawValue::NULL),
                         cx.clone(),
                     );
+                } else {
+                    let response = response_message(
+                        request.id,
+                        Err(Error {
+                            message: format!("Method not found: {}", request.method),
+                            code: METHOD_NOT_FOUND,
+                        }),
+                    );
+                    outbound_tx.try_send(response).log_err();
                 }
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
@@ -458,6 +471,66 @@ impl Client {
     ) {
         self.notification_handlers.lock().insert(method, f);
     }
+
+    /// Registers a handler for requests sent by the context server, whose result is sent
+    /// back as the response.
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        mut f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) {
+        let outbound_tx = self.outbound_tx.clone();
+        self.request_handlers.lock().insert(
+            method,
+            Box::new(move |id, params, cx| {
+                let params = match serde_json::from_str(params.get()) {
+                    Ok(params) => params,
+                    Err(error) => {
+                        let response = response_message(
+                            id,
+                            Err(Error {
+                                message: error.to_string(),
+                                code: INVALID_PARAMS,
+                            }),
+                        );
+                        outbound_tx.try_send(response).log_err();
+                        return;
+                    }
+                };
+                let task = f(params, cx.clone());
+                let outbound_tx = outbound_tx.clone();
+                cx.spawn(async move |_| {
+                    let result = task.await.map_err(|error| Error {
+                        message: format!("{error:#}"),
+                        code: INTERNAL_ERROR,
+                    });
+                    outbound_tx
+                        .send(response_message(id, result))
+                        .await
+                        .log_err();
+                })
+                .detach();
+            }),
+        );
+    }
+
+    /// Whether a handler is registered for requests with the given method.
+    pub fn handles_request(&self, method: &str) -> bool {
+        self.request_handlers.lock().contains_key(method)
+    }
+}
+
+fn response_message(id: RequestId, result: Result<Value, Error>) -> String {
+    let value = match result {
+        Ok(result) => CspResult::Ok(Some(result)),
+        Err(error) => CspResult::Error(Some(error)),
+    };
+    serde_json::to_string(&Response {
+        jsonrpc: JSON_RPC_VERSION,
+        id,
+        value,
+    })
+    .unwrap()
 }
 
 #[derive(Debug)]
@@ -8,13 +8,15 @@ pub mod types;
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fmt::Display, path::PathBuf};
 
 use anyhow::Result;
 use client::Client;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
+use http_client::HttpClient;
 use parking_lot::RwLock;
-pub use settings::ContextServerCommand;
+pub use settings::{ContextServerCommand, ContextServerEndpoint, ContextServerHttpTransport};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ContextServerId(pub Arc<str>);
@@ -27,6 +29,7 @@ impl Display for ContextServerId {
 
 enum ContextServerTransport {
     Stdio(ContextServerCommand, Option<PathBuf>),
+    Http(ContextServerEndpoint, Arc<dyn HttpClient>),
     Custom(Arc<dyn crate::transport::Transport>),
 }
 
@@ -52,6 +55,18 @@ impl ContextServer {
         }
     }
 
+    pub fn http(
+        id: ContextServerId,
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Self {
+        Self {
+            id,
+            client: RwLock::new(None),
+            configuration: ContextServerTransport::Http(endpoint, http_client),
+        }
+    }
+
     pub fn new(id: ContextServerId, transport: Arc<dyn crate::transport::Transport>) -> Self {
         Self {
             id,
@@ -73,18 +88,31 @@ impl ContextServer {
     }
 
     /// Starts the context server, making sure handlers are registered before initialization happens
+    ///
+    /// The capabilities advertised to the server depend on which request handlers are registered.
     pub async fn start_with_handlers(
         &self,
         notification_handlers: Vec<(
             &'static str,
             Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp)>,
         )>,
+        request_handlers: Vec<(
+            &'static str,
+            Box<
+                dyn 'static
+                    + Send
+                    + FnMut(serde_json::Value, AsyncApp) -> Task<Result<serde_json::Value>>,
+            >,
+        )>,
         cx: &AsyncApp,
     ) -> Result<()> {
         let client = self.new_client(cx)?;
         for (method, handler) in notification_handlers {
             client.on_notification(method, handler);
         }
+        for (method, handler) in request_handlers {
+            client.on_request(method, handler);
+        }
         self.initialize(client).await
     }
 
@@ -101,6 +129,17 @@ impl ContextServer {
                 working_directory,
                 cx.clone(),
             )?,
+            ContextServerTransport::Http(endpoint, http_client) => Client::new(
+                client::ContextServerId(self.id.0.clone()),
+                self.id().0,
+                Arc::new(crate::transport::HttpTransport::new(
+                    endpoint.clone(),
+                    http_client.clone(),
+                    cx,
+                )?),
+                endpoint.timeout.map(Duration::from_millis),
+                cx.clone(),
+            )?,
             ContextServerTransport::Custom(transport) => Client::new(
                 client::ContextServerId(self.id.0.clone()),
                 self.id().0,
@@ -27,21 +27,34 @@ impl ModelContextProtocol {
     fn supported_protocols() -> Vec<types::ProtocolVersion> {
         vec![
             types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            types::ProtocolVersion(types::VERSION_2025_03_26.to_string()),
             types::ProtocolVersion(types::VERSION_2024_11_05.to_string()),
         ]
     }
 
+    /// Advertises the client capabilities for which request handlers have been registered.
+    fn client_capabilities(&self) -> types::ClientCapabilities {
+        let handles = |method| self.inner.handles_request(method);
+        types::ClientCapabilities {
+            experimental: None,
+            sampling: handles(types::requests::CreateMessage::METHOD)
+                .then(|| serde_json::json!({})),
+            roots: handles(types::requests::ListRoots::METHOD).then_some(
+                types::RootsCapabilities {
+                    list_changed: Some(true),
+                },
+            ),
+            elicitation: handles(types::requests::Elicit::METHOD).then(|| serde_json::json!({})),
+        }
+    }
+
     pub async fn initialize(
         self,
         client_info: types::Implementation,
     ) -> Result<InitializedContextServerProtocol> {
         let params = types::InitializeParams {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
-            capabilities: types::ClientCapabilities {
-                experimental: None,
-                sampling: None,
-                roots: None,
-            },
+            capabilities: self.client_capabilities(),
             meta: None,
             client_info,
         };
@@ -1,6 +1,8 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use futures::{FutureExt, Stream, StreamExt as _, future::BoxFuture, lock::Mutex};
+use futures::{
+    FutureExt, Stream, StreamExt as _, channel::oneshot, future::BoxFuture, lock::Mutex,
+};
 use gpui::BackgroundExecutor;
 use std::{pin::Pin, sync::Arc};
 
@@ -41,6 +43,8 @@ pub struct FakeTransport {
     >,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
+    pending_responses: Arc<parking_lot::Mutex<HashMap<String, oneshot::Sender<serde_json::Value>>>>,
+    next_request_id: std::sync::atomic::AtomicUsize,
     executor: BackgroundExecutor,
 }
 
@@ -51,6 +55,8 @@ impl FakeTransport {
             request_handlers: Default::default(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
+            pending_responses: Default::default(),
+            next_request_id: Default::default(),
             executor,
         }
     }
@@ -78,6 +84,28 @@ impl FakeTransport {
         );
         self
     }
+
+    /// Sends a request from the server to the client, resolving to the client's response message.
+    pub fn request<T: crate::types::Request>(
+        &self,
+        params: T::Params,
+    ) -> impl Future<Output = serde_json::Value> + use<T> {
+        let id = format!(
+            "server-{}",
+            self.next_request_id
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+        );
+        let (tx, rx) = oneshot::channel();
+        self.pending_responses.lock().insert(id.clone(), tx);
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": T::METHOD,
+            "params": params,
+        });
+        self.tx.unbounded_send(request.to_string()).ok();
+        async move { rx.await.expect("no response to request") }
+    }
 }
 
 #[async_trait::async_trait]
@@ -101,6 +129,10 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(id) = msg.get("id").and_then(|id| id.as_str())
+                && let Some(tx) = self.pending_responses.lock().remove(id)
+            {
+                tx.send(msg).ok();
             }
         }
         Ok(())
@@ -1,3 +1,5 @@
+mod http_transport;
+mod sse;
 mod stdio_transport;
 
 use std::pin::Pin;
@@ -6,6 +8,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use futures::Stream;
 
+pub use http_transport::*;
 pub use stdio_transport::*;
 
 #[async_trait]
@@ -0,0 +1,773 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use futures::io::BufReader;
+use futures::{AsyncReadExt as _, Stream};
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+use http_client::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::Value;
+use smol::channel;
+use util::ResultExt as _;
+
+use crate::client::INTERNAL_ERROR;
+use crate::transport::Transport;
+use crate::transport::sse::{SseEvent, read_sse_event};
+use crate::{ContextServerEndpoint, ContextServerHttpTransport};
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_ATTEMPTS: usize = 5;
+
+/// A transport for context servers reachable over HTTP.
+///
+/// With [`ContextServerHttpTransport::StreamableHttp`], every message is posted to the server's
+/// URL and answered with either JSON or a stream of server-sent events, and messages initiated
+/// by the server arrive on an event stream opened once the connection is initialized.
+///
+/// With the legacy [`ContextServerHttpTransport::Sse`] transport, an event stream is opened on
+/// the server's URL first, and the server announces the endpoint messages are posted to.
+///
+/// Interrupted event streams are reconnected, resuming from the last event the server sent.
+pub struct HttpTransport {
+    connection: Arc<HttpConnection>,
+    outbound_tx: channel::Sender<String>,
+    inbound_rx: channel::Receiver<String>,
+    err_rx: channel::Receiver<String>,
+    executor: BackgroundExecutor,
+    _tasks: Vec<Task<()>>,
+}
+
+impl HttpTransport {
+    pub fn new(
+        endpoint: ContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Result<Self> {
+        let url = Url::parse(&endpoint.url)
+            .with_context(|| format!("invalid context server url {:?}", endpoint.url))?;
+        let kind = endpoint.transport.unwrap_or_default();
+
+        let mut headers = endpoint
+            .headers
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if let Some(token) = endpoint.bearer_token
+            && !headers
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(AUTHORIZATION.as_str()))
+        {
+            headers.push((
+                AUTHORIZATION.as_str().to_string(),
+                format!("Bearer {token}"),
+            ));
+        }
+
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (err_tx, err_rx) = channel::unbounded::<String>();
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+
+        let executor = cx.background_executor().clone();
+        let connection = Arc::new(HttpConnection {
+            http_client,
+            post_url: Mutex::new(match kind {
+                ContextServerHttpTransport::StreamableHttp => Some(url.clone()),
+                ContextServerHttpTransport::Sse => None,
+            }),
+            url,
+            headers,
+            session_id: Mutex::new(None),
+            protocol_version: Mutex::new(None),
+            endpoint_tx: Mutex::new(Some(endpoint_tx)),
+            inbound_tx,
+            err_tx,
+        });
+
+        let mut tasks = Vec::new();
+        let endpoint_rx =
+            match kind {
+                ContextServerHttpTransport::StreamableHttp => None,
+                ContextServerHttpTransport::Sse => {
+                    tasks.push(executor.spawn(connection.clone().stream_events(
+                        None,
+                        true,
+                        executor.clone(),
+                    )));
+                    Some(endpoint_rx)
+                }
+            };
+        tasks.push(executor.spawn(connection.clone().handle_output(
+            kind,
+            outbound_rx,
+            endpoint_rx,
+            executor.clone(),
+        )));
+
+        Ok(Self {
+            connection,
+            outbound_tx,
+            inbound_rx,
+            err_rx,
+            executor,
+            _tasks: tasks,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, message: String) -> Result<()> {
+        Ok(self.outbound_tx.send(message).await?)
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.inbound_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.err_rx.clone())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        if let Some(terminate_session) = self.connection.terminate_session() {
+            self.executor
+                .spawn(async move { terminate_session.await.log_err() })
+                .detach();
+        }
+    }
+}
+
+/// The fields of an outgoing JSON-RPC message needed to route its response.
+#[derive(Deserialize)]
+struct OutgoingMessage {
+    id: Option<Value>,
+    method: Option<String>,
+}
+
+struct HttpConnection {
+    http_client: Arc<dyn HttpClient>,
+    url: Url,
+    /// The URL messages are posted to, which the legacy transport only learns from the server.
+    post_url: Mutex<Option<Url>>,
+    headers: Vec<(String, String)>,
+    session_id: Mutex<Option<String>>,
+    protocol_version: Mutex<Option<String>>,
+    endpoint_tx: Mutex<Option<oneshot::Sender<()>>>,
+    inbound_tx: channel::Sender<String>,
+    err_tx: channel::Sender<String>,
+}
+
+impl HttpConnection {
+    fn request(&self, method: Method, url: &Url) -> http_client::Builder {
+        let mut request = Request::builder().method(method).uri(url.as_str());
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(session_id) = self.session_id.lock().as_deref() {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        if let Some(protocol_version) = self.protocol_version.lock().as_deref() {
+            request = request.header(PROTOCOL_VERSION_HEADER, protocol_version);
+        }
+        request
+    }
+
+    async fn handle_output(
+        self: Arc<Self>,
+        kind: ContextServerHttpTransport,
+        outbound_rx: channel::Receiver<String>,
+        endpoint_rx: Option<oneshot::Receiver<()>>,
+        executor: BackgroundExecutor,
+    ) {
+        if let Some(endpoint_rx) = endpoint_rx
+            && endpoint_rx.await.is_err()
+        {
+            return;
+        }
+
+        let mut listen_task = None;
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing message: {}", message);
+            let outgoing = serde_json::from_str::<OutgoingMessage>(&message).ok();
+            let method = outgoing.as_ref().and_then(|message| message.method.clone());
+
+            // Responses are read concurrently, but messages are posted one at a time so that the
+            // server receives them in order.
+            match self.post(message).await {
+                Ok(response) => {
+                    let is_initialize = method.as_deref() == Some("initialize");
+                    executor
+                        .spawn(self.clone().handle_response(
+                            response,
+                            is_initialize,
+                            executor.clone(),
+                        ))
+                        .detach();
+                }
+                Err(error) => self.fail_request(outgoing, error).await,
+            }
+
+            if kind == ContextServerHttpTransport::StreamableHttp
+                && listen_task.is_none()
+                && method.as_deref() == Some("notifications/initialized")
+            {
+                listen_task =
+                    Some(executor.spawn(self.clone().stream_events(None, true, executor.clone())));
+            }
+        }
+    }
+
+    async fn post(&self, message: String) -> Result<Response<AsyncBody>> {
+        let post_url = self
+            .post_url
+            .lock()
+            .clone()
+            .context("context server has not announced its message endpoint")?;
+        let had_session = self.session_id.lock().is_some();
+        let request = self
+            .request(Method::POST, &post_url)
+            .header(CONTENT_TYPE, JSON_CONTENT_TYPE)
+            .header(
+                ACCEPT,
+                format!("{JSON_CONTENT_TYPE}, {EVENT_STREAM_CONTENT_TYPE}"),
+            )
+            .body(AsyncBody::from(message))?;
+        let mut response = self.http_client.send(request).await?;
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+        {
+            *self.session_id.lock() = Some(session_id.to_string());
+        }
+
+        let status = response.status();
+        if !status.is_success() {
+            if status == StatusCode::NOT_FOUND && had_session {
+                anyhow::bail!("context server session has expired");
+            }
+            let mut body = String::new();
+            response.body_mut().read_to_string(&mut body).await.ok();
+            anyhow::bail!("context server responded with {status}: {body}");
+        }
+        Ok(response)
+    }
+
+    async fn handle_response(
+        self: Arc<Self>,
+        response: Response<AsyncBody>,
+        is_initialize: bool,
+        executor: BackgroundExecutor,
+    ) {
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if content_type.starts_with(EVENT_STREAM_CONTENT_TYPE) {
+            let mut body = BufReader::new(response.into_body());
+            let mut last_event_id = None;
+            loop {
+                match read_sse_event(&mut body).await {
+                    Ok(Some(event)) => {
+                        if event.id.is_some() {
+                            last_event_id = event.id.clone();
+                        }
+                        self.handle_event(event, is_initialize).await;
+                    }
+                    Ok(None) => break,
+                    Err(error) => {
+                        self.report_error(format!("context server event stream failed: {error}"))
+                            .await;
+                        // Ask the server to replay what was lost, if it supports resumption.
+                        if let Some(last_event_id) = last_event_id {
+                            executor
+                                .spawn(self.clone().stream_events(
+                                    Some(last_event_id),
+                                    false,
+                                    executor.clone(),
+                                ))
+                                .detach();
+                        }
+                        break;
+                    }
+                }
+            }
+        } else if content_type.starts_with(JSON_CONTENT_TYPE) {
+            let mut body = String::new();
+            if let Err(error) = response.into_body().read_to_string(&mut body).await {
+                self.report_error(format!("failed to read context server response: {error}"))
+                    .await;
+                return;
+            }
+            match serde_json::from_str::<Value>(&body) {
+                Ok(Value::Array(messages)) => {
+                    for message in messages {
+                        self.handle_message(message.to_string(), is_initialize)
+                            .await;
+                    }
+                }
+                Ok(_) => self.handle_message(body, is_initialize).await,
+                Err(error) => {
+                    self.report_error(format!("invalid context server response: {error}"))
+                        .await
+                }
+            }
+        }
+    }
+
+    /// Reads server-sent events from a `GET` request to the server's URL, reconnecting with the
+    /// last event id when the stream breaks.
+    ///
+    /// If `reconnect_on_close` is false, a stream that the server closes itself isn't reopened.
+    async fn stream_events(
+        self: Arc<Self>,
+        mut last_event_id: Option<String>,
+        reconnect_on_close: bool,
+        executor: BackgroundExecutor,
+    ) {
+        let mut reconnect_delay = DEFAULT_RECONNECT_DELAY;
+        let mut failed_attempts = 0;
+        loop {
+            match self.open_event_stream(last_event_id.as_deref()).await {
+                Ok(Some(response)) => {
+                    failed_attempts = 0;
+                    let mut body = BufReader::new(response.into_body());
+                    let closed_by_server = loop {
+                        match read_sse_event(&mut body).await {
+                            Ok(Some(event)) => {
+                                if event.id.is_some() {
+                                    last_event_id = event.id.clone();
+                                }
+                                if let Some(retry) = event.retry {
+                                    reconnect_delay = retry;
+                                }
+                                self.handle_event(event, false).await;
+                            }
+                            Ok(None) => break true,
+                            Err(error) => {
+                                self.report_error(format!(
+                                    "context server event stream failed: {error}"
+                                ))
+                                .await;
+                                break false;
+                            }
+                        }
+                    };
+                    if closed_by_server && !reconnect_on_close {
+                        return;
+                    }
+                }
+                // The server doesn't offer an event stream.
+                Ok(None) => return,
+                Err(error) => {
+                    failed_attempts += 1;
+                    self.report_error(format!(
+                        "failed to connect to context server event stream: {error}"
+                    ))
+                    .await;
+                    if failed_attempts >= MAX_RECONNECT_ATTEMPTS {
+                        log::error!(
+                            "giving up on context server event stream at {} after {failed_attempts} attempts",
+                            self.url
+                        );
+                        // Stop waiting for the legacy transport's endpoint, failing pending messages.
+                        self.endpoint_tx.lock().take();
+                        return;
+                    }
+                }
+            }
+
+            if self.inbound_tx.is_closed() {
+                return;
+            }
+            executor.timer(reconnect_delay).await;
+        }
+    }
+
+    async fn open_event_stream(
+        &self,
+        last_event_id: Option<&str>,
+    ) -> Result<Option<Response<AsyncBody>>> {
+        let mut request = self
+            .request(Method::GET, &self.url)
+            .header(ACCEPT, EVENT_STREAM_CONTENT_TYPE);
+        if let Some(last_event_id) = last_event_id {
+            request = request.header(LAST_EVENT_ID_HEADER, last_event_id);
+        }
+        let response = self
+            .http_client
+            .send(request.body(AsyncBody::empty())?)
+            .await?;
+
+        let status = response.status();
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(anyhow!("context server responded with {status}"));
+        }
+        Ok(Some(response))
+    }
+
+    async fn handle_event(&self, event: SseEvent, is_initialize: bool) {
+        match event.event.as_deref() {
+            None | Some("message") => {
+                if !event.data.is_empty() {
+                    self.handle_message(event.data, is_initialize).await;
+                }
+            }
+            Some("endpoint") => match self.url.join(event.data.trim()) {
+                Ok(post_url) => {
+                    *self.post_url.lock() = Some(post_url);
+                    if let Some(endpoint_tx) = self.endpoint_tx.lock().take() {
+                        endpoint_tx.send(()).ok();
+                    }
+                }
+                Err(error) => {
+                    self.report_error(format!("invalid context server endpoint: {error}"))
+                        .await
+                }
+            },
+            Some(event) => log::debug!("ignoring context server event {event:?}"),
+        }
+    }
+
+    async fn handle_message(&self, message: String, is_initialize: bool) {
+        if is_initialize
+            && let Ok(response) = serde_json::from_str::<Value>(&message)
+            && let Some(protocol_version) = response
+                .pointer("/result/protocolVersion")
+                .and_then(|version| version.as_str())
+        {
+            *self.protocol_version.lock() = Some(protocol_version.to_string());
+        }
+        self.inbound_tx.send(message).await.ok();
+    }
+
+    /// Answers a request that couldn't be sent with an error, rather than leaving it to time out.
+    async fn fail_request(&self, outgoing: Option<OutgoingMessage>, error: anyhow::Error) {
+        self.report_error(format!(
+            "failed to send message to context server: {error:#}"
+        ))
+        .await;
+        if let Some(OutgoingMessage {
+            id: Some(id),
+            method: Some(_),
+        }) = outgoing
+        {
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": {
+                    "code": INTERNAL_ERROR,
+                    "message": format!("{error:#}"),
+                },
+            });
+            self.inbound_tx.send(response.to_string()).await.ok();
+        }
+    }
+
+    async fn report_error(&self, error: String) {
+        self.err_tx.send(error).await.ok();
+    }
+
+    fn terminate_session(
+        &self,
+    ) -> Option<futures::future::BoxFuture<'static, Result<Response<AsyncBody>>>> {
+        self.session_id.lock().as_ref()?;
+        let request = self
+            .request(Method::DELETE, &self.url)
+            .body(AsyncBody::empty())
+            .log_err()?;
+        Some(self.http_client.send(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{AsyncReadExt as _, StreamExt as _};
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use serde_json::json;
+
+    #[derive(Debug)]
+    struct RecordedRequest {
+        method: Method,
+        uri: String,
+        headers: http_client::http::HeaderMap,
+        body: String,
+    }
+
+    impl RecordedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers.get(name).and_then(|value| value.to_str().ok())
+        }
+    }
+
+    fn fake_server<F>(handle_request: F) -> (Arc<dyn HttpClient>, Arc<Mutex<Vec<RecordedRequest>>>)
+    where
+        F: Fn(&RecordedRequest) -> Response<AsyncBody> + Send + Sync + 'static,
+    {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handle_request = Arc::new(handle_request);
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                let handle_request = handle_request.clone();
+                async move {
+                    let (parts, mut body) = request.into_parts();
+                    let mut text = String::new();
+                    body.read_to_string(&mut text).await?;
+                    let request = RecordedRequest {
+                        method: parts.method,
+                        uri: parts.uri.to_string(),
+                        headers: parts.headers,
+                        body: text,
+                    };
+                    let response = handle_request(&request);
+                    requests.lock().push(request);
+                    Ok(response)
+                }
+            }
+        });
+        (http_client, requests)
+    }
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> Response<AsyncBody> {
+        let mut response = Response::builder().status(status);
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        response.body(AsyncBody::from(body.to_string())).unwrap()
+    }
+
+    fn endpoint(url: &str, transport: ContextServerHttpTransport) -> ContextServerEndpoint {
+        ContextServerEndpoint {
+            url: url.to_string(),
+            transport: Some(transport),
+            headers: Some(
+                [("X-Team".to_string(), "zed".to_string())]
+                    .into_iter()
+                    .collect(),
+            ),
+            bearer_token: Some("secret".to_string()),
+            timeout: None,
+        }
+    }
+
+    fn message_method(request: &RecordedRequest) -> Option<String> {
+        serde_json::from_str::<Value>(&request.body)
+            .ok()?
+            .get("method")?
+            .as_str()
+            .map(ToString::to_string)
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http_transport(cx: &mut TestAppContext) {
+        let (http_client, requests) = fake_server(|request| {
+            if request.method == Method::GET {
+                return response(405, &[], "");
+            }
+            match message_method(request).as_deref() {
+                Some("initialize") => response(
+                    200,
+                    &[
+                        (CONTENT_TYPE.as_str(), JSON_CONTENT_TYPE),
+                        (SESSION_ID_HEADER, "session-1"),
+                    ],
+                    r#"{"jsonrpc":"2.0","id":0,"result":{"protocolVersion":"2025-06-18"}}"#,
+                ),
+                Some("tools/list") => response(
+                    200,
+                    &[(CONTENT_TYPE.as_str(), EVENT_STREAM_CONTENT_TYPE)],
+                    concat!(
+                        ": keep-alive\n\n",
+                        "id: 1\n",
+                        "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n",
+                        "id: 2\n",
+                        "data: {\"jsonrpc\":\"2.0\",\"id\":1,\n",
+                        "data: \"result\":{\"tools\":[]}}\n\n",
+                    ),
+                ),
+                _ => response(202, &[], ""),
+            }
+        });
+
+        let transport = HttpTransport::new(
+            endpoint(
+                "http://localhost:3000/mcp",
+                ContextServerHttpTransport::StreamableHttp,
+            ),
+            http_client,
+            &cx.to_async(),
+        )
+        .unwrap();
+        let mut messages = transport.receive();
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}).to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let initialize_response = messages.next().await.unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&initialize_response).unwrap()["result"]["protocolVersion"],
+            "2025-06-18"
+        );
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}).to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}).to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let progress = serde_json::from_str::<Value>(&messages.next().await.unwrap()).unwrap();
+        assert_eq!(progress["method"], "notifications/progress");
+        let tools = serde_json::from_str::<Value>(&messages.next().await.unwrap()).unwrap();
+        assert_eq!(
+            tools,
+            json!({"jsonrpc": "2.0", "id": 1, "result": {"tools": []}})
+        );
+
+        let requests = requests.lock();
+        assert_eq!(
+            requests
+                .iter()
+                .map(|request| (request.method.clone(), message_method(request)))
+                .collect::<Vec<_>>(),
+            vec![
+                (Method::POST, Some("initialize".to_string())),
+                (Method::POST, Some("notifications/initialized".to_string())),
+                (Method::GET, None),
+                (Method::POST, Some("tools/list".to_string())),
+            ]
+        );
+        for request in requests.iter() {
+            assert_eq!(request.uri, "http://localhost:3000/mcp");
+            assert_eq!(request.header("authorization"), Some("Bearer secret"));
+            assert_eq!(request.header("x-team"), Some("zed"));
+        }
+        assert_eq!(requests[0].header(SESSION_ID_HEADER), None);
+        assert_eq!(requests[3].header(SESSION_ID_HEADER), Some("session-1"));
+        assert_eq!(
+            requests[3].header(PROTOCOL_VERSION_HEADER),
+            Some("2025-06-18")
+        );
+        assert_eq!(
+            requests[2].header(ACCEPT.as_str()),
+            Some(EVENT_STREAM_CONTENT_TYPE)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_failed_request_is_answered_with_error(cx: &mut TestAppContext) {
+        let (http_client, _) = fake_server(|_| response(500, &[], "internal error"));
+        let transport = HttpTransport::new(
+            endpoint(
+                "http://localhost:3000/mcp",
+                ContextServerHttpTransport::StreamableHttp,
+            ),
+            http_client,
+            &cx.to_async(),
+        )
+        .unwrap();
+        let mut messages = transport.receive();
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call"}).to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let response = serde_json::from_str::<Value>(&messages.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 5);
+        assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+    }
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let (http_client, requests) = fake_server(|request| {
+            if request.method == Method::POST {
+                return response(202, &[], "");
+            }
+            if request.header(LAST_EVENT_ID_HEADER).is_some() {
+                return response(405, &[], "");
+            }
+            response(
+                200,
+                &[(CONTENT_TYPE.as_str(), EVENT_STREAM_CONTENT_TYPE)],
+                concat!(
+                    "event: endpoint\n",
+                    "data: /messages?session_id=abc\n\n",
+                    "id: 7\n",
+                    "event: message\n",
+                    "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/tools/list_changed\"}\n\n",
+                ),
+            )
+        });
+
+        let transport = HttpTransport::new(
+            endpoint("http://localhost:3000/sse", ContextServerHttpTransport::Sse),
+            http_client,
+            &cx.to_async(),
+        )
+        .unwrap();
+        let mut messages = transport.receive();
+
+        transport
+            .send(json!({"jsonrpc": "2.0", "id": 0, "method": "initialize"}).to_string())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let notification = serde_json::from_str::<Value>(&messages.next().await.unwrap()).unwrap();
+        assert_eq!(notification["method"], "notifications/tools/list_changed");
+        {
+            let requests = requests.lock();
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[0].method, Method::GET);
+            assert_eq!(requests[0].uri, "http://localhost:3000/sse");
+            assert_eq!(requests[1].method, Method::POST);
+            assert_eq!(
+                requests[1].uri,
+                "http://localhost:3000/messages?session_id=abc"
+            );
+            assert_eq!(requests[1].header("authorization"), Some("Bearer secret"));
+        }
+
+        // The event stream ended, so it is reopened from the last event received.
+        cx.executor().advance_clock(DEFAULT_RECONNECT_DELAY);
+        cx.run_until_parked();
+        let requests = requests.lock();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].method, Method::GET);
+        assert_eq!(requests[2].header(LAST_EVENT_ID_HEADER), Some("7"));
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::{AsyncBufRead, AsyncBufReadExt as _};
+
+/// A server-sent event, as described in the [HTML spec](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+    pub retry: Option<Duration>,
+}
+
+/// Reads the next event from a `text/event-stream` body.
+///
+/// Returns `None` once the stream ends. Events without data are still returned, so that their
+/// `id` and `retry` fields can be tracked for reconnecting.
+pub(crate) async fn read_sse_event<R>(reader: &mut R) -> Result<Option<SseEvent>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut event = SseEvent::default();
+    let mut has_fields = false;
+    let mut has_data = false;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            // An event that isn't terminated by a blank line is discarded.
+            return Ok(None);
+        }
+
+        let field_line = line.trim_end_matches(['\r', '\n']);
+        if field_line.is_empty() {
+            if has_fields {
+                return Ok(Some(event));
+            }
+            continue;
+        }
+        if field_line.starts_with(':') {
+            continue;
+        }
+
+        let (field, value) = match field_line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (field_line, ""),
+        };
+        match field {
+            "event" => event.event = Some(value.to_string()),
+            "data" => {
+                if has_data {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+                has_data = true;
+            }
+            "id" if !value.contains('\0') => event.id = Some(value.to_string()),
+            "retry" => {
+                if let Ok(millis) = value.parse() {
+                    event.retry = Some(Duration::from_millis(millis));
+                }
+            }
+            _ => continue,
+        }
+        has_fields = true;
+    }
+}
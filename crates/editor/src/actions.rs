@@ -733,8 +733,16 @@ actions!(
         ShowCharacterPalette,
         /// Shows edit prediction at cursor.
         ShowEditPrediction,
+        /// Shows the functions calling the symbol under the cursor.
+        ShowIncomingCalls,
+        /// Shows the functions called by the symbol under the cursor.
+        ShowOutgoingCalls,
         /// Shows signature help for the current function.
         ShowSignatureHelp,
+        /// Shows the types implementing or extending the type under the cursor.
+        ShowSubtypes,
+        /// Shows the types the type under the cursor implements or extends.
+        ShowSupertypes,
         /// Shows word completions.
         ShowWordCompletions,
         /// Randomly shuffles selected lines.
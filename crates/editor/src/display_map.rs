@@ -85,6 +85,8 @@ pub enum FoldStatus {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum HighlightKey {
+    /// Declared first, so that any other text highlight takes precedence over semantic tokens.
+    SemanticToken(usize),
     Type(TypeId),
     TypePlus(TypeId, usize),
 }
@@ -94,6 +96,9 @@ pub trait ToDisplayPoint {
 }
 
 type TextHighlights = TreeMap<HighlightKey, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
+/// The semantic token highlights of each buffer, with one list of ranges per language server.
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[Vec<(Range<Anchor>, HighlightStyle)>]>>;
+type LspFoldingRanges = TreeMap<BufferId, Arc<[Range<Anchor>]>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
@@ -116,8 +121,12 @@ pub struct DisplayMap {
     block_map: BlockMap,
     /// Regions of text that should be highlighted.
     text_highlights: TextHighlights,
+    /// Styles of the semantic tokens reported by language servers, per buffer, layered on top of syntax highlighting.
+    semantic_token_highlights: SemanticTokenHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Foldable ranges reported by language servers, per buffer, which supersede indentation based fold range suggestions.
+    lsp_folding_ranges: LspFoldingRanges,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -163,6 +172,8 @@ impl DisplayMap {
             fold_placeholder,
             diagnostics_max_severity,
             text_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
+            lsp_folding_ranges: Default::default(),
             inlay_highlights: Default::default(),
             clip_at_line_ends: false,
             masked: false,
@@ -187,6 +198,8 @@ impl DisplayMap {
             diagnostics_max_severity: self.diagnostics_max_severity,
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
+            lsp_folding_ranges: self.lsp_folding_ranges.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
@@ -488,6 +501,51 @@ impl DisplayMap {
         self.text_highlights.insert(key, Arc::new((style, ranges)));
     }
 
+    /// Replaces the semantic token highlights of the given buffer, given per language server.
+    /// The ranges of each language server are expected to be sorted by their start and not to
+    /// overlap each other, while ranges of different language servers may overlap.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Arc<[Vec<(Range<Anchor>, HighlightStyle)>]>,
+    ) {
+        self.semantic_token_highlights.insert(buffer_id, highlights);
+    }
+
+    pub(crate) fn remove_semantic_token_highlights(&mut self, buffer_id: BufferId) -> bool {
+        self.semantic_token_highlights.remove(&buffer_id).is_some()
+    }
+
+    pub(crate) fn clear_semantic_token_highlights(&mut self) -> bool {
+        let had_highlights = !self.semantic_token_highlights.is_empty();
+        self.semantic_token_highlights = TreeMap::default();
+        had_highlights
+    }
+
+    /// Replaces the language server folding ranges of the given buffer.
+    /// The ranges are expected to be sorted by their start, with the outermost range first.
+    pub(crate) fn set_lsp_folding_ranges(
+        &mut self,
+        buffer_id: BufferId,
+        ranges: Arc<[Range<Anchor>]>,
+    ) {
+        if ranges.is_empty() {
+            self.lsp_folding_ranges.remove(&buffer_id);
+        } else {
+            self.lsp_folding_ranges.insert(buffer_id, ranges);
+        }
+    }
+
+    pub(crate) fn remove_lsp_folding_ranges(&mut self, buffer_id: BufferId) -> bool {
+        self.lsp_folding_ranges.remove(&buffer_id).is_some()
+    }
+
+    pub(crate) fn clear_lsp_folding_ranges(&mut self) -> bool {
+        let had_ranges = !self.lsp_folding_ranges.is_empty();
+        self.lsp_folding_ranges = TreeMap::default();
+        had_ranges
+    }
+
     pub(crate) fn highlight_inlays(
         &mut self,
         type_id: TypeId,
@@ -616,6 +674,7 @@ impl DisplayMap {
 #[derive(Debug, Default)]
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
     pub styles: HighlightStyles,
 }
@@ -749,6 +808,8 @@ pub struct DisplaySnapshot {
     pub crease_snapshot: CreaseSnapshot,
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
+    lsp_folding_ranges: LspFoldingRanges,
     inlay_highlights: InlayHighlights,
     clip_at_line_ends: bool,
     masked: bool,
@@ -956,6 +1017,7 @@ impl DisplaySnapshot {
             self.masked,
             Highlights {
                 text_highlights: Some(&self.text_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
                 styles: highlight_styles,
             },
@@ -1285,6 +1347,49 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether a fold can start at the given row, either because of the language server folding
+    /// ranges of its buffer or, when there are none, because of its indentation.
+    pub fn starts_fold(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.lsp_folding_ranges_for_buffer_row(buffer_row) {
+            Some(ranges) => self.lsp_folding_range_at(ranges, buffer_row).is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    fn lsp_folding_ranges_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<&Arc<[Range<Anchor>]>> {
+        if self.lsp_folding_ranges.is_empty() {
+            return None;
+        }
+        let (buffer, _) = self.buffer_snapshot().buffer_line_for_row(buffer_row)?;
+        self.lsp_folding_ranges.get(&buffer.remote_id())
+    }
+
+    /// Returns the largest of the language server folding ranges starting at the given row,
+    /// spanning from the end of that row to the end of the range's last row.
+    fn lsp_folding_range_at(
+        &self,
+        ranges: &[Range<Anchor>],
+        buffer_row: MultiBufferRow,
+    ) -> Option<Range<Point>> {
+        let buffer_snapshot = self.buffer_snapshot();
+        let first_candidate = ranges
+            .partition_point(|range| range.start.to_point(buffer_snapshot).row < buffer_row.0);
+        let end_row = ranges[first_candidate..]
+            .iter()
+            .map(|range| range.to_point(buffer_snapshot))
+            .take_while(|range| range.start.row == buffer_row.0)
+            .map(|range| range.end.row)
+            .max()
+            .filter(|&end_row| end_row > buffer_row.0)?;
+        Some(
+            Point::new(buffer_row.0, buffer_snapshot.line_len(buffer_row))
+                ..Point::new(end_row, buffer_snapshot.line_len(MultiBufferRow(end_row))),
+        )
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start =
             MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot().line_len(buffer_row));
@@ -1322,6 +1427,17 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(ranges) = self.lsp_folding_ranges_for_buffer_row(buffer_row) {
+            if self.is_line_folded(buffer_row) {
+                return None;
+            }
+            Some(Crease::Inline {
+                range: self.lsp_folding_range_at(ranges, buffer_row)?,
+                placeholder: self.fold_placeholder.clone(),
+                render_toggle: None,
+                render_trailer: None,
+                metadata: None,
+            })
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
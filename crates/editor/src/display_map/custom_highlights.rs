@@ -9,7 +9,7 @@ use std::{
     vec,
 };
 
-use crate::display_map::{HighlightKey, TextHighlights};
+use crate::display_map::{HighlightKey, SemanticTokenHighlights, TextHighlights};
 
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
@@ -20,6 +20,7 @@ pub struct CustomHighlightsChunks<'a> {
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<HighlightKey, HighlightStyle>,
     text_highlights: Option<&'a TextHighlights>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -34,6 +35,7 @@ impl<'a> CustomHighlightsChunks<'a> {
         range: Range<usize>,
         language_aware: bool,
         text_highlights: Option<&'a TextHighlights>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -42,9 +44,11 @@ impl<'a> CustomHighlightsChunks<'a> {
             offset: range.start,
 
             text_highlights,
+            semantic_token_highlights,
             highlight_endpoints: create_highlight_endpoints(
                 &range,
                 text_highlights,
+                semantic_token_highlights,
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
@@ -53,8 +57,12 @@ impl<'a> CustomHighlightsChunks<'a> {
     }
 
     pub fn seek(&mut self, new_range: Range<usize>) {
-        self.highlight_endpoints =
-            create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.highlight_endpoints = create_highlight_endpoints(
+            &new_range,
+            self.text_highlights,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -65,12 +73,59 @@ impl<'a> CustomHighlightsChunks<'a> {
 fn create_highlight_endpoints(
     range: &Range<usize>,
     text_highlights: Option<&TextHighlights>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
     buffer: &MultiBufferSnapshot,
 ) -> iter::Peekable<vec::IntoIter<HighlightEndpoint>> {
     let mut highlight_endpoints = Vec::new();
+    let start = buffer.anchor_after(range.start);
+    let end = buffer.anchor_after(range.end);
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        let mut token_ix = 0;
+        // Tokens of a single language server don't overlap, so their ends are sorted just like
+        // their starts, which is not the case across language servers.
+        for server_tokens in semantic_token_highlights
+            .values()
+            .flat_map(|tokens| tokens.iter())
+        {
+            let start_ix = match server_tokens.binary_search_by(|(probe, _)| {
+                let cmp = probe.end.cmp(&start, buffer);
+                if cmp.is_gt() {
+                    cmp::Ordering::Greater
+                } else {
+                    cmp::Ordering::Less
+                }
+            }) {
+                Ok(i) | Err(i) => i,
+            };
+
+            for (range, style) in &server_tokens[start_ix..] {
+                if range.start.cmp(&end, buffer).is_ge() {
+                    break;
+                }
+
+                let start = range.start.to_offset(buffer);
+                let end = range.end.to_offset(buffer);
+                if start == end {
+                    continue;
+                }
+                // Each token gets its own key, so that tokens of different language servers
+                // overlapping each other are layered rather than cutting each other short.
+                let tag = HighlightKey::SemanticToken(token_ix);
+                token_ix += 1;
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: start,
+                    tag,
+                    style: Some(*style),
+                });
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: end,
+                    tag,
+                    style: None,
+                });
+            }
+        }
+    }
     if let Some(text_highlights) = text_highlights {
-        let start = buffer.anchor_after(range.start);
-        let end = buffer.anchor_after(range.end);
         for (&tag, text_highlights) in text_highlights.iter() {
             let style = text_highlights.0;
             let ranges = &text_highlights.1;
@@ -108,8 +163,8 @@ fn create_highlight_endpoints(
                 });
             }
         }
-        highlight_endpoints.sort();
     }
+    highlight_endpoints.sort();
     highlight_endpoints.into_iter().peekable()
 }
 
@@ -192,6 +247,74 @@ mod tests {
     use rand::prelude::*;
     use util::RandomCharIter;
 
+    #[gpui::test]
+    fn test_overlapping_semantic_tokens_of_different_servers(cx: &mut App) {
+        let text = "let value = other_value;";
+        let buffer = MultiBuffer::build_simple(text, cx);
+        let snapshot = buffer.read(cx).snapshot(cx);
+        let buffer_id = snapshot.as_singleton().unwrap().1;
+        let range = |range: Range<usize>| {
+            snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end)
+        };
+        let bold = HighlightStyle {
+            font_weight: Some(gpui::FontWeight::BOLD),
+            ..Default::default()
+        };
+        let red = HighlightStyle {
+            color: Some(gpui::red()),
+            ..Default::default()
+        };
+
+        // The first server covers the whole statement with a single token, ending after the
+        // tokens of the second server that start after it.
+        let mut semantic_token_highlights = SemanticTokenHighlights::default();
+        semantic_token_highlights.insert(
+            buffer_id,
+            Arc::from(vec![
+                vec![(range(0..text.len()), bold)],
+                vec![(range(4..9), red), (range(12..23), red)],
+            ]),
+        );
+
+        let chunks = |range: Range<usize>| {
+            CustomHighlightsChunks::new(
+                range,
+                false,
+                None,
+                Some(&semantic_token_highlights),
+                &snapshot,
+            )
+            .map(|chunk| {
+                let style = chunk.highlight_style.unwrap_or_default();
+                (
+                    chunk.text.to_string(),
+                    style.font_weight.is_some(),
+                    style.color.is_some(),
+                )
+            })
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            chunks(0..text.len()),
+            [
+                ("let ".to_string(), true, false),
+                ("value".to_string(), true, true),
+                (" = ".to_string(), true, false),
+                ("other_value".to_string(), true, true),
+                (";".to_string(), true, false),
+            ]
+        );
+        assert_eq!(
+            chunks(10..text.len()),
+            [
+                ("= ".to_string(), true, false),
+                ("other_value".to_string(), true, true),
+                (";".to_string(), true, false),
+            ]
+        );
+    }
+
     #[gpui::test(iterations = 100)]
     fn test_random_chunk_bitmaps(cx: &mut App, mut rng: StdRng) {
         // Generate random buffer using existing test infrastructure
@@ -253,8 +376,13 @@ mod tests {
         }
 
         // Get all chunks and verify their bitmaps
-        let chunks =
-            CustomHighlightsChunks::new(0..buffer_snapshot.len(), false, None, &buffer_snapshot);
+        let chunks = CustomHighlightsChunks::new(
+            0..buffer_snapshot.len(),
+            false,
+            None,
+            None,
+            &buffer_snapshot,
+        );
 
         for chunk in chunks {
             let chunk_text = chunk.text;
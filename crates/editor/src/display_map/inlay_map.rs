@@ -1074,6 +1074,7 @@ impl InlaySnapshot {
             buffer_range,
             language_aware,
             highlights.text_highlights,
+            highlights.semantic_token_highlights,
             &self.buffer,
         );
 
@@ -2078,6 +2079,7 @@ mod tests {
 
         let highlights = crate::display_map::Highlights {
             text_highlights: None,
+            semantic_token_highlights: None,
             inlay_highlights: Some(&inlay_highlights),
             styles: crate::display_map::HighlightStyles::default(),
         };
@@ -2193,6 +2195,7 @@ mod tests {
 
             let highlights = crate::display_map::Highlights {
                 text_highlights: None,
+                semantic_token_highlights: None,
                 inlay_highlights: Some(&inlay_highlights),
                 styles: crate::display_map::HighlightStyles::default(),
             };
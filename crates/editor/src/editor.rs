@@ -19,6 +19,7 @@ pub mod display_map;
 mod editor_settings;
 mod element;
 mod git;
+mod hierarchy;
 mod highlight_matching_bracket;
 mod hover_links;
 pub mod hover_popover;
@@ -29,6 +30,8 @@ mod jsx_tag_auto_close;
 mod linked_editing_ranges;
 mod lsp_colors;
 mod lsp_ext;
+mod lsp_folding_ranges;
+mod lsp_semantic_tokens;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -44,6 +47,7 @@ mod edit_prediction_tests;
 #[cfg(test)]
 mod editor_tests;
 mod signature_help;
+mod sticky_scroll;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test;
 
@@ -131,6 +135,8 @@ use lsp::{
     LanguageServerId,
 };
 use lsp_colors::LspColorData;
+use lsp_folding_ranges::LspFoldingRangesData;
+use lsp_semantic_tokens::LspSemanticTokensData;
 use markdown::Markdown;
 use mouse_context_menu::MouseContextMenu;
 use movement::TextLayoutDetails;
@@ -141,9 +147,9 @@ use parking_lot::Mutex;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, InlayId,
-    InvalidationStrategy, Location, LocationLink, PrepareRenameResponse, Project, ProjectItem,
-    ProjectPath, ProjectTransaction, TaskSourceKind,
+    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, DocumentLink,
+    InlayHint, InlayId, InvalidationStrategy, Location, LocationLink, PrepareRenameResponse,
+    Project, ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -1177,6 +1183,10 @@ pub struct Editor {
     colors: Option<LspColorData>,
     post_scroll_update: Task<()>,
     refresh_colors_task: Task<()>,
+    semantic_tokens: Option<LspSemanticTokensData>,
+    refresh_semantic_tokens_task: Task<()>,
+    lsp_folding_ranges: Option<LspFoldingRangesData>,
+    refresh_lsp_folding_ranges_task: Task<()>,
     inlay_hints: Option<LspInlayHintData>,
     folding_newlines: Task<()>,
     pub lookup_key: Option<Box<dyn Any + Send + Sync>>,
@@ -1519,6 +1529,15 @@ struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
     active_index: usize,
     choices: Vec<Option<Vec<String>>>,
+    transforms: Vec<SnippetTransform>,
+}
+
+/// A range of an inserted snippet that mirrors a tabstop through a transform.
+#[derive(Debug)]
+struct SnippetTransform {
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: snippet::Transform,
 }
 
 #[doc(hidden)]
@@ -1849,6 +1868,9 @@ impl Editor {
                     project::Event::RefreshCodeLens => {
                         // we always query lens with actions, without storing them, always refreshing them
                     }
+                    project::Event::RefreshSemanticTokens => {
+                        editor.refresh_semantic_tokens(None, window, cx);
+                    }
                     project::Event::RefreshInlayHints {
                         server_id,
                         request_id,
@@ -2230,6 +2252,10 @@ impl Editor {
             pull_diagnostics_task: Task::ready(()),
             colors: None,
             refresh_colors_task: Task::ready(()),
+            semantic_tokens: None,
+            refresh_semantic_tokens_task: Task::ready(()),
+            lsp_folding_ranges: None,
+            refresh_lsp_folding_ranges_task: Task::ready(()),
             inlay_hints: None,
             next_color_inlay_id: 0,
             post_scroll_update: Task::ready(()),
@@ -2371,6 +2397,12 @@ impl Editor {
             editor.minimap =
                 editor.create_minimap(EditorSettings::get_global(cx).minimap, window, cx);
             editor.colors = Some(LspColorData::new(cx));
+            if EditorSettings::get_global(cx).lsp_semantic_tokens {
+                editor.semantic_tokens = Some(LspSemanticTokensData::new(cx));
+            }
+            if EditorSettings::get_global(cx).lsp_folding_ranges {
+                editor.lsp_folding_ranges = Some(LspFoldingRangesData::default());
+            }
             editor.inlay_hints = Some(LspInlayHintData::new(inlay_hint_settings));
 
             if let Some(buffer) = multi_buffer.read(cx).as_singleton() {
@@ -5794,6 +5826,12 @@ impl Editor {
         cx.stop_propagation();
 
         let buffer_handle = completions_menu.buffer.clone();
+        let selected_text = self
+            .buffer
+            .read(cx)
+            .snapshot(cx)
+            .text_for_range(self.selections.newest_anchor().range())
+            .collect::<String>();
 
         let CompletionEdit {
             new_text,
@@ -5804,6 +5842,7 @@ impl Editor {
             intent,
             &buffer_handle,
             &completions_menu.initial_position.text_anchor,
+            Some(&selected_text),
             cx,
         );
 
@@ -9629,6 +9668,7 @@ impl Editor {
             choices: Option<Vec<String>>,
         }
 
+        let mut transforms = Vec::new();
         let tabstops = self.buffer.update(cx, |buffer, cx| {
             let snippet_text: Arc<str> = snippet.text.clone().into();
             let edits = insertion_ranges
@@ -9642,6 +9682,30 @@ impl Editor {
 
             let snapshot = &*buffer.read(cx);
             let snippet = &snippet;
+
+            let mut delta = 0_isize;
+            for insertion_range in insertion_ranges {
+                let insertion_start = insertion_range.start as isize + delta;
+                delta += snippet.text.len() as isize - insertion_range.len() as isize;
+                let to_anchors = |range: &Range<isize>| {
+                    let start = ((insertion_start + range.start) as usize).min(snapshot.len());
+                    let end = ((insertion_start + range.end) as usize).min(snapshot.len());
+                    snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                };
+                for tabstop in &snippet.tabstops {
+                    let Some(source) = tabstop.ranges.first() else {
+                        continue;
+                    };
+                    for (target, transform) in &tabstop.transforms {
+                        transforms.push(SnippetTransform {
+                            source: to_anchors(source),
+                            target: to_anchors(target),
+                            transform: transform.clone(),
+                        });
+                    }
+                }
+            }
+
             snippet
                 .tabstops
                 .iter()
@@ -9707,6 +9771,7 @@ impl Editor {
                     active_index: 0,
                     ranges,
                     choices,
+                    transforms,
                 });
             }
 
@@ -9772,6 +9837,38 @@ impl Editor {
         Ok(())
     }
 
+    /// Updates the snippet ranges that mirror a tabstop through a transform, after that tabstop was edited.
+    fn refresh_snippet_transforms(&mut self, cx: &mut Context<Self>) {
+        if self
+            .snippet_stack
+            .iter()
+            .all(|snippet| snippet.transforms.is_empty())
+        {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self
+            .snippet_stack
+            .iter()
+            .flat_map(|snippet| &snippet.transforms)
+            .filter_map(|transform| {
+                let source = snapshot
+                    .text_for_range(transform.source.clone())
+                    .collect::<String>();
+                let new_text = transform.transform.apply(&source);
+                let target = transform.target.start.to_offset(&snapshot)
+                    ..transform.target.end.to_offset(&snapshot);
+                let old_text = snapshot.text_for_range(target.clone()).collect::<String>();
+                (old_text != new_text).then_some((target, new_text))
+            })
+            .collect::<Vec<_>>();
+        if !edits.is_empty() {
+            self.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        }
+    }
+
     pub fn move_to_next_snippet_tabstop(
         &mut self,
         window: &mut Window,
@@ -18036,6 +18133,7 @@ impl Editor {
         self.with_selection_effects_deferred(window, cx, |this, window, cx| {
             this.start_transaction_at(Instant::now(), window, cx);
             update(this, window, cx);
+            this.refresh_snippet_transforms(cx);
             this.end_transaction_at(Instant::now(), cx)
         })
     }
@@ -21051,6 +21149,8 @@ impl Editor {
                 for buffer_id in removed_buffer_ids {
                     self.registered_buffers.remove(buffer_id);
                 }
+                self.remove_semantic_tokens(removed_buffer_ids, cx);
+                self.remove_lsp_folding_ranges(removed_buffer_ids, cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved {
                     ids: ids.clone(),
@@ -21227,6 +21327,26 @@ impl Editor {
             self.refresh_colors_for_visible_range(None, window, cx);
         }
 
+        if self.mode.is_full() {
+            let semantic_tokens_enabled = EditorSettings::get_global(cx).lsp_semantic_tokens;
+            if semantic_tokens_enabled && self.semantic_tokens.is_none() {
+                self.semantic_tokens = Some(LspSemanticTokensData::new(cx));
+                self.refresh_semantic_tokens(None, window, cx);
+            } else if !semantic_tokens_enabled && self.semantic_tokens.is_some() {
+                self.clear_semantic_tokens(cx);
+            } else {
+                self.refresh_semantic_token_styles(cx);
+            }
+
+            let folding_ranges_enabled = EditorSettings::get_global(cx).lsp_folding_ranges;
+            if folding_ranges_enabled && self.lsp_folding_ranges.is_none() {
+                self.lsp_folding_ranges = Some(LspFoldingRangesData::default());
+                self.refresh_lsp_folding_ranges(None, window, cx);
+            } else if !folding_ranges_enabled && self.lsp_folding_ranges.is_some() {
+                self.clear_lsp_folding_ranges(cx);
+            }
+        }
+
         cx.notify();
     }
 
@@ -22074,6 +22194,8 @@ impl Editor {
     ) {
         self.pull_diagnostics(for_buffer, window, cx);
         self.refresh_colors_for_visible_range(for_buffer, window, cx);
+        self.refresh_semantic_tokens(for_buffer, window, cx);
+        self.refresh_lsp_folding_ranges(for_buffer, window, cx);
     }
 
     fn register_visible_buffers(&mut self, cx: &mut Context<Self>) {
@@ -22156,6 +22278,7 @@ fn process_completion_for_edit(
     intent: CompletionIntent,
     buffer: &Entity<Buffer>,
     cursor_position: &text::Anchor,
+    selected_text: Option<&str>,
     cx: &mut Context<Editor>,
 ) -> CompletionEdit {
     let buffer = buffer.read(cx);
@@ -22181,7 +22304,15 @@ fn process_completion_for_edit(
         {
             snippet_source = label;
         }
-        match Snippet::parse(&snippet_source).log_err() {
+        let offset = cursor_position.to_offset(&buffer_snapshot);
+        let parsed_snippet = project::parse_snippet_for_buffer(
+            &snippet_source,
+            &buffer_snapshot,
+            offset,
+            selected_text,
+            cx,
+        );
+        match parsed_snippet.log_err() {
             Some(parsed_snippet) => (Some(parsed_snippet.clone()), parsed_snippet.text),
             None => (None, completion.new_text.clone()),
         }
@@ -22858,6 +22989,13 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Result<Option<Vec<LocationLink>>>>>;
 
+    fn document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLink>>>>;
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -23304,6 +23442,17 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn document_link(
+        &self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut App,
+    ) -> Option<Task<Result<Option<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.document_link_at(buffer, position, cx)
+        }))
+    }
+
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool {
         self.update(cx, |project, cx| {
             if project
@@ -23729,7 +23878,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_fold(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
@@ -53,7 +53,10 @@ pub struct EditorSettings {
     pub diagnostics_max_severity: Option<DiagnosticSeverity>,
     pub inline_code_actions: bool,
     pub drag_and_drop_selection: DragAndDropSelection,
+    pub sticky_scroll: StickyScroll,
     pub lsp_document_colors: DocumentColorsRenderMode,
+    pub lsp_semantic_tokens: bool,
+    pub lsp_folding_ranges: bool,
     pub minimum_contrast_for_highlights: f32,
     pub completion_menu_scrollbar: ShowScrollbar,
 }
@@ -151,6 +154,19 @@ pub struct DragAndDropSelection {
     pub delay: DelayMs,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StickyScroll {
+    /// When true, the lines opening the scopes that enclose the top of the viewport stay pinned.
+    ///
+    /// Default: true
+    pub enabled: bool,
+
+    /// The maximum number of nested scopes to pin at once.
+    ///
+    /// Default: 5
+    pub max_depth: usize,
+}
+
 /// Default options for buffer and project search items.
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct SearchSettings {
@@ -185,6 +201,7 @@ impl Settings for EditorSettings {
         let toolbar = editor.toolbar.unwrap();
         let search = editor.search.unwrap();
         let drag_and_drop_selection = editor.drag_and_drop_selection.unwrap();
+        let sticky_scroll = editor.sticky_scroll.unwrap();
         Self {
             cursor_blink: editor.cursor_blink.unwrap(),
             cursor_shape: editor.cursor_shape.map(Into::into),
@@ -267,7 +284,13 @@ impl Settings for EditorSettings {
                 enabled: drag_and_drop_selection.enabled.unwrap(),
                 delay: drag_and_drop_selection.delay.unwrap(),
             },
+            sticky_scroll: StickyScroll {
+                enabled: sticky_scroll.enabled.unwrap(),
+                max_depth: sticky_scroll.max_depth.unwrap() as usize,
+            },
             lsp_document_colors: editor.lsp_document_colors.unwrap(),
+            lsp_semantic_tokens: editor.lsp_semantic_tokens.unwrap(),
+            lsp_folding_ranges: editor.lsp_folding_ranges.unwrap(),
             minimum_contrast_for_highlights: editor.minimum_contrast_for_highlights.unwrap().0,
             completion_menu_scrollbar: editor.completion_menu_scrollbar.map(Into::into).unwrap(),
         }
@@ -11339,6 +11339,163 @@ async fn test_snippets(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_snippet_transforms(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        ˇ
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        let snippet = Snippet::parse(indoc! {"
+            struct ${1:foo_bar};
+            impl ${1/(.*)/${1:/pascalcase}/} {}$0"})
+        .unwrap();
+        let insertion_ranges = editor
+            .selections
+            .all(&editor.display_snapshot(cx))
+            .iter()
+            .map(|s| s.range())
+            .collect::<Vec<_>>();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, window, cx)
+            .unwrap();
+    });
+
+    cx.assert_editor_state(indoc! {"
+        struct «foo_barˇ»;
+        impl FooBar {}
+    "});
+
+    // Editing the tabstop updates its transformed mirror.
+    cx.simulate_input("my_type");
+    cx.assert_editor_state(indoc! {"
+        struct my_typeˇ;
+        impl MyType {}
+    "});
+
+    cx.update_editor(|editor, window, cx| assert!(editor.move_to_next_snippet_tabstop(window, cx)));
+    cx.assert_editor_state(indoc! {"
+        struct my_type;
+        impl MyType {}ˇ
+    "});
+
+    // Once the snippet is done, its mirrors are left alone.
+    cx.simulate_input("!");
+    cx.assert_editor_state(indoc! {"
+        struct my_type;
+        impl MyType {}!ˇ
+    "});
+}
+
+#[gpui::test]
+async fn test_snippet_variables_in_completions(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions::default()),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    cx.set_state("let x = «valueˇ»;");
+    cx.lsp
+        .set_request_handler::<lsp::request::Completion, _, _>(move |_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "Some".to_string(),
+                    insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                    text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                        range: lsp::Range::new(lsp::Position::new(0, 8), lsp::Position::new(0, 13)),
+                        new_text: "Some(${1:$TM_SELECTED_TEXT}) // $TM_FILENAME$0".to_string(),
+                    })),
+                    ..Default::default()
+                },
+            ])))
+        });
+    cx.update_editor(|editor, window, cx| {
+        editor.show_completions(&ShowCompletions, window, cx);
+    });
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, window, cx| {
+        editor.confirm_completion(&ConfirmCompletion::default(), window, cx)
+    });
+    cx.executor().run_until_parked();
+
+    // The selection replaced by the completion is the selected text.
+    cx.assert_editor_state("let x = Some(«valueˇ») // file.rs;");
+}
+
+#[gpui::test]
+async fn test_snippet_variables_in_lsp_snippet_edits(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "let x = value;\n".into())
+        .await;
+
+    let project = Project::test(fs, [path!("/file.rs").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    let edit = serde_json::from_value::<lsp::WorkspaceEdit>(json!({
+        "documentChanges": [{
+            "textDocument": {
+                "uri": lsp::Uri::from_file_path(path!("/file.rs")).unwrap(),
+                "version": null,
+            },
+            "edits": [{
+                "range": {
+                    "start": { "line": 0, "character": 8 },
+                    "end": { "line": 0, "character": 13 },
+                },
+                "snippet": {
+                    "kind": "snippet",
+                    "value": "Some(${1:$TM_SELECTED_TEXT}) // $TM_FILENAME:$TM_LINE_NUMBER$0",
+                },
+            }],
+        }],
+    }))
+    .unwrap();
+    fake_server
+        .server
+        .request::<lsp::request::ApplyWorkspaceEdit>(lsp::ApplyWorkspaceEditParams {
+            label: None,
+            edit,
+        })
+        .await
+        .into_response()
+        .unwrap();
+    cx.run_until_parked();
+
+    // The snippet edit isn't in the active entry, so it's applied as text, with its variables
+    // expanded and the range it replaces as the selected text.
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "let x = Some(value) // file.rs:1;\n");
+    });
+}
+
 #[gpui::test]
 async fn test_snippet_indentation(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
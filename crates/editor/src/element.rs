@@ -8,8 +8,8 @@ use crate::{
     HandleInput, HoveredCursor, InlayHintRefreshReason, JumpData, LineDown, LineHighlight, LineUp,
     MAX_LINE_LEN, MINIMAP_FONT_SIZE, MULTI_BUFFER_EXCERPT_HEADER_HEIGHT, OpenExcerpts, PageDown,
     PageUp, PhantomBreakpointIndicator, Point, RowExt, RowRangeExt, SelectPhase,
-    SelectedTextHighlight, Selection, SelectionDragState, SizingBehavior, SoftWrap,
-    StickyHeaderExcerpt, ToPoint, ToggleFold, ToggleFoldAll,
+    SelectedTextHighlight, Selection, SelectionDragState, SelectionEffects, SizingBehavior,
+    SoftWrap, StickyHeaderExcerpt, ToPoint, ToggleFold, ToggleFoldAll,
     code_context_menus::{CodeActionsMenu, MENU_ASIDE_MAX_WIDTH, MENU_ASIDE_MIN_WIDTH, MENU_GAP},
     display_map::{
         Block, BlockContext, BlockStyle, ChunkRendererId, DisplaySnapshot, EditorMargins,
@@ -29,7 +29,7 @@ use crate::{
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition},
     scroll::{
-        ActiveScrollbarState, ScrollOffset, ScrollPixelOffset, ScrollbarThumbState,
+        ActiveScrollbarState, Autoscroll, ScrollOffset, ScrollPixelOffset, ScrollbarThumbState,
         scroll_amount::ScrollAmount,
     },
 };
@@ -580,7 +580,11 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(editor, window, Editor::show_incoming_calls);
+        register_action(editor, window, Editor::show_outgoing_calls);
         register_action(editor, window, Editor::show_signature_help);
+        register_action(editor, window, Editor::show_supertypes);
+        register_action(editor, window, Editor::show_subtypes);
         register_action(editor, window, Editor::signature_help_prev);
         register_action(editor, window, Editor::signature_help_next);
         register_action(editor, window, Editor::next_edit_prediction);
@@ -4502,6 +4506,124 @@ impl EditorElement {
         header
     }
 
+    fn layout_sticky_scopes(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<ScrollOffset>,
+        scroll_pixel_position: gpui::Point<ScrollPixelOffset>,
+        line_height: Pixels,
+        right_margin: Pixels,
+        editor_width: Pixels,
+        hitbox: &Hitbox,
+        gutter_hitbox: &Hitbox,
+        gutter_dimensions: GutterDimensions,
+        content_origin: gpui::Point<Pixels>,
+        is_row_soft_wrapped: impl Copy + Fn(usize) -> bool,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<StickyScopeLayout> {
+        let scopes = self
+            .editor
+            .read(cx)
+            .sticky_scopes(scroll_position.y, snapshot, cx);
+        if scopes.is_empty() {
+            return Vec::new();
+        }
+
+        let include_line_numbers = snapshot
+            .show_line_numbers
+            .unwrap_or_else(|| EditorSettings::get_global(cx).gutter.line_numbers);
+        let editor_bg_color = cx.theme().colors().editor_background;
+        let border_color = cx.theme().colors().border_variant;
+        let line_number_color = cx.theme().colors().editor_line_number;
+        let available_width = hitbox.size.width - right_margin;
+        let text_x =
+            Pixels::from(ScrollPixelOffset::from(content_origin.x) - scroll_pixel_position.x);
+        let last_slot = scopes.len() - 1;
+
+        scopes
+            .into_iter()
+            .enumerate()
+            .map(|(slot, scope)| {
+                // Slide the line up, under the lines of the outer scopes, as the end of its
+                // scope scrolls past it.
+                let rows_from_top = (scope.end_row.as_f64() - scroll_position.y).min(slot as f64);
+                let origin =
+                    hitbox.origin + point(Pixels::ZERO, line_height * rows_from_top as f32);
+
+                let line = layout_line(
+                    scope.start_row,
+                    snapshot,
+                    &self.style,
+                    editor_width,
+                    is_row_soft_wrapped,
+                    window,
+                    cx,
+                );
+                let line_number = include_line_numbers.then(|| {
+                    let buffer_row = snapshot
+                        .display_point_to_point(DisplayPoint::new(scope.start_row, 0), Bias::Left)
+                        .row;
+                    let shaped_line = self.shape_line_number(
+                        SharedString::from((buffer_row + 1).to_string()),
+                        line_number_color,
+                        window,
+                    );
+                    let x = gutter_hitbox.origin.x + gutter_hitbox.size.width
+                        - shaped_line.width
+                        - gutter_dimensions.right_padding;
+                    (shaped_line, point(x, origin.y))
+                });
+
+                let anchor = scope.anchor;
+                let mut element = div()
+                    .id(("sticky-scope", slot))
+                    .w(available_width)
+                    .h(line_height)
+                    .bg(editor_bg_color)
+                    .when(slot == last_slot, |div| {
+                        div.border_b_1().border_color(border_color)
+                    })
+                    .cursor_pointer()
+                    .block_mouse_except_scroll()
+                    .on_click(window.listener_for(
+                        &self.editor,
+                        move |editor, _: &ClickEvent, window, cx| {
+                            // Keep the scope's first line where it was pinned, below the lines
+                            // of the scopes it is nested in.
+                            editor.change_selections(
+                                SelectionEffects::scroll(Autoscroll::top_relative(slot)),
+                                window,
+                                cx,
+                                |s| s.select_anchor_ranges([anchor..anchor]),
+                            );
+                        },
+                    ))
+                    .into_any_element();
+                element.prepaint_as_root(
+                    origin,
+                    size(
+                        AvailableSpace::Definite(available_width),
+                        AvailableSpace::Definite(line_height),
+                    ),
+                    window,
+                    cx,
+                );
+
+                StickyScopeLayout {
+                    element,
+                    line,
+                    line_number,
+                    text_bounds: Bounds::from_corners(
+                        point(gutter_hitbox.origin.x + gutter_hitbox.size.width, origin.y),
+                        point(hitbox.origin.x + available_width, origin.y + line_height),
+                    ),
+                    text_origin: point(text_x, origin.y),
+                }
+            })
+            .collect()
+    }
+
     fn layout_cursor_popovers(
         &self,
         line_height: Pixels,
@@ -5914,6 +6036,44 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_scopes(
+        &mut self,
+        layout: &mut EditorLayout,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let line_height = layout.position_map.line_height;
+        // Paint the innermost scope first, so the outer ones cover it as it slides out of view.
+        for scope in layout.sticky_scopes.iter_mut().rev() {
+            scope.element.paint(window, cx);
+            if let Some((line_number, origin)) = &scope.line_number {
+                line_number
+                    .paint(*origin, line_height, window, cx)
+                    .log_err();
+            }
+            window.with_content_mask(
+                Some(ContentMask {
+                    bounds: scope.text_bounds,
+                }),
+                |window| {
+                    let mut fragment_origin = scope.text_origin;
+                    for fragment in &scope.line.fragments {
+                        match fragment {
+                            LineFragment::Text(line) => {
+                                line.paint(fragment_origin, line_height, window, cx)
+                                    .log_err();
+                                fragment_origin.x += line.width;
+                            }
+                            LineFragment::Element { size, .. } => {
+                                fragment_origin.x += size.width;
+                            }
+                        }
+                    }
+                },
+            );
+        }
+    }
+
     fn paint_gutter_diff_hunks(layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
         if layout.display_hunks.is_empty() {
             return;
@@ -9174,6 +9334,24 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let sticky_scopes = window.with_element_namespace("sticky_scopes", |window| {
+                        self.layout_sticky_scopes(
+                            &snapshot,
+                            scroll_position,
+                            scroll_pixel_position,
+                            line_height,
+                            right_margin,
+                            editor_width,
+                            &hitbox,
+                            &gutter_hitbox,
+                            gutter_dimensions,
+                            content_origin,
+                            is_row_soft_wrapped,
+                            window,
+                            cx,
+                        )
+                    });
+
                     let crease_trailers =
                         window.with_element_namespace("crease_trailers", |window| {
                             self.prepaint_crease_trailers(
@@ -9636,6 +9814,7 @@ impl Element for EditorElement {
                         tab_invisible,
                         space_invisible,
                         sticky_buffer_header,
+                        sticky_scopes,
                         expand_toggles,
                     }
                 })
@@ -9700,6 +9879,12 @@ impl Element for EditorElement {
                         });
                     }
 
+                    if !layout.sticky_scopes.is_empty() {
+                        window.with_element_namespace("sticky_scopes", |window| {
+                            self.paint_sticky_scopes(layout, window, cx);
+                        });
+                    }
+
                     window.with_element_namespace("blocks", |window| {
                         if let Some(mut sticky_header) = layout.sticky_buffer_header.take() {
                             sticky_header.paint(window, cx)
@@ -9814,6 +9999,7 @@ pub struct EditorLayout {
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
     sticky_buffer_header: Option<AnyElement>,
+    sticky_scopes: Vec<StickyScopeLayout>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
@@ -10246,6 +10432,14 @@ struct CreaseTrailerLayout {
     bounds: Bounds<Pixels>,
 }
 
+struct StickyScopeLayout {
+    element: AnyElement,
+    line: LineWithInvisibles,
+    line_number: Option<(ShapedLine, gpui::Point<Pixels>)>,
+    text_bounds: Bounds<Pixels>,
+    text_origin: gpui::Point<Pixels>,
+}
+
 pub(crate) struct PositionMap {
     pub size: Size<Pixels>,
     pub line_height: Pixels,
@@ -0,0 +1,690 @@
+use crate::{
+    Editor,
+    actions::{ShowIncomingCalls, ShowOutgoingCalls, ShowSubtypes, ShowSupertypes},
+};
+use gpui::{
+    Entity, EntityId, EventEmitter, FocusHandle, Focusable, ScrollStrategy, Task,
+    UniformListScrollHandle, WeakEntity, uniform_list,
+};
+use language::{Anchor, Location, ToOffset as _, ToPoint as _};
+use project::{CallHierarchyItem, Project, TypeHierarchyItem};
+use std::ops::Range;
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{Pane, SplitDirection, Workspace, item::Item};
+
+/// How deep "Expand All" descends, as hierarchies like the implementors of a
+/// widely used trait can be arbitrarily large.
+const MAX_EXPAND_ALL_DEPTH: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+/// A symbol shown in a [`HierarchyView`].
+#[derive(Clone)]
+enum HierarchyEntry {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyEntry {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn location(&self) -> &Location {
+        match self {
+            Self::Call(item) => &item.location,
+            Self::Type(item) => &item.location,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+
+    /// Identifies the symbol independently of the request that returned it.
+    fn key(&self, cx: &App) -> (EntityId, usize) {
+        let buffer = &self.location().buffer;
+        (
+            buffer.entity_id(),
+            self.selection_range().start.to_offset(buffer.read(cx)),
+        )
+    }
+
+    /// Queries the relatives of this entry, along with the locations relating them to it.
+    fn children(
+        &self,
+        kind: HierarchyKind,
+        project: &mut Project,
+        cx: &mut Context<Project>,
+    ) -> Task<anyhow::Result<Vec<(HierarchyEntry, Vec<Location>)>>> {
+        let calls = match (kind, self) {
+            (HierarchyKind::IncomingCalls, Self::Call(item)) => project.incoming_calls(item, cx),
+            (HierarchyKind::OutgoingCalls, Self::Call(item)) => project.outgoing_calls(item, cx),
+            (HierarchyKind::Supertypes, Self::Type(item)) => {
+                let types = project.supertypes(item, cx);
+                return cx.spawn(async move |_, _| Ok(type_entries(types.await?)));
+            }
+            (HierarchyKind::Subtypes, Self::Type(item)) => {
+                let types = project.subtypes(item, cx);
+                return cx.spawn(async move |_, _| Ok(type_entries(types.await?)));
+            }
+            _ => return Task::ready(Ok(Vec::new())),
+        };
+        cx.spawn(async move |_, _| {
+            Ok(calls
+                .await?
+                .into_iter()
+                .map(|call| (Self::Call(call.item), call.call_sites))
+                .collect())
+        })
+    }
+}
+
+fn type_entries(items: Vec<TypeHierarchyItem>) -> Vec<(HierarchyEntry, Vec<Location>)> {
+    items
+        .into_iter()
+        .map(|item| (HierarchyEntry::Type(item), Vec::new()))
+        .collect()
+}
+
+impl Editor {
+    pub fn show_incoming_calls(
+        &mut self,
+        _: &ShowIncomingCalls,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_hierarchy(HierarchyKind::IncomingCalls, window, cx);
+    }
+
+    pub fn show_outgoing_calls(
+        &mut self,
+        _: &ShowOutgoingCalls,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_hierarchy(HierarchyKind::OutgoingCalls, window, cx);
+    }
+
+    pub fn show_supertypes(
+        &mut self,
+        _: &ShowSupertypes,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.show_hierarchy(HierarchyKind::Supertypes, window, cx);
+    }
+
+    pub fn show_subtypes(&mut self, _: &ShowSubtypes, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_hierarchy(HierarchyKind::Subtypes, window, cx);
+    }
+
+    fn show_hierarchy(&mut self, kind: HierarchyKind, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        let roots = project.update(cx, |project, cx| match kind {
+            HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls => {
+                let items = project.prepare_call_hierarchy(&buffer, position, cx);
+                cx.spawn(async move |_, _| {
+                    anyhow::Ok(
+                        items
+                            .await?
+                            .into_iter()
+                            .map(HierarchyEntry::Call)
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            }
+            HierarchyKind::Supertypes | HierarchyKind::Subtypes => {
+                let items = project.prepare_type_hierarchy(&buffer, position, cx);
+                cx.spawn(async move |_, _| {
+                    anyhow::Ok(
+                        items
+                            .await?
+                            .into_iter()
+                            .map(HierarchyEntry::Type)
+                            .collect::<Vec<_>>(),
+                    )
+                })
+            }
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            let roots = roots.await?;
+            if roots.is_empty() {
+                return anyhow::Ok(());
+            }
+            workspace.update_in(cx, |workspace, window, cx| {
+                HierarchyView::deploy(workspace, project, roots, kind, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+/// A tree of the callers, callees, supertypes or subtypes of the symbol under the cursor,
+/// expanded lazily.
+pub struct HierarchyView {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    /// The pane that locations are opened in, so that the view itself stays visible.
+    target_pane: WeakEntity<Pane>,
+    kind: HierarchyKind,
+    roots: Vec<HierarchyEntry>,
+    nodes: Vec<HierarchyNode>,
+    root_nodes: Vec<usize>,
+    visible_nodes: Vec<usize>,
+    selected_index: Option<usize>,
+    /// Whether nodes are expanded as soon as they are loaded, until the tree is collapsed.
+    expanding_all: bool,
+    /// Bumped whenever the tree is rebuilt, so that responses for stale nodes are dropped.
+    generation: usize,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+struct HierarchyNode {
+    entry: HierarchyEntry,
+    /// Where the entry calls or is called by its parent; empty for type hierarchies.
+    call_sites: Vec<Location>,
+    parent: Option<usize>,
+    depth: usize,
+    /// Whether the entry also appears among its ancestors.
+    recursive: bool,
+    expanded: bool,
+    children: HierarchyNodeChildren,
+}
+
+enum HierarchyNodeChildren {
+    NotLoaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+impl HierarchyView {
+    fn deploy(
+        workspace: &mut Workspace,
+        project: Entity<Project>,
+        roots: Vec<HierarchyEntry>,
+        kind: HierarchyKind,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(existing) = workspace.item_of_type::<HierarchyView>(cx) {
+            existing.update(cx, |view, cx| {
+                view.kind = kind;
+                view.roots = roots;
+                view.rebuild(cx);
+            });
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let target_pane = workspace.active_pane().downgrade();
+        let workspace_handle = workspace.weak_handle();
+        let view = cx.new(|cx| Self::new(workspace_handle, project, target_pane, roots, kind, cx));
+        workspace.split_item(SplitDirection::Right, Box::new(view), window, cx);
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        target_pane: WeakEntity<Pane>,
+        roots: Vec<HierarchyEntry>,
+        kind: HierarchyKind,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            workspace,
+            project,
+            target_pane,
+            kind,
+            roots,
+            nodes: Vec::new(),
+            root_nodes: Vec::new(),
+            visible_nodes: Vec::new(),
+            selected_index: None,
+            expanding_all: false,
+            generation: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        };
+        this.rebuild(cx);
+        this
+    }
+
+    fn rebuild(&mut self, cx: &mut Context<Self>) {
+        self.generation += 1;
+        self.expanding_all = false;
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|entry| HierarchyNode {
+                entry: entry.clone(),
+                call_sites: Vec::new(),
+                parent: None,
+                depth: 0,
+                recursive: false,
+                expanded: false,
+                children: HierarchyNodeChildren::NotLoaded,
+            })
+            .collect();
+        self.root_nodes = (0..self.nodes.len()).collect();
+        self.selected_index = (!self.nodes.is_empty()).then_some(0);
+        for node_ix in self.root_nodes.clone() {
+            self.set_expanded(node_ix, true, cx);
+        }
+        self.update_visible_nodes();
+        cx.notify();
+    }
+
+    fn toggle_kind(&mut self, cx: &mut Context<Self>) {
+        self.kind = self.kind.opposite();
+        self.rebuild(cx);
+    }
+
+    /// Expands every node, loading the children of each until the depth limit is reached
+    /// or an entry repeats one of its ancestors.
+    fn expand_all(&mut self, cx: &mut Context<Self>) {
+        self.expanding_all = true;
+        for node_ix in 0..self.nodes.len() {
+            if self.should_expand_all(node_ix) {
+                self.set_expanded(node_ix, true, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    fn collapse_all(&mut self, cx: &mut Context<Self>) {
+        self.expanding_all = false;
+        for node in &mut self.nodes {
+            node.expanded = false;
+        }
+        self.update_visible_nodes();
+        cx.notify();
+    }
+
+    fn should_expand_all(&self, node_ix: usize) -> bool {
+        let node = &self.nodes[node_ix];
+        !node.recursive && node.depth < MAX_EXPAND_ALL_DEPTH
+    }
+
+    fn set_expanded(&mut self, node_ix: usize, expanded: bool, cx: &mut Context<Self>) {
+        let node = &mut self.nodes[node_ix];
+        node.expanded = expanded;
+        if expanded && matches!(node.children, HierarchyNodeChildren::NotLoaded) {
+            let entry = node.entry.clone();
+            let kind = self.kind;
+            let generation = self.generation;
+            let children = self
+                .project
+                .update(cx, |project, cx| entry.children(kind, project, cx));
+            let task = cx.spawn(async move |this, cx| {
+                let children = children.await.log_err().unwrap_or_default();
+                this.update(cx, |this, cx| {
+                    if this.generation == generation {
+                        this.insert_children(node_ix, children, cx);
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+            self.nodes[node_ix].children = HierarchyNodeChildren::Loading(task);
+        }
+        self.update_visible_nodes();
+    }
+
+    fn insert_children(
+        &mut self,
+        parent_ix: usize,
+        children: Vec<(HierarchyEntry, Vec<Location>)>,
+        cx: &mut Context<Self>,
+    ) {
+        let mut ancestor_keys = Vec::new();
+        let mut ancestor = Some(parent_ix);
+        while let Some(node_ix) = ancestor {
+            ancestor_keys.push(self.nodes[node_ix].entry.key(cx));
+            ancestor = self.nodes[node_ix].parent;
+        }
+
+        let depth = self.nodes[parent_ix].depth + 1;
+        let mut child_nodes = Vec::with_capacity(children.len());
+        for (entry, call_sites) in children {
+            child_nodes.push(self.nodes.len());
+            let recursive = ancestor_keys.contains(&entry.key(cx));
+            self.nodes.push(HierarchyNode {
+                entry,
+                call_sites,
+                parent: Some(parent_ix),
+                depth,
+                recursive,
+                expanded: false,
+                children: HierarchyNodeChildren::NotLoaded,
+            });
+        }
+        self.nodes[parent_ix].children = HierarchyNodeChildren::Loaded(child_nodes.clone());
+
+        if self.expanding_all && self.nodes[parent_ix].expanded {
+            for node_ix in child_nodes {
+                if self.should_expand_all(node_ix) {
+                    self.set_expanded(node_ix, true, cx);
+                }
+            }
+        }
+        self.update_visible_nodes();
+    }
+
+    fn update_visible_nodes(&mut self) {
+        let selected_node = self
+            .selected_index
+            .and_then(|ix| self.visible_nodes.get(ix).copied());
+
+        let mut visible_nodes = Vec::with_capacity(self.nodes.len());
+        let mut stack = self.root_nodes.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node_ix) = stack.pop() {
+            visible_nodes.push(node_ix);
+            let node = &self.nodes[node_ix];
+            if node.expanded
+                && let HierarchyNodeChildren::Loaded(children) = &node.children
+            {
+                stack.extend(children.iter().rev().copied());
+            }
+        }
+        self.visible_nodes = visible_nodes;
+
+        if let Some(selected_node) = selected_node {
+            self.selected_index = self
+                .visible_nodes
+                .iter()
+                .position(|node_ix| *node_ix == selected_node)
+                .or(Some(0));
+        }
+    }
+
+    fn select_index(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(index);
+        self.scroll_handle
+            .scroll_to_item(index, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.visible_nodes.is_empty() {
+            return;
+        }
+        let index = match self.selected_index {
+            Some(ix) if ix + 1 < self.visible_nodes.len() => ix + 1,
+            Some(ix) => ix,
+            None => 0,
+        };
+        self.select_index(index, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.visible_nodes.is_empty() {
+            return;
+        }
+        let index = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_index(index, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.selected_index {
+            self.open_index(index, window, cx);
+        }
+    }
+
+    fn toggle_index(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(&node_ix) = self.visible_nodes.get(index) {
+            let expanded = self.nodes[node_ix].expanded;
+            self.set_expanded(node_ix, !expanded, cx);
+            cx.notify();
+        }
+    }
+
+    /// Reveals the call site of the entry, or the entry's definition when there is none.
+    fn open_index(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(&node_ix) = self.visible_nodes.get(index) else {
+            return;
+        };
+        let node = &self.nodes[node_ix];
+        let (buffer, range) = match node.call_sites.first() {
+            Some(call_site) => (call_site.buffer.clone(), call_site.range.clone()),
+            None => (
+                node.entry.location().buffer.clone(),
+                node.entry.selection_range().clone(),
+            ),
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let target_pane = self.target_pane.upgrade();
+        workspace.update(cx, |workspace, cx| {
+            let pane = target_pane.unwrap_or_else(|| workspace.active_pane().clone());
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                buffer.clone(),
+                true,
+                false,
+                window,
+                cx,
+            );
+            let point_range: Range<_> = {
+                let snapshot = buffer.read(cx);
+                range.start.to_point(snapshot)..range.end.to_point(snapshot)
+            };
+            editor.update(cx, |editor, cx| {
+                editor.go_to_singleton_buffer_range(point_range, window, cx);
+            });
+        });
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        range
+            .filter_map(|index| {
+                let node = &self.nodes[*self.visible_nodes.get(index)?];
+                let location = {
+                    let buffer = node.entry.location().buffer.read(cx);
+                    let row = node.entry.selection_range().start.to_point(buffer).row + 1;
+                    buffer
+                        .file()
+                        .map(|file| format!("{}:{row}", file.file_name(cx)))
+                        .unwrap_or_else(|| format!("{row}"))
+                };
+                let call_count = node.call_sites.len();
+                Some(
+                    ListItem::new(index)
+                        .spacing(ListItemSpacing::Sparse)
+                        .indent_level(node.depth)
+                        .indent_step_size(px(12.))
+                        .always_show_disclosure_icon(true)
+                        .toggle(match &node.children {
+                            HierarchyNodeChildren::Loaded(children) if children.is_empty() => None,
+                            _ => Some(node.expanded),
+                        })
+                        .toggle_state(self.selected_index == Some(index))
+                        .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_index(index, cx)))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.selected_index = Some(index);
+                            this.open_index(index, window, cx);
+                            cx.notify();
+                        }))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(Label::new(node.entry.name().to_string()))
+                                .when_some(node.entry.detail(), |this, detail| {
+                                    this.child(
+                                        Label::new(detail.to_string())
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted)
+                                            .truncate(),
+                                    )
+                                })
+                                .when(call_count > 1, |this| {
+                                    this.child(
+                                        Label::new(format!("{call_count} calls"))
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                })
+                                .when(node.recursive, |this| {
+                                    this.child(
+                                        Label::new("recursive")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                })
+                                .child(
+                                    Label::new(location)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl EventEmitter<()> for HierarchyView {}
+
+impl Focusable for HierarchyView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HierarchyView {
+    type Event = ();
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        match self.roots.as_slice() {
+            [root] => format!("{} of {}", self.kind.label(), root.name()).into(),
+            _ => self.kind.label().into(),
+        }
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl Render for HierarchyView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let opposite = self.kind.opposite();
+        v_flex()
+            .key_context("HierarchyView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.kind.label()).size(LabelSize::Small))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new("expand-all", IconName::ExpandVertical)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Expand All"))
+                                    .on_click(cx.listener(|this, _, _, cx| this.expand_all(cx))),
+                            )
+                            .child(
+                                IconButton::new("collapse-all", IconName::ListCollapse)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Collapse All"))
+                                    .on_click(cx.listener(|this, _, _, cx| this.collapse_all(cx))),
+                            )
+                            .child(
+                                IconButton::new("toggle-hierarchy-kind", IconName::ArrowRightLeft)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text(format!("Show {}", opposite.label())))
+                                    .on_click(cx.listener(|this, _, _, cx| this.toggle_kind(cx))),
+                            ),
+                    ),
+            )
+            .child(
+                uniform_list(
+                    "hierarchy-entries",
+                    self.visible_nodes.len(),
+                    cx.processor(|this, range: Range<usize>, window, cx| {
+                        this.render_entries(range, window, cx)
+                    }),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .size_full(),
+            )
+    }
+}
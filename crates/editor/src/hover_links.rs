@@ -1,8 +1,8 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind,
-    Navigated, PointForPosition, SelectPhase, editor_settings::GoToDefinitionFallback,
-    scroll::ScrollAmount,
+    Navigated, PointForPosition, SelectPhase, SemanticsProvider,
+    editor_settings::GoToDefinitionFallback, scroll::ScrollAmount,
 };
 use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, Window, px};
 use language::{Bias, ToOffset};
@@ -359,6 +359,21 @@ pub fn show_link_definition(
                             (range, vec![HoverLink::Url(url)])
                         })
                         .ok()
+                    } else if let Some((link_range, link)) = find_document_link(
+                        &buffer,
+                        project.as_ref(),
+                        provider.as_deref(),
+                        text_anchor,
+                        cx,
+                    )
+                    .await
+                    {
+                        let range = maybe!({
+                            let range = snapshot.anchor_range_in_excerpt(excerpt_id, link_range)?;
+                            Some(RangeInEditor::Text(range))
+                        });
+
+                        Some((range, vec![link]))
                     } else if let Some((filename_range, filename)) =
                         find_file(&buffer, project.clone(), text_anchor, cx).await
                     {
@@ -585,6 +600,35 @@ pub(crate) fn find_url_from_range(
     None
 }
 
+/// Looks up the document link reported by a language server at the given position,
+/// opening `file://` targets through the workspace and any other target as a URL.
+pub(crate) async fn find_document_link(
+    buffer: &Entity<language::Buffer>,
+    project: Option<&Entity<Project>>,
+    provider: Option<&dyn SemanticsProvider>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let provider = provider?;
+    let link_task = cx
+        .update(|_, cx| provider.document_link(buffer, position, cx))
+        .ok()??;
+    let link = link_task.await.log_err()??;
+    let target = link.lsp_link.target?;
+    if target.scheme() == "file" {
+        let path = target.to_file_path().ok()?;
+        let resolved_path = project?
+            .update(cx, |project, cx| {
+                project.resolve_abs_path(&path.to_string_lossy(), cx)
+            })
+            .ok()?
+            .await?;
+        Some((link.range, HoverLink::File(resolved_path)))
+    } else {
+        Some((link.range, HoverLink::Url(target.to_string())))
+    }
+}
+
 pub(crate) async fn find_file(
     buffer: &Entity<language::Buffer>,
     project: Option<Entity<Project>>,
@@ -738,6 +782,7 @@ mod tests {
     use indoc::indoc;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
     use settings::InlayHintSettingsContent;
+    use std::str::FromStr as _;
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1264,6 +1309,65 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            use serde::Deserializeˇ;
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            use «serde»::Deserialize;
+        "});
+        let mut link_requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: None,
+                    tooltip: None,
+                    data: Some(serde_json::json!({ "crate": "serde" })),
+                }]))
+            },
+        );
+        let mut resolve_requests = cx
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>(
+                move |link, _, _| async move {
+                    assert_eq!(link.data, Some(serde_json::json!({ "crate": "serde" })));
+                    Ok(lsp::DocumentLink {
+                        target: Some(lsp::Uri::from_str("https://docs.rs/serde").unwrap()),
+                        ..link
+                    })
+                },
+            );
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            use seˇrde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.background_executor
+            .advance_clock(std::time::Duration::from_millis(100));
+        link_requests.next().await;
+        resolve_requests.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serde»::Deserialize;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
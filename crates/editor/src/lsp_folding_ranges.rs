@@ -0,0 +1,163 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::Task;
+use itertools::Itertools;
+use multi_buffer::Anchor;
+use text::BufferId;
+use ui::{Context, Window};
+
+use crate::{Editor, FETCH_COLORS_DEBOUNCE_TIMEOUT};
+
+#[derive(Debug, Default)]
+pub(super) struct LspFoldingRangesData {
+    /// The cache versions of the folding ranges last fetched for each buffer.
+    cache_versions_used: HashMap<BufferId, Option<usize>>,
+}
+
+impl Editor {
+    pub(super) fn refresh_lsp_folding_ranges(
+        &mut self,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.ignore_lsp_data() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(folding_ranges) = self.lsp_folding_ranges.as_ref() else {
+            return;
+        };
+
+        let visible_buffers = self
+            .visible_excerpts(cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                let editor_buffer_id = editor_buffer.read(cx).remote_id();
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer_id)
+                    && self.registered_buffers.contains_key(&editor_buffer_id)
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let all_ranges_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let known_cache_version = folding_ranges
+                        .cache_versions_used
+                        .get(&buffer_id)
+                        .copied()
+                        .flatten();
+                    let ranges_task = lsp_store.folding_ranges(known_cache_version, buffer, cx)?;
+                    Some(async move { (buffer_id, ranges_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if all_ranges_task.is_empty() {
+            self.refresh_lsp_folding_ranges_task = Task::ready(());
+            return;
+        }
+
+        self.refresh_lsp_folding_ranges_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_COLORS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_ranges = join_all(all_ranges_task).await;
+            editor
+                .update(cx, |editor, cx| {
+                    let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let Some(folding_ranges) = &mut editor.lsp_folding_ranges else {
+                        return;
+                    };
+
+                    let mut new_ranges = Vec::new();
+                    for (buffer_id, ranges) in all_ranges {
+                        let ranges = match ranges {
+                            Ok(ranges) => ranges,
+                            Err(e) => {
+                                log::error!("Failed to retrieve folding ranges: {e}");
+                                continue;
+                            }
+                        };
+
+                        let mut buffer_ranges = Vec::<Range<Anchor>>::new();
+                        for (excerpt_id, buffer_snapshot, _) in multi_buffer_snapshot.excerpts() {
+                            if buffer_snapshot.remote_id() != buffer_id {
+                                continue;
+                            }
+                            for folding_range in ranges.ranges.values().flat_map(|r| r.iter()) {
+                                if let Some(range) = multi_buffer_snapshot.anchor_range_in_excerpt(
+                                    excerpt_id,
+                                    folding_range.range.clone(),
+                                ) {
+                                    buffer_ranges.push(range);
+                                }
+                            }
+                        }
+                        buffer_ranges.sort_by(|a, b| {
+                            a.start
+                                .cmp(&b.start, &multi_buffer_snapshot)
+                                .then_with(|| b.end.cmp(&a.end, &multi_buffer_snapshot))
+                        });
+
+                        folding_ranges
+                            .cache_versions_used
+                            .insert(buffer_id, ranges.cache_version);
+                        new_ranges.push((buffer_id, Arc::from(buffer_ranges)));
+                    }
+
+                    if new_ranges.is_empty() {
+                        return;
+                    }
+                    editor.display_map.update(cx, |display_map, _| {
+                        for (buffer_id, ranges) in new_ranges {
+                            display_map.set_lsp_folding_ranges(buffer_id, ranges);
+                        }
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    pub(super) fn remove_lsp_folding_ranges(
+        &mut self,
+        buffer_ids: &[BufferId],
+        cx: &mut Context<Self>,
+    ) {
+        let Some(folding_ranges) = &mut self.lsp_folding_ranges else {
+            return;
+        };
+        for buffer_id in buffer_ids {
+            folding_ranges.cache_versions_used.remove(buffer_id);
+        }
+        let removed = self.display_map.update(cx, |display_map, _| {
+            buffer_ids.iter().fold(false, |removed, buffer_id| {
+                display_map.remove_lsp_folding_ranges(*buffer_id) || removed
+            })
+        });
+        if removed {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn clear_lsp_folding_ranges(&mut self, cx: &mut Context<Self>) {
+        self.lsp_folding_ranges = None;
+        self.refresh_lsp_folding_ranges_task = Task::ready(());
+        if self
+            .display_map
+            .update(cx, |display_map, _| display_map.clear_lsp_folding_ranges())
+        {
+            cx.notify();
+        }
+    }
+}
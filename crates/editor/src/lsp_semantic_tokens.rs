@@ -0,0 +1,392 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use futures::future::join_all;
+use gpui::{HighlightStyle, Hsla, StrikethroughStyle, Task, UnderlineStyle, px};
+use itertools::Itertools;
+use multi_buffer::Anchor;
+use project::SemanticToken;
+use text::BufferId;
+use theme::{ActiveTheme as _, SyntaxTheme, Theme};
+use ui::{App, Context, Window};
+
+use crate::{Editor, FETCH_COLORS_DEBOUNCE_TIMEOUT};
+
+#[derive(Debug)]
+pub(super) struct LspSemanticTokensData {
+    buffer_tokens: HashMap<BufferId, BufferSemanticTokens>,
+    theme: Arc<Theme>,
+}
+
+#[derive(Debug, Default)]
+struct BufferSemanticTokens {
+    /// Tokens of every excerpt of the buffer, per language server, each sorted by their start in
+    /// the multi buffer. Tokens of one language server don't overlap, but those of different
+    /// servers may.
+    tokens: Vec<Vec<(Range<Anchor>, SemanticToken)>>,
+    cache_version_used: Option<usize>,
+}
+
+impl LspSemanticTokensData {
+    pub fn new(cx: &App) -> Self {
+        Self {
+            buffer_tokens: HashMap::default(),
+            theme: cx.theme().clone(),
+        }
+    }
+
+    /// Resolves the highlight styles of the buffer's tokens against the current theme.
+    /// Tokens that the theme has no style for are left to the tree-sitter highlighting.
+    fn buffer_highlights(
+        &self,
+        buffer_id: BufferId,
+    ) -> Arc<[Vec<(Range<Anchor>, HighlightStyle)>]> {
+        let Some(buffer_tokens) = self.buffer_tokens.get(&buffer_id) else {
+            return Arc::default();
+        };
+        let mut resolved_styles = HashMap::<(&str, &[_]), Option<HighlightStyle>>::default();
+        buffer_tokens
+            .tokens
+            .iter()
+            .map(|server_tokens| {
+                server_tokens
+                    .iter()
+                    .filter_map(|(range, token)| {
+                        let style = *resolved_styles
+                            .entry((token.token_type.as_ref(), token.token_modifiers.as_slice()))
+                            .or_insert_with(|| {
+                                semantic_token_style(
+                                    token,
+                                    self.theme.syntax(),
+                                    self.theme.status().warning,
+                                )
+                            });
+                        Some((range.clone(), style?))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Editor {
+    pub(super) fn refresh_semantic_tokens(
+        &mut self,
+        buffer_id: Option<BufferId>,
+        _: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.ignore_lsp_data() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(semantic_tokens) = self.semantic_tokens.as_ref() else {
+            return;
+        };
+
+        let visible_buffers = self
+            .visible_excerpts(cx)
+            .into_values()
+            .map(|(buffer, ..)| buffer)
+            .filter(|editor_buffer| {
+                let editor_buffer_id = editor_buffer.read(cx).remote_id();
+                buffer_id.is_none_or(|buffer_id| buffer_id == editor_buffer_id)
+                    && self.registered_buffers.contains_key(&editor_buffer_id)
+            })
+            .unique_by(|buffer| buffer.read(cx).remote_id())
+            .collect::<Vec<_>>();
+
+        let all_tokens_task = project.read(cx).lsp_store().update(cx, |lsp_store, cx| {
+            visible_buffers
+                .into_iter()
+                .filter_map(|buffer| {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    let known_cache_version = semantic_tokens
+                        .buffer_tokens
+                        .get(&buffer_id)
+                        .and_then(|buffer_tokens| buffer_tokens.cache_version_used);
+                    let tokens_task = lsp_store.semantic_tokens(known_cache_version, buffer, cx)?;
+                    Some(async move { (buffer_id, tokens_task.await) })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        if all_tokens_task.is_empty() {
+            self.refresh_semantic_tokens_task = Task::ready(());
+            return;
+        }
+
+        self.refresh_semantic_tokens_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(FETCH_COLORS_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let all_tokens = join_all(all_tokens_task).await;
+            editor
+                .update(cx, |editor, cx| {
+                    let multi_buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let Some(semantic_tokens) = &mut editor.semantic_tokens else {
+                        return;
+                    };
+
+                    let mut updated_buffers = Vec::new();
+                    for (buffer_id, tokens) in all_tokens {
+                        let tokens = match tokens {
+                            Ok(tokens) => tokens,
+                            Err(e) => {
+                                log::error!("Failed to retrieve semantic tokens: {e}");
+                                continue;
+                            }
+                        };
+
+                        let mut new_tokens = Vec::new();
+                        for (_, server_tokens) in tokens
+                            .tokens
+                            .iter()
+                            .sorted_by_key(|(server_id, _)| **server_id)
+                        {
+                            let mut new_server_tokens = Vec::new();
+                            for (excerpt_id, buffer_snapshot, _) in multi_buffer_snapshot.excerpts()
+                            {
+                                if buffer_snapshot.remote_id() != buffer_id {
+                                    continue;
+                                }
+                                for token in server_tokens.iter() {
+                                    if let Some(range) = multi_buffer_snapshot
+                                        .anchor_range_in_excerpt(excerpt_id, token.range.clone())
+                                    {
+                                        new_server_tokens.push((range, token.clone()));
+                                    }
+                                }
+                            }
+                            new_server_tokens.sort_by(|(a, _), (b, _)| {
+                                a.start.cmp(&b.start, &multi_buffer_snapshot)
+                            });
+                            new_tokens.push(new_server_tokens);
+                        }
+
+                        semantic_tokens.buffer_tokens.insert(
+                            buffer_id,
+                            BufferSemanticTokens {
+                                tokens: new_tokens,
+                                cache_version_used: tokens.cache_version,
+                            },
+                        );
+                        updated_buffers.push(buffer_id);
+                    }
+
+                    if updated_buffers.is_empty() {
+                        return;
+                    }
+                    let highlights = updated_buffers
+                        .into_iter()
+                        .map(|buffer_id| (buffer_id, semantic_tokens.buffer_highlights(buffer_id)))
+                        .collect::<Vec<_>>();
+                    editor.display_map.update(cx, |display_map, _| {
+                        for (buffer_id, highlights) in highlights {
+                            display_map.set_semantic_token_highlights(buffer_id, highlights);
+                        }
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    /// Re-resolves the styles of the known semantic tokens if the theme has changed.
+    pub(super) fn refresh_semantic_token_styles(&mut self, cx: &mut Context<Self>) {
+        let Some(semantic_tokens) = &mut self.semantic_tokens else {
+            return;
+        };
+        let theme = cx.theme();
+        if Arc::ptr_eq(&semantic_tokens.theme, theme) {
+            return;
+        }
+        semantic_tokens.theme = theme.clone();
+
+        let highlights = semantic_tokens
+            .buffer_tokens
+            .keys()
+            .map(|&buffer_id| (buffer_id, semantic_tokens.buffer_highlights(buffer_id)))
+            .collect::<Vec<_>>();
+        self.display_map.update(cx, |display_map, _| {
+            for (buffer_id, highlights) in highlights {
+                display_map.set_semantic_token_highlights(buffer_id, highlights);
+            }
+        });
+        cx.notify();
+    }
+
+    pub(super) fn remove_semantic_tokens(
+        &mut self,
+        buffer_ids: &[BufferId],
+        cx: &mut Context<Self>,
+    ) {
+        let Some(semantic_tokens) = &mut self.semantic_tokens else {
+            return;
+        };
+        for buffer_id in buffer_ids {
+            semantic_tokens.buffer_tokens.remove(buffer_id);
+        }
+        let removed = self.display_map.update(cx, |display_map, _| {
+            buffer_ids.iter().fold(false, |removed, buffer_id| {
+                display_map.remove_semantic_token_highlights(*buffer_id) || removed
+            })
+        });
+        if removed {
+            cx.notify();
+        }
+    }
+
+    pub(super) fn clear_semantic_tokens(&mut self, cx: &mut Context<Self>) {
+        self.semantic_tokens = None;
+        self.refresh_semantic_tokens_task = Task::ready(());
+        if self.display_map.update(cx, |display_map, _| {
+            display_map.clear_semantic_token_highlights()
+        }) {
+            cx.notify();
+        }
+    }
+}
+
+/// Looks up the theme style of a token, trying its type combined with each of its modifiers
+/// (e.g. `variable.readonly`), the type alone, and then the closest tree-sitter capture names.
+///
+/// Themes rarely define `type.modifier` keys, so when none matches, the modifiers that servers
+/// commonly report get a default treatment on top of the type's style: `mutable` bindings are
+/// underlined, `unsafe` operations use the warning color and inactive code is faded out.
+fn semantic_token_style(
+    token: &SemanticToken,
+    theme: &SyntaxTheme,
+    warning_color: Hsla,
+) -> Option<HighlightStyle> {
+    let token_type = token.token_type.as_ref();
+    let has_modifier = |name: &str| {
+        token
+            .token_modifiers
+            .iter()
+            .any(|modifier| modifier.as_ref() == name)
+    };
+    let modifier_style = token
+        .token_modifiers
+        .iter()
+        .find_map(|modifier| theme_style(theme, &format!("{token_type}.{modifier}")));
+    let has_modifier_style = modifier_style.is_some();
+    let mut style = modifier_style
+        .or_else(|| theme_style(theme, token_type))
+        .or_else(|| {
+            fallback_syntax_names(token_type)
+                .iter()
+                .find_map(|name| theme_style(theme, name))
+        });
+
+    if !has_modifier_style {
+        if has_modifier("mutable") {
+            style.get_or_insert_default().underline = Some(UnderlineStyle {
+                thickness: px(1.),
+                color: None,
+                wavy: false,
+            });
+        }
+        if has_modifier("unsafe") {
+            style.get_or_insert_default().color = Some(warning_color);
+        }
+        if is_inactive_code(token) {
+            style.get_or_insert_default().fade_out = Some(0.5);
+        }
+    }
+
+    if has_modifier("deprecated") {
+        style.get_or_insert_default().strikethrough = Some(StrikethroughStyle {
+            thickness: px(1.),
+            color: None,
+        });
+    }
+    style
+}
+
+/// clangd reports preprocessor branches that are compiled out as `comment` tokens without
+/// modifiers, other servers mark them with an `inactive` or `disabled` modifier.
+fn is_inactive_code(token: &SemanticToken) -> bool {
+    (token.token_type.as_ref() == "comment" && token.token_modifiers.is_empty())
+        || token
+            .token_modifiers
+            .iter()
+            .any(|modifier| matches!(modifier.as_ref(), "inactive" | "disabled"))
+}
+
+fn theme_style(theme: &SyntaxTheme, name: &str) -> Option<HighlightStyle> {
+    theme
+        .highlights
+        .iter()
+        .find_map(|(highlight_name, style)| (highlight_name == name).then_some(*style))
+}
+
+fn fallback_syntax_names(token_type: &str) -> &'static [&'static str] {
+    match token_type {
+        "class" | "enum" | "interface" | "struct" | "typeParameter" | "typeAlias" => &["type"],
+        "enumMember" => &["variant", "constant"],
+        "parameter" => &["variable.parameter", "variable"],
+        "method" => &["function.method", "function"],
+        "macro" => &["function.special", "function"],
+        "decorator" => &["attribute"],
+        "regexp" => &["string.regex"],
+        "modifier" => &["keyword"],
+        "event" => &["property"],
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{blue, green, red, yellow};
+    use text::Anchor;
+
+    use super::*;
+
+    fn token(token_type: &'static str, token_modifiers: &[&'static str]) -> SemanticToken {
+        SemanticToken {
+            range: Anchor::MIN..Anchor::MAX,
+            token_type: token_type.into(),
+            token_modifiers: token_modifiers.iter().map(|&m| m.into()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_modifier_fallbacks() {
+        let theme = SyntaxTheme::new_test([
+            ("variable", red()),
+            ("function", blue()),
+            ("comment", green()),
+        ]);
+        let warning = yellow();
+        let style = |token_type, modifiers: &[&'static str]| {
+            semantic_token_style(&token(token_type, modifiers), &theme, warning).unwrap()
+        };
+
+        let variable = style("variable", &[]);
+        assert_eq!(variable.color, Some(red()));
+        assert_eq!(variable.underline, None);
+
+        let mutable = style("variable", &["declaration", "mutable"]);
+        assert_eq!(mutable.color, Some(red()));
+        assert!(mutable.underline.is_some());
+
+        let unsafe_call = style("function", &["unsafe"]);
+        assert_eq!(unsafe_call.color, Some(warning));
+
+        let inactive = style("comment", &[]);
+        assert_eq!(inactive.color, Some(green()));
+        assert_eq!(inactive.fade_out, Some(0.5));
+        assert_eq!(style("comment", &["documentation"]).fade_out, None);
+        assert_eq!(style("variable", &["inactive"]).fade_out, Some(0.5));
+
+        let theme = SyntaxTheme::new_test([("variable", red()), ("variable.mutable", blue())]);
+        let themed_mutable =
+            semantic_token_style(&token("variable", &["mutable"]), &theme, warning).unwrap();
+        assert_eq!(themed_mutable.color, Some(blue()));
+        assert_eq!(themed_mutable.underline, None);
+    }
+}
@@ -3,7 +3,10 @@ use crate::{
     EvaluateSelectedText, FindAllReferences, GoToDeclaration, GoToDefinition, GoToImplementation,
     GoToTypeDefinition, Paste, Rename, RevealInFileManager, RunToCursor, SelectMode,
     SelectionEffects, SelectionExt, ToDisplayPoint, ToggleCodeActions,
-    actions::{Format, FormatSelections},
+    actions::{
+        Format, FormatSelections, ShowIncomingCalls, ShowOutgoingCalls, ShowSubtypes,
+        ShowSupertypes,
+    },
     selections_collection::SelectionsCollection,
 };
 use gpui::prelude::FluentBuilder;
@@ -221,6 +224,10 @@ pub fn deploy_context_menu(
                 .action("Go to Type Definition", Box::new(GoToTypeDefinition))
                 .action("Go to Implementation", Box::new(GoToImplementation))
                 .action("Find All References", Box::new(FindAllReferences))
+                .action("Show Incoming Calls", Box::new(ShowIncomingCalls))
+                .action("Show Outgoing Calls", Box::new(ShowOutgoingCalls))
+                .action("Show Supertypes", Box::new(ShowSupertypes))
+                .action("Show Subtypes", Box::new(ShowSubtypes))
                 .separator()
                 .action("Rename Symbol", Box::new(Rename))
                 .action("Format Buffer", Box::new(Format))
@@ -259,7 +266,8 @@ pub fn deploy_context_menu(
                     !has_git_repo,
                     "Copy Permalink",
                     Box::new(CopyPermalinkToLine),
-                );
+                )
+                .action_disabled_when(!has_git_repo, "File History", Box::new(git::FileHistory));
             match focus {
                 Some(focus) => builder.context(focus),
                 None => builder,
@@ -0,0 +1,210 @@
+use gpui::App;
+use multi_buffer::{Anchor, ToPoint as _};
+use settings::Settings as _;
+use text::{Bias, Point};
+
+use crate::{DisplayPoint, DisplayRow, DisplaySnapshot, Editor, EditorSettings, ToDisplayPoint};
+
+/// A scope enclosing the top of the viewport, whose first line stays pinned above the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StickyScope {
+    /// The display row of the line that opens the scope.
+    pub start_row: DisplayRow,
+    /// The display row the scope ends on. The pinned line is pushed out of view once this row
+    /// reaches it.
+    pub end_row: DisplayRow,
+    /// Where the cursor goes when jumping to the scope.
+    pub anchor: Anchor,
+}
+
+impl Editor {
+    /// Returns the scopes whose opening lines should be pinned at the top of the viewport,
+    /// outermost first.
+    pub fn sticky_scopes(
+        &self,
+        scroll_top: f64,
+        snapshot: &DisplaySnapshot,
+        cx: &App,
+    ) -> Vec<StickyScope> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if !settings.enabled || !self.mode.is_full() || !self.buffer.read(cx).is_singleton() {
+            return Vec::new();
+        }
+        sticky_scopes_at(snapshot, scroll_top, settings.max_depth)
+    }
+}
+
+fn sticky_scopes_at(
+    snapshot: &DisplaySnapshot,
+    scroll_top: f64,
+    max_depth: usize,
+) -> Vec<StickyScope> {
+    let mut scopes = Vec::new();
+    let top_row = DisplayRow(scroll_top.max(0.) as u32);
+    if max_depth == 0 || top_row > snapshot.max_point().row() {
+        return scopes;
+    }
+
+    // Probe the end of the top row, so that a folded or wrapped line is looked at as a whole
+    // and the closing line of a scope still counts as being inside it.
+    let buffer = snapshot.buffer_snapshot();
+    let probe = snapshot.display_point_to_point(
+        DisplayPoint::new(top_row, snapshot.line_len(top_row)),
+        Bias::Left,
+    );
+    let Some((_, items)) = buffer.symbols_containing(probe, None) else {
+        return scopes;
+    };
+
+    for item in items {
+        if scopes.len() >= max_depth {
+            break;
+        }
+        let start = item.source_range_for_text.start.to_point(buffer);
+        let start_row = snapshot
+            .point_to_display_point(Point::new(start.row, 0), Bias::Left)
+            .row();
+        // Each pinned line covers one more row of text, so a scope opening on a row that would
+        // still be visible below the pinned lines doesn't need to be pinned, nor do its children.
+        if start_row.0 >= top_row.0 + scopes.len() as u32 {
+            break;
+        }
+        let end_row = item.range.end.to_display_point(snapshot).row();
+        if end_row <= start_row
+            || end_row < top_row
+            || scopes
+                .last()
+                .is_some_and(|scope: &StickyScope| scope.start_row == start_row)
+        {
+            continue;
+        }
+        scopes.push(StickyScope {
+            start_row,
+            end_row,
+            anchor: item.source_range_for_text.start,
+        });
+    }
+    scopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RowExt as _, editor_tests::init_test, test::editor_test_context::EditorTestContext,
+    };
+    use indoc::indoc;
+    use language::{Language, LanguageConfig};
+    use settings::{SettingsStore, StickyScrollContent};
+    use std::sync::Arc;
+
+    fn sticky_rows(cx: &mut EditorTestContext, top: Point) -> Vec<u32> {
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let scroll_top = snapshot.point_to_display_point(top, Bias::Left).row();
+            editor
+                .sticky_scopes(scroll_top.as_f64(), &snapshot, cx)
+                .iter()
+                .map(|scope| {
+                    snapshot
+                        .display_point_to_point(DisplayPoint::new(scope.start_row, 0), Bias::Left)
+                        .row
+                })
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_sticky_scopes(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (impl_item "impl" @context type: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+        cx.set_state(indoc! {"
+            ˇmod outer {
+                struct Foo;
+
+                impl Foo {
+                    fn one() {
+                        let a = 1;
+                        let b = 2;
+                    }
+
+                    fn two() {
+                        let c = 3;
+                    }
+                }
+            }
+        "});
+        cx.run_until_parked();
+
+        // Nothing is pinned while the scopes start in view.
+        assert_eq!(sticky_rows(&mut cx, Point::new(0, 0)), Vec::<u32>::new());
+        // A pinned line covers the top row, so the scope opening there is pinned below it.
+        assert_eq!(sticky_rows(&mut cx, Point::new(3, 0)), vec![0, 3]);
+        assert_eq!(sticky_rows(&mut cx, Point::new(5, 0)), vec![0, 3, 4]);
+        // The closing line of a scope is still inside of it.
+        assert_eq!(sticky_rows(&mut cx, Point::new(7, 0)), vec![0, 3, 4]);
+        assert_eq!(sticky_rows(&mut cx, Point::new(8, 0)), vec![0, 3]);
+        assert_eq!(sticky_rows(&mut cx, Point::new(10, 0)), vec![0, 3, 9]);
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.editor.sticky_scroll = Some(StickyScrollContent {
+                        max_depth: Some(2),
+                        ..Default::default()
+                    });
+                });
+            });
+        });
+        assert_eq!(sticky_rows(&mut cx, Point::new(5, 0)), vec![0, 3]);
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.editor.sticky_scroll = Some(StickyScrollContent {
+                        enabled: Some(false),
+                        ..Default::default()
+                    });
+                });
+            });
+        });
+        assert_eq!(sticky_rows(&mut cx, Point::new(5, 0)), Vec::<u32>::new());
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.editor.sticky_scroll = None;
+                });
+            });
+        });
+
+        // A folded scope fits on a single line, so it isn't pinned, and the rows below it move up.
+        cx.update_editor(|editor, window, cx| {
+            editor.fold_ranges(vec![Point::new(4, 18)..Point::new(7, 8)], false, window, cx);
+        });
+        assert_eq!(sticky_rows(&mut cx, Point::new(4, 0)), vec![0, 3]);
+        assert_eq!(sticky_rows(&mut cx, Point::new(10, 0)), vec![0, 3, 9]);
+
+        // Soft wrapped rows are accounted for.
+        cx.update_editor(|editor, _, cx| {
+            editor.set_wrap_width(Some(100.0.into()), cx);
+        });
+        assert_eq!(sticky_rows(&mut cx, Point::new(10, 0)), vec![0, 3, 9]);
+    }
+}
@@ -8,35 +8,26 @@ mod tool_metrics;
 
 use assertions::{AssertionsReport, display_error_row};
 use instance::{ExampleInstance, JudgeOutput, RunOutput, run_git};
-use language_extension::LspAccess;
 pub(crate) use tool_metrics::*;
 
-use ::fs::RealFs;
 use clap::Parser;
-use client::{Client, ProxySettings, UserStore};
+use client::{Client, UserStore};
 use collections::{HashMap, HashSet};
-use extension::ExtensionHostProxy;
 use futures::future;
-use gpui::http_client::read_proxy_from_env;
-use gpui::{App, AppContext, Application, AsyncApp, Entity, UpdateGlobal};
-use gpui_tokio::Tokio;
+use gpui::{App, Application, AsyncApp, Entity, UpdateGlobal};
+use headless_app::HeadlessAppState;
 use language::LanguageRegistry;
-use language_model::{ConfiguredModel, LanguageModel, LanguageModelRegistry, SelectedModel};
-use node_runtime::{NodeBinaryOptions, NodeRuntime};
-use project::Project;
-use project::project_settings::ProjectSettings;
+use language_model::{ConfiguredModel, LanguageModel, LanguageModelRegistry};
+use node_runtime::NodeRuntime;
 use prompt_store::PromptBuilder;
-use release_channel::AppVersion;
 use reqwest_client::ReqwestClient;
-use settings::{Settings, SettingsStore};
+use settings::SettingsStore;
 use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::str::FromStr;
 use std::sync::{Arc, LazyLock};
-use util::ResultExt as _;
 
 static CARGO_MANIFEST_DIR: LazyLock<PathBuf> =
     LazyLock::new(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")));
@@ -348,89 +339,14 @@ pub struct AgentAppState {
 }
 
 pub fn init(cx: &mut App) -> Arc<AgentAppState> {
-    let app_version = AppVersion::load(env!("ZED_PKG_VERSION"));
-    release_channel::init(app_version, cx);
-    gpui_tokio::init(cx);
-
-    let settings_store = SettingsStore::new(cx, &settings::default_settings());
-    cx.set_global(settings_store);
-    client::init_settings(cx);
-
-    // Set User-Agent so we can download language servers from GitHub
-    let user_agent = format!(
-        "Zed Agent Eval/{} ({}; {})",
-        app_version,
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    );
-    let proxy_str = ProxySettings::get_global(cx).proxy.to_owned();
-    let proxy_url = proxy_str
-        .as_ref()
-        .and_then(|input| input.parse().ok())
-        .or_else(read_proxy_from_env);
-    let http = {
-        let _guard = Tokio::handle(cx).enter();
-
-        ReqwestClient::proxy_and_user_agent(proxy_url, &user_agent)
-            .expect("could not start HTTP client")
-    };
-    cx.set_http_client(Arc::new(http));
-
-    Project::init_settings(cx);
-
-    let client = Client::production(cx);
-    cx.set_http_client(client.http_client());
-
-    let git_binary_path = None;
-    let fs = Arc::new(RealFs::new(
-        git_binary_path,
-        cx.background_executor().clone(),
-    ));
-
-    let mut languages = LanguageRegistry::new(cx.background_executor().clone());
-    languages.set_language_server_download_dir(paths::languages_dir().clone());
-    let languages = Arc::new(languages);
-
-    let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
-
-    extension::init(cx);
-
-    let (mut tx, rx) = watch::channel(None);
-    cx.observe_global::<SettingsStore>(move |cx| {
-        let settings = &ProjectSettings::get_global(cx).node;
-        let options = NodeBinaryOptions {
-            allow_path_lookup: !settings.ignore_system_version,
-            allow_binary_download: true,
-            use_paths: settings.path.as_ref().map(|node_path| {
-                let node_path = PathBuf::from(shellexpand::tilde(node_path).as_ref());
-                let npm_path = settings
-                    .npm_path
-                    .as_ref()
-                    .map(|path| PathBuf::from(shellexpand::tilde(&path).as_ref()));
-                (
-                    node_path.clone(),
-                    npm_path.unwrap_or_else(|| {
-                        let base_path = PathBuf::new();
-                        node_path.parent().unwrap_or(&base_path).join("npm")
-                    }),
-                )
-            }),
-        };
-        tx.send(Some(options)).log_err();
-    })
-    .detach();
-    let node_runtime = NodeRuntime::new(client.http_client(), None, rx);
-
-    let extension_host_proxy = ExtensionHostProxy::global(cx);
-
-    language::init(cx);
-    debug_adapter_extension::init(extension_host_proxy.clone(), cx);
-    language_extension::init(LspAccess::Noop, extension_host_proxy, languages.clone());
-    language_model::init(client.clone(), cx);
-    language_models::init(user_store.clone(), client.clone(), cx);
-    languages::init(languages.clone(), fs.clone(), node_runtime.clone(), cx);
-    prompt_store::init(cx);
-    terminal_view::init(cx);
+    let HeadlessAppState {
+        languages,
+        client,
+        user_store,
+        fs,
+        node_runtime,
+    } = headless_app::init("Zed Agent Eval", cx);
+
     let stdout_is_a_pty = false;
     let prompt_builder = PromptBuilder::load(fs.clone(), stdout_is_a_pty, cx);
     agent_ui::init(
@@ -457,33 +373,10 @@ pub fn init(cx: &mut App) -> Arc<AgentAppState> {
     })
 }
 
-pub fn find_model(
-    model_name: &str,
-    model_registry: &LanguageModelRegistry,
-    cx: &App,
-) -> anyhow::Result<Arc<dyn LanguageModel>> {
-    let selected = SelectedModel::from_str(model_name).map_err(|e| anyhow::anyhow!(e))?;
-    model_registry
-        .available_models(cx)
-        .find(|model| model.id() == selected.model && model.provider_id() == selected.provider)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "No language model with ID {}/{} was available. Available models: {}",
-                selected.model.0,
-                selected.provider.0,
-                model_registry
-                    .available_models(cx)
-                    .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )
-        })
-}
-
 pub fn load_model(model_name: &str, cx: &mut App) -> anyhow::Result<ConfiguredModel> {
     let model = {
         let model_registry = LanguageModelRegistry::read_global(cx);
-        find_model(model_name, model_registry, cx)?
+        model_registry.find_model(model_name, cx)?
     };
 
     let provider = {
@@ -5,9 +5,12 @@ use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid,
     blame::Blame,
+    log::{GitLog, LogEntry, LogOptions},
+    rebase::{RebaseProgress, RebaseTodoDelegate, RebaseTodoEntry},
     repository::{
         AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode, Worktree,
+        GitRepositoryCheckpoint, InProgressOperation, IndexStage, OperationControl, PushOptions,
+        Remote, RepoPath, ResetMode, Tag, Worktree,
     },
     status::{
         DiffTreeType, FileStatus, GitStatus, StatusCode, TrackedStatus, TreeDiff, TreeDiffStatus,
@@ -44,14 +47,24 @@ pub struct FakeGitRepositoryState {
     pub unmerged_paths: HashMap<RepoPath, UnmergedStatus>,
     pub head_contents: HashMap<RepoPath, String>,
     pub index_contents: HashMap<RepoPath, String>,
+    /// The base, ours and theirs index entries of conflicted paths.
+    pub conflicted_index_contents: HashMap<(RepoPath, IndexStage), String>,
     // everything in commit contents is in oids
     pub merge_base_contents: HashMap<RepoPath, Oid>,
     pub oids: HashMap<Oid, String>,
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub tags: HashMap<String, Tag>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    pub rebase_progress: Option<RebaseProgress>,
+    /// The todo list given to the todo editor of interactive rebases, and then edited by it.
+    pub rebase_todo: Vec<RebaseTodoEntry>,
+    /// The commits cherry-picked, reverted or merged, in order.
+    pub applied_commits: Vec<String>,
+    /// The history listed by `log`, newest first. Each commit touches the file in its `path`.
+    pub commits: Vec<LogEntry>,
 }
 
 impl FakeGitRepositoryState {
@@ -60,14 +73,20 @@ impl FakeGitRepositoryState {
             event_emitter,
             head_contents: Default::default(),
             index_contents: Default::default(),
+            conflicted_index_contents: Default::default(),
             unmerged_paths: Default::default(),
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            tags: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
             merge_base_contents: Default::default(),
             oids: Default::default(),
+            rebase_progress: Default::default(),
+            rebase_todo: Default::default(),
+            applied_commits: Default::default(),
+            commits: Default::default(),
         }
     }
 }
@@ -105,6 +124,26 @@ impl GitRepository for FakeGitRepository {
             .boxed()
     }
 
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>> {
+        if stage == IndexStage::Normal {
+            return self.load_index_text(path);
+        }
+        let fut = self.with_state_async(false, move |state| {
+            state
+                .conflicted_index_contents
+                .get(&(path, stage))
+                .context("not present in index")
+                .cloned()
+        });
+        self.executor
+            .spawn_labeled(*LOAD_INDEX_TEXT_TASK, async move { fut.await.ok() })
+            .boxed()
+    }
+
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
         let fut = self.with_state_async(false, move |state| {
             state
@@ -232,6 +271,11 @@ impl GitRepository for FakeGitRepository {
         async move { None }.boxed()
     }
 
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>> {
+        let progress = self.with_state_async(false, |state| Ok(state.rebase_progress.clone()));
+        async move { progress.await.ok().flatten() }.boxed()
+    }
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>> {
         let workdir_path = self.dot_git_path.parent().unwrap();
 
@@ -367,6 +411,49 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<GitLog>> {
+        self.with_state_async(false, move |state| {
+            options.validate()?;
+            let start = match options.revision.as_deref() {
+                None | Some("HEAD") => 0,
+                Some(revision) => state
+                    .commits
+                    .iter()
+                    .position(|commit| {
+                        commit.sha == revision || commit.refs.iter().any(|name| name == revision)
+                    })
+                    .with_context(|| format!("bad revision '{revision}'"))?,
+            };
+            let mut entries = state.commits[start..]
+                .iter()
+                .filter(|commit| {
+                    options
+                        .path
+                        .as_ref()
+                        .is_none_or(|path| commit.path.as_ref() == Some(path))
+                })
+                .filter(|commit| {
+                    options.author.as_ref().is_none_or(|author| {
+                        commit.author_name.contains(author.as_str())
+                            || commit.author_email.contains(author.as_str())
+                    })
+                })
+                .skip(options.skip)
+                .take(options.limit.saturating_add(1))
+                .cloned()
+                .map(|mut commit| {
+                    if options.path.is_none() {
+                        commit.path = None;
+                    }
+                    commit
+                })
+                .collect::<Vec<_>>();
+            let has_more = entries.len() > options.limit;
+            entries.truncate(options.limit);
+            Ok(GitLog { entries, has_more })
+        })
+    }
+
     fn stash_entries(&self) -> BoxFuture<'_, Result<git::stash::GitStash>> {
         async { Ok(git::stash::GitStash::default()) }.boxed()
     }
@@ -427,6 +514,61 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn delete_branch(&self, name: String, _force: bool) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.current_branch_name.as_ref() == Some(&name) {
+                bail!("cannot delete the current branch: {name}");
+            }
+            if !state.branches.remove(&name) {
+                bail!("no such branch: {name}");
+            }
+            Ok(())
+        })
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| {
+            let mut tags = state.tags.values().cloned().collect::<Vec<_>>();
+            tags.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(tags)
+        })
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.contains_key(&name) {
+                bail!("tag '{name}' already exists");
+            }
+            let sha = match target {
+                Some(target) => target,
+                None => state.refs.get("HEAD").context("no HEAD")?.clone(),
+            };
+            let tag = Tag {
+                name: name.clone().into(),
+                sha: sha.into(),
+                message: message.map(Into::into),
+                unix_timestamp: None,
+            };
+            state.tags.insert(name, tag);
+            Ok(())
+        })
+    }
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.remove(&name).is_none() {
+                bail!("tag '{name}' not found");
+            }
+            Ok(())
+        })
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<'_, Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -539,6 +681,102 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn push_tag(
+        &self,
+        _tag_name: String,
+        _remote_name: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn delete_remote_branch(
+        &self,
+        _branch_name: String,
+        _remote_name: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn merge(
+        &self,
+        branch: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.applied_commits.push(branch);
+            Ok(())
+        })
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.applied_commits.extend(commits);
+            Ok(())
+        })
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            state.applied_commits.extend(commits);
+            Ok(())
+        })
+    }
+
+    fn rebase(
+        &self,
+        _upstream: String,
+        todo_editor: Option<RebaseTodoDelegate>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        async move {
+            let Some(todo_editor) = todo_editor else {
+                return Ok(());
+            };
+            let todo = self
+                .with_state_async(false, |state| Ok(state.rebase_todo.clone()))
+                .await?;
+            let Some(todo) = todo_editor.edit_todo(todo).await else {
+                return Ok(());
+            };
+            self.with_state_async(true, move |state| {
+                state.rebase_todo = todo;
+                Ok(())
+            })
+            .await
+        }
+        .boxed()
+    }
+
+    fn control_operation(
+        &self,
+        _operation: InProgressOperation,
+        control: OperationControl,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if control != OperationControl::Skip {
+                state.rebase_progress = None;
+            } else if let Some(progress) = &mut state.rebase_progress {
+                progress.step += 1;
+            }
+            Ok(())
+        })
+    }
+
     fn pull(
         &self,
         _branch: String,
@@ -1772,6 +1772,13 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_commits_for_repo(&self, dot_git: &Path, commits: Vec<git::log::LogEntry>) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commits = commits;
+        })
+        .unwrap();
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(RepoPath, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
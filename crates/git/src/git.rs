@@ -1,6 +1,8 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod log;
+pub mod rebase;
 mod remote;
 pub mod repository;
 pub mod stash;
@@ -98,6 +100,18 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Opens the commit history of the repository.
+        ViewLog,
+        /// Opens the commit history of the current file.
+        FileHistory,
+        /// Opens the current file in a three-way merge editor to resolve its conflicts.
+        OpenMergeEditor,
+        /// Continues the merge, rebase, cherry-pick or revert in progress.
+        ContinueOperation,
+        /// Aborts the merge, rebase, cherry-pick or revert in progress.
+        AbortOperation,
+        /// Skips the current commit of the rebase, cherry-pick or revert in progress.
+        SkipOperation,
     ]
 );
 
@@ -225,3 +239,71 @@ impl From<Oid> for usize {
         u64::from_ne_bytes(u64_bytes) as usize
     }
 }
+
+/// Checks that a revision, which may come from a remote peer, can't be mistaken for an option
+/// of the git command it's passed to.
+pub fn validate_revision(revision: &str) -> Result<()> {
+    anyhow::ensure!(
+        !revision.starts_with('-'),
+        "Invalid revision {revision:?}: revisions can't start with '-'"
+    );
+    Ok(())
+}
+
+/// Checks that a branch, tag or remote name, which may come from a remote peer, follows the rules
+/// of `git check-ref-format` and can't be mistaken for an option. `kind` names it in the error.
+pub fn validate_ref_name(kind: &str, name: &str) -> Result<()> {
+    let is_valid = !name.starts_with('-')
+        && name != "@"
+        && !name.contains("..")
+        && !name.contains("@{")
+        && !name.ends_with('.')
+        && !name.chars().any(|char| {
+            char.is_ascii_control()
+                || matches!(char, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')
+        })
+        && name.split('/').all(|component| {
+            !component.is_empty() && !component.starts_with('.') && !component.ends_with(".lock")
+        });
+    anyhow::ensure!(is_valid, "Invalid {kind} name {name:?}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ref_name() {
+        for name in ["main", "feature/login", "v1.0.0", "user@host", "release-1"] {
+            assert!(validate_ref_name("branch", name).is_ok(), "{name}");
+        }
+        for name in [
+            "",
+            "-f",
+            "--force",
+            "--receive-pack=touch /tmp/pwned",
+            "@",
+            "a..b",
+            "a@{1}",
+            "a b",
+            "a~1",
+            "a^",
+            "a:b",
+            "a?",
+            "a*",
+            "a[b",
+            "a\\b",
+            "a\tb",
+            "/a",
+            "a/",
+            "a//b",
+            ".a",
+            "a/.b",
+            "a.",
+            "a.lock",
+        ] {
+            assert!(validate_ref_name("branch", name).is_err(), "{name:?}");
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::repository::RepoPath;
+use anyhow::{Context as _, Result};
+use gpui::SharedString;
+
+/// Separates the commits in the output of `git log`.
+const RECORD_SEPARATOR: char = '\x1e';
+
+/// The `--format` argument matching [`GitLog::parse`].
+pub(crate) const LOG_FORMAT: &str = "--format=%x1e%H%x00%P%x00%at%x00%an%x00%ae%x00%D%x00%s";
+
+/// Filters and pagination for [`crate::repository::GitRepository::log`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LogOptions {
+    /// The revision to list the history of, `HEAD` when unset.
+    pub revision: Option<String>,
+    /// Only list the commits touching this path, following its renames.
+    pub path: Option<RepoPath>,
+    /// Only list the commits whose author matches this pattern.
+    pub author: Option<String>,
+    /// Number of commits to skip from the start of the history.
+    pub skip: usize,
+    /// Maximum number of commits to list.
+    pub limit: usize,
+}
+
+impl LogOptions {
+    /// Checks that the options can't be mistaken for other `git log` arguments, as they may
+    /// come from a remote peer.
+    pub fn validate(&self) -> Result<()> {
+        if let Some(revision) = &self.revision {
+            crate::validate_revision(revision)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn args(&self) -> Result<Vec<String>> {
+        self.validate()?;
+        let mut args = vec![
+            "--no-optional-locks".to_string(),
+            "-c".to_string(),
+            "core.quotePath=false".to_string(),
+            "log".to_string(),
+            LOG_FORMAT.to_string(),
+            format!("--skip={}", self.skip),
+            // One more commit than requested tells whether there's another page.
+            format!("--max-count={}", self.limit.saturating_add(1)),
+        ];
+        if self.path.is_none() {
+            // Children must be listed before their parents to draw the commit graph.
+            args.push("--date-order".to_string());
+        }
+        if let Some(author) = &self.author {
+            args.push(format!("--author={author}"));
+        }
+        if self.path.is_some() {
+            args.push("--follow".to_string());
+            args.push("--name-only".to_string());
+        }
+        args.push(self.revision.clone().unwrap_or_else(|| "HEAD".to_string()));
+        if let Some(path) = &self.path {
+            args.push("--".to_string());
+            args.push(path.as_unix_str().to_string());
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parents: Vec<SharedString>,
+    pub subject: SharedString,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// Branches and tags pointing at this commit, as decorated by `git log`.
+    pub refs: Vec<SharedString>,
+    /// When following a path, its name in this commit, which differs from the requested path
+    /// before a rename.
+    pub path: Option<RepoPath>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GitLog {
+    pub entries: Vec<LogEntry>,
+    /// Whether there are more commits after the requested page.
+    pub has_more: bool,
+}
+
+impl GitLog {
+    /// Parses the output of `git log` run with [`LogOptions::args`].
+    pub(crate) fn parse(output: &str, limit: usize) -> Result<Self> {
+        let mut entries = output
+            .split(RECORD_SEPARATOR)
+            .filter(|record| !record.trim().is_empty())
+            .map(parse_log_entry)
+            .collect::<Result<Vec<_>>>()?;
+        let has_more = entries.len() > limit;
+        entries.truncate(limit);
+        Ok(Self { entries, has_more })
+    }
+}
+
+/// Parses a commit in the format "<sha>\0<parents>\0<timestamp>\0<author name>\0<author email>\0<refs>\0<subject>",
+/// optionally followed by the path of the file it touched.
+fn parse_log_entry(record: &str) -> Result<LogEntry> {
+    let (header, names) = record.split_once('\n').unwrap_or((record, ""));
+    let fields = header.split('\0').collect::<Vec<_>>();
+    anyhow::ensure!(
+        fields.len() == 7,
+        "Expected 7 null-separated fields, got {}: {header:?}",
+        fields.len()
+    );
+
+    let commit_timestamp = fields[2]
+        .parse()
+        .with_context(|| format!("Failed to parse timestamp from '{}'", fields[2]))?;
+    let path = names
+        .lines()
+        .find(|line| !line.is_empty())
+        .map(RepoPath::new)
+        .transpose()?;
+
+    Ok(LogEntry {
+        sha: fields[0].to_string().into(),
+        parents: fields[1]
+            .split_whitespace()
+            .map(|parent| parent.to_string().into())
+            .collect(),
+        commit_timestamp,
+        author_name: fields[3].to_string().into(),
+        author_email: fields[4].to_string().into(),
+        refs: parse_refs(fields[5]),
+        subject: fields[6].to_string().into(),
+        path,
+    })
+}
+
+/// Parses `%D` decorations such as "HEAD -> main, tag: v1.0, origin/main".
+fn parse_refs(decorations: &str) -> Vec<SharedString> {
+    decorations
+        .split(", ")
+        .filter(|decoration| !decoration.is_empty())
+        .map(|decoration| {
+            let decoration = decoration.strip_prefix("HEAD -> ").unwrap_or(decoration);
+            decoration.to_string().into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "\x1eaaa\0bbb ccc\01700000000\0Jane Doe\0jane@example.com\0HEAD -> main, tag: v1.0, origin/main\0Merge branch 'feature'\n\
+            \x1ebbb\0ddd\01699999999\0John Doe\0john@example.com\0\0Add a feature\n\
+            \x1eddd\0\01699999998\0Jane Doe\0jane@example.com\0\0Initial commit\n";
+
+        let log = GitLog::parse(output, 2).unwrap();
+        assert!(log.has_more);
+        assert_eq!(
+            log.entries,
+            vec![
+                LogEntry {
+                    sha: "aaa".into(),
+                    parents: vec!["bbb".into(), "ccc".into()],
+                    subject: "Merge branch 'feature'".into(),
+                    author_name: "Jane Doe".into(),
+                    author_email: "jane@example.com".into(),
+                    commit_timestamp: 1700000000,
+                    refs: vec!["main".into(), "tag: v1.0".into(), "origin/main".into()],
+                    path: None,
+                },
+                LogEntry {
+                    sha: "bbb".into(),
+                    parents: vec!["ddd".into()],
+                    subject: "Add a feature".into(),
+                    author_name: "John Doe".into(),
+                    author_email: "john@example.com".into(),
+                    commit_timestamp: 1699999999,
+                    refs: vec![],
+                    path: None,
+                },
+            ]
+        );
+
+        let log = GitLog::parse(output, 3).unwrap();
+        assert!(!log.has_more);
+        assert_eq!(log.entries.len(), 3);
+        assert!(log.entries[2].parents.is_empty());
+
+        assert_eq!(GitLog::parse("", 10).unwrap(), GitLog::default());
+        assert!(GitLog::parse("\x1eaaa\0bbb\n", 10).is_err());
+    }
+
+    #[test]
+    fn test_log_args() {
+        let options = LogOptions {
+            revision: Some("main".into()),
+            path: Some(RepoPath::new("src/lib.rs").unwrap()),
+            author: Some("Jane".into()),
+            skip: 10,
+            limit: usize::MAX,
+        };
+        let args = options.args().unwrap();
+        assert!(args.contains(&format!("--max-count={}", usize::MAX)));
+        assert_eq!(
+            &args[args.len() - 6..],
+            [
+                "--author=Jane",
+                "--follow",
+                "--name-only",
+                "main",
+                "--",
+                "src/lib.rs"
+            ]
+        );
+
+        for revision in ["--output=/tmp/log", "-p", "--exec=true"] {
+            let options = LogOptions {
+                revision: Some(revision.into()),
+                ..LogOptions::default()
+            };
+            assert!(options.validate().is_err(), "{revision}");
+            assert!(options.args().is_err(), "{revision}");
+        }
+    }
+
+    #[test]
+    fn test_parse_file_history() {
+        let output = "\x1eaaa\0bbb\01700000000\0Jane Doe\0jane@example.com\0\0Rename the module\n\nsrc/new_name.rs\n\
+            \x1ebbb\0\01699999999\0Jane Doe\0jane@example.com\0\0Add the module\n\nsrc/old_name.rs\n";
+
+        let log = GitLog::parse(output, 10).unwrap();
+        assert_eq!(
+            log.entries
+                .iter()
+                .map(|entry| entry.path.as_ref().map(|path| path.as_unix_str()))
+                .collect::<Vec<_>>(),
+            vec![Some("src/new_name.rs"), Some("src/old_name.rs")]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use futures::channel::{mpsc, oneshot};
+use futures::{SinkExt as _, StreamExt as _};
+use gpui::{AsyncApp, SharedString, Task};
+
+/// The commands of an interactive rebase todo list that can be edited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseTodoAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseTodoAction {
+    pub const ALL: [Self; 5] = [
+        Self::Pick,
+        Self::Reword,
+        Self::Squash,
+        Self::Fixup,
+        Self::Drop,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Reword => "reword",
+            Self::Squash => "squash",
+            Self::Fixup => "fixup",
+            Self::Drop => "drop",
+        }
+    }
+
+    /// Parses a todo command, in its long or abbreviated form.
+    pub fn parse(command: &str) -> Option<Self> {
+        match command {
+            "pick" | "p" => Some(Self::Pick),
+            "reword" | "r" => Some(Self::Reword),
+            "squash" | "s" => Some(Self::Squash),
+            "fixup" | "f" => Some(Self::Fixup),
+            "drop" | "d" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+
+    /// Whether the commit is melded into the previous one.
+    pub fn is_melded(self) -> bool {
+        matches!(self, Self::Squash | Self::Fixup)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseTodoAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The full message of the commit, loaded before the todo list is edited. A reworded commit
+    /// is given the message as edited; git's own editor is never shown, so a reworded commit
+    /// without one keeps its message.
+    pub message: Option<SharedString>,
+}
+
+/// Parses the todo list git writes for an interactive rebase.
+pub fn parse_rebase_todo(todo: &str) -> Result<Vec<RebaseTodoEntry>> {
+    todo.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let command = parts.next().unwrap_or_default();
+            let action = RebaseTodoAction::parse(command)
+                .with_context(|| format!("unsupported rebase todo command: {line:?}"))?;
+            let sha = parts
+                .next()
+                .with_context(|| format!("missing commit in rebase todo: {line:?}"))?;
+            Ok(RebaseTodoEntry {
+                action,
+                sha: sha.to_string().into(),
+                subject: parts.next().unwrap_or_default().to_string().into(),
+                message: None,
+            })
+        })
+        .collect()
+}
+
+/// Formats a todo list for git. The new messages of reworded commits are returned alongside,
+/// to be written in `message_dir` before git runs the todo list.
+pub(crate) fn format_rebase_todo(
+    entries: &[RebaseTodoEntry],
+    message_dir: &Path,
+) -> (String, Vec<(PathBuf, SharedString)>) {
+    let mut todo = String::new();
+    let mut messages = Vec::new();
+    for entry in entries {
+        let message = entry
+            .message
+            .as_ref()
+            .filter(|_| entry.action == RebaseTodoAction::Reword);
+        // Rewording is done by amending the picked commit, as git would otherwise open an
+        // editor for the message.
+        let action = if message.is_some() {
+            RebaseTodoAction::Pick
+        } else {
+            entry.action
+        };
+        todo.push_str(&format!(
+            "{} {} {}\n",
+            action.as_str(),
+            entry.sha,
+            entry.subject
+        ));
+        if let Some(message) = message {
+            let path = message_dir.join(format!("zed-message-{}", messages.len()));
+            todo.push_str(&format!(
+                "exec git commit --amend --only --no-verify --allow-empty --cleanup=strip --file={}\n",
+                shell_quote(&path.to_string_lossy())
+            ));
+            messages.push((path, message.clone()));
+        }
+    }
+    (todo, messages)
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Progress of a rebase stopped on a conflict, an edit or an error.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RebaseProgress {
+    /// The branch being rebased, unless the rebase started from a detached HEAD.
+    pub head_name: Option<SharedString>,
+    /// Number of the todo list commands done so far, including the current one.
+    pub step: usize,
+    pub total: usize,
+}
+
+/// Edits the todo list of interactive rebases on the main thread, where the todo editor is shown.
+pub struct RebaseTodoDelegate {
+    tx: mpsc::UnboundedSender<(
+        Vec<RebaseTodoEntry>,
+        oneshot::Sender<Option<Vec<RebaseTodoEntry>>>,
+    )>,
+    _task: Task<()>,
+}
+
+impl RebaseTodoDelegate {
+    /// `edit_todo` is given the todo list, and sends back the edited one, or `None` to abort the
+    /// rebase.
+    pub fn new(
+        cx: &mut AsyncApp,
+        edit_todo: impl Fn(
+            Vec<RebaseTodoEntry>,
+            oneshot::Sender<Option<Vec<RebaseTodoEntry>>>,
+            &mut AsyncApp,
+        ) + 'static,
+    ) -> Self {
+        let (tx, mut rx) = mpsc::unbounded::<(Vec<RebaseTodoEntry>, oneshot::Sender<_>)>();
+        let task = cx.spawn(async move |cx: &mut AsyncApp| {
+            while let Some((entries, channel)) = rx.next().await {
+                edit_todo(entries, channel, cx);
+            }
+        });
+        Self { tx, _task: task }
+    }
+
+    pub async fn edit_todo(&self, entries: Vec<RebaseTodoEntry>) -> Option<Vec<RebaseTodoEntry>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.clone().send((entries, tx)).await.ok()?;
+        rx.await.ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rebase_todo() {
+        let todo = "pick 1111111 Add a feature\n\
+            p 2222222 Fix the feature\n\
+            \n\
+            # Rebase 0000000..2222222 onto 0000000 (2 commands)\n\
+            # Commands:\n";
+        assert_eq!(
+            parse_rebase_todo(todo).unwrap(),
+            vec![
+                RebaseTodoEntry {
+                    action: RebaseTodoAction::Pick,
+                    sha: "1111111".into(),
+                    subject: "Add a feature".into(),
+                    message: None,
+                },
+                RebaseTodoEntry {
+                    action: RebaseTodoAction::Pick,
+                    sha: "2222222".into(),
+                    subject: "Fix the feature".into(),
+                    message: None,
+                },
+            ]
+        );
+        assert!(parse_rebase_todo("exec make test\n").is_err());
+    }
+
+    #[test]
+    fn test_format_rebase_todo() {
+        let entry = |action, sha: &str, message: Option<&str>| RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: format!("Commit {sha}").into(),
+            message: message.map(|message| message.to_string().into()),
+        };
+        let (todo, messages) = format_rebase_todo(
+            &[
+                entry(RebaseTodoAction::Reword, "3333333", Some("New message")),
+                entry(RebaseTodoAction::Reword, "1111111", None),
+                entry(RebaseTodoAction::Fixup, "2222222", None),
+                entry(RebaseTodoAction::Drop, "4444444", Some("Ignored")),
+            ],
+            Path::new("rebase-merge"),
+        );
+        let message_path = Path::new("rebase-merge").join("zed-message-0");
+        assert_eq!(
+            todo,
+            format!(
+                "pick 3333333 Commit 3333333\n\
+                exec git commit --amend --only --no-verify --allow-empty --cleanup=strip --file='{}'\n\
+                reword 1111111 Commit 1111111\n\
+                fixup 2222222 Commit 2222222\n\
+                drop 4444444 Commit 4444444\n",
+                message_path.display()
+            )
+        );
+        assert_eq!(messages, vec![(message_path, "New message".into())]);
+    }
+}
@@ -1,7 +1,11 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::log::{GitLog, LogOptions};
+use crate::rebase::{
+    RebaseProgress, RebaseTodoDelegate, RebaseTodoEntry, format_rebase_todo, parse_rebase_todo,
+};
 use crate::stash::GitStash;
 use crate::status::{DiffTreeType, GitStatus, StatusCode, TreeDiff};
-use crate::{Oid, SHORT_SHA_LENGTH};
+use crate::{Oid, SHORT_SHA_LENGTH, validate_ref_name, validate_revision};
 use anyhow::{Context as _, Result, anyhow, bail};
 use collections::HashMap;
 use futures::future::BoxFuture;
@@ -21,7 +25,10 @@ use std::{
     cmp::Ordering,
     future,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{self, AtomicBool},
+    },
 };
 use sum_tree::MapSeekTarget;
 use thiserror::Error;
@@ -31,6 +38,7 @@ use util::rel_path::RelPath;
 use util::{ResultExt, paths};
 use uuid::Uuid;
 
+use askpass::SequenceEditorSession;
 pub use askpass::{AskPassDelegate, AskPassResult, AskPassSession};
 
 pub const REMOTE_CANCELLED_BY_USER: &str = "Operation cancelled by user";
@@ -231,6 +239,23 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+/// A tag in the repository.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points to.
+    pub sha: SharedString,
+    /// The subject of the message of annotated tags, or `None` for lightweight tags.
+    pub message: Option<SharedString>,
+    pub unix_timestamp: Option<i64>,
+}
+
+impl Tag {
+    pub fn is_annotated(&self) -> bool {
+        self.message.is_some()
+    }
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -240,6 +265,61 @@ pub enum ResetMode {
     Mixed,
 }
 
+/// An operation stopped by git, usually on a conflict, until it is continued or aborted.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl InProgressOperation {
+    /// The git command running the operation.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+        }
+    }
+
+    pub fn can_skip(self) -> bool {
+        self != Self::Merge
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum OperationControl {
+    Continue,
+    Abort,
+    Skip,
+}
+
+impl OperationControl {
+    pub fn flag(self) -> &'static str {
+        match self {
+            Self::Continue => "--continue",
+            Self::Abort => "--abort",
+            Self::Skip => "--skip",
+        }
+    }
+}
+
+/// A stage of an index entry. A conflicted path has an entry for each side of the merge that
+/// touched it instead of a normal entry.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum IndexStage {
+    Normal = 0,
+    /// The common ancestor of both sides.
+    Base = 1,
+    /// The current branch.
+    Ours = 2,
+    /// The branch or commit being merged in.
+    Theirs = 3,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum FetchOptions {
     All,
@@ -388,7 +468,19 @@ pub trait GitRepository: Send + Sync {
     /// Returns the contents of an entry in the repository's index, or None if there is no entry for the given path.
     ///
     /// Also returns `None` for symlinks.
-    fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
+    fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+        self.load_index_stage_text(path, IndexStage::Normal)
+    }
+
+    /// Returns the contents of the entry at the given stage of the repository's index, or None if
+    /// there is no such entry for the given path.
+    ///
+    /// Also returns `None` for symlinks.
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>>;
 
     /// Returns the contents of an entry in the repository's HEAD, or None if HEAD does not exist or has no entry for the given path.
     ///
@@ -423,6 +515,9 @@ pub trait GitRepository: Send + Sync {
 
     fn merge_message(&self) -> BoxFuture<'_, Option<String>>;
 
+    /// Returns the progress of the rebase in progress, if any.
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>>;
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>>;
     fn diff_tree(&self, request: DiffTreeType) -> BoxFuture<'_, Result<TreeDiff>>;
 
@@ -433,6 +528,22 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
     fn rename_branch(&self, branch: String, new_name: String) -> BoxFuture<'_, Result<()>>;
+    /// Deletes a local branch. Unless `force` is set, git refuses to delete a branch that isn't
+    /// merged into its upstream, or into HEAD when it has none.
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>>;
+
+    /// Lists the tags of the repository, newest first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+    /// Creates a tag on `target`, or on HEAD when it is `None`. The tag is annotated when it has a
+    /// message, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>>;
 
     fn worktrees(&self) -> BoxFuture<'_, Result<Vec<Worktree>>>;
 
@@ -460,6 +571,10 @@ pub trait GitRepository: Send + Sync {
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
+
+    /// Lists a page of the commits reachable from a revision, newest first.
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<GitLog>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -551,6 +666,63 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
 
+    fn push_tag(
+        &self,
+        tag_name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    fn delete_remote_branch(
+        &self,
+        branch_name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Merges a branch into the current one.
+    fn merge(&self, branch: String, env: Arc<HashMap<String, String>>)
+    -> BoxFuture<'_, Result<()>>;
+
+    /// Applies the changes of the given commits on top of HEAD, in order.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Commits the reverse of the changes of the given commits, in order.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Rebases HEAD onto `upstream`. When a todo editor is given, the rebase is interactive and
+    /// the editor is asked for the todo list; nothing is done when it rejects the list.
+    fn rebase(
+        &self,
+        upstream: String,
+        todo_editor: Option<RebaseTodoDelegate>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Continues, aborts or skips the current commit of an operation in progress.
+    fn control_operation(
+        &self,
+        operation: InProgressOperation,
+        control: OperationControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>>;
 
     /// returns a list of remote branches that contain HEAD
@@ -582,6 +754,70 @@ pub trait GitRepository: Send + Sync {
     fn default_branch(&self) -> BoxFuture<'_, Result<Option<SharedString>>>;
 }
 
+/// Hands the todo list of an interactive rebase to the todo editor, and writes back its edits.
+/// Returns whether the rebase should go on.
+async fn edit_rebase_todo(
+    todo_path: &Path,
+    todo_editor: &RebaseTodoDelegate,
+    git_binary_path: &Path,
+    working_directory: &Path,
+) -> Result<bool> {
+    let todo = smol::fs::read_to_string(todo_path)
+        .await
+        .context("reading the rebase todo list")?;
+    let mut entries = parse_rebase_todo(&todo)?;
+    load_rebase_todo_messages(&mut entries, git_binary_path, working_directory)
+        .await
+        .log_err();
+    let Some(entries) = todo_editor.edit_todo(entries).await else {
+        return Ok(false);
+    };
+    let message_dir = todo_path
+        .parent()
+        .context("the rebase todo list has no parent directory")?;
+    let (todo, messages) = format_rebase_todo(&entries, message_dir);
+    for (path, message) in messages {
+        smol::fs::write(path, message.as_bytes()).await?;
+    }
+    smol::fs::write(todo_path, todo).await?;
+    Ok(true)
+}
+
+/// Fills in the full messages of the commits in a rebase todo list, which only has their subject.
+async fn load_rebase_todo_messages(
+    entries: &mut [RebaseTodoEntry],
+    git_binary_path: &Path,
+    working_directory: &Path,
+) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let output = new_smol_command(git_binary_path)
+        .current_dir(working_directory)
+        .args(["log", "-z", "--no-walk=unsorted", "--format=%H%n%B"])
+        .args(entries.iter().map(|entry| entry.sha.as_ref()))
+        .output()
+        .await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Failed to load commit messages:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for (sha, message) in stdout
+        .split('\0')
+        .filter_map(|commit| commit.split_once('\n'))
+    {
+        for entry in entries
+            .iter_mut()
+            .filter(|entry| sha.starts_with(entry.sha.as_ref()))
+        {
+            entry.message = Some(message.trim_end().to_string().into());
+        }
+    }
+    Ok(())
+}
+
 pub enum DiffType {
     HeadToIndex,
     HeadToWorktree,
@@ -631,6 +867,45 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    /// Runs a command that may stop on a conflict, without ever opening an editor: commit
+    /// messages are taken as git prepares them. The revisions may come from a remote peer, so
+    /// they're passed after `--end-of-options`, and rejected if they look like options.
+    fn run_sequencer_command(
+        &self,
+        args: Vec<String>,
+        revisions: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.system_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                for revision in &revisions {
+                    validate_revision(revision)?;
+                }
+                let git_binary_path = git_binary_path
+                    .with_context(|| format!("git not found on $PATH, can't {}", args[0]))?;
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_EDITOR", "true")
+                    .args(&args);
+                if !revisions.is_empty() {
+                    command.arg("--end-of-options").args(&revisions);
+                }
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to {}:\n{}",
+                    args[0],
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -917,20 +1192,27 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
-    fn load_index_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+    fn load_index_stage_text(
+        &self,
+        path: RepoPath,
+        stage: IndexStage,
+    ) -> BoxFuture<'_, Option<String>> {
         // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
         const GIT_MODE_SYMLINK: u32 = 0o120000;
 
         let repo = self.repository.clone();
         self.executor
             .spawn(async move {
-                fn logic(repo: &git2::Repository, path: &RepoPath) -> Result<Option<String>> {
+                fn logic(
+                    repo: &git2::Repository,
+                    path: &RepoPath,
+                    stage: IndexStage,
+                ) -> Result<Option<String>> {
                     // This check is required because index.get_path() unwraps internally :(
                     let mut index = repo.index()?;
                     index.read(false)?;
 
-                    const STAGE_NORMAL: i32 = 0;
-                    let oid = match index.get_path(path.as_std_path(), STAGE_NORMAL) {
+                    let oid = match index.get_path(path.as_std_path(), stage as i32) {
                         Some(entry) if entry.mode != GIT_MODE_SYMLINK => entry.id,
                         _ => return Ok(None),
                     };
@@ -939,7 +1221,7 @@ impl GitRepository for RealGitRepository {
                     Ok(String::from_utf8(content).ok())
                 }
 
-                match logic(&repo.lock(), &path) {
+                match logic(&repo.lock(), &path, stage) {
                     Ok(value) => return value,
                     Err(err) => log::error!("Error loading index text: {:?}", err),
                 }
@@ -1103,6 +1385,47 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn rebase_progress(&self) -> BoxFuture<'_, Option<RebaseProgress>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                // Interactive rebases keep their state in `rebase-merge`, and rebases applying
+                // patches in `rebase-apply`, which also holds the state of `git am`.
+                let (state_dir, step_file, total_file) = if git_dir.join("rebase-merge").is_dir() {
+                    (git_dir.join("rebase-merge"), "msgnum", "end")
+                } else if git_dir.join("rebase-apply").join("rebasing").exists() {
+                    (git_dir.join("rebase-apply"), "next", "last")
+                } else {
+                    return None;
+                };
+                let read = |name: &str| {
+                    let state_dir = state_dir.join(name);
+                    async move {
+                        smol::fs::read_to_string(state_dir)
+                            .await
+                            .ok()
+                            .map(|content| content.trim().to_string())
+                    }
+                };
+                let head_name = read("head-name")
+                    .await
+                    .filter(|name| *name != "detached HEAD");
+                let step = read(step_file).await.and_then(|step| step.parse().ok());
+                let total = read(total_file).await.and_then(|total| total.parse().ok());
+                Some(RebaseProgress {
+                    head_name: head_name.map(|name| {
+                        name.strip_prefix("refs/heads/")
+                            .unwrap_or(&name)
+                            .to_string()
+                            .into()
+                    }),
+                    step: step.unwrap_or_default(),
+                    total: total.unwrap_or_default(),
+                })
+            })
+            .boxed()
+    }
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>> {
         let git_binary_path = self.any_git_binary_path.clone();
         let working_directory = match self.working_directory() {
@@ -1171,6 +1494,26 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<GitLog>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(working_directory?)
+                    .args(options.args()?)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "git log failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                GitLog::parse(&String::from_utf8_lossy(&output.stdout), options.limit)
+            })
+            .boxed()
+    }
+
     fn stash_entries(&self) -> BoxFuture<'_, Result<GitStash>> {
         let git_binary_path = self.any_git_binary_path.clone();
         let working_directory = self.working_directory();
@@ -1385,6 +1728,107 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                validate_ref_name("branch", &name)?;
+                let flag = if force { "-D" } else { "-d" };
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["branch", flag, "--end-of-options", &name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname:strip=2)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(creatordate:unix)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&[
+                        "for-each-ref",
+                        "refs/tags",
+                        "--sort=-creatordate",
+                        "--format",
+                        &fields,
+                    ])
+                    .await?;
+                Ok(parse_tag_input(&output))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                validate_ref_name("tag", &name)?;
+                if let Some(target) = &target {
+                    validate_revision(target)?;
+                }
+                let mut args = vec!["tag".to_string()];
+                if let Some(message) = message {
+                    args.extend(["--annotate".to_string(), "--message".to_string(), message]);
+                }
+                args.extend(["--end-of-options".to_string(), name]);
+                args.extend(target);
+                let output = new_smol_command(git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(args)
+                    .output()
+                    .await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_tag(&self, name: String) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                validate_ref_name("tag", &name)?;
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["tag", "--delete", "--end-of-options", &name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -1687,6 +2131,68 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn push_tag(
+        &self,
+        tag_name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            validate_ref_name("tag", &tag_name)?;
+            validate_ref_name("remote", &remote_name)?;
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't push")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["push", "--end-of-options"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{tag_name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn delete_remote_branch(
+        &self,
+        branch_name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            validate_ref_name("branch", &branch_name)?;
+            validate_ref_name("remote", &remote_name)?;
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't push")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["push", "--delete", "--end-of-options"])
+                .arg(remote_name)
+                .arg(branch_name)
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn pull(
         &self,
         branch_name: String,
@@ -1748,6 +2254,120 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn merge(
+        &self,
+        branch: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(vec!["merge".into(), "--no-edit".into()], vec![branch], env)
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(vec!["cherry-pick".into()], commits, env)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(vec!["revert".into(), "--no-edit".into()], commits, env)
+    }
+
+    fn rebase(
+        &self,
+        upstream: String,
+        todo_editor: Option<RebaseTodoDelegate>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.system_git_binary_path.clone();
+        let executor = self.executor.clone();
+        async move {
+            validate_revision(&upstream)?;
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't rebase")?;
+            let working_directory = working_directory?;
+            let mut command = new_smol_command(&git_binary_path);
+            command
+                .envs(env.iter())
+                .env("GIT_EDITOR", "true")
+                .current_dir(&working_directory)
+                .arg("rebase");
+
+            let mut _session = None;
+            let cancelled = Arc::new(AtomicBool::new(false));
+            if let Some(todo_editor) = todo_editor {
+                let todo_editor = Arc::new(todo_editor);
+                let session = SequenceEditorSession::new(&executor, {
+                    let executor = executor.clone();
+                    let git_binary_path = git_binary_path.clone();
+                    let working_directory = working_directory.clone();
+                    let cancelled = cancelled.clone();
+                    move |todo_path| {
+                        let todo_path = working_directory.join(todo_path);
+                        let todo_editor = todo_editor.clone();
+                        let git_binary_path = git_binary_path.clone();
+                        let working_directory = working_directory.clone();
+                        let cancelled = cancelled.clone();
+                        executor.spawn(async move {
+                            let accepted = edit_rebase_todo(
+                                &todo_path,
+                                &todo_editor,
+                                &git_binary_path,
+                                &working_directory,
+                            )
+                            .await
+                            .log_err()
+                            .unwrap_or(false);
+                            cancelled.store(!accepted, atomic::Ordering::SeqCst);
+                            accepted
+                        })
+                    }
+                })
+                .await?;
+                command
+                    .env("GIT_SEQUENCE_EDITOR", session.script_path())
+                    .arg("--interactive");
+                _session = Some(session);
+            }
+
+            let output = command
+                .arg("--end-of-options")
+                .arg(upstream)
+                .output()
+                .await?;
+            // Rejecting the todo list leaves the branch untouched, like an empty todo list would.
+            if cancelled.load(atomic::Ordering::SeqCst) {
+                return Ok(());
+            }
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to rebase:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn control_operation(
+        &self,
+        operation: InProgressOperation,
+        control: OperationControl,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.run_sequencer_command(
+            vec![operation.command().into(), control.flag().into()],
+            Vec::new(),
+            env,
+        )
+    }
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
@@ -2366,6 +2986,35 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+fn parse_tag_input(input: &str) -> Vec<Tag> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let name = fields.next()?;
+            let object_type = fields.next()?;
+            let object = fields.next()?;
+            let peeled_object = fields.next()?;
+            let timestamp = fields.next()?;
+            let subject = fields.next()?;
+
+            // Annotated tags point to a tag object, which points to the commit.
+            let is_annotated = object_type == "tag";
+            let sha = if is_annotated && !peeled_object.is_empty() {
+                peeled_object
+            } else {
+                object
+            };
+            Some(Tag {
+                name: name.to_string().into(),
+                sha: sha.to_string().into(),
+                message: is_annotated.then(|| subject.to_string().into()),
+                unix_timestamp: timestamp.parse().ok(),
+            })
+        })
+        .collect()
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -2454,6 +3103,35 @@ mod tests {
     use super::*;
     use gpui::TestAppContext;
 
+    async fn repo_with_initial_commit(
+        cx: &mut TestAppContext,
+    ) -> (tempfile::TempDir, RealGitRepository) {
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "initial")
+            .await
+            .unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            Arc::new(checkpoint_author_envs()),
+        )
+        .await
+        .unwrap();
+        (repo_dir, repo)
+    }
+
     #[gpui::test]
     async fn test_checkpoint_basic(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -2539,6 +3217,90 @@ mod tests {
         // );
     }
 
+    #[gpui::test]
+    async fn test_sequencer_commands_reject_options(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let (repo_dir, repo) = repo_with_initial_commit(cx).await;
+        let env = Arc::new(checkpoint_author_envs());
+
+        repo.merge("HEAD".into(), env.clone()).await.unwrap();
+
+        let marker = repo_dir.path().join("marker");
+        let exec = format!("--exec=touch {}", marker.display());
+        assert!(
+            repo.merge("--strategy=ours".into(), env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.cherry_pick(vec!["HEAD".into(), "-Xours".into()], env.clone())
+                .await
+                .is_err()
+        );
+        assert!(repo.revert(vec![exec.clone()], env.clone()).await.is_err());
+        assert!(repo.rebase(exec.clone(), None, env.clone()).await.is_err());
+        assert!(
+            repo.rebase(format!("-xtouch {}", marker.display()), None, env)
+                .await
+                .is_err()
+        );
+        assert!(!marker.exists());
+    }
+
+    #[gpui::test]
+    async fn test_ref_commands_reject_options(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let (repo_dir, repo) = repo_with_initial_commit(cx).await;
+        let env = Arc::new(checkpoint_author_envs());
+
+        repo.create_tag("v1".into(), Some("HEAD".into()), None, env.clone())
+            .await
+            .unwrap();
+        assert!(
+            repo.create_tag("--force".into(), None, None, env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.create_tag(
+                "v2".into(),
+                Some("--points-at=HEAD".into()),
+                None,
+                env.clone()
+            )
+            .await
+            .is_err()
+        );
+        assert!(repo.delete_tag("--list".into()).await.is_err());
+        assert!(repo.delete_branch("-r".into(), true).await.is_err());
+        repo.delete_tag("v1".into()).await.unwrap();
+
+        let marker = repo_dir.path().join("marker");
+        let receive_pack = format!("--receive-pack=touch {}", marker.display());
+        let mut async_cx = cx.to_async();
+        let askpass = AskPassDelegate::new(&mut async_cx, |_, _, _| {});
+        assert!(
+            repo.push_tag(
+                "v1".into(),
+                receive_pack.clone(),
+                askpass,
+                env.clone(),
+                cx.to_async()
+            )
+            .await
+            .is_err()
+        );
+        let askpass = AskPassDelegate::new(&mut async_cx, |_, _, _| {});
+        assert!(
+            repo.delete_remote_branch("main".into(), receive_pack, askpass, env, cx.to_async())
+                .await
+                .is_err()
+        );
+        assert!(!marker.exists());
+    }
+
     #[gpui::test]
     async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
@@ -2711,6 +3473,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_tags_parsing() {
+        #[allow(clippy::octal_escapes)]
+        let input = "v1.0.0\0tag\0a1b2c3\0060964da\01733187470\0First release\nnightly\0commit\0060964da\0\01733187000\0Fix the build\n";
+        assert_eq!(
+            parse_tag_input(input),
+            vec![
+                Tag {
+                    name: "v1.0.0".into(),
+                    sha: "060964da".into(),
+                    message: Some("First release".into()),
+                    unix_timestamp: Some(1733187470),
+                },
+                Tag {
+                    name: "nightly".into(),
+                    sha: "060964da".into(),
+                    message: None,
+                    unix_timestamp: Some(1733187000),
+                },
+            ]
+        )
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<'_, Result<()>> {
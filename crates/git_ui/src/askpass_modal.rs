@@ -1,7 +1,7 @@
-use askpass::EncryptedPassword;
+use askpass::{AskPassDelegate, EncryptedPassword};
 use editor::Editor;
 use futures::channel::oneshot;
-use gpui::{AppContext, DismissEvent, Entity, EventEmitter, Focusable, Styled};
+use gpui::{AppContext, DismissEvent, Entity, EventEmitter, Focusable, Styled, WeakEntity};
 use ui::{
     ActiveTheme, AnyElement, App, Button, Clickable, Color, Context, DynamicSpacing, Headline,
     HeadlineSize, Icon, IconName, IconSize, InteractiveElement, IntoElement, Label, LabelCommon,
@@ -9,7 +9,7 @@ use ui::{
     h_flex, v_flex,
 };
 use util::maybe;
-use workspace::ModalView;
+use workspace::{ModalView, Workspace};
 use zeroize::Zeroize;
 
 pub(crate) struct AskPassModal {
@@ -145,3 +145,25 @@ impl Render for AskPassModal {
             .children(self.render_hint(cx))
     }
 }
+
+/// Creates an [`AskPassDelegate`] that prompts for credentials in a modal on the given workspace.
+pub(crate) fn askpass_delegate(
+    operation: impl Into<SharedString>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) -> AskPassDelegate {
+    let operation = operation.into();
+    let window = window.window_handle();
+    AskPassDelegate::new(&mut cx.to_async(), move |prompt, tx, cx| {
+        window
+            .update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        AskPassModal::new(operation.clone(), prompt.into(), tx, window, cx)
+                    });
+                })
+            })
+            .ok();
+    })
+}
@@ -6,7 +6,7 @@ use git::repository::Branch;
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
     IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    Subscription, Task, Window, actions, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
@@ -20,6 +20,18 @@ use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::askpass_modal;
+
+actions!(
+    branch_picker,
+    [
+        /// Deletes the selected branch. Remote branches are deleted on the remote.
+        DeleteBranch,
+        /// Deletes the selected branch even if it has not been merged.
+        ForceDeleteBranch,
+    ]
+);
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
@@ -172,6 +184,32 @@ impl BranchList {
         self.picker
             .update(cx, |picker, _| picker.delegate.modifiers = ev.modifiers)
     }
+
+    fn handle_delete_branch(
+        &mut self,
+        _: &DeleteBranch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_branch_at(picker.delegate.selected_index(), false, window, cx);
+        });
+    }
+
+    fn handle_force_delete_branch(
+        &mut self,
+        _: &ForceDeleteBranch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_branch_at(picker.delegate.selected_index(), true, window, cx);
+        });
+    }
 }
 impl ModalView for BranchList {}
 impl EventEmitter<DismissEvent> for BranchList {}
@@ -188,6 +226,8 @@ impl Render for BranchList {
             .key_context("GitBranchSelector")
             .w(self.width)
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
+            .on_action(cx.listener(Self::handle_delete_branch))
+            .on_action(cx.listener(Self::handle_force_delete_branch))
             .child(self.picker.clone())
             .on_mouse_down_out({
                 cx.listener(move |this, _, window, cx| {
@@ -264,6 +304,65 @@ impl BranchListDelegate {
         });
         cx.emit(DismissEvent);
     }
+
+    fn delete_branch_at(
+        &self,
+        ix: usize,
+        force: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(entry) = self.matches.get(ix).filter(|entry| !entry.is_new) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let branch = entry.branch.clone();
+
+        let delete = if branch.is_remote() {
+            let Some((remote, branch_name)) = branch.name().split_once('/') else {
+                return;
+            };
+            let Some(workspace) = window.root::<Workspace>().flatten() else {
+                return;
+            };
+            if workspace.read(cx).project().read(cx).is_via_collab() {
+                return;
+            }
+            let remote: SharedString = remote.to_string().into();
+            let branch_name: SharedString = branch_name.to_string().into();
+            let askpass = askpass_modal::askpass_delegate(
+                format!("git push {remote} --delete"),
+                workspace.downgrade(),
+                window,
+                cx,
+            );
+            let delete = repo.update(cx, |repo, cx| {
+                repo.delete_remote_branch(branch_name, remote, askpass, cx)
+            });
+            cx.background_spawn(async move { delete.await?.map(|_| ()) })
+        } else {
+            let delete = repo.update(cx, |repo, _| {
+                repo.delete_branch(branch.name().to_string(), force)
+            });
+            cx.background_spawn(async move { delete.await? })
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            delete.await?;
+            picker.update_in(cx, |picker, window, cx| {
+                if let Some(all_branches) = picker.delegate.all_branches.as_mut() {
+                    all_branches.retain(|candidate| candidate.ref_name != branch.ref_name);
+                }
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete branch", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
 }
 
 impl PickerDelegate for BranchListDelegate {
@@ -474,6 +573,23 @@ impl PickerDelegate for BranchListDelegate {
                         )
                     }),
             )
+        } else if !entry.is_new && !entry.branch.is_head {
+            let force = self.modifiers.secondary() && !entry.branch.is_remote();
+            Some(
+                IconButton::new("delete-branch", IconName::Trash)
+                    .icon_size(IconSize::Small)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.delegate.delete_branch_at(ix, force, window, cx);
+                    }))
+                    .tooltip(move |_window, cx| {
+                        if force {
+                            Tooltip::for_action("Force Delete Branch", &ForceDeleteBranch, cx)
+                        } else {
+                            Tooltip::for_action("Delete Branch", &DeleteBranch, cx)
+                        }
+                    }),
+            )
         } else {
             None
         };
@@ -0,0 +1,811 @@
+use std::ops::Range;
+
+use futures::channel::oneshot;
+use git::{
+    log::{LogEntry, LogOptions},
+    repository::RepoPath,
+};
+use gpui::{
+    AnyElement, App, Corner, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    MouseButton, MouseDownEvent, PathBuilder, Pixels, Point, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, Window, anchored, canvas, deferred, point, uniform_list,
+};
+use project::git_store::{Repository, RepositoryEvent};
+use theme::AccentColors;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{ContextMenu, Tooltip, prelude::*};
+use workspace::{Item, Workspace};
+
+use crate::{commit_view::CommitView, rebase_todo_editor::rebase_todo_delegate};
+
+/// Number of commits loaded at once.
+const PAGE_SIZE: usize = 200;
+const LANE_WIDTH: Pixels = px(14.);
+const COMMIT_DOT_RADIUS: Pixels = px(3.5);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::ViewLog, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        GitLogView::open(repository, None, workspace, window, cx);
+    });
+    workspace.register_action(|workspace, _: &git::FileHistory, window, cx| {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        GitLogView::open(repository, Some(path), workspace, window, cx);
+    });
+}
+
+/// Lists the history of a repository, or of a single file in it.
+pub struct GitLogView {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    options: LogOptions,
+    entries: Vec<LogEntry>,
+    graph: CommitGraph,
+    rows: Vec<GraphRow>,
+    lane_count: usize,
+    has_more: bool,
+    error: Option<SharedString>,
+    load_task: Option<Task<()>>,
+    selected_index: Option<usize>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    timezone: UtcOffset,
+    context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
+    _subscription: Subscription,
+}
+
+impl GitLogView {
+    /// Opens the history of the repository, or of `path` when given, reusing an open view when
+    /// there is one.
+    pub fn open(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.active_pane().read(cx).items().find_map(|item| {
+            let view = item.downcast::<GitLogView>()?;
+            let matches = {
+                let view = view.read(cx);
+                view.repository == repository && view.options.path == path
+            };
+            matches.then_some(view)
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = cx.entity().downgrade();
+        let view = cx.new(|cx| GitLogView::new(repository, path, workspace_handle, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        path: Option<RepoPath>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&repository, |this, _, event, cx| {
+            if let RepositoryEvent::BranchChanged = event {
+                this.reload(cx);
+            }
+        });
+        let timezone =
+            UtcOffset::from_whole_seconds(chrono::Local::now().offset().local_minus_utc())
+                .unwrap_or(UtcOffset::UTC);
+
+        let mut this = Self {
+            repository,
+            workspace,
+            options: LogOptions {
+                path,
+                ..LogOptions::default()
+            },
+            entries: Vec::new(),
+            graph: CommitGraph::default(),
+            rows: Vec::new(),
+            lane_count: 0,
+            has_more: false,
+            error: None,
+            load_task: None,
+            selected_index: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            timezone,
+            context_menu: None,
+            _subscription: subscription,
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.load_page(0, cx);
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.load_task.is_none() && self.has_more {
+            self.load_page(self.entries.len(), cx);
+        }
+    }
+
+    fn load_page(&mut self, skip: usize, cx: &mut Context<Self>) {
+        let options = LogOptions {
+            skip,
+            limit: PAGE_SIZE,
+            ..self.options.clone()
+        };
+        let log = self
+            .repository
+            .update(cx, |repository, _| repository.log(options));
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let log = log.await.map_err(anyhow::Error::from).and_then(|log| log);
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                match log {
+                    Ok(log) => {
+                        if skip == 0 {
+                            this.entries.clear();
+                            this.rows.clear();
+                            this.graph = CommitGraph::default();
+                            this.lane_count = 0;
+                            this.selected_index = None;
+                        }
+                        for entry in &log.entries {
+                            let row = this.graph.push(entry);
+                            this.lane_count = this.lane_count.max(row.lane_count());
+                            this.rows.push(row);
+                        }
+                        this.entries.extend(log.entries);
+                        this.has_more = log.has_more;
+                        this.error = None;
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Whether to draw the commit graph, which is meaningless when following a single file.
+    fn shows_graph(&self) -> bool {
+        self.options.path.is_none()
+    }
+
+    fn select_index(&mut self, index: usize, cx: &mut Context<Self>) {
+        self.selected_index = Some(index);
+        self.scroll_handle
+            .scroll_to_item(index, ScrollStrategy::Center);
+        if index + 1 == self.entries.len() {
+            self.load_more(cx);
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let index = match self.selected_index {
+            Some(ix) if ix + 1 < self.entries.len() => ix + 1,
+            Some(ix) => ix,
+            None => 0,
+        };
+        self.select_index(index, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let index = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        self.select_index(index, cx);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.selected_index {
+            self.open_commit(index, window, cx);
+        }
+    }
+
+    fn open_commit(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        CommitView::open(
+            entry.sha.to_string(),
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            None,
+            window,
+            cx,
+        );
+    }
+
+    fn deploy_context_menu(
+        &mut self,
+        position: Point<Pixels>,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        // Merging a branch rather than its tip gives the merge commit a meaningful message.
+        let merge_target = entry
+            .refs
+            .iter()
+            .find(|name| !name.starts_with("tag: ") && name.as_ref() != "HEAD")
+            .cloned()
+            .unwrap_or_else(|| entry.sha.clone());
+        let this = cx.weak_entity();
+        let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
+            context_menu
+                .context(self.focus_handle.clone())
+                .entry("Cherry-Pick Commit", None, {
+                    let this = this.clone();
+                    move |_, cx| {
+                        this.update(cx, |this, cx| this.cherry_pick(index, cx)).ok();
+                    }
+                })
+                .entry("Revert Commit", None, {
+                    let this = this.clone();
+                    move |_, cx| {
+                        this.update(cx, |this, cx| this.revert(index, cx)).ok();
+                    }
+                })
+                .separator()
+                .entry(format!("Merge {merge_target} into Current Branch"), None, {
+                    let this = this.clone();
+                    move |_, cx| {
+                        this.update(cx, |this, cx| this.merge(merge_target.clone(), cx))
+                            .ok();
+                    }
+                })
+                .entry("Rebase onto This Commit", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.rebase(index, false, window, cx))
+                            .ok();
+                    }
+                })
+                .entry("Interactive Rebase onto This Commit", None, {
+                    let this = this.clone();
+                    move |window, cx| {
+                        this.update(cx, |this, cx| this.rebase(index, true, window, cx))
+                            .ok();
+                    }
+                })
+        });
+        let subscription = cx.subscribe_in(
+            &context_menu,
+            window,
+            |this, _, _: &DismissEvent, window, cx| {
+                if this.context_menu.as_ref().is_some_and(|context_menu| {
+                    context_menu.0.focus_handle(cx).contains_focused(window, cx)
+                }) {
+                    cx.focus_self(window);
+                }
+                this.context_menu.take();
+                cx.notify();
+            },
+        );
+        self.selected_index = Some(index);
+        self.context_menu = Some((context_menu, position, subscription));
+        cx.notify();
+    }
+
+    fn cherry_pick(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        let task = self.repository.update(cx, |repository, _| {
+            repository.cherry_pick(vec![entry.sha.clone()])
+        });
+        self.report_errors(task, cx);
+    }
+
+    fn revert(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        let task = self.repository.update(cx, |repository, _| {
+            repository.revert(vec![entry.sha.clone()])
+        });
+        self.report_errors(task, cx);
+    }
+
+    fn merge(&mut self, target: SharedString, cx: &mut Context<Self>) {
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.merge(target));
+        self.report_errors(task, cx);
+    }
+
+    fn rebase(
+        &mut self,
+        index: usize,
+        interactive: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        let upstream = entry.sha.clone();
+        let todo_editor = interactive
+            .then(|| rebase_todo_delegate(upstream.clone(), self.workspace.clone(), window, cx));
+        let task = self
+            .repository
+            .update(cx, |repository, _| repository.rebase(upstream, todo_editor));
+        self.report_errors(task, cx);
+    }
+
+    /// Shows the error of a git operation, such as the conflicts it stopped on.
+    fn report_errors(&self, task: oneshot::Receiver<anyhow::Result<()>>, cx: &mut Context<Self>) {
+        let workspace = self.workspace.clone();
+        cx.spawn(async move |_, cx| {
+            if let Err(error) = task
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            {
+                workspace
+                    .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn format_timestamp(timestamp: i64, timezone: UtcOffset) -> String {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp(timestamp).unwrap_or(OffsetDateTime::now_utc());
+        time_format::format_localized_timestamp(
+            timestamp,
+            OffsetDateTime::now_utc(),
+            timezone,
+            time_format::TimestampFormat::EnhancedAbsolute,
+        )
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let accents = cx.theme().accents().clone();
+        let shows_graph = self.shows_graph();
+        range
+            .filter_map(|index| {
+                let entry = self.entries.get(index)?;
+                let short_sha = entry.sha.get(0..7).unwrap_or(&*entry.sha).to_string();
+                // Renames are followed, so show the name of the file at the time of the commit.
+                let renamed_path = entry
+                    .path
+                    .as_ref()
+                    .filter(|path| Some(*path) != self.options.path.as_ref());
+                Some(
+                    h_flex()
+                        .id(index)
+                        .h(rems(1.75))
+                        .px_2()
+                        .gap_2()
+                        .cursor_pointer()
+                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                        .when(self.selected_index == Some(index), |this| {
+                            this.bg(cx.theme().colors().element_selected)
+                        })
+                        .when(shows_graph, |this| {
+                            this.child(render_graph(
+                                self.rows[index].clone(),
+                                self.lane_count,
+                                accents.clone(),
+                            ))
+                        })
+                        .child(
+                            Label::new(short_sha)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .buffer_font(cx),
+                        )
+                        .children(entry.refs.iter().map(|name| {
+                            div()
+                                .px_1()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(cx.theme().colors().border_variant)
+                                .child(
+                                    Label::new(name.clone())
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Accent),
+                                )
+                        }))
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .child(Label::new(entry.subject.clone()).truncate()),
+                        )
+                        .when_some(renamed_path, |this, path| {
+                            this.child(
+                                Label::new(path.as_unix_str().to_string())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .child(
+                            Label::new(entry.author_name.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            Label::new(Self::format_timestamp(
+                                entry.commit_timestamp,
+                                self.timezone,
+                            ))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        )
+                        .tooltip(Tooltip::text(format!(
+                            "{} <{}>",
+                            entry.author_name, entry.author_email
+                        )))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.selected_index = Some(index);
+                            this.open_commit(index, window, cx);
+                            cx.notify();
+                        }))
+                        .on_mouse_down(
+                            MouseButton::Right,
+                            cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                                this.deploy_context_menu(event.position, index, window, cx);
+                                cx.stop_propagation();
+                            }),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn render_graph(row: GraphRow, lane_count: usize, accents: AccentColors) -> impl IntoElement {
+    canvas(
+        |_, _, _| {},
+        move |bounds, _, window, _| {
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * lane as f32 + LANE_WIDTH / 2.;
+            let middle = bounds.center().y;
+            let mut paint_edge = |from: Point<Pixels>, to: Point<Pixels>, lane: usize| {
+                let mut builder = PathBuilder::stroke(px(1.5));
+                builder.move_to(from);
+                if from.x == to.x {
+                    builder.line_to(to);
+                } else {
+                    builder.cubic_bezier_to(to, point(from.x, to.y), point(to.x, from.y));
+                }
+                if let Ok(path) = builder.build() {
+                    window.paint_path(path, accents.color_for_index(lane as u32));
+                }
+            };
+
+            for &(from, to) in &row.top {
+                paint_edge(
+                    point(lane_x(from), bounds.top()),
+                    point(lane_x(to), middle),
+                    from,
+                );
+            }
+            for &(from, to) in &row.bottom {
+                paint_edge(
+                    point(lane_x(from), middle),
+                    point(lane_x(to), bounds.bottom()),
+                    to,
+                );
+            }
+
+            let center = point(lane_x(row.lane), middle);
+            window.paint_quad(
+                gpui::fill(
+                    gpui::Bounds::from_corners(
+                        center - point(COMMIT_DOT_RADIUS, COMMIT_DOT_RADIUS),
+                        center + point(COMMIT_DOT_RADIUS, COMMIT_DOT_RADIUS),
+                    ),
+                    accents.color_for_index(row.lane as u32),
+                )
+                .corner_radii(COMMIT_DOT_RADIUS),
+            );
+        },
+    )
+    .w(LANE_WIDTH * lane_count as f32)
+    .h_full()
+    .flex_none()
+}
+
+impl EventEmitter<()> for GitLogView {}
+
+impl Focusable for GitLogView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for GitLogView {
+    type Event = ();
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        match self.options.path.as_ref().and_then(|path| path.file_name()) {
+            Some(file_name) => format!("History of {file_name}").into(),
+            None => "Git Log".into(),
+        }
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        let path = self.options.path.as_ref()?;
+        Some(format!("History of {}", path.as_unix_str()).into())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Git Log Opened")
+    }
+}
+
+impl Render for GitLogView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_loading = self.load_task.is_some();
+        v_flex()
+            .key_context("GitLog")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small)),
+                )
+            })
+            .child(
+                uniform_list(
+                    "git-log-entries",
+                    self.entries.len(),
+                    cx.processor(|this, range: Range<usize>, window, cx| {
+                        this.render_entries(range, window, cx)
+                    }),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_1(),
+            )
+            .when(self.has_more || is_loading, |this| {
+                this.child(
+                    h_flex()
+                        .p_1()
+                        .justify_center()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(
+                            Button::new("load-more-commits", "Load More")
+                                .label_size(LabelSize::Small)
+                                .disabled(is_loading)
+                                .on_click(cx.listener(|this, _, _, cx| this.load_more(cx))),
+                        ),
+                )
+            })
+            .children(self.context_menu.as_ref().map(|(menu, position, _)| {
+                deferred(
+                    anchored()
+                        .position(*position)
+                        .anchor(Corner::TopLeft)
+                        .child(menu.clone()),
+                )
+                .with_priority(1)
+            }))
+    }
+}
+
+/// How a commit's row of the graph is drawn: the lane of the commit, the edges coming into
+/// the row from the rows above and the edges going out to the rows below.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GraphRow {
+    lane: usize,
+    /// Edges from the top of the row, in the lane of the first element, to the middle of the
+    /// row, in the lane of the second one.
+    top: Vec<(usize, usize)>,
+    /// Edges from the middle of the row to its bottom.
+    bottom: Vec<(usize, usize)>,
+}
+
+impl GraphRow {
+    fn lane_count(&self) -> usize {
+        self.top
+            .iter()
+            .chain(&self.bottom)
+            .flat_map(|&(from, to)| [from, to])
+            .chain([self.lane])
+            .max()
+            .map_or(0, |lane| lane + 1)
+    }
+}
+
+/// Lays out the commit graph one commit at a time, children before their parents.
+#[derive(Default)]
+struct CommitGraph {
+    /// The commit expected next in each lane, `None` for free lanes.
+    lanes: Vec<Option<SharedString>>,
+}
+
+impl CommitGraph {
+    fn push(&mut self, entry: &LogEntry) -> GraphRow {
+        let lane = self
+            .lanes
+            .iter()
+            .position(|expected| expected.as_ref() == Some(&entry.sha))
+            .unwrap_or_else(|| self.free_lane());
+
+        let mut top = Vec::new();
+        for (ix, expected) in self.lanes.iter_mut().enumerate() {
+            let Some(sha) = expected else {
+                continue;
+            };
+            if *sha == entry.sha {
+                top.push((ix, lane));
+                *expected = None;
+            } else {
+                top.push((ix, ix));
+            }
+        }
+
+        let mut bottom = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| expected.is_some())
+            .map(|(ix, _)| (ix, ix))
+            .collect::<Vec<_>>();
+        for (parent_ix, parent) in entry.parents.iter().enumerate() {
+            let existing = self
+                .lanes
+                .iter()
+                .position(|expected| expected.as_ref() == Some(parent));
+            let target = match existing {
+                Some(ix) => ix,
+                None => {
+                    // The first parent continues straight down when it can.
+                    let ix = if parent_ix == 0 && self.lanes[lane].is_none() {
+                        lane
+                    } else {
+                        self.free_lane()
+                    };
+                    self.lanes[ix] = Some(parent.clone());
+                    ix
+                }
+            };
+            bottom.push((lane, target));
+        }
+
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        GraphRow { lane, top, bottom }
+    }
+
+    fn free_lane(&mut self) -> usize {
+        match self.lanes.iter().position(Option::is_none) {
+            Some(ix) => ix,
+            None => {
+                self.lanes.push(None);
+                self.lanes.len() - 1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parents: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            ..LogEntry::default()
+        }
+    }
+
+    fn row(lane: usize, top: &[(usize, usize)], bottom: &[(usize, usize)]) -> GraphRow {
+        GraphRow {
+            lane,
+            top: top.to_vec(),
+            bottom: bottom.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let mut graph = CommitGraph::default();
+        assert_eq!(graph.push(&commit("c", &["b"])), row(0, &[], &[(0, 0)]));
+        assert_eq!(
+            graph.push(&commit("b", &["a"])),
+            row(0, &[(0, 0)], &[(0, 0)])
+        );
+        assert_eq!(graph.push(&commit("a", &[])), row(0, &[(0, 0)], &[]));
+        assert!(graph.lanes.is_empty());
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        // d merges c into b, which both branched off a.
+        let mut graph = CommitGraph::default();
+        let merge = graph.push(&commit("d", &["b", "c"]));
+        assert_eq!(merge, row(0, &[], &[(0, 0), (0, 1)]));
+        assert_eq!(merge.lane_count(), 2);
+        assert_eq!(
+            graph.push(&commit("c", &["a"])),
+            row(1, &[(0, 0), (1, 1)], &[(0, 0), (1, 1)])
+        );
+        assert_eq!(
+            graph.push(&commit("b", &["a"])),
+            row(0, &[(0, 0), (1, 1)], &[(1, 1), (0, 1)])
+        );
+        assert_eq!(graph.push(&commit("a", &[])), row(1, &[(1, 1)], &[]));
+        assert!(graph.lanes.is_empty());
+
+        // A second root gets its own lane, which is freed once it ends.
+        let mut graph = CommitGraph::default();
+        graph.push(&commit("b", &["a"]));
+        assert_eq!(
+            graph.push(&commit("y", &["x"])),
+            row(1, &[(0, 0)], &[(0, 0), (1, 1)])
+        );
+        assert_eq!(
+            graph.push(&commit("x", &[])),
+            row(1, &[(0, 0), (1, 1)], &[(0, 0)])
+        );
+        assert_eq!(graph.lanes.len(), 1);
+    }
+}
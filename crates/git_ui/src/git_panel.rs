@@ -2,6 +2,7 @@ use crate::askpass_modal::AskPassModal;
 use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
+use crate::merge_editor::MergeEditor;
 use crate::project_diff::{self, Diff, ProjectDiff};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
@@ -17,8 +18,8 @@ use futures::StreamExt as _;
 use git::blame::ParsedCommitMessage;
 use git::repository::{
     Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, FetchOptions, GitCommitter,
-    PushOptions, Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking,
-    UpstreamTrackingStatus, get_git_committer,
+    InProgressOperation, OperationControl, PushOptions, Remote, RemoteCommandOutput, ResetMode,
+    Upstream, UpstreamTracking, UpstreamTrackingStatus, get_git_committer,
 };
 use git::stash::GitStash;
 use git::status::StageStatus;
@@ -834,6 +835,24 @@ impl GitPanel {
         });
     }
 
+    fn open_merge_editor(
+        &mut self,
+        _: &git::OpenMergeEditor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        maybe!({
+            let entry = self.entries.get(self.selected_entry?)?.status_entry()?;
+            let active_repo = self.active_repository.clone()?;
+            let repo_path = entry.repo_path.clone();
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    MergeEditor::open(active_repo, repo_path, workspace, window, cx);
+                })
+                .ok()
+        });
+    }
+
     fn revert_selected(
         &mut self,
         action: &git::RestoreFile,
@@ -1375,6 +1394,40 @@ impl GitPanel {
         .detach();
     }
 
+    /// Continues, aborts or skips the current commit of the merge, rebase, cherry-pick or revert
+    /// in progress in the active repository.
+    pub fn control_operation(&mut self, control: OperationControl, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(operation) = active_repository.read(cx).in_progress_operation else {
+            return;
+        };
+        if control == OperationControl::Skip && !operation.can_skip() {
+            return;
+        }
+
+        let task =
+            active_repository.update(cx, |repo, _| repo.control_operation(operation, control));
+        cx.spawn(async move |this, cx| {
+            let result = task
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            this.update(cx, |this, cx| {
+                if let Err(e) = result {
+                    this.show_error_toast(
+                        format!("{} {}", operation.command(), control.flag()),
+                        e,
+                        cx,
+                    );
+                }
+                cx.notify();
+            })
+        })
+        .detach();
+    }
+
     pub fn stash_apply(&mut self, _: &StashApply, _window: &mut Window, cx: &mut Context<Self>) {
         let Some(active_repository) = self.active_repository.clone() else {
             return;
@@ -3576,6 +3629,90 @@ impl GitPanel {
             ))
     }
 
+    fn render_operation_in_progress(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let repository = self.active_repository.as_ref()?.read(cx);
+        let operation = repository.in_progress_operation?;
+        let description: SharedString = match operation {
+            InProgressOperation::Rebase => match &repository.rebase_progress {
+                Some(progress) => match &progress.head_name {
+                    Some(head_name) => format!(
+                        "Rebasing {head_name} ({}/{})",
+                        progress.step, progress.total
+                    )
+                    .into(),
+                    None => format!("Rebasing ({}/{})", progress.step, progress.total).into(),
+                },
+                None => "Rebase in progress".into(),
+            },
+            InProgressOperation::Merge => "Merge in progress".into(),
+            InProgressOperation::CherryPick => "Cherry-pick in progress".into(),
+            InProgressOperation::Revert => "Revert in progress".into(),
+        };
+        let command = operation.command();
+
+        Some(
+            h_flex()
+                .py_1p5()
+                .px_2()
+                .gap_1p5()
+                .justify_between()
+                .border_b_1()
+                .border_color(cx.theme().colors().border.opacity(0.8))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(Label::new(description).size(LabelSize::Small).truncate()),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .when(operation.can_skip(), |this| {
+                            this.child(
+                                panel_button("Skip")
+                                    .tooltip(move |_, cx| {
+                                        Tooltip::for_action(
+                                            format!("git {command} --skip"),
+                                            &git::SkipOperation,
+                                            cx,
+                                        )
+                                    })
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.control_operation(OperationControl::Skip, cx)
+                                    })),
+                            )
+                        })
+                        .child(
+                            panel_button("Abort")
+                                .tooltip(move |_, cx| {
+                                    Tooltip::for_action(
+                                        format!("git {command} --abort"),
+                                        &git::AbortOperation,
+                                        cx,
+                                    )
+                                })
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.control_operation(OperationControl::Abort, cx)
+                                })),
+                        )
+                        .child(
+                            panel_filled_button("Continue")
+                                .disabled(self.conflicted_count > 0)
+                                .tooltip(move |_, cx| {
+                                    Tooltip::for_action(
+                                        format!("git {command} --continue"),
+                                        &git::ContinueOperation,
+                                        cx,
+                                    )
+                                })
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.control_operation(OperationControl::Continue, cx)
+                                })),
+                        ),
+                ),
+        )
+    }
+
     fn render_pending_amend(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .py_1p5()
@@ -3921,10 +4058,17 @@ impl GitPanel {
                     context_menu.action("Add to .gitignore", git::AddToGitignore.boxed_clone());
             }
 
-            context_menu
+            context_menu = context_menu
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
-                .action("Open File", SecondaryConfirm.boxed_clone())
+                .action("Open File", SecondaryConfirm.boxed_clone());
+
+            if entry.status.is_conflicted() {
+                context_menu =
+                    context_menu.action("Open Merge Editor", git::OpenMergeEditor.boxed_clone());
+            }
+
+            context_menu
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -4357,6 +4501,7 @@ impl Render for GitPanel {
             .on_action(cx.listener(Self::close_panel))
             .on_action(cx.listener(Self::open_diff))
             .on_action(cx.listener(Self::open_file))
+            .on_action(cx.listener(Self::open_merge_editor))
             .on_action(cx.listener(Self::focus_changes_list))
             .on_action(cx.listener(Self::focus_editor))
             .on_action(cx.listener(Self::expand_commit_editor))
@@ -4371,6 +4516,7 @@ impl Render for GitPanel {
                 v_flex()
                     .size_full()
                     .children(self.render_panel_header(window, cx))
+                    .children(self.render_operation_in_progress(cx))
                     .map(|this| {
                         if has_entries {
                             this.child(self.render_entries(has_write_access, window, cx))
@@ -12,7 +12,7 @@ use ui::{
 mod blame_ui;
 
 use git::{
-    repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
+    repository::{Branch, OperationControl, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
 use git_panel_settings::GitPanelSettings;
@@ -37,14 +37,18 @@ pub mod commit_tooltip;
 pub mod commit_view;
 mod conflict_view;
 pub mod file_diff_view;
+pub mod git_log_view;
 pub mod git_panel;
 mod git_panel_settings;
+pub mod merge_editor;
 pub mod onboarding;
 pub mod picker_prompt;
 pub mod project_diff;
+mod rebase_todo_editor;
 pub(crate) mod remote_output;
 pub mod repository_selector;
 pub mod stash_picker;
+pub mod tag_picker;
 pub mod text_diff_view;
 pub mod worktree_picker;
 
@@ -75,6 +79,9 @@ pub fn init(cx: &mut App) {
         branch_picker::register(workspace);
         worktree_picker::register(workspace);
         stash_picker::register(workspace);
+        tag_picker::register(workspace);
+        git_log_view::register(workspace);
+        merge_editor::register(workspace);
 
         let project = workspace.project().read(cx);
         if project.is_read_only(cx) {
@@ -178,6 +185,30 @@ pub fn init(cx: &mut App) {
                 panel.unstage_all(action, window, cx);
             });
         });
+        workspace.register_action(|workspace, _: &git::ContinueOperation, _, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.control_operation(OperationControl::Continue, cx);
+            });
+        });
+        workspace.register_action(|workspace, _: &git::AbortOperation, _, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.control_operation(OperationControl::Abort, cx);
+            });
+        });
+        workspace.register_action(|workspace, _: &git::SkipOperation, _, cx| {
+            let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.control_operation(OperationControl::Skip, cx);
+            });
+        });
         workspace.register_action(|workspace, _: &git::Uncommit, window, cx| {
             let Some(panel) = workspace.panel::<git_panel::GitPanel>(cx) else {
                 return;
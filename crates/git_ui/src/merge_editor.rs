@@ -0,0 +1,856 @@
+//! A three-way merge editor for resolving the conflicts of a file, showing the base, ours and
+//! theirs versions of the file above the editable result.
+
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use editor::{
+    Editor, EditorEvent, RowHighlightOptions, SelectionEffects, ToOffset as _, scroll::Autoscroll,
+};
+use git::repository::RepoPath;
+use gpui::{
+    AnyElement, AnyView, App, Entity, EventEmitter, FocusHandle, Focusable, Hsla, Subscription,
+    Task, WeakEntity, Window, actions, relative,
+};
+use language::{Anchor, Buffer, ToOffset as _};
+use project::{
+    ConflictRegion, ConflictSet, ConflictSetUpdate, ConflictStages, Project, git_store::Repository,
+};
+use ui::{Tooltip, prelude::*};
+use workspace::{
+    Item, ItemHandle as _, ItemNavHistory, Workspace,
+    item::{ItemEvent, SaveOptions},
+    notifications::DetachAndPromptErr,
+    searchable::SearchableItemHandle,
+};
+
+use crate::conflict_view::resolve_conflict;
+
+actions!(
+    merge_editor,
+    [
+        /// Moves to the next conflict of the result.
+        NextConflict,
+        /// Moves to the previous conflict of the result.
+        PreviousConflict,
+        /// Resolves the current conflict with our version.
+        AcceptOurs,
+        /// Resolves the current conflict with their version.
+        AcceptTheirs,
+        /// Resolves the current conflict with our version followed by theirs.
+        AcceptBoth,
+        /// Saves the result and stages it, marking the file as resolved.
+        MarkResolved,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &git::OpenMergeEditor, window, cx| {
+        let Some(project_path) = workspace
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+        else {
+            return;
+        };
+        let Some((repository, path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        MergeEditor::open(repository, path, workspace, window, cx);
+    });
+}
+
+struct MergeEditorHunk;
+
+/// A side of the merge, shown read-only above the result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Base,
+    Ours,
+    Theirs,
+}
+
+impl Side {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Base => "Base",
+            Self::Ours => "Ours",
+            Self::Theirs => "Theirs",
+        }
+    }
+
+    /// The part of the conflict coming from this side. The base is only known for conflicts
+    /// written in the diff3 style.
+    fn hunk(self, conflict: &ConflictRegion) -> Option<Range<Anchor>> {
+        match self {
+            Self::Base => conflict.base.clone(),
+            Self::Ours => Some(conflict.ours.clone()),
+            Self::Theirs => Some(conflict.theirs.clone()),
+        }
+    }
+
+    fn color(self, cx: &App) -> Hsla {
+        match self {
+            Self::Base => cx.theme().colors().version_control_conflict.opacity(0.1),
+            Self::Ours => cx.theme().colors().version_control_conflict_marker_ours,
+            Self::Theirs => cx.theme().colors().version_control_conflict_marker_theirs,
+        }
+    }
+}
+
+struct SidePane {
+    side: Side,
+    /// The file on this side, or `None` when it doesn't exist there.
+    text: Option<String>,
+    editor: Entity<Editor>,
+    /// The range of the text holding each conflict of the result, when it could be found.
+    hunks: Vec<Option<Range<usize>>>,
+}
+
+pub struct MergeEditor {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    repository: Entity<Repository>,
+    repo_path: RepoPath,
+    buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    panes: [SidePane; 3],
+    result_editor: Entity<Editor>,
+    current_conflict: Option<usize>,
+    mark_resolved_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeEditor {
+    /// Opens the merge editor for a conflicted file, reusing an open one when there is one.
+    pub fn open(
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.active_pane().read(cx).items().find_map(|item| {
+            let editor = item.downcast::<MergeEditor>()?;
+            let matches = {
+                let editor = editor.read(cx);
+                editor.repository == repository && editor.repo_path == repo_path
+            };
+            matches.then_some(editor)
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let Some(project_path) = repository
+            .read(cx)
+            .repo_path_to_project_path(&repo_path, cx)
+        else {
+            return;
+        };
+        let project = workspace.project().clone();
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        let stages = repository.update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path.clone())
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let buffer = open_buffer.await?;
+            let stages = stages.await??;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let workspace_handle = cx.entity().downgrade();
+                let editor = cx.new(|cx| {
+                    MergeEditor::new(
+                        workspace_handle,
+                        project,
+                        repository,
+                        repo_path,
+                        buffer,
+                        stages,
+                        window,
+                        cx,
+                    )
+                });
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+        })
+        .detach_and_prompt_err(
+            "Failed to open merge editor",
+            window,
+            cx,
+            |error, _, _| Some(error.to_string()),
+        );
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        repository: Entity<Repository>,
+        repo_path: RepoPath,
+        buffer: Entity<Buffer>,
+        stages: ConflictStages,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let language = buffer.read(cx).language().cloned();
+        let mut side_pane = |side: Side, text: Option<String>| {
+            let side_buffer = cx.new(|cx| {
+                let mut buffer = Buffer::local(text.clone().unwrap_or_default(), cx);
+                buffer.set_language(language.clone(), cx);
+                buffer
+            });
+            let editor = cx.new(|cx| {
+                let mut editor = Editor::for_buffer(side_buffer, None, window, cx);
+                editor.set_read_only(true);
+                editor.set_show_code_actions(false, cx);
+                editor.set_show_runnables(false, cx);
+                editor.set_show_breakpoints(false, cx);
+                editor
+            });
+            SidePane {
+                side,
+                text,
+                editor,
+                hunks: Vec::new(),
+            }
+        };
+        let panes = [
+            side_pane(Side::Base, stages.base),
+            side_pane(Side::Ours, stages.ours),
+            side_pane(Side::Theirs, stages.theirs),
+        ];
+
+        // The result is edited in a regular editor for the file, which shows the buttons to resolve
+        // each conflict inline.
+        let result_editor =
+            cx.new(|cx| Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx));
+        let conflict_set = project.update(cx, |project, cx| {
+            project.git_store().update(cx, |git_store, cx| {
+                git_store.open_conflict_set(buffer.clone(), cx)
+            })
+        });
+
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &conflict_set,
+                window,
+                |this, _, _: &ConflictSetUpdate, window, cx| {
+                    this.update_hunks(cx);
+                    this.update_current_conflict(window, cx);
+                },
+            ),
+            cx.subscribe_in(&result_editor, window, |this, _, event, window, cx| {
+                if let EditorEvent::SelectionsChanged { .. } = event {
+                    this.update_current_conflict(window, cx);
+                }
+                cx.emit(event.clone());
+            }),
+            cx.observe(&repository, |_, _, cx| cx.notify()),
+        ];
+
+        let mut this = Self {
+            workspace,
+            project,
+            repository,
+            repo_path,
+            buffer,
+            conflict_set,
+            panes,
+            result_editor,
+            current_conflict: None,
+            mark_resolved_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.update_hunks(cx);
+        if !this.conflicts(cx).is_empty() {
+            this.go_to_conflict(0, window, cx);
+        }
+        this
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    fn is_resolved(&self, cx: &App) -> bool {
+        !self
+            .repository
+            .read(cx)
+            .status_for_path(&self.repo_path)
+            .is_some_and(|entry| entry.status.is_conflicted())
+    }
+
+    /// Finds the conflicts of the result in each side, and highlights them there.
+    fn update_hunks(&mut self, cx: &mut Context<Self>) {
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let options = RowHighlightOptions {
+            include_gutter: true,
+            ..Default::default()
+        };
+        for pane in &mut self.panes {
+            let hunks = conflicts
+                .iter()
+                .map(|conflict| {
+                    let range = pane.side.hunk(conflict)?;
+                    Some(buffer.text_for_range(range).collect::<String>())
+                })
+                .collect::<Vec<_>>();
+            pane.hunks = locate_hunks(pane.text.as_deref().unwrap_or_default(), &hunks);
+
+            let color = pane.side.color(cx);
+            let hunks = &pane.hunks;
+            pane.editor.update(cx, |editor, cx| {
+                editor.clear_row_highlights::<MergeEditorHunk>();
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                for range in hunks.iter().flatten() {
+                    let range =
+                        snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+                    editor.highlight_rows::<MergeEditorHunk>(range, color, options, cx);
+                }
+            });
+        }
+        cx.notify();
+    }
+
+    fn cursor_offset(&self, cx: &App) -> usize {
+        let editor = self.result_editor.read(cx);
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .selections
+            .newest_anchor()
+            .head()
+            .to_offset(&snapshot)
+    }
+
+    /// The conflict containing the cursor, or else the first one after it.
+    fn conflict_at_cursor(&self, cx: &App) -> Option<usize> {
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        conflicts
+            .iter()
+            .position(|conflict| conflict.range.end.to_offset(&buffer) >= cursor)
+            .or_else(|| conflicts.len().checked_sub(1))
+    }
+
+    fn update_current_conflict(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let current_conflict = self.conflict_at_cursor(cx);
+        if current_conflict == self.current_conflict {
+            return;
+        }
+        self.current_conflict = current_conflict;
+
+        // Keep the current conflict in view in each side.
+        if let Some(ix) = current_conflict {
+            for pane in &self.panes {
+                let Some(Some(range)) = pane.hunks.get(ix) else {
+                    continue;
+                };
+                let start = range.start;
+                pane.editor.update(cx, |editor, cx| {
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::center()),
+                        window,
+                        cx,
+                        |selections| selections.select_ranges([start..start]),
+                    );
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    fn go_to_conflict(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self.conflicts(cx).get(ix).cloned() else {
+            return;
+        };
+        let offset = conflict
+            .range
+            .start
+            .to_offset(&self.buffer.read(cx).snapshot());
+        self.result_editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([offset..offset]),
+            );
+        });
+        window.focus(&self.result_editor.focus_handle(cx));
+    }
+
+    fn next_conflict(&mut self, _: &NextConflict, window: &mut Window, cx: &mut Context<Self>) {
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        let next = conflicts
+            .iter()
+            .position(|conflict| conflict.range.start.to_offset(&buffer) > cursor);
+        if let Some(ix) = next.or((!conflicts.is_empty()).then_some(0)) {
+            self.go_to_conflict(ix, window, cx);
+        }
+    }
+
+    fn previous_conflict(
+        &mut self,
+        _: &PreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let conflicts = self.conflicts(cx);
+        let buffer = self.buffer.read(cx).snapshot();
+        let cursor = self.cursor_offset(cx);
+        let previous = conflicts
+            .iter()
+            .rposition(|conflict| conflict.range.end.to_offset(&buffer) < cursor);
+        if let Some(ix) = previous.or(conflicts.len().checked_sub(1)) {
+            self.go_to_conflict(ix, window, cx);
+        }
+    }
+
+    fn accept(&mut self, sides: &[Side], window: &mut Window, cx: &mut Context<Self>) {
+        let Some(conflict) = self
+            .conflict_at_cursor(cx)
+            .and_then(|ix| self.conflicts(cx).get(ix).cloned())
+        else {
+            return;
+        };
+        let Some(ranges) = sides
+            .iter()
+            .map(|side| side.hunk(&conflict))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return;
+        };
+        let Some(excerpt_id) = self
+            .result_editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .excerpt_ids()
+            .first()
+            .copied()
+        else {
+            return;
+        };
+        resolve_conflict(
+            self.result_editor.downgrade(),
+            excerpt_id,
+            conflict,
+            ranges,
+            window,
+            cx,
+        )
+        .detach();
+    }
+
+    fn accept_ours(&mut self, _: &AcceptOurs, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(&[Side::Ours], window, cx);
+    }
+
+    fn accept_theirs(&mut self, _: &AcceptTheirs, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(&[Side::Theirs], window, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.accept(&[Side::Ours, Side::Theirs], window, cx);
+    }
+
+    fn can_mark_resolved(&self, cx: &App) -> bool {
+        self.mark_resolved_task.is_none() && self.conflicts(cx).is_empty() && !self.is_resolved(cx)
+    }
+
+    fn mark_resolved(&mut self, _: &MarkResolved, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.can_mark_resolved(cx) {
+            return;
+        }
+        let save = self.project.update(cx, |project, cx| {
+            project.save_buffer(self.buffer.clone(), cx)
+        });
+        let repository = self.repository.clone();
+        let repo_path = self.repo_path.clone();
+        self.mark_resolved_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result: Result<()> = async {
+                save.await?;
+                repository
+                    .update(cx, |repository, cx| {
+                        repository.stage_entries(vec![repo_path], cx)
+                    })?
+                    .await
+            }
+            .await;
+            this.update(cx, |this, cx| {
+                this.mark_resolved_task = None;
+                if let Err(error) = result {
+                    this.workspace
+                        .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+                        .ok();
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+        cx.notify();
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflicts = self.conflicts(cx);
+        let focus_handle = self.result_editor.focus_handle(cx);
+        let status = if self.is_resolved(cx) {
+            Label::new("Resolved").color(Color::Success)
+        } else if conflicts.is_empty() {
+            Label::new("No conflicts remaining").color(Color::Muted)
+        } else {
+            let current = self.current_conflict.map_or(0, |ix| ix + 1);
+            Label::new(format!("Conflict {current} of {}", conflicts.len())).color(Color::Muted)
+        };
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(self.repo_path.as_unix_str().to_string()).size(LabelSize::Small))
+            .child(status.size(LabelSize::Small))
+            .child(div().flex_1())
+            .child(
+                IconButton::new("previous-conflict", IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(conflicts.is_empty())
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Previous Conflict",
+                        &PreviousConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.previous_conflict(&PreviousConflict, window, cx)
+                    })),
+            )
+            .child(
+                IconButton::new("next-conflict", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(conflicts.is_empty())
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Next Conflict",
+                        &NextConflict,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.next_conflict(&NextConflict, window, cx)
+                    })),
+            )
+            .child(
+                Button::new("accept-both", "Accept Both")
+                    .label_size(LabelSize::Small)
+                    .disabled(conflicts.is_empty())
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Use ours followed by theirs for the current conflict",
+                        &AcceptBoth,
+                        &focus_handle,
+                    ))
+                    .on_click(
+                        cx.listener(|this, _, window, cx| {
+                            this.accept_both(&AcceptBoth, window, cx)
+                        }),
+                    ),
+            )
+            .child(
+                Button::new("mark-resolved", "Mark as Resolved")
+                    .label_size(LabelSize::Small)
+                    .style(ButtonStyle::Filled)
+                    .disabled(!self.can_mark_resolved(cx))
+                    .tooltip(Tooltip::for_action_title_in(
+                        "Save and stage the file",
+                        &MarkResolved,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.mark_resolved(&MarkResolved, window, cx)
+                    })),
+            )
+    }
+
+    fn render_pane(&self, pane: &SidePane, cx: &mut Context<Self>) -> impl IntoElement {
+        let side = pane.side;
+        let can_accept = self
+            .current_conflict
+            .and_then(|ix| self.conflicts(cx).get(ix).cloned())
+            .is_some_and(|conflict| side.hunk(&conflict).is_some());
+        let button_id = match side {
+            Side::Base => "accept-base",
+            Side::Ours => "accept-ours",
+            Side::Theirs => "accept-theirs",
+        };
+        let tooltip: Box<dyn Fn(&mut Window, &mut App) -> AnyView> = match side {
+            Side::Base => Box::new(Tooltip::text("Use the base for the current conflict")),
+            Side::Ours => Box::new(Tooltip::for_action_title_in(
+                "Use ours for the current conflict",
+                &AcceptOurs,
+                &self.result_editor.focus_handle(cx),
+            )),
+            Side::Theirs => Box::new(Tooltip::for_action_title_in(
+                "Use theirs for the current conflict",
+                &AcceptTheirs,
+                &self.result_editor.focus_handle(cx),
+            )),
+        };
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .h_full()
+            .when(side != Side::Theirs, |this| {
+                this.border_r_1().border_color(cx.theme().colors().border)
+            })
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(side.label())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new(button_id, "Accept")
+                            .label_size(LabelSize::Small)
+                            .disabled(!can_accept)
+                            .tooltip(tooltip)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.accept(&[side], window, cx)
+                            })),
+                    ),
+            )
+            .child(if pane.text.is_some() {
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .child(pane.editor.clone())
+                    .into_any_element()
+            } else {
+                h_flex()
+                    .flex_1()
+                    .justify_center()
+                    .child(
+                        Label::new("The file doesn't exist on this side")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .into_any_element()
+            })
+    }
+}
+
+/// Finds the text of each hunk in `text`. Hunks are searched for in order, each one after the
+/// previous one found, so that text repeated in the file is matched to the right conflict.
+fn locate_hunks(text: &str, hunks: &[Option<String>]) -> Vec<Option<Range<usize>>> {
+    let mut offset = 0;
+    hunks
+        .iter()
+        .map(|hunk| {
+            let hunk = hunk.as_deref()?;
+            let start = offset + text[offset..].find(hunk)?;
+            offset = start + hunk.len();
+            Some(start..offset)
+        })
+        .collect()
+}
+
+impl EventEmitter<EditorEvent> for MergeEditor {}
+
+impl Focusable for MergeEditor {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeEditor {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranchAlt).color(Color::Muted))
+    }
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        let file_name = self.repo_path.file_name().unwrap_or_default();
+        format!("Merge {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", self.repo_path.as_unix_str()).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Editor Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("MergeEditor")
+            .on_action(cx.listener(Self::next_conflict))
+            .on_action(cx.listener(Self::previous_conflict))
+            .on_action(cx.listener(Self::accept_ours))
+            .on_action(cx.listener(Self::accept_theirs))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::mark_resolved))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .h(relative(0.4))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .children(
+                        self.panes
+                            .iter()
+                            .map(|pane| self.render_pane(pane, cx).into_any_element())
+                            .collect::<Vec<AnyElement>>(),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(
+                                Label::new("Result")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    )
+                    .child(div().flex_1().min_h_0().child(self.result_editor.clone())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_hunks() {
+        let text = "a\nx\nb\nx\nc\n";
+        let hunks = [
+            Some("x\n".to_string()),
+            None,
+            Some("x\n".to_string()),
+            Some("missing\n".to_string()),
+            Some("c\n".to_string()),
+        ];
+        assert_eq!(
+            locate_hunks(text, &hunks),
+            [Some(2..4), None, Some(6..8), None, Some(8..10)]
+        );
+    }
+}
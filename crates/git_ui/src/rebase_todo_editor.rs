@@ -0,0 +1,426 @@
+use editor::{Editor, EditorEvent};
+use futures::channel::oneshot;
+use git::rebase::{RebaseTodoAction, RebaseTodoDelegate, RebaseTodoEntry};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle, Subscription,
+    WeakEntity, Window, actions,
+};
+use ui::{KeyBinding, Tooltip, prelude::*};
+use workspace::{ModalView, Workspace};
+
+actions!(
+    rebase_todo_editor,
+    [
+        /// Moves the selected commit up in the todo list, so that it is applied earlier.
+        MoveUp,
+        /// Moves the selected commit down in the todo list, so that it is applied later.
+        MoveDown,
+        /// Applies the selected commit as is.
+        PickCommit,
+        /// Applies the selected commit with a new message.
+        RewordCommit,
+        /// Melds the selected commit into the previous one, combining their messages.
+        SquashCommit,
+        /// Melds the selected commit into the previous one, discarding its message.
+        FixupCommit,
+        /// Removes the selected commit from the branch.
+        DropCommit,
+    ]
+);
+
+/// Returns a todo editor showing the todo list of an interactive rebase onto `upstream` in a
+/// modal of the workspace.
+pub(crate) fn rebase_todo_delegate(
+    upstream: SharedString,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) -> RebaseTodoDelegate {
+    let window = window.window_handle();
+    RebaseTodoDelegate::new(&mut cx.to_async(), move |entries, tx, cx| {
+        let upstream = upstream.clone();
+        window
+            .update(cx, |_, window, cx| {
+                workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(window, cx, |window, cx| {
+                        RebaseTodoEditor::new(upstream, entries, tx, window, cx)
+                    });
+                })
+            })
+            .ok();
+    })
+}
+
+/// Edits the todo list of an interactive rebase before git runs it.
+pub(crate) struct RebaseTodoEditor {
+    upstream: SharedString,
+    entries: Vec<RebaseTodoEntry>,
+    selected_index: usize,
+    /// Edits the message of the selected commit, when it is reworded.
+    message_editor: Entity<Editor>,
+    tx: Option<oneshot::Sender<Option<Vec<RebaseTodoEntry>>>>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _subscription: Subscription,
+}
+
+impl RebaseTodoEditor {
+    fn new(
+        upstream: SharedString,
+        entries: Vec<RebaseTodoEntry>,
+        tx: oneshot::Sender<Option<Vec<RebaseTodoEntry>>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let message_editor = cx.new(|cx| {
+            let mut editor = Editor::auto_height(4, 12, window, cx);
+            editor.set_placeholder_text("Commit message", window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&message_editor, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.save_message(cx);
+            }
+        });
+        let focus_handle = cx.focus_handle();
+        window.focus(&focus_handle);
+        let mut this = Self {
+            upstream,
+            entries,
+            selected_index: 0,
+            message_editor,
+            tx: Some(tx),
+            focus_handle,
+            scroll_handle: ScrollHandle::new(),
+            _subscription: subscription,
+        };
+        this.load_message(window, cx);
+        this
+    }
+
+    fn save_message(&mut self, cx: &mut Context<Self>) {
+        let message = self.message_editor.read(cx).text(cx);
+        if let Some(entry) = self.entries.get_mut(self.selected_index)
+            && entry.action == RebaseTodoAction::Reword
+        {
+            entry.message = Some(message.into());
+        }
+    }
+
+    fn select_index(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.selected_index = index;
+        self.scroll_handle.scroll_to_item(index);
+        self.load_message(window, cx);
+        cx.notify();
+    }
+
+    /// Shows the message of the selected commit in the message editor.
+    fn load_message(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(self.selected_index) else {
+            return;
+        };
+        let message = entry
+            .message
+            .clone()
+            .unwrap_or_else(|| entry.subject.clone());
+        self.message_editor.update(cx, |editor, cx| {
+            if editor.text(cx) != message.as_ref() {
+                editor.set_text(message.to_string(), window, cx);
+            }
+        });
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.select_index(self.selected_index + 1, window, cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_index > 0 {
+            self.select_index(self.selected_index - 1, window, cx);
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveUp, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_entry(self.selected_index, -1, window, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_entry(self.selected_index, 1, window, cx);
+    }
+
+    fn move_entry(
+        &mut self,
+        index: usize,
+        offset: isize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(target) = index
+            .checked_add_signed(offset)
+            .filter(|target| *target < self.entries.len())
+        else {
+            return;
+        };
+        self.entries.swap(index, target);
+        self.select_index(target, window, cx);
+    }
+
+    fn set_action(&mut self, index: usize, action: RebaseTodoAction, cx: &mut Context<Self>) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.action = action;
+            cx.notify();
+        }
+    }
+
+    fn pick_commit(&mut self, _: &PickCommit, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseTodoAction::Pick, cx);
+    }
+
+    fn reword_commit(&mut self, _: &RewordCommit, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseTodoAction::Reword, cx);
+        self.load_message(window, cx);
+    }
+
+    fn squash_commit(&mut self, _: &SquashCommit, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseTodoAction::Squash, cx);
+    }
+
+    fn fixup_commit(&mut self, _: &FixupCommit, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseTodoAction::Fixup, cx);
+    }
+
+    fn drop_commit(&mut self, _: &DropCommit, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_action(self.selected_index, RebaseTodoAction::Drop, cx);
+    }
+
+    /// Explains why the todo list can't be run as is.
+    fn validation_error(&self) -> Option<SharedString> {
+        let first = self
+            .entries
+            .iter()
+            .find(|entry| entry.action != RebaseTodoAction::Drop)?;
+        first.action.is_melded().then(|| {
+            "The first commit can't be squashed or fixed up, as there is no previous commit to \
+            meld it into."
+                .into()
+        })
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        if self.validation_error().is_some() {
+            return;
+        }
+        self.save_message(cx);
+        if let Some(tx) = self.tx.take() {
+            tx.send(Some(self.entries.clone())).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(None).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(&self, index: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let entry = &self.entries[index];
+        let is_dropped = entry.action == RebaseTodoAction::Drop;
+        let short_sha = entry.sha.get(0..7).unwrap_or(&entry.sha).to_string();
+        let next_action = RebaseTodoAction::ALL
+            .into_iter()
+            .cycle()
+            .skip_while(|action| *action != entry.action)
+            .nth(1)
+            .unwrap_or(RebaseTodoAction::Pick);
+
+        h_flex()
+            .id(("rebase-todo-entry", index))
+            .h(rems(1.75))
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .hover(|style| style.bg(cx.theme().colors().element_hover))
+            .when(index == self.selected_index, |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .child(
+                Button::new(("rebase-todo-action", index), entry.action.as_str())
+                    .label_size(LabelSize::Small)
+                    .style(ButtonStyle::Filled)
+                    .width(rems(4.5))
+                    .tooltip(Tooltip::text("Change Action"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.set_action(index, next_action, cx);
+                        this.select_index(index, window, cx);
+                    })),
+            )
+            .child(
+                Label::new(short_sha)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .buffer_font(cx),
+            )
+            .child(
+                div().flex_1().min_w_0().child(
+                    Label::new(entry.subject.clone())
+                        .truncate()
+                        .when(is_dropped, |label| {
+                            label.strikethrough().color(Color::Muted)
+                        }),
+                ),
+            )
+            .child(
+                IconButton::new(("rebase-todo-move-up", index), IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .disabled(index == 0)
+                    .tooltip(Tooltip::text("Move Up"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.move_entry(index, -1, window, cx);
+                    })),
+            )
+            .child(
+                IconButton::new(("rebase-todo-move-down", index), IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .disabled(index + 1 == self.entries.len())
+                    .tooltip(Tooltip::text("Move Down"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.move_entry(index, 1, window, cx);
+                    })),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select_index(index, window, cx);
+            }))
+    }
+}
+
+impl EventEmitter<DismissEvent> for RebaseTodoEditor {}
+
+impl ModalView for RebaseTodoEditor {}
+
+impl Focusable for RebaseTodoEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RebaseTodoEditor {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let validation_error = self.validation_error();
+        let is_rewording = self
+            .entries
+            .get(self.selected_index)
+            .is_some_and(|entry| entry.action == RebaseTodoAction::Reword);
+        let short_upstream = self
+            .upstream
+            .get(0..7)
+            .unwrap_or(&self.upstream)
+            .to_string();
+        let focus_handle = self.focus_handle.clone();
+
+        v_flex()
+            .key_context("RebaseTodoEditor")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .elevation_3(cx)
+            .w(rems(40.))
+            .overflow_hidden()
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_2()
+                    .gap_1p5()
+                    .child(Icon::new(IconName::GitBranch).size(IconSize::XSmall))
+                    .child(
+                        Headline::new(format!(
+                            "Rebase {} commits onto {short_upstream}",
+                            self.entries.len()
+                        ))
+                        .size(HeadlineSize::XSmall),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("rebase-todo-entries")
+                    .key_context("RebaseTodoList")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_previous))
+                    .on_action(cx.listener(Self::move_up))
+                    .on_action(cx.listener(Self::move_down))
+                    .on_action(cx.listener(Self::pick_commit))
+                    .on_action(cx.listener(Self::reword_commit))
+                    .on_action(cx.listener(Self::squash_commit))
+                    .on_action(cx.listener(Self::fixup_commit))
+                    .on_action(cx.listener(Self::drop_commit))
+                    .max_h(rems(20.))
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .bg(cx.theme().colors().editor_background)
+                    .children((0..self.entries.len()).map(|index| self.render_entry(index, cx))),
+            )
+            .when(is_rewording, |this| {
+                this.child(
+                    div()
+                        .p_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .when_some(validation_error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .gap_1()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(error).size(LabelSize::Small)),
+                )
+            })
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_1p5()
+                    .gap_0p5()
+                    .justify_end()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Button::new("cancel-rebase", "Cancel")
+                            .key_binding(
+                                KeyBinding::for_action_in(&menu::Cancel, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.cancel(&menu::Cancel, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("start-rebase", "Start Rebase")
+                            .disabled(validation_error.is_some())
+                            .key_binding(
+                                KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.confirm(&menu::Confirm, window, cx)
+                            })),
+                    ),
+            )
+    }
+}
@@ -0,0 +1,619 @@
+use fuzzy::StringMatchCandidate;
+
+use git::repository::Tag;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render,
+    SharedString, Styled, Subscription, Task, WeakEntity, Window, actions, rems,
+};
+use notifications::status_toast::{StatusToast, ToastIcon};
+use picker::{Picker, PickerDelegate};
+use project::git_store::Repository;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{HighlightedLabel, KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, Workspace};
+
+use crate::askpass_modal;
+use crate::commit_view::CommitView;
+
+actions!(
+    tag_picker,
+    [
+        /// Delete the selected tag.
+        DeleteTag,
+        /// Push the selected tag to the upstream remote.
+        PushTag,
+    ]
+);
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &zed_actions::git::Tags,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx);
+    let can_push = !workspace.project().read(cx).is_via_collab();
+    let weak_workspace = workspace.weak_handle();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        TagList::new(repository, weak_workspace, can_push, rems(34.), window, cx)
+    })
+}
+
+pub struct TagList {
+    width: Rems,
+    pub picker: Entity<Picker<TagListDelegate>>,
+    picker_focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl TagList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        can_push: bool,
+        width: Rems,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = TagListDelegate::new(repository, workspace, can_push, cx);
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        let picker_focus_handle = picker.focus_handle(cx);
+        picker.update(cx, |picker, cx| {
+            picker.delegate.focus_handle = picker_focus_handle.clone();
+            picker.delegate.reload_tags(window, cx);
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            picker_focus_handle,
+            width,
+            _subscription,
+        }
+    }
+
+    fn handle_delete_tag(&mut self, _: &DeleteTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .delete_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+        cx.notify();
+    }
+
+    fn handle_push_tag(&mut self, _: &PushTag, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            picker
+                .delegate
+                .push_tag_at(picker.delegate.selected_index(), window, cx);
+        });
+        cx.notify();
+    }
+
+    fn handle_modifiers_changed(
+        &mut self,
+        ev: &ModifiersChangedEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker
+            .update(cx, |picker, _| picker.delegate.modifiers = ev.modifiers)
+    }
+}
+
+impl ModalView for TagList {}
+impl EventEmitter<DismissEvent> for TagList {}
+impl Focusable for TagList {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.picker_focus_handle.clone()
+    }
+}
+
+impl Render for TagList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("TagList")
+            .w(self.width)
+            .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
+            .on_action(cx.listener(Self::handle_delete_tag))
+            .on_action(cx.listener(Self::handle_push_tag))
+            .child(self.picker.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TagEntry {
+    tag: Tag,
+    positions: Vec<usize>,
+    is_new: bool,
+}
+
+pub struct TagListDelegate {
+    matches: Vec<TagEntry>,
+    all_tags: Option<Vec<Tag>>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    can_push: bool,
+    selected_index: usize,
+    last_query: String,
+    modifiers: Modifiers,
+    focus_handle: FocusHandle,
+}
+
+impl TagListDelegate {
+    fn new(
+        repo: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        can_push: bool,
+        cx: &mut Context<TagList>,
+    ) -> Self {
+        Self {
+            matches: vec![],
+            all_tags: None,
+            repo,
+            workspace,
+            can_push,
+            selected_index: 0,
+            last_query: Default::default(),
+            modifiers: Default::default(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    /// Splits a query like `v1.2.0 Release 1.2.0` into the tag name and an optional annotation.
+    fn parse_query(query: &str) -> (String, Option<String>) {
+        let query = query.trim();
+        match query.split_once(char::is_whitespace) {
+            Some((name, message)) if !message.trim().is_empty() => {
+                (name.to_string(), Some(message.trim().to_string()))
+            }
+            _ => (query.to_string(), None),
+        }
+    }
+
+    fn reload_tags(&self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(tags_request) = self
+            .repo
+            .clone()
+            .map(|repo| repo.update(cx, |repo, _| repo.tags()))
+        else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let tags = tags_request.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_tags = Some(tags);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        message: Option<String>,
+        annotated: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let message = if annotated {
+            Some(message.unwrap_or_else(|| name.clone()))
+        } else {
+            None
+        };
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, _| repo.create_tag(name, None, message))?
+                .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn delete_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(ix).filter(|entry| !entry.is_new) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let name = entry.tag.name.to_string();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            repo.update(cx, |repo, _| repo.delete_tag(name))?.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload_tags(window, cx);
+            })?;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag_at(&self, ix: usize, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if !self.can_push {
+            return;
+        }
+        let Some(entry) = self.matches.get(ix).filter(|entry| !entry.is_new) else {
+            return;
+        };
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let tag = entry.tag.name.clone();
+        let remote: SharedString = repo
+            .read(cx)
+            .branch
+            .as_ref()
+            .and_then(|branch| branch.upstream.as_ref())
+            .and_then(|upstream| upstream.remote_name())
+            .unwrap_or("origin")
+            .to_string()
+            .into();
+        let workspace = self.workspace.clone();
+        let askpass = askpass_modal::askpass_delegate(
+            format!("git push {remote}"),
+            workspace.clone(),
+            window,
+            cx,
+        );
+
+        cx.spawn(async move |_, cx| {
+            repo.update(cx, |repo, cx| {
+                repo.push_tag(tag.clone(), remote.clone(), askpass, cx)
+            })?
+            .await??;
+            workspace.update(cx, |workspace, cx| {
+                let toast = StatusToast::new(format!("Pushed {tag} to {remote}"), cx, |this, _| {
+                    this.icon(ToastIcon::new(IconName::Hash).color(Color::Muted))
+                });
+                workspace.toggle_status_toast(toast, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to push tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for TagListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select or create a tag…".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_tags) = self.all_tags.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let (name, _) = Self::parse_query(&query);
+            let mut matches: Vec<TagEntry> = if name.is_empty() {
+                all_tags
+                    .into_iter()
+                    .map(|tag| TagEntry {
+                        tag,
+                        positions: Vec::new(),
+                        is_new: false,
+                    })
+                    .collect()
+            } else {
+                let candidates = all_tags
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, tag)| StringMatchCandidate::new(ix, &tag.name))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &name,
+                    true,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| TagEntry {
+                    tag: all_tags[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                    is_new: false,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    if !name.is_empty()
+                        && !matches.iter().any(|entry| entry.tag.name.as_ref() == name)
+                    {
+                        matches.push(TagEntry {
+                            tag: Tag {
+                                name: name.into(),
+                                sha: SharedString::default(),
+                                message: None,
+                                unix_timestamp: None,
+                            },
+                            positions: Vec::new(),
+                            is_new: true,
+                        });
+                    }
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                    delegate.last_query = query;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        if entry.is_new {
+            let (name, message) = Self::parse_query(&self.last_query);
+            let annotated = secondary || message.is_some();
+            self.create_tag(name, message, annotated, window, cx);
+            return;
+        }
+
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        CommitView::open(
+            entry.tag.sha.to_string(),
+            repo.downgrade(),
+            self.workspace.clone(),
+            None,
+            window,
+            cx,
+        );
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = self.matches.get(ix)?;
+
+        let tag_name = if entry.is_new {
+            let (_, message) = Self::parse_query(&self.last_query);
+            let label = if message.is_some() || self.modifiers.secondary() {
+                format!("Create annotated tag \"{}\"…", entry.tag.name)
+            } else {
+                format!("Create tag \"{}\"…", entry.tag.name)
+            };
+            h_flex()
+                .gap_1()
+                .child(
+                    Icon::new(IconName::Plus)
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(label).single_line().truncate())
+                .into_any_element()
+        } else {
+            HighlightedLabel::new(entry.tag.name.clone(), entry.positions.clone())
+                .truncate()
+                .into_any_element()
+        };
+
+        let details = if entry.is_new {
+            let (_, message) = Self::parse_query(&self.last_query);
+            message.unwrap_or_else(|| "at HEAD".to_string())
+        } else {
+            let short_sha = entry.tag.sha.get(..7).unwrap_or(entry.tag.sha.as_ref());
+            match &entry.tag.message {
+                Some(message) => format!("{short_sha} • {message}"),
+                None => short_sha.to_string(),
+            }
+        };
+
+        let timestamp = entry.tag.unix_timestamp.map(|timestamp| {
+            let time = OffsetDateTime::from_unix_timestamp(timestamp)
+                .unwrap_or_else(|_| OffsetDateTime::now_utc());
+            format_local_timestamp(
+                time,
+                OffsetDateTime::now_utc(),
+                time_format::TimestampFormat::Relative,
+            )
+        });
+
+        Some(
+            ListItem::new(SharedString::from(format!("tag-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .tooltip(Tooltip::text(entry.tag.name.clone()))
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_hidden()
+                        .child(
+                            h_flex()
+                                .gap_6()
+                                .justify_between()
+                                .overflow_x_hidden()
+                                .child(tag_name)
+                                .when_some(timestamp, |this, timestamp| {
+                                    this.child(
+                                        Label::new(timestamp)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    )
+                                }),
+                        )
+                        .child(
+                            Label::new(details)
+                                .size(LabelSize::Small)
+                                .truncate()
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No tags found".into())
+    }
+
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        let focus_handle = self.focus_handle.clone();
+        let selected_existing = self
+            .matches
+            .get(self.selected_index)
+            .is_some_and(|entry| !entry.is_new);
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_0p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .when(selected_existing, |this| {
+                    this.child(
+                        Button::new("delete-tag", "Delete")
+                            .key_binding(
+                                KeyBinding::for_action_in(&DeleteTag, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(DeleteTag.boxed_clone(), cx)
+                            }),
+                    )
+                    .when(self.can_push, |this| {
+                        this.child(
+                            Button::new("push-tag", "Push")
+                                .key_binding(
+                                    KeyBinding::for_action_in(&PushTag, &focus_handle, cx)
+                                        .map(|kb| kb.size(rems_from_px(12.))),
+                                )
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(PushTag.boxed_clone(), cx)
+                                }),
+                        )
+                    })
+                    .child(
+                        Button::new("view-tag", "View")
+                            .key_binding(
+                                KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                            }),
+                    )
+                })
+                .when(!selected_existing, |this| {
+                    this.child(
+                        Button::new("create-annotated-tag", "Create Annotated")
+                            .key_binding(
+                                KeyBinding::for_action_in(
+                                    &menu::SecondaryConfirm,
+                                    &focus_handle,
+                                    cx,
+                                )
+                                .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                            }),
+                    )
+                    .child(
+                        Button::new("create-tag", "Create")
+                            .key_binding(
+                                KeyBinding::for_action_in(&menu::Confirm, &focus_handle, cx)
+                                    .map(|kb| kb.size(rems_from_px(12.))),
+                            )
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                            }),
+                    )
+                })
+                .into_any(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            TagListDelegate::parse_query("v1.0.0"),
+            ("v1.0.0".to_string(), None)
+        );
+        assert_eq!(
+            TagListDelegate::parse_query("  v1.0.0  "),
+            ("v1.0.0".to_string(), None)
+        );
+        assert_eq!(
+            TagListDelegate::parse_query("v1.0.0 Release 1.0.0"),
+            ("v1.0.0".to_string(), Some("Release 1.0.0".to_string()))
+        );
+    }
+}
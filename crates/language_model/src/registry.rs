@@ -212,6 +212,25 @@ impl LanguageModelRegistry {
             .flat_map(|provider| provider.provided_models(cx))
     }
 
+    /// Finds an available model from a `provider_id/model_id` identifier, such as the ones passed
+    /// to headless runs of the agent.
+    pub fn find_model(&self, model_name: &str, cx: &App) -> anyhow::Result<Arc<dyn LanguageModel>> {
+        let selected = SelectedModel::from_str(model_name).map_err(|e| anyhow::anyhow!(e))?;
+        self.available_models(cx)
+            .find(|model| model.id() == selected.model && model.provider_id() == selected.provider)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No language model with ID {}/{} was available. Available models: {}",
+                    selected.provider.0,
+                    selected.model.0,
+                    self.available_models(cx)
+                        .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+    }
+
     pub fn provider(&self, id: &LanguageModelProviderId) -> Option<Arc<dyn LanguageModelProvider>> {
         self.providers.get(id).cloned()
     }
@@ -411,4 +430,18 @@ mod tests {
         let providers = registry.read(cx).providers();
         assert!(providers.is_empty());
     }
+
+    #[gpui::test]
+    fn test_find_model(cx: &mut App) {
+        let registry = cx.new(|_| LanguageModelRegistry::default());
+        registry.update(cx, |registry, cx| {
+            registry.register_provider(Arc::new(FakeLanguageModelProvider::default()), cx);
+        });
+
+        let registry = registry.read(cx);
+        let model = registry.find_model("fake/fake", cx).unwrap();
+        assert_eq!(model.id().0, "fake");
+        assert!(registry.find_model("fake/missing", cx).is_err());
+        assert!(registry.find_model("fake", cx).is_err());
+    }
 }
@@ -34,7 +34,7 @@ use std::{
     pin::Pin,
     sync::{
         Arc, Weak,
-        atomic::{AtomicI32, Ordering::SeqCst},
+        atomic::{AtomicBool, AtomicI32, Ordering::SeqCst},
     },
     task::Poll,
     time::{Duration, Instant},
@@ -82,6 +82,16 @@ pub struct LanguageServerBinaryOptions {
 
 struct NotificationSerializer(Box<dyn FnOnce() -> String + Send + Sync>);
 
+/// The `initialize` request, with the result left as JSON so that capabilities
+/// `lsp-types` does not model yet can still be read from it.
+enum RawInitialize {}
+
+impl request::Request for RawInitialize {
+    type Params = InitializeParams;
+    type Result = Value;
+    const METHOD: &'static str = request::Initialize::METHOD;
+}
+
 /// A running language server process.
 pub struct LanguageServer {
     server_id: LanguageServerId,
@@ -92,6 +102,8 @@ pub struct LanguageServer {
     process_name: Arc<str>,
     binary: LanguageServerBinary,
     capabilities: RwLock<ServerCapabilities>,
+    /// `lsp-types` does not model `typeHierarchyProvider` in [`ServerCapabilities`], so it is tracked separately.
+    type_hierarchy_provider: AtomicBool,
     /// Configuration sent to the server, stored for display in the language server logs
     /// buffer. This is represented as the message sent to the LSP in order to avoid cloning it (can
     /// be large in cases like sending schemas to the json server).
@@ -310,6 +322,8 @@ pub struct AdapterServerCapabilities {
     pub server_capabilities: ServerCapabilities,
     // List of code actions supported by the LspAdapter matching the server
     pub code_action_kinds: Option<Vec<CodeActionKind>>,
+    // Whether the server handles `textDocument/prepareTypeHierarchy`
+    pub type_hierarchy_provider: bool,
 }
 
 impl LanguageServer {
@@ -508,6 +522,7 @@ impl LanguageServer {
                 .unwrap_or_default(),
             binary,
             capabilities: Default::default(),
+            type_hierarchy_provider: AtomicBool::new(false),
             configuration,
             code_action_kinds,
             next_id: Default::default(),
@@ -701,6 +716,9 @@ impl LanguageServer {
                     code_lens: Some(CodeLensWorkspaceClientCapabilities {
                         refresh_support: Some(true),
                     }),
+                    semantic_tokens: Some(SemanticTokensWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     workspace_edit: Some(WorkspaceEditClientCapabilities {
                         resource_operations: Some(vec![
                             ResourceOperationKind::Create,
@@ -869,6 +887,80 @@ impl LanguageServer {
                     color_provider: Some(DocumentColorClientCapabilities {
                         dynamic_registration: Some(true),
                     }),
+                    call_hierarchy: Some(CallHierarchyClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    type_hierarchy: Some(TypeHierarchyClientCapabilities {
+                        dynamic_registration: Some(true),
+                    }),
+                    semantic_tokens: Some(SemanticTokensClientCapabilities {
+                        dynamic_registration: Some(true),
+                        requests: SemanticTokensClientCapabilitiesRequests {
+                            range: Some(false),
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                        },
+                        token_types: vec![
+                            SemanticTokenType::NAMESPACE,
+                            SemanticTokenType::TYPE,
+                            SemanticTokenType::CLASS,
+                            SemanticTokenType::ENUM,
+                            SemanticTokenType::INTERFACE,
+                            SemanticTokenType::STRUCT,
+                            SemanticTokenType::TYPE_PARAMETER,
+                            SemanticTokenType::PARAMETER,
+                            SemanticTokenType::VARIABLE,
+                            SemanticTokenType::PROPERTY,
+                            SemanticTokenType::ENUM_MEMBER,
+                            SemanticTokenType::EVENT,
+                            SemanticTokenType::FUNCTION,
+                            SemanticTokenType::METHOD,
+                            SemanticTokenType::MACRO,
+                            SemanticTokenType::KEYWORD,
+                            SemanticTokenType::MODIFIER,
+                            SemanticTokenType::COMMENT,
+                            SemanticTokenType::STRING,
+                            SemanticTokenType::NUMBER,
+                            SemanticTokenType::REGEXP,
+                            SemanticTokenType::OPERATOR,
+                            SemanticTokenType::DECORATOR,
+                        ],
+                        token_modifiers: vec![
+                            SemanticTokenModifier::DECLARATION,
+                            SemanticTokenModifier::DEFINITION,
+                            SemanticTokenModifier::READONLY,
+                            SemanticTokenModifier::STATIC,
+                            SemanticTokenModifier::DEPRECATED,
+                            SemanticTokenModifier::ABSTRACT,
+                            SemanticTokenModifier::ASYNC,
+                            SemanticTokenModifier::MODIFICATION,
+                            SemanticTokenModifier::DOCUMENTATION,
+                            SemanticTokenModifier::DEFAULT_LIBRARY,
+                        ],
+                        formats: vec![TokenFormat::RELATIVE],
+                        overlapping_token_support: Some(false),
+                        multiline_token_support: Some(false),
+                        server_cancel_support: Some(true),
+                        augments_syntax_tokens: Some(true),
+                    }),
+                    document_link: Some(DocumentLinkClientCapabilities {
+                        dynamic_registration: Some(true),
+                        tooltip_support: Some(true),
+                    }),
+                    folding_range: Some(FoldingRangeClientCapabilities {
+                        dynamic_registration: Some(true),
+                        range_limit: None,
+                        line_folding_only: Some(true),
+                        folding_range_kind: Some(FoldingRangeKindCapability {
+                            value_set: Some(vec![
+                                FoldingRangeKind::Comment,
+                                FoldingRangeKind::Imports,
+                                FoldingRangeKind::Region,
+                            ]),
+                        }),
+                        folding_range: Some(FoldingRangeCapability {
+                            collapsed_text: Some(false),
+                        }),
+                    }),
                     ..TextDocumentClientCapabilities::default()
                 }),
                 experimental: Some(json!({
@@ -908,7 +1000,7 @@ impl LanguageServer {
     ) -> Task<Result<Arc<Self>>> {
         cx.background_spawn(async move {
             let response = self
-                .request::<request::Initialize>(params)
+                .request::<RawInitialize>(params)
                 .await
                 .into_response()
                 .with_context(|| {
@@ -918,10 +1010,16 @@ impl LanguageServer {
                         self.server_id()
                     )
                 })?;
+            let type_hierarchy_provider = response
+                .pointer("/capabilities/typeHierarchyProvider")
+                .is_some_and(|provider| !matches!(provider, Value::Null | Value::Bool(false)));
+            let response = serde_json::from_value::<InitializeResult>(response)
+                .context("parsing initialize response")?;
             if let Some(info) = response.server_info {
                 self.process_name = info.name.into();
             }
             self.capabilities = RwLock::new(response.capabilities);
+            self.type_hierarchy_provider = AtomicBool::new(type_hierarchy_provider);
             self.configuration = configuration;
 
             self.notify::<notification::Initialized>(InitializedParams {})?;
@@ -1164,6 +1262,7 @@ impl LanguageServer {
         AdapterServerCapabilities {
             server_capabilities: self.capabilities(),
             code_action_kinds: self.code_action_kinds(),
+            type_hierarchy_provider: self.type_hierarchy_provider.load(SeqCst),
         }
     }
 
@@ -1171,6 +1270,10 @@ impl LanguageServer {
         update(self.capabilities.write().deref_mut());
     }
 
+    pub fn set_type_hierarchy_provider(&self, provider: bool) {
+        self.type_hierarchy_provider.store(provider, SeqCst);
+    }
+
     pub fn configuration(&self) -> &Value {
         &self.configuration.settings
     }
@@ -3,19 +3,22 @@ pub mod registry;
 
 use std::sync::Arc;
 
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
-use context_server::{ContextServer, ContextServerCommand, ContextServerId};
+use context_server::types::{self, Notification as _, Request as _};
+use context_server::{ContextServer, ContextServerCommand, ContextServerEndpoint, ContextServerId};
 use futures::{FutureExt as _, future::join_all};
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity, actions};
 use registry::ContextServerDescriptorRegistry;
 use settings::{Settings as _, SettingsStore};
+use smol::channel::Sender;
+use url::Url;
 use util::{ResultExt as _, rel_path::RelPath};
 
 use crate::{
     Project,
     project_settings::{ContextServerSettings, ProjectSettings},
-    worktree_store::WorktreeStore,
+    worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 
 pub fn init(cx: &mut App) {
@@ -95,6 +98,9 @@ pub enum ContextServerConfiguration {
     Custom {
         command: ContextServerCommand,
     },
+    Http {
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         command: ContextServerCommand,
         settings: serde_json::Value,
@@ -102,10 +108,12 @@ pub enum ContextServerConfiguration {
 }
 
 impl ContextServerConfiguration {
-    pub fn command(&self) -> &ContextServerCommand {
+    /// The command that runs the server, unless it's reached over HTTP.
+    pub fn command(&self) -> Option<&ContextServerCommand> {
         match self {
-            ContextServerConfiguration::Custom { command } => command,
-            ContextServerConfiguration::Extension { command, .. } => command,
+            ContextServerConfiguration::Custom { command } => Some(command),
+            ContextServerConfiguration::Http { .. } => None,
+            ContextServerConfiguration::Extension { command, .. } => Some(command),
         }
     }
 
@@ -121,6 +129,10 @@ impl ContextServerConfiguration {
                 enabled: _,
                 command,
             } => Some(ContextServerConfiguration::Custom { command }),
+            ContextServerSettings::Http {
+                enabled: _,
+                endpoint,
+            } => Some(ContextServerConfiguration::Http { endpoint }),
             ContextServerSettings::Extension {
                 enabled: _,
                 settings,
@@ -158,10 +170,44 @@ pub enum Event {
         server_id: ContextServerId,
         status: ContextServerStatus,
     },
+    SamplingRequested(ContextServerSamplingRequest),
+    ElicitationRequested(ContextServerElicitationRequest),
 }
 
 impl EventEmitter<Event> for ContextServerStore {}
 
+/// A request by a context server to generate a message with a language model, which needs
+/// the user's approval.
+#[derive(Clone, Debug)]
+pub struct ContextServerSamplingRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<types::CreateMessageRequest>,
+    response_channel: Sender<Result<types::CreateMessageResult>>,
+}
+
+impl ContextServerSamplingRequest {
+    pub fn respond(self, result: Result<types::CreateMessageResult>) {
+        self.response_channel.try_send(result).ok();
+    }
+}
+
+/// A request by a context server for information from the user, to be entered in a form.
+#[derive(Clone, Debug)]
+pub struct ContextServerElicitationRequest {
+    pub server_id: ContextServerId,
+    pub params: Arc<types::ElicitRequest>,
+    response_channel: Sender<types::ElicitResult>,
+}
+
+impl ContextServerElicitationRequest {
+    pub fn respond(self, result: types::ElicitResult) {
+        self.response_channel.try_send(result).ok();
+    }
+}
+
+type RequestHandler =
+    Box<dyn 'static + Send + FnMut(serde_json::Value, AsyncApp) -> Task<Result<serde_json::Value>>>;
+
 impl ContextServerStore {
     pub fn new(
         worktree_store: Entity<WorktreeStore>,
@@ -223,7 +269,7 @@ impl ContextServerStore {
         weak_project: WeakEntity<Project>,
         cx: &mut Context<Self>,
     ) -> Self {
-        let subscriptions = if maintain_server_loop {
+        let mut subscriptions = if maintain_server_loop {
             vec![
                 cx.observe(&registry, |this, _registry, cx| {
                     this.available_context_servers_changed(cx);
@@ -240,6 +286,13 @@ impl ContextServerStore {
         } else {
             Vec::new()
         };
+        subscriptions.push(cx.subscribe(&worktree_store, |this, _, event, cx| {
+            if let WorktreeStoreEvent::WorktreeAdded(_) | WorktreeStoreEvent::WorktreeRemoved(..) =
+                event
+            {
+                this.roots_changed(cx);
+            }
+        }));
 
         let mut this = Self {
             _subscriptions: subscriptions,
@@ -407,7 +460,12 @@ impl ContextServerStore {
             let server = server.clone();
             let configuration = configuration.clone();
             async move |this, cx| {
-                match server.clone().start(cx).await {
+                let request_handlers = Self::request_handlers(this.clone(), id.clone());
+                match server
+                    .clone()
+                    .start_with_handlers(Vec::new(), request_handlers, cx)
+                    .await
+                {
                     Ok(_) => {
                         debug_assert!(server.client().is_some());
 
@@ -453,6 +511,117 @@ impl ContextServerStore {
         );
     }
 
+    /// Handlers for the requests that context servers send to Zed.
+    fn request_handlers(
+        this: WeakEntity<Self>,
+        server_id: ContextServerId,
+    ) -> Vec<(&'static str, RequestHandler)> {
+        let roots_handler: RequestHandler = Box::new({
+            let this = this.clone();
+            move |_, cx| {
+                let roots = this.read_with(&cx, |this, cx| this.roots(cx));
+                Task::ready(roots.and_then(|roots| {
+                    Ok(serde_json::to_value(types::ListRootsResponse {
+                        roots,
+                        meta: None,
+                    })?)
+                }))
+            }
+        });
+
+        let sampling_handler: RequestHandler = Box::new({
+            let this = this.clone();
+            let server_id = server_id.clone();
+            move |params, cx| {
+                let this = this.clone();
+                let server_id = server_id.clone();
+                cx.spawn(async move |cx| {
+                    let params = serde_json::from_value(params)?;
+                    let (tx, rx) = smol::channel::bounded(1);
+                    this.update(cx, |_, cx| {
+                        cx.emit(Event::SamplingRequested(ContextServerSamplingRequest {
+                            server_id,
+                            params: Arc::new(params),
+                            response_channel: tx,
+                        }))
+                    })?;
+                    let result = rx
+                        .recv()
+                        .await
+                        .map_err(|_| anyhow!("User rejected sampling request"))??;
+                    Ok(serde_json::to_value(result)?)
+                })
+            }
+        });
+
+        let elicitation_handler: RequestHandler = Box::new(move |params, cx| {
+            let this = this.clone();
+            let server_id = server_id.clone();
+            cx.spawn(async move |cx| {
+                let params = serde_json::from_value(params)?;
+                let (tx, rx) = smol::channel::bounded(1);
+                this.update(cx, |_, cx| {
+                    cx.emit(Event::ElicitationRequested(
+                        ContextServerElicitationRequest {
+                            server_id,
+                            params: Arc::new(params),
+                            response_channel: tx,
+                        },
+                    ))
+                })?;
+                // Dismissing the form without responding cancels the elicitation.
+                let result = rx.recv().await.unwrap_or(types::ElicitResult {
+                    action: types::ElicitAction::Cancel,
+                    content: None,
+                    meta: None,
+                });
+                Ok(serde_json::to_value(result)?)
+            })
+        });
+
+        vec![
+            (types::requests::ListRoots::METHOD, roots_handler),
+            (types::requests::CreateMessage::METHOD, sampling_handler),
+            (types::requests::Elicit::METHOD, elicitation_handler),
+        ]
+    }
+
+    /// The project's directories, which context servers are allowed to operate on.
+    fn roots(&self, cx: &App) -> Vec<types::Root> {
+        let is_local = self
+            .project
+            .upgrade()
+            .is_some_and(|project| project.read(cx).is_local());
+        if !is_local {
+            return Vec::new();
+        }
+
+        self.worktree_store
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                if worktree.is_single_file() {
+                    return None;
+                }
+                Some(types::Root {
+                    uri: Url::from_file_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name_str().to_string()),
+                })
+            })
+            .collect()
+    }
+
+    fn roots_changed(&self, _cx: &mut Context<Self>) {
+        for server in self.running_servers() {
+            if let Some(client) = server.client() {
+                client
+                    .notify::<types::notifications::RootsListChanged>(())
+                    .log_err();
+            }
+        }
+    }
+
     fn remove_server(&mut self, id: &ContextServerId, cx: &mut Context<Self>) -> Result<()> {
         let state = self
             .servers
@@ -493,11 +662,15 @@ impl ContextServerStore {
         if let Some(factory) = self.context_server_factory.as_ref() {
             factory(id, configuration)
         } else {
-            Arc::new(ContextServer::stdio(
-                id,
-                configuration.command().clone(),
-                root_path,
-            ))
+            match configuration.as_ref() {
+                ContextServerConfiguration::Http { endpoint } => {
+                    Arc::new(ContextServer::http(id, endpoint.clone(), cx.http_client()))
+                }
+                ContextServerConfiguration::Custom { command }
+                | ContextServerConfiguration::Extension { command, .. } => {
+                    Arc::new(ContextServer::stdio(id, command.clone(), root_path))
+                }
+            }
         }
     }
 
@@ -1204,6 +1377,91 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_context_server_requests(cx: &mut TestAppContext) {
+        const SERVER_1_ID: &str = "mcp-1";
+
+        let (_fs, project) = setup_context_server_test(
+            cx,
+            json!({"code.rs": ""}),
+            vec![(SERVER_1_ID.into(), dummy_server_settings())],
+        )
+        .await;
+
+        let registry = cx.new(|_| ContextServerDescriptorRegistry::new());
+        let store = cx.new(|cx| {
+            ContextServerStore::test(
+                registry.clone(),
+                project.read(cx).worktree_store(),
+                project.downgrade(),
+                cx,
+            )
+        });
+
+        let transport = Arc::new(create_fake_transport(SERVER_1_ID, cx.executor()));
+        let server = Arc::new(ContextServer::new(
+            ContextServerId(SERVER_1_ID.into()),
+            transport.clone(),
+        ));
+        store.update(cx, |store, cx| store.start_server(server, cx));
+        cx.run_until_parked();
+
+        let response = transport.request::<types::requests::ListRoots>(()).await;
+        assert_eq!(
+            response["result"],
+            json!({
+                "roots": [{
+                    "uri": Url::from_file_path(path!("/test")).unwrap(),
+                    "name": "test",
+                }]
+            })
+        );
+
+        let _subscription = cx.update(|cx| {
+            cx.subscribe(&store, |_, event, _| {
+                if let Event::SamplingRequested(request) = event {
+                    assert_eq!(request.params.max_tokens, 100);
+                    request.clone().respond(Ok(types::CreateMessageResult {
+                        role: types::Role::Assistant,
+                        content: types::MessageContent::Text {
+                            text: "Hi!".into(),
+                            annotations: None,
+                        },
+                        model: "fake".into(),
+                        stop_reason: Some("endTurn".into()),
+                    }));
+                }
+            })
+        });
+        let response = transport
+            .request::<types::requests::CreateMessage>(types::CreateMessageRequest {
+                messages: vec![types::SamplingMessage {
+                    role: types::Role::User,
+                    content: types::MessageContent::Text {
+                        text: "Hello".into(),
+                        annotations: None,
+                    },
+                }],
+                model_preferences: None,
+                system_prompt: None,
+                include_context: None,
+                temperature: None,
+                max_tokens: 100,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await;
+        assert_eq!(
+            response["result"],
+            json!({
+                "role": "assistant",
+                "content": {"type": "text", "text": "Hi!"},
+                "model": "fake",
+                "stopReason": "endTurn",
+            })
+        );
+    }
+
     fn set_context_server_configuration(
         context_servers: Vec<(Arc<str>, settings::ContextServerSettingsContent)>,
         cx: &mut TestAppContext,
@@ -1281,6 +1539,7 @@ mod tests {
                         ix += 1;
                         *received_event_count.borrow_mut() += 1;
                     }
+                    _ => {}
                 }
             });
             ServerEvents {
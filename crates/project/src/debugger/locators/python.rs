@@ -119,6 +119,8 @@ mod test {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: vec![],
+            depends_order: Default::default(),
         };
 
         let expected_scenario = DebugScenario {
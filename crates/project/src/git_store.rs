@@ -23,11 +23,14 @@ use futures::{
 use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, Oid,
     blame::Blame,
+    log::{GitLog, LogEntry, LogOptions},
     parse_git_remote_url,
+    rebase::{RebaseProgress, RebaseTodoAction, RebaseTodoDelegate, RebaseTodoEntry},
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus, Worktree as GitWorktree,
+        GitRepository, GitRepositoryCheckpoint, InProgressOperation, IndexStage, OperationControl,
+        PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode, Tag, UpstreamTrackingStatus,
+        Worktree as GitWorktree,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -244,6 +247,14 @@ pub struct MergeDetails {
     pub heads: Vec<Option<SharedString>>,
 }
 
+/// The contents of a conflicted file on each side of a merge, as recorded in the index.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RepositorySnapshot {
     pub id: RepositoryId,
@@ -257,6 +268,8 @@ pub struct RepositorySnapshot {
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    pub in_progress_operation: Option<InProgressOperation>,
+    pub rebase_progress: Option<RebaseProgress>,
 }
 
 type JobId = u64;
@@ -280,6 +293,8 @@ pub struct Repository {
     job_id: JobId,
     askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
     latest_askpass_id: u64,
+    rebase_todo_editors: Arc<Mutex<HashMap<u64, RebaseTodoDelegate>>>,
+    latest_rebase_todo_editor_id: u64,
 }
 
 impl std::ops::Deref for Repository {
@@ -415,6 +430,12 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_change_branch);
         client.add_entity_request_handler(Self::handle_create_branch);
         client.add_entity_request_handler(Self::handle_rename_branch);
+        client.add_entity_request_handler(Self::handle_delete_branch);
+        client.add_entity_request_handler(Self::handle_delete_remote_branch);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_push_tag);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
         client.add_entity_request_handler(Self::handle_pull);
@@ -428,6 +449,14 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_merge);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_edit_rebase_todo);
+        client.add_entity_request_handler(Self::handle_control_operation);
+        client.add_entity_request_handler(Self::handle_load_conflict_stages);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
@@ -2074,6 +2103,149 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_delete_branch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteBranch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let branch_name = envelope.payload.branch_name;
+        let force = envelope.payload.force;
+        git::validate_ref_name("branch", &branch_name)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_branch(branch_name, force)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_remote_branch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteRemoteBranch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        git::validate_ref_name("branch", &envelope.payload.branch_name)?;
+        git::validate_ref_name("remote", &envelope.payload.remote_name)?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+        let branch_name = envelope.payload.branch_name.into();
+        let remote_name = envelope.payload.remote_name.into();
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.delete_remote_branch(branch_name, remote_name, askpass, cx)
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
+
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let proto::GitCreateTag {
+            name,
+            target,
+            message,
+            ..
+        } = envelope.payload;
+        git::validate_ref_name("tag", &name)?;
+        if let Some(target) = &target {
+            git::validate_revision(target)?;
+        }
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(name, target, message)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let name = envelope.payload.name;
+        git::validate_ref_name("tag", &name)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(name)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_push_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        git::validate_ref_name("tag", &envelope.payload.tag_name)?;
+        git::validate_ref_name("remote", &envelope.payload.remote_name)?;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+        let tag_name = envelope.payload.tag_name.into();
+        let remote_name = envelope.payload.remote_name.into();
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.push_tag(tag_name, remote_name, askpass, cx)
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
     async fn handle_show(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitShow>,
@@ -2096,6 +2268,70 @@ impl GitStore {
         })
     }
 
+    async fn handle_load_conflict_stages(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadConflictStages>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadConflictStagesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let stages = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_conflict_stages(path)
+            })?
+            .await??;
+        Ok(proto::LoadConflictStagesResponse {
+            base: stages.base,
+            ours: stages.ours,
+            theirs: stages.theirs,
+        })
+    }
+
+    async fn handle_log(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = LogOptions {
+            revision: envelope.payload.revision,
+            path: envelope
+                .payload
+                .path
+                .map(|path| RepoPath::from_proto(&path))
+                .transpose()?,
+            author: envelope.payload.author,
+            skip: envelope.payload.skip as usize,
+            limit: envelope.payload.limit as usize,
+        };
+
+        let log = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(options)
+            })?
+            .await??;
+        Ok(proto::GitLogResponse {
+            entries: log
+                .entries
+                .into_iter()
+                .map(|entry| proto::GitLogEntry {
+                    sha: entry.sha.into(),
+                    parents: entry.parents.into_iter().map(Into::into).collect(),
+                    subject: entry.subject.into(),
+                    author_name: entry.author_name.into(),
+                    author_email: entry.author_email.into(),
+                    commit_timestamp: entry.commit_timestamp,
+                    refs: entry.refs.into_iter().map(Into::into).collect(),
+                    path: entry.path.map(|path| path.to_proto()),
+                })
+                .collect(),
+            has_more: log.has_more,
+        })
+    }
+
     async fn handle_load_commit_diff(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::LoadCommitDiff>,
@@ -2143,6 +2379,151 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_merge(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitMerge>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let branch = envelope.payload.branch;
+        git::validate_revision(&branch)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.merge(branch.into())
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_cherry_pick(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commits = envelope.payload.commits;
+        for commit in &commits {
+            git::validate_revision(commit)?;
+        }
+        let commits = commits.into_iter().map(Into::into).collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(commits)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_revert(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRevert>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let commits = envelope.payload.commits;
+        for commit in &commits {
+            git::validate_revision(commit)?;
+        }
+        let commits = commits.into_iter().map(Into::into).collect();
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(commits)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_rebase(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let upstream = envelope.payload.upstream;
+        git::validate_revision(&upstream)?;
+        let todo_editor = envelope.payload.todo_editor_id.map(|todo_editor_id| {
+            make_remote_todo_editor(
+                this,
+                envelope.payload.project_id,
+                repository_id,
+                todo_editor_id,
+                &mut cx,
+            )
+        });
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase(upstream.into(), todo_editor)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_edit_rebase_todo(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitEditRebaseTodo>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitEditRebaseTodoResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let todo_editors = cx.update(|cx| repository.read(cx).rebase_todo_editors.clone())?;
+        let Some(todo_editor) = todo_editors.lock().remove(&envelope.payload.todo_editor_id) else {
+            debug_panic!("no rebase todo editor found");
+            anyhow::bail!("no rebase todo editor found");
+        };
+
+        let entries = envelope
+            .payload
+            .entries
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+        let edited_entries = todo_editor.edit_todo(entries).await;
+
+        todo_editors
+            .lock()
+            .insert(envelope.payload.todo_editor_id, todo_editor);
+
+        Ok(proto::GitEditRebaseTodoResponse {
+            accepted: edited_entries.is_some(),
+            entries: edited_entries
+                .iter()
+                .flatten()
+                .map(rebase_todo_entry_to_proto)
+                .collect(),
+        })
+    }
+
+    async fn handle_control_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitControlOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let operation = proto_to_operation(envelope.payload.operation());
+        let control = match envelope.payload.control() {
+            proto::git_control_operation::Control::Continue => OperationControl::Continue,
+            proto::git_control_operation::Control::Abort => OperationControl::Abort,
+            proto::git_control_operation::Control::Skip => OperationControl::Skip,
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.control_operation(operation, control)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_checkout_files(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitCheckoutFiles>,
@@ -2939,6 +3320,42 @@ fn make_remote_delegate(
     })
 }
 
+fn make_remote_todo_editor(
+    this: Entity<GitStore>,
+    project_id: u64,
+    repository_id: RepositoryId,
+    todo_editor_id: u64,
+    cx: &mut AsyncApp,
+) -> RebaseTodoDelegate {
+    RebaseTodoDelegate::new(cx, move |entries, tx, cx| {
+        this.update(cx, |this, cx| {
+            let Some((client, _)) = this.downstream_client() else {
+                return;
+            };
+            let response = client.request(proto::GitEditRebaseTodo {
+                project_id,
+                repository_id: repository_id.to_proto(),
+                todo_editor_id,
+                entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+            });
+            cx.spawn(async move |_, _| {
+                let response = response.await?;
+                let entries = response.accepted.then(|| {
+                    response
+                        .entries
+                        .iter()
+                        .map(proto_to_rebase_todo_entry)
+                        .collect()
+                });
+                tx.send(entries).ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        })
+        .log_err();
+    })
+}
+
 impl RepositoryId {
     pub fn to_proto(self) -> u64 {
         self.0
@@ -2962,6 +3379,8 @@ impl RepositorySnapshot {
             remote_origin_url: None,
             remote_upstream_url: None,
             stash_entries: Default::default(),
+            in_progress_operation: None,
+            rebase_progress: None,
             path_style,
         }
     }
@@ -2995,6 +3414,10 @@ impl RepositorySnapshot {
                 .iter()
                 .map(stash_to_proto)
                 .collect(),
+            in_progress_operation: self
+                .in_progress_operation
+                .map(|operation| operation_to_proto(operation).into()),
+            rebase_progress: self.rebase_progress.as_ref().map(rebase_progress_to_proto),
         }
     }
 
@@ -3064,6 +3487,10 @@ impl RepositorySnapshot {
                 .iter()
                 .map(stash_to_proto)
                 .collect(),
+            in_progress_operation: self
+                .in_progress_operation
+                .map(|operation| operation_to_proto(operation).into()),
+            rebase_progress: self.rebase_progress.as_ref().map(rebase_progress_to_proto),
         }
     }
 
@@ -3148,15 +3575,85 @@ pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
     })
 }
 
-impl MergeDetails {
-    async fn load(
-        backend: &Arc<dyn GitRepository>,
-        status: &SumTree<StatusEntry>,
-        prev_snapshot: &RepositorySnapshot,
-    ) -> Result<(MergeDetails, bool)> {
-        log::debug!("load merge details");
-        let message = backend.merge_message().await;
-        let heads = backend
+fn operation_to_proto(operation: InProgressOperation) -> proto::GitOperation {
+    match operation {
+        InProgressOperation::Merge => proto::GitOperation::Merge,
+        InProgressOperation::Rebase => proto::GitOperation::Rebase,
+        InProgressOperation::CherryPick => proto::GitOperation::CherryPick,
+        InProgressOperation::Revert => proto::GitOperation::Revert,
+    }
+}
+
+fn proto_to_operation(operation: proto::GitOperation) -> InProgressOperation {
+    match operation {
+        proto::GitOperation::Merge => InProgressOperation::Merge,
+        proto::GitOperation::Rebase => InProgressOperation::Rebase,
+        proto::GitOperation::CherryPick => InProgressOperation::CherryPick,
+        proto::GitOperation::Revert => InProgressOperation::Revert,
+    }
+}
+
+fn rebase_progress_to_proto(progress: &RebaseProgress) -> proto::GitRebaseProgress {
+    proto::GitRebaseProgress {
+        head_name: progress.head_name.as_ref().map(ToString::to_string),
+        step: progress.step as u64,
+        total: progress.total as u64,
+    }
+}
+
+fn proto_to_rebase_progress(progress: &proto::GitRebaseProgress) -> RebaseProgress {
+    RebaseProgress {
+        head_name: progress.head_name.clone().map(SharedString::from),
+        step: progress.step as usize,
+        total: progress.total as usize,
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::GitRebaseTodoEntry {
+    use proto::git_rebase_todo_entry::Action;
+
+    let action = match entry.action {
+        RebaseTodoAction::Pick => Action::Pick,
+        RebaseTodoAction::Reword => Action::Reword,
+        RebaseTodoAction::Squash => Action::Squash,
+        RebaseTodoAction::Fixup => Action::Fixup,
+        RebaseTodoAction::Drop => Action::Drop,
+    };
+    proto::GitRebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_rebase_todo_entry(entry: &proto::GitRebaseTodoEntry) -> RebaseTodoEntry {
+    use proto::git_rebase_todo_entry::Action;
+
+    let action = match entry.action() {
+        Action::Pick => RebaseTodoAction::Pick,
+        Action::Reword => RebaseTodoAction::Reword,
+        Action::Squash => RebaseTodoAction::Squash,
+        Action::Fixup => RebaseTodoAction::Fixup,
+        Action::Drop => RebaseTodoAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: entry.sha.clone().into(),
+        subject: entry.subject.clone().into(),
+        message: entry.message.clone().map(SharedString::from),
+    }
+}
+
+impl MergeDetails {
+    async fn load(
+        backend: &Arc<dyn GitRepository>,
+        status: &SumTree<StatusEntry>,
+        prev_snapshot: &RepositorySnapshot,
+    ) -> Result<(MergeDetails, bool)> {
+        log::debug!("load merge details");
+        let message = backend.merge_message().await;
+        let heads = backend
             .revparse_batch(vec![
                 "MERGE_HEAD".into(),
                 "CHERRY_PICK_HEAD".into(),
@@ -3237,6 +3734,8 @@ impl Repository {
             askpass_delegates: Default::default(),
             paths_needing_status_update: Default::default(),
             latest_askpass_id: 0,
+            rebase_todo_editors: Default::default(),
+            latest_rebase_todo_editor_id: 0,
             job_sender: Repository::spawn_local_git_worker(
                 work_directory_abs_path,
                 dot_git_abs_path,
@@ -3270,6 +3769,8 @@ impl Repository {
             job_sender: Self::spawn_remote_git_worker(project_id, client, cx),
             askpass_delegates: Default::default(),
             latest_askpass_id: 0,
+            rebase_todo_editors: Default::default(),
+            latest_rebase_todo_editor_id: 0,
             active_jobs: Default::default(),
             job_id: 0,
         }
@@ -3705,6 +4206,178 @@ impl Repository {
         })
     }
 
+    pub fn merge(&mut self, branch: SharedString) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git merge {branch}").into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.merge(branch.to_string(), environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitMerge {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch: branch.to_string(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn cherry_pick(&mut self, commits: Vec<SharedString>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git cherry-pick {}", commits.join(" ")).into()),
+            move |git_repo, _| async move {
+                let commits = commits.iter().map(ToString::to_string).collect();
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.cherry_pick(commits, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCherryPick {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commits,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn revert(&mut self, commits: Vec<SharedString>) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git revert {}", commits.join(" ")).into()),
+            move |git_repo, _| async move {
+                let commits = commits.iter().map(ToString::to_string).collect();
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.revert(commits, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitRevert {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commits,
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Rebases HEAD onto `upstream`, interactively when a todo editor is given.
+    pub fn rebase(
+        &mut self,
+        upstream: SharedString,
+        todo_editor: Option<RebaseTodoDelegate>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let todo_editors = self.rebase_todo_editors.clone();
+        let todo_editor_id = util::post_inc(&mut self.latest_rebase_todo_editor_id);
+        let id = self.id;
+        let status = if todo_editor.is_some() {
+            format!("git rebase --interactive {upstream}")
+        } else {
+            format!("git rebase {upstream}")
+        };
+
+        self.send_job(Some(status.into()), move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    backend
+                        .rebase(upstream.to_string(), todo_editor, environment)
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let interactive = todo_editor.is_some();
+                    if let Some(todo_editor) = todo_editor {
+                        todo_editors.lock().insert(todo_editor_id, todo_editor);
+                    }
+                    let _defer = util::defer(|| {
+                        todo_editors.lock().remove(&todo_editor_id);
+                    });
+                    client
+                        .request(proto::GitRebase {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            upstream: upstream.to_string(),
+                            todo_editor_id: interactive.then_some(todo_editor_id),
+                        })
+                        .await
+                        .context("sending rebase request")?;
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Continues, aborts or skips the current commit of the operation in progress.
+    pub fn control_operation(
+        &mut self,
+        operation: InProgressOperation,
+        control: OperationControl,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git {} {}", operation.command(), control.flag()).into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .control_operation(operation, control, environment)
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        use proto::git_control_operation::Control;
+
+                        let control = match control {
+                            OperationControl::Continue => Control::Continue,
+                            OperationControl::Abort => Control::Abort,
+                            OperationControl::Skip => Control::Skip,
+                        };
+                        client
+                            .request(proto::GitControlOperation {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                operation: operation_to_proto(operation).into(),
+                                control: control.into(),
+                            })
+                            .await?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
     pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
         let id = self.id;
         self.send_job(None, move |git_repo, _cx| async move {
@@ -3731,6 +4404,86 @@ impl Repository {
         })
     }
 
+    /// Loads the base, ours and theirs versions of a conflicted file from the index.
+    pub fn load_conflict_stages(
+        &mut self,
+        path: RepoPath,
+    ) -> oneshot::Receiver<Result<ConflictStages>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => {
+                    let (base, ours, theirs) = futures::join!(
+                        backend.load_index_stage_text(path.clone(), IndexStage::Base),
+                        backend.load_index_stage_text(path.clone(), IndexStage::Ours),
+                        backend.load_index_stage_text(path, IndexStage::Theirs),
+                    );
+                    Ok(ConflictStages { base, ours, theirs })
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::LoadConflictStages {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                        })
+                        .await?;
+                    Ok(ConflictStages {
+                        base: response.base,
+                        ours: response.ours,
+                        theirs: response.theirs,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn log(&mut self, options: LogOptions) -> oneshot::Receiver<Result<GitLog>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(options).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            revision: options.revision,
+                            path: options.path.map(|path| path.to_proto()),
+                            author: options.author,
+                            skip: options.skip as u64,
+                            limit: options.limit as u64,
+                        })
+                        .await?;
+
+                    let entries = response
+                        .entries
+                        .into_iter()
+                        .map(|entry| {
+                            Ok(LogEntry {
+                                sha: entry.sha.into(),
+                                parents: entry.parents.into_iter().map(Into::into).collect(),
+                                subject: entry.subject.into(),
+                                author_name: entry.author_name.into(),
+                                author_email: entry.author_email.into(),
+                                commit_timestamp: entry.commit_timestamp,
+                                refs: entry.refs.into_iter().map(Into::into).collect(),
+                                path: entry
+                                    .path
+                                    .map(|path| RepoPath::from_proto(&path))
+                                    .transpose()?,
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok(GitLog {
+                        entries,
+                        has_more: response.has_more,
+                    })
+                }
+            }
+        })
+    }
+
     pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
         let id = self.id;
         self.send_job(None, move |git_repo, cx| async move {
@@ -4718,6 +5471,230 @@ impl Repository {
         )
     }
 
+    pub fn delete_branch(
+        &mut self,
+        branch_name: String,
+        force: bool,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let flag = if force { "-D" } else { "-d" };
+        self.send_job(
+            Some(format!("git branch {flag} {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.delete_branch(branch_name, force).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitDeleteBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                                force,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_remote_branch(
+        &mut self,
+        branch: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some(format!("git push {remote} --delete {branch}").into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .delete_remote_branch(
+                                branch.to_string(),
+                                remote.to_string(),
+                                askpass,
+                                environment,
+                                cx,
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+
+                        let response = client
+                            .request(proto::GitDeleteRemoteBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                remote_name: remote.to_string(),
+                                branch_name: branch.to_string(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending delete remote branch request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.tags().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
+                }
+            }
+        })
+    }
+
+    /// Creates a tag on `target`, or on HEAD. The tag is annotated when given a message.
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.create_tag(name, target, message, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                target,
+                                message,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag --delete {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => backend.delete_tag(name).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn push_tag(
+        &mut self,
+        tag: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some(format!("git push {remote} {tag}").into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .push_tag(
+                                tag.to_string(),
+                                remote.to_string(),
+                                askpass,
+                                environment,
+                                cx,
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+
+                        let response = client
+                            .request(proto::GitPushTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                remote_name: remote.to_string(),
+                                tag_name: tag.to_string(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending push tag request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
     pub fn check_for_pushed_commits(&mut self) -> oneshot::Receiver<Result<Vec<SharedString>>> {
         let id = self.id;
         self.send_job(None, move |repo, _cx| async move {
@@ -4784,6 +5761,21 @@ impl Repository {
         self.snapshot.branch = new_branch;
         self.snapshot.head_commit = new_head_commit;
 
+        let new_in_progress_operation = update
+            .in_progress_operation
+            .map(|_| proto_to_operation(update.in_progress_operation()));
+        let new_rebase_progress = update
+            .rebase_progress
+            .as_ref()
+            .map(proto_to_rebase_progress);
+        if self.snapshot.in_progress_operation != new_in_progress_operation
+            || self.snapshot.rebase_progress != new_rebase_progress
+        {
+            cx.emit(RepositoryEvent::MergeHeadsChanged)
+        }
+        self.snapshot.in_progress_operation = new_in_progress_operation;
+        self.snapshot.rebase_progress = new_rebase_progress;
+
         self.snapshot.merge.conflicted_paths = conflicted_paths;
         self.snapshot.merge.message = update.merge_message.map(SharedString::from);
         let new_stash_entries = GitStash {
@@ -5338,6 +6330,24 @@ fn deserialize_blame_buffer_response(
     })
 }
 
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+        unix_timestamp: tag.unix_timestamp,
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().map(Into::into),
+        unix_timestamp: proto.unix_timestamp,
+    }
+}
+
 fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     proto::Branch {
         is_head: branch.is_head,
@@ -5461,7 +6471,22 @@ async fn compute_snapshot(
         MergeDetails::load(&backend, &statuses_by_path, &prev_snapshot).await?;
     log::debug!("new merge details (changed={merge_heads_changed:?}): {merge_details:?}");
 
-    if merge_heads_changed {
+    let rebase_progress = backend.rebase_progress().await;
+    let in_progress_operation = if rebase_progress.is_some() {
+        Some(InProgressOperation::Rebase)
+    } else {
+        match merge_details.heads.as_slice() {
+            [Some(_), ..] => Some(InProgressOperation::Merge),
+            [_, Some(_), ..] => Some(InProgressOperation::CherryPick),
+            [_, _, _, Some(_), ..] => Some(InProgressOperation::Revert),
+            _ => None,
+        }
+    };
+
+    if merge_heads_changed
+        || in_progress_operation != prev_snapshot.in_progress_operation
+        || rebase_progress != prev_snapshot.rebase_progress
+    {
         events.push(RepositoryEvent::MergeHeadsChanged);
     }
 
@@ -5495,6 +6520,8 @@ async fn compute_snapshot(
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        in_progress_operation,
+        rebase_progress,
     };
 
     Ok((snapshot, events))
@@ -1,11 +1,12 @@
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, CoreCompletionResponse, DocumentColor,
-    DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
-    InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
-    LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
+    CallHierarchyCall, CallHierarchyItem, CodeAction, CompletionSource, CoreCompletion,
+    CoreCompletionResponse, DocumentColor, DocumentHighlight, DocumentLink, DocumentSymbol,
+    HierarchyItem, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
+    InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink,
+    LspAction, LspFoldingRange, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
+    ProjectTransaction, PulledDiagnostics, ResolveState, TypeHierarchyItem,
     lsp_store::{LocalLspStore, LspStore},
 };
 use anyhow::{Context as _, Result};
@@ -17,7 +18,7 @@ use futures::future;
 use gpui::{App, AsyncApp, Entity, Task};
 use language::{
     Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, CharScopeContext,
-    OffsetRangeExt, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
+    OffsetRangeExt, Point, PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{InlayHintKind, LanguageSettings, language_settings},
     point_from_lsp, point_to_lsp,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
@@ -204,6 +205,36 @@ pub(crate) struct GetDocumentHighlights {
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentSymbols;
 
+#[derive(Clone, Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: CallHierarchyItem,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: CallHierarchyItem,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GetSupertypes {
+    pub item: TypeHierarchyItem,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct GetSubtypes {
+    pub item: TypeHierarchyItem,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -245,6 +276,91 @@ pub(crate) struct GetCodeLens;
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentColor;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetSemanticTokens;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetFoldingRanges;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GetDocumentLinks;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetSemanticTokensDelta {
+    pub previous: RawSemanticTokens,
+}
+
+/// Semantic tokens as reported by the language server, with each token flattened into
+/// the five integers of the LSP wire format, so that delta edits can be applied in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct RawSemanticTokens {
+    pub result_id: Option<String>,
+    pub data: Vec<u32>,
+}
+
+impl RawSemanticTokens {
+    fn from_lsp(tokens: lsp::SemanticTokens) -> Self {
+        Self {
+            result_id: tokens.result_id,
+            data: flatten_semantic_tokens(tokens.data),
+        }
+    }
+
+    fn apply_edits(&mut self, mut edits: Vec<lsp::SemanticTokensEdit>) -> Result<()> {
+        // Edits refer to offsets in the previous data, so apply them back to front.
+        edits.sort_by_key(|edit| Reverse(edit.start));
+        for edit in edits {
+            let start = edit.start as usize;
+            let end = start + edit.delete_count as usize;
+            anyhow::ensure!(
+                end <= self.data.len(),
+                "semantic tokens edit {start}..{end} is out of bounds for {} items",
+                self.data.len()
+            );
+            self.data.splice(
+                start..end,
+                flatten_semantic_tokens(edit.data.unwrap_or_default()),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn flatten_semantic_tokens(tokens: Vec<lsp::SemanticToken>) -> Vec<u32> {
+    tokens
+        .into_iter()
+        .flat_map(|token| {
+            [
+                token.delta_line,
+                token.delta_start,
+                token.length,
+                token.token_type,
+                token.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+pub(crate) fn semantic_tokens_options(
+    capabilities: &ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+/// Returns whether the server can provide semantic tokens for a whole document,
+/// and whether it can send them as a delta against the previous result.
+pub(crate) fn semantic_tokens_full_support(capabilities: &ServerCapabilities) -> Option<bool> {
+    match semantic_tokens_options(capabilities)?.full.as_ref()? {
+        lsp::SemanticTokensFullOptions::Bool(supported) => supported.then_some(false),
+        lsp::SemanticTokensFullOptions::Delta { delta } => Some(delta.unwrap_or(false)),
+    }
+}
+
 impl GetCodeLens {
     pub(crate) fn can_resolve_lens(capabilities: &ServerCapabilities) -> bool {
         capabilities
@@ -1801,21 +1917,321 @@ impl LspCommand for GetDocumentSymbols {
     }
 }
 
+fn call_hierarchy_supported(capabilities: &AdapterServerCapabilities) -> bool {
+    capabilities
+        .server_capabilities
+        .call_hierarchy_provider
+        .is_some_and(|capability| match capability {
+            lsp::CallHierarchyServerCapability::Simple(supported) => supported,
+            lsp::CallHierarchyServerCapability::Options(_) => true,
+        })
+}
+
+fn type_hierarchy_supported(capabilities: &AdapterServerCapabilities) -> bool {
+    capabilities.type_hierarchy_provider
+}
+
+fn anchor_range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+/// The fields shared by the items of call and type hierarchies.
+pub(crate) trait LspHierarchyItem:
+    Clone + serde::Serialize + serde::de::DeserializeOwned
+{
+    fn name(&self) -> &str;
+    fn kind(&self) -> lsp::SymbolKind;
+    fn detail(&self) -> Option<&str>;
+    fn uri(&self) -> &lsp::Uri;
+    fn range(&self) -> lsp::Range;
+    fn selection_range(&self) -> lsp::Range;
+}
+
+impl LspHierarchyItem for lsp::CallHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Uri {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+impl LspHierarchyItem for lsp::TypeHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Uri {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+async fn hierarchy_item_from_lsp<T: LspHierarchyItem>(
+    lsp_item: T,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem<T>> {
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.open_local_buffer_via_lsp(lsp_item.uri().clone(), server_id, cx)
+        })?
+        .await?;
+    buffer.clone().read_with(cx, |snapshot, _| HierarchyItem {
+        name: lsp_item.name().to_string(),
+        kind: lsp_item.kind(),
+        detail: lsp_item.detail().map(ToString::to_string),
+        location: Location {
+            buffer,
+            range: anchor_range_from_lsp(snapshot, lsp_item.range()),
+        },
+        selection_range: anchor_range_from_lsp(snapshot, lsp_item.selection_range()),
+        language_server_id: server_id,
+        lsp_item,
+    })
+}
+
+async fn hierarchy_items_from_lsp<T: LspHierarchyItem>(
+    lsp_items: Option<Vec<T>>,
+    lsp_store: &Entity<LspStore>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem<T>>> {
+    let mut items = Vec::new();
+    for lsp_item in lsp_items.unwrap_or_default() {
+        items.push(hierarchy_item_from_lsp(lsp_item, lsp_store, server_id, cx).await?);
+    }
+    Ok(items)
+}
+
+fn hierarchy_item_to_proto<T: LspHierarchyItem>(
+    item: &HierarchyItem<T>,
+    buffer_id: BufferId,
+) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        name: item.name.clone(),
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail.clone(),
+        location: Some(proto::Location {
+            buffer_id: buffer_id.into(),
+            start: Some(serialize_anchor(&item.location.range.start)),
+            end: Some(serialize_anchor(&item.location.range.end)),
+        }),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        language_server_id: item.language_server_id.to_proto(),
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap_or_default(),
+    }
+}
+
+/// Serializes an item of a response, sharing its buffer with the peer that requested it.
+fn hierarchy_item_to_peer<T: LspHierarchyItem>(
+    item: &HierarchyItem<T>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::HierarchyItem {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&item.location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    hierarchy_item_to_proto(item, item.location.buffer.read(cx).remote_id())
+}
+
+async fn hierarchy_item_from_proto<T: LspHierarchyItem>(
+    item: proto::HierarchyItem,
+    buffer: Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem<T>> {
+    let location = item.location.context("missing item location")?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .context("missing item start")?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .context("missing item end")?;
+    let selection_start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .context("missing item selection start")?;
+    let selection_end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .context("missing item selection end")?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_anchors([start, end, selection_start, selection_end])
+        })?
+        .await?;
+    Ok(HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location: Location {
+            buffer,
+            range: start..end,
+        },
+        selection_range: selection_start..selection_end,
+        language_server_id: LanguageServerId::from_proto(item.language_server_id),
+        lsp_item: serde_json::from_slice(&item.lsp_item).context("deserializing hierarchy item")?,
+    })
+}
+
+async fn remote_hierarchy_item_from_proto<T: LspHierarchyItem>(
+    item: proto::HierarchyItem,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<HierarchyItem<T>> {
+    let buffer_id = BufferId::new(
+        item.location
+            .as_ref()
+            .context("missing item location")?
+            .buffer_id,
+    )?;
+    let buffer = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    hierarchy_item_from_proto(item, buffer, cx).await
+}
+
+async fn remote_hierarchy_items_from_proto<T: LspHierarchyItem>(
+    items: Vec<proto::HierarchyItem>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<HierarchyItem<T>>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        result.push(remote_hierarchy_item_from_proto(item, lsp_store, cx).await?);
+    }
+    Ok(result)
+}
+
+fn call_hierarchy_calls_to_proto(
+    calls: Vec<CallHierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::CallHierarchyCall> {
+    calls
+        .into_iter()
+        .map(|call| {
+            let item = hierarchy_item_to_peer(&call.item, lsp_store, peer_id, cx);
+            let call_sites = call
+                .call_sites
+                .into_iter()
+                .map(|call_site| {
+                    lsp_store
+                        .buffer_store()
+                        .update(cx, |buffer_store, cx| {
+                            buffer_store.create_buffer_for_peer(&call_site.buffer, peer_id, cx)
+                        })
+                        .detach_and_log_err(cx);
+                    proto::Location {
+                        buffer_id: call_site.buffer.read(cx).remote_id().into(),
+                        start: Some(serialize_anchor(&call_site.range.start)),
+                        end: Some(serialize_anchor(&call_site.range.end)),
+                    }
+                })
+                .collect();
+            proto::CallHierarchyCall {
+                item: Some(item),
+                call_sites,
+            }
+        })
+        .collect()
+}
+
+async fn call_hierarchy_calls_from_proto(
+    calls: Vec<proto::CallHierarchyCall>,
+    lsp_store: &Entity<LspStore>,
+    cx: &mut AsyncApp,
+) -> Result<Vec<CallHierarchyCall>> {
+    let mut result = Vec::with_capacity(calls.len());
+    for call in calls {
+        let item = call.item.context("missing call item")?;
+        let item = remote_hierarchy_item_from_proto(item, lsp_store, cx).await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            let buffer_id = BufferId::new(call_site.buffer_id)?;
+            let buffer = lsp_store
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.wait_for_remote_buffer(buffer_id, cx)
+                })?
+                .await?;
+            let start = call_site
+                .start
+                .and_then(deserialize_anchor)
+                .context("missing call site start")?;
+            let end = call_site
+                .end
+                .and_then(deserialize_anchor)
+                .context("missing call site end")?;
+            buffer
+                .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+                .await?;
+            call_sites.push(Location {
+                buffer,
+                range: start..end,
+            });
+        }
+        result.push(CallHierarchyCall { item, call_sites });
+    }
+    Ok(result)
+}
+
 #[async_trait(?Send)]
-impl LspCommand for GetSignatureHelp {
-    type Response = Option<SignatureHelp>;
-    type LspRequest = lsp::SignatureHelpRequest;
-    type ProtoRequest = proto::GetSignatureHelp;
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<CallHierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
 
     fn display_name(&self) -> &str {
-        "Get signature help"
+        "Prepare call hierarchy"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        capabilities
-            .server_capabilities
-            .signature_help_provider
-            .is_some()
+        call_hierarchy_supported(&capabilities)
     }
 
     fn to_lsp(
@@ -1823,375 +2239,212 @@ impl LspCommand for GetSignatureHelp {
         path: &Path,
         _: &Buffer,
         _: &Arc<LanguageServer>,
-        _cx: &App,
-    ) -> Result<lsp::SignatureHelpParams> {
-        Ok(lsp::SignatureHelpParams {
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
             text_document_position_params: make_lsp_text_document_position(path, self.position)?,
-            context: None,
             work_done_progress_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<lsp::SignatureHelp>,
+        lsp_items: Option<Vec<lsp::CallHierarchyItem>>,
         lsp_store: Entity<LspStore>,
         _: Entity<Buffer>,
-        id: LanguageServerId,
-        cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        let Some(message) = message else {
-            return Ok(None);
-        };
-        cx.update(|cx| {
-            SignatureHelp::new(
-                message,
-                Some(lsp_store.read(cx).languages.clone()),
-                Some(id),
-                cx,
-            )
-        })
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
-        let offset = buffer.point_utf16_to_offset(self.position);
-        proto::GetSignatureHelp {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
             project_id,
-            buffer_id: buffer.remote_id().to_proto(),
-            position: Some(serialize_anchor(&buffer.anchor_after(offset))),
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        payload: Self::ProtoRequest,
+        message: proto::PrepareCallHierarchy,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
         buffer
             .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&payload.version))
+                buffer.wait_for_version(deserialize_version(&message.version))
             })?
-            .await
-            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
-        let buffer_snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+            .await?;
         Ok(Self {
-            position: payload
-                .position
-                .and_then(deserialize_anchor)
-                .context("invalid position")?
-                .to_point_utf16(&buffer_snapshot),
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
         })
     }
 
     fn response_to_proto(
-        response: Self::Response,
-        _: &mut LspStore,
-        _: PeerId,
-        _: &Global,
-        _: &mut App,
-    ) -> proto::GetSignatureHelpResponse {
-        proto::GetSignatureHelpResponse {
-            signature_help: response
-                .map(|signature_help| lsp_to_proto_signature(signature_help.original_data)),
+        response: Vec<CallHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: response
+                .iter()
+                .map(|item| hierarchy_item_to_peer(item, lsp_store, peer_id, cx))
+                .collect(),
         }
     }
 
     async fn response_from_proto(
         self,
-        response: proto::GetSignatureHelpResponse,
+        message: proto::PrepareCallHierarchyResponse,
         lsp_store: Entity<LspStore>,
         _: Entity<Buffer>,
-        cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        cx.update(|cx| {
-            response
-                .signature_help
-                .map(proto_to_lsp_signature)
-                .and_then(|signature| {
-                    SignatureHelp::new(
-                        signature,
-                        Some(lsp_store.read(cx).languages.clone()),
-                        None,
-                        cx,
-                    )
-                })
-        })
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        remote_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
     }
 
-    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
 #[async_trait(?Send)]
-impl LspCommand for GetHover {
-    type Response = Option<Hover>;
-    type LspRequest = lsp::request::HoverRequest;
-    type ProtoRequest = proto::GetHover;
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
 
     fn display_name(&self) -> &str {
-        "Get hover"
+        "Get incoming calls"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        match capabilities.server_capabilities.hover_provider {
-            Some(lsp::HoverProviderCapability::Simple(enabled)) => enabled,
-            Some(lsp::HoverProviderCapability::Options(_)) => true,
-            None => false,
-        }
+        call_hierarchy_supported(&capabilities)
     }
 
     fn to_lsp(
         &self,
-        path: &Path,
+        _: &Path,
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::HoverParams> {
-        Ok(lsp::HoverParams {
-            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.lsp_item.clone(),
             work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<lsp::Hover>,
-        _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        _: LanguageServerId,
-        cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        let Some(hover) = message else {
-            return Ok(None);
-        };
-
-        let (language, range) = buffer.read_with(&cx, |buffer, _| {
-            (
-                buffer.language().cloned(),
-                hover.range.map(|range| {
-                    let token_start =
-                        buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
-                    let token_end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
-                    buffer.anchor_after(token_start)..buffer.anchor_before(token_end)
-                }),
-            )
-        })?;
-
-        fn hover_blocks_from_marked_string(marked_string: lsp::MarkedString) -> Option<HoverBlock> {
-            let block = match marked_string {
-                lsp::MarkedString::String(content) => HoverBlock {
-                    text: content,
-                    kind: HoverBlockKind::Markdown,
-                },
-                lsp::MarkedString::LanguageString(lsp::LanguageString { language, value }) => {
-                    HoverBlock {
-                        text: value,
-                        kind: HoverBlockKind::Code { language },
-                    }
-                }
-            };
-            if block.text.is_empty() {
-                None
-            } else {
-                Some(block)
-            }
-        }
-
-        let contents = match hover.contents {
-            lsp::HoverContents::Scalar(marked_string) => {
-                hover_blocks_from_marked_string(marked_string)
+        lsp_calls: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item =
+                hierarchy_item_from_lsp(lsp_call.from, &lsp_store, server_id, &mut cx).await?;
+            let caller = item.location.buffer.clone();
+            let call_sites = caller.read_with(&cx, |snapshot, _| {
+                lsp_call
+                    .from_ranges
                     .into_iter()
+                    .map(|range| Location {
+                        buffer: caller.clone(),
+                        range: anchor_range_from_lsp(snapshot, range),
+                    })
                     .collect()
-            }
-            lsp::HoverContents::Array(marked_strings) => marked_strings
-                .into_iter()
-                .filter_map(hover_blocks_from_marked_string)
-                .collect(),
-            lsp::HoverContents::Markup(markup_content) => vec![HoverBlock {
-                text: markup_content.value,
-                kind: if markup_content.kind == lsp::MarkupKind::Markdown {
-                    HoverBlockKind::Markdown
-                } else {
-                    HoverBlockKind::PlainText
-                },
-            }],
-        };
-
-        Ok(Some(Hover {
-            contents,
-            range,
-            language,
-        }))
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
-        proto::GetHover {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
             project_id,
             buffer_id: buffer.remote_id().into(),
-            position: Some(language::proto::serialize_anchor(
-                &buffer.anchor_before(self.position),
-            )),
-            version: serialize_version(&buffer.version),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
         }
     }
 
     async fn from_proto(
-        message: Self::ProtoRequest,
+        message: proto::GetIncomingCalls,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
-        let position = message
-            .position
-            .and_then(deserialize_anchor)
-            .context("invalid position")?;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
+        let item = message.item.context("missing call hierarchy item")?;
         Ok(Self {
-            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+            item: hierarchy_item_from_proto(item, buffer, &mut cx).await?,
         })
     }
 
     fn response_to_proto(
-        response: Self::Response,
-        _: &mut LspStore,
-        _: PeerId,
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
         _: &clock::Global,
-        _: &mut App,
-    ) -> proto::GetHoverResponse {
-        if let Some(response) = response {
-            let (start, end) = if let Some(range) = response.range {
-                (
-                    Some(language::proto::serialize_anchor(&range.start)),
-                    Some(language::proto::serialize_anchor(&range.end)),
-                )
-            } else {
-                (None, None)
-            };
-
-            let contents = response
-                .contents
-                .into_iter()
-                .map(|block| proto::HoverBlock {
-                    text: block.text,
-                    is_markdown: block.kind == HoverBlockKind::Markdown,
-                    language: if let HoverBlockKind::Code { language } = block.kind {
-                        Some(language)
-                    } else {
-                        None
-                    },
-                })
-                .collect();
-
-            proto::GetHoverResponse {
-                start,
-                end,
-                contents,
-            }
-        } else {
-            proto::GetHoverResponse {
-                start: None,
-                end: None,
-                contents: Vec::new(),
-            }
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
         }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetHoverResponse,
-        _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
         mut cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        let contents: Vec<_> = message
-            .contents
-            .into_iter()
-            .map(|block| HoverBlock {
-                text: block.text,
-                kind: if let Some(language) = block.language {
-                    HoverBlockKind::Code { language }
-                } else if block.is_markdown {
-                    HoverBlockKind::Markdown
-                } else {
-                    HoverBlockKind::PlainText
-                },
-            })
-            .collect();
-        if contents.is_empty() {
-            return Ok(None);
-        }
-
-        let language = buffer.read_with(&cx, |buffer, _| buffer.language().cloned())?;
-        let range = if let (Some(start), Some(end)) = (message.start, message.end) {
-            language::proto::deserialize_anchor(start)
-                .and_then(|start| language::proto::deserialize_anchor(end).map(|end| start..end))
-        } else {
-            None
-        };
-        if let Some(range) = range.as_ref() {
-            buffer
-                .update(&mut cx, |buffer, _| {
-                    buffer.wait_for_anchors([range.start, range.end])
-                })?
-                .await?;
-        }
-
-        Ok(Some(Hover {
-            contents,
-            range,
-            language,
-        }))
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, &lsp_store, &mut cx).await
     }
 
-    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-impl GetCompletions {
-    pub fn can_resolve_completions(capabilities: &lsp::ServerCapabilities) -> bool {
-        capabilities
-            .completion_provider
-            .as_ref()
-            .and_then(|options| options.resolve_provider)
-            .unwrap_or(false)
-    }
-}
-
 #[async_trait(?Send)]
-impl LspCommand for GetCompletions {
-    type Response = CoreCompletionResponse;
-    type LspRequest = lsp::request::Completion;
-    type ProtoRequest = proto::GetCompletions;
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<CallHierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
 
     fn display_name(&self) -> &str {
-        "Get completion"
+        "Get outgoing calls"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        capabilities
-            .server_capabilities
-            .completion_provider
-            .is_some()
+        call_hierarchy_supported(&capabilities)
     }
 
     fn to_lsp(
         &self,
-        path: &Path,
+        _: &Path,
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::CompletionParams> {
-        Ok(lsp::CompletionParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
-            context: Some(self.context.clone()),
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.lsp_item.clone(),
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
         })
@@ -2199,608 +2452,371 @@ impl LspCommand for GetCompletions {
 
     async fn response_from_lsp(
         self,
-        completions: Option<lsp::CompletionResponse>,
+        lsp_calls: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
         lsp_store: Entity<LspStore>,
-        buffer: Entity<Buffer>,
+        _: Entity<Buffer>,
         server_id: LanguageServerId,
         mut cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        let mut response_list = None;
-        let (mut completions, mut is_incomplete) = if let Some(completions) = completions {
-            match completions {
-                lsp::CompletionResponse::Array(completions) => (completions, false),
-                lsp::CompletionResponse::List(mut list) => {
-                    let is_incomplete = list.is_incomplete;
-                    let items = std::mem::take(&mut list.items);
-                    response_list = Some(list);
-                    (items, is_incomplete)
-                }
-            }
-        } else {
-            (Vec::new(), false)
-        };
-
-        let unfiltered_completions_count = completions.len();
+    ) -> Result<Vec<CallHierarchyCall>> {
+        // Outgoing call sites are located in the queried item, not in the callee.
+        let caller = self.item.location.buffer.clone();
+        let mut calls = Vec::new();
+        for lsp_call in lsp_calls.unwrap_or_default() {
+            let item = hierarchy_item_from_lsp(lsp_call.to, &lsp_store, server_id, &mut cx).await?;
+            let call_sites = caller.read_with(&cx, |snapshot, _| {
+                lsp_call
+                    .from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: caller.clone(),
+                        range: anchor_range_from_lsp(snapshot, range),
+                    })
+                    .collect()
+            })?;
+            calls.push(CallHierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
 
-        let language_server_adapter = lsp_store
-            .read_with(&cx, |lsp_store, _| {
-                lsp_store.language_server_adapter_for_id(server_id)
-            })?
-            .with_context(|| format!("no language server with id {server_id}"))?;
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
 
-        let lsp_defaults = response_list
-            .as_ref()
-            .and_then(|list| list.item_defaults.clone())
-            .map(Arc::new);
-
-        let mut completion_edits = Vec::new();
-        buffer.update(&mut cx, |buffer, _cx| {
-            let snapshot = buffer.snapshot();
-            let clipped_position = buffer.clip_point_utf16(Unclipped(self.position), Bias::Left);
-
-            let mut range_for_token = None;
-            completions.retain(|lsp_completion| {
-                let lsp_edit = lsp_completion.text_edit.clone().or_else(|| {
-                    let default_text_edit = lsp_defaults.as_deref()?.edit_range.as_ref()?;
-                    let new_text = lsp_completion
-                        .text_edit_text
-                        .as_ref()
-                        .unwrap_or(&lsp_completion.label)
-                        .clone();
-                    match default_text_edit {
-                        CompletionListItemDefaultsEditRange::Range(range) => {
-                            Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
-                                range: *range,
-                                new_text,
-                            }))
-                        }
-                        CompletionListItemDefaultsEditRange::InsertAndReplace {
-                            insert,
-                            replace,
-                        } => Some(lsp::CompletionTextEdit::InsertAndReplace(
-                            lsp::InsertReplaceEdit {
-                                new_text,
-                                insert: *insert,
-                                replace: *replace,
-                            },
-                        )),
-                    }
-                });
-
-                let edit = match lsp_edit {
-                    // If the language server provides a range to overwrite, then
-                    // check that the range is valid.
-                    Some(completion_text_edit) => {
-                        match parse_completion_text_edit(&completion_text_edit, &snapshot) {
-                            Some(edit) => edit,
-                            None => return false,
-                        }
-                    }
-                    // If the language server does not provide a range, then infer
-                    // the range based on the syntax tree.
-                    None => {
-                        if self.position != clipped_position {
-                            log::info!("completion out of expected range ");
-                            return false;
-                        }
-
-                        let default_edit_range = lsp_defaults.as_ref().and_then(|lsp_defaults| {
-                            lsp_defaults
-                                .edit_range
-                                .as_ref()
-                                .and_then(|range| match range {
-                                    CompletionListItemDefaultsEditRange::Range(r) => Some(r),
-                                    _ => None,
-                                })
-                        });
-
-                        let range = if let Some(range) = default_edit_range {
-                            let range = range_from_lsp(*range);
-                            let start = snapshot.clip_point_utf16(range.start, Bias::Left);
-                            let end = snapshot.clip_point_utf16(range.end, Bias::Left);
-                            if start != range.start.0 || end != range.end.0 {
-                                log::info!("completion out of expected range");
-                                return false;
-                            }
-
-                            snapshot.anchor_before(start)..snapshot.anchor_after(end)
-                        } else {
-                            range_for_token
-                                .get_or_insert_with(|| {
-                                    let offset = self.position.to_offset(&snapshot);
-                                    let (range, kind) = snapshot.surrounding_word(
-                                        offset,
-                                        Some(CharScopeContext::Completion),
-                                    );
-                                    let range = if kind == Some(CharKind::Word) {
-                                        range
-                                    } else {
-                                        offset..offset
-                                    };
-
-                                    snapshot.anchor_before(range.start)
-                                        ..snapshot.anchor_after(range.end)
-                                })
-                                .clone()
-                        };
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing call hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, buffer, &mut cx).await?,
+        })
+    }
 
-                        // We already know text_edit is None here
-                        let text = lsp_completion
-                            .insert_text
-                            .as_ref()
-                            .unwrap_or(&lsp_completion.label)
-                            .clone();
+    fn response_to_proto(
+        response: Vec<CallHierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: call_hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
 
-                        ParsedCompletionEdit {
-                            replace_range: range,
-                            insert_range: None,
-                            new_text: text,
-                        }
-                    }
-                };
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CallHierarchyCall>> {
+        call_hierarchy_calls_from_proto(message.calls, &lsp_store, &mut cx).await
+    }
 
-                completion_edits.push(edit);
-                true
-            });
-        })?;
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
 
-        // If completions were filtered out due to errors that may be transient, mark the result
-        // incomplete so that it is re-queried.
-        if unfiltered_completions_count != completions.len() {
-            is_incomplete = true;
-        }
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
 
-        language_server_adapter
-            .process_completions(&mut completions)
-            .await;
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
 
-        let completions = completions
-            .into_iter()
-            .zip(completion_edits)
-            .map(|(mut lsp_completion, mut edit)| {
-                LineEnding::normalize(&mut edit.new_text);
-                if lsp_completion.data.is_none()
-                    && let Some(default_data) = lsp_defaults
-                        .as_ref()
-                        .and_then(|item_defaults| item_defaults.data.clone())
-                {
-                    // Servers (e.g. JDTLS) prefer unchanged completions, when resolving the items later,
-                    // so we do not insert the defaults here, but `data` is needed for resolving, so this is an exception.
-                    lsp_completion.data = Some(default_data);
-                }
-                CoreCompletion {
-                    replace_range: edit.replace_range,
-                    new_text: edit.new_text,
-                    source: CompletionSource::Lsp {
-                        insert_range: edit.insert_range,
-                        server_id,
-                        lsp_completion: Box::new(lsp_completion),
-                        lsp_defaults: lsp_defaults.clone(),
-                        resolved: false,
-                    },
-                }
-            })
-            .collect();
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        type_hierarchy_supported(&capabilities)
+    }
 
-        Ok(CoreCompletionResponse {
-            completions,
-            is_incomplete,
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
         })
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCompletions {
-        let anchor = buffer.anchor_after(self.position);
-        proto::GetCompletions {
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
             project_id,
             buffer_id: buffer.remote_id().into(),
-            position: Some(language::proto::serialize_anchor(&anchor)),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        message: proto::GetCompletions,
+        message: proto::PrepareTypeHierarchy,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
-        let version = deserialize_version(&message.version);
-        buffer
-            .update(&mut cx, |buffer, _| buffer.wait_for_version(version))?
-            .await?;
         let position = message
             .position
-            .and_then(language::proto::deserialize_anchor)
-            .map(|p| {
-                buffer.read_with(&cx, |buffer, _| {
-                    buffer.clip_point_utf16(Unclipped(p.to_point_utf16(buffer)), Bias::Left)
-                })
-            })
-            .context("invalid position")??;
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
         Ok(Self {
-            position,
-            context: CompletionContext {
-                trigger_kind: CompletionTriggerKind::INVOKED,
-                trigger_character: None,
-            },
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
         })
     }
 
     fn response_to_proto(
-        response: CoreCompletionResponse,
-        _: &mut LspStore,
-        _: PeerId,
-        buffer_version: &clock::Global,
-        _: &mut App,
-    ) -> proto::GetCompletionsResponse {
-        proto::GetCompletionsResponse {
-            completions: response
-                .completions
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: response
                 .iter()
-                .map(LspStore::serialize_completion)
+                .map(|item| hierarchy_item_to_peer(item, lsp_store, peer_id, cx))
                 .collect(),
-            version: serialize_version(buffer_version),
-            can_reuse: !response.is_incomplete,
         }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetCompletionsResponse,
-        _project: Entity<LspStore>,
-        buffer: Entity<Buffer>,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
         mut cx: AsyncApp,
-    ) -> Result<Self::Response> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        remote_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
 
-        let completions = message
-            .completions
-            .into_iter()
-            .map(LspStore::deserialize_completion)
-            .collect::<Result<Vec<_>>>()?;
-
-        Ok(CoreCompletionResponse {
-            completions,
-            is_incomplete: !message.can_reuse,
-        })
-    }
-
-    fn buffer_id_from_proto(message: &proto::GetCompletions) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-pub struct ParsedCompletionEdit {
-    pub replace_range: Range<Anchor>,
-    pub insert_range: Option<Range<Anchor>>,
-    pub new_text: String,
-}
-
-pub(crate) fn parse_completion_text_edit(
-    edit: &lsp::CompletionTextEdit,
-    snapshot: &BufferSnapshot,
-) -> Option<ParsedCompletionEdit> {
-    let (replace_range, insert_range, new_text) = match edit {
-        lsp::CompletionTextEdit::Edit(edit) => (edit.range, None, &edit.new_text),
-        lsp::CompletionTextEdit::InsertAndReplace(edit) => {
-            (edit.replace, Some(edit.insert), &edit.new_text)
-        }
-    };
-
-    let replace_range = {
-        let range = range_from_lsp(replace_range);
-        let start = snapshot.clip_point_utf16(range.start, Bias::Left);
-        let end = snapshot.clip_point_utf16(range.end, Bias::Left);
-        if start != range.start.0 || end != range.end.0 {
-            log::info!(
-                "completion out of expected range, start: {start:?}, end: {end:?}, range: {range:?}"
-            );
-            return None;
-        }
-        snapshot.anchor_before(start)..snapshot.anchor_after(end)
-    };
-
-    let insert_range = match insert_range {
-        None => None,
-        Some(insert_range) => {
-            let range = range_from_lsp(insert_range);
-            let start = snapshot.clip_point_utf16(range.start, Bias::Left);
-            let end = snapshot.clip_point_utf16(range.end, Bias::Left);
-            if start != range.start.0 || end != range.end.0 {
-                log::info!("completion (insert) out of expected range");
-                return None;
-            }
-            Some(snapshot.anchor_before(start)..snapshot.anchor_after(end))
-        }
-    };
-
-    Some(ParsedCompletionEdit {
-        insert_range,
-        replace_range,
-        new_text: new_text.clone(),
-    })
-}
-
 #[async_trait(?Send)]
-impl LspCommand for GetCodeActions {
-    type Response = Vec<CodeAction>;
-    type LspRequest = lsp::request::CodeActionRequest;
-    type ProtoRequest = proto::GetCodeActions;
+impl LspCommand for GetSupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
 
     fn display_name(&self) -> &str {
-        "Get code actions"
+        "Get supertypes"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        match &capabilities.server_capabilities.code_action_provider {
-            None => false,
-            Some(lsp::CodeActionProviderCapability::Simple(false)) => false,
-            _ => {
-                // If we do know that we want specific code actions AND we know that
-                // the server only supports specific code actions, then we want to filter
-                // down to the ones that are supported.
-                if let Some((requested, supported)) = self
-                    .kinds
-                    .as_ref()
-                    .zip(Self::supported_code_action_kinds(capabilities))
-                {
-                    let server_supported = supported.into_iter().collect::<HashSet<_>>();
-                    requested.iter().any(|kind| server_supported.contains(kind))
-                } else {
-                    true
-                }
-            }
-        }
+        type_hierarchy_supported(&capabilities)
     }
 
     fn to_lsp(
         &self,
-        path: &Path,
-        buffer: &Buffer,
-        language_server: &Arc<LanguageServer>,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::CodeActionParams> {
-        let mut relevant_diagnostics = Vec::new();
-        for entry in buffer
-            .snapshot()
-            .diagnostics_in_range::<_, language::PointUtf16>(self.range.clone(), false)
-        {
-            relevant_diagnostics.push(entry.to_lsp_diagnostic_stub()?);
-        }
-
-        let supported =
-            Self::supported_code_action_kinds(language_server.adapter_server_capabilities());
-
-        let only = if let Some(requested) = &self.kinds {
-            if let Some(supported_kinds) = supported {
-                let server_supported = supported_kinds.into_iter().collect::<HashSet<_>>();
-
-                let filtered = requested
-                    .iter()
-                    .filter(|kind| server_supported.contains(kind))
-                    .cloned()
-                    .collect();
-                Some(filtered)
-            } else {
-                Some(requested.clone())
-            }
-        } else {
-            supported
-        };
-
-        Ok(lsp::CodeActionParams {
-            text_document: make_text_document_identifier(path)?,
-            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.lsp_item.clone(),
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
-            context: lsp::CodeActionContext {
-                diagnostics: relevant_diagnostics,
-                only,
-                ..lsp::CodeActionContext::default()
-            },
         })
     }
 
     async fn response_from_lsp(
         self,
-        actions: Option<lsp::CodeActionResponse>,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
         lsp_store: Entity<LspStore>,
         _: Entity<Buffer>,
         server_id: LanguageServerId,
-        cx: AsyncApp,
-    ) -> Result<Vec<CodeAction>> {
-        let requested_kinds_set = self
-            .kinds
-            .map(|kinds| kinds.into_iter().collect::<HashSet<_>>());
-
-        let language_server = cx.update(|cx| {
-            lsp_store
-                .read(cx)
-                .language_server_for_id(server_id)
-                .with_context(|| {
-                    format!("Missing the language server that just returned a response {server_id}")
-                })
-        })??;
-
-        let server_capabilities = language_server.capabilities();
-        let available_commands = server_capabilities
-            .execute_command_provider
-            .as_ref()
-            .map(|options| options.commands.as_slice())
-            .unwrap_or_default();
-        Ok(actions
-            .unwrap_or_default()
-            .into_iter()
-            .filter_map(|entry| {
-                let (lsp_action, resolved) = match entry {
-                    lsp::CodeActionOrCommand::CodeAction(lsp_action) => {
-                        if let Some(command) = lsp_action.command.as_ref()
-                            && !available_commands.contains(&command.command)
-                        {
-                            return None;
-                        }
-                        (LspAction::Action(Box::new(lsp_action)), false)
-                    }
-                    lsp::CodeActionOrCommand::Command(command) => {
-                        if available_commands.contains(&command.command) {
-                            (LspAction::Command(command), true)
-                        } else {
-                            return None;
-                        }
-                    }
-                };
-
-                if let Some((requested_kinds, kind)) =
-                    requested_kinds_set.as_ref().zip(lsp_action.action_kind())
-                    && !requested_kinds.contains(&kind)
-                {
-                    return None;
-                }
-
-                Some(CodeAction {
-                    server_id,
-                    range: self.range.clone(),
-                    lsp_action,
-                    resolved,
-                })
-            })
-            .collect())
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeActions {
-        proto::GetCodeActions {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
             project_id,
             buffer_id: buffer.remote_id().into(),
-            start: Some(language::proto::serialize_anchor(&self.range.start)),
-            end: Some(language::proto::serialize_anchor(&self.range.end)),
-            version: serialize_version(&buffer.version()),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
         }
     }
 
     async fn from_proto(
-        message: proto::GetCodeActions,
+        message: proto::GetSupertypes,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
-        let start = message
-            .start
-            .and_then(language::proto::deserialize_anchor)
-            .context("invalid start")?;
-        let end = message
-            .end
-            .and_then(language::proto::deserialize_anchor)
-            .context("invalid end")?;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-
+        let item = message.item.context("missing type hierarchy item")?;
         Ok(Self {
-            range: start..end,
-            kinds: None,
+            item: hierarchy_item_from_proto(item, buffer, &mut cx).await?,
         })
     }
 
     fn response_to_proto(
-        code_actions: Vec<CodeAction>,
-        _: &mut LspStore,
-        _: PeerId,
-        buffer_version: &clock::Global,
-        _: &mut App,
-    ) -> proto::GetCodeActionsResponse {
-        proto::GetCodeActionsResponse {
-            actions: code_actions
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: response
                 .iter()
-                .map(LspStore::serialize_code_action)
+                .map(|item| hierarchy_item_to_peer(item, lsp_store, peer_id, cx))
                 .collect(),
-            version: serialize_version(buffer_version),
         }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetCodeActionsResponse,
-        _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
         mut cx: AsyncApp,
-    ) -> Result<Vec<CodeAction>> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-        message
-            .actions
-            .into_iter()
-            .map(LspStore::deserialize_code_action)
-            .collect()
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        remote_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
     }
 
-    fn buffer_id_from_proto(message: &proto::GetCodeActions) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-impl GetCodeActions {
-    fn supported_code_action_kinds(
-        capabilities: AdapterServerCapabilities,
-    ) -> Option<Vec<CodeActionKind>> {
-        match capabilities.server_capabilities.code_action_provider {
-            Some(lsp::CodeActionProviderCapability::Options(CodeActionOptions {
-                code_action_kinds: Some(supported_action_kinds),
-                ..
-            })) => Some(supported_action_kinds),
-            _ => capabilities.code_action_kinds,
-        }
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
     }
 
-    pub fn can_resolve_actions(capabilities: &ServerCapabilities) -> bool {
-        capabilities
-            .code_action_provider
-            .as_ref()
-            .and_then(|options| match options {
-                lsp::CodeActionProviderCapability::Simple(_is_supported) => None,
-                lsp::CodeActionProviderCapability::Options(options) => options.resolve_provider,
-            })
-            .unwrap_or(false)
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        type_hierarchy_supported(&capabilities)
     }
-}
 
-impl OnTypeFormatting {
-    pub fn supports_on_type_formatting(trigger: &str, capabilities: &ServerCapabilities) -> bool {
-        let Some(on_type_formatting_options) = &capabilities.document_on_type_formatting_provider
-        else {
-            return false;
-        };
-        on_type_formatting_options
-            .first_trigger_character
-            .contains(trigger)
-            || on_type_formatting_options
-                .more_trigger_character
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        hierarchy_items_from_lsp(lsp_items, &lsp_store, server_id, &mut cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(hierarchy_item_to_proto(&self.item, buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("missing type hierarchy item")?;
+        Ok(Self {
+            item: hierarchy_item_from_proto(item, buffer, &mut cx).await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<TypeHierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: response
                 .iter()
-                .flatten()
-                .any(|chars| chars.contains(trigger))
+                .map(|item| hierarchy_item_to_peer(item, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        remote_hierarchy_items_from_proto(message.items, &lsp_store, &mut cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
     }
 }
 
 #[async_trait(?Send)]
-impl LspCommand for OnTypeFormatting {
-    type Response = Option<Transaction>;
-    type LspRequest = lsp::request::OnTypeFormatting;
-    type ProtoRequest = proto::OnTypeFormatting;
+impl LspCommand for GetSignatureHelp {
+    type Response = Option<SignatureHelp>;
+    type LspRequest = lsp::SignatureHelpRequest;
+    type ProtoRequest = proto::GetSignatureHelp;
 
     fn display_name(&self) -> &str {
-        "Formatting on typing"
+        "Get signature help"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        Self::supports_on_type_formatting(&self.trigger, &capabilities.server_capabilities)
+        capabilities
+            .server_capabilities
+            .signature_help_provider
+            .is_some()
     }
 
     fn to_lsp(
@@ -2808,149 +2824,1134 @@ impl LspCommand for OnTypeFormatting {
         path: &Path,
         _: &Buffer,
         _: &Arc<LanguageServer>,
-        _: &App,
-    ) -> Result<lsp::DocumentOnTypeFormattingParams> {
-        Ok(lsp::DocumentOnTypeFormattingParams {
-            text_document_position: make_lsp_text_document_position(path, self.position)?,
-            ch: self.trigger.clone(),
-            options: self.options.clone(),
+        _cx: &App,
+    ) -> Result<lsp::SignatureHelpParams> {
+        Ok(lsp::SignatureHelpParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            context: None,
+            work_done_progress_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<Vec<lsp::TextEdit>>,
+        message: Option<lsp::SignatureHelp>,
         lsp_store: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        server_id: LanguageServerId,
-        mut cx: AsyncApp,
-    ) -> Result<Option<Transaction>> {
-        if let Some(edits) = message {
-            let (lsp_adapter, lsp_server) =
-                language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
-            LocalLspStore::deserialize_text_edits(
-                lsp_store,
-                buffer,
-                edits,
-                self.push_to_history,
-                lsp_adapter,
-                lsp_server,
-                &mut cx,
+        _: Entity<Buffer>,
+        id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        cx.update(|cx| {
+            SignatureHelp::new(
+                message,
+                Some(lsp_store.read(cx).languages.clone()),
+                Some(id),
+                cx,
             )
-            .await
-        } else {
-            Ok(None)
-        }
+        })
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::OnTypeFormatting {
-        proto::OnTypeFormatting {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        let offset = buffer.point_utf16_to_offset(self.position);
+        proto::GetSignatureHelp {
             project_id,
-            buffer_id: buffer.remote_id().into(),
-            position: Some(language::proto::serialize_anchor(
-                &buffer.anchor_before(self.position),
-            )),
-            trigger: self.trigger.clone(),
+            buffer_id: buffer.remote_id().to_proto(),
+            position: Some(serialize_anchor(&buffer.anchor_after(offset))),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        message: proto::OnTypeFormatting,
+        payload: Self::ProtoRequest,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
-        let position = message
-            .position
-            .and_then(deserialize_anchor)
-            .context("invalid position")?;
         buffer
             .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
+                buffer.wait_for_version(deserialize_version(&payload.version))
             })?
-            .await?;
-
-        let options = buffer.update(&mut cx, |buffer, cx| {
-            lsp_formatting_options(
-                language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).as_ref(),
-            )
-        })?;
-
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+        let buffer_snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
         Ok(Self {
-            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
-            trigger: message.trigger.clone(),
-            options,
-            push_to_history: false,
+            position: payload
+                .position
+                .and_then(deserialize_anchor)
+                .context("invalid position")?
+                .to_point_utf16(&buffer_snapshot),
         })
     }
 
     fn response_to_proto(
-        response: Option<Transaction>,
+        response: Self::Response,
         _: &mut LspStore,
         _: PeerId,
-        _: &clock::Global,
+        _: &Global,
         _: &mut App,
-    ) -> proto::OnTypeFormattingResponse {
-        proto::OnTypeFormattingResponse {
-            transaction: response
-                .map(|transaction| language::proto::serialize_transaction(&transaction)),
+    ) -> proto::GetSignatureHelpResponse {
+        proto::GetSignatureHelpResponse {
+            signature_help: response
+                .map(|signature_help| lsp_to_proto_signature(signature_help.original_data)),
         }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::OnTypeFormattingResponse,
-        _: Entity<LspStore>,
+        response: proto::GetSignatureHelpResponse,
+        lsp_store: Entity<LspStore>,
         _: Entity<Buffer>,
-        _: AsyncApp,
-    ) -> Result<Option<Transaction>> {
-        let Some(transaction) = message.transaction else {
-            return Ok(None);
-        };
-        Ok(Some(language::proto::deserialize_transaction(transaction)?))
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        cx.update(|cx| {
+            response
+                .signature_help
+                .map(proto_to_lsp_signature)
+                .and_then(|signature| {
+                    SignatureHelp::new(
+                        signature,
+                        Some(lsp_store.read(cx).languages.clone()),
+                        None,
+                        cx,
+                    )
+                })
+        })
     }
 
-    fn buffer_id_from_proto(message: &proto::OnTypeFormatting) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-impl InlayHints {
-    pub async fn lsp_to_project_hint(
-        lsp_hint: lsp::InlayHint,
-        buffer_handle: &Entity<Buffer>,
-        server_id: LanguageServerId,
-        resolve_state: ResolveState,
-        force_no_type_left_padding: bool,
-        cx: &mut AsyncApp,
-    ) -> anyhow::Result<InlayHint> {
-        let kind = lsp_hint.kind.and_then(|kind| match kind {
-            lsp::InlayHintKind::TYPE => Some(InlayHintKind::Type),
-            lsp::InlayHintKind::PARAMETER => Some(InlayHintKind::Parameter),
-            _ => None,
-        });
+#[async_trait(?Send)]
+impl LspCommand for GetHover {
+    type Response = Option<Hover>;
+    type LspRequest = lsp::request::HoverRequest;
+    type ProtoRequest = proto::GetHover;
 
-        let position = buffer_handle.read_with(cx, |buffer, _| {
-            let position = buffer.clip_point_utf16(point_from_lsp(lsp_hint.position), Bias::Left);
-            if kind == Some(InlayHintKind::Parameter) {
-                buffer.anchor_before(position)
-            } else {
-                buffer.anchor_after(position)
-            }
-        })?;
-        let label = Self::lsp_inlay_label_to_project(lsp_hint.label, server_id)
-            .await
-            .context("lsp to project inlay hint conversion")?;
-        let padding_left = if force_no_type_left_padding && kind == Some(InlayHintKind::Type) {
-            false
-        } else {
-            lsp_hint.padding_left.unwrap_or(false)
-        };
+    fn display_name(&self) -> &str {
+        "Get hover"
+    }
 
-        Ok(InlayHint {
-            position,
-            padding_left,
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.hover_provider {
+            Some(lsp::HoverProviderCapability::Simple(enabled)) => enabled,
+            Some(lsp::HoverProviderCapability::Options(_)) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::HoverParams> {
+        Ok(lsp::HoverParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::Hover>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let Some(hover) = message else {
+            return Ok(None);
+        };
+
+        let (language, range) = buffer.read_with(&cx, |buffer, _| {
+            (
+                buffer.language().cloned(),
+                hover.range.map(|range| {
+                    let token_start =
+                        buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                    let token_end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                    buffer.anchor_after(token_start)..buffer.anchor_before(token_end)
+                }),
+            )
+        })?;
+
+        fn hover_blocks_from_marked_string(marked_string: lsp::MarkedString) -> Option<HoverBlock> {
+            let block = match marked_string {
+                lsp::MarkedString::String(content) => HoverBlock {
+                    text: content,
+                    kind: HoverBlockKind::Markdown,
+                },
+                lsp::MarkedString::LanguageString(lsp::LanguageString { language, value }) => {
+                    HoverBlock {
+                        text: value,
+                        kind: HoverBlockKind::Code { language },
+                    }
+                }
+            };
+            if block.text.is_empty() {
+                None
+            } else {
+                Some(block)
+            }
+        }
+
+        let contents = match hover.contents {
+            lsp::HoverContents::Scalar(marked_string) => {
+                hover_blocks_from_marked_string(marked_string)
+                    .into_iter()
+                    .collect()
+            }
+            lsp::HoverContents::Array(marked_strings) => marked_strings
+                .into_iter()
+                .filter_map(hover_blocks_from_marked_string)
+                .collect(),
+            lsp::HoverContents::Markup(markup_content) => vec![HoverBlock {
+                text: markup_content.value,
+                kind: if markup_content.kind == lsp::MarkupKind::Markdown {
+                    HoverBlockKind::Markdown
+                } else {
+                    HoverBlockKind::PlainText
+                },
+            }],
+        };
+
+        Ok(Some(Hover {
+            contents,
+            range,
+            language,
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetHover {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version),
+        }
+    }
+
+    async fn from_proto(
+        message: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetHoverResponse {
+        if let Some(response) = response {
+            let (start, end) = if let Some(range) = response.range {
+                (
+                    Some(language::proto::serialize_anchor(&range.start)),
+                    Some(language::proto::serialize_anchor(&range.end)),
+                )
+            } else {
+                (None, None)
+            };
+
+            let contents = response
+                .contents
+                .into_iter()
+                .map(|block| proto::HoverBlock {
+                    text: block.text,
+                    is_markdown: block.kind == HoverBlockKind::Markdown,
+                    language: if let HoverBlockKind::Code { language } = block.kind {
+                        Some(language)
+                    } else {
+                        None
+                    },
+                })
+                .collect();
+
+            proto::GetHoverResponse {
+                start,
+                end,
+                contents,
+            }
+        } else {
+            proto::GetHoverResponse {
+                start: None,
+                end: None,
+                contents: Vec::new(),
+            }
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetHoverResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let contents: Vec<_> = message
+            .contents
+            .into_iter()
+            .map(|block| HoverBlock {
+                text: block.text,
+                kind: if let Some(language) = block.language {
+                    HoverBlockKind::Code { language }
+                } else if block.is_markdown {
+                    HoverBlockKind::Markdown
+                } else {
+                    HoverBlockKind::PlainText
+                },
+            })
+            .collect();
+        if contents.is_empty() {
+            return Ok(None);
+        }
+
+        let language = buffer.read_with(&cx, |buffer, _| buffer.language().cloned())?;
+        let range = if let (Some(start), Some(end)) = (message.start, message.end) {
+            language::proto::deserialize_anchor(start)
+                .and_then(|start| language::proto::deserialize_anchor(end).map(|end| start..end))
+        } else {
+            None
+        };
+        if let Some(range) = range.as_ref() {
+            buffer
+                .update(&mut cx, |buffer, _| {
+                    buffer.wait_for_anchors([range.start, range.end])
+                })?
+                .await?;
+        }
+
+        Ok(Some(Hover {
+            contents,
+            range,
+            language,
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl GetCompletions {
+    pub fn can_resolve_completions(capabilities: &lsp::ServerCapabilities) -> bool {
+        capabilities
+            .completion_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetCompletions {
+    type Response = CoreCompletionResponse;
+    type LspRequest = lsp::request::Completion;
+    type ProtoRequest = proto::GetCompletions;
+
+    fn display_name(&self) -> &str {
+        "Get completion"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        capabilities
+            .server_capabilities
+            .completion_provider
+            .is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CompletionParams> {
+        Ok(lsp::CompletionParams {
+            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            context: Some(self.context.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        completions: Option<lsp::CompletionResponse>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        let mut response_list = None;
+        let (mut completions, mut is_incomplete) = if let Some(completions) = completions {
+            match completions {
+                lsp::CompletionResponse::Array(completions) => (completions, false),
+                lsp::CompletionResponse::List(mut list) => {
+                    let is_incomplete = list.is_incomplete;
+                    let items = std::mem::take(&mut list.items);
+                    response_list = Some(list);
+                    (items, is_incomplete)
+                }
+            }
+        } else {
+            (Vec::new(), false)
+        };
+
+        let unfiltered_completions_count = completions.len();
+
+        let language_server_adapter = lsp_store
+            .read_with(&cx, |lsp_store, _| {
+                lsp_store.language_server_adapter_for_id(server_id)
+            })?
+            .with_context(|| format!("no language server with id {server_id}"))?;
+
+        let lsp_defaults = response_list
+            .as_ref()
+            .and_then(|list| list.item_defaults.clone())
+            .map(Arc::new);
+
+        let mut completion_edits = Vec::new();
+        buffer.update(&mut cx, |buffer, _cx| {
+            let snapshot = buffer.snapshot();
+            let clipped_position = buffer.clip_point_utf16(Unclipped(self.position), Bias::Left);
+
+            let mut range_for_token = None;
+            completions.retain(|lsp_completion| {
+                let lsp_edit = lsp_completion.text_edit.clone().or_else(|| {
+                    let default_text_edit = lsp_defaults.as_deref()?.edit_range.as_ref()?;
+                    let new_text = lsp_completion
+                        .text_edit_text
+                        .as_ref()
+                        .unwrap_or(&lsp_completion.label)
+                        .clone();
+                    match default_text_edit {
+                        CompletionListItemDefaultsEditRange::Range(range) => {
+                            Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+                                range: *range,
+                                new_text,
+                            }))
+                        }
+                        CompletionListItemDefaultsEditRange::InsertAndReplace {
+                            insert,
+                            replace,
+                        } => Some(lsp::CompletionTextEdit::InsertAndReplace(
+                            lsp::InsertReplaceEdit {
+                                new_text,
+                                insert: *insert,
+                                replace: *replace,
+                            },
+                        )),
+                    }
+                });
+
+                let edit = match lsp_edit {
+                    // If the language server provides a range to overwrite, then
+                    // check that the range is valid.
+                    Some(completion_text_edit) => {
+                        match parse_completion_text_edit(&completion_text_edit, &snapshot) {
+                            Some(edit) => edit,
+                            None => return false,
+                        }
+                    }
+                    // If the language server does not provide a range, then infer
+                    // the range based on the syntax tree.
+                    None => {
+                        if self.position != clipped_position {
+                            log::info!("completion out of expected range ");
+                            return false;
+                        }
+
+                        let default_edit_range = lsp_defaults.as_ref().and_then(|lsp_defaults| {
+                            lsp_defaults
+                                .edit_range
+                                .as_ref()
+                                .and_then(|range| match range {
+                                    CompletionListItemDefaultsEditRange::Range(r) => Some(r),
+                                    _ => None,
+                                })
+                        });
+
+                        let range = if let Some(range) = default_edit_range {
+                            let range = range_from_lsp(*range);
+                            let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                            let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+                            if start != range.start.0 || end != range.end.0 {
+                                log::info!("completion out of expected range");
+                                return false;
+                            }
+
+                            snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                        } else {
+                            range_for_token
+                                .get_or_insert_with(|| {
+                                    let offset = self.position.to_offset(&snapshot);
+                                    let (range, kind) = snapshot.surrounding_word(
+                                        offset,
+                                        Some(CharScopeContext::Completion),
+                                    );
+                                    let range = if kind == Some(CharKind::Word) {
+                                        range
+                                    } else {
+                                        offset..offset
+                                    };
+
+                                    snapshot.anchor_before(range.start)
+                                        ..snapshot.anchor_after(range.end)
+                                })
+                                .clone()
+                        };
+
+                        // We already know text_edit is None here
+                        let text = lsp_completion
+                            .insert_text
+                            .as_ref()
+                            .unwrap_or(&lsp_completion.label)
+                            .clone();
+
+                        ParsedCompletionEdit {
+                            replace_range: range,
+                            insert_range: None,
+                            new_text: text,
+                        }
+                    }
+                };
+
+                completion_edits.push(edit);
+                true
+            });
+        })?;
+
+        // If completions were filtered out due to errors that may be transient, mark the result
+        // incomplete so that it is re-queried.
+        if unfiltered_completions_count != completions.len() {
+            is_incomplete = true;
+        }
+
+        language_server_adapter
+            .process_completions(&mut completions)
+            .await;
+
+        let completions = completions
+            .into_iter()
+            .zip(completion_edits)
+            .map(|(mut lsp_completion, mut edit)| {
+                LineEnding::normalize(&mut edit.new_text);
+                if lsp_completion.data.is_none()
+                    && let Some(default_data) = lsp_defaults
+                        .as_ref()
+                        .and_then(|item_defaults| item_defaults.data.clone())
+                {
+                    // Servers (e.g. JDTLS) prefer unchanged completions, when resolving the items later,
+                    // so we do not insert the defaults here, but `data` is needed for resolving, so this is an exception.
+                    lsp_completion.data = Some(default_data);
+                }
+                CoreCompletion {
+                    replace_range: edit.replace_range,
+                    new_text: edit.new_text,
+                    source: CompletionSource::Lsp {
+                        insert_range: edit.insert_range,
+                        server_id,
+                        lsp_completion: Box::new(lsp_completion),
+                        lsp_defaults: lsp_defaults.clone(),
+                        resolved: false,
+                    },
+                }
+            })
+            .collect();
+
+        Ok(CoreCompletionResponse {
+            completions,
+            is_incomplete,
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCompletions {
+        let anchor = buffer.anchor_after(self.position);
+        proto::GetCompletions {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(&anchor)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCompletions,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let version = deserialize_version(&message.version);
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_version(version))?
+            .await?;
+        let position = message
+            .position
+            .and_then(language::proto::deserialize_anchor)
+            .map(|p| {
+                buffer.read_with(&cx, |buffer, _| {
+                    buffer.clip_point_utf16(Unclipped(p.to_point_utf16(buffer)), Bias::Left)
+                })
+            })
+            .context("invalid position")??;
+        Ok(Self {
+            position,
+            context: CompletionContext {
+                trigger_kind: CompletionTriggerKind::INVOKED,
+                trigger_character: None,
+            },
+        })
+    }
+
+    fn response_to_proto(
+        response: CoreCompletionResponse,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetCompletionsResponse {
+        proto::GetCompletionsResponse {
+            completions: response
+                .completions
+                .iter()
+                .map(LspStore::serialize_completion)
+                .collect(),
+            version: serialize_version(buffer_version),
+            can_reuse: !response.is_incomplete,
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCompletionsResponse,
+        _project: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        let completions = message
+            .completions
+            .into_iter()
+            .map(LspStore::deserialize_completion)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CoreCompletionResponse {
+            completions,
+            is_incomplete: !message.can_reuse,
+        })
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCompletions) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+pub struct ParsedCompletionEdit {
+    pub replace_range: Range<Anchor>,
+    pub insert_range: Option<Range<Anchor>>,
+    pub new_text: String,
+}
+
+pub(crate) fn parse_completion_text_edit(
+    edit: &lsp::CompletionTextEdit,
+    snapshot: &BufferSnapshot,
+) -> Option<ParsedCompletionEdit> {
+    let (replace_range, insert_range, new_text) = match edit {
+        lsp::CompletionTextEdit::Edit(edit) => (edit.range, None, &edit.new_text),
+        lsp::CompletionTextEdit::InsertAndReplace(edit) => {
+            (edit.replace, Some(edit.insert), &edit.new_text)
+        }
+    };
+
+    let replace_range = {
+        let range = range_from_lsp(replace_range);
+        let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+        let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+        if start != range.start.0 || end != range.end.0 {
+            log::info!(
+                "completion out of expected range, start: {start:?}, end: {end:?}, range: {range:?}"
+            );
+            return None;
+        }
+        snapshot.anchor_before(start)..snapshot.anchor_after(end)
+    };
+
+    let insert_range = match insert_range {
+        None => None,
+        Some(insert_range) => {
+            let range = range_from_lsp(insert_range);
+            let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+            let end = snapshot.clip_point_utf16(range.end, Bias::Left);
+            if start != range.start.0 || end != range.end.0 {
+                log::info!("completion (insert) out of expected range");
+                return None;
+            }
+            Some(snapshot.anchor_before(start)..snapshot.anchor_after(end))
+        }
+    };
+
+    Some(ParsedCompletionEdit {
+        insert_range,
+        replace_range,
+        new_text: new_text.clone(),
+    })
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetCodeActions {
+    type Response = Vec<CodeAction>;
+    type LspRequest = lsp::request::CodeActionRequest;
+    type ProtoRequest = proto::GetCodeActions;
+
+    fn display_name(&self) -> &str {
+        "Get code actions"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match &capabilities.server_capabilities.code_action_provider {
+            None => false,
+            Some(lsp::CodeActionProviderCapability::Simple(false)) => false,
+            _ => {
+                // If we do know that we want specific code actions AND we know that
+                // the server only supports specific code actions, then we want to filter
+                // down to the ones that are supported.
+                if let Some((requested, supported)) = self
+                    .kinds
+                    .as_ref()
+                    .zip(Self::supported_code_action_kinds(capabilities))
+                {
+                    let server_supported = supported.into_iter().collect::<HashSet<_>>();
+                    requested.iter().any(|kind| server_supported.contains(kind))
+                } else {
+                    true
+                }
+            }
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        language_server: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CodeActionParams> {
+        let mut relevant_diagnostics = Vec::new();
+        for entry in buffer
+            .snapshot()
+            .diagnostics_in_range::<_, language::PointUtf16>(self.range.clone(), false)
+        {
+            relevant_diagnostics.push(entry.to_lsp_diagnostic_stub()?);
+        }
+
+        let supported =
+            Self::supported_code_action_kinds(language_server.adapter_server_capabilities());
+
+        let only = if let Some(requested) = &self.kinds {
+            if let Some(supported_kinds) = supported {
+                let server_supported = supported_kinds.into_iter().collect::<HashSet<_>>();
+
+                let filtered = requested
+                    .iter()
+                    .filter(|kind| server_supported.contains(kind))
+                    .cloned()
+                    .collect();
+                Some(filtered)
+            } else {
+                Some(requested.clone())
+            }
+        } else {
+            supported
+        };
+
+        Ok(lsp::CodeActionParams {
+            text_document: make_text_document_identifier(path)?,
+            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: lsp::CodeActionContext {
+                diagnostics: relevant_diagnostics,
+                only,
+                ..lsp::CodeActionContext::default()
+            },
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        actions: Option<lsp::CodeActionResponse>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<CodeAction>> {
+        let requested_kinds_set = self
+            .kinds
+            .map(|kinds| kinds.into_iter().collect::<HashSet<_>>());
+
+        let language_server = cx.update(|cx| {
+            lsp_store
+                .read(cx)
+                .language_server_for_id(server_id)
+                .with_context(|| {
+                    format!("Missing the language server that just returned a response {server_id}")
+                })
+        })??;
+
+        let server_capabilities = language_server.capabilities();
+        let available_commands = server_capabilities
+            .execute_command_provider
+            .as_ref()
+            .map(|options| options.commands.as_slice())
+            .unwrap_or_default();
+        Ok(actions
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|entry| {
+                let (lsp_action, resolved) = match entry {
+                    lsp::CodeActionOrCommand::CodeAction(lsp_action) => {
+                        if let Some(command) = lsp_action.command.as_ref()
+                            && !available_commands.contains(&command.command)
+                        {
+                            return None;
+                        }
+                        (LspAction::Action(Box::new(lsp_action)), false)
+                    }
+                    lsp::CodeActionOrCommand::Command(command) => {
+                        if available_commands.contains(&command.command) {
+                            (LspAction::Command(command), true)
+                        } else {
+                            return None;
+                        }
+                    }
+                };
+
+                if let Some((requested_kinds, kind)) =
+                    requested_kinds_set.as_ref().zip(lsp_action.action_kind())
+                    && !requested_kinds.contains(&kind)
+                {
+                    return None;
+                }
+
+                Some(CodeAction {
+                    server_id,
+                    range: self.range.clone(),
+                    lsp_action,
+                    resolved,
+                })
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeActions {
+        proto::GetCodeActions {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            start: Some(language::proto::serialize_anchor(&self.range.start)),
+            end: Some(language::proto::serialize_anchor(&self.range.end)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeActions,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let start = message
+            .start
+            .and_then(language::proto::deserialize_anchor)
+            .context("invalid start")?;
+        let end = message
+            .end
+            .and_then(language::proto::deserialize_anchor)
+            .context("invalid end")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        Ok(Self {
+            range: start..end,
+            kinds: None,
+        })
+    }
+
+    fn response_to_proto(
+        code_actions: Vec<CodeAction>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetCodeActionsResponse {
+        proto::GetCodeActionsResponse {
+            actions: code_actions
+                .iter()
+                .map(LspStore::serialize_code_action)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeActionsResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<CodeAction>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .actions
+            .into_iter()
+            .map(LspStore::deserialize_code_action)
+            .collect()
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeActions) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl GetCodeActions {
+    fn supported_code_action_kinds(
+        capabilities: AdapterServerCapabilities,
+    ) -> Option<Vec<CodeActionKind>> {
+        match capabilities.server_capabilities.code_action_provider {
+            Some(lsp::CodeActionProviderCapability::Options(CodeActionOptions {
+                code_action_kinds: Some(supported_action_kinds),
+                ..
+            })) => Some(supported_action_kinds),
+            _ => capabilities.code_action_kinds,
+        }
+    }
+
+    pub fn can_resolve_actions(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .code_action_provider
+            .as_ref()
+            .and_then(|options| match options {
+                lsp::CodeActionProviderCapability::Simple(_is_supported) => None,
+                lsp::CodeActionProviderCapability::Options(options) => options.resolve_provider,
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl OnTypeFormatting {
+    pub fn supports_on_type_formatting(trigger: &str, capabilities: &ServerCapabilities) -> bool {
+        let Some(on_type_formatting_options) = &capabilities.document_on_type_formatting_provider
+        else {
+            return false;
+        };
+        on_type_formatting_options
+            .first_trigger_character
+            .contains(trigger)
+            || on_type_formatting_options
+                .more_trigger_character
+                .iter()
+                .flatten()
+                .any(|chars| chars.contains(trigger))
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for OnTypeFormatting {
+    type Response = Option<Transaction>;
+    type LspRequest = lsp::request::OnTypeFormatting;
+    type ProtoRequest = proto::OnTypeFormatting;
+
+    fn display_name(&self) -> &str {
+        "Formatting on typing"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        Self::supports_on_type_formatting(&self.trigger, &capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::DocumentOnTypeFormattingParams> {
+        Ok(lsp::DocumentOnTypeFormattingParams {
+            text_document_position: make_lsp_text_document_position(path, self.position)?,
+            ch: self.trigger.clone(),
+            options: self.options.clone(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TextEdit>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Option<Transaction>> {
+        if let Some(edits) = message {
+            let (lsp_adapter, lsp_server) =
+                language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+            LocalLspStore::deserialize_text_edits(
+                lsp_store,
+                buffer,
+                edits,
+                self.push_to_history,
+                lsp_adapter,
+                lsp_server,
+                &mut cx,
+            )
+            .await
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::OnTypeFormatting {
+        proto::OnTypeFormatting {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            trigger: self.trigger.clone(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::OnTypeFormatting,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        let options = buffer.update(&mut cx, |buffer, cx| {
+            lsp_formatting_options(
+                language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).as_ref(),
+            )
+        })?;
+
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+            trigger: message.trigger.clone(),
+            options,
+            push_to_history: false,
+        })
+    }
+
+    fn response_to_proto(
+        response: Option<Transaction>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::OnTypeFormattingResponse {
+        proto::OnTypeFormattingResponse {
+            transaction: response
+                .map(|transaction| language::proto::serialize_transaction(&transaction)),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::OnTypeFormattingResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Option<Transaction>> {
+        let Some(transaction) = message.transaction else {
+            return Ok(None);
+        };
+        Ok(Some(language::proto::deserialize_transaction(transaction)?))
+    }
+
+    fn buffer_id_from_proto(message: &proto::OnTypeFormatting) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl InlayHints {
+    pub async fn lsp_to_project_hint(
+        lsp_hint: lsp::InlayHint,
+        buffer_handle: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        resolve_state: ResolveState,
+        force_no_type_left_padding: bool,
+        cx: &mut AsyncApp,
+    ) -> anyhow::Result<InlayHint> {
+        let kind = lsp_hint.kind.and_then(|kind| match kind {
+            lsp::InlayHintKind::TYPE => Some(InlayHintKind::Type),
+            lsp::InlayHintKind::PARAMETER => Some(InlayHintKind::Parameter),
+            _ => None,
+        });
+
+        let position = buffer_handle.read_with(cx, |buffer, _| {
+            let position = buffer.clip_point_utf16(point_from_lsp(lsp_hint.position), Bias::Left);
+            if kind == Some(InlayHintKind::Parameter) {
+                buffer.anchor_before(position)
+            } else {
+                buffer.anchor_after(position)
+            }
+        })?;
+        let label = Self::lsp_inlay_label_to_project(lsp_hint.label, server_id)
+            .await
+            .context("lsp to project inlay hint conversion")?;
+        let padding_left = if force_no_type_left_padding && kind == Some(InlayHintKind::Type) {
+            false
+        } else {
+            lsp_hint.padding_left.unwrap_or(false)
+        };
+
+        Ok(InlayHint {
+            position,
+            padding_left,
             padding_right: lsp_hint.padding_right.unwrap_or(false),
             label,
             kind,
@@ -3269,200 +4270,773 @@ impl InlayHints {
         }
     }
 
-    pub fn can_resolve_inlays(capabilities: &ServerCapabilities) -> bool {
-        capabilities
-            .inlay_hint_provider
-            .as_ref()
-            .and_then(|options| match options {
-                OneOf::Left(_is_supported) => None,
-                OneOf::Right(capabilities) => match capabilities {
-                    lsp::InlayHintServerCapabilities::Options(o) => o.resolve_provider,
-                    lsp::InlayHintServerCapabilities::RegistrationOptions(o) => {
-                        o.inlay_hint_options.resolve_provider
-                    }
-                },
-            })
-            .unwrap_or(false)
+    pub fn can_resolve_inlays(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .inlay_hint_provider
+            .as_ref()
+            .and_then(|options| match options {
+                OneOf::Left(_is_supported) => None,
+                OneOf::Right(capabilities) => match capabilities {
+                    lsp::InlayHintServerCapabilities::Options(o) => o.resolve_provider,
+                    lsp::InlayHintServerCapabilities::RegistrationOptions(o) => {
+                        o.inlay_hint_options.resolve_provider
+                    }
+                },
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn check_capabilities(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .inlay_hint_provider
+            .as_ref()
+            .is_some_and(|inlay_hint_provider| match inlay_hint_provider {
+                lsp::OneOf::Left(enabled) => *enabled,
+                lsp::OneOf::Right(_) => true,
+            })
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for InlayHints {
+    type Response = Vec<InlayHint>;
+    type LspRequest = lsp::InlayHintRequest;
+    type ProtoRequest = proto::InlayHints;
+
+    fn display_name(&self) -> &str {
+        "Inlay hints"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        Self::check_capabilities(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::InlayHintParams> {
+        Ok(lsp::InlayHintParams {
+            text_document: lsp::TextDocumentIdentifier {
+                uri: file_path_to_lsp_url(path)?,
+            },
+            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::InlayHint>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<Vec<InlayHint>> {
+        let (lsp_adapter, lsp_server) =
+            language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
+        // `typescript-language-server` adds padding to the left for type hints, turning
+        // `const foo: boolean` into `const foo : boolean` which looks odd.
+        // `rust-analyzer` does not have the padding for this case, and we have to accommodate both.
+        //
+        // We could trim the whole string, but being pessimistic on par with the situation above,
+        // there might be a hint with multiple whitespaces at the end(s) which we need to display properly.
+        // Hence let's use a heuristic first to handle the most awkward case and look for more.
+        let force_no_type_left_padding =
+            lsp_adapter.name.0.as_ref() == "typescript-language-server";
+
+        let hints = message.unwrap_or_default().into_iter().map(|lsp_hint| {
+            let resolve_state = if InlayHints::can_resolve_inlays(&lsp_server.capabilities()) {
+                ResolveState::CanResolve(lsp_server.server_id(), lsp_hint.data.clone())
+            } else {
+                ResolveState::Resolved
+            };
+
+            let buffer = buffer.clone();
+            cx.spawn(async move |cx| {
+                InlayHints::lsp_to_project_hint(
+                    lsp_hint,
+                    &buffer,
+                    server_id,
+                    resolve_state,
+                    force_no_type_left_padding,
+                    cx,
+                )
+                .await
+            })
+        });
+        future::join_all(hints)
+            .await
+            .into_iter()
+            .collect::<anyhow::Result<_>>()
+            .context("lsp to project inlay hints conversion")
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::InlayHints {
+        proto::InlayHints {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            start: Some(language::proto::serialize_anchor(&self.range.start)),
+            end: Some(language::proto::serialize_anchor(&self.range.end)),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::InlayHints,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let start = message
+            .start
+            .and_then(language::proto::deserialize_anchor)
+            .context("invalid start")?;
+        let end = message
+            .end
+            .and_then(language::proto::deserialize_anchor)
+            .context("invalid end")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        Ok(Self { range: start..end })
+    }
+
+    fn response_to_proto(
+        response: Vec<InlayHint>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::InlayHintsResponse {
+        proto::InlayHintsResponse {
+            hints: response
+                .into_iter()
+                .map(InlayHints::project_to_proto_hint)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::InlayHintsResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<Vec<InlayHint>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+
+        let mut hints = Vec::new();
+        for message_hint in message.hints {
+            hints.push(InlayHints::proto_to_project_hint(message_hint)?);
+        }
+
+        Ok(hints)
     }
 
-    pub fn check_capabilities(capabilities: &ServerCapabilities) -> bool {
-        capabilities
-            .inlay_hint_provider
-            .as_ref()
-            .is_some_and(|inlay_hint_provider| match inlay_hint_provider {
-                lsp::OneOf::Left(enabled) => *enabled,
-                lsp::OneOf::Right(_) => true,
-            })
+    fn buffer_id_from_proto(message: &proto::InlayHints) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
     }
 }
 
 #[async_trait(?Send)]
-impl LspCommand for InlayHints {
-    type Response = Vec<InlayHint>;
-    type LspRequest = lsp::InlayHintRequest;
-    type ProtoRequest = proto::InlayHints;
+impl LspCommand for GetCodeLens {
+    type Response = Vec<CodeAction>;
+    type LspRequest = lsp::CodeLensRequest;
+    type ProtoRequest = proto::GetCodeLens;
 
     fn display_name(&self) -> &str {
-        "Inlay hints"
+        "Code Lens"
     }
 
     fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        Self::check_capabilities(&capabilities.server_capabilities)
+        capabilities
+            .server_capabilities
+            .code_lens_provider
+            .is_some()
     }
 
     fn to_lsp(
         &self,
         path: &Path,
-        buffer: &Buffer,
+        _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::InlayHintParams> {
-        Ok(lsp::InlayHintParams {
+    ) -> Result<lsp::CodeLensParams> {
+        Ok(lsp::CodeLensParams {
             text_document: lsp::TextDocumentIdentifier {
                 uri: file_path_to_lsp_url(path)?,
             },
-            range: range_to_lsp(self.range.to_point_utf16(buffer))?,
-            work_done_progress_params: Default::default(),
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<Vec<lsp::InlayHint>>,
+        message: Option<Vec<lsp::CodeLens>>,
         lsp_store: Entity<LspStore>,
         buffer: Entity<Buffer>,
         server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> anyhow::Result<Vec<CodeAction>> {
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        let language_server = cx.update(|cx| {
+            lsp_store
+                .read(cx)
+                .language_server_for_id(server_id)
+                .with_context(|| {
+                    format!("Missing the language server that just returned a response {server_id}")
+                })
+        })??;
+        let server_capabilities = language_server.capabilities();
+        let available_commands = server_capabilities
+            .execute_command_provider
+            .as_ref()
+            .map(|options| options.commands.as_slice())
+            .unwrap_or_default();
+        Ok(message
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|code_lens| {
+                code_lens
+                    .command
+                    .as_ref()
+                    .is_none_or(|command| available_commands.contains(&command.command))
+            })
+            .map(|code_lens| {
+                let code_lens_range = range_from_lsp(code_lens.range);
+                let start = snapshot.clip_point_utf16(code_lens_range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(code_lens_range.end, Bias::Right);
+                let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
+                CodeAction {
+                    server_id,
+                    range,
+                    lsp_action: LspAction::CodeLens(code_lens),
+                    resolved: false,
+                }
+            })
+            .collect())
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLens {
+        proto::GetCodeLens {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetCodeLens,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
         mut cx: AsyncApp,
-    ) -> anyhow::Result<Vec<InlayHint>> {
-        let (lsp_adapter, lsp_server) =
-            language_server_for_buffer(&lsp_store, &buffer, server_id, &mut cx)?;
-        // `typescript-language-server` adds padding to the left for type hints, turning
-        // `const foo: boolean` into `const foo : boolean` which looks odd.
-        // `rust-analyzer` does not have the padding for this case, and we have to accommodate both.
-        //
-        // We could trim the whole string, but being pessimistic on par with the situation above,
-        // there might be a hint with multiple whitespaces at the end(s) which we need to display properly.
-        // Hence let's use a heuristic first to handle the most awkward case and look for more.
-        let force_no_type_left_padding =
-            lsp_adapter.name.0.as_ref() == "typescript-language-server";
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
+    }
 
-        let hints = message.unwrap_or_default().into_iter().map(|lsp_hint| {
-            let resolve_state = if InlayHints::can_resolve_inlays(&lsp_server.capabilities()) {
-                ResolveState::CanResolve(lsp_server.server_id(), lsp_hint.data.clone())
-            } else {
-                ResolveState::Resolved
-            };
+    fn response_to_proto(
+        response: Vec<CodeAction>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetCodeLensResponse {
+        proto::GetCodeLensResponse {
+            lens_actions: response
+                .iter()
+                .map(LspStore::serialize_code_action)
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetCodeLensResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> anyhow::Result<Vec<CodeAction>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .lens_actions
+            .into_iter()
+            .map(LspStore::deserialize_code_action)
+            .collect::<Result<Vec<_>>>()
+            .context("deserializing proto code lens response")
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetCodeLens) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl LinkedEditingRange {
+    pub fn check_server_capabilities(capabilities: ServerCapabilities) -> bool {
+        let Some(linked_editing_options) = capabilities.linked_editing_range_provider else {
+            return false;
+        };
+        if let LinkedEditingRangeServerCapabilities::Simple(false) = linked_editing_options {
+            return false;
+        }
+        true
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for LinkedEditingRange {
+    type Response = Vec<Range<Anchor>>;
+    type LspRequest = lsp::request::LinkedEditingRange;
+    type ProtoRequest = proto::LinkedEditingRange;
+
+    fn display_name(&self) -> &str {
+        "Linked editing range"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        Self::check_server_capabilities(capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        buffer: &Buffer,
+        _server: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::LinkedEditingRangeParams> {
+        let position = self.position.to_point_utf16(&buffer.snapshot());
+        Ok(lsp::LinkedEditingRangeParams {
+            text_document_position_params: make_lsp_text_document_position(path, position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::LinkedEditingRanges>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<Range<Anchor>>> {
+        if let Some(lsp::LinkedEditingRanges { mut ranges, .. }) = message {
+            ranges.sort_by_key(|range| range.start);
 
-            let buffer = buffer.clone();
-            cx.spawn(async move |cx| {
-                InlayHints::lsp_to_project_hint(
-                    lsp_hint,
-                    &buffer,
-                    server_id,
-                    resolve_state,
-                    force_no_type_left_padding,
-                    cx,
-                )
-                .await
+            buffer.read_with(&cx, |buffer, _| {
+                ranges
+                    .into_iter()
+                    .map(|range| {
+                        let start =
+                            buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+                        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+                        buffer.anchor_before(start)..buffer.anchor_after(end)
+                    })
+                    .collect()
             })
-        });
-        future::join_all(hints)
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<_>>()
-            .context("lsp to project inlay hints conversion")
+        } else {
+            Ok(vec![])
+        }
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::InlayHints {
-        proto::InlayHints {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::LinkedEditingRange {
+        proto::LinkedEditingRange {
             project_id,
-            buffer_id: buffer.remote_id().into(),
-            start: Some(language::proto::serialize_anchor(&self.range.start)),
-            end: Some(language::proto::serialize_anchor(&self.range.end)),
+            buffer_id: buffer.remote_id().to_proto(),
+            position: Some(serialize_anchor(&self.position)),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        message: proto::InlayHints,
+        message: proto::LinkedEditingRange,
         _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         mut cx: AsyncApp,
     ) -> Result<Self> {
-        let start = message
-            .start
-            .and_then(language::proto::deserialize_anchor)
-            .context("invalid start")?;
-        let end = message
-            .end
-            .and_then(language::proto::deserialize_anchor)
-            .context("invalid end")?;
+        let position = message.position.context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let position = deserialize_anchor(position).context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors([position]))?
+            .await?;
+        Ok(Self { position })
+    }
+
+    fn response_to_proto(
+        response: Vec<Range<Anchor>>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::LinkedEditingRangeResponse {
+        proto::LinkedEditingRangeResponse {
+            items: response
+                .into_iter()
+                .map(|range| proto::AnchorRange {
+                    start: Some(serialize_anchor(&range.start)),
+                    end: Some(serialize_anchor(&range.end)),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::LinkedEditingRangeResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<Range<Anchor>>> {
         buffer
             .update(&mut cx, |buffer, _| {
                 buffer.wait_for_version(deserialize_version(&message.version))
             })?
             .await?;
+        let items: Vec<Range<Anchor>> = message
+            .items
+            .into_iter()
+            .filter_map(|range| {
+                let start = deserialize_anchor(range.start?)?;
+                let end = deserialize_anchor(range.end?)?;
+                Some(start..end)
+            })
+            .collect();
+        for range in &items {
+            buffer
+                .update(&mut cx, |buffer, _| {
+                    buffer.wait_for_anchors([range.start, range.end])
+                })?
+                .await?;
+        }
+        Ok(items)
+    }
+
+    fn buffer_id_from_proto(message: &proto::LinkedEditingRange) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+impl GetDocumentDiagnostics {
+    pub fn diagnostics_from_proto(
+        response: proto::GetDocumentDiagnosticsResponse,
+    ) -> Vec<LspPullDiagnostics> {
+        response
+            .pulled_diagnostics
+            .into_iter()
+            .filter_map(|diagnostics| {
+                Some(LspPullDiagnostics::Response {
+                    server_id: LanguageServerId::from_proto(diagnostics.server_id),
+                    uri: lsp::Uri::from_str(diagnostics.uri.as_str()).log_err()?,
+                    diagnostics: if diagnostics.changed {
+                        PulledDiagnostics::Unchanged {
+                            result_id: diagnostics.result_id?,
+                        }
+                    } else {
+                        PulledDiagnostics::Changed {
+                            result_id: diagnostics.result_id,
+                            diagnostics: diagnostics
+                                .diagnostics
+                                .into_iter()
+                                .filter_map(|diagnostic| {
+                                    GetDocumentDiagnostics::deserialize_lsp_diagnostic(diagnostic)
+                                        .context("deserializing diagnostics")
+                                        .log_err()
+                                })
+                                .collect(),
+                        }
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn deserialize_lsp_diagnostic(diagnostic: proto::LspDiagnostic) -> Result<lsp::Diagnostic> {
+        let start = diagnostic.start.context("invalid start range")?;
+        let end = diagnostic.end.context("invalid end range")?;
+
+        let range = Range::<PointUtf16> {
+            start: PointUtf16 {
+                row: start.row,
+                column: start.column,
+            },
+            end: PointUtf16 {
+                row: end.row,
+                column: end.column,
+            },
+        };
+
+        let data = diagnostic.data.and_then(|data| Value::from_str(&data).ok());
+        let code = diagnostic.code.map(lsp::NumberOrString::String);
+
+        let related_information = diagnostic
+            .related_information
+            .into_iter()
+            .map(|info| {
+                let start = info.location_range_start.unwrap();
+                let end = info.location_range_end.unwrap();
+
+                lsp::DiagnosticRelatedInformation {
+                    location: lsp::Location {
+                        range: lsp::Range {
+                            start: point_to_lsp(PointUtf16::new(start.row, start.column)),
+                            end: point_to_lsp(PointUtf16::new(end.row, end.column)),
+                        },
+                        uri: lsp::Uri::from_str(&info.location_url.unwrap()).unwrap(),
+                    },
+                    message: info.message,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let tags = diagnostic
+            .tags
+            .into_iter()
+            .filter_map(|tag| match proto::LspDiagnosticTag::from_i32(tag) {
+                Some(proto::LspDiagnosticTag::Unnecessary) => Some(lsp::DiagnosticTag::UNNECESSARY),
+                Some(proto::LspDiagnosticTag::Deprecated) => Some(lsp::DiagnosticTag::DEPRECATED),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(lsp::Diagnostic {
+            range: language::range_to_lsp(range)?,
+            severity: match proto::lsp_diagnostic::Severity::from_i32(diagnostic.severity).unwrap()
+            {
+                proto::lsp_diagnostic::Severity::Error => Some(lsp::DiagnosticSeverity::ERROR),
+                proto::lsp_diagnostic::Severity::Warning => Some(lsp::DiagnosticSeverity::WARNING),
+                proto::lsp_diagnostic::Severity::Information => {
+                    Some(lsp::DiagnosticSeverity::INFORMATION)
+                }
+                proto::lsp_diagnostic::Severity::Hint => Some(lsp::DiagnosticSeverity::HINT),
+                _ => None,
+            },
+            code,
+            code_description: diagnostic
+                .code_description
+                .map(|code_description| CodeDescription {
+                    href: Some(lsp::Uri::from_str(&code_description).unwrap()),
+                }),
+            related_information: Some(related_information),
+            tags: Some(tags),
+            source: diagnostic.source.clone(),
+            message: diagnostic.message,
+            data,
+        })
+    }
+
+    fn serialize_lsp_diagnostic(diagnostic: lsp::Diagnostic) -> Result<proto::LspDiagnostic> {
+        let range = language::range_from_lsp(diagnostic.range);
+        let related_information = diagnostic
+            .related_information
+            .unwrap_or_default()
+            .into_iter()
+            .map(|related_information| {
+                let location_range_start =
+                    point_from_lsp(related_information.location.range.start).0;
+                let location_range_end = point_from_lsp(related_information.location.range.end).0;
+
+                Ok(proto::LspDiagnosticRelatedInformation {
+                    location_url: Some(related_information.location.uri.to_string()),
+                    location_range_start: Some(proto::PointUtf16 {
+                        row: location_range_start.row,
+                        column: location_range_start.column,
+                    }),
+                    location_range_end: Some(proto::PointUtf16 {
+                        row: location_range_end.row,
+                        column: location_range_end.column,
+                    }),
+                    message: related_information.message,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let tags = diagnostic
+            .tags
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tag| match tag {
+                lsp::DiagnosticTag::UNNECESSARY => proto::LspDiagnosticTag::Unnecessary,
+                lsp::DiagnosticTag::DEPRECATED => proto::LspDiagnosticTag::Deprecated,
+                _ => proto::LspDiagnosticTag::None,
+            } as i32)
+            .collect();
 
-        Ok(Self { range: start..end })
+        Ok(proto::LspDiagnostic {
+            start: Some(proto::PointUtf16 {
+                row: range.start.0.row,
+                column: range.start.0.column,
+            }),
+            end: Some(proto::PointUtf16 {
+                row: range.end.0.row,
+                column: range.end.0.column,
+            }),
+            severity: match diagnostic.severity {
+                Some(lsp::DiagnosticSeverity::ERROR) => proto::lsp_diagnostic::Severity::Error,
+                Some(lsp::DiagnosticSeverity::WARNING) => proto::lsp_diagnostic::Severity::Warning,
+                Some(lsp::DiagnosticSeverity::INFORMATION) => {
+                    proto::lsp_diagnostic::Severity::Information
+                }
+                Some(lsp::DiagnosticSeverity::HINT) => proto::lsp_diagnostic::Severity::Hint,
+                _ => proto::lsp_diagnostic::Severity::None,
+            } as i32,
+            code: diagnostic.code.as_ref().map(|code| match code {
+                lsp::NumberOrString::Number(code) => code.to_string(),
+                lsp::NumberOrString::String(code) => code.clone(),
+            }),
+            source: diagnostic.source.clone(),
+            related_information,
+            tags,
+            code_description: diagnostic
+                .code_description
+                .and_then(|desc| desc.href.map(|url| url.to_string())),
+            message: diagnostic.message,
+            data: diagnostic.data.as_ref().map(|data| data.to_string()),
+        })
     }
 
-    fn response_to_proto(
-        response: Vec<InlayHint>,
-        _: &mut LspStore,
-        _: PeerId,
-        buffer_version: &clock::Global,
-        _: &mut App,
-    ) -> proto::InlayHintsResponse {
-        proto::InlayHintsResponse {
-            hints: response
-                .into_iter()
-                .map(InlayHints::project_to_proto_hint)
-                .collect(),
-            version: serialize_version(buffer_version),
+    pub fn deserialize_workspace_diagnostics_report(
+        report: lsp::WorkspaceDiagnosticReportResult,
+        server_id: LanguageServerId,
+    ) -> Vec<WorkspaceLspPullDiagnostics> {
+        let mut pulled_diagnostics = HashMap::default();
+        match report {
+            lsp::WorkspaceDiagnosticReportResult::Report(workspace_diagnostic_report) => {
+                for report in workspace_diagnostic_report.items {
+                    match report {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                            process_full_workspace_diagnostics_report(
+                                &mut pulled_diagnostics,
+                                server_id,
+                                report,
+                            )
+                        }
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
+                            process_unchanged_workspace_diagnostics_report(
+                                &mut pulled_diagnostics,
+                                server_id,
+                                report,
+                            )
+                        }
+                    }
+                }
+            }
+            lsp::WorkspaceDiagnosticReportResult::Partial(
+                workspace_diagnostic_report_partial_result,
+            ) => {
+                for report in workspace_diagnostic_report_partial_result.items {
+                    match report {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                            process_full_workspace_diagnostics_report(
+                                &mut pulled_diagnostics,
+                                server_id,
+                                report,
+                            )
+                        }
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
+                            process_unchanged_workspace_diagnostics_report(
+                                &mut pulled_diagnostics,
+                                server_id,
+                                report,
+                            )
+                        }
+                    }
+                }
+            }
         }
+        pulled_diagnostics.into_values().collect()
     }
+}
 
-    async fn response_from_proto(
-        self,
-        message: proto::InlayHintsResponse,
-        _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        mut cx: AsyncApp,
-    ) -> anyhow::Result<Vec<InlayHint>> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-
-        let mut hints = Vec::new();
-        for message_hint in message.hints {
-            hints.push(InlayHints::proto_to_project_hint(message_hint)?);
-        }
+#[derive(Debug)]
+pub struct WorkspaceLspPullDiagnostics {
+    pub version: Option<i32>,
+    pub diagnostics: LspPullDiagnostics,
+}
 
-        Ok(hints)
-    }
+fn process_full_workspace_diagnostics_report(
+    diagnostics: &mut HashMap<lsp::Uri, WorkspaceLspPullDiagnostics>,
+    server_id: LanguageServerId,
+    report: lsp::WorkspaceFullDocumentDiagnosticReport,
+) {
+    let mut new_diagnostics = HashMap::default();
+    process_full_diagnostics_report(
+        &mut new_diagnostics,
+        server_id,
+        report.uri,
+        report.full_document_diagnostic_report,
+    );
+    diagnostics.extend(new_diagnostics.into_iter().map(|(uri, diagnostics)| {
+        (
+            uri,
+            WorkspaceLspPullDiagnostics {
+                version: report.version.map(|v| v as i32),
+                diagnostics,
+            },
+        )
+    }));
+}
 
-    fn buffer_id_from_proto(message: &proto::InlayHints) -> Result<BufferId> {
-        BufferId::new(message.buffer_id)
-    }
+fn process_unchanged_workspace_diagnostics_report(
+    diagnostics: &mut HashMap<lsp::Uri, WorkspaceLspPullDiagnostics>,
+    server_id: LanguageServerId,
+    report: lsp::WorkspaceUnchangedDocumentDiagnosticReport,
+) {
+    let mut new_diagnostics = HashMap::default();
+    process_unchanged_diagnostics_report(
+        &mut new_diagnostics,
+        server_id,
+        report.uri,
+        report.unchanged_document_diagnostic_report,
+    );
+    diagnostics.extend(new_diagnostics.into_iter().map(|(uri, diagnostics)| {
+        (
+            uri,
+            WorkspaceLspPullDiagnostics {
+                version: report.version.map(|v| v as i32),
+                diagnostics,
+            },
+        )
+    }));
 }
 
 #[async_trait(?Send)]
-impl LspCommand for GetCodeLens {
-    type Response = Vec<CodeAction>;
-    type LspRequest = lsp::CodeLensRequest;
-    type ProtoRequest = proto::GetCodeLens;
+impl LspCommand for GetDocumentDiagnostics {
+    type Response = Vec<LspPullDiagnostics>;
+    type LspRequest = lsp::request::DocumentDiagnosticRequest;
+    type ProtoRequest = proto::GetDocumentDiagnostics;
 
     fn display_name(&self) -> &str {
-        "Code Lens"
+        "Get diagnostics"
     }
 
-    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        capabilities
-            .server_capabilities
-            .code_lens_provider
-            .is_some()
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
     }
 
     fn to_lsp(
@@ -3471,65 +5045,95 @@ impl LspCommand for GetCodeLens {
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::CodeLensParams> {
-        Ok(lsp::CodeLensParams {
+    ) -> Result<lsp::DocumentDiagnosticParams> {
+        let identifier = match &self.dynamic_caps {
+            lsp::DiagnosticServerCapabilities::Options(options) => options.identifier.clone(),
+            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+                options.diagnostic_options.identifier.clone()
+            }
+        };
+
+        Ok(lsp::DocumentDiagnosticParams {
             text_document: lsp::TextDocumentIdentifier {
                 uri: file_path_to_lsp_url(path)?,
             },
-            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
-            partial_result_params: lsp::PartialResultParams::default(),
+            identifier,
+            previous_result_id: self.previous_result_id.clone(),
+            partial_result_params: Default::default(),
+            work_done_progress_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<Vec<lsp::CodeLens>>,
-        lsp_store: Entity<LspStore>,
+        message: lsp::DocumentDiagnosticReportResult,
+        _: Entity<LspStore>,
         buffer: Entity<Buffer>,
         server_id: LanguageServerId,
         cx: AsyncApp,
-    ) -> anyhow::Result<Vec<CodeAction>> {
-        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
-        let language_server = cx.update(|cx| {
-            lsp_store
-                .read(cx)
-                .language_server_for_id(server_id)
-                .with_context(|| {
-                    format!("Missing the language server that just returned a response {server_id}")
+    ) -> Result<Self::Response> {
+        let url = buffer.read_with(&cx, |buffer, cx| {
+            buffer
+                .file()
+                .and_then(|file| file.as_local())
+                .map(|file| {
+                    let abs_path = file.abs_path(cx);
+                    file_path_to_lsp_url(&abs_path)
                 })
-        })??;
-        let server_capabilities = language_server.capabilities();
-        let available_commands = server_capabilities
-            .execute_command_provider
-            .as_ref()
-            .map(|options| options.commands.as_slice())
-            .unwrap_or_default();
-        Ok(message
-            .unwrap_or_default()
-            .into_iter()
-            .filter(|code_lens| {
-                code_lens
-                    .command
-                    .as_ref()
-                    .is_none_or(|command| available_commands.contains(&command.command))
-            })
-            .map(|code_lens| {
-                let code_lens_range = range_from_lsp(code_lens.range);
-                let start = snapshot.clip_point_utf16(code_lens_range.start, Bias::Left);
-                let end = snapshot.clip_point_utf16(code_lens_range.end, Bias::Right);
-                let range = snapshot.anchor_before(start)..snapshot.anchor_after(end);
-                CodeAction {
-                    server_id,
-                    range,
-                    lsp_action: LspAction::CodeLens(code_lens),
-                    resolved: false,
+                .transpose()?
+                .with_context(|| format!("missing url on buffer {}", buffer.remote_id()))
+        })??;
+
+        let mut pulled_diagnostics = HashMap::default();
+        match message {
+            lsp::DocumentDiagnosticReportResult::Report(report) => match report {
+                lsp::DocumentDiagnosticReport::Full(report) => {
+                    if let Some(related_documents) = report.related_documents {
+                        process_related_documents(
+                            &mut pulled_diagnostics,
+                            server_id,
+                            related_documents,
+                        );
+                    }
+                    process_full_diagnostics_report(
+                        &mut pulled_diagnostics,
+                        server_id,
+                        url,
+                        report.full_document_diagnostic_report,
+                    );
                 }
-            })
-            .collect())
+                lsp::DocumentDiagnosticReport::Unchanged(report) => {
+                    if let Some(related_documents) = report.related_documents {
+                        process_related_documents(
+                            &mut pulled_diagnostics,
+                            server_id,
+                            related_documents,
+                        );
+                    }
+                    process_unchanged_diagnostics_report(
+                        &mut pulled_diagnostics,
+                        server_id,
+                        url,
+                        report.unchanged_document_diagnostic_report,
+                    );
+                }
+            },
+            lsp::DocumentDiagnosticReportResult::Partial(report) => {
+                if let Some(related_documents) = report.related_documents {
+                    process_related_documents(
+                        &mut pulled_diagnostics,
+                        server_id,
+                        related_documents,
+                    );
+                }
+            }
+        }
+
+        Ok(pulled_diagnostics.into_values().collect())
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetCodeLens {
-        proto::GetCodeLens {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentDiagnostics {
+        proto::GetDocumentDiagnostics {
             project_id,
             buffer_id: buffer.remote_id().into(),
             version: serialize_version(&buffer.version()),
@@ -3537,168 +5141,178 @@ impl LspCommand for GetCodeLens {
     }
 
     async fn from_proto(
-        message: proto::GetCodeLens,
+        _: proto::GetDocumentDiagnostics,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        mut cx: AsyncApp,
+        _: Entity<Buffer>,
+        _: AsyncApp,
     ) -> Result<Self> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-        Ok(Self)
+        anyhow::bail!(
+            "proto::GetDocumentDiagnostics is not expected to be converted from proto directly, as it needs `previous_result_id` fetched first"
+        )
     }
 
     fn response_to_proto(
-        response: Vec<CodeAction>,
+        response: Self::Response,
         _: &mut LspStore,
         _: PeerId,
-        buffer_version: &clock::Global,
+        _: &clock::Global,
         _: &mut App,
-    ) -> proto::GetCodeLensResponse {
-        proto::GetCodeLensResponse {
-            lens_actions: response
-                .iter()
-                .map(LspStore::serialize_code_action)
-                .collect(),
-            version: serialize_version(buffer_version),
-        }
+    ) -> proto::GetDocumentDiagnosticsResponse {
+        let pulled_diagnostics = response
+            .into_iter()
+            .filter_map(|diagnostics| match diagnostics {
+                LspPullDiagnostics::Default => None,
+                LspPullDiagnostics::Response {
+                    server_id,
+                    uri,
+                    diagnostics,
+                } => {
+                    let mut changed = false;
+                    let (diagnostics, result_id) = match diagnostics {
+                        PulledDiagnostics::Unchanged { result_id } => (Vec::new(), Some(result_id)),
+                        PulledDiagnostics::Changed {
+                            result_id,
+                            diagnostics,
+                        } => {
+                            changed = true;
+                            (diagnostics, result_id)
+                        }
+                    };
+                    Some(proto::PulledDiagnostics {
+                        changed,
+                        result_id,
+                        uri: uri.to_string(),
+                        server_id: server_id.to_proto(),
+                        diagnostics: diagnostics
+                            .into_iter()
+                            .filter_map(|diagnostic| {
+                                GetDocumentDiagnostics::serialize_lsp_diagnostic(diagnostic)
+                                    .context("serializing diagnostics")
+                                    .log_err()
+                            })
+                            .collect(),
+                    })
+                }
+            })
+            .collect();
+
+        proto::GetDocumentDiagnosticsResponse { pulled_diagnostics }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetCodeLensResponse,
+        response: proto::GetDocumentDiagnosticsResponse,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        mut cx: AsyncApp,
-    ) -> anyhow::Result<Vec<CodeAction>> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-        message
-            .lens_actions
-            .into_iter()
-            .map(LspStore::deserialize_code_action)
-            .collect::<Result<Vec<_>>>()
-            .context("deserializing proto code lens response")
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(Self::diagnostics_from_proto(response))
     }
 
-    fn buffer_id_from_proto(message: &proto::GetCodeLens) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &proto::GetDocumentDiagnostics) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-impl LinkedEditingRange {
-    pub fn check_server_capabilities(capabilities: ServerCapabilities) -> bool {
-        let Some(linked_editing_options) = capabilities.linked_editing_range_provider else {
-            return false;
-        };
-        if let LinkedEditingRangeServerCapabilities::Simple(false) = linked_editing_options {
-            return false;
-        }
-        true
-    }
-}
-
 #[async_trait(?Send)]
-impl LspCommand for LinkedEditingRange {
-    type Response = Vec<Range<Anchor>>;
-    type LspRequest = lsp::request::LinkedEditingRange;
-    type ProtoRequest = proto::LinkedEditingRange;
+impl LspCommand for GetDocumentColor {
+    type Response = Vec<DocumentColor>;
+    type LspRequest = lsp::request::DocumentColor;
+    type ProtoRequest = proto::GetDocumentColor;
 
     fn display_name(&self) -> &str {
-        "Linked editing range"
+        "Document color"
     }
 
-    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
-        Self::check_server_capabilities(capabilities.server_capabilities)
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .color_provider
+            .as_ref()
+            .is_some_and(|capability| match capability {
+                lsp::ColorProviderCapability::Simple(supported) => *supported,
+                lsp::ColorProviderCapability::ColorProvider(..) => true,
+                lsp::ColorProviderCapability::Options(..) => true,
+            })
     }
 
     fn to_lsp(
         &self,
         path: &Path,
-        buffer: &Buffer,
-        _server: &Arc<LanguageServer>,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::LinkedEditingRangeParams> {
-        let position = self.position.to_point_utf16(&buffer.snapshot());
-        Ok(lsp::LinkedEditingRangeParams {
-            text_document_position_params: make_lsp_text_document_position(path, position)?,
+    ) -> Result<lsp::DocumentColorParams> {
+        Ok(lsp::DocumentColorParams {
+            text_document: make_text_document_identifier(path)?,
             work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: Option<lsp::LinkedEditingRanges>,
+        message: Vec<lsp::ColorInformation>,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        _server_id: LanguageServerId,
-        cx: AsyncApp,
-    ) -> Result<Vec<Range<Anchor>>> {
-        if let Some(lsp::LinkedEditingRanges { mut ranges, .. }) = message {
-            ranges.sort_by_key(|range| range.start);
-
-            buffer.read_with(&cx, |buffer, _| {
-                ranges
-                    .into_iter()
-                    .map(|range| {
-                        let start =
-                            buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
-                        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
-                        buffer.anchor_before(start)..buffer.anchor_after(end)
-                    })
-                    .collect()
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(message
+            .into_iter()
+            .map(|color| DocumentColor {
+                lsp_range: color.range,
+                color: color.color,
+                resolved: false,
+                color_presentations: Vec::new(),
             })
-        } else {
-            Ok(vec![])
-        }
+            .collect())
     }
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::LinkedEditingRange {
-        proto::LinkedEditingRange {
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetDocumentColor {
             project_id,
             buffer_id: buffer.remote_id().to_proto(),
-            position: Some(serialize_anchor(&self.position)),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        message: proto::LinkedEditingRange,
+        _: Self::ProtoRequest,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        mut cx: AsyncApp,
+        _: Entity<Buffer>,
+        _: AsyncApp,
     ) -> Result<Self> {
-        let position = message.position.context("invalid position")?;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-        let position = deserialize_anchor(position).context("invalid position")?;
-        buffer
-            .update(&mut cx, |buffer, _| buffer.wait_for_anchors([position]))?
-            .await?;
-        Ok(Self { position })
+        Ok(Self {})
     }
 
     fn response_to_proto(
-        response: Vec<Range<Anchor>>,
+        response: Self::Response,
         _: &mut LspStore,
         _: PeerId,
         buffer_version: &clock::Global,
         _: &mut App,
-    ) -> proto::LinkedEditingRangeResponse {
-        proto::LinkedEditingRangeResponse {
-            items: response
+    ) -> proto::GetDocumentColorResponse {
+        proto::GetDocumentColorResponse {
+            colors: response
                 .into_iter()
-                .map(|range| proto::AnchorRange {
-                    start: Some(serialize_anchor(&range.start)),
-                    end: Some(serialize_anchor(&range.end)),
+                .map(|color| {
+                    let start = point_from_lsp(color.lsp_range.start).0;
+                    let end = point_from_lsp(color.lsp_range.end).0;
+                    proto::ColorInformation {
+                        red: color.color.red,
+                        green: color.color.green,
+                        blue: color.color.blue,
+                        alpha: color.color.alpha,
+                        lsp_range_start: Some(proto::PointUtf16 {
+                            row: start.row,
+                            column: start.column,
+                        }),
+                        lsp_range_end: Some(proto::PointUtf16 {
+                            row: end.row,
+                            column: end.column,
+                        }),
+                    }
                 })
                 .collect(),
             version: serialize_version(buffer_version),
@@ -3707,335 +5321,151 @@ impl LspCommand for LinkedEditingRange {
 
     async fn response_from_proto(
         self,
-        message: proto::LinkedEditingRangeResponse,
+        message: proto::GetDocumentColorResponse,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        mut cx: AsyncApp,
-    ) -> Result<Vec<Range<Anchor>>> {
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(deserialize_version(&message.version))
-            })?
-            .await?;
-        let items: Vec<Range<Anchor>> = message
-            .items
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(message
+            .colors
             .into_iter()
-            .filter_map(|range| {
-                let start = deserialize_anchor(range.start?)?;
-                let end = deserialize_anchor(range.end?)?;
-                Some(start..end)
+            .filter_map(|color| {
+                let start = color.lsp_range_start?;
+                let start = PointUtf16::new(start.row, start.column);
+                let end = color.lsp_range_end?;
+                let end = PointUtf16::new(end.row, end.column);
+                Some(DocumentColor {
+                    resolved: false,
+                    color_presentations: Vec::new(),
+                    lsp_range: lsp::Range {
+                        start: point_to_lsp(start),
+                        end: point_to_lsp(end),
+                    },
+                    color: lsp::Color {
+                        red: color.red,
+                        green: color.green,
+                        blue: color.blue,
+                        alpha: color.alpha,
+                    },
+                })
             })
-            .collect();
-        for range in &items {
-            buffer
-                .update(&mut cx, |buffer, _| {
-                    buffer.wait_for_anchors([range.start, range.end])
-                })?
-                .await?;
-        }
-        Ok(items)
+            .collect())
     }
 
-    fn buffer_id_from_proto(message: &proto::LinkedEditingRange) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
-impl GetDocumentDiagnostics {
-    pub fn diagnostics_from_proto(
-        response: proto::GetDocumentDiagnosticsResponse,
-    ) -> Vec<LspPullDiagnostics> {
-        response
-            .pulled_diagnostics
-            .into_iter()
-            .filter_map(|diagnostics| {
-                Some(LspPullDiagnostics::Response {
-                    server_id: LanguageServerId::from_proto(diagnostics.server_id),
-                    uri: lsp::Uri::from_str(diagnostics.uri.as_str()).log_err()?,
-                    diagnostics: if diagnostics.changed {
-                        PulledDiagnostics::Unchanged {
-                            result_id: diagnostics.result_id?,
-                        }
-                    } else {
-                        PulledDiagnostics::Changed {
-                            result_id: diagnostics.result_id,
-                            diagnostics: diagnostics
-                                .diagnostics
-                                .into_iter()
-                                .filter_map(|diagnostic| {
-                                    GetDocumentDiagnostics::deserialize_lsp_diagnostic(diagnostic)
-                                        .context("deserializing diagnostics")
-                                        .log_err()
-                                })
-                                .collect(),
-                        }
-                    },
-                })
-            })
-            .collect()
-    }
-
-    fn deserialize_lsp_diagnostic(diagnostic: proto::LspDiagnostic) -> Result<lsp::Diagnostic> {
-        let start = diagnostic.start.context("invalid start range")?;
-        let end = diagnostic.end.context("invalid end range")?;
-
-        let range = Range::<PointUtf16> {
-            start: PointUtf16 {
-                row: start.row,
-                column: start.column,
-            },
-            end: PointUtf16 {
-                row: end.row,
-                column: end.column,
-            },
-        };
-
-        let data = diagnostic.data.and_then(|data| Value::from_str(&data).ok());
-        let code = diagnostic.code.map(lsp::NumberOrString::String);
-
-        let related_information = diagnostic
-            .related_information
-            .into_iter()
-            .map(|info| {
-                let start = info.location_range_start.unwrap();
-                let end = info.location_range_end.unwrap();
+#[async_trait(?Send)]
+impl LspCommand for GetSemanticTokens {
+    type Response = RawSemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
 
-                lsp::DiagnosticRelatedInformation {
-                    location: lsp::Location {
-                        range: lsp::Range {
-                            start: point_to_lsp(PointUtf16::new(start.row, start.column)),
-                            end: point_to_lsp(PointUtf16::new(end.row, end.column)),
-                        },
-                        uri: lsp::Uri::from_str(&info.location_url.unwrap()).unwrap(),
-                    },
-                    message: info.message,
-                }
-            })
-            .collect::<Vec<_>>();
+    fn display_name(&self) -> &str {
+        "Semantic tokens"
+    }
 
-        let tags = diagnostic
-            .tags
-            .into_iter()
-            .filter_map(|tag| match proto::LspDiagnosticTag::from_i32(tag) {
-                Some(proto::LspDiagnosticTag::Unnecessary) => Some(lsp::DiagnosticTag::UNNECESSARY),
-                Some(proto::LspDiagnosticTag::Deprecated) => Some(lsp::DiagnosticTag::DEPRECATED),
-                _ => None,
-            })
-            .collect::<Vec<_>>();
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        semantic_tokens_full_support(&server_capabilities.server_capabilities).is_some()
+    }
 
-        Ok(lsp::Diagnostic {
-            range: language::range_to_lsp(range)?,
-            severity: match proto::lsp_diagnostic::Severity::from_i32(diagnostic.severity).unwrap()
-            {
-                proto::lsp_diagnostic::Severity::Error => Some(lsp::DiagnosticSeverity::ERROR),
-                proto::lsp_diagnostic::Severity::Warning => Some(lsp::DiagnosticSeverity::WARNING),
-                proto::lsp_diagnostic::Severity::Information => {
-                    Some(lsp::DiagnosticSeverity::INFORMATION)
-                }
-                proto::lsp_diagnostic::Severity::Hint => Some(lsp::DiagnosticSeverity::HINT),
-                _ => None,
-            },
-            code,
-            code_description: diagnostic
-                .code_description
-                .map(|code_description| CodeDescription {
-                    href: Some(lsp::Uri::from_str(&code_description).unwrap()),
-                }),
-            related_information: Some(related_information),
-            tags: Some(tags),
-            source: diagnostic.source.clone(),
-            message: diagnostic.message,
-            data,
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SemanticTokensParams> {
+        Ok(lsp::SemanticTokensParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
         })
     }
 
-    fn serialize_lsp_diagnostic(diagnostic: lsp::Diagnostic) -> Result<proto::LspDiagnostic> {
-        let range = language::range_from_lsp(diagnostic.range);
-        let related_information = diagnostic
-            .related_information
-            .unwrap_or_default()
-            .into_iter()
-            .map(|related_information| {
-                let location_range_start =
-                    point_from_lsp(related_information.location.range.start).0;
-                let location_range_end = point_from_lsp(related_information.location.range.end).0;
-
-                Ok(proto::LspDiagnosticRelatedInformation {
-                    location_url: Some(related_information.location.uri.to_string()),
-                    location_range_start: Some(proto::PointUtf16 {
-                        row: location_range_start.row,
-                        column: location_range_start.column,
-                    }),
-                    location_range_end: Some(proto::PointUtf16 {
-                        row: location_range_end.row,
-                        column: location_range_end.column,
-                    }),
-                    message: related_information.message,
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        let tags = diagnostic
-            .tags
-            .unwrap_or_default()
-            .into_iter()
-            .map(|tag| match tag {
-                lsp::DiagnosticTag::UNNECESSARY => proto::LspDiagnosticTag::Unnecessary,
-                lsp::DiagnosticTag::DEPRECATED => proto::LspDiagnosticTag::Deprecated,
-                _ => proto::LspDiagnosticTag::None,
-            } as i32)
-            .collect();
-
-        Ok(proto::LspDiagnostic {
-            start: Some(proto::PointUtf16 {
-                row: range.start.0.row,
-                column: range.start.0.column,
-            }),
-            end: Some(proto::PointUtf16 {
-                row: range.end.0.row,
-                column: range.end.0.column,
-            }),
-            severity: match diagnostic.severity {
-                Some(lsp::DiagnosticSeverity::ERROR) => proto::lsp_diagnostic::Severity::Error,
-                Some(lsp::DiagnosticSeverity::WARNING) => proto::lsp_diagnostic::Severity::Warning,
-                Some(lsp::DiagnosticSeverity::INFORMATION) => {
-                    proto::lsp_diagnostic::Severity::Information
-                }
-                Some(lsp::DiagnosticSeverity::HINT) => proto::lsp_diagnostic::Severity::Hint,
-                _ => proto::lsp_diagnostic::Severity::None,
-            } as i32,
-            code: diagnostic.code.as_ref().map(|code| match code {
-                lsp::NumberOrString::Number(code) => code.to_string(),
-                lsp::NumberOrString::String(code) => code.clone(),
-            }),
-            source: diagnostic.source.clone(),
-            related_information,
-            tags,
-            code_description: diagnostic
-                .code_description
-                .and_then(|desc| desc.href.map(|url| url.to_string())),
-            message: diagnostic.message,
-            data: diagnostic.data.as_ref().map(|data| data.to_string()),
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SemanticTokensResult>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(match message {
+            Some(lsp::SemanticTokensResult::Tokens(tokens)) => RawSemanticTokens::from_lsp(tokens),
+            Some(lsp::SemanticTokensResult::Partial(partial)) => RawSemanticTokens {
+                result_id: None,
+                data: flatten_semantic_tokens(partial.data),
+            },
+            None => RawSemanticTokens::default(),
         })
     }
 
-    pub fn deserialize_workspace_diagnostics_report(
-        report: lsp::WorkspaceDiagnosticReportResult,
-        server_id: LanguageServerId,
-    ) -> Vec<WorkspaceLspPullDiagnostics> {
-        let mut pulled_diagnostics = HashMap::default();
-        match report {
-            lsp::WorkspaceDiagnosticReportResult::Report(workspace_diagnostic_report) => {
-                for report in workspace_diagnostic_report.items {
-                    match report {
-                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
-                            process_full_workspace_diagnostics_report(
-                                &mut pulled_diagnostics,
-                                server_id,
-                                report,
-                            )
-                        }
-                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
-                            process_unchanged_workspace_diagnostics_report(
-                                &mut pulled_diagnostics,
-                                server_id,
-                                report,
-                            )
-                        }
-                    }
-                }
-            }
-            lsp::WorkspaceDiagnosticReportResult::Partial(
-                workspace_diagnostic_report_partial_result,
-            ) => {
-                for report in workspace_diagnostic_report_partial_result.items {
-                    match report {
-                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
-                            process_full_workspace_diagnostics_report(
-                                &mut pulled_diagnostics,
-                                server_id,
-                                report,
-                            )
-                        }
-                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
-                            process_unchanged_workspace_diagnostics_report(
-                                &mut pulled_diagnostics,
-                                server_id,
-                                report,
-                            )
-                        }
-                    }
-                }
-            }
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
         }
-        pulled_diagnostics.into_values().collect()
     }
-}
 
-#[derive(Debug)]
-pub struct WorkspaceLspPullDiagnostics {
-    pub version: Option<i32>,
-    pub diagnostics: LspPullDiagnostics,
-}
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        Ok(Self)
+    }
 
-fn process_full_workspace_diagnostics_report(
-    diagnostics: &mut HashMap<lsp::Uri, WorkspaceLspPullDiagnostics>,
-    server_id: LanguageServerId,
-    report: lsp::WorkspaceFullDocumentDiagnosticReport,
-) {
-    let mut new_diagnostics = HashMap::default();
-    process_full_diagnostics_report(
-        &mut new_diagnostics,
-        server_id,
-        report.uri,
-        report.full_document_diagnostic_report,
-    );
-    diagnostics.extend(new_diagnostics.into_iter().map(|(uri, diagnostics)| {
-        (
-            uri,
-            WorkspaceLspPullDiagnostics {
-                version: report.version.map(|v| v as i32),
-                diagnostics,
-            },
-        )
-    }));
-}
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            result_id: response.result_id,
+            data: response.data,
+            version: serialize_version(buffer_version),
+        }
+    }
 
-fn process_unchanged_workspace_diagnostics_report(
-    diagnostics: &mut HashMap<lsp::Uri, WorkspaceLspPullDiagnostics>,
-    server_id: LanguageServerId,
-    report: lsp::WorkspaceUnchangedDocumentDiagnosticReport,
-) {
-    let mut new_diagnostics = HashMap::default();
-    process_unchanged_diagnostics_report(
-        &mut new_diagnostics,
-        server_id,
-        report.uri,
-        report.unchanged_document_diagnostic_report,
-    );
-    diagnostics.extend(new_diagnostics.into_iter().map(|(uri, diagnostics)| {
-        (
-            uri,
-            WorkspaceLspPullDiagnostics {
-                version: report.version.map(|v| v as i32),
-                diagnostics,
-            },
-        )
-    }));
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self::Response> {
+        Ok(RawSemanticTokens {
+            result_id: message.result_id,
+            data: message.data,
+        })
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
 }
 
 #[async_trait(?Send)]
-impl LspCommand for GetDocumentDiagnostics {
-    type Response = Vec<LspPullDiagnostics>;
-    type LspRequest = lsp::request::DocumentDiagnosticRequest;
-    type ProtoRequest = proto::GetDocumentDiagnostics;
+impl LspCommand for GetSemanticTokensDelta {
+    type Response = RawSemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullDeltaRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
 
     fn display_name(&self) -> &str {
-        "Get diagnostics"
+        "Semantic tokens delta"
     }
 
-    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
-        true
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        self.previous.result_id.is_some()
+            && semantic_tokens_full_support(&server_capabilities.server_capabilities)
+                .unwrap_or(false)
     }
 
     fn to_lsp(
@@ -4044,195 +5474,258 @@ impl LspCommand for GetDocumentDiagnostics {
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::DocumentDiagnosticParams> {
-        let identifier = match &self.dynamic_caps {
-            lsp::DiagnosticServerCapabilities::Options(options) => options.identifier.clone(),
-            lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
-                options.diagnostic_options.identifier.clone()
-            }
-        };
-
-        Ok(lsp::DocumentDiagnosticParams {
-            text_document: lsp::TextDocumentIdentifier {
-                uri: file_path_to_lsp_url(path)?,
-            },
-            identifier,
-            previous_result_id: self.previous_result_id.clone(),
-            partial_result_params: Default::default(),
+    ) -> Result<lsp::SemanticTokensDeltaParams> {
+        Ok(lsp::SemanticTokensDeltaParams {
+            text_document: make_text_document_identifier(path)?,
+            previous_result_id: self.previous.result_id.clone().unwrap_or_default(),
             work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
         })
     }
 
     async fn response_from_lsp(
         self,
-        message: lsp::DocumentDiagnosticReportResult,
+        message: Option<lsp::SemanticTokensFullDeltaResult>,
         _: Entity<LspStore>,
-        buffer: Entity<Buffer>,
-        server_id: LanguageServerId,
-        cx: AsyncApp,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
     ) -> Result<Self::Response> {
-        let url = buffer.read_with(&cx, |buffer, cx| {
-            buffer
-                .file()
-                .and_then(|file| file.as_local())
-                .map(|file| {
-                    let abs_path = file.abs_path(cx);
-                    file_path_to_lsp_url(&abs_path)
-                })
-                .transpose()?
-                .with_context(|| format!("missing url on buffer {}", buffer.remote_id()))
-        })??;
-
-        let mut pulled_diagnostics = HashMap::default();
+        let mut tokens = self.previous;
         match message {
-            lsp::DocumentDiagnosticReportResult::Report(report) => match report {
-                lsp::DocumentDiagnosticReport::Full(report) => {
-                    if let Some(related_documents) = report.related_documents {
-                        process_related_documents(
-                            &mut pulled_diagnostics,
-                            server_id,
-                            related_documents,
-                        );
-                    }
-                    process_full_diagnostics_report(
-                        &mut pulled_diagnostics,
-                        server_id,
-                        url,
-                        report.full_document_diagnostic_report,
-                    );
-                }
-                lsp::DocumentDiagnosticReport::Unchanged(report) => {
-                    if let Some(related_documents) = report.related_documents {
-                        process_related_documents(
-                            &mut pulled_diagnostics,
-                            server_id,
-                            related_documents,
-                        );
-                    }
-                    process_unchanged_diagnostics_report(
-                        &mut pulled_diagnostics,
-                        server_id,
-                        url,
-                        report.unchanged_document_diagnostic_report,
-                    );
-                }
-            },
-            lsp::DocumentDiagnosticReportResult::Partial(report) => {
-                if let Some(related_documents) = report.related_documents {
-                    process_related_documents(
-                        &mut pulled_diagnostics,
-                        server_id,
-                        related_documents,
-                    );
-                }
+            Some(lsp::SemanticTokensFullDeltaResult::Tokens(full)) => {
+                return Ok(RawSemanticTokens::from_lsp(full));
             }
+            Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                tokens.apply_edits(delta.edits)?;
+                tokens.result_id = delta.result_id;
+            }
+            Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                tokens.apply_edits(edits)?;
+                tokens.result_id = None;
+            }
+            None => {}
+        }
+        Ok(tokens)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id().to_proto(),
+            version: serialize_version(&buffer.version()),
         }
+    }
+
+    async fn from_proto(
+        _: Self::ProtoRequest,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        anyhow::bail!(
+            "proto::GetSemanticTokens is not expected to be converted into a delta request, as it needs the previous tokens fetched first"
+        )
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        buffer_version: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSemanticTokensResponse {
+        GetSemanticTokens::response_to_proto(response, lsp_store, peer_id, buffer_version, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        GetSemanticTokens
+            .response_from_proto(message, lsp_store, buffer, cx)
+            .await
+    }
 
-        Ok(pulled_diagnostics.into_values().collect())
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
     }
+}
 
-    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetDocumentDiagnostics {
-        proto::GetDocumentDiagnostics {
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    type Response = Vec<LspFoldingRange>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn display_name(&self) -> &str {
+        "Folding ranges"
+    }
+
+    fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
+        server_capabilities
+            .server_capabilities
+            .folding_range_provider
+            .as_ref()
+            .is_some_and(|capability| match capability {
+                lsp::FoldingRangeProviderCapability::Simple(supported) => *supported,
+                lsp::FoldingRangeProviderCapability::FoldingProvider(..) => true,
+                lsp::FoldingRangeProviderCapability::Options(..) => true,
+            })
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::FoldingRangeParams> {
+        Ok(lsp::FoldingRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::FoldingRange>>,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Self::Response> {
+        buffer.read_with(&cx, |buffer, _| {
+            let max_row = buffer.max_point().row;
+            let mut lsp_ranges = message.unwrap_or_default();
+            // We only fold whole lines, so the characters are ignored, as advertised in the client capabilities.
+            lsp_ranges.sort_unstable_by_key(|range| (range.start_line, Reverse(range.end_line)));
+            lsp_ranges.dedup_by_key(|range| (range.start_line, range.end_line));
+            lsp_ranges
+                .into_iter()
+                .filter_map(|lsp_range| {
+                    let end_row = lsp_range.end_line.min(max_row);
+                    if lsp_range.start_line >= end_row {
+                        return None;
+                    }
+                    let start =
+                        Point::new(lsp_range.start_line, buffer.line_len(lsp_range.start_line));
+                    let end = Point::new(end_row, buffer.line_len(end_row));
+                    Some(LspFoldingRange {
+                        range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                        kind: lsp_range.kind,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetFoldingRanges {
             project_id,
-            buffer_id: buffer.remote_id().into(),
+            buffer_id: buffer.remote_id().to_proto(),
             version: serialize_version(&buffer.version()),
         }
     }
 
     async fn from_proto(
-        _: proto::GetDocumentDiagnostics,
+        _: Self::ProtoRequest,
         _: Entity<LspStore>,
         _: Entity<Buffer>,
         _: AsyncApp,
     ) -> Result<Self> {
-        anyhow::bail!(
-            "proto::GetDocumentDiagnostics is not expected to be converted from proto directly, as it needs `previous_result_id` fetched first"
-        )
+        Ok(Self)
     }
 
     fn response_to_proto(
         response: Self::Response,
         _: &mut LspStore,
         _: PeerId,
-        _: &clock::Global,
+        buffer_version: &clock::Global,
         _: &mut App,
-    ) -> proto::GetDocumentDiagnosticsResponse {
-        let pulled_diagnostics = response
-            .into_iter()
-            .filter_map(|diagnostics| match diagnostics {
-                LspPullDiagnostics::Default => None,
-                LspPullDiagnostics::Response {
-                    server_id,
-                    uri,
-                    diagnostics,
-                } => {
-                    let mut changed = false;
-                    let (diagnostics, result_id) = match diagnostics {
-                        PulledDiagnostics::Unchanged { result_id } => (Vec::new(), Some(result_id)),
-                        PulledDiagnostics::Changed {
-                            result_id,
-                            diagnostics,
-                        } => {
-                            changed = true;
-                            (diagnostics, result_id)
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: response
+                .into_iter()
+                .map(|folding_range| proto::FoldingRange {
+                    start: Some(serialize_anchor(&folding_range.range.start)),
+                    end: Some(serialize_anchor(&folding_range.range.end)),
+                    kind: folding_range.kind.map(|kind| {
+                        match kind {
+                            lsp::FoldingRangeKind::Comment => proto::folding_range::Kind::Comment,
+                            lsp::FoldingRangeKind::Imports => proto::folding_range::Kind::Imports,
+                            lsp::FoldingRangeKind::Region => proto::folding_range::Kind::Region,
                         }
-                    };
-                    Some(proto::PulledDiagnostics {
-                        changed,
-                        result_id,
-                        uri: uri.to_string(),
-                        server_id: server_id.to_proto(),
-                        diagnostics: diagnostics
-                            .into_iter()
-                            .filter_map(|diagnostic| {
-                                GetDocumentDiagnostics::serialize_lsp_diagnostic(diagnostic)
-                                    .context("serializing diagnostics")
-                                    .log_err()
-                            })
-                            .collect(),
-                    })
-                }
-            })
-            .collect();
-
-        proto::GetDocumentDiagnosticsResponse { pulled_diagnostics }
+                        .into()
+                    }),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
     }
 
     async fn response_from_proto(
         self,
-        response: proto::GetDocumentDiagnosticsResponse,
+        message: proto::GetFoldingRangesResponse,
         _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: AsyncApp,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
     ) -> Result<Self::Response> {
-        Ok(Self::diagnostics_from_proto(response))
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(message
+            .ranges
+            .into_iter()
+            .filter_map(|folding_range| {
+                let start = deserialize_anchor(folding_range.start?)?;
+                let end = deserialize_anchor(folding_range.end?)?;
+                let kind = match folding_range.kind.map(proto::folding_range::Kind::from_i32) {
+                    Some(Some(proto::folding_range::Kind::Comment)) => {
+                        Some(lsp::FoldingRangeKind::Comment)
+                    }
+                    Some(Some(proto::folding_range::Kind::Imports)) => {
+                        Some(lsp::FoldingRangeKind::Imports)
+                    }
+                    Some(Some(proto::folding_range::Kind::Region)) => {
+                        Some(lsp::FoldingRangeKind::Region)
+                    }
+                    Some(None) | None => None,
+                };
+                Some(LspFoldingRange {
+                    range: start..end,
+                    kind,
+                })
+            })
+            .collect())
     }
 
-    fn buffer_id_from_proto(message: &proto::GetDocumentDiagnostics) -> Result<BufferId> {
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
         BufferId::new(message.buffer_id)
     }
 }
 
 #[async_trait(?Send)]
-impl LspCommand for GetDocumentColor {
-    type Response = Vec<DocumentColor>;
-    type LspRequest = lsp::request::DocumentColor;
-    type ProtoRequest = proto::GetDocumentColor;
+impl LspCommand for GetDocumentLinks {
+    type Response = Vec<DocumentLink>;
+    type LspRequest = lsp::request::DocumentLinkRequest;
+    type ProtoRequest = proto::GetDocumentLinks;
 
     fn display_name(&self) -> &str {
-        "Document color"
+        "Document links"
     }
 
     fn check_capabilities(&self, server_capabilities: AdapterServerCapabilities) -> bool {
         server_capabilities
             .server_capabilities
-            .color_provider
-            .as_ref()
-            .is_some_and(|capability| match capability {
-                lsp::ColorProviderCapability::Simple(supported) => *supported,
-                lsp::ColorProviderCapability::ColorProvider(..) => true,
-                lsp::ColorProviderCapability::Options(..) => true,
-            })
+            .document_link_provider
+            .is_some()
     }
 
     fn to_lsp(
@@ -4241,8 +5734,8 @@ impl LspCommand for GetDocumentColor {
         _: &Buffer,
         _: &Arc<LanguageServer>,
         _: &App,
-    ) -> Result<lsp::DocumentColorParams> {
-        Ok(lsp::DocumentColorParams {
+    ) -> Result<lsp::DocumentLinkParams> {
+        Ok(lsp::DocumentLinkParams {
             text_document: make_text_document_identifier(path)?,
             work_done_progress_params: Default::default(),
             partial_result_params: Default::default(),
@@ -4251,25 +5744,38 @@ impl LspCommand for GetDocumentColor {
 
     async fn response_from_lsp(
         self,
-        message: Vec<lsp::ColorInformation>,
-        _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: LanguageServerId,
-        _: AsyncApp,
+        message: Option<Vec<lsp::DocumentLink>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
     ) -> Result<Self::Response> {
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        let can_resolve = lsp_store.read_with(&cx, |lsp_store, _| {
+            lsp_store
+                .lsp_server_capabilities
+                .get(&server_id)
+                .is_some_and(Self::can_resolve_links)
+        })?;
         Ok(message
+            .unwrap_or_default()
             .into_iter()
-            .map(|color| DocumentColor {
-                lsp_range: color.range,
-                color: color.color,
-                resolved: false,
-                color_presentations: Vec::new(),
+            .map(|lsp_link| {
+                let range = range_from_lsp(lsp_link.range);
+                let start = snapshot.clip_point_utf16(range.start, Bias::Left);
+                let end = snapshot.clip_point_utf16(range.end, Bias::Right);
+                DocumentLink {
+                    range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                    server_id,
+                    resolved: lsp_link.target.is_some() || !can_resolve,
+                    lsp_link,
+                }
             })
             .collect())
     }
 
     fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
-        proto::GetDocumentColor {
+        proto::GetDocumentLinks {
             project_id,
             buffer_id: buffer.remote_id().to_proto(),
             version: serialize_version(&buffer.version()),
@@ -4277,12 +5783,17 @@ impl LspCommand for GetDocumentColor {
     }
 
     async fn from_proto(
-        _: Self::ProtoRequest,
+        message: Self::ProtoRequest,
         _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: AsyncApp,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
     ) -> Result<Self> {
-        Ok(Self {})
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self)
     }
 
     fn response_to_proto(
@@ -4291,64 +5802,31 @@ impl LspCommand for GetDocumentColor {
         _: PeerId,
         buffer_version: &clock::Global,
         _: &mut App,
-    ) -> proto::GetDocumentColorResponse {
-        proto::GetDocumentColorResponse {
-            colors: response
-                .into_iter()
-                .map(|color| {
-                    let start = point_from_lsp(color.lsp_range.start).0;
-                    let end = point_from_lsp(color.lsp_range.end).0;
-                    proto::ColorInformation {
-                        red: color.color.red,
-                        green: color.color.green,
-                        blue: color.color.blue,
-                        alpha: color.color.alpha,
-                        lsp_range_start: Some(proto::PointUtf16 {
-                            row: start.row,
-                            column: start.column,
-                        }),
-                        lsp_range_end: Some(proto::PointUtf16 {
-                            row: end.row,
-                            column: end.column,
-                        }),
-                    }
-                })
-                .collect(),
+    ) -> proto::GetDocumentLinksResponse {
+        proto::GetDocumentLinksResponse {
+            links: response.iter().map(Self::serialize_link).collect(),
             version: serialize_version(buffer_version),
         }
     }
 
     async fn response_from_proto(
         self,
-        message: proto::GetDocumentColorResponse,
+        message: proto::GetDocumentLinksResponse,
         _: Entity<LspStore>,
-        _: Entity<Buffer>,
-        _: AsyncApp,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
     ) -> Result<Self::Response> {
-        Ok(message
-            .colors
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        message
+            .links
             .into_iter()
-            .filter_map(|color| {
-                let start = color.lsp_range_start?;
-                let start = PointUtf16::new(start.row, start.column);
-                let end = color.lsp_range_end?;
-                let end = PointUtf16::new(end.row, end.column);
-                Some(DocumentColor {
-                    resolved: false,
-                    color_presentations: Vec::new(),
-                    lsp_range: lsp::Range {
-                        start: point_to_lsp(start),
-                        end: point_to_lsp(end),
-                    },
-                    color: lsp::Color {
-                        red: color.red,
-                        green: color.green,
-                        blue: color.blue,
-                        alpha: color.alpha,
-                    },
-                })
-            })
-            .collect())
+            .map(Self::deserialize_link)
+            .collect::<Result<Vec<_>>>()
+            .context("deserializing proto document links response")
     }
 
     fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
@@ -4356,6 +5834,43 @@ impl LspCommand for GetDocumentColor {
     }
 }
 
+impl GetDocumentLinks {
+    pub fn can_resolve_links(capabilities: &ServerCapabilities) -> bool {
+        capabilities
+            .document_link_provider
+            .as_ref()
+            .and_then(|options| options.resolve_provider)
+            .unwrap_or(false)
+    }
+
+    pub fn serialize_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            server_id: link.server_id.to_proto(),
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+            resolved: link.resolved,
+        }
+    }
+
+    pub fn deserialize_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid end")?;
+        Ok(DocumentLink {
+            range: start..end,
+            server_id: LanguageServerId::from_proto(link.server_id),
+            lsp_link: serde_json::from_slice(&link.lsp_link)?,
+            resolved: link.resolved,
+        })
+    }
+}
+
 fn process_related_documents(
     diagnostics: &mut HashMap<lsp::Uri, LspPullDiagnostics>,
     server_id: LanguageServerId,
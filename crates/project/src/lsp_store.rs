@@ -21,9 +21,10 @@ mod inlay_hint_cache;
 use self::inlay_hint_cache::BufferInlayHints;
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, InlayId, LocationLink,
-    LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
+    CompletionSource, CoreCompletion, DocumentColor, DocumentLink, Hover, InlayHint, InlayId,
+    LocationLink, LspAction, LspFoldingRange, LspPullDiagnostics, ManifestProvidersStore, Project,
+    ProjectItem, ProjectPath, ProjectTransaction, PulledDiagnostics, ResolveState, SemanticToken,
+    Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -36,6 +37,7 @@ use crate::{
         LanguageServerTree, LanguageServerTreeNode, LaunchDisposition, ManifestQueryDelegate,
         ManifestTree,
     },
+    parse_snippet_for_buffer,
     prettier_store::{self, PrettierStore, PrettierStoreEvent},
     project_settings::{LspSettings, ProjectSettings},
     toolchain_store::{LocalToolchainStore, ToolchainStoreEvent},
@@ -282,6 +284,7 @@ pub struct LocalLspStore {
     registered_buffers: HashMap<BufferId, usize>,
     buffers_opened_in_servers: HashMap<BufferId, HashSet<LanguageServerId>>,
     buffer_pull_diagnostics_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, Option<String>>>,
+    buffer_semantic_tokens_results: HashMap<LanguageServerId, HashMap<BufferId, RawSemanticTokens>>,
 }
 
 impl LocalLspStore {
@@ -907,6 +910,29 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = lsp_store.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.invalidate_semantic_tokens();
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
                 let this = lsp_store.clone();
@@ -3061,8 +3087,18 @@ impl LocalLspStore {
                                         }
                                     }
                                     Edit::Snippet(edit) => {
-                                        let Ok(snippet) = Snippet::parse(&edit.snippet.value)
-                                        else {
+                                        let snapshot = buffer_to_edit.read(cx).snapshot();
+                                        let range = range_from_lsp(edit.range).to_offset(&snapshot);
+                                        let replaced_text = snapshot
+                                            .text_for_range(range.clone())
+                                            .collect::<String>();
+                                        let Ok(snippet) = parse_snippet_for_buffer(
+                                            &edit.snippet.value,
+                                            &snapshot,
+                                            range.start,
+                                            Some(&replaced_text),
+                                            cx,
+                                        ) else {
                                             continue;
                                         };
 
@@ -3213,6 +3249,8 @@ impl LocalLspStore {
             self.language_servers.remove(server_id_to_remove);
             self.buffer_pull_diagnostics_result_ids
                 .remove(server_id_to_remove);
+            self.buffer_semantic_tokens_results
+                .remove(server_id_to_remove);
             for buffer_servers in self.buffers_opened_in_servers.values_mut() {
                 buffer_servers.remove(server_id_to_remove);
             }
@@ -3587,7 +3625,10 @@ pub struct LspStore {
 pub struct BufferLspData {
     buffer_version: Global,
     document_colors: Option<DocumentColorData>,
+    semantic_tokens: Option<SemanticTokensData>,
+    folding_ranges: Option<FoldingRangesData>,
     code_lens: Option<CodeLensData>,
+    document_links: Option<DocumentLinksData>,
     inlay_hints: BufferInlayHints,
     lsp_requests: HashMap<LspKey, HashMap<LspRequestId, Task<()>>>,
     chunk_lsp_requests: HashMap<LspKey, HashMap<BufferChunk, LspRequestId>>,
@@ -3604,7 +3645,10 @@ impl BufferLspData {
         Self {
             buffer_version: buffer.read(cx).version(),
             document_colors: None,
+            semantic_tokens: None,
+            folding_ranges: None,
             code_lens: None,
+            document_links: None,
             inlay_hints: BufferInlayHints::new(buffer, cx),
             lsp_requests: HashMap::default(),
             chunk_lsp_requests: HashMap::default(),
@@ -3617,10 +3661,24 @@ impl BufferLspData {
             document_colors.cache_version += 1;
         }
 
+        if let Some(semantic_tokens) = &mut self.semantic_tokens {
+            semantic_tokens.tokens.remove(&for_server);
+            semantic_tokens.cache_version += 1;
+        }
+
+        if let Some(folding_ranges) = &mut self.folding_ranges {
+            folding_ranges.ranges.remove(&for_server);
+            folding_ranges.cache_version += 1;
+        }
+
         if let Some(code_lens) = &mut self.code_lens {
             code_lens.lens.remove(&for_server);
         }
 
+        if let Some(document_links) = &mut self.document_links {
+            document_links.links.remove(&for_server);
+        }
+
         self.inlay_hints.remove_server_data(for_server);
     }
 
@@ -3636,8 +3694,25 @@ pub struct DocumentColors {
     pub cache_version: Option<usize>,
 }
 
+#[derive(Debug, Default, Clone)]
+pub struct BufferSemanticTokens {
+    pub tokens: HashMap<LanguageServerId, Arc<[SemanticToken]>>,
+    pub cache_version: Option<usize>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BufferFoldingRanges {
+    pub ranges: HashMap<LanguageServerId, Arc<[LspFoldingRange]>>,
+    pub cache_version: Option<usize>,
+}
+
 type DocumentColorTask = Shared<Task<std::result::Result<DocumentColors, Arc<anyhow::Error>>>>;
+type SemanticTokensTask =
+    Shared<Task<std::result::Result<BufferSemanticTokens, Arc<anyhow::Error>>>>;
+type FoldingRangesTask = Shared<Task<std::result::Result<BufferFoldingRanges, Arc<anyhow::Error>>>>;
 type CodeLensTask = Shared<Task<std::result::Result<Option<Vec<CodeAction>>, Arc<anyhow::Error>>>>;
+type DocumentLinksTask =
+    Shared<Task<std::result::Result<Option<Vec<DocumentLink>>, Arc<anyhow::Error>>>>;
 
 #[derive(Debug, Default)]
 struct DocumentColorData {
@@ -3646,12 +3721,32 @@ struct DocumentColorData {
     colors_update: Option<(Global, DocumentColorTask)>,
 }
 
+#[derive(Debug, Default)]
+struct SemanticTokensData {
+    tokens: HashMap<LanguageServerId, Arc<[SemanticToken]>>,
+    cache_version: usize,
+    tokens_update: Option<(Global, SemanticTokensTask)>,
+}
+
+#[derive(Debug, Default)]
+struct FoldingRangesData {
+    ranges: HashMap<LanguageServerId, Arc<[LspFoldingRange]>>,
+    cache_version: usize,
+    ranges_update: Option<(Global, FoldingRangesTask)>,
+}
+
 #[derive(Debug, Default)]
 struct CodeLensData {
     lens: HashMap<LanguageServerId, Vec<CodeAction>>,
     update: Option<(Global, CodeLensTask)>,
 }
 
+#[derive(Debug, Default)]
+struct DocumentLinksData {
+    links: HashMap<LanguageServerId, Vec<DocumentLink>>,
+    update: Option<(Global, DocumentLinksTask)>,
+}
+
 #[derive(Debug)]
 pub enum LspStoreEvent {
     LanguageServerAdded(LanguageServerId, LanguageServerName, Option<WorktreeId>),
@@ -3673,6 +3768,7 @@ pub enum LspStoreEvent {
         request_id: Option<usize>,
     },
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         server_id: LanguageServerId,
         paths: Vec<ProjectPath>,
@@ -3745,10 +3841,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_apply_code_action);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3757,6 +3855,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3880,6 +3984,7 @@ impl LspStore {
                 registered_buffers: HashMap::default(),
                 buffers_opened_in_servers: HashMap::default(),
                 buffer_pull_diagnostics_result_ids: HashMap::default(),
+                buffer_semantic_tokens_results: HashMap::default(),
                 watched_manifest_filenames: ManifestProvidersStore::global(cx)
                     .manifest_file_names(),
             }),
@@ -4154,6 +4259,9 @@ impl LspStore {
                         let local = lsp_store.as_local_mut().unwrap();
                         local.registered_buffers.remove(&buffer_id);
                         local.buffers_opened_in_servers.remove(&buffer_id);
+                        for server_results in local.buffer_semantic_tokens_results.values_mut() {
+                            server_results.remove(&buffer_id);
+                        }
                         if let Some(file) = File::from_dyn(buffer.read(cx).file()).cloned() {
                             local.unregister_old_buffer_from_language_servers(buffer, &file, cx);
                         }
@@ -4428,6 +4536,8 @@ impl LspStore {
                 request.check_capabilities(AdapterServerCapabilities {
                     server_capabilities: capabilities.clone(),
                     code_action_kinds: None,
+                    // Not replicated to guests; the host checks it when handling the request.
+                    type_hierarchy_provider: true,
                 })
             },
             cx,
@@ -5886,6 +5996,225 @@ impl LspStore {
         }
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> DocumentLinksTask {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+        let existing_servers = self.as_local().map(|local| {
+            local
+                .buffers_opened_in_servers
+                .get(&buffer_id)
+                .cloned()
+                .unwrap_or_default()
+        });
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id)
+            && let Some(cached_links) = &lsp_data.document_links
+        {
+            if !version_queried_for.changed_since(&lsp_data.buffer_version) {
+                let has_different_servers = existing_servers.is_some_and(|existing_servers| {
+                    existing_servers != cached_links.links.keys().copied().collect()
+                });
+                if !has_different_servers {
+                    return Task::ready(Ok(Some(
+                        cached_links.links.values().flatten().cloned().collect(),
+                    )))
+                    .shared();
+                }
+            } else if let Some((updating_for, running_update)) = cached_links.update.as_ref()
+                && !version_queried_for.changed_since(updating_for)
+            {
+                return running_update.clone();
+            }
+        }
+
+        let links_lsp_data = self
+            .latest_lsp_data(buffer, cx)
+            .document_links
+            .get_or_insert_default();
+        let buffer = buffer.clone();
+        let query_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_links = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_document_links(&buffer, cx)
+                    })
+                    .map_err(Arc::new)?
+                    .await
+                    .context("fetching document links")
+                    .map_err(Arc::new);
+                let fetched_links = match fetched_links {
+                    Ok(fetched_links) => fetched_links,
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(links_lsp_data) = lsp_store
+                                    .lsp_data
+                                    .get_mut(&buffer_id)
+                                    .and_then(|lsp_data| lsp_data.document_links.as_mut())
+                                {
+                                    links_lsp_data.update = None;
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, _| {
+                        let lsp_data = lsp_store.current_lsp_data(buffer_id)?;
+                        let document_links = lsp_data.document_links.as_mut()?;
+                        if let Some(fetched_links) = fetched_links {
+                            if lsp_data.buffer_version == query_version_queried_for {
+                                document_links.links.extend(fetched_links);
+                            } else if !lsp_data
+                                .buffer_version
+                                .changed_since(&query_version_queried_for)
+                            {
+                                lsp_data.buffer_version = query_version_queried_for;
+                                document_links.links = fetched_links;
+                            }
+                        }
+                        document_links.update = None;
+                        Some(document_links.links.values().flatten().cloned().collect())
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        links_lsp_data.update = Some((version_queried_for, new_task.clone()));
+        new_task
+    }
+
+    fn fetch_document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<HashMap<LanguageServerId, Vec<DocumentLink>>>>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = GetDocumentLinks;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+            let request_task = upstream_client.request_lsp(
+                project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let Some(lsp_store) = weak_lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let Some(responses) = request_task.await? else {
+                    return Ok(None);
+                };
+
+                let document_links = join_all(responses.payload.into_iter().map(|response| {
+                    let lsp_store = lsp_store.clone();
+                    let buffer = buffer.clone();
+                    let cx = cx.clone();
+                    async move {
+                        (
+                            LanguageServerId::from_proto(response.server_id),
+                            GetDocumentLinks
+                                .response_from_proto(response.response, lsp_store, buffer, cx)
+                                .await,
+                        )
+                    }
+                }))
+                .await;
+
+                let mut has_errors = false;
+                let document_links = document_links
+                    .into_iter()
+                    .filter_map(|(server_id, links)| match links {
+                        Ok(links) => Some((server_id, links)),
+                        Err(e) => {
+                            has_errors = true;
+                            log::error!("{e:#}");
+                            None
+                        }
+                    })
+                    .collect::<HashMap<_, _>>();
+                anyhow::ensure!(
+                    !has_errors || !document_links.is_empty(),
+                    "Failed to fetch document links"
+                );
+                Ok(Some(document_links))
+            })
+        } else {
+            let document_links_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetDocumentLinks, cx);
+            cx.background_spawn(
+                async move { Ok(Some(document_links_task.await.into_iter().collect())) },
+            )
+        }
+    }
+
+    /// Fills in the target of a document link that the server left out, via `documentLink/resolve`.
+    pub fn resolve_document_link(
+        &self,
+        mut link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.resolved {
+            return Task::ready(Ok(link));
+        }
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                link: Some(GetDocumentLinks::serialize_link(&link)),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                match response.link {
+                    Some(resolved_link) => GetDocumentLinks::deserialize_link(resolved_link)
+                        .context("document link proto resolve response conversion"),
+                    None => Ok(link),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, link.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&lang_server.capabilities()) {
+                link.resolved = true;
+                return Task::ready(Ok(link));
+            }
+            cx.background_spawn(async move {
+                let resolved_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(link.lsp_link.clone())
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")?;
+                link.lsp_link.target = resolved_link.target;
+                link.lsp_link.tooltip = resolved_link
+                    .tooltip
+                    .or_else(|| link.lsp_link.tooltip.take());
+                link.resolved = true;
+                Ok(link)
+            })
+        }
+    }
+
     #[inline(never)]
     pub fn completions(
         &self,
@@ -7244,64 +7573,481 @@ impl LspStore {
         }
     }
 
-    pub fn signature_help<T: ToPointUtf16>(
+    pub fn semantic_tokens(
         &mut self,
-        buffer: &Entity<Buffer>,
-        position: T,
+        known_cache_version: Option<usize>,
+        buffer: Entity<Buffer>,
         cx: &mut Context<Self>,
-    ) -> Task<Option<Vec<SignatureHelp>>> {
-        let position = position.to_point_utf16(buffer.read(cx));
+    ) -> Option<SemanticTokensTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
 
-        if let Some((client, upstream_project_id)) = self.upstream_client() {
-            let request = GetSignatureHelp { position };
-            if !self.is_capable_for_proto_request(buffer, &request, cx) {
-                return Task::ready(None);
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id)
+            && let Some(cached_tokens) = &lsp_data.semantic_tokens
+            && cached_tokens.tokens_update.is_none()
+            && !version_queried_for.changed_since(&lsp_data.buffer_version)
+        {
+            let cache_version = cached_tokens.cache_version;
+            if Some(cache_version) == known_cache_version {
+                return None;
+            } else {
+                return Some(
+                    Task::ready(Ok(BufferSemanticTokens {
+                        tokens: cached_tokens.tokens.clone(),
+                        cache_version: Some(cache_version),
+                    }))
+                    .shared(),
+                );
             }
-            let request_task = client.request_lsp(
-                upstream_project_id,
-                None,
-                LSP_REQUEST_TIMEOUT,
-                cx.background_executor().clone(),
-                request.to_proto(upstream_project_id, buffer.read(cx)),
-            );
-            let buffer = buffer.clone();
-            cx.spawn(async move |weak_lsp_store, cx| {
-                let lsp_store = weak_lsp_store.upgrade()?;
-                let signatures = join_all(
-                    request_task
-                        .await
-                        .log_err()
-                        .flatten()
-                        .map(|response| response.payload)
-                        .unwrap_or_default()
-                        .into_iter()
-                        .map(|response| {
-                            let response = GetSignatureHelp { position }.response_from_proto(
-                                response.response,
-                                lsp_store.clone(),
-                                buffer.clone(),
-                                cx.clone(),
-                            );
-                            async move { response.await.log_err().flatten() }
-                        }),
-                )
-                .await
-                .into_iter()
-                .flatten()
-                .collect();
-                Some(signatures)
-            })
-        } else {
-            let all_actions_task = self.request_multiple_lsp_locally(
-                buffer,
-                Some(position),
-                GetSignatureHelp { position },
-                cx,
-            );
-            cx.background_spawn(async move {
-                Some(
-                    all_actions_task
-                        .await
+        }
+
+        let tokens_lsp_data = self
+            .latest_lsp_data(&buffer, cx)
+            .semantic_tokens
+            .get_or_insert_default();
+        if let Some((updating_for, running_update)) = &tokens_lsp_data.tokens_update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return Some(running_update.clone());
+        }
+        let buffer_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_tokens = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_semantic_tokens_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching semantic tokens")
+                    .map_err(Arc::new);
+                let fetched_tokens = match fetched_tokens {
+                    Ok(Some(fetched_tokens)) => fetched_tokens,
+                    Ok(None) => HashMap::default(),
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id)
+                                    && let Some(semantic_tokens) = &mut lsp_data.semantic_tokens
+                                {
+                                    semantic_tokens.tokens_update = None;
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        if lsp_data.buffer_version != buffer_version_queried_for {
+                            // The tokens are anchored to the snapshot they were requested for,
+                            // so they're still usable until the next fetch, but must not be cached.
+                            return BufferSemanticTokens {
+                                tokens: fetched_tokens,
+                                cache_version: None,
+                            };
+                        }
+                        let semantic_tokens = lsp_data.semantic_tokens.get_or_insert_default();
+                        semantic_tokens.tokens = fetched_tokens;
+                        semantic_tokens.cache_version += 1;
+                        semantic_tokens.tokens_update = None;
+                        BufferSemanticTokens {
+                            tokens: semantic_tokens.tokens.clone(),
+                            cache_version: Some(semantic_tokens.cache_version),
+                        }
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        tokens_lsp_data.tokens_update = Some((version_queried_for, new_task.clone()));
+        Some(new_task)
+    }
+
+    fn fetch_semantic_tokens_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Arc<[SemanticToken]>>>>> {
+        let snapshot = buffer.read(cx).text_snapshot();
+        let buffer_id = snapshot.remote_id();
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetSemanticTokens;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let responses = request_task
+                    .await
+                    .log_err()
+                    .flatten()
+                    .map(|response| response.payload)
+                    .unwrap_or_default();
+                let mut tokens = HashMap::default();
+                for response in responses {
+                    let server_id = LanguageServerId::from_proto(response.server_id);
+                    let Some(raw_tokens) = request
+                        .response_from_proto(
+                            response.response,
+                            lsp_store.clone(),
+                            buffer.clone(),
+                            cx.clone(),
+                        )
+                        .await
+                        .log_err()
+                    else {
+                        continue;
+                    };
+                    let legend = lsp_store.read_with(cx, |lsp_store, _| {
+                        lsp_store
+                            .lsp_server_capabilities
+                            .get(&server_id)
+                            .and_then(semantic_tokens_options)
+                            .map(|options| options.legend.clone())
+                    })?;
+                    if let Some(legend) = legend {
+                        tokens.insert(
+                            server_id,
+                            decode_semantic_tokens(&raw_tokens.data, &legend, &snapshot),
+                        );
+                    }
+                }
+                Ok(Some(tokens))
+            })
+        } else {
+            let Some(local) = self.as_local() else {
+                return Task::ready(Ok(None));
+            };
+            let servers_to_query = local
+                .buffers_opened_in_servers
+                .get(&buffer_id)
+                .into_iter()
+                .flatten()
+                .filter_map(|&server_id| {
+                    let capabilities = self.lsp_server_capabilities.get(&server_id)?;
+                    let supports_delta = semantic_tokens_full_support(capabilities)?;
+                    let legend = semantic_tokens_options(capabilities)?.legend.clone();
+                    let previous_tokens = local
+                        .buffer_semantic_tokens_results
+                        .get(&server_id)
+                        .and_then(|results| results.get(&buffer_id))
+                        .filter(|previous| supports_delta && previous.result_id.is_some())
+                        .cloned();
+                    Some((server_id, legend, previous_tokens))
+                })
+                .collect::<Vec<_>>();
+
+            let tasks = servers_to_query
+                .into_iter()
+                .map(|(server_id, legend, previous_tokens)| {
+                    let buffer = buffer.clone();
+                    let snapshot = snapshot.clone();
+                    cx.spawn(async move |lsp_store, cx| {
+                        let mut raw_tokens = None;
+                        if let Some(previous) = previous_tokens {
+                            match lsp_store
+                                .update(cx, |lsp_store, cx| {
+                                    lsp_store.request_lsp(
+                                        buffer.clone(),
+                                        LanguageServerToQuery::Other(server_id),
+                                        GetSemanticTokensDelta { previous },
+                                        cx,
+                                    )
+                                })?
+                                .await
+                            {
+                                Ok(delta_tokens) => raw_tokens = Some(delta_tokens),
+                                Err(e) => log::debug!(
+                                    "Falling back to full semantic tokens for server {server_id}: {e:#}"
+                                ),
+                            }
+                        }
+                        let raw_tokens = match raw_tokens {
+                            Some(raw_tokens) => raw_tokens,
+                            None => {
+                                lsp_store
+                                    .update(cx, |lsp_store, cx| {
+                                        lsp_store.request_lsp(
+                                            buffer.clone(),
+                                            LanguageServerToQuery::Other(server_id),
+                                            GetSemanticTokens,
+                                            cx,
+                                        )
+                                    })?
+                                    .await?
+                            }
+                        };
+                        let (raw_tokens, tokens) = cx
+                            .background_spawn(async move {
+                                let tokens =
+                                    decode_semantic_tokens(&raw_tokens.data, &legend, &snapshot);
+                                (raw_tokens, tokens)
+                            })
+                            .await;
+                        lsp_store.update(cx, |lsp_store, _| {
+                            if let Some(local) = lsp_store.as_local_mut() {
+                                local
+                                    .buffer_semantic_tokens_results
+                                    .entry(server_id)
+                                    .or_default()
+                                    .insert(buffer_id, raw_tokens);
+                            }
+                        })?;
+                        anyhow::Ok((server_id, tokens))
+                    })
+                })
+                .collect::<Vec<_>>();
+            cx.background_spawn(async move {
+                Ok(Some(
+                    join_all(tasks)
+                        .await
+                        .into_iter()
+                        .filter_map(|result| result.log_err())
+                        .collect(),
+                ))
+            })
+        }
+    }
+
+    pub fn folding_ranges(
+        &mut self,
+        known_cache_version: Option<usize>,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<FoldingRangesTask> {
+        let version_queried_for = buffer.read(cx).version();
+        let buffer_id = buffer.read(cx).remote_id();
+
+        if let Some(lsp_data) = self.current_lsp_data(buffer_id)
+            && let Some(cached_ranges) = &lsp_data.folding_ranges
+            && cached_ranges.ranges_update.is_none()
+            && !version_queried_for.changed_since(&lsp_data.buffer_version)
+        {
+            let cache_version = cached_ranges.cache_version;
+            if Some(cache_version) == known_cache_version {
+                return None;
+            } else {
+                return Some(
+                    Task::ready(Ok(BufferFoldingRanges {
+                        ranges: cached_ranges.ranges.clone(),
+                        cache_version: Some(cache_version),
+                    }))
+                    .shared(),
+                );
+            }
+        }
+
+        let ranges_lsp_data = self
+            .latest_lsp_data(&buffer, cx)
+            .folding_ranges
+            .get_or_insert_default();
+        if let Some((updating_for, running_update)) = &ranges_lsp_data.ranges_update
+            && !version_queried_for.changed_since(updating_for)
+        {
+            return Some(running_update.clone());
+        }
+        let buffer_version_queried_for = version_queried_for.clone();
+        let new_task = cx
+            .spawn(async move |lsp_store, cx| {
+                cx.background_executor()
+                    .timer(Duration::from_millis(30))
+                    .await;
+                let fetched_ranges = lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.fetch_folding_ranges_for_buffer(&buffer, cx)
+                    })?
+                    .await
+                    .context("fetching folding ranges")
+                    .map_err(Arc::new);
+                let fetched_ranges = match fetched_ranges {
+                    Ok(Some(fetched_ranges)) => fetched_ranges,
+                    Ok(None) => HashMap::default(),
+                    Err(e) => {
+                        lsp_store
+                            .update(cx, |lsp_store, _| {
+                                if let Some(lsp_data) = lsp_store.lsp_data.get_mut(&buffer_id)
+                                    && let Some(folding_ranges) = &mut lsp_data.folding_ranges
+                                {
+                                    folding_ranges.ranges_update = None;
+                                }
+                            })
+                            .ok();
+                        return Err(e);
+                    }
+                };
+
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        let lsp_data = lsp_store.latest_lsp_data(&buffer, cx);
+                        if lsp_data.buffer_version != buffer_version_queried_for {
+                            return BufferFoldingRanges {
+                                ranges: fetched_ranges,
+                                cache_version: None,
+                            };
+                        }
+                        let folding_ranges = lsp_data.folding_ranges.get_or_insert_default();
+                        folding_ranges.ranges = fetched_ranges;
+                        folding_ranges.cache_version += 1;
+                        folding_ranges.ranges_update = None;
+                        BufferFoldingRanges {
+                            ranges: folding_ranges.ranges.clone(),
+                            cache_version: Some(folding_ranges.cache_version),
+                        }
+                    })
+                    .map_err(Arc::new)
+            })
+            .shared();
+        ranges_lsp_data.ranges_update = Some((version_queried_for, new_task.clone()));
+        Some(new_task)
+    }
+
+    fn fetch_folding_ranges_for_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<Option<HashMap<LanguageServerId, Arc<[LspFoldingRange]>>>>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = GetFoldingRanges;
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(Ok(None));
+            }
+
+            let request_task = client.request_lsp(
+                project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |lsp_store, cx| {
+                let Some(lsp_store) = lsp_store.upgrade() else {
+                    return Ok(None);
+                };
+                let ranges = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|ranges_response| {
+                            let response = request.response_from_proto(
+                                ranges_response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move {
+                                (
+                                    LanguageServerId::from_proto(ranges_response.server_id),
+                                    response.await.log_err().unwrap_or_default(),
+                                )
+                            }
+                        }),
+                )
+                .await
+                .into_iter()
+                .map(|(server_id, ranges)| (server_id, Arc::from(ranges)))
+                .collect();
+                Ok(Some(ranges))
+            })
+        } else {
+            let folding_ranges_task =
+                self.request_multiple_lsp_locally(buffer, None::<usize>, GetFoldingRanges, cx);
+            cx.background_spawn(async move {
+                Ok(Some(
+                    folding_ranges_task
+                        .await
+                        .into_iter()
+                        .map(|(server_id, ranges)| (server_id, Arc::from(ranges)))
+                        .collect(),
+                ))
+            })
+        }
+    }
+
+    /// Drops the cached semantic tokens of every buffer, so the next query fetches them anew.
+    fn invalidate_semantic_tokens(&mut self) {
+        for lsp_data in self.lsp_data.values_mut() {
+            lsp_data.semantic_tokens = None;
+        }
+    }
+
+    pub fn signature_help<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Vec<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+
+        if let Some((client, upstream_project_id)) = self.upstream_client() {
+            let request = GetSignatureHelp { position };
+            if !self.is_capable_for_proto_request(buffer, &request, cx) {
+                return Task::ready(None);
+            }
+            let request_task = client.request_lsp(
+                upstream_project_id,
+                None,
+                LSP_REQUEST_TIMEOUT,
+                cx.background_executor().clone(),
+                request.to_proto(upstream_project_id, buffer.read(cx)),
+            );
+            let buffer = buffer.clone();
+            cx.spawn(async move |weak_lsp_store, cx| {
+                let lsp_store = weak_lsp_store.upgrade()?;
+                let signatures = join_all(
+                    request_task
+                        .await
+                        .log_err()
+                        .flatten()
+                        .map(|response| response.payload)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|response| {
+                            let response = GetSignatureHelp { position }.response_from_proto(
+                                response.response,
+                                lsp_store.clone(),
+                                buffer.clone(),
+                                cx.clone(),
+                            );
+                            async move { response.await.log_err().flatten() }
+                        }),
+                )
+                .await
+                .into_iter()
+                .flatten()
+                .collect();
+                Some(signatures)
+            })
+        } else {
+            let all_actions_task = self.request_multiple_lsp_locally(
+                buffer,
+                Some(position),
+                GetSignatureHelp { position },
+                cx,
+            );
+            cx.background_spawn(async move {
+                Some(
+                    all_actions_task
+                        .await
                         .into_iter()
                         .flat_map(|(_, actions)| actions)
                         .collect::<Vec<_>>(),
@@ -8517,6 +9263,42 @@ impl LspStore {
                 )
                 .await?;
             }
+            Request::GetSemanticTokens(get_semantic_tokens) => {
+                Self::query_lsp_locally::<GetSemanticTokens>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_semantic_tokens,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
+            Request::GetDocumentLinks(get_document_links) => {
+                Self::query_lsp_locally::<GetDocumentLinks>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_document_links,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
+            Request::GetFoldingRanges(get_folding_ranges) => {
+                Self::query_lsp_locally::<GetFoldingRanges>(
+                    lsp_store,
+                    server_id,
+                    sender_id,
+                    lsp_request_id,
+                    get_folding_ranges,
+                    None,
+                    &mut cx,
+                )
+                .await?;
+            }
             Request::GetHover(get_hover) => {
                 let position = get_hover.position.clone().and_then(deserialize_anchor);
                 Self::query_lsp_locally::<GetHover>(
@@ -9891,6 +10673,30 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = GetDocumentLinks::deserialize_link(
+            envelope
+                .payload
+                .link
+                .context("missing the document link to resolve")?,
+        )?;
+        let resolved_link = lsp_store
+            .update(&mut cx, |lsp_store, cx| {
+                let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+                let buffer = lsp_store.buffer_store.read(cx).get_existing(buffer_id)?;
+                anyhow::Ok(lsp_store.resolve_document_link(link, buffer, cx))
+            })??
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(GetDocumentLinks::serialize_link(&resolved_link)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -9902,6 +10708,18 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            this.invalidate_semantic_tokens();
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -11628,6 +12446,7 @@ impl LspStore {
         }
         if let Some(local) = self.as_local_mut() {
             local.buffer_pull_diagnostics_result_ids.remove(&for_server);
+            local.buffer_semantic_tokens_results.remove(&for_server);
             for buffer_servers in local.buffers_opened_in_servers.values_mut() {
                 buffer_servers.remove(&for_server);
             }
@@ -12128,6 +12947,50 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareTypeHierarchy" => {
+                    server.set_type_hierarchy_provider(true);
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/semanticTokens" => {
+                    let options = reg
+                        .register_options
+                        .context("missing semantic tokens registration options")?;
+                    let options =
+                        serde_json::from_value::<lsp::SemanticTokensRegistrationOptions>(options)?;
+                    server.update_capabilities(|capabilities| {
+                        capabilities.semantic_tokens_provider = Some(
+                            lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
+                                options,
+                            ),
+                        );
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(_) => lsp::DocumentLinkOptions {
+                            resolve_provider: None,
+                            work_done_progress_options: Default::default(),
+                        },
+                        OneOf::Right(options) => options,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/foldingRange" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::FoldingRangeProviderCapability::Simple(value),
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.folding_range_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -12321,6 +13184,31 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/prepareTypeHierarchy" => {
+                    server.set_type_hierarchy_provider(false);
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/semanticTokens" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.semantic_tokens_provider = None;
+                    });
+                    if let Some(local) = self.as_local_mut() {
+                        local.buffer_semantic_tokens_results.remove(&server_id);
+                    }
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/foldingRange" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.folding_range_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
@@ -12544,6 +13432,65 @@ impl LspStore {
 
 // Registration with registerOptions as null, should fallback to true.
 // https://github.com/microsoft/vscode-languageserver-node/blob/d90a87f9557a0df9142cfb33e251cfa6fe27d970/client/src/common/client.ts#L2133
+/// Converts semantic tokens from the LSP wire format into anchored ranges, using the
+/// snapshot of the buffer the tokens were requested for.
+fn decode_semantic_tokens(
+    data: &[u32],
+    legend: &lsp::SemanticTokensLegend,
+    snapshot: &TextBufferSnapshot,
+) -> Arc<[SemanticToken]> {
+    let token_types = legend
+        .token_types
+        .iter()
+        .map(|token_type| SharedString::new(token_type.as_str()))
+        .collect::<Vec<_>>();
+    let token_modifiers = legend
+        .token_modifiers
+        .iter()
+        .take(u32::BITS as usize)
+        .map(|modifier| SharedString::new(modifier.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut tokens = Vec::with_capacity(data.len() / 5);
+    let mut line = 0;
+    let mut start = 0;
+    for token in data.chunks_exact(5) {
+        let &[delta_line, delta_start, length, token_type, modifiers] = token else {
+            continue;
+        };
+        if delta_line == 0 {
+            start += delta_start;
+        } else {
+            line += delta_line;
+            start = delta_start;
+        }
+        let Some(token_type) = token_types.get(token_type as usize) else {
+            continue;
+        };
+
+        let range_start =
+            snapshot.clip_point_utf16(Unclipped(PointUtf16::new(line, start)), Bias::Left);
+        let range_end = snapshot.clip_point_utf16(
+            Unclipped(PointUtf16::new(line, start.saturating_add(length))),
+            Bias::Left,
+        );
+        if range_start >= range_end {
+            continue;
+        }
+        tokens.push(SemanticToken {
+            range: snapshot.anchor_after(range_start)..snapshot.anchor_before(range_end),
+            token_type: token_type.clone(),
+            token_modifiers: token_modifiers
+                .iter()
+                .enumerate()
+                .filter(|(ix, _)| modifiers & (1 << ix) != 0)
+                .map(|(_, modifier)| modifier.clone())
+                .collect(),
+        });
+    }
+    tokens.into()
+}
+
 fn parse_register_capabilities<T: serde::de::DeserializeOwned>(
     reg: lsp::Registration,
 ) -> Result<OneOf<bool, T>> {
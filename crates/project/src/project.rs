@@ -13,6 +13,7 @@ mod manifest_tree;
 pub mod prettier_store;
 pub mod project_settings;
 pub mod search;
+mod snippet_variables;
 mod task_inventory;
 pub mod task_store;
 pub mod telemetry_snapshot;
@@ -41,7 +42,7 @@ use crate::{
 };
 pub use agent_server_store::{AgentServerStore, AgentServersUpdated, ExternalAgentServerName};
 pub use git_store::{
-    ConflictRegion, ConflictSet, ConflictSetSnapshot, ConflictSetUpdate,
+    ConflictRegion, ConflictSet, ConflictSetSnapshot, ConflictSetUpdate, ConflictStages,
     git_traversal::{ChildEntriesGitIter, GitEntry, GitEntryRef, GitTraversal},
 };
 pub use manifest_tree::ManifestTree;
@@ -105,6 +106,7 @@ use settings::{InvalidSettingsError, Settings, SettingsLocation, SettingsStore};
 use smol::channel::Receiver;
 use snippet::Snippet;
 use snippet_provider::SnippetProvider;
+pub use snippet_variables::parse_snippet_for_buffer;
 use std::{
     borrow::Cow,
     collections::BTreeMap,
@@ -138,7 +140,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskExecutionGraph, TaskExecutionNode, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -341,6 +343,7 @@ pub enum Event {
         request_id: Option<usize>,
     },
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -784,6 +787,30 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// An item of a call or type hierarchy, opened in the buffer that contains it.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem<T> {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    pub location: Location,
+    pub selection_range: Range<language::Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The item as returned by the language server, sent back verbatim when querying its relatives.
+    pub lsp_item: T,
+}
+
+pub type CallHierarchyItem = HierarchyItem<lsp::CallHierarchyItem>;
+pub type TypeHierarchyItem = HierarchyItem<lsp::TypeHierarchyItem>;
+
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// Ranges of the call expressions, located in the caller.
+    pub call_sites: Vec<Location>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -848,6 +875,32 @@ impl std::hash::Hash for DocumentColor {
     }
 }
 
+/// A classified range of a buffer, as reported by a language server's semantic tokens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    pub token_type: SharedString,
+    pub token_modifiers: Vec<SharedString>,
+}
+
+/// A range of a buffer linking to some target, as reported by a language server.
+/// Servers may leave the target out until the link is resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub server_id: LanguageServerId,
+    pub lsp_link: lsp::DocumentLink,
+    pub resolved: bool,
+}
+
+/// A foldable range of a buffer, as reported by a language server.
+/// Spans from the end of the first line of the range to the end of its last line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LspFoldingRange {
+    pub range: Range<language::Anchor>,
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ColorPresentation {
     pub label: SharedString,
@@ -3068,6 +3121,7 @@ impl Project {
                 request_id: *request_id,
             }),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3733,6 +3787,88 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3878,6 +4014,43 @@ impl Project {
         })
     }
 
+    /// Returns the innermost document link at the given position, with its target resolved.
+    pub fn document_link_at(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: Anchor,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<DocumentLink>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        let document_links = self
+            .lsp_store
+            .update(cx, |lsp_store, cx| lsp_store.document_links(buffer, cx));
+        let lsp_store = self.lsp_store.clone();
+        let buffer = buffer.clone();
+        cx.spawn(async move |_, cx| {
+            let document_links = document_links
+                .await
+                .map_err(|e| anyhow!("document links fetch failed: {e:#}"))?;
+            let Some(link) = document_links
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|link| {
+                    link.range.start.cmp(&position, &snapshot).is_le()
+                        && link.range.end.cmp(&position, &snapshot).is_ge()
+                })
+                .min_by_key(|link| link.range.to_offset(&snapshot).len())
+            else {
+                return Ok(None);
+            };
+            let link = lsp_store
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.resolve_document_link(link, buffer, cx)
+                })?
+                .await?;
+            Ok(Some(link))
+        })
+    }
+
     pub fn apply_code_action(
         &self,
         buffer_handle: Entity<Buffer>,
@@ -1,6 +1,6 @@
 use anyhow::Context as _;
 use collections::HashMap;
-use context_server::ContextServerCommand;
+use context_server::{ContextServerCommand, ContextServerEndpoint};
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
@@ -125,6 +125,14 @@ pub enum ContextServerSettings {
         #[serde(flatten)]
         command: ContextServerCommand,
     },
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         /// Whether the context server is enabled.
         #[serde(default = "default_true")]
@@ -143,6 +151,9 @@ impl From<settings::ContextServerSettingsContent> for ContextServerSettings {
             settings::ContextServerSettingsContent::Custom { enabled, command } => {
                 ContextServerSettings::Custom { enabled, command }
             }
+            settings::ContextServerSettingsContent::Http { enabled, endpoint } => {
+                ContextServerSettings::Http { enabled, endpoint }
+            }
             settings::ContextServerSettingsContent::Extension { enabled, settings } => {
                 ContextServerSettings::Extension { enabled, settings }
             }
@@ -155,6 +166,9 @@ impl Into<settings::ContextServerSettingsContent> for ContextServerSettings {
             ContextServerSettings::Custom { enabled, command } => {
                 settings::ContextServerSettingsContent::Custom { enabled, command }
             }
+            ContextServerSettings::Http { enabled, endpoint } => {
+                settings::ContextServerSettingsContent::Http { enabled, endpoint }
+            }
             ContextServerSettings::Extension { enabled, settings } => {
                 settings::ContextServerSettingsContent::Extension { enabled, settings }
             }
@@ -173,6 +187,7 @@ impl ContextServerSettings {
     pub fn enabled(&self) -> bool {
         match self {
             ContextServerSettings::Custom { enabled, .. } => *enabled,
+            ContextServerSettings::Http { enabled, .. } => *enabled,
             ContextServerSettings::Extension { enabled, .. } => *enabled,
         }
     }
@@ -180,6 +195,7 @@ impl ContextServerSettings {
     pub fn set_enabled(&mut self, enabled: bool) {
         match self {
             ContextServerSettings::Custom { enabled: e, .. } => *e = enabled,
+            ContextServerSettings::Http { enabled: e, .. } => *e = enabled,
             ContextServerSettings::Extension { enabled: e, .. } => *e = enabled,
         }
     }
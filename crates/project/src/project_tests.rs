@@ -2,7 +2,7 @@
 
 use crate::{
     Event,
-    git_store::{GitStoreEvent, RepositoryEvent, StatusEntry},
+    git_store::{ConflictStages, GitStoreEvent, RepositoryEvent, StatusEntry},
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
     *,
@@ -16,7 +16,8 @@ use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
     GitHostingProviderRegistry,
-    repository::{RepoPath, repo_path},
+    rebase::{RebaseProgress, RebaseTodoAction, RebaseTodoDelegate, RebaseTodoEntry},
+    repository::{InProgressOperation, IndexStage, OperationControl, RepoPath, repo_path},
     status::{StatusCode, TrackedStatus},
 };
 use git2::RepositoryInitOptions;
@@ -3276,6 +3277,595 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() { b() }",
+            "b.rs": "fn b() { c() }\nfn c() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item = |uri: &str, name: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Uri::from_file_path(uri).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let b_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    let b_item = lsp_item(path!("/dir/b.rs"), "b", b_range);
+
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![b_item]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            let a_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+            Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                from: lsp_item(path!("/dir/a.rs"), "a", a_range),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+    fake_server.set_request_handler::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.item.name, "b");
+            let c_range = lsp::Range::new(lsp::Position::new(1, 3), lsp::Position::new(1, 4));
+            Ok(Some(vec![lsp::CallHierarchyOutgoingCall {
+                to: lsp_item(path!("/dir/b.rs"), "c", c_range),
+                from_ranges: vec![lsp::Range::new(
+                    lsp::Position::new(0, 9),
+                    lsp::Position::new(0, 10),
+                )],
+            }]))
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = items.into_iter().next().unwrap();
+    assert_eq!(item.name, "b");
+    assert_eq!(item.location.buffer, buffer);
+
+    let incoming = project
+        .update(cx, |project, cx| project.incoming_calls(&item, cx))
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        assert_eq!(incoming.len(), 1);
+        let caller = &incoming[0];
+        assert_eq!(caller.item.name, "a");
+        let caller_buffer = caller.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/a.rs")),
+        );
+        assert_eq!(
+            caller
+                .call_sites
+                .iter()
+                .map(|call_site| call_site.range.to_offset(caller_buffer))
+                .collect::<Vec<_>>(),
+            vec![9..10],
+        );
+    });
+
+    let outgoing = project
+        .update(cx, |project, cx| project.outgoing_calls(&item, cx))
+        .await
+        .unwrap();
+    cx.update(|cx| {
+        assert_eq!(outgoing.len(), 1);
+        let callee = &outgoing[0];
+        assert_eq!(callee.item.name, "c");
+        assert_eq!(callee.call_sites.len(), 1);
+        assert_eq!(callee.call_sites[0].buffer, buffer);
+        assert_eq!(callee.call_sites[0].range.to_offset(buffer.read(cx)), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "trait A {}\nstruct B;\nimpl A for B {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp("Rust", FakeLspAdapter::default());
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item = |name: &str, kind: lsp::SymbolKind, range: lsp::Range| lsp::TypeHierarchyItem {
+        name: name.to_string(),
+        kind,
+        tags: None,
+        detail: None,
+        uri: lsp::Uri::from_file_path(path!("/dir/a.rs")).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let a_item = lsp_item(
+        "A",
+        lsp::SymbolKind::INTERFACE,
+        lsp::Range::new(lsp::Position::new(0, 6), lsp::Position::new(0, 7)),
+    );
+    let b_item = lsp_item(
+        "B",
+        lsp::SymbolKind::STRUCT,
+        lsp::Range::new(lsp::Position::new(1, 7), lsp::Position::new(1, 8)),
+    );
+
+    fake_server.set_request_handler::<lsp::request::TypeHierarchyPrepare, _, _>({
+        let a_item = a_item.clone();
+        move |_, _| {
+            let a_item = a_item.clone();
+            async move { Ok(Some(vec![a_item])) }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySubtypes, _, _>({
+        let b_item = b_item.clone();
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                Ok(Some(if params.item.name == "A" {
+                    vec![b_item]
+                } else {
+                    Vec::new()
+                }))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::TypeHierarchySupertypes, _, _>(
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                Ok(Some(if params.item.name == "B" {
+                    vec![a_item]
+                } else {
+                    Vec::new()
+                }))
+            }
+        },
+    );
+
+    // The server has not announced type hierarchy support yet.
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 6, cx)
+        })
+        .await
+        .unwrap();
+    assert!(items.is_empty());
+
+    fake_server
+        .request::<lsp::request::RegisterCapability>(lsp::RegistrationParams {
+            registrations: vec![lsp::Registration {
+                id: Default::default(),
+                method: "textDocument/prepareTypeHierarchy".to_string(),
+                register_options: None,
+            }],
+        })
+        .await
+        .into_response()
+        .unwrap();
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, 6, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let a = items.into_iter().next().unwrap();
+    assert_eq!(a.name, "A");
+    assert_eq!(a.kind, lsp::SymbolKind::INTERFACE);
+    cx.update(|cx| assert_eq!(a.selection_range.to_offset(buffer.read(cx)), 6..7));
+
+    let subtypes = project
+        .update(cx, |project, cx| project.subtypes(&a, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        subtypes
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["B"]
+    );
+    cx.update(|cx| {
+        assert_eq!(
+            subtypes[0].selection_range.to_offset(buffer.read(cx)),
+            18..19
+        )
+    });
+
+    let supertypes = project
+        .update(cx, |project, cx| project.supertypes(&subtypes[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        supertypes
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["A"]
+    );
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}\nfn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::KEYWORD,
+                                    lsp::SemanticTokenType::FUNCTION,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::DECLARATION],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let token =
+        |delta_line, delta_start, length, token_type, token_modifiers_bitset| lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset,
+        };
+    let full_requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let delta_requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullRequest, _, _>({
+        let full_requests = full_requests.clone();
+        move |_, _| {
+            full_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                Ok(Some(lsp::SemanticTokensResult::Tokens(
+                    lsp::SemanticTokens {
+                        result_id: Some("1".to_string()),
+                        data: vec![
+                            token(0, 0, 2, 0, 0),
+                            token(0, 3, 1, 1, 1),
+                            token(1, 0, 2, 0, 0),
+                            token(0, 3, 1, 1, 1),
+                        ],
+                    },
+                )))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::SemanticTokensFullDeltaRequest, _, _>({
+        let delta_requests = delta_requests.clone();
+        move |params, _| {
+            delta_requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            assert_eq!(params.previous_result_id, "1");
+            async move {
+                // Drop the declaration modifier of the last token.
+                Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                    lsp::SemanticTokensDelta {
+                        result_id: Some("2".to_string()),
+                        edits: vec![lsp::SemanticTokensEdit {
+                            start: 15,
+                            delete_count: 5,
+                            data: Some(vec![token(0, 3, 1, 1, 0)]),
+                        }],
+                    },
+                )))
+            }
+        }
+    });
+
+    let describe = |tokens: &crate::lsp_store::BufferSemanticTokens, cx: &App| {
+        tokens
+            .tokens
+            .values()
+            .flat_map(|tokens| tokens.iter())
+            .map(|token| {
+                (
+                    token.range.to_offset(buffer.read(cx)),
+                    token.token_type.to_string(),
+                    token
+                        .token_modifiers
+                        .iter()
+                        .map(|modifier| modifier.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let tokens_task = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(None, buffer.clone(), cx)
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    let tokens = tokens_task.await.unwrap();
+    assert_eq!(
+        cx.update(|cx| describe(&tokens, cx)),
+        vec![
+            (0..2, "keyword".to_string(), Vec::new()),
+            (
+                3..4,
+                "function".to_string(),
+                vec!["declaration".to_string()]
+            ),
+            (10..12, "keyword".to_string(), Vec::new()),
+            (
+                13..14,
+                "function".to_string(),
+                vec!["declaration".to_string()]
+            ),
+        ]
+    );
+    assert!(tokens.cache_version.is_some());
+
+    // Nothing changed since the last query, so there is nothing to update.
+    assert!(
+        lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.semantic_tokens(tokens.cache_version, buffer.clone(), cx)
+            })
+            .is_none()
+    );
+
+    buffer.update(cx, |buffer, cx| {
+        let len = buffer.len();
+        buffer.edit([(len..len, "\n")], None, cx);
+    });
+    let tokens_task = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(tokens.cache_version, buffer.clone(), cx)
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    let tokens = tokens_task.await.unwrap();
+    assert_eq!(
+        cx.update(|cx| describe(&tokens, cx)),
+        vec![
+            (0..2, "keyword".to_string(), Vec::new()),
+            (
+                3..4,
+                "function".to_string(),
+                vec!["declaration".to_string()]
+            ),
+            (10..12, "keyword".to_string(), Vec::new()),
+            (13..14, "function".to_string(), Vec::new()),
+        ]
+    );
+    assert_eq!(full_requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(delta_requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[gpui::test]
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "// #region setup\nuse a;\nuse b;\n// #endregion\nfn main() {\n    a();\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::FoldingRangeRequest, _, _>({
+        let requests = requests.clone();
+        move |_, _| {
+            requests.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move {
+                Ok(Some(vec![
+                    lsp::FoldingRange {
+                        start_line: 4,
+                        end_line: 5,
+                        ..Default::default()
+                    },
+                    lsp::FoldingRange {
+                        start_line: 1,
+                        end_line: 2,
+                        kind: Some(lsp::FoldingRangeKind::Imports),
+                        ..Default::default()
+                    },
+                    lsp::FoldingRange {
+                        start_line: 0,
+                        end_line: 3,
+                        kind: Some(lsp::FoldingRangeKind::Region),
+                        ..Default::default()
+                    },
+                    // Single line ranges have nothing to fold.
+                    lsp::FoldingRange {
+                        start_line: 6,
+                        end_line: 6,
+                        ..Default::default()
+                    },
+                ]))
+            }
+        }
+    });
+
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let ranges_task = lsp_store
+        .update(cx, |lsp_store, cx| {
+            lsp_store.folding_ranges(None, buffer.clone(), cx)
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    let ranges = ranges_task.await.unwrap();
+    assert_eq!(
+        cx.update(|cx| {
+            ranges
+                .ranges
+                .values()
+                .flat_map(|ranges| ranges.iter())
+                .map(|folding_range| {
+                    (
+                        folding_range.range.to_point(buffer.read(cx)),
+                        folding_range.kind.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        }),
+        vec![
+            (
+                Point::new(0, 16)..Point::new(3, 13),
+                Some(lsp::FoldingRangeKind::Region)
+            ),
+            (
+                Point::new(1, 6)..Point::new(2, 6),
+                Some(lsp::FoldingRangeKind::Imports)
+            ),
+            (Point::new(4, 11)..Point::new(5, 8), None),
+        ]
+    );
+    assert!(ranges.cache_version.is_some());
+
+    // Nothing changed since the last query, so there is nothing to update.
+    assert!(
+        lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.folding_ranges(ranges.cache_version, buffer.clone(), cx)
+            })
+            .is_none()
+    );
+    assert_eq!(requests.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -9564,6 +10154,297 @@ async fn test_git_worktrees_and_submodules(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_rebase(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+
+    let entry = |sha: &str| RebaseTodoEntry {
+        action: RebaseTodoAction::Pick,
+        sha: sha.to_string().into(),
+        subject: format!("Commit {sha}").into(),
+        message: None,
+    };
+    fs.with_git_state(path!("/project/.git").as_ref(), false, |state| {
+        state.rebase_todo = vec![entry("111"), entry("222"), entry("333")];
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    // The todo editor reorders the commits and drops one of them.
+    let todo_editor = RebaseTodoDelegate::new(&mut cx.to_async(), |mut entries, tx, _| {
+        entries.swap(0, 1);
+        entries[2].action = RebaseTodoAction::Drop;
+        tx.send(Some(entries)).ok();
+    });
+    repository
+        .update(cx, |repository, _| {
+            repository.rebase("main".into(), Some(todo_editor))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let todo = fs
+        .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+            state.rebase_todo.clone()
+        })
+        .unwrap();
+    assert_eq!(
+        todo.iter()
+            .map(|entry| (entry.action, entry.sha.as_ref()))
+            .collect::<Vec<_>>(),
+        [
+            (RebaseTodoAction::Pick, "222"),
+            (RebaseTodoAction::Pick, "111"),
+            (RebaseTodoAction::Drop, "333"),
+        ]
+    );
+
+    // Rejecting the todo list cancels the rebase.
+    let todo_editor = RebaseTodoDelegate::new(&mut cx.to_async(), |_, tx, _| {
+        tx.send(None).ok();
+    });
+    repository
+        .update(cx, |repository, _| {
+            repository.rebase("main".into(), Some(todo_editor))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let cancelled_todo = fs
+        .with_git_state(path!("/project/.git").as_ref(), false, |state| {
+            state.rebase_todo.clone()
+        })
+        .unwrap();
+    assert_eq!(cancelled_todo, todo);
+
+    // A rebase stopped on a conflict is reported, until it is aborted.
+    fs.with_git_state(path!("/project/.git").as_ref(), true, |state| {
+        state.rebase_progress = Some(RebaseProgress {
+            head_name: Some("feature".into()),
+            step: 1,
+            total: 3,
+        });
+    })
+    .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository.in_progress_operation,
+            Some(InProgressOperation::Rebase)
+        );
+        assert_eq!(
+            repository
+                .rebase_progress
+                .as_ref()
+                .map(|progress| progress.step),
+            Some(1)
+        );
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.control_operation(InProgressOperation::Rebase, OperationControl::Skip)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository
+                .rebase_progress
+                .as_ref()
+                .map(|progress| progress.step),
+            Some(2)
+        );
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.control_operation(InProgressOperation::Rebase, OperationControl::Abort)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.in_progress_operation, None);
+        assert_eq!(repository.rebase_progress, None);
+    });
+}
+
+#[gpui::test]
+async fn test_load_conflict_stages(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n",
+        }),
+    )
+    .await;
+    fs.with_git_state(path!("/project/.git").as_ref(), false, |state| {
+        for (stage, text) in [
+            (IndexStage::Base, "base\n"),
+            (IndexStage::Ours, "ours\n"),
+            (IndexStage::Theirs, "theirs\n"),
+        ] {
+            state
+                .conflicted_index_contents
+                .insert((repo_path("a.txt"), stage), text.into());
+        }
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    let stages = repository
+        .update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path("a.txt"))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stages,
+        ConflictStages {
+            base: Some("base\n".into()),
+            ours: Some("ours\n".into()),
+            theirs: Some("theirs\n".into()),
+        }
+    );
+
+    // A path added on only one side of the merge has no base.
+    fs.with_git_state(path!("/project/.git").as_ref(), false, |state| {
+        state
+            .conflicted_index_contents
+            .remove(&(repo_path("a.txt"), IndexStage::Base));
+    })
+    .unwrap();
+    let stages = repository
+        .update(cx, |repository, _| {
+            repository.load_conflict_stages(repo_path("a.txt"))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stages.base, None);
+    assert_eq!(stages.ours.as_deref(), Some("ours\n"));
+}
+
+#[gpui::test]
+async fn test_tags_and_branch_deletion(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.with_git_state(path!("/project/.git").as_ref(), false, |state| {
+        state
+            .branches
+            .extend(["main".to_string(), "feature".to_string()]);
+        state.current_branch_name = Some("main".into());
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| project.active_repository(cx).unwrap());
+
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("v1.0".into(), None, Some("First release".into()))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("nightly".into(), Some("def".into()), None)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags.iter()
+            .map(|tag| (tag.name.as_ref(), tag.sha.as_ref(), tag.is_annotated()))
+            .collect::<Vec<_>>(),
+        [("nightly", "def", false), ("v1.0", "abc", true)]
+    );
+
+    repository
+        .update(cx, |repository, _| repository.delete_tag("nightly".into()))
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags.iter().map(|tag| tag.name.as_ref()).collect::<Vec<_>>(),
+        ["v1.0"]
+    );
+
+    // The current branch can't be deleted.
+    repository
+        .update(cx, |repository, _| {
+            repository.delete_branch("main".into(), true)
+        })
+        .await
+        .unwrap()
+        .unwrap_err();
+    repository
+        .update(cx, |repository, _| {
+            repository.delete_branch("feature".into(), false)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let branches = repository
+        .update(cx, |repository, _| repository.branches())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        branches
+            .iter()
+            .map(|branch| branch.name())
+            .collect::<Vec<_>>(),
+        ["main"]
+    );
+}
+
 #[gpui::test]
 async fn test_repository_deduplication(cx: &mut gpui::TestAppContext) {
     init_test(cx);
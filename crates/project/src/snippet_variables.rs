@@ -0,0 +1,109 @@
+use anyhow::Result;
+use gpui::App;
+use language::{BufferSnapshot, CharKind, Point};
+use snippet::Snippet;
+use std::path::Path;
+
+/// Parses a snippet inserted at `offset` in `buffer`, expanding the variables that depend on
+/// the buffer, such as `$TM_FILENAME`. `selected_text` is the text the snippet replaces, which
+/// `$TM_SELECTED_TEXT` and `$SELECTION` expand to.
+pub fn parse_snippet_for_buffer(
+    source: &str,
+    buffer: &BufferSnapshot,
+    offset: usize,
+    selected_text: Option<&str>,
+    cx: &App,
+) -> Result<Snippet> {
+    Snippet::parse_with_variables(source, |name| {
+        resolve_snippet_variable(name, buffer, offset, selected_text, cx)
+    })
+}
+
+fn resolve_snippet_variable(
+    name: &str,
+    buffer: &BufferSnapshot,
+    offset: usize,
+    selected_text: Option<&str>,
+    cx: &App,
+) -> Option<String> {
+    let file = buffer.file();
+    let point = buffer.offset_to_point(offset);
+    let value = match name {
+        "TM_SELECTED_TEXT" | "SELECTION" => {
+            selected_text.filter(|text| !text.is_empty())?.to_string()
+        }
+        "TM_CURRENT_LINE" => buffer
+            .text_for_range(
+                Point::new(point.row, 0)..Point::new(point.row, buffer.line_len(point.row)),
+            )
+            .collect(),
+        "TM_CURRENT_WORD" => {
+            let (range, kind) = buffer.surrounding_word(offset, None);
+            if range.is_empty() || kind != Some(CharKind::Word) {
+                return None;
+            }
+            buffer.text_for_range(range).collect()
+        }
+        "TM_LINE_INDEX" => point.row.to_string(),
+        "TM_LINE_NUMBER" => (point.row + 1).to_string(),
+        "TM_FILENAME" => file?.file_name(cx).to_string(),
+        "TM_FILENAME_BASE" => Path::new(file?.file_name(cx))
+            .file_stem()?
+            .to_string_lossy()
+            .into_owned(),
+        "TM_DIRECTORY" => file?
+            .as_local()?
+            .abs_path(cx)
+            .parent()?
+            .to_string_lossy()
+            .into_owned(),
+        "TM_FILEPATH" => file?
+            .as_local()?
+            .abs_path(cx)
+            .to_string_lossy()
+            .into_owned(),
+        "RELATIVE_FILEPATH" => {
+            let file = file?;
+            file.path().display(file.path_style(cx)).into_owned()
+        }
+        "WORKSPACE_NAME" => file?
+            .full_path(cx)
+            .components()
+            .next()?
+            .as_os_str()
+            .to_string_lossy()
+            .into_owned(),
+        "WORKSPACE_FOLDER" => {
+            let file = file?;
+            file.as_local()?
+                .abs_path(cx)
+                .ancestors()
+                .nth(file.path().components().count())?
+                .to_string_lossy()
+                .into_owned()
+        }
+        "CLIPBOARD" => cx.read_from_clipboard()?.text()?,
+        "CURSOR_INDEX" => "0".to_string(),
+        "CURSOR_NUMBER" => "1".to_string(),
+        "LINE_COMMENT" => buffer
+            .language_scope_at(offset)?
+            .line_comment_prefixes()
+            .first()?
+            .trim_end()
+            .to_string(),
+        "BLOCK_COMMENT_START" => buffer
+            .language_scope_at(offset)?
+            .block_comment()?
+            .start
+            .trim_end()
+            .to_string(),
+        "BLOCK_COMMENT_END" => buffer
+            .language_scope_at(offset)?
+            .block_comment()?
+            .end
+            .trim_start()
+            .to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -21,8 +21,8 @@ use lsp::{LanguageServerId, LanguageServerName};
 use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
-    DebugScenario, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
-    VariableName,
+    DebugScenario, DependsOrder, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates,
+    TaskVariables, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
@@ -155,6 +155,135 @@ pub enum TaskSourceKind {
     },
 }
 
+/// The tasks to run for a task with dependencies, see [`TaskTemplate::depends_on`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskExecutionGraph {
+    /// Every task comes after all tasks it waits for, the task the graph was built for is the last one.
+    pub tasks: Vec<TaskExecutionNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskExecutionNode {
+    pub source_kind: TaskSourceKind,
+    pub template: TaskTemplate,
+    /// Indices into [`TaskExecutionGraph::tasks`] of the tasks that have to succeed before this one starts.
+    pub waits_for: Vec<usize>,
+}
+
+impl TaskExecutionGraph {
+    /// Builds the graph for `root`, looking its dependencies up by label in `available`.
+    /// A task that several other tasks depend on is only run once.
+    /// Fails if a dependency can't be found or if the dependencies form a cycle.
+    pub fn new(
+        root: (TaskSourceKind, TaskTemplate),
+        available: &[(TaskSourceKind, TaskTemplate)],
+    ) -> Result<Self> {
+        let mut index_by_label = HashMap::from_iter([(root.1.label.clone(), 0)]);
+        let mut tasks = vec![root];
+        let mut waits_for = Vec::<Vec<usize>>::new();
+        let mut sequence_edges = Vec::new();
+        let mut ix = 0;
+        while ix < tasks.len() {
+            let (source_kind, template) = tasks[ix].clone();
+            let mut dependencies = Vec::with_capacity(template.depends_on.len());
+            for label in &template.depends_on {
+                let dependency_ix = match index_by_label.get(label) {
+                    Some(dependency_ix) => *dependency_ix,
+                    None => {
+                        // Prefer tasks from the same file as the one depending on them.
+                        let dependency = available
+                            .iter()
+                            .filter(|(_, candidate)| &candidate.label == label)
+                            .min_by_key(|(kind, _)| kind != &source_kind)
+                            .with_context(|| {
+                                format!(
+                                    "Task `{}` depends on an unknown task `{label}`",
+                                    template.label
+                                )
+                            })?;
+                        tasks.push(dependency.clone());
+                        index_by_label.insert(label.clone(), tasks.len() - 1);
+                        tasks.len() - 1
+                    }
+                };
+                if !dependencies.contains(&dependency_ix) {
+                    dependencies.push(dependency_ix);
+                }
+            }
+            if template.depends_order == DependsOrder::Sequence {
+                sequence_edges.extend(
+                    dependencies
+                        .iter()
+                        .tuple_windows()
+                        .map(|(previous, next)| (*next, *previous)),
+                );
+            }
+            waits_for.push(dependencies);
+            ix += 1;
+        }
+        for (task_ix, dependency_ix) in sequence_edges {
+            if !waits_for[task_ix].contains(&dependency_ix) {
+                waits_for[task_ix].push(dependency_ix);
+            }
+        }
+
+        let mut order = Vec::with_capacity(tasks.len());
+        let mut visited = vec![false; tasks.len()];
+        let mut path = Vec::new();
+        Self::visit(0, &tasks, &waits_for, &mut visited, &mut path, &mut order)?;
+
+        let mut new_indices = vec![0; tasks.len()];
+        for (new_ix, old_ix) in order.iter().enumerate() {
+            new_indices[*old_ix] = new_ix;
+        }
+        let mut tasks = tasks.into_iter().map(Some).collect::<Vec<_>>();
+        let tasks = order
+            .into_iter()
+            .filter_map(|old_ix| {
+                let (source_kind, template) = tasks[old_ix].take()?;
+                Some(TaskExecutionNode {
+                    source_kind,
+                    template,
+                    waits_for: waits_for[old_ix]
+                        .iter()
+                        .map(|dependency_ix| new_indices[*dependency_ix])
+                        .collect(),
+                })
+            })
+            .collect();
+        Ok(Self { tasks })
+    }
+
+    fn visit(
+        ix: usize,
+        tasks: &[(TaskSourceKind, TaskTemplate)],
+        waits_for: &[Vec<usize>],
+        visited: &mut [bool],
+        path: &mut Vec<usize>,
+        order: &mut Vec<usize>,
+    ) -> Result<()> {
+        if let Some(cycle_start) = path.iter().position(|path_ix| *path_ix == ix) {
+            let cycle = path[cycle_start..]
+                .iter()
+                .chain([&ix])
+                .map(|ix| tasks[*ix].1.label.as_str())
+                .join(" -> ");
+            bail!("Task dependencies form a cycle: {cycle}");
+        }
+        if visited[ix] {
+            return Ok(());
+        }
+        path.push(ix);
+        for dependency_ix in &waits_for[ix] {
+            Self::visit(*dependency_ix, tasks, waits_for, visited, path, order)?;
+        }
+        path.pop();
+        visited[ix] = true;
+        order.push(ix);
+        Ok(())
+    }
+}
+
 /// A collection of task contexts, derived from the current state of the workspace.
 /// Only contains worktrees that are visible and with their root being a directory.
 #[derive(Debug, Default)]
@@ -373,6 +502,20 @@ impl Inventory {
         })
     }
 
+    /// Looks up the tasks that `template` depends on, transitively, among the tasks available for the worktree given.
+    pub fn task_execution_graph(
+        &self,
+        task_source_kind: TaskSourceKind,
+        template: TaskTemplate,
+        worktree_id: Option<WorktreeId>,
+        cx: &App,
+    ) -> Task<Result<TaskExecutionGraph>> {
+        let tasks = self.list_tasks(None, None, worktree_id, cx);
+        cx.background_spawn(async move {
+            TaskExecutionGraph::new((task_source_kind, template), &tasks.await)
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -1543,6 +1686,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_task_execution_graph() {
+        let kind = TaskSourceKind::UserInput;
+        let task = |label: &str, depends_on: &[&str], depends_order: DependsOrder| {
+            (
+                kind.clone(),
+                TaskTemplate {
+                    label: label.to_string(),
+                    command: "echo".to_string(),
+                    depends_on: depends_on.iter().map(|label| label.to_string()).collect(),
+                    depends_order,
+                    ..TaskTemplate::default()
+                },
+            )
+        };
+        let labels = |graph: &TaskExecutionGraph| {
+            graph
+                .tasks
+                .iter()
+                .map(|node| (node.template.label.clone(), node.waits_for.clone()))
+                .collect::<Vec<_>>()
+        };
+        let available = [
+            task("build", &[], DependsOrder::Parallel),
+            task("lint", &[], DependsOrder::Parallel),
+            task("test", &["build"], DependsOrder::Parallel),
+            task("check", &["lint", "test"], DependsOrder::Parallel),
+            task("release", &["lint", "test"], DependsOrder::Sequence),
+            task("ping", &["pong"], DependsOrder::Parallel),
+            task("pong", &["ping"], DependsOrder::Parallel),
+            task("broken", &["missing"], DependsOrder::Parallel),
+        ];
+
+        let graph = TaskExecutionGraph::new(available[3].clone(), &available).unwrap();
+        assert_eq!(
+            labels(&graph),
+            [
+                ("lint".to_string(), vec![]),
+                ("build".to_string(), vec![]),
+                ("test".to_string(), vec![1]),
+                ("check".to_string(), vec![0, 2]),
+            ]
+        );
+
+        let graph = TaskExecutionGraph::new(available[4].clone(), &available).unwrap();
+        assert_eq!(
+            labels(&graph),
+            [
+                ("lint".to_string(), vec![]),
+                ("build".to_string(), vec![]),
+                ("test".to_string(), vec![1, 0]),
+                ("release".to_string(), vec![0, 2]),
+            ]
+        );
+
+        let error = TaskExecutionGraph::new(available[5].clone(), &available).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependencies form a cycle: ping -> pong -> ping"
+        );
+
+        let error = TaskExecutionGraph::new(available[7].clone(), &available).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `broken` depends on an unknown task `missing`"
+        );
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
@@ -15,7 +15,7 @@ use editor::{
 };
 use file_icons::FileIcons;
 use git::status::GitSummary;
-use git_ui::file_diff_view::FileDiffView;
+use git_ui::{file_diff_view::FileDiffView, git_log_view::GitLogView};
 use gpui::{
     Action, AnyElement, App, AsyncWindowContext, Bounds, ClipboardItem, Context, CursorStyle,
     DismissEvent, Div, DragMoveEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable,
@@ -1002,6 +1002,18 @@ impl ProjectPanel {
                 && (cfg!(target_os = "windows")
                     || (settings.hide_root && visible_worktrees_count == 1));
             let should_show_compare = !is_dir && self.file_abs_paths_to_diff(cx).is_some();
+            let should_show_file_history = !is_dir
+                && project
+                    .git_store()
+                    .read(cx)
+                    .repository_and_path_for_project_path(
+                        &ProjectPath {
+                            worktree_id: worktree.id(),
+                            path: entry.path.clone(),
+                        },
+                        cx,
+                    )
+                    .is_some();
 
             let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -1037,6 +1049,10 @@ impl ProjectPanel {
                                 menu.separator()
                                     .action("Compare marked files", Box::new(CompareMarkedFiles))
                             })
+                            .when(should_show_file_history, |menu| {
+                                menu.separator()
+                                    .action("File History", Box::new(git::FileHistory))
+                            })
                             .separator()
                             .action("Cut", Box::new(Cut))
                             .action("Copy", Box::new(Copy))
@@ -2872,6 +2888,30 @@ impl ProjectPanel {
         }
     }
 
+    fn file_history(&mut self, _: &git::FileHistory, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((worktree, entry)) = self.selected_sub_entry(cx) else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: entry.path.clone(),
+        };
+        let Some((repository, path)) = self
+            .project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                GitLogView::open(repository, Some(path), workspace, window, cx);
+            })
+            .ok();
+    }
+
     fn open_system(&mut self, _: &OpenWithSystem, _: &mut Window, cx: &mut Context<Self>) {
         if let Some((worktree, entry)) = self.selected_entry(cx) {
             let abs_path = worktree.absolutize(&entry.path);
@@ -5466,6 +5506,7 @@ impl Render for ProjectPanel {
                 .on_action(cx.listener(Self::fold_directory))
                 .on_action(cx.listener(Self::remove_from_project))
                 .on_action(cx.listener(Self::compare_marked_files))
+                .on_action(cx.listener(Self::file_history))
                 .when(!project.is_read_only(cx), |el| {
                     el.on_action(cx.listener(Self::new_file))
                         .on_action(cx.listener(Self::new_directory))
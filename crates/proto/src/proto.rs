@@ -289,6 +289,17 @@ messages!(
     (GitCheckoutFiles, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitMerge, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitRebase, Background),
+    (GitEditRebaseTodo, Background),
+    (GitEditRebaseTodoResponse, Background),
+    (GitControlOperation, Background),
+    (LoadConflictStages, Background),
+    (LoadConflictStagesResponse, Background),
     (SetIndexText, Background),
     (Push, Background),
     (Fetch, Background),
@@ -301,6 +312,13 @@ messages!(
     (GitCreateBranch, Background),
     (GitChangeBranch, Background),
     (GitRenameBranch, Background),
+    (GitDeleteBranch, Background),
+    (GitDeleteRemoteBranch, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
     (CheckForPushedCommits, Background),
     (CheckForPushedCommitsResponse, Background),
     (GitDiff, Background),
@@ -333,7 +351,28 @@ messages!(
     (RemoteStarted, Background),
     (GitGetWorktrees, Background),
     (GitWorktreesResponse, Background),
-    (GitCreateWorktree, Background)
+    (GitCreateWorktree, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background)
 );
 
 request_messages!(
@@ -368,6 +407,12 @@ request_messages!(
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -405,6 +450,7 @@ request_messages!(
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -423,6 +469,10 @@ request_messages!(
     (ResolveInlayHint, ResolveInlayHintResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetColorPresentation, GetColorPresentationResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
@@ -484,6 +534,14 @@ request_messages!(
     (InstallExtension, Ack),
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
+    (GitLog, GitLogResponse),
+    (GitMerge, Ack),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitRebase, Ack),
+    (GitEditRebaseTodo, GitEditRebaseTodoResponse),
+    (GitControlOperation, Ack),
+    (LoadConflictStages, LoadConflictStagesResponse),
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
     (SetIndexText, Ack),
@@ -495,6 +553,12 @@ request_messages!(
     (GitCreateBranch, Ack),
     (GitChangeBranch, Ack),
     (GitRenameBranch, Ack),
+    (GitDeleteBranch, Ack),
+    (GitDeleteRemoteBranch, RemoteMessageResponse),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
     (GitInit, Ack),
@@ -529,6 +593,9 @@ lsp_messages!(
     (GetTypeDefinition, GetTypeDefinitionResponse, true),
     (GetImplementation, GetImplementationResponse, true),
     (InlayHints, InlayHintsResponse, false),
+    (GetSemanticTokens, GetSemanticTokensResponse, true),
+    (GetFoldingRanges, GetFoldingRangesResponse, true),
+    (GetDocumentLinks, GetDocumentLinksResponse, true),
 );
 
 entity_messages!(
@@ -547,6 +614,9 @@ entity_messages!(
     CreateBufferForPeer,
     CreateProjectEntry,
     GetDocumentColor,
+    GetSemanticTokens,
+    GetFoldingRanges,
+    GetDocumentLinks,
     DeleteProjectEntry,
     ExpandProjectEntry,
     ExpandAllForProjectEntry,
@@ -561,6 +631,12 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetDocumentSymbols,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
     GetHover,
     GetProjectSymbols,
     GetReferences,
@@ -587,11 +663,13 @@ entity_messages!(
     PrepareRename,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    ResolveDocumentLink,
     SaveBuffer,
     Stage,
     StartLanguageServer,
@@ -645,6 +723,14 @@ entity_messages!(
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
     GitShow,
+    GitLog,
+    GitMerge,
+    GitCherryPick,
+    GitRevert,
+    GitRebase,
+    GitEditRebaseTodo,
+    GitControlOperation,
+    LoadConflictStages,
     GitReset,
     GitCheckoutFiles,
     SetIndexText,
@@ -658,6 +744,12 @@ entity_messages!(
     AskPassRequest,
     GitChangeBranch,
     GitRenameBranch,
+    GitDeleteBranch,
+    GitDeleteRemoteBranch,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
     GitCreateBranch,
     CheckForPushedCommits,
     GitDiff,
@@ -864,6 +956,9 @@ impl LspQuery {
             Some(lsp_query::Request::GetReferences(_)) => ("GetReferences", false),
             Some(lsp_query::Request::GetDocumentColor(_)) => ("GetDocumentColor", false),
             Some(lsp_query::Request::InlayHints(_)) => ("InlayHints", false),
+            Some(lsp_query::Request::GetSemanticTokens(_)) => ("GetSemanticTokens", false),
+            Some(lsp_query::Request::GetFoldingRanges(_)) => ("GetFoldingRanges", false),
+            Some(lsp_query::Request::GetDocumentLinks(_)) => ("GetDocumentLinks", false),
             None => ("<unknown>", true),
         }
     }
@@ -9,6 +9,7 @@ mod wsl_picker;
 use remote::RemoteConnectionOptions;
 pub use remote_connections::{RemoteConnectionModal, connect, open_remote_project};
 
+use anyhow::Context as _;
 use disconnected_overlay::DisconnectedOverlay;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
@@ -23,7 +24,10 @@ use picker::{
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
 use settings::Settings;
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
 use util::{ResultExt, paths::PathExt};
 use workspace::{
@@ -31,7 +35,7 @@ use workspace::{
     WORKSPACE_DB, Workspace, WorkspaceId, notifications::DetachAndPromptErr,
     with_active_or_new_workspace,
 };
-use zed_actions::{OpenRecent, OpenRemote};
+use zed_actions::{OpenDevContainer, OpenRecent, OpenRemote};
 
 pub fn init(cx: &mut App) {
     SshSettings::register(cx);
@@ -161,6 +165,61 @@ pub fn init(cx: &mut App) {
         });
     });
 
+    cx.on_action(|open_dev_container: &OpenDevContainer, cx| {
+        let create_new_window = open_dev_container.create_new_window;
+        with_active_or_new_workspace(cx, move |workspace, window, cx| {
+            let project = workspace.project().read(cx);
+            let local_folder = project
+                .is_local()
+                .then(|| project.visible_worktrees(cx).next())
+                .flatten()
+                .map(|worktree| worktree.read(cx).abs_path());
+            let app_state = workspace.app_state().clone();
+            let replace_window = if create_new_window {
+                None
+            } else {
+                window.window_handle().downcast::<Workspace>()
+            };
+
+            cx.spawn_in(window, async move |_, cx| {
+                let local_folder = local_folder
+                    .context("Dev containers can only be opened from a local project")?;
+                let mut config_path = None;
+                for candidate in remote::DEV_CONTAINER_CONFIG_PATHS {
+                    let path = local_folder.join(candidate);
+                    if app_state.fs.is_file(&path).await {
+                        config_path = Some(path);
+                        break;
+                    }
+                }
+                let config_path = config_path.with_context(|| {
+                    format!("No devcontainer.json found in {}", local_folder.display())
+                })?;
+                let config = remote::DevContainer::parse(&app_state.fs.load(&config_path).await?)?;
+
+                let connection_options = config.connection_options(&config_path);
+                let workspace_folder = config.workspace_folder(&local_folder);
+                open_remote_project(
+                    RemoteConnectionOptions::Docker(connection_options),
+                    vec![PathBuf::from(workspace_folder)],
+                    app_state,
+                    OpenOptions {
+                        replace_window,
+                        ..Default::default()
+                    },
+                    cx,
+                )
+                .await
+            })
+            .detach_and_prompt_err(
+                "Failed to open dev container",
+                window,
+                cx,
+                |_, _, _| None,
+            );
+        });
+    });
+
     cx.observe_new(DisconnectedOverlay::register).detach();
 }
 
@@ -564,6 +623,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                     Icon::new(match options {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
+                                        RemoteConnectionOptions::Docker { .. } => IconName::Server,
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
@@ -291,6 +291,7 @@ impl RemoteConnectionModal {
                 (options.connection_string(), options.nickname.clone(), false)
             }
             RemoteConnectionOptions::Wsl(options) => (options.distro_name.clone(), None, true),
+            RemoteConnectionOptions::Docker(options) => (options.display_name(), None, false),
         };
         Self {
             prompt: cx.new(|cx| {
@@ -667,6 +668,9 @@ pub async fn open_remote_project(
                                 match connection_options {
                                     RemoteConnectionOptions::Ssh(_) => "Failed to connect over SSH",
                                     RemoteConnectionOptions::Wsl(_) => "Failed to connect to WSL",
+                                    RemoteConnectionOptions::Docker(_) => {
+                                        "Failed to connect to container"
+                                    }
                                 },
                                 Some(&e.to_string()),
                                 &["Retry", "Cancel"],
@@ -723,6 +727,9 @@ pub async fn open_remote_project(
                             match connection_options {
                                 RemoteConnectionOptions::Ssh(_) => "Failed to connect over SSH",
                                 RemoteConnectionOptions::Wsl(_) => "Failed to connect to WSL",
+                                RemoteConnectionOptions::Docker(_) => {
+                                    "Failed to connect to container"
+                                }
                             },
                             Some(&e.to_string()),
                             &["Retry", "Cancel"],
@@ -134,6 +134,9 @@ enum ProjectPickerData {
     Wsl {
         distro_name: SharedString,
     },
+    Docker {
+        container: SharedString,
+    },
 }
 
 struct ProjectPicker {
@@ -207,6 +210,9 @@ impl ProjectPicker {
             RemoteConnectionOptions::Wsl(connection) => ProjectPickerData::Wsl {
                 distro_name: connection.distro_name.clone().into(),
             },
+            RemoteConnectionOptions::Docker(connection) => ProjectPickerData::Docker {
+                container: connection.display_name().into(),
+            },
         };
         let _path_task = cx
             .spawn_in(window, {
@@ -358,6 +364,13 @@ impl gpui::Render for ProjectPicker {
                     is_wsl: true,
                 }
                 .render(window, cx),
+                ProjectPickerData::Docker { container } => SshConnectionHeader {
+                    connection_string: container.clone(),
+                    paths: Default::default(),
+                    nickname: None,
+                    is_wsl: false,
+                }
+                .render(window, cx),
             })
             .child(
                 div()
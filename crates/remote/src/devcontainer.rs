@@ -0,0 +1,575 @@
+use crate::transport::docker::{
+    ContainerRuntime, DockerConnectionOptions, run_container_runtime_command,
+};
+use anyhow::{Context as _, Result, bail};
+use collections::{BTreeMap, FxHasher};
+use serde::Deserialize;
+use std::{
+    hash::{Hash as _, Hasher as _},
+    path::{Path, PathBuf},
+};
+
+/// Where a dev container configuration may live, relative to the project root.
+pub const DEV_CONTAINER_CONFIG_PATHS: [&str; 2] =
+    [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// The label attached to containers created from a `devcontainer.json`.
+const CONFIG_FILE_LABEL: &str = "dev.zed.devcontainer.config_file";
+
+/// The subset of a [`devcontainer.json`](https://containers.dev/implementors/json_reference/)
+/// needed to build, start and connect to a dev container.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevContainer {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub build: Option<DevContainerBuild>,
+    /// Legacy alias of `build.dockerfile`.
+    docker_file: Option<String>,
+    /// Legacy alias of `build.context`.
+    context: Option<String>,
+    pub docker_compose_file: Option<OneOrMany>,
+    pub service: Option<String>,
+    #[serde(default)]
+    pub run_services: Vec<String>,
+    pub workspace_folder: Option<String>,
+    pub workspace_mount: Option<String>,
+    #[serde(default)]
+    pub run_args: Vec<String>,
+    #[serde(default)]
+    pub container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub mounts: Vec<DevContainerMount>,
+    pub container_user: Option<String>,
+    pub remote_user: Option<String>,
+    pub override_command: Option<bool>,
+    pub post_create_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct DevContainerBuild {
+    pub dockerfile: Option<String>,
+    pub context: Option<String>,
+    #[serde(default)]
+    pub args: BTreeMap<String, String>,
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn to_vec(&self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value.clone()],
+            OneOrMany::Many(values) => values.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum DevContainerMount {
+    /// A mount in `--mount` syntax, e.g. `source=cache,target=/cache,type=volume`.
+    Raw(String),
+    Structured {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: String,
+    },
+}
+
+/// A lifecycle command: a shell string, an argv array, or a set of named commands.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LifecycleCommand {
+    Shell(String),
+    Exec(Vec<String>),
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+impl LifecycleCommand {
+    fn argvs(&self) -> Vec<Vec<String>> {
+        match self {
+            LifecycleCommand::Shell(script) => {
+                vec![vec![
+                    "/bin/sh".to_string(),
+                    "-c".to_string(),
+                    script.clone(),
+                ]]
+            }
+            LifecycleCommand::Exec(argv) => vec![argv.clone()],
+            LifecycleCommand::Parallel(commands) => {
+                commands.values().flat_map(Self::argvs).collect()
+            }
+        }
+    }
+}
+
+impl DevContainer {
+    pub fn parse(contents: &str) -> Result<Self> {
+        serde_json_lenient::from_str(contents).context("failed to parse devcontainer.json")
+    }
+
+    pub async fn load(config_path: &Path) -> Result<Self> {
+        let contents = smol::fs::read_to_string(config_path)
+            .await
+            .with_context(|| format!("failed to read {}", config_path.display()))?;
+        Self::parse(&contents)
+    }
+
+    /// The project folder a configuration belongs to.
+    pub fn local_folder(config_path: &Path) -> PathBuf {
+        let config_dir = config_path.parent().unwrap_or(Path::new("/"));
+        if config_dir
+            .file_name()
+            .is_some_and(|name| name == ".devcontainer")
+        {
+            config_dir.parent().unwrap_or(config_dir).to_path_buf()
+        } else {
+            config_dir.to_path_buf()
+        }
+    }
+
+    /// A stable container name (or Compose project name) for the given project folder.
+    pub fn container_name(local_folder: &Path) -> String {
+        let basename = local_folder
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let basename = basename
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let mut hasher = FxHasher::default();
+        local_folder.hash(&mut hasher);
+        format!("zed-{}-{:08x}", basename, hasher.finish() as u32)
+    }
+
+    /// The folder inside the container that the project is mounted at.
+    pub fn workspace_folder(&self, local_folder: &Path) -> String {
+        match &self.workspace_folder {
+            Some(folder) => self.substitute(folder, local_folder),
+            None => format!("/workspaces/{}", basename(local_folder)),
+        }
+    }
+
+    /// The user that Zed should connect as.
+    pub fn user(&self) -> Option<String> {
+        self.remote_user
+            .clone()
+            .or_else(|| self.container_user.clone())
+    }
+
+    pub fn connection_options(&self, config_path: &Path) -> DockerConnectionOptions {
+        DockerConnectionOptions {
+            container: Self::container_name(&Self::local_folder(config_path)),
+            runtime: ContainerRuntime::detect(),
+            user: self.user(),
+            devcontainer: Some(config_path.to_path_buf()),
+        }
+    }
+
+    /// Replaces the `${...}` variables supported in `devcontainer.json` values.
+    fn substitute(&self, value: &str, local_folder: &Path) -> String {
+        let mut result = String::new();
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            result.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            let variable = &rest[start + 2..start + len];
+            match self.resolve_variable(variable, local_folder) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..=start + len]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn resolve_variable(&self, variable: &str, local_folder: &Path) -> Option<String> {
+        if variable.starts_with("containerWorkspaceFolder")
+            && self
+                .workspace_folder
+                .as_ref()
+                .is_some_and(|folder| folder.contains("${containerWorkspaceFolder"))
+        {
+            return None;
+        }
+        match variable {
+            "localWorkspaceFolder" => Some(local_folder.to_string_lossy().into_owned()),
+            "localWorkspaceFolderBasename" => Some(basename(local_folder)),
+            "containerWorkspaceFolder" => Some(self.workspace_folder(local_folder)),
+            "containerWorkspaceFolderBasename" => {
+                let folder = self.workspace_folder(local_folder);
+                Some(basename(Path::new(&folder)))
+            }
+            _ => {
+                let env = variable
+                    .strip_prefix("localEnv:")
+                    .or_else(|| variable.strip_prefix("env:"))?;
+                let (name, default) = env.split_once(':').unwrap_or((env, ""));
+                Some(std::env::var(name).unwrap_or_else(|_| default.to_string()))
+            }
+        }
+    }
+
+    /// Builds or starts the container described by the configuration at `config_path`, returning
+    /// the id of the container to connect to.
+    pub(crate) async fn up(
+        config_path: &Path,
+        options: &DockerConnectionOptions,
+    ) -> Result<String> {
+        let config = Self::load(config_path).await?;
+        let local_folder = Self::local_folder(config_path);
+        let config_dir = config_path.parent().unwrap_or(Path::new("/"));
+
+        let (container_id, created) = if let Some(compose_files) = &config.docker_compose_file {
+            config
+                .compose_up(compose_files, config_dir, options)
+                .await?
+        } else {
+            config
+                .run(config_path, config_dir, &local_folder, options)
+                .await?
+        };
+
+        if created && let Some(command) = &config.post_create_command {
+            for argv in command.argvs() {
+                let mut args = vec!["exec".to_string()];
+                if let Some(user) = &options.user {
+                    args.push("--user".to_string());
+                    args.push(user.clone());
+                }
+                args.push("--workdir".to_string());
+                args.push(config.workspace_folder(&local_folder));
+                args.push(container_id.clone());
+                args.extend(argv);
+                run_container_runtime_command(options.runtime, &args)
+                    .await
+                    .context("postCreateCommand failed")?;
+            }
+        }
+
+        Ok(container_id)
+    }
+
+    async fn compose_up(
+        &self,
+        compose_files: &OneOrMany,
+        config_dir: &Path,
+        options: &DockerConnectionOptions,
+    ) -> Result<(String, bool)> {
+        let service = self
+            .service
+            .as_deref()
+            .context("devcontainer.json must specify a service when using dockerComposeFile")?;
+        let mut compose_args = vec![
+            "compose".to_string(),
+            "--project-name".to_string(),
+            options.container.clone(),
+        ];
+        for file in compose_files.to_vec() {
+            compose_args.push("--file".to_string());
+            compose_args.push(config_dir.join(file).to_string_lossy().into_owned());
+        }
+
+        let ps_args = compose_args
+            .iter()
+            .cloned()
+            .chain(["ps".to_string(), "--quiet".to_string(), service.to_string()])
+            .collect::<Vec<_>>();
+        let existing = run_container_runtime_command(options.runtime, &ps_args)
+            .await
+            .unwrap_or_default();
+
+        let mut up_args = compose_args;
+        up_args.extend([
+            "up".to_string(),
+            "--detach".to_string(),
+            service.to_string(),
+        ]);
+        up_args.extend(self.run_services.iter().cloned());
+        run_container_runtime_command(options.runtime, &up_args)
+            .await
+            .context("failed to start Docker Compose services")?;
+
+        let container_id = run_container_runtime_command(options.runtime, &ps_args).await?;
+        let container_id = container_id.lines().next().unwrap_or_default().to_string();
+        if container_id.is_empty() {
+            bail!("no container is running for service {service}");
+        }
+        Ok((container_id, existing.is_empty()))
+    }
+
+    async fn run(
+        &self,
+        config_path: &Path,
+        config_dir: &Path,
+        local_folder: &Path,
+        options: &DockerConnectionOptions,
+    ) -> Result<(String, bool)> {
+        let runtime = options.runtime;
+        let name = &options.container;
+        if let Ok(running) = run_container_runtime_command(
+            runtime,
+            &[
+                "container",
+                "inspect",
+                "--format",
+                "{{.State.Running}}",
+                name,
+            ],
+        )
+        .await
+        {
+            if running != "true" {
+                run_container_runtime_command(runtime, &["start", name]).await?;
+            }
+            return Ok((name.clone(), false));
+        }
+
+        let image = self.image(config_dir, local_folder, options).await?;
+        let args = self.docker_run_args(config_path, local_folder, name, image);
+        run_container_runtime_command(runtime, &args)
+            .await
+            .context("failed to create dev container")?;
+        Ok((name.clone(), true))
+    }
+
+    async fn image(
+        &self,
+        config_dir: &Path,
+        local_folder: &Path,
+        options: &DockerConnectionOptions,
+    ) -> Result<String> {
+        let dockerfile = self
+            .build
+            .as_ref()
+            .and_then(|build| build.dockerfile.clone())
+            .or_else(|| self.docker_file.clone());
+        let Some(dockerfile) = dockerfile else {
+            return self.image.clone().context(
+                "devcontainer.json must specify an image, a Dockerfile or a Compose file",
+            );
+        };
+
+        let context = self
+            .build
+            .as_ref()
+            .and_then(|build| build.context.clone())
+            .or_else(|| self.context.clone())
+            .unwrap_or_else(|| ".".to_string());
+        let tag = format!("{}-image", options.container);
+        let mut args = vec![
+            "build".to_string(),
+            "--file".to_string(),
+            config_dir.join(dockerfile).to_string_lossy().into_owned(),
+            "--tag".to_string(),
+            tag.clone(),
+        ];
+        if let Some(build) = &self.build {
+            for (key, value) in &build.args {
+                args.push("--build-arg".to_string());
+                args.push(format!("{key}={}", self.substitute(value, local_folder)));
+            }
+            if let Some(target) = &build.target {
+                args.push("--target".to_string());
+                args.push(target.clone());
+            }
+        }
+        args.push(config_dir.join(context).to_string_lossy().into_owned());
+
+        run_container_runtime_command(options.runtime, &args)
+            .await
+            .context("failed to build dev container image")?;
+        Ok(tag)
+    }
+
+    fn docker_run_args(
+        &self,
+        config_path: &Path,
+        local_folder: &Path,
+        name: &str,
+        image: String,
+    ) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "--detach".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+            "--label".to_string(),
+            format!("{CONFIG_FILE_LABEL}={}", config_path.to_string_lossy()),
+            "--mount".to_string(),
+        ];
+        match &self.workspace_mount {
+            Some(mount) => args.push(self.substitute(mount, local_folder)),
+            None => args.push(format!(
+                "type=bind,source={},target={}",
+                local_folder.to_string_lossy(),
+                self.workspace_folder(local_folder)
+            )),
+        }
+        for mount in &self.mounts {
+            args.push("--mount".to_string());
+            args.push(match mount {
+                DevContainerMount::Raw(mount) => self.substitute(mount, local_folder),
+                DevContainerMount::Structured {
+                    source,
+                    target,
+                    kind,
+                } => {
+                    let mut mount = format!("type={kind}");
+                    if let Some(source) = source {
+                        mount.push_str(&format!(
+                            ",source={}",
+                            self.substitute(source, local_folder)
+                        ));
+                    }
+                    mount.push_str(&format!(
+                        ",target={}",
+                        self.substitute(target, local_folder)
+                    ));
+                    mount
+                }
+            });
+        }
+        for (key, value) in &self.container_env {
+            args.push("--env".to_string());
+            args.push(format!("{key}={}", self.substitute(value, local_folder)));
+        }
+        if let Some(user) = &self.container_user {
+            args.push("--user".to_string());
+            args.push(user.clone());
+        }
+        args.extend(
+            self.run_args
+                .iter()
+                .map(|arg| self.substitute(arg, local_folder)),
+        );
+
+        // Like other dev container tools, keep the container alive regardless of what the
+        // image would run by default.
+        if self.override_command.unwrap_or(true) {
+            args.extend([
+                "--entrypoint".to_string(),
+                "/bin/sh".to_string(),
+                image,
+                "-c".to_string(),
+                "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done".to_string(),
+            ]);
+        } else {
+            args.push(image);
+        }
+        args
+    }
+}
+
+fn basename(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devcontainer() {
+        let config = DevContainer::parse(include_str!("../test_data/devcontainer.json")).unwrap();
+        assert_eq!(config.name.as_deref(), Some("Rust"));
+        assert_eq!(
+            config.build,
+            Some(DevContainerBuild {
+                dockerfile: Some("Dockerfile".to_string()),
+                context: Some("..".to_string()),
+                args: BTreeMap::from_iter([("VARIANT".to_string(), "bookworm".to_string())]),
+                target: None,
+            })
+        );
+        assert_eq!(config.user().as_deref(), Some("vscode"));
+        assert_eq!(
+            config.post_create_command,
+            Some(LifecycleCommand::Exec(vec![
+                "cargo".to_string(),
+                "fetch".to_string()
+            ]))
+        );
+
+        let local_folder = Path::new("/home/me/projects/zed");
+        assert_eq!(
+            DevContainer::local_folder(Path::new(
+                "/home/me/projects/zed/.devcontainer/devcontainer.json"
+            )),
+            local_folder
+        );
+        assert_eq!(config.workspace_folder(local_folder), "/workspaces/zed/src");
+
+        let args = config.docker_run_args(
+            Path::new("/home/me/projects/zed/.devcontainer/devcontainer.json"),
+            local_folder,
+            "zed-dev",
+            "zed-dev-image".to_string(),
+        );
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--detach",
+                "--name",
+                "zed-dev",
+                "--label",
+                "dev.zed.devcontainer.config_file=/home/me/projects/zed/.devcontainer/devcontainer.json",
+                "--mount",
+                "type=bind,source=/home/me/projects/zed,target=/workspaces/zed",
+                "--mount",
+                "type=volume,source=zed-target,target=/workspaces/zed/target",
+                "--env",
+                "PROJECT=zed",
+                "--cap-add=SYS_PTRACE",
+                "--entrypoint",
+                "/bin/sh",
+                "zed-dev-image",
+                "-c",
+                "trap 'exit 0' TERM; while sleep 1000 & wait $!; do :; done",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_workspace_folder() {
+        let config = DevContainer::parse(r#"{ "image": "debian" }"#).unwrap();
+        assert_eq!(
+            config.workspace_folder(Path::new("/home/me/my-app")),
+            "/workspaces/my-app"
+        );
+        assert_eq!(
+            config.substitute("${unknownVariable}/x", Path::new("/home/me/my-app")),
+            "${unknownVariable}/x"
+        );
+        assert_eq!(
+            config.substitute(
+                "${localEnv:ZED_DEVCONTAINER_TEST_UNSET:fallback}",
+                Path::new("/home/me/my-app")
+            ),
+            "fallback"
+        );
+
+        let name = DevContainer::container_name(Path::new("/home/me/My App"));
+        assert!(name.starts_with("zed-my_app-"), "{name}");
+        assert_eq!(
+            name,
+            DevContainer::container_name(Path::new("/home/me/My App"))
+        );
+    }
+}
@@ -1,14 +1,17 @@
+mod devcontainer;
 pub mod json_log;
 pub mod protocol;
 pub mod proxy;
 pub mod remote_client;
 mod transport;
 
+pub use devcontainer::{DEV_CONTAINER_CONFIG_PATHS, DevContainer};
 #[cfg(target_os = "windows")]
 pub use remote_client::OpenWslPath;
 pub use remote_client::{
     ConnectionIdentifier, ConnectionState, RemoteClient, RemoteClientDelegate, RemoteClientEvent,
     RemoteConnection, RemoteConnectionOptions, RemotePlatform, connect,
 };
+pub use transport::docker::{ContainerRuntime, DockerConnectionOptions};
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
@@ -0,0 +1,721 @@
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    devcontainer::DevContainer,
+    remote_client::{CommandTemplate, RemoteConnection, RemoteConnectionOptions},
+};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::{
+    AsyncWriteExt as _,
+    channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender},
+};
+use gpui::{App, AppContext as _, AsyncApp, SemanticVersion, Task};
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use smol::{fs, process};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Instant,
+};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    shell::ShellKind,
+};
+
+/// The command line tool used to talk to the container engine.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    /// Picks `docker` when it is installed, falling back to `podman`.
+    pub fn detect() -> Self {
+        if which::which("docker").is_err() && which::which("podman").is_ok() {
+            Self::Podman
+        } else {
+            Self::Docker
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+
+    pub fn serialize(&self) -> &'static str {
+        self.program()
+    }
+
+    pub fn deserialize(text: &str) -> Option<Self> {
+        match text {
+            "docker" => Some(Self::Docker),
+            "podman" => Some(Self::Podman),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, schemars::JsonSchema)]
+pub struct DockerConnectionOptions {
+    /// The name or id of the container to connect to.
+    ///
+    /// For Docker Compose based dev containers, this is the compose project name instead.
+    pub container: String,
+    pub runtime: ContainerRuntime,
+    pub user: Option<String>,
+    /// A local `devcontainer.json` used to build or start the container before connecting.
+    pub devcontainer: Option<PathBuf>,
+}
+
+impl DockerConnectionOptions {
+    pub fn display_name(&self) -> String {
+        self.container.clone()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct DockerRemoteConnection {
+    container_id: String,
+    remote_binary_path: Option<String>,
+    platform: RemotePlatform,
+    home_dir: String,
+    shell: String,
+    shell_kind: ShellKind,
+    default_system_shell: String,
+    connection_options: DockerConnectionOptions,
+}
+
+impl DockerRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: DockerConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to {} container {} with user {:?}",
+            connection_options.runtime.program(),
+            connection_options.container,
+            connection_options.user
+        );
+        let (release_channel, version, commit) = cx.update(|cx| {
+            (
+                ReleaseChannel::global(cx),
+                AppVersion::global(cx),
+                AppCommitSha::try_global(cx),
+            )
+        })?;
+
+        let container_id = match &connection_options.devcontainer {
+            Some(config_path) => {
+                delegate.set_status(Some("Starting dev container"), cx);
+                DevContainer::up(config_path, &connection_options).await?
+            }
+            None => connection_options.container.clone(),
+        };
+
+        let mut this = Self {
+            container_id,
+            connection_options,
+            remote_binary_path: None,
+            platform: RemotePlatform { os: "", arch: "" },
+            home_dir: String::new(),
+            shell: String::new(),
+            shell_kind: ShellKind::Posix,
+            default_system_shell: String::from("/bin/sh"),
+        };
+        delegate.set_status(Some("Detecting container environment"), cx);
+        this.ensure_running().await?;
+        this.platform = this.detect_platform().await?;
+        this.home_dir = this.detect_home_dir().await?;
+        this.shell = this.detect_shell().await;
+        this.shell_kind = ShellKind::new(&this.shell, false);
+        this.remote_binary_path = Some(
+            this.ensure_server_binary(&delegate, release_channel, version, commit, cx)
+                .await?,
+        );
+        log::debug!("Detected container environment: {this:#?}");
+
+        Ok(this)
+    }
+
+    async fn ensure_running(&self) -> Result<()> {
+        let runtime = self.connection_options.runtime;
+        let running = run_container_runtime_command(
+            runtime,
+            &[
+                "container",
+                "inspect",
+                "--format",
+                "{{.State.Running}}",
+                &self.container_id,
+            ],
+        )
+        .await
+        .with_context(|| format!("no container named {}", self.container_id))?;
+        if running != "true" {
+            run_container_runtime_command(runtime, &["start", &self.container_id])
+                .await
+                .with_context(|| format!("failed to start container {}", self.container_id))?;
+        }
+        Ok(())
+    }
+
+    async fn detect_platform(&self) -> Result<RemotePlatform> {
+        let os = self.run_container_command("uname", &["-s"]).await?;
+        if !os.eq_ignore_ascii_case("linux") {
+            bail!("unsupported container OS: {os}");
+        }
+        let arch = self.run_container_command("uname", &["-m"]).await?;
+        let arch = match arch.as_str() {
+            "x86_64" => "x86_64",
+            "aarch64" | "arm64" => "aarch64",
+            _ => bail!("unsupported container architecture: {arch}"),
+        };
+        Ok(RemotePlatform { os: "linux", arch })
+    }
+
+    async fn detect_home_dir(&self) -> Result<String> {
+        let home_dir = self
+            .run_container_command("sh", &["-c", "cd && pwd"])
+            .await
+            .context("failed to detect home directory in container")?;
+        if home_dir.is_empty() {
+            Ok("/".to_string())
+        } else {
+            Ok(home_dir)
+        }
+    }
+
+    async fn detect_shell(&self) -> String {
+        self.run_container_command("sh", &["-c", "echo $SHELL"])
+            .await
+            .ok()
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or_else(|| "/bin/sh".to_string())
+    }
+
+    fn container_command(&self, program: &str, args: &[impl AsRef<OsStr>]) -> process::Command {
+        container_command_impl(&self.connection_options, &self.container_id, program, args)
+    }
+
+    async fn run_container_command(&self, program: &str, args: &[&str]) -> Result<String> {
+        let output = self.container_command(program, args).output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Command '{}' failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+
+    fn server_dir(&self) -> String {
+        format!(
+            "{}/{}",
+            self.home_dir.trim_end_matches('/'),
+            paths::remote_server_dir_relative().display(PathStyle::Posix)
+        )
+    }
+
+    async fn ensure_server_binary(
+        &self,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        release_channel: ReleaseChannel,
+        version: SemanticVersion,
+        commit: Option<AppCommitSha>,
+        cx: &mut AsyncApp,
+    ) -> Result<String> {
+        let version_str = match release_channel {
+            ReleaseChannel::Nightly => {
+                let commit = commit.map(|s| s.full()).unwrap_or_default();
+                format!("{}-{}", version, commit)
+            }
+            ReleaseChannel::Dev => "build".to_string(),
+            _ => version.to_string(),
+        };
+
+        let binary_name = format!(
+            "zed-remote-server-{}-{}",
+            release_channel.dev_name(),
+            version_str
+        );
+        let server_dir = self.server_dir();
+        let dst_path = format!("{server_dir}/{binary_name}");
+
+        self.run_container_command("mkdir", &["-p", &server_dir])
+            .await
+            .map_err(|e| anyhow!("Failed to create directory: {}", e))?;
+
+        #[cfg(debug_assertions)]
+        if let Some(remote_server_path) =
+            super::build_remote_server_from_source(&self.platform, delegate.as_ref(), cx).await?
+        {
+            let tmp_path = format!(
+                "{server_dir}/download-{}-{}",
+                std::process::id(),
+                remote_server_path.file_name().unwrap().to_string_lossy()
+            );
+            self.upload_file(&remote_server_path, &tmp_path, delegate, cx)
+                .await?;
+            self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+                .await?;
+            return Ok(dst_path);
+        }
+
+        if self
+            .run_container_command(&dst_path, &["version"])
+            .await
+            .is_ok()
+        {
+            return Ok(dst_path);
+        }
+
+        delegate.set_status(Some("Installing remote server"), cx);
+
+        let wanted_version = match release_channel {
+            ReleaseChannel::Nightly | ReleaseChannel::Dev => None,
+            _ => Some(cx.update(|cx| AppVersion::global(cx))?),
+        };
+
+        let src_path = delegate
+            .download_server_binary_locally(self.platform, release_channel, wanted_version, cx)
+            .await?;
+
+        let tmp_path = format!("{}.{}.gz", dst_path, std::process::id());
+        self.upload_file(&src_path, &tmp_path, delegate, cx).await?;
+        self.extract_and_install(&tmp_path, &dst_path, delegate, cx)
+            .await?;
+
+        Ok(dst_path)
+    }
+
+    /// Streams a local file into the container over the stdin of an exec'd `cat`, so that the file
+    /// ends up owned by the connecting user rather than by root, as it would with `docker cp`.
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Uploading remote server to container"), cx);
+
+        let t0 = Instant::now();
+        let src_stat = fs::metadata(&src_path).await?;
+        let size = src_stat.len();
+        log::info!(
+            "uploading remote server to container {:?} ({}kb)",
+            dst_path,
+            size / 1024
+        );
+
+        // The script runs under `sh`, whatever the user's shell in the container is.
+        let script = format!(
+            "cat > {}",
+            ShellKind::Posix
+                .try_quote(dst_path)
+                .context("shell quoting")?
+        );
+        let mut child = self.container_command("sh", &["-c", &script]).spawn()?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("failed to open container stdin")?;
+        let mut file = fs::File::open(src_path).await?;
+        futures::io::copy(&mut file, &mut stdin).await?;
+        stdin.close().await?;
+        drop(stdin);
+
+        let output = child.output().await?;
+        if !output.status.success() {
+            bail!(
+                "Failed to upload file {} to container {:?}: {}",
+                src_path.display(),
+                dst_path,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        log::info!("uploaded remote server in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    async fn extract_and_install(
+        &self,
+        tmp_path: &str,
+        dst_path: &str,
+        delegate: &Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        delegate.set_status(Some("Extracting remote server"), cx);
+
+        let script = if tmp_path.ends_with(".gz") {
+            let uncompressed = tmp_path.trim_end_matches(".gz");
+            format!(
+                "set -e; gunzip -f '{}' && chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path, uncompressed, uncompressed, dst_path
+            )
+        } else {
+            format!(
+                "set -e; chmod 755 '{}' && mv -f '{}' '{}'",
+                tmp_path, tmp_path, dst_path
+            )
+        };
+
+        self.run_container_command("sh", &["-c", &script])
+            .await
+            .map_err(|e| anyhow!("Failed to extract server binary: {}", e))?;
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for DockerRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut proxy_args = vec![];
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Some(value) = std::env::var(env_var).ok() {
+                proxy_args.push(format!("{}={}", env_var, value));
+            }
+        }
+        proxy_args.push(remote_binary_path.clone());
+        proxy_args.push("proxy".to_owned());
+        proxy_args.push("--identifier".to_owned());
+        proxy_args.push(unique_identifier);
+
+        if reconnect {
+            proxy_args.push("--reconnect".to_owned());
+        }
+        let proxy_process = match self
+            .container_command("env", &proxy_args)
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(process) => process,
+            Err(error) => {
+                return Task::ready(Err(anyhow!("failed to spawn remote server: {}", error)));
+            }
+        };
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let runtime = self.connection_options.runtime;
+        let destination = format!("{}:{}", self.container_id, dest_path);
+        cx.background_spawn(async move {
+            run_container_runtime_command(
+                runtime,
+                &["cp", &src_path.to_string_lossy(), &destination],
+            )
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "failed to upload directory {} -> {}: {}",
+                    src_path.display(),
+                    dest_path,
+                    e
+                )
+            })?;
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        false
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        if port_forward.is_some() {
+            bail!("port forwarding is not supported for containers, publish the port instead");
+        }
+
+        build_command(
+            program,
+            args,
+            env,
+            working_dir,
+            &self.container_id,
+            &self.connection_options,
+            &self.shell,
+            self.shell_kind,
+        )
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _: Vec<(u16, String, u16)>,
+    ) -> anyhow::Result<CommandTemplate> {
+        Err(anyhow!(
+            "port forwarding is not supported for containers, publish the port instead"
+        ))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Docker(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        self.default_system_shell.clone()
+    }
+}
+
+fn build_command(
+    program: Option<String>,
+    args: &[String],
+    env: &HashMap<String, String>,
+    working_dir: Option<String>,
+    container_id: &str,
+    connection_options: &DockerConnectionOptions,
+    shell: &str,
+    shell_kind: ShellKind,
+) -> Result<CommandTemplate> {
+    let mut exec = String::new();
+    match working_dir.map(|working_dir| RemotePathBuf::new(working_dir, PathStyle::Posix)) {
+        Some(working_dir) => {
+            let working_dir = working_dir.to_string();
+            // Quoting disables ~ expansion, so resolve it against $HOME instead.
+            if let Some(relative) = working_dir.strip_prefix("~/") {
+                write!(exec, "cd \"$HOME/{relative}\" && ")?;
+            } else if working_dir == "~" {
+                write!(exec, "cd && ")?;
+            } else {
+                write!(exec, "cd \"{working_dir}\" && ")?;
+            }
+        }
+        None => write!(exec, "cd && ")?,
+    }
+    write!(exec, "exec env ")?;
+
+    for (k, v) in env.iter() {
+        write!(
+            exec,
+            "{}={} ",
+            k,
+            shell_kind.try_quote(v).context("shell quoting")?
+        )?;
+    }
+
+    if let Some(program) = program {
+        write!(
+            exec,
+            "{}",
+            shell_kind
+                .try_quote_prefix_aware(&program)
+                .context("shell quoting")?
+        )?;
+        for arg in args {
+            let arg = shell_kind.try_quote(&arg).context("shell quoting")?;
+            write!(exec, " {}", &arg)?;
+        }
+    } else {
+        write!(exec, "{shell} -l")?;
+    }
+
+    let mut command_args = vec!["exec".to_string(), "-it".to_string()];
+    if let Some(user) = &connection_options.user {
+        command_args.push("--user".to_string());
+        command_args.push(user.clone());
+    }
+    command_args.extend([
+        container_id.to_string(),
+        shell.to_string(),
+        "-c".to_string(),
+        exec,
+    ]);
+
+    Ok(CommandTemplate {
+        program: connection_options.runtime.program().to_string(),
+        args: command_args,
+        env: HashMap::default(),
+    })
+}
+
+/// Runs a command of the container runtime itself, such as `docker start`.
+pub(crate) async fn run_container_runtime_command(
+    runtime: ContainerRuntime,
+    args: &[impl AsRef<OsStr>],
+) -> Result<String> {
+    let mut command = util::command::new_smol_command(runtime.program());
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    log::debug!("{} {:?}", runtime.program(), command);
+
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("failed to run {}", runtime.program()))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Command '{}' failed: {}",
+            runtime.program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Creates a new `docker exec` command that runs the given program inside the container.
+pub(crate) fn container_command_impl(
+    options: &DockerConnectionOptions,
+    container_id: &str,
+    program: &str,
+    args: &[impl AsRef<OsStr>],
+) -> process::Command {
+    let mut command = util::command::new_smol_command(options.runtime.program());
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .arg("exec")
+        .arg("-i");
+
+    if let Some(user) = &options.user {
+        command.arg("--user").arg(user);
+    }
+
+    command.arg(container_id).arg(program).args(args);
+
+    log::debug!("{} {:?}", options.runtime.program(), command);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_command() -> Result<()> {
+        let options = DockerConnectionOptions {
+            container: "dev".to_string(),
+            runtime: ContainerRuntime::Podman,
+            user: Some("vscode".to_string()),
+            devcontainer: None,
+        };
+        let mut env = HashMap::default();
+        env.insert("INPUT_VA".to_string(), "val".to_string());
+
+        let command = build_command(
+            Some("remote_program".to_string()),
+            &["arg1".to_string(), "arg2".to_string()],
+            &env,
+            Some("~/work".to_string()),
+            "3f2a",
+            &options,
+            "/bin/bash",
+            ShellKind::Posix,
+        )?;
+        assert_eq!(command.program, "podman");
+        assert_eq!(
+            command.args.iter().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "exec",
+                "-it",
+                "--user",
+                "vscode",
+                "3f2a",
+                "/bin/bash",
+                "-c",
+                "cd \"$HOME/work\" && exec env INPUT_VA=val remote_program arg1 arg2"
+            ]
+        );
+
+        let command = build_command(
+            None,
+            &[],
+            &HashMap::default(),
+            Some("/workspaces/zed".to_string()),
+            "3f2a",
+            &DockerConnectionOptions {
+                user: None,
+                ..options
+            },
+            "/bin/bash",
+            ShellKind::Posix,
+        )?;
+        assert_eq!(
+            command.args.iter().map(String::as_str).collect::<Vec<_>>(),
+            [
+                "exec",
+                "-it",
+                "3f2a",
+                "/bin/bash",
+                "-c",
+                "cd \"/workspaces/zed\" && exec env /bin/bash -l"
+            ]
+        );
+
+        Ok(())
+    }
+}
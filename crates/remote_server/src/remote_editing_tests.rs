@@ -10,6 +10,10 @@ use language_model::LanguageModelToolResultContent;
 
 use extension::ExtensionHostProxy;
 use fs::{FakeFs, Fs};
+use git::{
+    log::{LogEntry, LogOptions},
+    repository::RepoPath,
+};
 use gpui::{AppContext as _, Entity, SemanticVersion, SharedString, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
@@ -1703,6 +1707,124 @@ async fn test_remote_git_branches(cx: &mut TestAppContext, server_cx: &mut TestA
     assert_eq!(server_branch.name(), "totally-new-branch");
 }
 
+#[gpui::test]
+async fn test_remote_git_log(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+                "src": { "lib.rs": "" },
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let commit = |sha: &str, author: &str, path: &str, refs: &[&str]| LogEntry {
+        sha: sha.to_string().into(),
+        subject: format!("Commit {sha}").into(),
+        author_name: author.to_string().into(),
+        author_email: format!("{}@example.com", author.to_lowercase()).into(),
+        refs: refs.iter().map(|name| name.to_string().into()).collect(),
+        path: Some(RepoPath::new(path).unwrap()),
+        ..LogEntry::default()
+    };
+    fs.set_commits_for_repo(
+        Path::new(path!("/code/project1/.git")),
+        vec![
+            commit("ddd", "Jane", "src/lib.rs", &["main"]),
+            commit("ccc", "John", "README.md", &[]),
+            commit("bbb", "Jane", "README.md", &["tag: v1.0"]),
+            commit("aaa", "John", "src/lib.rs", &[]),
+        ],
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let repository = project.update(cx, |project, cx| project.active_repository(cx).unwrap());
+    let mut log = async |options: LogOptions| {
+        let log = repository
+            .update(cx, |repository, _| repository.log(options))
+            .await
+            .unwrap()?;
+        anyhow::Ok((
+            log.entries
+                .iter()
+                .map(|entry| entry.sha.to_string())
+                .collect::<Vec<_>>(),
+            log.has_more,
+        ))
+    };
+
+    assert_eq!(
+        log(LogOptions {
+            limit: 3,
+            ..LogOptions::default()
+        })
+        .await
+        .unwrap(),
+        (vec!["ddd".into(), "ccc".into(), "bbb".into()], true)
+    );
+    assert_eq!(
+        log(LogOptions {
+            skip: 3,
+            limit: 3,
+            ..LogOptions::default()
+        })
+        .await
+        .unwrap(),
+        (vec!["aaa".into()], false)
+    );
+    assert_eq!(
+        log(LogOptions {
+            path: Some(RepoPath::new("README.md").unwrap()),
+            limit: 10,
+            ..LogOptions::default()
+        })
+        .await
+        .unwrap(),
+        (vec!["ccc".into(), "bbb".into()], false)
+    );
+    assert_eq!(
+        log(LogOptions {
+            author: Some("Jane".into()),
+            limit: 10,
+            ..LogOptions::default()
+        })
+        .await
+        .unwrap(),
+        (vec!["ddd".into(), "bbb".into()], false)
+    );
+    assert_eq!(
+        log(LogOptions {
+            revision: Some("ccc".into()),
+            limit: 1,
+            ..LogOptions::default()
+        })
+        .await
+        .unwrap(),
+        (vec!["ccc".into()], true)
+    );
+    assert!(
+        log(LogOptions {
+            revision: Some("--output=/tmp/log".into()),
+            limit: 10,
+            ..LogOptions::default()
+        })
+        .await
+        .is_err()
+    );
+}
+
 #[gpui::test]
 async fn test_remote_agent_fs_tool_calls(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
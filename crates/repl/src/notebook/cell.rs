@@ -125,7 +125,7 @@ impl Cell {
                 id,
                 metadata,
                 source,
-                ..
+                attachments,
             } => {
                 let source = source.join("");
 
@@ -155,6 +155,7 @@ impl Cell {
                         id: id.clone(),
                         metadata: metadata.clone(),
                         source: source.clone(),
+                        attachments: attachments.clone(),
                         parsed_markdown: None,
                         selected: false,
                         cell_position: None,
@@ -172,7 +173,7 @@ impl Cell {
             } => Cell::Code(cx.new(|cx| {
                 let text = source.join("");
 
-                let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
+                let buffer = cx.new(|cx| Buffer::local(text, cx));
                 let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
 
                 let editor_view = cx.new(|cx| {
@@ -197,7 +198,6 @@ impl Cell {
                         ..Default::default()
                     };
 
-                    editor.set_text(text, window, cx);
                     editor.set_show_gutter(false, cx);
                     editor.set_text_style_refinement(refinement);
 
@@ -221,6 +221,7 @@ impl Cell {
                     source: source.join(""),
                     editor: editor_view,
                     outputs: convert_outputs(outputs, window, cx),
+                    notebook_outputs: outputs.clone(),
                     selected: false,
                     language_task,
                     cell_position: None,
@@ -239,6 +240,47 @@ impl Cell {
             })),
         }
     }
+
+    /// Converts the cell back into its nbformat representation, picking up any edits made to it.
+    pub fn to_nbformat(&self, cx: &App) -> nbformat::v4::Cell {
+        match self {
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Markdown {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                    attachments: cell.attachments.clone(),
+                }
+            }
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Code {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    execution_count: cell.execution_count,
+                    source: source_lines(&cell.editor.read(cx).text(cx)),
+                    outputs: cell.notebook_outputs.clone(),
+                }
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                nbformat::v4::Cell::Raw {
+                    id: cell.id.clone(),
+                    metadata: cell.metadata.clone(),
+                    source: source_lines(&cell.source),
+                }
+            }
+        }
+    }
+}
+
+/// Splits a cell's source into the lines nbformat stores, each keeping its trailing newline.
+fn source_lines(source: &str) -> Vec<String> {
+    source
+        .split_inclusive('\n')
+        .map(ToString::to_string)
+        .collect()
 }
 
 pub trait RenderableCell: Render {
@@ -337,6 +379,7 @@ pub struct MarkdownCell {
     metadata: CellMetadata,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
+    attachments: Option<serde_json::Value>,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
     selected: bool,
@@ -386,6 +429,29 @@ impl RenderableCell for MarkdownCell {
     }
 }
 
+impl MarkdownCell {
+    /// Replaces the cell's markdown source and renders it again.
+    pub fn set_source(&mut self, source: String, cx: &mut Context<Self>) {
+        let languages = self.languages.clone();
+        let parsed_source = source.clone();
+        self.source = source;
+        self.markdown_parsing_task = cx.spawn(async move |this, cx| {
+            let parsed_markdown = cx
+                .background_spawn(async move {
+                    parse_markdown(&parsed_source, None, Some(languages)).await
+                })
+                .await;
+
+            this.update(cx, |cell, cx| {
+                cell.parsed_markdown = Some(parsed_markdown);
+                cx.notify();
+            })
+            .log_err();
+        });
+        cx.notify();
+    }
+}
+
 impl Render for MarkdownCell {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let Some(parsed) = self.parsed_markdown.as_ref() else {
@@ -435,6 +501,8 @@ pub struct CodeCell {
     source: String,
     editor: Entity<editor::Editor>,
     outputs: Vec<Output>,
+    /// The outputs as read from the notebook file, written back as-is when saving.
+    notebook_outputs: Vec<nbformat::v4::Output>,
     selected: bool,
     cell_position: Option<CellPosition>,
     language_task: Task<()>,
@@ -450,6 +518,11 @@ impl CodeCell {
 
     pub fn clear_outputs(&mut self) {
         self.outputs.clear();
+        self.notebook_outputs.clear();
+    }
+
+    pub fn buffer(&self, cx: &App) -> Option<Entity<Buffer>> {
+        self.editor.read(cx).buffer().read(cx).as_singleton()
     }
 
     fn output_control(&self) -> Option<CellControlType> {
@@ -98,29 +98,7 @@ impl NotebookEditor {
         let languages = project.read(cx).languages().clone();
         let language_name = notebook_item.read(cx).language_name();
 
-        let notebook_language = notebook_item.read(cx).notebook_language();
-        let notebook_language = cx
-            .spawn_in(window, async move |_, _| notebook_language.await)
-            .shared();
-
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
-
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
+        let (cell_order, cell_map) = Self::load_cells(&notebook_item, &languages, window, cx);
 
         let notebook_handle = cx.entity().downgrade();
         let cell_count = cell_order.len();
@@ -141,6 +119,94 @@ impl NotebookEditor {
         }
     }
 
+    fn load_cells(
+        notebook_item: &Entity<NotebookItem>,
+        languages: &Arc<LanguageRegistry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> (Vec<CellId>, HashMap<CellId, Cell>) {
+        let notebook_language = notebook_item.read(cx).notebook_language();
+        let notebook_language = cx
+            .spawn_in(window, async move |_, _| notebook_language.await)
+            .shared();
+
+        let mut cell_order = vec![]; // Vec<CellId>
+        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
+
+        for cell in notebook_item.read(cx).notebook.clone().cells.iter() {
+            let cell_id = cell.id();
+            cell_order.push(cell_id.clone());
+            cell_map.insert(
+                cell_id.clone(),
+                Cell::load(cell, languages, notebook_language.clone(), window, cx),
+            );
+        }
+
+        (cell_order, cell_map)
+    }
+
+    /// Builds the nbformat notebook from the original one, with the cells as they are in the editor.
+    fn to_notebook(&self, cx: &App) -> nbformat::v4::Notebook {
+        let mut notebook = self.notebook_item.read(cx).notebook.clone();
+        notebook.cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .map(|cell| cell.to_nbformat(cx))
+            .collect();
+        notebook
+    }
+
+    fn write_notebook(
+        &mut self,
+        project: Entity<Project>,
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let notebook = self.to_notebook(cx);
+        let saved_versions = self
+            .cell_map
+            .values()
+            .filter_map(|cell| match cell {
+                Cell::Code(code_cell) => code_cell.read(cx).buffer(cx),
+                Cell::Markdown(_) | Cell::Raw(_) => None,
+            })
+            .map(|buffer| {
+                let version = buffer.read(cx).version();
+                (buffer, version)
+            })
+            .collect::<Vec<_>>();
+        let fs = project.read(cx).fs().clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let content = serialize_notebook(&notebook)?;
+            fs.atomic_write(abs_path.clone(), content)
+                .await
+                .with_context(|| format!("writing notebook to {abs_path:?}"))?;
+
+            this.update(cx, |this, cx| {
+                let entry_id = project
+                    .read(cx)
+                    .entry_for_path(&project_path, cx)
+                    .map(|entry| entry.id);
+                this.notebook_item.update(cx, |notebook_item, _| {
+                    notebook_item.notebook = notebook;
+                    notebook_item.path = abs_path;
+                    notebook_item.project_path = project_path;
+                    if let Some(entry_id) = entry_id {
+                        notebook_item.id = entry_id;
+                    }
+                });
+                for (buffer, version) in saved_versions {
+                    buffer.update(cx, |buffer, cx| buffer.did_save(version, None, cx));
+                }
+                cx.notify();
+            })
+        })
+    }
+
     fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
         self.cell_map.values().any(|cell| {
             if let Cell::Code(code_cell) = cell {
@@ -566,22 +632,7 @@ impl project::ProjectItem for NotebookItem {
 
                 // todo: watch for changes to the file
                 let file_content = fs.load(abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-
-                        nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let notebook = parse_notebook(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| {
@@ -615,6 +666,54 @@ impl project::ProjectItem for NotebookItem {
     }
 }
 
+fn parse_notebook(file_content: &str) -> Result<nbformat::v4::Notebook> {
+    match nbformat::parse_notebook(file_content) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            // TODO: Decide if we want to mutate the notebook by including Cell IDs
+            // and any other conversions
+
+            Ok(nbformat::upgrade_legacy_notebook(legacy_notebook)?)
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(e) => {
+            anyhow::bail!("Failed to parse notebook: {:?}", e);
+        }
+    }
+}
+
+/// Serializes the notebook the way Jupyter does, with sorted keys, a single space of indentation
+/// and a trailing newline, so that saving a notebook doesn't reformat the whole file.
+fn serialize_notebook(notebook: &nbformat::v4::Notebook) -> Result<String> {
+    fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(map) => {
+                let mut entries = map.into_iter().collect::<Vec<_>>();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                serde_json::Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (key, sort_keys(value)))
+                        .collect(),
+                )
+            }
+            serde_json::Value::Array(values) => {
+                serde_json::Value::Array(values.into_iter().map(sort_keys).collect())
+            }
+            value => value,
+        }
+    }
+
+    let value = sort_keys(serde_json::to_value(notebook)?);
+    let mut content = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    serde::Serialize::serialize(&value, &mut serializer)?;
+    content.push(b'\n');
+    Ok(String::from_utf8(content)?)
+}
+
 impl NotebookItem {
     pub fn language_name(&self) -> Option<String> {
         self.notebook
@@ -769,39 +868,66 @@ impl Item for NotebookEditor {
         // TODO
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
     fn save(
         &mut self,
         _options: SaveOptions,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
+        let notebook_item = self.notebook_item.read(cx);
+        let abs_path = notebook_item.path.clone();
+        let project_path = notebook_item.project_path.clone();
+        self.write_notebook(project, abs_path, project_path, window, cx)
     }
 
-    // TODO
     fn save_as(
         &mut self,
-        _project: Entity<Project>,
-        _path: ProjectPath,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        path: ProjectPath,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save_as() must be implemented if can_save() returns true")
+        let Some(abs_path) = project.read(cx).absolute_path(&path, cx) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "finding the absolute path of {path:?}"
+            )));
+        };
+        self.write_notebook(project, abs_path, path, window, cx)
     }
-    // TODO
+
     fn reload(
         &mut self,
-        _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        let fs = project.read(cx).fs().clone();
+        let abs_path = self.notebook_item.read(cx).path.clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let file_content = fs.load(&abs_path).await?;
+            let notebook = parse_notebook(&file_content)?;
+
+            this.update_in(cx, |this, window, cx| {
+                this.notebook_item.update(cx, |notebook_item, _| {
+                    notebook_item.notebook = notebook;
+                });
+                let (cell_order, cell_map) =
+                    Self::load_cells(&this.notebook_item, &this.languages, window, cx);
+                this.cell_list.reset(cell_order.len());
+                this.selected_cell_index = this
+                    .selected_cell_index
+                    .min(cell_order.len().saturating_sub(1));
+                this.cell_order = cell_order;
+                this.cell_map = cell_map;
+                cx.notify();
+            })
+        })
     }
 
     fn is_dirty(&self, cx: &App) -> bool {
@@ -834,3 +960,174 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReplSettings;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Fs as _};
+    use serde_json::{Value, json};
+    use settings::{Settings as _, SettingsStore};
+    use util::{path, rel_path::rel_path};
+
+    const PYTHON_NOTEBOOK: &str = include_str!("../../test_data/python.ipynb");
+    const DENO_NOTEBOOK: &str = include_str!("../../test_data/deno.ipynb");
+
+    #[test]
+    fn test_notebook_round_trip() {
+        for fixture in [PYTHON_NOTEBOOK, DENO_NOTEBOOK] {
+            let notebook = parse_notebook(fixture).unwrap();
+            let serialized = serialize_notebook(&notebook).unwrap();
+            assert!(serialized.ends_with("}\n"));
+
+            let reserialized = serialize_notebook(&parse_notebook(&serialized).unwrap()).unwrap();
+            assert_eq!(serialized, reserialized, "saving should be stable");
+
+            let original = serde_json::from_str::<Value>(fixture).unwrap();
+            let saved = serde_json::from_str::<Value>(&serialized).unwrap();
+            assert_eq!(saved["nbformat"], original["nbformat"]);
+            assert_eq!(saved["nbformat_minor"], original["nbformat_minor"]);
+            assert_eq!(saved["metadata"], original["metadata"]);
+
+            let original_cells = original["cells"].as_array().unwrap();
+            let saved_cells = saved["cells"].as_array().unwrap();
+            assert_eq!(saved_cells.len(), original_cells.len());
+            for (saved_cell, original_cell) in saved_cells.iter().zip(original_cells) {
+                for key in [
+                    "cell_type",
+                    "id",
+                    "metadata",
+                    "source",
+                    "attachments",
+                    "execution_count",
+                ] {
+                    assert_eq!(saved_cell.get(key), original_cell.get(key), "cell {key}");
+                }
+                let output_types = |cell: &Value| {
+                    cell.get("outputs").map(|outputs| {
+                        outputs
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|output| output["output_type"].clone())
+                            .collect::<Vec<_>>()
+                    })
+                };
+                assert_eq!(output_types(saved_cell), output_types(original_cell));
+            }
+        }
+    }
+
+    #[gpui::test]
+    async fn test_notebook_edits_are_saved(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({ "notebook.ipynb": PYTHON_NOTEBOOK }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let project_path = ProjectPath {
+            worktree_id,
+            path: rel_path("notebook.ipynb").into(),
+        };
+        let notebook_item = cx
+            .update(|cx| {
+                <NotebookItem as project::ProjectItem>::try_open(&project, &project_path, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+
+        let cx = cx.add_empty_window();
+        let notebook = cx.new_window_entity(|window, cx| {
+            NotebookEditor::new(project.clone(), notebook_item, window, cx)
+        });
+        cx.run_until_parked();
+
+        notebook.update(cx, |notebook, cx| {
+            let cells = notebook
+                .cell_order
+                .iter()
+                .map(|id| notebook.cell_map[id].clone())
+                .collect::<Vec<_>>();
+            let Cell::Markdown(markdown_cell) = &cells[0] else {
+                panic!("expected a markdown cell");
+            };
+            markdown_cell.update(cx, |cell, cx| {
+                cell.set_source(
+                    "# Cleaned data\n\n![plot](attachment:plot.png)".to_string(),
+                    cx,
+                );
+            });
+            let Cell::Code(code_cell) = &cells[1] else {
+                panic!("expected a code cell");
+            };
+            let buffer = code_cell.read(cx).buffer(cx).unwrap();
+            buffer.update(cx, |buffer, cx| buffer.edit([(0..8, "rows = 5")], None, cx));
+        });
+        assert!(notebook.read_with(cx, |notebook, cx| notebook.is_dirty(cx)));
+
+        notebook
+            .update_in(cx, |notebook, window, cx| {
+                notebook.save(SaveOptions::default(), project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        assert!(!notebook.read_with(cx, |notebook, cx| notebook.is_dirty(cx)));
+
+        let saved = fs
+            .load(path!("/project/notebook.ipynb").as_ref())
+            .await
+            .unwrap();
+        let saved = serde_json::from_str::<Value>(&saved).unwrap();
+        let original = serde_json::from_str::<Value>(PYTHON_NOTEBOOK).unwrap();
+
+        assert_eq!(saved["metadata"], original["metadata"]);
+        let saved_cells = saved["cells"].as_array().unwrap();
+        let original_cells = original["cells"].as_array().unwrap();
+        assert_eq!(saved_cells.len(), original_cells.len());
+
+        // The edited cells keep their ids, metadata and attachments.
+        assert_eq!(
+            saved_cells[0]["source"],
+            json!(["# Cleaned data\n", "\n", "![plot](attachment:plot.png)"])
+        );
+        assert_eq!(
+            saved_cells[0]["attachments"],
+            original_cells[0]["attachments"]
+        );
+        assert_eq!(
+            saved_cells[1]["source"],
+            json!(["rows = 5\n", "columns = [\"a\", \"b\"]"])
+        );
+        for key in ["id", "metadata", "execution_count"] {
+            assert_eq!(saved_cells[1][key], original_cells[1][key], "cell {key}");
+        }
+
+        // The other cells, and the outputs of the code cells, are written back as they were.
+        for (saved_cell, original_cell) in saved_cells.iter().zip(original_cells).skip(2) {
+            assert_eq!(saved_cell, original_cell);
+        }
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            terminal::init(cx);
+            ReplSettings::register(cx);
+        });
+    }
+}
@@ -831,6 +831,18 @@ impl BufferSearchBar {
         }
     }
 
+    /// Fills an empty search history with the given queries, oldest first, for example with
+    /// the searches of a previous session.
+    pub fn restore_search_history(&mut self, queries: impl IntoIterator<Item = String>) {
+        if !self.search_history.is_empty() {
+            return;
+        }
+        let mut cursor = SearchHistoryCursor::default();
+        for query in queries {
+            self.search_history.add(&mut cursor, query);
+        }
+    }
+
     pub fn select_query(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             query_editor.select_all(&Default::default(), window, cx);
@@ -674,6 +674,91 @@ pub struct VimSettingsContent {
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
     pub highlight_on_yank_duration: Option<u64>,
     pub cursor_shape: Option<CursorShapeSettings>,
+    /// Which registers, macros and history to keep across sessions.
+    pub persistence: Option<VimPersistenceSettingsContent>,
+    /// The path of a vimrc file to load mappings, `let mapleader` and `set` options from.
+    ///
+    /// Default: null
+    pub vimrc: Option<String>,
+}
+
+/// The settings for persisting registers, macros and command/search history across sessions,
+/// like Vim's viminfo or Neovim's ShaDa file.
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug, JsonSchema, MergeFrom)]
+pub struct VimPersistenceSettingsContent {
+    /// Whether to persist registers, macros and history.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The names of the registers whose contents are persisted.
+    ///
+    /// Default: "\"0123456789abcdefghijklmnopqrstuvwxyz-"
+    pub registers: Option<String>,
+    /// Whether to persist the macros recorded with `q`.
+    ///
+    /// Default: true
+    pub macros: Option<bool>,
+    /// The maximum number of entries kept in the `:` command and `/` search history.
+    ///
+    /// Default: 100
+    pub max_history: Option<usize>,
+    /// Whether the persisted state is shared by all workspaces or kept per workspace.
+    ///
+    /// Default: global
+    pub scope: Option<VimPersistenceScope>,
+    /// The file to import registers and history from the first time they are loaded.
+    ///
+    /// Default: none
+    pub import_from: Option<VimPersistenceImport>,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VimPersistenceScope {
+    /// Share registers, macros and history between all workspaces.
+    #[default]
+    Global,
+    /// Keep registers, macros and history per workspace.
+    Workspace,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VimPersistenceImport {
+    /// Don't import anything.
+    #[default]
+    None,
+    /// Import from Vim's `~/.viminfo`.
+    Vim,
+    /// Import from Neovim's ShaDa file, `~/.local/state/nvim/shada/main.shada`.
+    Neovim,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Debug)]
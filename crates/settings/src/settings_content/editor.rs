@@ -195,10 +195,23 @@ pub struct EditorSettingsContent {
     /// Drag and drop related settings
     pub drag_and_drop_selection: Option<DragAndDropSelectionContent>,
 
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+
     /// How to render LSP `textDocument/documentColor` colors in the editor.
     ///
     /// Default: [`DocumentColorsRenderMode::Inlay`]
     pub lsp_document_colors: Option<DocumentColorsRenderMode>,
+    /// Whether to highlight code with the semantic tokens reported by language servers,
+    /// on top of the tree-sitter syntax highlighting.
+    ///
+    /// Default: true
+    pub lsp_semantic_tokens: Option<bool>,
+    /// Whether to fold code by the folding ranges reported by language servers,
+    /// instead of by indentation, for buffers whose servers report them.
+    ///
+    /// Default: true
+    pub lsp_folding_ranges: Option<bool>,
     /// When to show the scrollbar in the completion menu.
     /// This setting can take four values:
     ///
@@ -783,6 +796,22 @@ pub struct DragAndDropSelectionContent {
     pub delay: Option<DelayMs>,
 }
 
+/// Whether to keep the first lines of the scopes enclosing the top of the viewport in view.
+#[skip_serializing_none]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// When true, the first lines of the functions, types and other scopes that enclose the
+    /// top of the viewport stay at the top of the editor while scrolling through them.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+
+    /// The maximum number of nested scopes to show at the top of the editor.
+    ///
+    /// Default: 5
+    pub max_depth: Option<u32>,
+}
+
 /// When to show the minimap in the editor.
 ///
 /// Default: never
@@ -196,6 +196,14 @@ pub enum ContextServerSettingsContent {
         #[serde(flatten)]
         command: ContextServerCommand,
     },
+    Http {
+        /// Whether the context server is enabled.
+        #[serde(default = "default_true")]
+        enabled: bool,
+
+        #[serde(flatten)]
+        endpoint: ContextServerEndpoint,
+    },
     Extension {
         /// Whether the context server is enabled.
         #[serde(default = "default_true")]
@@ -216,6 +224,10 @@ impl ContextServerSettingsContent {
             } => {
                 *custom_enabled = enabled;
             }
+            ContextServerSettingsContent::Http {
+                enabled: http_enabled,
+                endpoint: _,
+            } => *http_enabled = enabled,
             ContextServerSettingsContent::Extension {
                 enabled: ext_enabled,
                 settings: _,
@@ -260,6 +272,57 @@ impl std::fmt::Debug for ContextServerCommand {
     }
 }
 
+#[skip_serializing_none]
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom)]
+pub struct ContextServerEndpoint {
+    /// The URL of the MCP server.
+    pub url: String,
+    /// The HTTP transport the MCP server speaks.
+    ///
+    /// Default: streamable_http
+    pub transport: Option<ContextServerHttpTransport>,
+    /// Headers to send with every request, e.g. for authentication.
+    pub headers: Option<HashMap<String, String>>,
+    /// Token to send in a `Authorization: Bearer` header with every request.
+    pub bearer_token: Option<String>,
+    /// Timeout for tool calls in milliseconds. Defaults to 60000 (60 seconds) if not specified.
+    pub timeout: Option<u64>,
+}
+
+impl std::fmt::Debug for ContextServerEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Header values often carry credentials, so only their names are shown.
+        let header_names = self
+            .headers
+            .as_ref()
+            .map(|headers| headers.keys().collect::<Vec<_>>());
+
+        f.debug_struct("ContextServerEndpoint")
+            .field("url", &self.url)
+            .field("transport", &self.transport)
+            .field("headers", &header_names)
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextServerHttpTransport {
+    /// Messages are posted to the URL and answered with JSON or a stream of server-sent events.
+    #[default]
+    StreamableHttp,
+    /// The legacy transport, where the server sends messages over a server-sent events stream
+    /// opened on the URL and announces the endpoint messages are posted to.
+    Sse,
+}
+
 #[skip_serializing_none]
 #[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct GitSettings {
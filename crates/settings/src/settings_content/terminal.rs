@@ -93,6 +93,12 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: true
     pub keep_selection_on_copy: Option<bool>,
+    /// Whether to load Zed's shell integration into bash, zsh and fish, which marks
+    /// where each prompt, command and its output begin and end.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
     /// Whether to show the terminal button in the status bar.
     ///
     /// Default: true
@@ -116,6 +122,13 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: 10_000
     pub max_scroll_history_lines: Option<usize>,
+    /// The maximum number of lines of each terminal's scrollback to save with the workspace,
+    /// and restore above the new shell session when the workspace is reopened.
+    /// The scrollback is stored unencrypted in Zed's database, including any secrets printed in
+    /// the terminal. 0 disables saving the scrollback.
+    ///
+    /// Default: 0
+    pub max_persisted_scrollback_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<TerminalToolbarContent>,
     /// Scrollbar-related settings
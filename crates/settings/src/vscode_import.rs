@@ -264,6 +264,10 @@ impl VsCodeSettings {
             inline_code_actions: None,
             jupyter: None,
             lsp_document_colors: None,
+            lsp_semantic_tokens: self.read_bool("editor.semanticHighlighting.enabled"),
+            lsp_folding_ranges: self
+                .read_string("editor.foldingStrategy")
+                .map(|strategy| strategy == "auto"),
             lsp_highlight_debounce: None,
             middle_click_paste: None,
             minimap: self.minimap_content(),
@@ -296,6 +300,7 @@ impl VsCodeSettings {
             selection_highlight: self.read_bool("editor.selectionHighlight"),
             show_signature_help_after_edits: self.read_bool("editor.parameterHints.enabled"),
             snippet_sort_order: None,
+            sticky_scroll: self.sticky_scroll_content(),
             toolbar: None,
             use_smartcase_search: self.read_bool("search.smartCase"),
             vertical_scroll_margin: self.read_f32("editor.cursorSurroundingLines"),
@@ -348,6 +353,13 @@ impl VsCodeSettings {
         })
     }
 
+    fn sticky_scroll_content(&self) -> Option<StickyScrollContent> {
+        skip_default(StickyScrollContent {
+            enabled: self.read_bool("editor.stickyScroll.enabled"),
+            max_depth: self.read_u32("editor.stickyScroll.maxLineCount"),
+        })
+    }
+
     fn minimap_content(&self) -> Option<MinimapContent> {
         let minimap_enabled = self.read_bool("editor.minimap.enabled");
         let autohide = self.read_bool("editor.minimap.autohide");
@@ -553,13 +565,35 @@ impl VsCodeSettings {
             env: Option<HashMap<String, String>>,
             // note: we don't support envFile and type
         }
+        #[derive(Deserialize)]
+        struct VsCodeContextServerEndpoint {
+            #[serde(rename = "type")]
+            kind: Option<String>,
+            url: String,
+            headers: Option<HashMap<String, String>>,
+        }
         let Some(mcp) = self.read_value("mcp").and_then(|v| v.as_object()) else {
             return Default::default();
         };
         mcp.iter()
             .filter_map(|(k, v)| {
-                Some((
-                    k.clone().into(),
+                let settings = if v.get("url").is_some() {
+                    let endpoint =
+                        serde_json::from_value::<VsCodeContextServerEndpoint>(v.clone()).ok()?;
+                    ContextServerSettingsContent::Http {
+                        enabled: true,
+                        endpoint: ContextServerEndpoint {
+                            url: endpoint.url,
+                            transport: match endpoint.kind.as_deref() {
+                                Some("sse") => Some(ContextServerHttpTransport::Sse),
+                                _ => None,
+                            },
+                            headers: endpoint.headers,
+                            bearer_token: None,
+                            timeout: None,
+                        },
+                    }
+                } else {
                     ContextServerSettingsContent::Custom {
                         enabled: true,
                         command: serde_json::from_value::<VsCodeContextServerCommand>(v.clone())
@@ -570,8 +604,9 @@ impl VsCodeSettings {
                                 env: cmd.env,
                                 timeout: None,
                             })?,
-                    },
-                ))
+                    }
+                };
+                Some((k.clone().into(), settings))
             })
             .collect()
     }
@@ -725,11 +760,18 @@ impl VsCodeSettings {
             line_height: self
                 .read_f32("terminal.integrated.lineHeight")
                 .map(|lh| TerminalLineHeight::Custom(lh)),
+            max_persisted_scrollback_lines: match self
+                .read_bool("terminal.integrated.enablePersistentSessions")
+            {
+                Some(false) => Some(0),
+                _ => self.read_usize("terminal.integrated.persistentSessionScrollback"),
+            },
             max_scroll_history_lines: self.read_usize("terminal.integrated.scrollback"),
             minimum_contrast: None,
             option_as_meta: self.read_bool("terminal.integrated.macOptionIsMeta"),
             project: self.project_terminal_settings_content(),
             scrollbar: None,
+            shell_integration: self.read_bool("terminal.integrated.shellIntegration.enabled"),
             toolbar: None,
         })
     }
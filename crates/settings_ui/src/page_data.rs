@@ -1352,6 +1352,53 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SectionHeader("Sticky Scroll"),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Enabled",
+                        description: "Keep the first lines of the functions, types and other scopes enclosing the top of the editor visible while scrolling through them.",
+                        field: Box::new(SettingField {
+                            json_path: Some("sticky_scroll.enabled"),
+                            pick: |settings_content| {
+                                settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .as_ref()
+                                    .and_then(|sticky_scroll| sticky_scroll.enabled.as_ref())
+                            },
+                            write: |settings_content, value| {
+                                settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .get_or_insert_default()
+                                    .enabled = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Max Depth",
+                        description: "The maximum number of nested scopes to keep visible.",
+                        field: Box::new(SettingField {
+                            json_path: Some("sticky_scroll.max_depth"),
+                            pick: |settings_content| {
+                                settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .as_ref()
+                                    .and_then(|sticky_scroll| sticky_scroll.max_depth.as_ref())
+                            },
+                            write: |settings_content, value| {
+                                settings_content
+                                    .editor
+                                    .sticky_scroll
+                                    .get_or_insert_default()
+                                    .max_depth = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                     SettingsPageItem::SectionHeader("Signature Help"),
                     SettingsPageItem::SettingItem(SettingItem {
                         title: "Auto Signature Help",
@@ -5069,6 +5116,24 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Shell Integration",
+                    description: "Whether to load Zed's shell integration into bash, zsh and fish, to mark where each command and its output begin and end.",
+                    field: Box::new(SettingField {
+                        json_path: Some("terminal.shell_integration"),
+                        pick: |settings_content| {
+                            settings_content.terminal.as_ref()?.shell_integration.as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .shell_integration = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SectionHeader("Layout Settings"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Default Width",
@@ -5129,6 +5194,28 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Max Persisted Scrollback Lines",
+                    description: "Maximum number of scrollback lines to restore when reopening a workspace (0 disables saving the scrollback).",
+                    field: Box::new(SettingField {
+                        json_path: Some("terminal.max_persisted_scrollback_lines"),
+                        pick: |settings_content| {
+                            settings_content
+                                .terminal
+                                .as_ref()?
+                                .max_persisted_scrollback_lines
+                                .as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .terminal
+                                .get_or_insert_default()
+                                .max_persisted_scrollback_lines = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SectionHeader("Toolbar"),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Breadcrumbs",
@@ -6783,6 +6870,32 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
             }),
             metadata: None,
             files: USER,
+        }));
+        items.push(SettingsPageItem::SettingItem(SettingItem {
+            title: "LSP Semantic Tokens",
+            description: "Whether to highlight code with the semantic tokens of language servers.",
+            field: Box::new(SettingField {
+                json_path: Some("lsp_semantic_tokens"),
+                pick: |settings_content| settings_content.editor.lsp_semantic_tokens.as_ref(),
+                write: |settings_content, value| {
+                    settings_content.editor.lsp_semantic_tokens = value;
+                },
+            }),
+            metadata: None,
+            files: USER,
+        }));
+        items.push(SettingsPageItem::SettingItem(SettingItem {
+            title: "LSP Folding Ranges",
+            description: "Whether to fold code by the folding ranges of language servers instead of by indentation.",
+            field: Box::new(SettingField {
+                json_path: Some("lsp_folding_ranges"),
+                pick: |settings_content| settings_content.editor.lsp_folding_ranges.as_ref(),
+                write: |settings_content, value| {
+                    settings_content.editor.lsp_folding_ranges = value;
+                },
+            }),
+            metadata: None,
+            files: USER,
         }))
     }
     items.extend([
@@ -1,6 +1,15 @@
+mod transform;
+mod variables;
+
 use anyhow::{Context as _, Result};
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+};
+
+pub use transform::Transform;
+pub use variables::KNOWN_VARIABLES;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
@@ -12,14 +21,78 @@ pub struct Snippet {
 pub struct TabStop {
     pub ranges: SmallVec<[Range<isize>; 2]>,
     pub choices: Option<Vec<String>>,
+    /// Ranges mirroring the tabstop's text through a transform, e.g. `${1/(.*)/${1:/upcase}/}`.
+    pub transforms: Vec<(Range<isize>, Transform)>,
+}
+
+struct ParseContext<'a> {
+    resolve_variable: &'a dyn Fn(&str) -> Option<String>,
+    resolved_variables: HashMap<String, Option<String>>,
+    /// Text of every tabstop, used to expand the transforms referring to them.
+    tabstop_values: HashMap<usize, String>,
+    has_tabstop_transforms: bool,
+    /// Unknown variables, which become placeholders once all tabstops are parsed.
+    unknown_variables: Vec<Range<isize>>,
+}
+
+impl ParseContext<'_> {
+    fn variable(&mut self, name: &str) -> Option<String> {
+        let resolve_variable = self.resolve_variable;
+        // Resolve each variable once, so that random values match across parsing passes.
+        self.resolved_variables
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                resolve_variable(name).or_else(|| variables::resolve_builtin_variable(name))
+            })
+            .clone()
+    }
 }
 
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
-            .context("failed to parse snippet")?;
+        Self::parse_with_variables(source, |_| None)
+    }
+
+    /// Parses a snippet, expanding its variables such as `$TM_FILENAME` with `resolve_variable`.
+    /// Date, time and random variables are resolved when `resolve_variable` returns `None` for them.
+    pub fn parse_with_variables(
+        source: &str,
+        resolve_variable: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut cx = ParseContext {
+            resolve_variable: &resolve_variable,
+            resolved_variables: HashMap::default(),
+            tabstop_values: HashMap::default(),
+            has_tabstop_transforms: false,
+            unknown_variables: Vec::new(),
+        };
+        let (mut text, mut tabstops) = parse(source, &mut cx)?;
+        if cx.has_tabstop_transforms {
+            // Transforms may refer to tabstops defined after them, so expand them in a second pass.
+            cx.tabstop_values = tabstops
+                .iter()
+                .filter_map(|(index, tabstop)| {
+                    let range = tabstop.ranges.first()?;
+                    let value = text.get(range.start as usize..range.end as usize)?;
+                    Some((*index, value.to_string()))
+                })
+                .collect();
+            (text, tabstops) = parse(source, &mut cx)?;
+        }
+
+        let mut next_index = tabstops.keys().last().map_or(1, |index| index + 1);
+        for range in cx.unknown_variables.drain(..) {
+            tabstops.insert(
+                next_index,
+                TabStop {
+                    ranges: [range].into_iter().collect(),
+                    ..Default::default()
+                },
+            );
+            next_index += 1;
+        }
+        // Tabstops that only appear in transforms can't be navigated to.
+        tabstops.retain(|_, tabstop| !tabstop.ranges.is_empty());
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
@@ -30,7 +103,7 @@ impl Snippet {
         } else {
             let end_tabstop = TabStop {
                 ranges: [len..len].into_iter().collect(),
-                choices: None,
+                ..Default::default()
             };
 
             if !tabstops.last().is_some_and(|t| *t == end_tabstop) {
@@ -42,17 +115,27 @@ impl Snippet {
     }
 }
 
+fn parse(source: &str, cx: &mut ParseContext) -> Result<(String, BTreeMap<usize, TabStop>)> {
+    let mut text = String::with_capacity(source.len());
+    let mut tabstops = BTreeMap::new();
+    cx.unknown_variables.clear();
+    parse_snippet(source, false, &mut text, &mut tabstops, cx)
+        .context("failed to parse snippet")?;
+    Ok((text, tabstops))
+}
+
 fn parse_snippet<'a>(
     mut source: &'a str,
     nested: bool,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    cx: &mut ParseContext,
 ) -> Result<&'a str> {
     loop {
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                source = parse_tabstop(&source[1..], text, tabstops, cx)?;
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -93,12 +176,18 @@ fn parse_tabstop<'a>(
     mut source: &'a str,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    cx: &mut ParseContext,
 ) -> Result<&'a str> {
     let tabstop_start = text.len();
     let tabstop_index;
     let mut choices = None;
+    let mut transform = None;
 
     if source.starts_with('{') {
+        if source[1..].starts_with(is_variable_start) {
+            return parse_variable(&source[1..], true, text, tabstops, cx);
+        }
+
         let (index, rest) = parse_int(&source[1..])?;
         tabstop_index = index;
         source = rest;
@@ -108,7 +197,14 @@ fn parse_tabstop<'a>(
         }
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, text, tabstops, cx)?;
+        } else if source.starts_with('/') {
+            let (parsed, rest) = Transform::parse(&source[1..])?;
+            source = rest;
+            let value = cx.tabstop_values.get(&tabstop_index);
+            text.push_str(&parsed.apply(value.map_or("", String::as_str)));
+            cx.has_tabstop_transforms = true;
+            transform = Some(parsed);
         }
 
         if source.starts_with('}') {
@@ -116,20 +212,79 @@ fn parse_tabstop<'a>(
         } else {
             anyhow::bail!("expected a closing brace");
         }
-    } else {
+    } else if source.starts_with(is_variable_start) {
+        return parse_variable(source, false, text, tabstops, cx);
+    } else if source.starts_with(|c: char| c.is_ascii_digit()) {
         let (index, rest) = parse_int(source)?;
         tabstop_index = index;
         source = rest;
+    } else {
+        // A dollar sign that starts neither a tabstop nor a variable is just text.
+        text.push('$');
+        return Ok(source);
     }
 
-    tabstops
-        .entry(tabstop_index)
-        .or_insert_with(|| TabStop {
-            ranges: Default::default(),
-            choices,
-        })
-        .ranges
-        .push(tabstop_start as isize..text.len() as isize);
+    let tabstop = tabstops.entry(tabstop_index).or_insert_with(|| TabStop {
+        ranges: Default::default(),
+        choices,
+        transforms: Vec::new(),
+    });
+    let range = tabstop_start as isize..text.len() as isize;
+    if let Some(transform) = transform {
+        tabstop.transforms.push((range, transform));
+    } else {
+        tabstop.ranges.push(range);
+    }
+    Ok(source)
+}
+
+fn is_variable_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+/// Parses `NAME`, or `NAME}`, `NAME:default}` and `NAME/regex/format/options}` when `braced`.
+fn parse_variable<'a>(
+    source: &'a str,
+    braced: bool,
+    text: &mut String,
+    tabstops: &mut BTreeMap<usize, TabStop>,
+    cx: &mut ParseContext,
+) -> Result<&'a str> {
+    let name_len = source
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(source.len());
+    let (name, mut source) = source.split_at(name_len);
+    let value = cx.variable(name);
+    let variable_start = text.len();
+
+    if braced && source.starts_with(':') {
+        let tabstops_before_default = tabstops.clone();
+        let unknown_variables_before_default = cx.unknown_variables.len();
+        source = parse_snippet(&source[1..], true, text, tabstops, cx)?;
+        if let Some(value) = value {
+            text.truncate(variable_start);
+            text.push_str(&value);
+            *tabstops = tabstops_before_default;
+            cx.unknown_variables
+                .truncate(unknown_variables_before_default);
+        }
+    } else if braced && source.starts_with('/') {
+        let (transform, rest) = Transform::parse(&source[1..])?;
+        source = rest;
+        text.push_str(&transform.apply(value.as_deref().unwrap_or("")));
+    } else if let Some(value) = value {
+        text.push_str(&value);
+    } else if !KNOWN_VARIABLES.contains(&name) {
+        text.push_str(name);
+        cx.unknown_variables
+            .push(variable_start as isize..text.len() as isize);
+    }
+
+    if braced {
+        source = source
+            .strip_prefix('}')
+            .context("expected a closing brace")?;
+    }
     Ok(source)
 }
 
@@ -324,10 +479,107 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_transforms() {
+        let snippet = Snippet::parse("${1:foo_bar} ${1/(.*)/${1:/upcase}/}").unwrap();
+        assert_eq!(snippet.text, "foo_bar FOO_BAR");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![15..15]]);
+        assert_eq!(
+            transform_ranges(&snippet),
+            &[vec![8..15], Vec::<Range<isize>>::new()]
+        );
+        let (_, transform) = &snippet.tabstops[0].transforms[0];
+        assert_eq!(transform.apply("baz"), "BAZ");
+
+        // Transforms may precede the tabstop they refer to.
+        let snippet =
+            Snippet::parse("${1/(\\w+)_(\\w+)/${2:/capitalize}$1/} = ${1:foo_bar};$0").unwrap();
+        assert_eq!(snippet.text, "Barfoo = foo_bar;");
+        assert_eq!(tabstops(&snippet), &[vec![9..16], vec![17..17]]);
+        assert_eq!(transform_ranges(&snippet), &[vec![0..6], vec![]]);
+
+        // Only the first match is replaced without the `g` option.
+        let snippet = Snippet::parse("${1:a-b-c} ${1/-/_/} ${1/-/_/g}").unwrap();
+        assert_eq!(snippet.text, "a-b-c a_b-c a_b_c");
+
+        // Conditional formats.
+        let snippet =
+            Snippet::parse("${1:x} ${1/(y)?(x)?/${1:?yes:no} ${2:+two} ${1:-none} ${2:default}/}")
+                .unwrap();
+        assert_eq!(snippet.text, "x no two none x");
+
+        assert!(Snippet::parse("${1/(/x/}").is_err());
+        assert!(Snippet::parse("${1/a/b/z}").is_err());
+    }
+
+    #[test]
+    fn test_snippet_with_variables() {
+        let resolve = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_FILENAME_BASE" => Some("main".to_string()),
+            "CURRENT_YEAR" => Some("2024".to_string()),
+            _ => None,
+        };
+
+        let snippet = Snippet::parse_with_variables(
+            "// $TM_FILENAME, ${CURRENT_YEAR}\nfn ${1:$TM_FILENAME_BASE}() {}",
+            resolve,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "// main.rs, 2024\nfn main() {}");
+        assert_eq!(tabstops(&snippet), &[vec![20..24], vec![29..29]]);
+
+        // Known variables without a value expand to their default, or to nothing.
+        let snippet = Snippet::parse_with_variables(
+            "${TM_SELECTED_TEXT:${1:default}}|$TM_SELECTED_TEXT|${TM_FILENAME:${2:unused}}",
+            resolve,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "default||main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![0..7], vec![16..16]]);
+
+        // Unknown variables become placeholders.
+        let snippet = Snippet::parse_with_variables("${1:a} $FOO ${BAR}$0", resolve).unwrap();
+        assert_eq!(snippet.text, "a FOO BAR");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![0..1], vec![2..5], vec![6..9], vec![9..9]]
+        );
+
+        // Variables can be transformed.
+        let snippet = Snippet::parse_with_variables(
+            "${TM_FILENAME/(.*)\\..+$/${1:/pascalcase}/}Test ${CLIPBOARD/^$/empty/}",
+            resolve,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "MainTest empty");
+
+        let snippet = Snippet::parse("$UUID").unwrap();
+        assert_eq!(snippet.text.len(), 36);
+
+        // A dollar sign that isn't followed by a tabstop or a variable is kept.
+        let snippet = Snippet::parse("$ $$1").unwrap();
+        assert_eq!(snippet.text, "$ $");
+        assert_eq!(tabstops(&snippet), &[vec![3..3]]);
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.ranges.to_vec()).collect()
     }
 
+    fn transform_ranges(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
+        snippet
+            .tabstops
+            .iter()
+            .map(|t| {
+                t.transforms
+                    .iter()
+                    .map(|(range, _)| range.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
     fn tabstop_choices(snippet: &Snippet) -> Vec<&Option<Vec<String>>> {
         snippet.tabstops.iter().map(|t| &t.choices).collect()
     }
@@ -0,0 +1,374 @@
+use anyhow::{Context as _, Result};
+use regex::{Captures, Regex};
+
+/// A regex substitution applied to a tabstop or a variable, e.g. `${1/(.*)/${1:/upcase}/g}`.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    Case(usize, CaseModifier),
+    /// `${1:+if}`, `${1:?if:else}`, `${1:-else}` and `${1:else}`.
+    /// When `if_matched` is `None`, the group itself is inserted if it matched.
+    Conditional {
+        group: usize,
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CaseModifier {
+    Upcase,
+    Downcase,
+    Capitalize,
+    CamelCase,
+    PascalCase,
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.global == other.global
+    }
+}
+
+impl Transform {
+    /// Parses the `regex/format/options` part of a transform, right after its opening slash.
+    /// Returns the source following the options.
+    pub(crate) fn parse(source: &str) -> Result<(Self, &str)> {
+        let (pattern, source) = take_until_slash(source).context("unterminated transform regex")?;
+        let (format, source) = split_format(source).context("unterminated transform format")?;
+        let options_len = source
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(source.len());
+        let (options, source) = source.split_at(options_len);
+
+        let mut flags = String::new();
+        let mut global = false;
+        for option in options.chars() {
+            match option {
+                'g' => global = true,
+                'i' | 'm' | 's' => flags.push(option),
+                // JavaScript's unicode and sticky flags have no equivalent worth supporting.
+                'u' | 'y' => {}
+                _ => anyhow::bail!("unknown transform option '{option}'"),
+            }
+        }
+        let pattern = if flags.is_empty() {
+            pattern
+        } else {
+            format!("(?{flags}){pattern}")
+        };
+        let regex = Regex::new(&pattern).context("invalid transform regex")?;
+
+        Ok((
+            Self {
+                regex,
+                format: parse_format(format)?,
+                global,
+            },
+            source,
+        ))
+    }
+
+    /// Applies the transform to the given text, leaving the unmatched parts intact.
+    pub fn apply(&self, input: &str) -> String {
+        let replacement = |captures: &Captures| {
+            let group = |index: usize| captures.get(index).map_or("", |m| m.as_str());
+            let mut output = String::new();
+            for item in &self.format {
+                match item {
+                    FormatItem::Text(text) => output.push_str(text),
+                    FormatItem::Group(index) => output.push_str(group(*index)),
+                    FormatItem::Case(index, modifier) => {
+                        output.push_str(&modifier.apply(group(*index)))
+                    }
+                    FormatItem::Conditional {
+                        group: index,
+                        if_matched,
+                        otherwise,
+                    } => {
+                        let value = group(*index);
+                        if value.is_empty() {
+                            output.push_str(otherwise);
+                        } else {
+                            output.push_str(if_matched.as_deref().unwrap_or(value));
+                        }
+                    }
+                }
+            }
+            output
+        };
+
+        if self.global {
+            self.regex.replace_all(input, replacement).into_owned()
+        } else {
+            self.regex.replace(input, replacement).into_owned()
+        }
+    }
+}
+
+impl CaseModifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "upcase" => Some(Self::Upcase),
+            "downcase" => Some(Self::Downcase),
+            "capitalize" => Some(Self::Capitalize),
+            "camelcase" => Some(Self::CamelCase),
+            "pascalcase" => Some(Self::PascalCase),
+            _ => None,
+        }
+    }
+
+    fn apply(self, value: &str) -> String {
+        match self {
+            Self::Upcase => value.to_uppercase(),
+            Self::Downcase => value.to_lowercase(),
+            Self::Capitalize => capitalize(value),
+            Self::CamelCase | Self::PascalCase => {
+                let words = value
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|word| !word.is_empty())
+                    .collect::<Vec<_>>();
+                if words.is_empty() {
+                    return value.to_string();
+                }
+                let mut output = String::with_capacity(value.len());
+                for (ix, word) in words.into_iter().enumerate() {
+                    if ix == 0 && self == Self::CamelCase {
+                        let mut chars = word.chars();
+                        output.extend(chars.next().into_iter().flat_map(char::to_lowercase));
+                        output.push_str(chars.as_str());
+                    } else {
+                        output.push_str(&capitalize(word));
+                    }
+                }
+                output
+            }
+        }
+    }
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .into_iter()
+        .flat_map(char::to_uppercase)
+        .chain(chars)
+        .collect()
+}
+
+/// Reads up to the next unescaped slash, unescaping `\/` along the way.
+fn take_until_slash(mut source: &str) -> Option<(String, &str)> {
+    let mut text = String::new();
+    loop {
+        let ix = source.find(['/', '\\'])?;
+        text.push_str(&source[..ix]);
+        source = &source[ix..];
+        if let Some(rest) = source.strip_prefix("\\/") {
+            text.push('/');
+            source = rest;
+        } else if let Some(rest) = source.strip_prefix('\\') {
+            // Keep other escapes, they mean something to the regex or the format.
+            text.push('\\');
+            if let Some(c) = rest.chars().next() {
+                text.push(c);
+                source = &rest[c.len_utf8()..];
+            } else {
+                source = rest;
+            }
+        } else {
+            return Some((text, &source[1..]));
+        }
+    }
+}
+
+/// Splits the format off at the next slash outside of a `${...}` format item.
+fn split_format(source: &str) -> Option<(&str, &str)> {
+    let mut chars = source.char_indices().peekable();
+    let mut in_item = false;
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '$' if !in_item && chars.peek().is_some_and(|(_, c)| *c == '{') => {
+                chars.next();
+                in_item = true;
+            }
+            '}' if in_item => in_item = false,
+            '/' if !in_item => return Some((&source[..ix], &source[ix + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_format(mut source: &str) -> Result<Vec<FormatItem>> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    while let Some(c) = source.chars().next() {
+        match c {
+            '\\' => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some(c @ ('$' | '\\' | '/' | '}')) => {
+                        text.push(c);
+                        source = &source[1..];
+                    }
+                    _ => text.push('\\'),
+                }
+            }
+            '$' => {
+                let Some((item, rest)) = parse_format_item(&source[1..])? else {
+                    text.push('$');
+                    source = &source[1..];
+                    continue;
+                };
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(std::mem::take(&mut text)));
+                }
+                items.push(item);
+                source = rest;
+            }
+            _ => {
+                let chunk_end = source.find(['$', '\\']).unwrap_or(source.len());
+                text.push_str(&source[..chunk_end]);
+                source = &source[chunk_end..];
+            }
+        }
+    }
+    if !text.is_empty() {
+        items.push(FormatItem::Text(text));
+    }
+    Ok(items)
+}
+
+/// Parses a format item following a `$`, returns `None` if it's a literal dollar sign.
+fn parse_format_item(source: &str) -> Result<Option<(FormatItem, &str)>> {
+    if let Some((group, rest)) = parse_group(source) {
+        return Ok(Some((FormatItem::Group(group), rest)));
+    }
+    let Some(source) = source.strip_prefix('{') else {
+        return Ok(None);
+    };
+    let (group, source) = parse_group(source).context("expected a capture group index")?;
+    if let Some(rest) = source.strip_prefix('}') {
+        return Ok(Some((FormatItem::Group(group), rest)));
+    }
+    let source = source
+        .strip_prefix(':')
+        .context("expected a colon or a closing brace")?;
+
+    let (item, source) = if let Some(rest) = source.strip_prefix('/') {
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let modifier = CaseModifier::parse(&rest[..name_len])
+            .with_context(|| format!("unknown format modifier '{}'", &rest[..name_len]))?;
+        (FormatItem::Case(group, modifier), &rest[name_len..])
+    } else if let Some(rest) = source.strip_prefix('+') {
+        let (if_matched, rest) = take_conditional_text(rest, false);
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise: String::new(),
+        };
+        (item, rest)
+    } else if let Some(rest) = source.strip_prefix('?') {
+        let (if_matched, rest) = take_conditional_text(rest, true);
+        let rest = rest
+            .strip_prefix(':')
+            .context("expected a colon in a conditional format")?;
+        let (otherwise, rest) = take_conditional_text(rest, false);
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise,
+        };
+        (item, rest)
+    } else {
+        let rest = source.strip_prefix('-').unwrap_or(source);
+        let (otherwise, rest) = take_conditional_text(rest, false);
+        let item = FormatItem::Conditional {
+            group,
+            if_matched: None,
+            otherwise,
+        };
+        (item, rest)
+    };
+
+    let source = source
+        .strip_prefix('}')
+        .context("expected a closing brace in a format")?;
+    Ok(Some((item, source)))
+}
+
+fn parse_group(source: &str) -> Option<(usize, &str)> {
+    let len = source
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(source.len());
+    let group = source[..len].parse().ok()?;
+    Some((group, &source[len..]))
+}
+
+/// Reads the text of a conditional format, up to a closing brace (or a colon, if `stop_at_colon` is set).
+fn take_conditional_text(mut source: &str, stop_at_colon: bool) -> (String, &str) {
+    let mut text = String::new();
+    while let Some(c) = source.chars().next() {
+        match c {
+            '}' => break,
+            ':' if stop_at_colon => break,
+            '\\' => {
+                source = &source[1..];
+                match source.chars().next() {
+                    Some(c @ ('$' | '\\' | '/' | '}' | ':')) => {
+                        text.push(c);
+                        source = &source[1..];
+                    }
+                    _ => text.push('\\'),
+                }
+                continue;
+            }
+            c => text.push(c),
+        }
+        source = &source[c.len_utf8()..];
+    }
+    (text, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_modifiers() {
+        let transform = |source: &str| Transform::parse(source).unwrap().0;
+
+        let upcase = transform("(.)(.*)/${1:/upcase}${2:/downcase}/");
+        assert_eq!(upcase.apply("hELLO"), "Hello");
+
+        let camel_case = transform("(.*)/${1:/camelcase}/");
+        assert_eq!(camel_case.apply("Foo-bar baz_qux"), "fooBarBazQux");
+
+        let pascal_case = transform("(.*)/${1:/pascalcase}/");
+        assert_eq!(pascal_case.apply("foo-bar baz_qux"), "FooBarBazQux");
+
+        let capitalize = transform("\\w+/${0:/capitalize}/g");
+        assert_eq!(capitalize.apply("one two"), "One Two");
+
+        let escaped = transform("a\\/b/\\$1\\/$1/i");
+        assert_eq!(escaped.apply("A/B"), "$1/");
+
+        assert!(Transform::parse("(.*)/${1:/shout}/").is_err());
+    }
+}
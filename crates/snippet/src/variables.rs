@@ -0,0 +1,69 @@
+use chrono::{Datelike as _, Local};
+use rand::Rng as _;
+
+/// Variables that may be used in snippets, as defined by TextMate and VS Code.
+///
+/// A known variable that can't be resolved expands to its default value, or to nothing,
+/// while an unknown variable expands to a placeholder with its name.
+pub const KNOWN_VARIABLES: &[&str] = &[
+    "TM_SELECTED_TEXT",
+    "TM_CURRENT_LINE",
+    "TM_CURRENT_WORD",
+    "TM_LINE_INDEX",
+    "TM_LINE_NUMBER",
+    "TM_FILENAME",
+    "TM_FILENAME_BASE",
+    "TM_DIRECTORY",
+    "TM_FILEPATH",
+    "RELATIVE_FILEPATH",
+    "CLIPBOARD",
+    "WORKSPACE_NAME",
+    "WORKSPACE_FOLDER",
+    "CURSOR_INDEX",
+    "CURSOR_NUMBER",
+    "SELECTION",
+    "CURRENT_YEAR",
+    "CURRENT_YEAR_SHORT",
+    "CURRENT_MONTH",
+    "CURRENT_MONTH_NAME",
+    "CURRENT_MONTH_NAME_SHORT",
+    "CURRENT_DATE",
+    "CURRENT_DAY_NAME",
+    "CURRENT_DAY_NAME_SHORT",
+    "CURRENT_HOUR",
+    "CURRENT_MINUTE",
+    "CURRENT_SECOND",
+    "CURRENT_SECONDS_UNIX",
+    "CURRENT_TIMEZONE_OFFSET",
+    "RANDOM",
+    "RANDOM_HEX",
+    "UUID",
+    "BLOCK_COMMENT_START",
+    "BLOCK_COMMENT_END",
+    "LINE_COMMENT",
+];
+
+/// Resolves the variables that don't depend on the editor: dates, times and random values.
+pub(crate) fn resolve_builtin_variable(name: &str) -> Option<String> {
+    let now = Local::now();
+    let value = match name {
+        "CURRENT_YEAR" => now.year().to_string(),
+        "CURRENT_YEAR_SHORT" => now.format("%y").to_string(),
+        "CURRENT_MONTH" => now.format("%m").to_string(),
+        "CURRENT_MONTH_NAME" => now.format("%B").to_string(),
+        "CURRENT_MONTH_NAME_SHORT" => now.format("%b").to_string(),
+        "CURRENT_DATE" => now.format("%d").to_string(),
+        "CURRENT_DAY_NAME" => now.format("%A").to_string(),
+        "CURRENT_DAY_NAME_SHORT" => now.format("%a").to_string(),
+        "CURRENT_HOUR" => now.format("%H").to_string(),
+        "CURRENT_MINUTE" => now.format("%M").to_string(),
+        "CURRENT_SECOND" => now.format("%S").to_string(),
+        "CURRENT_SECONDS_UNIX" => now.timestamp().to_string(),
+        "CURRENT_TIMEZONE_OFFSET" => now.format("%:z").to_string(),
+        "RANDOM" => format!("{:06}", rand::rng().random_range(0..1_000_000)),
+        "RANDOM_HEX" => format!("{:06x}", rand::rng().random_range(0..0x1000000)),
+        "UUID" => uuid::Uuid::new_v4().to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
@@ -72,6 +72,15 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task starts.
+    /// A task with dependencies may omit its `command` to only run the tasks it depends on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks in `depends_on`:
+    /// * `parallel` — start all of them at once (default)
+    /// * `sequence` — start each of them after the previous one has succeeded
+    #[serde(default, skip_serializing_if = "DependsOrder::is_parallel")]
+    pub depends_order: DependsOrder,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +118,23 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Start all dependencies at once.
+    #[default]
+    Parallel,
+    /// Start each dependency after the previous one has succeeded.
+    Sequence,
+}
+
+impl DependsOrder {
+    fn is_parallel(&self) -> bool {
+        *self == Self::Parallel
+    }
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -133,7 +159,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -245,6 +273,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
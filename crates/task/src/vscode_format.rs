@@ -1,9 +1,9 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{DependsOrder, EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -19,11 +19,53 @@ struct VsCodeTaskDefinition {
     label: String,
     #[serde(flatten)]
     command: Option<Command>,
+    #[serde(default)]
+    depends_on: Option<DependsOn>,
+    #[serde(default)]
+    depends_order: Option<VsCodeDependsOrder>,
     #[serde(flatten)]
     other_attributes: HashMap<String, serde_json_lenient::Value>,
     options: Option<TaskOptions>,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum DependsOn {
+    One(TaskReference),
+    Many(Vec<TaskReference>),
+}
+
+/// A task is referenced either by its label, or by an object identifying a detected task, e.g. `{ "type": "npm", "script": "build" }`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum TaskReference {
+    Label(String),
+    Detected(HashMap<String, serde_json_lenient::Value>),
+}
+
+impl TaskReference {
+    fn label(&self) -> Option<String> {
+        match self {
+            TaskReference::Label(label) => Some(label.clone()),
+            TaskReference::Detected(fields) => {
+                let task_type = fields.get("type")?.as_str()?;
+                let name = fields
+                    .get("script")
+                    .or_else(|| fields.get("task"))?
+                    .as_str()?;
+                Some(format!("{task_type}: {name}"))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum VsCodeDependsOrder {
+    Parallel,
+    Sequence,
+}
+
 #[derive(Clone, Deserialize, PartialEq, Debug)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
@@ -46,25 +88,35 @@ impl VsCodeTaskDefinition {
         self,
         replacer: &EnvVariableReplacer,
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on = match self.depends_on {
+            Some(DependsOn::One(reference)) => vec![reference],
+            Some(DependsOn::Many(references)) => references,
+            None => Vec::new(),
+        };
+        let depends_on = depends_on
+            .iter()
+            .map(|reference| {
+                reference.label().with_context(|| {
+                    format!(
+                        "Unsupported `dependsOn` entry {reference:?} in task `{}`",
+                        self.label
+                    )
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let depends_order = match self.depends_order {
+            Some(VsCodeDependsOrder::Sequence) => DependsOrder::Sequence,
+            Some(VsCodeDependsOrder::Parallel) | None => DependsOrder::Parallel,
         };
 
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in tasks that only use `dependsOn` to run other tasks; we still want to deserialize the whole object though (hence command is an Option),
+        // as that way we can provide more specific description of why deserialization failed.
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -73,6 +125,8 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
@@ -124,8 +178,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
-        vscode_format::{Command, VsCodeTaskDefinition},
+        DependsOrder, TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, DependsOn, TaskReference, VsCodeTaskDefinition},
     };
 
     use super::EnvVariableReplacer;
@@ -180,6 +234,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -194,6 +250,8 @@ mod tests {
                         "--watch".to_string(),
                     ],
                 }),
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -202,6 +260,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:compiler".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -210,6 +270,8 @@ mod tests {
                 command: Some(Command::Npm {
                     script: "build:tests:notypecheck".to_string(),
                 }),
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
                 options: None,
             },
@@ -270,6 +332,8 @@ mod tests {
                     script: "watch".to_string(),
                 }),
                 options: None,
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -278,6 +342,8 @@ mod tests {
                     script: "build".to_string(),
                 }),
                 options: None,
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -287,6 +353,8 @@ mod tests {
                     args: Default::default(),
                 }),
                 options: None,
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -296,6 +364,8 @@ mod tests {
                     args: Default::default(),
                 }),
                 options: None,
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
@@ -304,18 +374,30 @@ mod tests {
                     script: "pretest".to_string(),
                 }),
                 options: None,
+                depends_on: None,
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server and Extension".to_string(),
                 command: None,
                 options: None,
+                depends_on: Some(DependsOn::Many(vec![
+                    TaskReference::Label("Build Server".to_string()),
+                    TaskReference::Label("Build Extension".to_string()),
+                ])),
+                depends_order: None,
                 other_attributes: Default::default(),
             },
             VsCodeTaskDefinition {
                 label: "Build Server (Release) and Extension".to_string(),
                 command: None,
                 options: None,
+                depends_on: Some(DependsOn::Many(vec![
+                    TaskReference::Label("Build Server (Release)".to_string()),
+                    TaskReference::Label("Build Extension".to_string()),
+                ])),
+                depends_order: None,
                 other_attributes: Default::default(),
             },
         ];
@@ -354,8 +436,77 @@ mod tests {
                 args: vec!["run".to_string(), "pretest".to_string()],
                 ..Default::default()
             },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                ..Default::default()
+            },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_task_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "version": "2.0.0",
+                "tasks": [
+                    { "label": "lint", "type": "shell", "command": "eslint ." },
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "jest",
+                        "dependsOn": "lint"
+                    },
+                    {
+                        "label": "ci",
+                        "dependsOn": ["lint", { "type": "npm", "script": "build" }, "test"],
+                        "dependsOrder": "sequence"
+                    },
+                    {
+                        "label": "broken",
+                        "dependsOn": [{ "type": "shell" }]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "lint".to_string(),
+                    command: "eslint .".to_string(),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "jest".to_string(),
+                    depends_on: vec!["lint".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "ci".to_string(),
+                    depends_on: vec![
+                        "lint".to_string(),
+                        "npm: build".to_string(),
+                        "test".to_string(),
+                    ],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,1396 @@
+//! Inline images, printed with the sixel, kitty graphics or iTerm2 image protocols.
+//!
+//! Alacritty supports none of these, so the image sequences are taken out of the PTY output
+//! before it reaches the parser. Each image is replaced with a blank cell on every line it
+//! covers, hyperlinked to the image. Those cells move with the grid like any other, so the
+//! image is drawn wherever they end up, and goes away once they're cleared or scrolled out
+//! of the history.
+
+use std::{
+    io::{self, Read},
+    iter::Peekable,
+    mem,
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{Event as AlacTermEvent, EventListener, OnResize, WindowSize},
+    index::{Line, Point as AlacPoint},
+    sync::FairMutex,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use anyhow::{Context as _, Result, anyhow, bail};
+use base64::{
+    Engine as _, alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+};
+use collections::{HashMap, HashSet, VecDeque};
+use gpui::RenderImage;
+use image::{Frame, ImageFormat, RgbaImage};
+use polling::{Event, PollMode, Poller};
+
+use crate::{IndexedCell, ZedListener};
+
+/// The scheme of the hyperlinks that anchor images to the grid.
+const ANCHOR_SCHEME: &str = "zed-image:";
+/// Image sequences longer than this are dropped.
+const MAX_SEQUENCE_LEN: usize = 64 * 1024 * 1024;
+/// The pixel data kept for each terminal, the oldest images are dropped past this.
+const MAX_IMAGE_BYTES: usize = 256 * 1024 * 1024;
+const MAX_IMAGE_SIDE: u32 = 10_000;
+/// Images transmitted with the kitty protocol, kept to be placed again by their id.
+const MAX_KITTY_IMAGES: usize = 64;
+/// Used until the terminal has been laid out.
+const DEFAULT_CELL_SIZE: (f32, f32) = (8., 16.);
+const READ_BUFFER_SIZE: usize = 0x10000;
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// An image on the screen.
+#[derive(Clone, Debug)]
+pub struct ImagePlacement {
+    pub image: Arc<RenderImage>,
+    /// The grid point of the image's top left cell, which may be above the screen.
+    pub point: AlacPoint,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+pub(crate) fn is_anchor(uri: &str) -> bool {
+    uri.starts_with(ANCHOR_SCHEME)
+}
+
+/// Returns the image id and the row of the image that an anchor is on.
+fn parse_anchor(uri: &str) -> Option<(u64, usize)> {
+    let (id, row) = uri.strip_prefix(ANCHOR_SCHEME)?.split_once(':')?;
+    Some((id.parse().ok()?, row.parse().ok()?))
+}
+
+struct StoredImage {
+    image: Arc<RenderImage>,
+    columns: usize,
+    rows: usize,
+    kitty_id: Option<u32>,
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct ImageStore {
+    images: HashMap<u64, StoredImage>,
+    /// Image ids, oldest first.
+    order: VecDeque<u64>,
+    bytes: usize,
+    next_id: u64,
+    /// Images that are gone, with textures that are yet to be freed.
+    dropped: Vec<Arc<RenderImage>>,
+}
+
+impl ImageStore {
+    fn insert(&mut self, image: StoredImage) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.bytes += image.bytes;
+        self.images.insert(id, image);
+        self.order.push_back(id);
+
+        while self.bytes > MAX_IMAGE_BYTES
+            && let Some(oldest) = self.order.pop_front()
+        {
+            if let Some(image) = self.images.remove(&oldest) {
+                self.bytes -= image.bytes;
+                self.dropped.push(image.image);
+            }
+        }
+        id
+    }
+
+    fn remove_where(&mut self, predicate: impl Fn(&StoredImage) -> bool) {
+        let mut removed = Vec::new();
+        self.images.retain(|_, image| {
+            if predicate(image) {
+                removed.push((image.bytes, image.image.clone()));
+                false
+            } else {
+                true
+            }
+        });
+        for (bytes, image) in removed {
+            self.bytes -= bytes;
+            self.dropped.push(image);
+        }
+        let images = &self.images;
+        self.order.retain(|id| images.contains_key(id));
+    }
+}
+
+/// Where the cursor goes once an image has been placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CursorMovement {
+    /// To the start of the line below the image.
+    NextLine,
+    /// To the cell after the image, on its last line.
+    AfterImage,
+    /// Back to where it was.
+    Stay,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SequenceKind {
+    Osc,
+    Dcs,
+    Apc,
+}
+
+impl SequenceKind {
+    fn introducer(self) -> &'static [u8] {
+        match self {
+            Self::Osc => b"\x1b]",
+            Self::Dcs => b"\x1bP",
+            Self::Apc => b"\x1b_",
+        }
+    }
+}
+
+enum PrefixMatch {
+    Image,
+    NotImage,
+    Pending,
+}
+
+fn match_prefix(kind: SequenceKind, prefix: &[u8]) -> PrefixMatch {
+    const ITERM2_PREFIX: &[u8] = b"1337;File=";
+    match kind {
+        SequenceKind::Osc if prefix.len() > ITERM2_PREFIX.len() => PrefixMatch::Image,
+        SequenceKind::Osc if ITERM2_PREFIX.starts_with(prefix) => PrefixMatch::Pending,
+        SequenceKind::Osc => PrefixMatch::NotImage,
+        // Sixel parameters are numbers, other DCS sequences have intermediate characters.
+        SequenceKind::Dcs => match prefix.last() {
+            Some(b'q') => PrefixMatch::Image,
+            Some(b'0'..=b'9' | b';') if prefix.len() < 32 => PrefixMatch::Pending,
+            _ => PrefixMatch::NotImage,
+        },
+        SequenceKind::Apc if prefix == b"G" => PrefixMatch::Image,
+        SequenceKind::Apc => PrefixMatch::NotImage,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// After an ESC, which is held back until the next byte tells what it starts.
+    Escape,
+    /// The start of a string that may be an image, held back until that's known.
+    Prefix(SequenceKind),
+    Image(SequenceKind),
+    /// A string that isn't an image, passed through up to its terminator.
+    Passthrough(SequenceKind),
+    /// An image sequence that is too long, dropped up to its terminator.
+    Discard(SequenceKind),
+    /// An image sequence ended with the ESC of `ESC \`, the backslash is dropped too.
+    Terminator,
+}
+
+struct KittyTransmission {
+    command: KittyCommand,
+    payload: Vec<u8>,
+}
+
+/// Replaces the image sequences in the PTY output with the cells that anchor the images.
+struct ImageScanner {
+    state: ScanState,
+    sequence: Vec<u8>,
+    window_size: Option<WindowSize>,
+    kitty_images: HashMap<u32, Arc<RenderImage>>,
+    kitty_image_order: VecDeque<u32>,
+    kitty_transmission: Option<KittyTransmission>,
+    store: Arc<FairMutex<ImageStore>>,
+    listener: ZedListener,
+}
+
+impl ImageScanner {
+    fn new(store: Arc<FairMutex<ImageStore>>, listener: ZedListener) -> Self {
+        Self {
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+            window_size: None,
+            kitty_images: HashMap::default(),
+            kitty_image_order: VecDeque::new(),
+            kitty_transmission: None,
+            store,
+            listener,
+        }
+    }
+
+    fn scan(&mut self, mut input: &[u8], output: &mut Vec<u8>) {
+        while let Some((&byte, rest)) = input.split_first() {
+            if self.state == ScanState::Ground {
+                let text_len = input
+                    .iter()
+                    .position(|&byte| byte == 0x1b)
+                    .unwrap_or(input.len());
+                output.extend_from_slice(&input[..text_len]);
+                input = &input[text_len..];
+                if !input.is_empty() {
+                    self.state = ScanState::Escape;
+                    input = &input[1..];
+                }
+                continue;
+            }
+            if let ScanState::Image(kind) = self.state {
+                let data_len = input
+                    .iter()
+                    .position(|&byte| is_terminator(kind, byte))
+                    .unwrap_or(input.len());
+                if data_len > 0 && self.sequence.len() + data_len <= MAX_SEQUENCE_LEN {
+                    self.sequence.extend_from_slice(&input[..data_len]);
+                    input = &input[data_len..];
+                    continue;
+                }
+            }
+            self.scan_byte(byte, output);
+            input = rest;
+        }
+    }
+
+    fn scan_byte(&mut self, byte: u8, output: &mut Vec<u8>) {
+        match self.state {
+            ScanState::Ground => {
+                if byte == 0x1b {
+                    self.state = ScanState::Escape;
+                } else {
+                    output.push(byte);
+                }
+            }
+            ScanState::Escape => {
+                let kind = match byte {
+                    b']' => SequenceKind::Osc,
+                    b'P' => SequenceKind::Dcs,
+                    b'_' => SequenceKind::Apc,
+                    0x1b => {
+                        output.push(0x1b);
+                        return;
+                    }
+                    _ => {
+                        output.extend_from_slice(&[0x1b, byte]);
+                        self.state = ScanState::Ground;
+                        return;
+                    }
+                };
+                self.sequence.clear();
+                self.state = ScanState::Prefix(kind);
+            }
+            ScanState::Prefix(kind) => {
+                if is_terminator(kind, byte) {
+                    self.pass_through(kind, output);
+                    self.scan_byte(byte, output);
+                    return;
+                }
+                self.sequence.push(byte);
+                match match_prefix(kind, &self.sequence) {
+                    PrefixMatch::Image => self.state = ScanState::Image(kind),
+                    PrefixMatch::NotImage => self.pass_through(kind, output),
+                    PrefixMatch::Pending => {}
+                }
+            }
+            ScanState::Image(kind) => match byte {
+                0x07 if kind == SequenceKind::Osc => {
+                    self.finish(kind, output);
+                    self.state = ScanState::Ground;
+                }
+                0x1b => {
+                    self.finish(kind, output);
+                    self.state = ScanState::Terminator;
+                }
+                0x18 | 0x1a => {
+                    self.sequence = Vec::new();
+                    self.state = ScanState::Ground;
+                }
+                _ if self.sequence.len() >= MAX_SEQUENCE_LEN => {
+                    log::debug!(
+                        "dropping an inline image sequence of over {MAX_SEQUENCE_LEN} bytes"
+                    );
+                    self.sequence = Vec::new();
+                    self.state = ScanState::Discard(kind);
+                }
+                _ => self.sequence.push(byte),
+            },
+            ScanState::Passthrough(kind) => match byte {
+                0x1b => self.state = ScanState::Escape,
+                0x07 if kind == SequenceKind::Osc => {
+                    output.push(byte);
+                    self.state = ScanState::Ground;
+                }
+                0x18 | 0x1a => {
+                    output.push(byte);
+                    self.state = ScanState::Ground;
+                }
+                _ => output.push(byte),
+            },
+            ScanState::Discard(kind) => match byte {
+                0x07 if kind == SequenceKind::Osc => self.state = ScanState::Ground,
+                0x1b => self.state = ScanState::Terminator,
+                0x18 | 0x1a => self.state = ScanState::Ground,
+                _ => {}
+            },
+            ScanState::Terminator => {
+                if byte == b'\\' {
+                    self.state = ScanState::Ground;
+                } else {
+                    self.state = ScanState::Escape;
+                    self.scan_byte(byte, output);
+                }
+            }
+        }
+    }
+
+    /// Outputs the held back start of a string that turned out not to be an image.
+    fn pass_through(&mut self, kind: SequenceKind, output: &mut Vec<u8>) {
+        output.extend_from_slice(kind.introducer());
+        output.extend_from_slice(&self.sequence);
+        self.sequence.clear();
+        self.state = ScanState::Passthrough(kind);
+    }
+
+    fn finish(&mut self, kind: SequenceKind, output: &mut Vec<u8>) {
+        let sequence = mem::take(&mut self.sequence);
+        match kind {
+            SequenceKind::Osc => self.iterm2_image(&sequence, output),
+            SequenceKind::Dcs => self.sixel_image(&sequence, output),
+            SequenceKind::Apc => self.kitty_graphics(&sequence[1..], output),
+        }
+    }
+
+    fn cell_size(&self) -> (f32, f32) {
+        match self.window_size {
+            Some(size) if size.cell_width > 0 && size.cell_height > 0 => {
+                (size.cell_width as f32, size.cell_height as f32)
+            }
+            _ => DEFAULT_CELL_SIZE,
+        }
+    }
+
+    fn screen_size(&self) -> (f32, f32) {
+        let (cell_width, cell_height) = self.cell_size();
+        match self.window_size {
+            Some(size) if size.num_cols > 0 && size.num_lines > 0 => (
+                size.num_cols as f32 * cell_width,
+                size.num_lines as f32 * cell_height,
+            ),
+            _ => (80. * cell_width, 24. * cell_height),
+        }
+    }
+
+    /// Returns the columns and rows covered by an image of the given size in pixels, scaled
+    /// down to the width of the screen.
+    fn cells_for(&self, mut width: f32, mut height: f32) -> (usize, usize) {
+        let (cell_width, cell_height) = self.cell_size();
+        let (screen_width, _) = self.screen_size();
+        if width > screen_width {
+            height *= screen_width / width;
+            width = screen_width;
+        }
+        let columns = (width / cell_width).ceil().max(1.) as usize;
+        let rows = (height / cell_height).ceil().max(1.) as usize;
+        (columns, rows)
+    }
+
+    fn place(
+        &mut self,
+        image: Arc<RenderImage>,
+        (columns, rows): (usize, usize),
+        kitty_id: Option<u32>,
+        cursor_movement: CursorMovement,
+        output: &mut Vec<u8>,
+    ) {
+        let size = image.size(0);
+        let id = self.store.lock().insert(StoredImage {
+            image,
+            columns,
+            rows,
+            kitty_id,
+            bytes: size.width.0 as usize * size.height.0 as usize * 4,
+        });
+
+        if cursor_movement == CursorMovement::Stay {
+            output.extend_from_slice(b"\x1b7");
+        }
+        for row in 0..rows {
+            if row > 0 {
+                output.push(b'\n');
+            }
+            // A blank hyperlinked cell, with the cursor moved back onto it.
+            output.extend_from_slice(
+                format!("\x1b]8;;{ANCHOR_SCHEME}{id}:{row}\x1b\\ \x1b]8;;\x1b\\\x08").as_bytes(),
+            );
+        }
+        match cursor_movement {
+            CursorMovement::NextLine => output.extend_from_slice(b"\r\n"),
+            CursorMovement::AfterImage => {
+                output.extend_from_slice(format!("\x1b[{columns}C").as_bytes())
+            }
+            CursorMovement::Stay => output.extend_from_slice(b"\x1b8"),
+        }
+    }
+
+    /// Handles `OSC 1337 ; File = [arguments] : [base64 data]`.
+    fn iterm2_image(&mut self, sequence: &[u8], output: &mut Vec<u8>) {
+        let Some(sequence) = sequence.strip_prefix(b"1337;File=") else {
+            return;
+        };
+        let Some(separator) = sequence.iter().position(|&byte| byte == b':') else {
+            return;
+        };
+        let arguments = String::from_utf8_lossy(&sequence[..separator]);
+        let mut inline = false;
+        let mut preserve_aspect_ratio = true;
+        let mut width = None;
+        let mut height = None;
+        for argument in arguments.split(';') {
+            match argument.split_once('=') {
+                Some(("inline", value)) => inline = value == "1",
+                Some(("preserveAspectRatio", value)) => preserve_aspect_ratio = value != "0",
+                Some(("width", value)) => width = Some(value.to_string()),
+                Some(("height", value)) => height = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        // Files that aren't inline are meant to be downloaded.
+        if !inline {
+            return;
+        }
+
+        let image = match decode_base64(&sequence[separator + 1..])
+            .and_then(|data| Ok(image::load_from_memory(&data)?.into_rgba8()))
+            .and_then(check_image_size)
+        {
+            Ok(image) => image,
+            Err(error) => {
+                log::debug!("failed to decode an iTerm2 inline image: {error:#}");
+                return;
+            }
+        };
+
+        let (image_width, image_height) = (image.width() as f32, image.height() as f32);
+        let (cell_width, cell_height) = self.cell_size();
+        let (screen_width, screen_height) = self.screen_size();
+        let width = width.and_then(|width| iterm2_dimension(&width, cell_width, screen_width));
+        let height =
+            height.and_then(|height| iterm2_dimension(&height, cell_height, screen_height));
+        let (width, height) = match (width, height) {
+            (None, None) => (image_width, image_height),
+            (Some(width), None) if preserve_aspect_ratio => {
+                (width, image_height * width / image_width)
+            }
+            (None, Some(height)) if preserve_aspect_ratio => {
+                (image_width * height / image_height, height)
+            }
+            (Some(width), Some(height)) if preserve_aspect_ratio => {
+                let scale = (width / image_width).min(height / image_height);
+                (image_width * scale, image_height * scale)
+            }
+            (width, height) => (width.unwrap_or(image_width), height.unwrap_or(image_height)),
+        };
+
+        let cells = self.cells_for(width, height);
+        self.place(
+            render_image(image),
+            cells,
+            None,
+            CursorMovement::NextLine,
+            output,
+        );
+    }
+
+    /// Handles `DCS [parameters] q [sixel data] ST`.
+    fn sixel_image(&mut self, sequence: &[u8], output: &mut Vec<u8>) {
+        let Some(start) = sequence.iter().position(|&byte| byte == b'q') else {
+            return;
+        };
+        let Some(image) = decode_sixel(&sequence[start + 1..]) else {
+            return;
+        };
+        let cells = self.cells_for(image.width() as f32, image.height() as f32);
+        self.place(
+            render_image(image),
+            cells,
+            None,
+            CursorMovement::NextLine,
+            output,
+        );
+    }
+
+    /// Handles `APC G [control data] ; [base64 payload] ST`.
+    fn kitty_graphics(&mut self, sequence: &[u8], output: &mut Vec<u8>) {
+        let (control, payload) = match sequence.iter().position(|&byte| byte == b';') {
+            Some(separator) => (&sequence[..separator], &sequence[separator + 1..]),
+            None => (sequence, &[][..]),
+        };
+        let command = KittyCommand::parse(&String::from_utf8_lossy(control));
+
+        // The chunks after the first one only say whether there are more to come.
+        if let Some(mut transmission) = self.kitty_transmission.take() {
+            transmission.payload.extend_from_slice(payload);
+            if transmission.payload.len() > MAX_SEQUENCE_LEN {
+                log::debug!("dropping a kitty image of over {MAX_SEQUENCE_LEN} bytes");
+                return;
+            }
+            if command.more {
+                self.kitty_transmission = Some(transmission);
+            } else {
+                self.kitty_command(transmission.command, &transmission.payload, output);
+            }
+            return;
+        }
+
+        if command.more {
+            self.kitty_transmission = Some(KittyTransmission {
+                command,
+                payload: payload.to_vec(),
+            });
+        } else {
+            self.kitty_command(command, payload, output);
+        }
+    }
+
+    fn kitty_command(&mut self, command: KittyCommand, payload: &[u8], output: &mut Vec<u8>) {
+        let result = match command.action {
+            b'q' => decode_kitty_image(&command, payload).map(|_| ()),
+            b't' | b'T' => decode_kitty_image(&command, payload).map(|image| {
+                let image = render_image(image);
+                if let Some(id) = command.id {
+                    self.store_kitty_image(id, image.clone());
+                }
+                if command.action == b'T' {
+                    self.place_kitty_image(&command, image, output);
+                }
+            }),
+            b'p' => command
+                .id
+                .and_then(|id| self.kitty_images.get(&id).cloned())
+                .map(|image| self.place_kitty_image(&command, image, output))
+                .ok_or_else(|| anyhow!("ENOENT:no image with this id")),
+            b'd' => {
+                self.delete_kitty_images(&command);
+                return;
+            }
+            _ => Err(anyhow!("EINVAL:unsupported action")),
+        };
+
+        let Some(id) = command.id else {
+            return;
+        };
+        let response = match result {
+            Ok(()) if command.quiet == 0 => "OK".to_string(),
+            Err(error) if command.quiet < 2 => error.to_string(),
+            _ => return,
+        };
+        self.listener.send_event(AlacTermEvent::PtyWrite(format!(
+            "\x1b_Gi={id};{response}\x1b\\"
+        )));
+    }
+
+    fn store_kitty_image(&mut self, id: u32, image: Arc<RenderImage>) {
+        if self.kitty_images.insert(id, image).is_none() {
+            self.kitty_image_order.push_back(id);
+        }
+        while self.kitty_images.len() > MAX_KITTY_IMAGES
+            && let Some(oldest) = self.kitty_image_order.pop_front()
+        {
+            self.kitty_images.remove(&oldest);
+        }
+    }
+
+    fn place_kitty_image(
+        &mut self,
+        command: &KittyCommand,
+        image: Arc<RenderImage>,
+        output: &mut Vec<u8>,
+    ) {
+        let size = image.size(0);
+        let (width, height) = (size.width.0 as f32, size.height.0 as f32);
+        let (cell_width, cell_height) = self.cell_size();
+        let cells = match (command.columns, command.rows) {
+            (Some(columns), Some(rows)) => (columns, rows),
+            (Some(columns), None) => {
+                let rows = columns as f32 * cell_width * height / width / cell_height;
+                (columns, rows.ceil().max(1.) as usize)
+            }
+            (None, Some(rows)) => {
+                let columns = rows as f32 * cell_height * width / height / cell_width;
+                (columns.ceil().max(1.) as usize, rows)
+            }
+            (None, None) => self.cells_for(width, height),
+        };
+        let cursor_movement = if command.move_cursor {
+            CursorMovement::AfterImage
+        } else {
+            CursorMovement::Stay
+        };
+        self.place(image, cells, command.id, cursor_movement, output);
+    }
+
+    fn delete_kitty_images(&mut self, command: &KittyCommand) {
+        match command.delete {
+            b'a' | b'A' => self
+                .store
+                .lock()
+                .remove_where(|image| image.kitty_id.is_some()),
+            b'i' | b'I' => {
+                let Some(id) = command.id else {
+                    return;
+                };
+                self.store
+                    .lock()
+                    .remove_where(|image| image.kitty_id == Some(id));
+                if command.delete == b'I' {
+                    self.kitty_images.remove(&id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_terminator(kind: SequenceKind, byte: u8) -> bool {
+    matches!(byte, 0x1b | 0x18 | 0x1a) || (kind == SequenceKind::Osc && byte == 0x07)
+}
+
+/// Returns a width or height argument of an iTerm2 image in pixels, or `None` for `auto`.
+fn iterm2_dimension(value: &str, cell: f32, screen: f32) -> Option<f32> {
+    let pixels = if let Some(pixels) = value.strip_suffix("px") {
+        pixels.parse::<f32>().ok()?
+    } else if let Some(percent) = value.strip_suffix('%') {
+        percent.parse::<f32>().ok()? * screen / 100.
+    } else {
+        value.parse::<f32>().ok()? * cell
+    };
+    (pixels > 0.).then_some(pixels)
+}
+
+fn decode_base64(data: &[u8]) -> Result<Vec<u8>> {
+    let data = data
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    Ok(BASE64.decode(data)?)
+}
+
+fn check_image_size(image: RgbaImage) -> Result<RgbaImage> {
+    if image.width() == 0
+        || image.height() == 0
+        || image.width() > MAX_IMAGE_SIDE
+        || image.height() > MAX_IMAGE_SIDE
+    {
+        bail!(
+            "unsupported image size {}x{}",
+            image.width(),
+            image.height()
+        );
+    }
+    Ok(image)
+}
+
+fn render_image(mut image: RgbaImage) -> Arc<RenderImage> {
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Arc::new(RenderImage::new(vec![Frame::new(image)]))
+}
+
+/// The control data of a kitty graphics command.
+#[derive(Debug, PartialEq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    width: u32,
+    height: u32,
+    id: Option<u32>,
+    columns: Option<usize>,
+    rows: Option<usize>,
+    more: bool,
+    quiet: u8,
+    move_cursor: bool,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &str) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            width: 0,
+            height: 0,
+            id: None,
+            columns: None,
+            rows: None,
+            more: false,
+            quiet: 0,
+            move_cursor: true,
+            delete: b'a',
+        };
+        for (key, value) in control
+            .split(',')
+            .filter_map(|argument| argument.split_once('='))
+        {
+            let first_byte = value.bytes().next().unwrap_or_default();
+            match key {
+                "a" => command.action = first_byte,
+                "f" => command.format = value.parse().unwrap_or(command.format),
+                "t" => command.medium = first_byte,
+                "o" => command.compressed = value == "z",
+                "s" => command.width = value.parse().unwrap_or_default(),
+                "v" => command.height = value.parse().unwrap_or_default(),
+                "i" => command.id = value.parse().ok().filter(|&id| id > 0),
+                "c" => command.columns = value.parse().ok().filter(|&columns| columns > 0),
+                "r" => command.rows = value.parse().ok().filter(|&rows| rows > 0),
+                "m" => command.more = value == "1",
+                "q" => command.quiet = value.parse().unwrap_or_default(),
+                "C" => command.move_cursor = value != "1",
+                "d" => command.delete = first_byte,
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+fn decode_kitty_image(command: &KittyCommand, payload: &[u8]) -> Result<RgbaImage> {
+    if command.medium != b'd' {
+        bail!("EINVAL:only direct transmission is supported");
+    }
+    let mut data = decode_base64(payload).context("EINVAL:invalid base64 data")?;
+    if command.compressed {
+        let mut decompressed = Vec::new();
+        flate2::read::ZlibDecoder::new(data.as_slice())
+            .take(MAX_IMAGE_BYTES as u64)
+            .read_to_end(&mut decompressed)
+            .context("EINVAL:invalid compressed data")?;
+        data = decompressed;
+    }
+
+    let image = match command.format {
+        100 => image::load_from_memory_with_format(&data, ImageFormat::Png)
+            .context("EBADPNG:invalid PNG data")?
+            .into_rgba8(),
+        24 | 32 => {
+            let (width, height) = (command.width, command.height);
+            if width == 0 || height == 0 || width > MAX_IMAGE_SIDE || height > MAX_IMAGE_SIDE {
+                bail!("EINVAL:invalid image size");
+            }
+            let pixels = width as usize * height as usize;
+            let data = if command.format == 24 {
+                if data.len() < pixels * 3 {
+                    bail!("ENODATA:insufficient image data");
+                }
+                data.chunks_exact(3)
+                    .take(pixels)
+                    .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+                    .collect()
+            } else {
+                data.truncate(pixels * 4);
+                data
+            };
+            RgbaImage::from_raw(width, height, data)
+                .ok_or_else(|| anyhow!("ENODATA:insufficient image data"))?
+        }
+        _ => bail!("EINVAL:unsupported format"),
+    };
+    check_image_size(image).context("EINVAL:invalid image size")
+}
+
+/// The colors of the VT340, which are the default sixel palette, in percent.
+const SIXEL_DEFAULT_PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+fn percent_to_channel(percent: u32) -> u8 {
+    (percent.min(100) * 255 / 100) as u8
+}
+
+/// Converts a sixel HLS color, where blue is at 0°, to RGB.
+fn sixel_hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 4] {
+    let hue = ((hue + 240) % 360) as f32 / 360.;
+    let lightness = lightness.min(100) as f32 / 100.;
+    let saturation = saturation.min(100) as f32 / 100.;
+    let q = if lightness < 0.5 {
+        lightness * (1. + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2. * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.);
+        let value = if t < 1. / 6. {
+            p + (q - p) * 6. * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2. / 3. {
+            p + (q - p) * (2. / 3. - t) * 6.
+        } else {
+            p
+        };
+        (value * 255.).round() as u8
+    };
+    [
+        channel(hue + 1. / 3.),
+        channel(hue),
+        channel(hue - 1. / 3.),
+        u8::MAX,
+    ]
+}
+
+fn sixel_parameters(data: &mut Peekable<impl Iterator<Item = u8>>) -> Vec<u32> {
+    let mut parameters = vec![0u32];
+    while let Some(&byte) = data.peek() {
+        match byte {
+            b'0'..=b'9' => {
+                if let Some(last) = parameters.last_mut() {
+                    *last = last.saturating_mul(10).saturating_add((byte - b'0') as u32);
+                }
+            }
+            b';' => parameters.push(0),
+            _ => break,
+        }
+        data.next();
+    }
+    parameters
+}
+
+/// Decodes sixel data, the pixels that aren't drawn are left transparent.
+fn decode_sixel(data: &[u8]) -> Option<RgbaImage> {
+    let mut palette = [[0, 0, 0, u8::MAX]; 256];
+    for (color, [r, g, b]) in palette.iter_mut().zip(SIXEL_DEFAULT_PALETTE) {
+        *color = [
+            percent_to_channel(r as u32),
+            percent_to_channel(g as u32),
+            percent_to_channel(b as u32),
+            u8::MAX,
+        ];
+    }
+
+    let mut rows: Vec<Vec<[u8; 4]>> = Vec::new();
+    let mut color = palette[0];
+    let (mut x, mut y) = (0usize, 0usize);
+    let (mut raster_width, mut raster_height) = (0usize, 0usize);
+    let mut data = data.iter().copied().peekable();
+    while let Some(byte) = data.next() {
+        let mut repeat = 1;
+        let sixel = match byte {
+            b'"' => {
+                let parameters = sixel_parameters(&mut data);
+                raster_width = parameters.get(2).copied().unwrap_or(0) as usize;
+                raster_height = parameters.get(3).copied().unwrap_or(0) as usize;
+                continue;
+            }
+            b'#' => {
+                let parameters = sixel_parameters(&mut data);
+                let index = parameters[0] as usize % palette.len();
+                if let [_, space, a, b, c] = parameters[..] {
+                    palette[index] = match space {
+                        1 => sixel_hls_to_rgb(a, b, c),
+                        _ => [
+                            percent_to_channel(a),
+                            percent_to_channel(b),
+                            percent_to_channel(c),
+                            u8::MAX,
+                        ],
+                    };
+                }
+                color = palette[index];
+                continue;
+            }
+            b'!' => {
+                repeat = sixel_parameters(&mut data)[0].max(1) as usize;
+                match data.next() {
+                    Some(sixel @ 0x3f..=0x7e) => sixel,
+                    _ => continue,
+                }
+            }
+            b'$' => {
+                x = 0;
+                continue;
+            }
+            b'-' => {
+                x = 0;
+                y += 6;
+                continue;
+            }
+            0x3f..=0x7e => byte,
+            _ => continue,
+        };
+
+        let end = (x + repeat).min(MAX_IMAGE_SIDE as usize);
+        let bits = sixel - 0x3f;
+        for bit in 0..6 {
+            let row_index = y + bit;
+            if bits & (1 << bit) == 0 || row_index >= MAX_IMAGE_SIDE as usize {
+                continue;
+            }
+            if rows.len() <= row_index {
+                rows.resize_with(row_index + 1, Vec::new);
+            }
+            let row = &mut rows[row_index];
+            if row.len() < end {
+                row.resize(end, [0; 4]);
+            }
+            row[x..end].fill(color);
+        }
+        x = end;
+    }
+
+    let width = rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(raster_width)
+        .min(MAX_IMAGE_SIDE as usize);
+    let height = rows.len().max(raster_height).min(MAX_IMAGE_SIDE as usize);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut image = RgbaImage::new(width as u32, height as u32);
+    for (y, row) in rows.iter().enumerate().take(height) {
+        for (x, pixel) in row.iter().enumerate().take(width) {
+            image.put_pixel(x as u32, y as u32, image::Rgba(*pixel));
+        }
+    }
+    Some(image)
+}
+
+/// Wraps a PTY so that the images in its output are replaced with their anchors.
+pub(crate) struct InlineImagePty<T> {
+    reader: ImageScanningReader<T>,
+}
+
+pub(crate) struct ImageScanningReader<T> {
+    pty: T,
+    scanner: ImageScanner,
+    input: Box<[u8]>,
+    output: Vec<u8>,
+    output_start: usize,
+}
+
+impl<T: EventedReadWrite> Read for ImageScanningReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // An image can take many reads, which produce no output until it is complete.
+        while self.output_start == self.output.len() {
+            self.output.clear();
+            self.output_start = 0;
+            let len = self.pty.reader().read(&mut self.input)?;
+            if len == 0 {
+                return Ok(0);
+            }
+            self.scanner.scan(&self.input[..len], &mut self.output);
+        }
+
+        let output = &self.output[self.output_start..];
+        let len = buf.len().min(output.len());
+        buf[..len].copy_from_slice(&output[..len]);
+        self.output_start += len;
+        Ok(len)
+    }
+}
+
+impl<T: EventedReadWrite> EventedReadWrite for InlineImagePty<T> {
+    type Reader = ImageScanningReader<T>;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.reader.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.reader.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.reader.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.reader.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for InlineImagePty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.reader.pty.next_child_event()
+    }
+}
+
+impl<T: OnResize> OnResize for InlineImagePty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.reader.scanner.window_size = Some(window_size);
+        self.reader.pty.on_resize(window_size)
+    }
+}
+
+/// The inline images of a terminal.
+pub(crate) struct InlineImages {
+    store: Arc<FairMutex<ImageStore>>,
+}
+
+impl InlineImages {
+    pub(crate) fn new() -> Self {
+        Self {
+            store: Arc::new(FairMutex::new(ImageStore::default())),
+        }
+    }
+
+    pub(crate) fn wrap_pty<T>(&self, pty: T, listener: ZedListener) -> InlineImagePty<T> {
+        InlineImagePty {
+            reader: ImageScanningReader {
+                pty,
+                scanner: ImageScanner::new(self.store.clone(), listener),
+                input: vec![0; READ_BUFFER_SIZE].into_boxed_slice(),
+                output: Vec::new(),
+                output_start: 0,
+            },
+        }
+    }
+
+    /// Takes the image anchors out of `cells`, so that they aren't treated as hyperlinks,
+    /// and returns the images they belong to.
+    pub(crate) fn place(&self, cells: &mut [IndexedCell]) -> Vec<ImagePlacement> {
+        let mut placements = Vec::new();
+        let mut placed = HashSet::default();
+        let mut store = None;
+        for cell in cells {
+            let Some((id, row)) = cell
+                .cell
+                .hyperlink()
+                .and_then(|hyperlink| parse_anchor(hyperlink.uri()))
+            else {
+                continue;
+            };
+            cell.cell.set_hyperlink(None);
+            if !placed.insert(id) {
+                continue;
+            }
+            let store = store.get_or_insert_with(|| self.store.lock());
+            if let Some(image) = store.images.get(&id) {
+                placements.push(ImagePlacement {
+                    image: image.image.clone(),
+                    point: AlacPoint::new(Line(cell.point.line.0 - row as i32), cell.point.column),
+                    columns: image.columns,
+                    rows: image.rows,
+                });
+            }
+        }
+        placements
+    }
+
+    /// Forgets all images, as the screen they were on has been cleared.
+    pub(crate) fn clear(&self) {
+        self.store.lock().remove_where(|_| true);
+    }
+
+    /// Returns the images that are gone, to free their textures.
+    pub(crate) fn take_dropped(&self) -> Vec<Arc<RenderImage>> {
+        mem::take(&mut self.store.lock().dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        Term,
+        event::VoidListener,
+        grid::Dimensions,
+        index::Column,
+        term::Config,
+        vte::ansi::{Processor, StdSyncHandler},
+    };
+    use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+    use gpui::{bounds, point, px, size};
+
+    use super::*;
+    use crate::TerminalBounds;
+
+    struct TestTerminal {
+        term: Term<VoidListener>,
+        scanner: ImageScanner,
+        images: InlineImages,
+        responses: UnboundedReceiver<AlacTermEvent>,
+    }
+
+    impl TestTerminal {
+        fn new(columns: usize, lines: usize) -> Self {
+            let cell_width = px(10.);
+            let line_height = px(20.);
+            let terminal_bounds = TerminalBounds::new(
+                line_height,
+                cell_width,
+                bounds(
+                    point(px(0.), px(0.)),
+                    size(cell_width * columns as f32, line_height * lines as f32),
+                ),
+            );
+            let images = InlineImages::new();
+            let (events_tx, responses) = unbounded();
+            let mut scanner = ImageScanner::new(images.store.clone(), ZedListener(events_tx));
+            scanner.window_size = Some(terminal_bounds.into());
+            Self {
+                term: Term::new(Config::default(), &terminal_bounds, VoidListener),
+                scanner,
+                images,
+                responses,
+            }
+        }
+
+        fn output(&mut self, chunks: &[&[u8]]) {
+            let mut output = Vec::new();
+            for chunk in chunks {
+                self.scanner.scan(chunk, &mut output);
+            }
+            Processor::<StdSyncHandler>::new().advance(&mut self.term, &output);
+        }
+
+        fn text(&self) -> String {
+            self.term
+                .bounds_to_string(
+                    AlacPoint::new(self.term.topmost_line(), Column(0)),
+                    AlacPoint::new(self.term.bottommost_line(), self.term.last_column()),
+                )
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim_end()
+                .to_string()
+        }
+
+        fn placements(&self) -> Vec<(AlacPoint, usize, usize, (i32, i32))> {
+            let content = self.term.renderable_content();
+            let mut cells = content
+                .display_iter
+                .map(|indexed| IndexedCell {
+                    point: indexed.point,
+                    cell: indexed.cell.clone(),
+                })
+                .collect::<Vec<_>>();
+            let placements = self.images.place(&mut cells);
+            assert!(cells.iter().all(|cell| cell.hyperlink().is_none()));
+            placements
+                .into_iter()
+                .map(|placement| {
+                    let size = placement.image.size(0);
+                    (
+                        placement.point,
+                        placement.columns,
+                        placement.rows,
+                        (size.width.0, size.height.0),
+                    )
+                })
+                .collect()
+        }
+
+        fn response(&mut self) -> Option<String> {
+            match self.responses.try_next() {
+                Ok(Some(AlacTermEvent::PtyWrite(response))) => Some(response),
+                _ => None,
+            }
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        RgbaImage::from_pixel(width, height, image::Rgba([255, 0, 0, 255]))
+            .write_to(&mut io::Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    fn grid_point(line: i32, column: usize) -> AlacPoint {
+        AlacPoint::new(Line(line), Column(column))
+    }
+
+    #[test]
+    fn test_iterm2_image() {
+        let mut terminal = TestTerminal::new(20, 10);
+        let image = BASE64.encode(png(30, 50));
+        let sequence = format!("\x1b]1337;File=name=cGxvdA==;inline=1:{image}\x07");
+        let (first, second) = sequence.as_bytes().split_at(20);
+        terminal.output(&[b"before\r\n", first, second, b"after"]);
+
+        assert_eq!(terminal.text(), "before\n\n\n\nafter");
+        assert_eq!(terminal.placements(), [(grid_point(1, 0), 3, 3, (30, 50))]);
+        assert_eq!(terminal.term.grid().cursor.point, grid_point(4, 5));
+    }
+
+    #[test]
+    fn test_iterm2_image_size_arguments() {
+        let mut terminal = TestTerminal::new(20, 10);
+        let image = BASE64.encode(png(30, 50));
+        terminal.output(&[
+            format!("\x1b]1337;File=inline=1;width=6:{image}\x1b\\").as_bytes(),
+            format!("\x1b]1337;File=inline=1;height=40px;width=auto:{image}\x1b\\").as_bytes(),
+        ]);
+        assert_eq!(
+            terminal.placements(),
+            [
+                (grid_point(0, 0), 6, 5, (30, 50)),
+                (grid_point(5, 0), 3, 2, (30, 50))
+            ]
+        );
+
+        // Images that are downloaded rather than shown are dropped.
+        let mut terminal = TestTerminal::new(20, 10);
+        terminal.output(&[format!("\x1b]1337;File=name=eA==:{image}\x07$").as_bytes()]);
+        assert_eq!(terminal.text(), "$");
+        assert!(terminal.placements().is_empty());
+    }
+
+    #[test]
+    fn test_images_scale_to_screen_width() {
+        let mut terminal = TestTerminal::new(10, 10);
+        let image = BASE64.encode(png(200, 100));
+        terminal.output(&[format!("\x1b]1337;File=inline=1:{image}\x07").as_bytes()]);
+        assert_eq!(
+            terminal.placements(),
+            [(grid_point(0, 0), 10, 3, (200, 100))]
+        );
+    }
+
+    #[test]
+    fn test_sixel_image() {
+        let mut terminal = TestTerminal::new(20, 10);
+        // A 12x12 image: two bands of six rows, in a defined red and the default blue.
+        terminal.output(&[
+            b"$ \x1bPq\"1;1;12;12#5;2;100;0;0!12~-#1!6~!6?\x1b\\",
+            b"done",
+        ]);
+
+        assert_eq!(terminal.text(), "$\ndone");
+        assert_eq!(terminal.placements(), [(grid_point(0, 2), 2, 1, (12, 12))]);
+
+        let image = decode_sixel(b"\"1;1;4;12#5;2;100;0;0!4~-#1!2~").unwrap();
+        assert_eq!(image.dimensions(), (4, 12));
+        assert_eq!(image.get_pixel(3, 0).0, [255, 0, 0, 255]);
+        assert_eq!(image.get_pixel(0, 6).0, [51, 51, 204, 255]);
+        assert_eq!(image.get_pixel(3, 6).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_kitty_image() {
+        let mut terminal = TestTerminal::new(20, 10);
+        let image = BASE64.encode(png(20, 40));
+        let (first, second) = image.split_at(image.len() / 8 * 4);
+        terminal.output(&[
+            format!("\x1b_Ga=T,f=100,i=7,m=1;{first}\x1b\\").as_bytes(),
+            format!("\x1b_Gm=0;{second}\x1b\\").as_bytes(),
+            b"x",
+        ]);
+
+        assert_eq!(terminal.response().as_deref(), Some("\x1b_Gi=7;OK\x1b\\"));
+        assert_eq!(terminal.placements(), [(grid_point(0, 0), 2, 2, (20, 40))]);
+        // The cursor is left after the image, on its last line.
+        assert_eq!(terminal.term.grid().cursor.point, grid_point(1, 3));
+
+        // Placed again by its id, in a given size, without moving the cursor.
+        terminal.output(&[b"\r\n\r\n\x1b_Ga=p,i=7,c=4,C=1,q=1\x1b\\"]);
+        assert_eq!(terminal.response(), None);
+        assert_eq!(terminal.term.grid().cursor.point, grid_point(3, 0));
+        assert_eq!(
+            terminal.placements(),
+            [
+                (grid_point(0, 0), 2, 2, (20, 40)),
+                (grid_point(3, 0), 4, 4, (20, 40))
+            ]
+        );
+
+        terminal.output(&[b"\x1b_Ga=d,d=i,i=7\x1b\\"]);
+        assert!(terminal.placements().is_empty());
+    }
+
+    #[test]
+    fn test_kitty_raw_image_and_query() {
+        let mut terminal = TestTerminal::new(20, 10);
+        let rgb = BASE64.encode([255u8, 0, 0].repeat(4));
+        terminal.output(&[
+            format!("\x1b_Ga=q,i=1,s=2,v=2,f=24;{rgb}\x1b\\").as_bytes(),
+            b"\x1b_Ga=q,i=2,t=f;L3RtcC9pbWFnZQ==\x1b\\",
+        ]);
+        assert_eq!(terminal.response().as_deref(), Some("\x1b_Gi=1;OK\x1b\\"));
+        assert_eq!(
+            terminal.response().as_deref(),
+            Some("\x1b_Gi=2;EINVAL:only direct transmission is supported\x1b\\")
+        );
+        assert!(terminal.placements().is_empty());
+
+        terminal.output(&[format!("\x1b_Ga=T,s=2,v=2,f=24;{rgb}\x1b\\").as_bytes()]);
+        assert_eq!(terminal.response(), None);
+        assert_eq!(terminal.placements(), [(grid_point(0, 0), 1, 1, (2, 2))]);
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        let images = InlineImages::new();
+        let (events_tx, _events_rx) = unbounded();
+        let mut scanner = ImageScanner::new(images.store.clone(), ZedListener(events_tx));
+        let input: &[u8] = b"\x1b]0;title\x07\x1b]133;A\x1b\\\x1bP$qm\x1b\\\x1b_Xapc\x1b\\\x1b[1m\x1b\x1b]8;;x\x07";
+        for split in 0..input.len() {
+            let mut output = Vec::new();
+            scanner.scan(&input[..split], &mut output);
+            scanner.scan(&input[split..], &mut output);
+            assert_eq!(output, input, "split at {split}");
+        }
+        images.clear();
+        assert!(mem::take(&mut images.store.lock().dropped).is_empty());
+    }
+
+    #[test]
+    fn test_images_move_with_the_grid() {
+        let mut terminal = TestTerminal::new(20, 4);
+        let image = BASE64.encode(png(10, 40));
+        terminal.output(&[format!("\x1b]1337;File=inline=1:{image}\x07").as_bytes()]);
+        assert_eq!(terminal.placements(), [(grid_point(0, 0), 1, 2, (10, 40))]);
+
+        // Scrolled partly off the screen, the image is still drawn from its top line.
+        terminal.output(&[b"1\r\n2\r\n3"]);
+        assert_eq!(terminal.placements(), [(grid_point(-1, 0), 1, 2, (10, 40))]);
+
+        // Overwritten, it's gone.
+        terminal.output(&[b"\x1b[2J\x1b[3J"]);
+        assert!(terminal.placements().is_empty());
+    }
+}
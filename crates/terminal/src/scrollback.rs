@@ -0,0 +1,263 @@
+//! Encoding of a terminal's scrollback as text with escape sequences, so that it can be saved
+//! and replayed into a new terminal with its colors and styles.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    Term,
+    grid::Dimensions,
+    index::{Column, Line},
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color, NamedColor},
+};
+
+/// Printed between the restored scrollback and the output of the new session.
+pub(crate) const RESTORED_SCROLLBACK_SEPARATOR: &str = "\x1b[0;2m[Restored session]\x1b[0m\r\n";
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+#[derive(Clone, Copy, PartialEq)]
+struct Style {
+    fg: Color,
+    bg: Color,
+    flags: Flags,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+        }
+    }
+}
+
+impl Style {
+    fn of(cell: &Cell) -> Self {
+        Self {
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags & STYLE_FLAGS,
+        }
+    }
+
+    fn write_sgr(&self, output: &mut String) {
+        output.push_str("\x1b[0");
+        for (flag, code) in [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "21"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ] {
+            if self.flags.contains(flag) {
+                output.push(';');
+                output.push_str(code);
+            }
+        }
+        write_color(output, self.fg, 30, 90, 38);
+        write_color(output, self.bg, 40, 100, 48);
+        output.push('m');
+    }
+}
+
+fn write_color(output: &mut String, color: Color, base: u8, bright_base: u8, extended: u8) {
+    match color {
+        Color::Named(named) => {
+            let index = named as usize;
+            if index < 8 {
+                write!(output, ";{}", base as usize + index).ok();
+            } else if index < 16 {
+                write!(output, ";{}", bright_base as usize + index - 8).ok();
+            }
+        }
+        Color::Indexed(index) => {
+            write!(output, ";{extended};5;{index}").ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' ' && Style::of(cell) == Style::default() && cell.zerowidth().is_none()
+}
+
+/// Encodes the lines of `term` from `start` to `end`, with their colors and styles.
+///
+/// Trailing blanks are left out, except on the last line when `end_column` is given, which
+/// is then encoded up to that column. The output doesn't end with a line break.
+pub(crate) fn encode_lines<T>(
+    term: &Term<T>,
+    start: Line,
+    end: Line,
+    end_column: Option<Column>,
+) -> String {
+    let grid = term.grid();
+    let columns = grid.columns();
+    let mut output = String::new();
+    let mut style = Style::default();
+
+    let mut line = start.max(grid.topmost_line());
+    let end = end.min(grid.bottommost_line());
+    while line <= end {
+        let row = &grid[line];
+        let wrapped = row[Column(columns - 1)].flags.contains(Flags::WRAPLINE);
+        let len = match end_column {
+            Some(column) if line == end => column.0.min(columns),
+            _ if wrapped => columns,
+            _ => (0..columns)
+                .rev()
+                .find(|&column| !is_blank(&row[Column(column)]))
+                .map_or(0, |column| column + 1),
+        };
+
+        for column in 0..len {
+            let cell = &row[Column(column)];
+            if cell
+                .flags
+                .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+            {
+                continue;
+            }
+            let cell_style = Style::of(cell);
+            if cell_style != style {
+                cell_style.write_sgr(&mut output);
+                style = cell_style;
+            }
+            output.push(cell.c);
+            if let Some(zerowidth) = cell.zerowidth() {
+                output.extend(zerowidth);
+            }
+        }
+
+        if line < end && !(wrapped && len == columns) {
+            // Reset before the line break, as it would otherwise extend the background.
+            if style != Style::default() {
+                output.push_str("\x1b[0m");
+                style = Style::default();
+            }
+            output.push_str("\r\n");
+        }
+        line = Line(line.0 + 1);
+    }
+
+    if style != Style::default() {
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+/// Encodes up to `max_lines` of the scrollback of `term`, ending with the cursor's line.
+pub(crate) fn encode_scrollback<T>(term: &Term<T>, max_lines: usize) -> String {
+    let cursor_line = term.grid().cursor.point.line;
+    let start = Line(cursor_line.0 - max_lines.saturating_sub(1) as i32);
+    let scrollback = encode_lines(term, start, cursor_line, None);
+    scrollback.trim_end_matches(['\r', '\n']).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::Config,
+        vte::ansi::{Processor, Rgb, StdSyncHandler},
+    };
+    use gpui::{bounds, point, px, size};
+
+    use super::*;
+    use crate::TerminalBounds;
+
+    fn term(columns: usize, lines: usize, output: &str) -> Term<VoidListener> {
+        let cell_width = px(10.);
+        let line_height = px(10.);
+        let terminal_bounds = TerminalBounds::new(
+            line_height,
+            cell_width,
+            bounds(
+                point(px(0.), px(0.)),
+                size(cell_width * columns as f32, line_height * lines as f32),
+            ),
+        );
+        let mut term = Term::new(Config::default(), &terminal_bounds, VoidListener);
+        Processor::<StdSyncHandler>::new().advance(&mut term, output.as_bytes());
+        term
+    }
+
+    fn text(term: &Term<VoidListener>) -> String {
+        term.bounds_to_string(
+            alacritty_terminal::index::Point::new(term.topmost_line(), Column(0)),
+            alacritty_terminal::index::Point::new(term.bottommost_line(), term.last_column()),
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    #[test]
+    fn test_encode_scrollback_round_trip() {
+        let output = "plain\r\n\x1b[1;31mbold red\x1b[0m \x1b[48;2;1;2;3mrgb\x1b[0m\r\n\x1b[4mwide 🦀\x1b[0m\r\n$ ";
+        let original = term(20, 10, output);
+        let scrollback = encode_scrollback(&original, 100);
+        assert_eq!(
+            scrollback,
+            "plain\r\n\x1b[0;1;31mbold red\x1b[0m \x1b[0;48;2;1;2;3mrgb\x1b[0m\r\n\x1b[0;4mwide 🦀\x1b[0m\r\n$"
+        );
+
+        let restored = term(20, 10, &scrollback);
+        assert_eq!(text(&restored), text(&original));
+        let cell = &restored.grid()[Line(1)][Column(0)];
+        assert!(cell.flags.contains(Flags::BOLD));
+        assert_eq!(cell.fg, Color::Named(NamedColor::Red));
+        assert_eq!(
+            restored.grid()[Line(1)][Column(9)].bg,
+            Color::Spec(Rgb { r: 1, g: 2, b: 3 })
+        );
+    }
+
+    #[test]
+    fn test_encode_scrollback_wrapped_lines() {
+        let original = term(5, 10, "0123456789ab\r\nnext\r\n");
+        let scrollback = encode_scrollback(&original, 100);
+        assert_eq!(scrollback, "0123456789ab\r\nnext");
+
+        // Wrapped lines are joined again, so that they reflow in a terminal of another width.
+        let restored = term(8, 10, &scrollback);
+        assert_eq!(text(&restored), "0123456789ab\nnext");
+        assert!(
+            restored.grid()[Line(0)][Column(7)]
+                .flags
+                .contains(Flags::WRAPLINE)
+        );
+    }
+
+    #[test]
+    fn test_encode_scrollback_line_cap() {
+        let original = term(10, 3, "1\r\n2\r\n3\r\n4\r\n5");
+        assert_eq!(encode_scrollback(&original, 2), "4\r\n5");
+        assert_eq!(encode_scrollback(&original, 100), "1\r\n2\r\n3\r\n4\r\n5");
+    }
+
+    #[test]
+    fn test_encode_lines_up_to_cursor() {
+        let original = term(10, 3, "$ ");
+        let cursor = original.grid().cursor.point;
+        assert_eq!(
+            encode_lines(&original, Line(0), cursor.line, Some(cursor.column)),
+            "$ "
+        );
+    }
+}
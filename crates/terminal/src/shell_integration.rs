@@ -0,0 +1,884 @@
+//! Shell integration through the OSC 133 semantic prompt sequences.
+//!
+//! Shells mark the start of their prompt (`A`), the end of it (`B`), the start of a
+//! command's output (`C`) and the command's completion (`D`, with its exit code). Alacritty
+//! ignores these sequences, so they are picked out of the PTY output before it reaches the
+//! parser, and are then placed on the grid lines they were printed at.
+
+use std::{
+    borrow::Cow,
+    io::{self, Read},
+    mem,
+    ops::Range,
+    path::Path,
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    Term,
+    event::{OnResize, WindowSize},
+    grid::Dimensions,
+    index::Line,
+    sync::FairMutex,
+    term::TermMode,
+    tty::{ChildEvent, EventedPty, EventedReadWrite},
+};
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use polling::{Event, PollMode, Poller};
+
+/// OSC 133 sequences longer than this are dropped.
+const MAX_OSC_LEN: usize = 64 * 1024;
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSHENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const ZPROFILE_SCRIPT: &str = include_str!("shell_integration/zprofile.zsh");
+const ZSHRC_SCRIPT: &str = include_str!("shell_integration/zshrc.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ShellMark {
+    PromptStart,
+    CommandStart,
+    CommandExecuted { command: Option<String> },
+    CommandFinished { exit_code: Option<i32> },
+}
+
+impl ShellMark {
+    fn parse(params: &str) -> Option<Self> {
+        let mut params = params.split(';');
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "B" => Some(Self::CommandStart),
+            "C" => {
+                let command = params.find_map(|param| {
+                    if let Some(command) = param.strip_prefix("cmdline_url=") {
+                        urlencoding::decode(command).ok().map(Cow::into_owned)
+                    } else {
+                        param.strip_prefix("cmdline=").map(ToOwned::to_owned)
+                    }
+                });
+                Some(Self::CommandExecuted { command })
+            }
+            "D" => Some(Self::CommandFinished {
+                exit_code: params.next().and_then(|code| code.parse().ok()),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingMark {
+    mark: ShellMark,
+    /// The number of line feeds in the output before this mark.
+    newlines: u64,
+}
+
+#[derive(Default)]
+struct SharedState {
+    marks: Vec<PendingMark>,
+    newlines: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Osc,
+}
+
+/// Picks OSC 133 sequences out of the PTY output, and counts the line feeds around them so
+/// that they can be placed on the grid once the output has been parsed.
+struct MarkScanner {
+    state: ScanState,
+    osc: Vec<u8>,
+    ignoring_osc: bool,
+    newlines: u64,
+    shared: Arc<FairMutex<SharedState>>,
+}
+
+impl MarkScanner {
+    fn new(shared: Arc<FairMutex<SharedState>>) -> Self {
+        Self {
+            state: ScanState::Ground,
+            osc: Vec::new(),
+            ignoring_osc: false,
+            newlines: 0,
+            shared,
+        }
+    }
+
+    fn scan(&mut self, bytes: &[u8]) {
+        let mut marks = Vec::new();
+        for &byte in bytes {
+            match self.state {
+                ScanState::Ground => match byte {
+                    0x1b => self.state = ScanState::Escape,
+                    b'\n' | 0x0b | 0x0c => self.newlines += 1,
+                    _ => {}
+                },
+                ScanState::Escape => match byte {
+                    b']' => {
+                        self.osc.clear();
+                        self.ignoring_osc = false;
+                        self.state = ScanState::Osc;
+                    }
+                    0x1b => {}
+                    b'\n' | 0x0b | 0x0c => {
+                        self.newlines += 1;
+                        self.state = ScanState::Ground;
+                    }
+                    _ => self.state = ScanState::Ground,
+                },
+                ScanState::Osc => match byte {
+                    // BEL terminates the sequence, and so does ESC, which is usually the
+                    // start of the `ESC \` string terminator.
+                    0x07 | 0x1b => {
+                        if let Some(mark) = self.finish_osc() {
+                            marks.push(PendingMark {
+                                mark,
+                                newlines: self.newlines,
+                            });
+                        }
+                        self.state = if byte == 0x1b {
+                            ScanState::Escape
+                        } else {
+                            ScanState::Ground
+                        };
+                    }
+                    // CAN and SUB abort the sequence.
+                    0x18 | 0x1a => self.state = ScanState::Ground,
+                    _ => self.push_osc(byte),
+                },
+            }
+        }
+
+        let mut shared = self.shared.lock();
+        shared.marks.append(&mut marks);
+        shared.newlines = self.newlines;
+    }
+
+    fn push_osc(&mut self, byte: u8) {
+        if self.ignoring_osc {
+            return;
+        }
+        if self.osc.len() >= MAX_OSC_LEN {
+            self.ignoring_osc = true;
+            self.osc = Vec::new();
+            return;
+        }
+        self.osc.push(byte);
+        if self.osc.len() == 4 && self.osc != b"133;" {
+            self.ignoring_osc = true;
+        }
+    }
+
+    fn finish_osc(&mut self) -> Option<ShellMark> {
+        if self.ignoring_osc {
+            return None;
+        }
+        let params = self.osc.strip_prefix(b"133;")?;
+        ShellMark::parse(std::str::from_utf8(params).ok()?)
+    }
+}
+
+/// Wraps a PTY so that the shell integration sequences in its output are recorded.
+pub(crate) struct ShellIntegrationPty<T> {
+    reader: MarkScanningReader<T>,
+}
+
+pub(crate) struct MarkScanningReader<T> {
+    pty: T,
+    scanner: MarkScanner,
+}
+
+impl<T: EventedReadWrite> Read for MarkScanningReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.pty.reader().read(buf)?;
+        self.scanner.scan(&buf[..len]);
+        Ok(len)
+    }
+}
+
+impl<T: EventedReadWrite> EventedReadWrite for ShellIntegrationPty<T> {
+    type Reader = MarkScanningReader<T>;
+    type Writer = T::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        unsafe { self.reader.pty.register(poll, interest, mode) }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.reader.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.reader.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.reader.pty.writer()
+    }
+}
+
+impl<T: EventedPty> EventedPty for ShellIntegrationPty<T> {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.reader.pty.next_child_event()
+    }
+}
+
+impl<T: OnResize> OnResize for ShellIntegrationPty<T> {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.reader.pty.on_resize(window_size)
+    }
+}
+
+/// A prompt and the command that was run from it.
+///
+/// Lines are counted from the first line the terminal has ever shown, so that they stay
+/// put while the output scrolls.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandBlock {
+    pub prompt_line: i64,
+    pub command_line: Option<i64>,
+    /// The first line of the command's output.
+    pub output_line: Option<i64>,
+    /// The line after the command's output.
+    pub end_line: Option<i64>,
+    pub command: Option<String>,
+    pub exit_code: Option<i32>,
+}
+
+impl CommandBlock {
+    pub fn status(&self) -> CommandStatus {
+        if self.output_line.is_none() {
+            CommandStatus::Prompt
+        } else if self.end_line.is_none() {
+            CommandStatus::Running
+        } else {
+            CommandStatus::Finished {
+                exit_code: self.exit_code,
+            }
+        }
+    }
+
+    fn last_line(&self) -> i64 {
+        self.end_line
+            .or(self.output_line)
+            .or(self.command_line)
+            .unwrap_or(self.prompt_line)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    /// The shell is waiting for a command.
+    Prompt,
+    Running,
+    Finished {
+        exit_code: Option<i32>,
+    },
+}
+
+/// A command block on the screen, for painting its gutter marker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandMark {
+    /// The grid line of the prompt.
+    pub line: Line,
+    /// The last grid line of the block.
+    pub end_line: Line,
+    pub status: CommandStatus,
+    pub selected: bool,
+}
+
+/// The command blocks of a terminal, built from the marks its shell has printed.
+pub(crate) struct ShellIntegration {
+    shared: Arc<FairMutex<SharedState>>,
+    blocks: Vec<CommandBlock>,
+    max_history: usize,
+    /// The number of lines that have scrolled off the top of the screen.
+    scrolled_lines: i64,
+    last_history_size: usize,
+    last_cursor_line: i32,
+    last_newlines: u64,
+    /// The prompt line of the block picked by prompt navigation.
+    selected: Option<i64>,
+}
+
+impl ShellIntegration {
+    pub(crate) fn new(max_history: usize) -> Self {
+        Self {
+            shared: Arc::new(FairMutex::new(SharedState::default())),
+            blocks: Vec::new(),
+            max_history,
+            scrolled_lines: 0,
+            last_history_size: 0,
+            last_cursor_line: 0,
+            last_newlines: 0,
+            selected: None,
+        }
+    }
+
+    pub(crate) fn wrap_pty<T>(&self, pty: T) -> ShellIntegrationPty<T> {
+        ShellIntegrationPty {
+            reader: MarkScanningReader {
+                pty,
+                scanner: MarkScanner::new(self.shared.clone()),
+            },
+        }
+    }
+
+    pub(crate) fn blocks(&self) -> &[CommandBlock] {
+        &self.blocks
+    }
+
+    /// Places the marks printed since the last call on the grid.
+    ///
+    /// This must be called with every output that has been read parsed into `term`, which is
+    /// the case while holding its lock.
+    pub(crate) fn sync<T>(&mut self, term: &Term<T>) {
+        // Full screen applications have no prompts, and their output says nothing about
+        // the lines of the main screen.
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let (marks, newlines) = {
+            let mut shared = self.shared.lock();
+            (mem::take(&mut shared.marks), shared.newlines)
+        };
+        let grid = term.grid();
+        let history_size = grid.history_size();
+        let cursor_line = grid.cursor.point.line.0;
+        let cursor_moved = cursor_line - self.last_cursor_line;
+        // With the history full, the only hint of scrolling are the line feeds that didn't
+        // move the cursor down.
+        let history_full = history_size > 0 && history_size >= self.max_history;
+        let newline_scroll = newlines
+            .saturating_sub(self.last_newlines)
+            .saturating_sub(cursor_moved.max(0) as u64) as i64;
+
+        if history_size > self.last_history_size {
+            let grown_by = (history_size - self.last_history_size) as i64;
+            self.scrolled_lines += if history_full {
+                grown_by.max(newline_scroll)
+            } else {
+                grown_by
+            };
+        } else if history_size < self.last_history_size {
+            // Growing the terminal pulls lines back out of the history, the history being
+            // cleared doesn't move the lines on the screen.
+            let shrunk_by = self.last_history_size - history_size;
+            if cursor_moved == shrunk_by as i32 {
+                self.scrolled_lines -= shrunk_by as i64;
+            }
+        } else if history_full {
+            self.scrolled_lines += newline_scroll;
+        }
+
+        self.last_history_size = history_size;
+        self.last_cursor_line = cursor_line;
+        self.last_newlines = newlines;
+
+        let cursor_line = self.scrolled_lines + cursor_line as i64;
+        let mut floor = self.blocks.last().map_or(i64::MIN, CommandBlock::last_line);
+        for PendingMark {
+            mark,
+            newlines: mark_newlines,
+        } in marks
+        {
+            let line = cursor_line - newlines.saturating_sub(mark_newlines) as i64;
+            let line = line.clamp(floor.min(cursor_line), cursor_line);
+            floor = line;
+            self.apply_mark(mark, line);
+        }
+
+        let first_line = self.scrolled_lines - history_size as i64;
+        self.blocks.retain(|block| block.prompt_line >= first_line);
+        if self.selected.is_some_and(|selected| selected < first_line) {
+            self.selected = None;
+        }
+    }
+
+    fn apply_mark(&mut self, mark: ShellMark, line: i64) {
+        match mark {
+            ShellMark::PromptStart => {
+                // A prompt that was abandoned, or redrawn, didn't run anything.
+                if self
+                    .blocks
+                    .last()
+                    .is_some_and(|block| block.output_line.is_none())
+                {
+                    self.blocks.pop();
+                }
+                self.blocks.push(CommandBlock {
+                    prompt_line: line,
+                    ..CommandBlock::default()
+                });
+            }
+            ShellMark::CommandStart => {
+                if let Some(block) = self.blocks.last_mut()
+                    && block.output_line.is_none()
+                {
+                    block.command_line = Some(line);
+                }
+            }
+            ShellMark::CommandExecuted { command } => {
+                if !self
+                    .blocks
+                    .last()
+                    .is_some_and(|block| block.output_line.is_none())
+                {
+                    self.blocks.push(CommandBlock {
+                        prompt_line: line,
+                        ..CommandBlock::default()
+                    });
+                }
+                if let Some(block) = self.blocks.last_mut() {
+                    block.output_line = Some(line);
+                    block.command = command.filter(|command| !command.trim().is_empty());
+                }
+            }
+            ShellMark::CommandFinished { exit_code } => {
+                if let Some(block) = self.blocks.last_mut()
+                    && block.output_line.is_some()
+                    && block.end_line.is_none()
+                {
+                    block.end_line = Some(line);
+                    block.exit_code = exit_code;
+                }
+            }
+        }
+    }
+
+    /// Forgets all blocks, as the screen they were on has been cleared.
+    pub(crate) fn clear(&mut self) {
+        self.blocks.clear();
+        self.selected = None;
+    }
+
+    pub(crate) fn clear_selection(&mut self) {
+        self.selected = None;
+    }
+
+    fn grid_line(&self, line: i64) -> Line {
+        Line((line - self.scrolled_lines) as i32)
+    }
+
+    /// Selects the block of the command before the selected one, or the last command, and
+    /// returns the grid line of its prompt.
+    pub(crate) fn select_previous(&mut self) -> Option<Line> {
+        let block = self
+            .blocks
+            .iter()
+            .rev()
+            .filter(|block| block.output_line.is_some())
+            .find(|block| self.selected.is_none_or(|line| block.prompt_line < line))?;
+        let line = block.prompt_line;
+        self.selected = Some(line);
+        Some(self.grid_line(line))
+    }
+
+    /// Selects the block of the command after the selected one, and returns the grid line of
+    /// its prompt. The selection is cleared after the last command.
+    pub(crate) fn select_next(&mut self) -> Option<Line> {
+        let selected = self.selected?;
+        let line = self
+            .blocks
+            .iter()
+            .filter(|block| block.output_line.is_some())
+            .map(|block| block.prompt_line)
+            .find(|&line| line > selected);
+        self.selected = line;
+        line.map(|line| self.grid_line(line))
+    }
+
+    /// The selected block, or the last one that ran a command.
+    pub(crate) fn target_block(&self) -> Option<&CommandBlock> {
+        match self.selected {
+            Some(selected) => self
+                .blocks
+                .iter()
+                .find(|block| block.prompt_line == selected),
+            None => self
+                .blocks
+                .iter()
+                .rev()
+                .find(|block| block.output_line.is_some()),
+        }
+    }
+
+    /// The grid lines of the target block's output, up to the cursor while it's running.
+    pub(crate) fn target_output_lines(&self, cursor_line: Line) -> Option<Range<Line>> {
+        let block = self.target_block()?;
+        let start = self.grid_line(block.output_line?);
+        let end = match block.end_line {
+            Some(end_line) => self.grid_line(end_line),
+            None => Line(cursor_line.0 + 1),
+        };
+        (start < end).then_some(start..end)
+    }
+
+    /// Whether the shell is waiting at its prompt.
+    pub(crate) fn is_at_prompt(&self) -> bool {
+        self.blocks
+            .last()
+            .is_some_and(|block| block.status() == CommandStatus::Prompt)
+    }
+
+    /// The gutter markers for the blocks on the given grid lines.
+    pub(crate) fn marks(&self, visible_lines: Range<Line>, cursor_line: Line) -> Vec<CommandMark> {
+        self.blocks
+            .iter()
+            .filter_map(|block| {
+                let line = self.grid_line(block.prompt_line);
+                let end_line = match block.end_line {
+                    Some(end_line) => self.grid_line(end_line - 1),
+                    None => cursor_line,
+                }
+                .max(line);
+                (line < visible_lines.end && end_line >= visible_lines.start).then(|| CommandMark {
+                    line,
+                    end_line,
+                    status: block.status(),
+                    selected: self.selected == Some(block.prompt_line),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Sets up the shell to load Zed's shell integration script, returning the arguments to
+/// start it with, or `None` if the shell isn't supported.
+pub(crate) fn inject(
+    program: &str,
+    args: &[String],
+    login: bool,
+    env: &mut HashMap<String, String>,
+) -> Result<Option<Vec<String>>> {
+    let Some(shell) = Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+    else {
+        return Ok(None);
+    };
+    let dir = paths::temp_dir().join("shell_integration");
+    let mut args = args.to_vec();
+    match shell {
+        "bash" => {
+            // Anything else is a script or a command rather than an interactive shell.
+            if !args.is_empty() {
+                return Ok(None);
+            }
+            let script = dir.join("zed.bash");
+            write_script(&script, BASH_SCRIPT)?;
+            if login {
+                env.insert("ZED_SHELL_INTEGRATION_LOGIN".to_string(), "1".to_string());
+            }
+            args.push("--init-file".to_string());
+            args.push(script.to_string_lossy().into_owned());
+        }
+        "zsh" => {
+            let zdotdir = dir.join("zsh");
+            write_script(&zdotdir.join(".zshenv"), ZSHENV_SCRIPT)?;
+            write_script(&zdotdir.join(".zprofile"), ZPROFILE_SCRIPT)?;
+            write_script(&zdotdir.join(".zshrc"), ZSHRC_SCRIPT)?;
+            if let Some(user_zdotdir) = env
+                .get("ZDOTDIR")
+                .cloned()
+                .or_else(|| std::env::var("ZDOTDIR").ok())
+            {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                zdotdir.to_string_lossy().into_owned(),
+            );
+            if login {
+                args.insert(0, "-l".to_string());
+            }
+        }
+        "fish" => {
+            let data_dir = dir.join("fish");
+            write_script(
+                &data_dir.join("fish").join("vendor_conf.d").join("zed.fish"),
+                FISH_SCRIPT,
+            )?;
+            let data_dir = data_dir.to_string_lossy().into_owned();
+            let data_dirs = env
+                .get("XDG_DATA_DIRS")
+                .cloned()
+                .or_else(|| std::env::var("XDG_DATA_DIRS").ok())
+                .filter(|dirs| !dirs.is_empty())
+                .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+            env.insert(
+                "XDG_DATA_DIRS".to_string(),
+                format!("{data_dir}:{data_dirs}"),
+            );
+            env.insert("ZED_SHELL_INTEGRATION_XDG_DIR".to_string(), data_dir);
+            if login {
+                args.insert(0, "-l".to_string());
+            }
+        }
+        _ => return Ok(None),
+    }
+    Ok(Some(args))
+}
+
+fn write_script(path: &Path, contents: &str) -> Result<()> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("creating directory {parent:?}"))?;
+    }
+    std::fs::write(path, contents).with_context(|| format!("writing {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use alacritty_terminal::{
+        event::VoidListener,
+        term::Config,
+        vte::ansi::{Processor, StdSyncHandler},
+    };
+
+    use super::*;
+    use crate::TerminalBounds;
+    use gpui::{bounds, point, px, size};
+
+    fn scan(chunks: &[&[u8]]) -> (Vec<ShellMark>, u64) {
+        let shared = Arc::new(FairMutex::new(SharedState::default()));
+        let mut scanner = MarkScanner::new(shared.clone());
+        for chunk in chunks {
+            scanner.scan(chunk);
+        }
+        let shared = shared.lock();
+        (
+            shared.marks.iter().map(|mark| mark.mark.clone()).collect(),
+            shared.newlines,
+        )
+    }
+
+    #[test]
+    fn test_scan_marks() {
+        let (marks, newlines) = scan(&[
+            b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C;cmdline_url=ls%20-la\x07",
+            b"a\r\nb\r\n\x1b]133;D;2\x1b\\",
+        ]);
+        assert_eq!(
+            marks,
+            vec![
+                ShellMark::PromptStart,
+                ShellMark::CommandStart,
+                ShellMark::CommandExecuted {
+                    command: Some("ls -la".to_string())
+                },
+                ShellMark::CommandFinished { exit_code: Some(2) },
+            ]
+        );
+        assert_eq!(newlines, 3);
+    }
+
+    #[test]
+    fn test_scan_split_sequences() {
+        let (marks, _) = scan(&[b"\x1b", b"]13", b"3;D", b";0\x1b", b"\\"]);
+        assert_eq!(
+            marks,
+            vec![ShellMark::CommandFinished { exit_code: Some(0) }]
+        );
+    }
+
+    #[test]
+    fn test_scan_ignores_other_sequences() {
+        let (marks, newlines) =
+            scan(&[b"\x1b]0;title\n\x07\x1b]1337;A\x07\x1b]133;A\x18\x1b[1B\n\x1b]133;Z\x07"]);
+        assert_eq!(marks, Vec::new());
+        assert_eq!(newlines, 1);
+
+        let (marks, _) = scan(&[b"\x1b]133;C\x07"]);
+        assert_eq!(marks, vec![ShellMark::CommandExecuted { command: None }]);
+    }
+
+    struct TestTerminal {
+        term: Term<VoidListener>,
+        processor: Processor<StdSyncHandler>,
+        scanner: MarkScanner,
+        shell_integration: ShellIntegration,
+    }
+
+    impl TestTerminal {
+        fn new(lines: usize, max_history: usize) -> Self {
+            let line_height = px(10.);
+            let cell_width = px(10.);
+            let bounds = TerminalBounds::new(
+                line_height,
+                cell_width,
+                bounds(
+                    point(px(0.), px(0.)),
+                    size(cell_width * 80., line_height * lines as f32),
+                ),
+            );
+            let config = Config {
+                scrolling_history: max_history,
+                ..Config::default()
+            };
+            let shell_integration = ShellIntegration::new(max_history);
+            Self {
+                term: Term::new(config, &bounds, VoidListener),
+                processor: Processor::new(),
+                scanner: MarkScanner::new(shell_integration.shared.clone()),
+                shell_integration,
+            }
+        }
+
+        fn output(&mut self, bytes: &str) {
+            self.scanner.scan(bytes.as_bytes());
+            self.processor.advance(&mut self.term, bytes.as_bytes());
+            self.shell_integration.sync(&self.term);
+        }
+
+        fn run(&mut self, command: &str, output: &str, exit_code: i32) {
+            self.output(&format!(
+                "\x1b]133;A\x07$ \x1b]133;B\x07{command}\r\n\x1b]133;C;cmdline_url={}\x07{output}\x1b]133;D;{exit_code}\x07",
+                urlencoding::encode(command)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_command_blocks() {
+        let mut terminal = TestTerminal::new(10, 100);
+        terminal.run("ls", "a\r\nb\r\n", 0);
+        terminal.output("\x1b]133;A\x07$ \x1b]133;B\x07");
+        assert_eq!(
+            terminal.shell_integration.blocks(),
+            &[
+                CommandBlock {
+                    prompt_line: 0,
+                    command_line: Some(0),
+                    output_line: Some(1),
+                    end_line: Some(3),
+                    command: Some("ls".to_string()),
+                    exit_code: Some(0),
+                },
+                CommandBlock {
+                    prompt_line: 3,
+                    command_line: Some(3),
+                    ..CommandBlock::default()
+                },
+            ]
+        );
+        assert!(terminal.shell_integration.is_at_prompt());
+        assert_eq!(
+            terminal.shell_integration.target_output_lines(Line(3)),
+            Some(Line(1)..Line(3))
+        );
+
+        // An empty prompt replaces the last one.
+        terminal.output("\r\n\x1b]133;A\x07$ \x1b]133;B\x07");
+        assert_eq!(terminal.shell_integration.blocks().len(), 2);
+        assert_eq!(terminal.shell_integration.blocks()[1].prompt_line, 4);
+    }
+
+    #[test]
+    fn test_command_blocks_scrolling() {
+        let mut terminal = TestTerminal::new(5, 100);
+        terminal.run("true", "", 0);
+        terminal.run("seq 6", "1\r\n2\r\n3\r\n4\r\n5\r\n6\r\n", 1);
+        terminal.output("\x1b]133;A\x07$ ");
+
+        let blocks = terminal.shell_integration.blocks();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[0].status(),
+            CommandStatus::Finished { exit_code: Some(0) }
+        );
+        assert_eq!(
+            (
+                blocks[1].prompt_line,
+                blocks[1].output_line,
+                blocks[1].end_line
+            ),
+            (1, Some(2), Some(8))
+        );
+        assert_eq!(
+            blocks[1].status(),
+            CommandStatus::Finished { exit_code: Some(1) }
+        );
+
+        // Eight lines are in use on a screen of five, the first four have scrolled off.
+        assert_eq!(terminal.shell_integration.select_previous(), Some(Line(-3)));
+        assert_eq!(
+            terminal.shell_integration.target_output_lines(Line(4)),
+            Some(Line(-2)..Line(4))
+        );
+        assert_eq!(terminal.shell_integration.select_previous(), Some(Line(-4)));
+        assert_eq!(terminal.shell_integration.select_previous(), None);
+        assert_eq!(terminal.shell_integration.select_next(), Some(Line(-3)));
+        assert_eq!(terminal.shell_integration.select_next(), None);
+        assert_eq!(
+            terminal
+                .shell_integration
+                .target_block()
+                .unwrap()
+                .command
+                .as_deref(),
+            Some("seq 6")
+        );
+    }
+
+    #[test]
+    fn test_command_blocks_with_full_history() {
+        let mut terminal = TestTerminal::new(3, 2);
+        terminal.run("seq 4", "1\r\n2\r\n3\r\n4\r\n", 0);
+        terminal.run("true", "", 0);
+        terminal.output("\x1b]133;A\x07$ ");
+
+        let blocks = terminal.shell_integration.blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            (
+                blocks[0].prompt_line,
+                blocks[0].output_line,
+                blocks[0].end_line
+            ),
+            (5, Some(6), Some(6))
+        );
+        assert_eq!(blocks[1].prompt_line, 6);
+        assert_eq!(
+            terminal.shell_integration.marks(Line(0)..Line(3), Line(2)),
+            vec![
+                CommandMark {
+                    line: Line(1),
+                    end_line: Line(1),
+                    status: CommandStatus::Finished { exit_code: Some(0) },
+                    selected: false,
+                },
+                CommandMark {
+                    line: Line(2),
+                    end_line: Line(2),
+                    status: CommandStatus::Prompt,
+                    selected: false,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+# Zed shell integration for bash.
+#
+# Zed starts bash with `--init-file` pointing at this file, which then reads the
+# user's own startup files. Prompts, commands and their output are marked with
+# OSC 133 sequences, so that Zed knows where each command starts and ends.
+
+if [ -n "$ZED_SHELL_INTEGRATION_LOGIN" ]; then
+    unset ZED_SHELL_INTEGRATION_LOGIN
+    # `--init-file` is ignored by login shells, read the login files ourselves.
+    [ -r /etc/profile ] && . /etc/profile
+    if [ -r ~/.bash_profile ]; then
+        . ~/.bash_profile
+    elif [ -r ~/.bash_login ]; then
+        . ~/.bash_login
+    elif [ -r ~/.profile ]; then
+        . ~/.profile
+    fi
+else
+    [ -r /etc/bash.bashrc ] && . /etc/bash.bashrc
+    [ -r ~/.bashrc ] && . ~/.bashrc
+fi
+
+if [[ $- == *i* && -z "$__zed_shell_integration" ]]; then
+    __zed_shell_integration=1
+    __zed_at_prompt=0
+    __zed_command_running=0
+
+    __zed_urlencode() {
+        local LC_ALL=C input="$1" output="" char i
+        for ((i = 0; i < ${#input}; i++)); do
+            char="${input:i:1}"
+            case "$char" in
+            [a-zA-Z0-9.~_-]) output+="$char" ;;
+            *)
+                printf -v char '%%%02X' "'$char"
+                output+="$char"
+                ;;
+            esac
+        done
+        printf '%s' "$output"
+    }
+
+    __zed_preexec() {
+        # The DEBUG trap runs before every simple command, including the ones in
+        # PROMPT_COMMAND. Only the first one after the prompt starts a command.
+        [[ "$__zed_at_prompt" == 1 && "$BASH_COMMAND" != __zed_precmd* ]] || return
+        __zed_at_prompt=0
+        __zed_command_running=1
+        local command
+        command=$(HISTTIMEFORMAT='' builtin history 1)
+        [[ "$command" =~ ^[[:space:]]*[0-9]+[*]?[[:space:]]+(.*)$ ]] && command="${BASH_REMATCH[1]}"
+        printf '\e]133;C;cmdline_url=%s\a' "$(__zed_urlencode "$command")"
+    }
+
+    __zed_precmd() {
+        local exit_code=$?
+        if [[ "$__zed_command_running" == 1 ]]; then
+            printf '\e]133;D;%s\a' "$exit_code"
+            __zed_command_running=0
+        fi
+        printf '\e]133;A\a'
+        return $exit_code
+    }
+
+    __zed_prompt_end() {
+        local exit_code=$?
+        # Prompt frameworks rebuild PS1 before every prompt, add the mark back.
+        if [[ "$PS1" != *'\[\e]133;B\a\]' ]]; then
+            PS1="$PS1"'\[\e]133;B\a\]'
+        fi
+        __zed_at_prompt=1
+        return $exit_code
+    }
+
+    if [[ "$(declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(__zed_precmd "${PROMPT_COMMAND[@]}" __zed_prompt_end)
+    else
+        PROMPT_COMMAND="__zed_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND};__zed_prompt_end"
+    fi
+    trap '__zed_preexec' DEBUG
+fi
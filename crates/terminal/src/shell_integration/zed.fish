@@ -0,0 +1,44 @@
+# Zed shell integration for fish.
+#
+# Zed adds a directory to XDG_DATA_DIRS so that fish reads this file from
+# vendor_conf.d. Prompts, commands and their output are marked with OSC 133
+# sequences, so that Zed knows where each command starts and ends.
+
+if set -q ZED_SHELL_INTEGRATION_XDG_DIR
+    set -l dirs (string split : -- $XDG_DATA_DIRS)
+    set dirs (string match -v -- $ZED_SHELL_INTEGRATION_XDG_DIR $dirs)
+    if test (count $dirs) -gt 0
+        set -gx XDG_DATA_DIRS (string join : -- $dirs)
+    else
+        set -e XDG_DATA_DIRS
+    end
+    set -e ZED_SHELL_INTEGRATION_XDG_DIR
+end
+
+status is-interactive; or exit
+set -q __zed_shell_integration; and exit
+set -g __zed_shell_integration 1
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]133;C;cmdline_url=%s\a' (string escape --style=url -- $argv[1])
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
+
+function __zed_prompt_start --on-event fish_prompt
+    printf '\e]133;A\a'
+end
+
+# config.fish is read after this file and may define its own prompt, so the
+# prompt is wrapped once it is about to be shown for the first time.
+function __zed_wrap_prompt --on-event fish_prompt
+    functions -e __zed_wrap_prompt
+    functions -q fish_prompt; or return
+    functions -c fish_prompt __zed_fish_prompt
+    function fish_prompt
+        __zed_fish_prompt
+        printf '\e]133;B\a'
+    end
+end
@@ -2,7 +2,10 @@ pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod inline_images;
 mod pty_info;
+mod scrollback;
+mod shell_integration;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
@@ -41,9 +44,13 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+pub use inline_images::ImagePlacement;
+use inline_images::InlineImages;
 use pty_info::{ProcessIdGetter, PtyProcessInfo};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::ShellIntegration;
+pub use shell_integration::{CommandBlock, CommandMark, CommandStatus};
 use smol::channel::{Receiver, Sender};
 use task::{HideStrategy, Shell, SpawnInTerminal};
 use terminal_hyperlinks::RegexSearches;
@@ -105,6 +112,16 @@ actions!(
         ToggleViMode,
         /// Selects all text in the terminal.
         SelectAll,
+        /// Scrolls to the prompt of the previous command.
+        ScrollToPreviousPrompt,
+        /// Scrolls to the prompt of the next command.
+        ScrollToNextPrompt,
+        /// Selects the output of the last command, or of the one scrolled to.
+        SelectCommandOutput,
+        /// Copies the output of the last command, or of the one scrolled to.
+        CopyCommandOutput,
+        /// Runs the last command, or the one scrolled to, again.
+        RerunCommand,
     ]
 );
 
@@ -388,6 +405,8 @@ impl TerminalBuilder {
             hyperlink_regex_searches: RegexSearches::new(),
             vi_mode_enabled: false,
             is_ssh_terminal: false,
+            shell_integration: ShellIntegration::new(scrolling_history),
+            inline_images: InlineImages::new(),
             last_mouse_move_time: Instant::now(),
             last_hyperlink_search_position: None,
             #[cfg(windows)]
@@ -426,6 +445,10 @@ impl TerminalBuilder {
         activation_script: Vec<String>,
     ) -> Task<Result<TerminalBuilder>> {
         let version = release_channel::AppVersion::global(cx);
+        let inject_shell_integration = cfg!(unix)
+            && task.is_none()
+            && !is_ssh_terminal
+            && TerminalSettings::try_get(cx).is_some_and(|settings| settings.shell_integration);
         cx.background_spawn(async move {
             // If the parent environment doesn't have a locale set
             // (As is the case when launched from a .app on MacOS),
@@ -503,18 +526,48 @@ impl TerminalBuilder {
             let shell_kind = shell.shell_kind(cfg!(windows));
 
             let pty_options = {
-                let alac_shell = shell_params.as_ref().map(|params| {
+                let mut alac_shell = shell_params.as_ref().map(|params| {
                     alacritty_terminal::tty::Shell::new(
                         params.program.clone(),
                         params.args.clone().unwrap_or_default(),
                     )
                 });
 
+                // The integration scripts are only passed to the shell spawned here, terminals
+                // copied from this one set them up again.
+                let mut pty_env = env.clone();
+                if inject_shell_integration {
+                    let (program, args, login) = match &shell_params {
+                        Some(params) => (
+                            params.program.clone(),
+                            params.args.clone().unwrap_or_default(),
+                            false,
+                        ),
+                        // Alacritty starts the system shell as a login shell on macOS.
+                        None => (
+                            util::get_system_shell(),
+                            Vec::new(),
+                            cfg!(target_os = "macos"),
+                        ),
+                    };
+                    match shell_integration::inject(&program, &args, login, &mut pty_env) {
+                        Ok(Some(args)) => {
+                            alac_shell = Some(alacritty_terminal::tty::Shell::new(program, args));
+                        }
+                        Ok(None) => {}
+                        Err(error) => {
+                            log::error!(
+                                "failed to set up shell integration for {program}: {error:#}"
+                            );
+                        }
+                    }
+                }
+
                 alacritty_terminal::tty::Options {
                     shell: alac_shell,
                     working_directory: working_directory.clone(),
                     drain_on_exit: true,
-                    env: env.clone().into_iter().collect(),
+                    env: pty_env.into_iter().collect(),
                     #[cfg(windows)]
                     escape_args: shell_kind.tty_escape_args(),
                 }
@@ -569,12 +622,15 @@ impl TerminalBuilder {
             let term = Arc::new(FairMutex::new(term));
 
             let pty_info = PtyProcessInfo::new(&pty);
+            let shell_integration = ShellIntegration::new(scrolling_history);
+            let inline_images = InlineImages::new();
+            let pty = inline_images.wrap_pty(pty, ZedListener(events_tx.clone()));
 
             //And connect them together
             let event_loop = EventLoop::new(
                 term.clone(),
                 ZedListener(events_tx),
-                pty,
+                shell_integration.wrap_pty(pty),
                 pty_options.drain_on_exit,
                 false,
             )
@@ -606,6 +662,8 @@ impl TerminalBuilder {
                 hyperlink_regex_searches: RegexSearches::new(),
                 vi_mode_enabled: false,
                 is_ssh_terminal,
+                shell_integration,
+                inline_images,
                 last_mouse_move_time: Instant::now(),
                 last_hyperlink_search_position: None,
                 #[cfg(windows)]
@@ -763,6 +821,9 @@ pub struct TerminalContent {
     pub last_hovered_word: Option<HoveredWord>,
     pub scrolled_to_top: bool,
     pub scrolled_to_bottom: bool,
+    /// The command blocks on the screen, when the shell integration is active.
+    pub command_marks: Vec<CommandMark>,
+    pub images: Vec<ImagePlacement>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -789,6 +850,8 @@ impl Default for TerminalContent {
             last_hovered_word: None,
             scrolled_to_top: false,
             scrolled_to_bottom: false,
+            command_marks: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -827,6 +890,8 @@ pub struct Terminal {
     task: Option<TaskState>,
     vi_mode_enabled: bool,
     is_ssh_terminal: bool,
+    shell_integration: ShellIntegration,
+    inline_images: InlineImages,
     last_mouse_move_time: Instant,
     last_hyperlink_search_position: Option<Point<Pixels>>,
     #[cfg(windows)]
@@ -934,6 +999,7 @@ impl Terminal {
                 //NOOP, Handled in render
             }
             AlacTermEvent::Wakeup => {
+                self.shell_integration.sync(&*self.term.lock());
                 cx.emit(Event::Wakeup);
 
                 if let TerminalType::Pty { info, .. } = &mut self.terminal_type {
@@ -989,6 +1055,8 @@ impl Terminal {
             }
             InternalEvent::Clear => {
                 trace!("Clearing");
+                self.shell_integration.clear();
+                self.inline_images.clear();
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -1325,6 +1393,84 @@ impl Terminal {
         self.events.push_back(InternalEvent::Clear)
     }
 
+    /// Scrolls the prompt of the command before the selected one to the top, and selects it.
+    pub fn scroll_to_previous_prompt(&mut self) {
+        if let Some(line) = self.shell_integration.select_previous() {
+            self.scroll_line_to_top(line);
+        }
+    }
+
+    /// Scrolls the prompt of the command after the selected one to the top, and selects it.
+    /// Past the last command, scrolls back to the bottom.
+    pub fn scroll_to_next_prompt(&mut self) {
+        match self.shell_integration.select_next() {
+            Some(line) => self.scroll_line_to_top(line),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
+    fn scroll_line_to_top(&mut self, line: Line) {
+        let delta = -line.0 - self.last_content.display_offset as i32;
+        self.events
+            .push_back(InternalEvent::Scroll(AlacScroll::Delta(delta)));
+    }
+
+    /// The commands run in this terminal that are still in its scrollback, when the shell
+    /// integration is active.
+    pub fn command_blocks(&self) -> &[CommandBlock] {
+        self.shell_integration.blocks()
+    }
+
+    /// The selected command block, or else the last command that was run.
+    pub fn command_block(&self) -> Option<&CommandBlock> {
+        self.shell_integration.target_block()
+    }
+
+    fn command_output_bounds(&self) -> Option<RangeInclusive<AlacPoint>> {
+        let term = self.term.lock();
+        let lines = self
+            .shell_integration
+            .target_output_lines(term.grid().cursor.point.line)?;
+        let start = AlacPoint::new(lines.start.max(term.topmost_line()), Column(0));
+        let end = AlacPoint::new(Line(lines.end.0 - 1), term.last_column());
+        (start <= end).then_some(start..=end)
+    }
+
+    /// Selects the output of the selected command, or of the last one.
+    pub fn select_command_output(&mut self) {
+        if let Some(bounds) = self.command_output_bounds() {
+            let end = *bounds.end();
+            self.set_selection(Some((make_selection(&bounds), end)));
+        }
+    }
+
+    /// Copies the output of the selected command, or of the last one, to the clipboard.
+    pub fn copy_command_output(&mut self, cx: &mut Context<Self>) {
+        if let Some(bounds) = self.command_output_bounds() {
+            let output = self
+                .term
+                .lock()
+                .bounds_to_string(*bounds.start(), *bounds.end());
+            cx.write_to_clipboard(ClipboardItem::new_string(
+                output.trim_end_matches('\n').to_string(),
+            ));
+        }
+    }
+
+    /// Runs the selected command, or the last one, again, if the shell is at its prompt.
+    pub fn rerun_command(&mut self) {
+        if !self.shell_integration.is_at_prompt() {
+            return;
+        }
+        if let Some(command) = self
+            .shell_integration
+            .target_block()
+            .and_then(|block| block.command.clone())
+        {
+            self.input(format!("{command}\r").into_bytes());
+        }
+    }
+
     pub fn scroll_line_up(&mut self) {
         self.events
             .push_back(InternalEvent::Scroll(AlacScroll::Delta(1)));
@@ -1392,6 +1538,7 @@ impl Terminal {
         self.events
             .push_back(InternalEvent::Scroll(AlacScroll::Bottom));
         self.events.push_back(InternalEvent::SetSelection(None));
+        self.shell_integration.clear_selection();
 
         self.write_to_pty(input);
     }
@@ -1545,6 +1692,16 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+
+        let top_line = Line(-(self.last_content.display_offset as i32));
+        let visible_lines = top_line..Line(top_line.0 + terminal.screen_lines() as i32);
+        self.last_content.command_marks = self
+            .shell_integration
+            .marks(visible_lines, terminal.grid().cursor.point.line);
+        self.last_content.images = self.inline_images.place(&mut self.last_content.cells);
+        for image in self.inline_images.take_dropped() {
+            cx.drop_image(image, Some(window));
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1577,9 +1734,57 @@ impl Terminal {
             last_hovered_word: last_content.last_hovered_word.clone(),
             scrolled_to_top: content.display_offset == term.history_size(),
             scrolled_to_bottom: content.display_offset == 0,
+            command_marks: Vec::new(),
+            images: Vec::new(),
         }
     }
 
+    /// Encodes up to `max_lines` of the terminal's output with its colors and styles, to be
+    /// replayed with [`Terminal::restore_scrollback`]. Returns `None` while a full screen
+    /// application is running, as the output of the shell isn't on the screen then.
+    pub fn scrollback(&self, max_lines: usize) -> Option<String> {
+        let term = self.term.lock();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+        Some(scrollback::encode_scrollback(&term, max_lines))
+    }
+
+    /// Puts the scrollback of a previous session above the output of this one.
+    pub fn restore_scrollback(&mut self, scrollback: &str, cx: &mut Context<Self>) {
+        if scrollback.trim().is_empty() {
+            return;
+        }
+        {
+            let mut term = self.term.lock();
+            if term.mode().contains(TermMode::ALT_SCREEN) {
+                return;
+            }
+            // The shell may have printed its prompt already, move that below the restored
+            // scrollback, leaving the cursor where the shell expects it to be.
+            let cursor = term.grid().cursor.point;
+            let current_output = scrollback::encode_lines(
+                &term,
+                term.topmost_line(),
+                cursor.line,
+                Some(cursor.column),
+            );
+
+            let mut output = String::from("\x1b[0m\x1b[2J\x1b[3J\x1b[H");
+            output.push_str(scrollback);
+            output.push_str("\x1b[0m\r\n");
+            output.push_str(scrollback::RESTORED_SCROLLBACK_SEPARATOR);
+            output.push_str(&current_output);
+
+            let mut processor = alacritty_terminal::vte::ansi::Processor::<
+                alacritty_terminal::vte::ansi::StdSyncHandler,
+            >::new();
+            processor.advance(&mut *term, output.as_bytes());
+        }
+        self.shell_integration.clear();
+        cx.emit(Event::Wakeup);
+    }
+
     pub fn get_content(&self) -> String {
         let term = self.term.lock_unfair();
         let start = AlacPoint::new(term.topmost_line(), Column(0));
@@ -2821,4 +3026,46 @@ mod tests {
             text
         );
     }
+
+    #[gpui::test]
+    async fn test_restore_scrollback(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"\x1b[32mold\x1b[0m\r\noutput\r\n", cx);
+            let scrollback = terminal.scrollback(100).unwrap();
+            assert_eq!(scrollback, "\x1b[0;32mold\x1b[0m\r\noutput");
+
+            let restored = cx.new(|cx| {
+                TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                )
+                .unwrap()
+                .subscribe(cx)
+            });
+            restored.update(cx, |restored, cx| {
+                restored.write_output(b"$ ", cx);
+                restored.restore_scrollback(&scrollback, cx);
+
+                let lines = restored
+                    .get_content()
+                    .lines()
+                    .map(|line| line.trim_end().to_string())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>();
+                assert_eq!(lines, ["old", "output", "[Restored session]", "$"]);
+                let term = restored.term.lock();
+                let cursor = term.grid().cursor.point;
+                assert_eq!(cursor.column, Column(2));
+                assert_eq!(term.grid()[cursor.line][Column(0)].c, '$');
+            });
+        });
+    }
 }
@@ -8,6 +8,8 @@ use alacritty_terminal::{
 use regex::Regex;
 use std::{ops::Index, sync::LazyLock};
 
+use crate::inline_images;
+
 const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`']+"#;
 // Optional suffix matches MSBuild diagnostic suffixes for path parsing in PathLikeWithPosition
 // https://learn.microsoft.com/en-us/visualstudio/msbuild/msbuild-diagnostic-format-for-tasks
@@ -51,7 +53,10 @@ pub(super) fn find_from_grid_point<T: EventListener>(
     regex_searches: &mut RegexSearches,
 ) -> Option<(String, bool, Match)> {
     let grid = term.grid();
-    let link = grid.index(point).hyperlink();
+    let link = grid
+        .index(point)
+        .hyperlink()
+        .filter(|link| !inline_images::is_anchor(link.uri()));
     let found_word = if let Some(ref url) = link {
         let mut min_index = point;
         loop {
@@ -427,6 +427,9 @@ impl Domain for TerminalDb {
             ALTER TABLE terminals ADD COLUMN working_directory_path TEXT;
             UPDATE terminals SET working_directory_path = CAST(working_directory AS TEXT);
         ),
+        sql! (
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
+        ),
     ];
 }
 
@@ -484,4 +487,23 @@ impl TerminalDb {
             WHERE item_id = ? AND workspace_id = ?
         }
     }
+
+    query! {
+        pub async fn save_scrollback(item_id: ItemId, workspace_id: WorkspaceId, scrollback: String) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, scrollback)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT DO UPDATE SET
+                item_id = ?1,
+                workspace_id = ?2,
+                scrollback = ?3
+        }
+    }
+
+    query! {
+        pub fn get_scrollback(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT scrollback
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ?
+        }
+    }
 }
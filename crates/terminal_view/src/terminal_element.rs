@@ -1,19 +1,20 @@
 use editor::{CursorLayout, EditorSettings, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle, FontWeight,
-    GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity,
-    IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
-    Point, ShapedLine, StatefulInteractiveElement, StrikethroughStyle, Styled, TextRun, TextStyle,
-    UTF16Selection, UnderlineStyle, WeakEntity, WhiteSpace, Window, div, fill, point, px, relative,
-    size,
+    AbsoluteLength, AnyElement, App, AvailableSpace, Bounds, ContentMask, Context, Corners,
+    DispatchPhase, Element, ElementId, Entity, FocusHandle, Font, FontFeatures, FontStyle,
+    FontWeight, GlobalElementId, HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement,
+    Interactivity, IntoElement, LayoutId, Length, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, RenderImage, ShapedLine, StatefulInteractiveElement,
+    StrikethroughStyle, Styled, TextRun, TextStyle, UTF16Selection, UnderlineStyle, WeakEntity,
+    WhiteSpace, Window, div, fill, point, px, relative, size,
 };
 use itertools::Itertools;
 use language::CursorShape;
 use settings::Settings;
 use std::time::Instant;
 use terminal::{
-    IndexedCell, Terminal, TerminalBounds, TerminalContent,
+    CommandMark, CommandStatus, ImagePlacement, IndexedCell, Terminal, TerminalBounds,
+    TerminalContent,
     alacritty_terminal::{
         grid::Dimensions,
         index::Point as AlacPoint,
@@ -32,7 +33,7 @@ use util::ResultExt;
 use workspace::Workspace;
 
 use std::mem;
-use std::{fmt::Debug, ops::RangeInclusive, rc::Rc};
+use std::{fmt::Debug, ops::RangeInclusive, rc::Rc, sync::Arc};
 
 use crate::{BlockContext, BlockProperties, ContentMode, TerminalMode, TerminalView};
 
@@ -49,11 +50,118 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    command_marks: Vec<CommandMarkLayout>,
+    images: Vec<ImageLayout>,
     block_below_cursor_element: Option<AnyElement>,
     base_text_style: TextStyle,
     content_mode: ContentMode,
 }
 
+/// A shell integration marker in the gutter, next to the prompt of a command.
+struct CommandMarkLayout {
+    /// The display lines of the command's block.
+    lines: RangeInclusive<i32>,
+    color: Hsla,
+    selected: bool,
+}
+
+impl CommandMarkLayout {
+    fn new(mark: &CommandMark, display_offset: usize, theme: &Theme) -> Option<Self> {
+        let color = match mark.status {
+            // Nothing has been run from the prompt the shell is waiting at yet.
+            CommandStatus::Prompt => return None,
+            CommandStatus::Running => theme.status().info,
+            CommandStatus::Finished { exit_code: Some(0) } => theme.status().success,
+            CommandStatus::Finished { exit_code: Some(_) } => theme.status().error,
+            CommandStatus::Finished { exit_code: None } => theme.colors().text_muted,
+        };
+        let display_offset = display_offset as i32;
+        Some(Self {
+            lines: mark.line.0 + display_offset..=mark.end_line.0 + display_offset,
+            color,
+            selected: mark.selected,
+        })
+    }
+
+    fn paint(
+        &self,
+        origin: Point<Pixels>,
+        gutter: Pixels,
+        line_height: Pixels,
+        window: &mut Window,
+    ) {
+        let top = origin.y + line_height * *self.lines.start() as f32;
+        if self.selected {
+            let line_count = (self.lines.end() - self.lines.start() + 1) as f32;
+            window.paint_quad(fill(
+                Bounds::new(
+                    point(origin.x + gutter * 0.4, top),
+                    size(gutter * 0.2, line_height * line_count),
+                ),
+                self.color.opacity(0.5),
+            ));
+        }
+        window.paint_quad(
+            fill(
+                Bounds::new(
+                    point(origin.x + gutter * 0.25, top + line_height * 0.2),
+                    size(gutter * 0.5, line_height * 0.6),
+                ),
+                self.color,
+            )
+            .corner_radii(gutter * 0.25),
+        );
+    }
+}
+
+/// An inline image, on the cells it was placed over.
+struct ImageLayout {
+    image: Arc<RenderImage>,
+    /// The display line of the image's top row.
+    line: i32,
+    column: usize,
+    columns: usize,
+    rows: usize,
+}
+
+impl ImageLayout {
+    fn new(placement: &ImagePlacement, display_offset: usize) -> Self {
+        Self {
+            image: placement.image.clone(),
+            line: placement.point.line.0 + display_offset as i32,
+            column: placement.point.column.0,
+            columns: placement.columns,
+            rows: placement.rows,
+        }
+    }
+
+    fn paint(&self, origin: Point<Pixels>, dimensions: &TerminalBounds, window: &mut Window) {
+        let image_size = self.image.size(0);
+        let available = size(
+            dimensions.cell_width * self.columns as f32,
+            dimensions.line_height * self.rows as f32,
+        );
+        // The cell size may have changed since the image was placed, so it's fitted into its
+        // cells rather than stretched over them.
+        let scale = (available.width / px(image_size.width.0 as f32))
+            .min(available.height / px(image_size.height.0 as f32));
+        let bounds = Bounds::new(
+            origin
+                + point(
+                    dimensions.cell_width * self.column as f32,
+                    dimensions.line_height * self.line as f32,
+                ),
+            size(
+                px(image_size.width.0 as f32 * scale),
+                px(image_size.height.0 as f32 * scale),
+            ),
+        );
+        window
+            .paint_image(bounds, Corners::default(), self.image.clone(), 0, false)
+            .log_err();
+    }
+}
+
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
 struct DisplayCursor {
     line: i32,
@@ -1039,10 +1147,20 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_marks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
+                let command_marks = command_marks
+                    .iter()
+                    .filter_map(|mark| CommandMarkLayout::new(mark, display_offset, &theme))
+                    .collect();
+                let images = images
+                    .iter()
+                    .map(|image| ImageLayout::new(image, display_offset))
+                    .collect();
 
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
@@ -1189,6 +1307,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    command_marks,
+                    images,
                     block_below_cursor_element,
                     base_text_style: text_style,
                     content_mode,
@@ -1275,6 +1395,20 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout.dimensions, window);
                     }
 
+                    for image in &layout.images {
+                        image.paint(origin, &layout.dimensions, window);
+                    }
+
+                    let gutter_origin = origin - Point::new(layout.gutter, px(0.));
+                    for command_mark in &layout.command_marks {
+                        command_mark.paint(
+                            gutter_origin,
+                            layout.gutter,
+                            layout.dimensions.line_height,
+                            window,
+                        );
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -18,8 +18,9 @@ use project::{Project, search::SearchQuery};
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
-    Clear, Copy, Event, HoveredWord, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp,
-    ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskState,
+    Clear, Copy, CopyCommandOutput, Event, HoveredWord, MaybeNavigationTarget, Paste, RerunCommand,
+    ScrollLineDown, ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, SelectCommandOutput, ShowCharacterPalette, TaskState,
     TaskStatus, Terminal, TerminalBounds, ToggleViMode,
     alacritty_terminal::{
         index::Point,
@@ -60,7 +61,7 @@ use std::{
     path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 struct ImeState {
@@ -69,6 +70,8 @@ struct ImeState {
 }
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLLBACK_SERIALIZATION_DEBOUNCE: Duration = Duration::from_secs(5);
+const SCROLLBACK_SERIALIZATION_MAX_DELAY: Duration = Duration::from_secs(60);
 
 /// Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -131,6 +134,9 @@ pub struct TerminalView {
     mode: TerminalMode,
     blinking_terminal_enabled: bool,
     cwd_serialized: bool,
+    scrollback_serialized: bool,
+    scrollback_serialized_at: Instant,
+    _deferred_scrollback_serialization: Task<()>,
     blinking_paused: bool,
     blink_epoch: usize,
     hover: Option<HoverTarget>,
@@ -259,6 +265,9 @@ impl TerminalView {
             scroll_top: Pixels::ZERO,
             scroll_handle,
             cwd_serialized: false,
+            scrollback_serialized: false,
+            scrollback_serialized_at: Instant::now(),
+            _deferred_scrollback_serialization: Task::ready(()),
             ime_state: None,
             _subscriptions: vec![
                 focus_in,
@@ -380,6 +389,11 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let has_command_output = self
+            .terminal
+            .read(cx)
+            .command_block()
+            .is_some_and(|block| block.output_line.is_some());
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -388,6 +402,12 @@ impl TerminalView {
                 .action("Paste", Box::new(Paste))
                 .action("Select All", Box::new(SelectAll))
                 .action("Clear", Box::new(Clear))
+                .when(has_command_output, |menu| {
+                    menu.separator()
+                        .action("Select Command Output", Box::new(SelectCommandOutput))
+                        .action("Copy Command Output", Box::new(CopyCommandOutput))
+                        .action("Rerun Command", Box::new(RerunCommand))
+                })
                 .when(assistant_enabled, |menu| {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
@@ -469,6 +489,32 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn select_command_output(
+        &mut self,
+        _: &SelectCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.select_command_output());
+        cx.notify();
+    }
+
+    fn copy_command_output(
+        &mut self,
+        _: &CopyCommandOutput,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, cx| term.copy_command_output(cx));
+    }
+
+    fn rerun_command(&mut self, _: &RerunCommand, _: &mut Window, cx: &mut Context<Self>) {
+        self.terminal.update(cx, |term, _| term.rerun_command());
+        cx.notify();
+    }
+
     fn rerun_task(&mut self, _: &RerunTask, window: &mut Window, cx: &mut Context<Self>) {
         let task = self
             .terminal
@@ -600,6 +646,28 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(
+        &mut self,
+        _: &ScrollToNextPrompt,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
     fn toggle_vi_mode(&mut self, _: &ToggleViMode, _: &mut Window, cx: &mut Context<Self>) {
         self.terminal.update(cx, |term, _| term.toggle_vi_mode());
         cx.notify();
@@ -877,6 +945,11 @@ fn subscribe_for_terminal_events(
 
             match event {
                 Event::Wakeup => {
+                    if terminal.read(cx).task().is_none()
+                        && TerminalSettings::get_global(cx).max_persisted_scrollback_lines > 0
+                    {
+                        terminal_view.scrollback_serialized = false;
+                    }
                     cx.notify();
                     cx.emit(Event::Wakeup);
                     cx.emit(ItemEvent::UpdateTab);
@@ -1071,9 +1144,14 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
             .on_action(cx.listener(TerminalView::toggle_vi_mode))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::select_command_output))
+            .on_action(cx.listener(TerminalView::copy_command_output))
+            .on_action(cx.listener(TerminalView::rerun_command))
             .on_action(cx.listener(TerminalView::rerun_task))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
@@ -1312,6 +1390,56 @@ impl Item for TerminalView {
     }
 }
 
+impl TerminalView {
+    /// Takes the scrollback to save. Encoding the scrollback is costly, so unless `force` is set,
+    /// it's saved once the output has settled for `SCROLLBACK_SERIALIZATION_DEBOUNCE`, or right
+    /// away if the terminal hasn't stopped printing for `SCROLLBACK_SERIALIZATION_MAX_DELAY`.
+    fn scrollback_to_serialize(
+        &mut self,
+        item_id: workspace::ItemId,
+        workspace_id: WorkspaceId,
+        force: bool,
+        cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let max_lines = TerminalSettings::get_global(cx).max_persisted_scrollback_lines;
+        if max_lines == 0 {
+            self.scrollback_serialized = true;
+            return None;
+        }
+
+        if !force && self.scrollback_serialized_at.elapsed() < SCROLLBACK_SERIALIZATION_MAX_DELAY {
+            self._deferred_scrollback_serialization = cx.spawn(async move |this, cx| {
+                cx.background_executor()
+                    .timer(SCROLLBACK_SERIALIZATION_DEBOUNCE)
+                    .await;
+                let scrollback = this
+                    .update(cx, |this, cx| {
+                        if this.scrollback_serialized {
+                            None
+                        } else {
+                            this.scrollback_to_serialize(item_id, workspace_id, true, cx)
+                        }
+                    })
+                    .ok()
+                    .flatten();
+                if let Some(scrollback) = scrollback {
+                    TERMINAL_DB
+                        .save_scrollback(item_id, workspace_id, scrollback)
+                        .await
+                        .log_err();
+                }
+            });
+            return None;
+        }
+
+        // Full screen applications hide the scrollback, it's saved once they exit.
+        let scrollback = self.terminal.read(cx).scrollback(max_lines)?;
+        self.scrollback_serialized = true;
+        self.scrollback_serialized_at = Instant::now();
+        Some(scrollback)
+    }
+}
+
 impl SerializableItem for TerminalView {
     fn serialized_item_kind() -> &'static str {
         "Terminal"
@@ -1330,29 +1458,49 @@ impl SerializableItem for TerminalView {
         &mut self,
         _workspace: &mut Workspace,
         item_id: workspace::ItemId,
-        _closing: bool,
+        closing: bool,
         _: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Task<anyhow::Result<()>>> {
-        let terminal = self.terminal().read(cx);
-        if terminal.task().is_some() {
+        if self.terminal().read(cx).task().is_some() {
             return None;
         }
+        let workspace_id = self.workspace_id?;
 
-        if let Some((cwd, workspace_id)) = terminal.working_directory().zip(self.workspace_id) {
-            self.cwd_serialized = true;
-            Some(cx.background_spawn(async move {
-                TERMINAL_DB
-                    .save_working_directory(item_id, workspace_id, cwd)
-                    .await
-            }))
+        let cwd = if self.cwd_serialized {
+            None
         } else {
+            self.terminal().read(cx).working_directory()
+        };
+        if cwd.is_some() {
+            self.cwd_serialized = true;
+        }
+        let scrollback = if self.scrollback_serialized {
             None
+        } else {
+            self.scrollback_to_serialize(item_id, workspace_id, closing, cx)
+        };
+        if cwd.is_none() && scrollback.is_none() {
+            return None;
         }
+
+        Some(cx.background_spawn(async move {
+            if let Some(cwd) = cwd {
+                TERMINAL_DB
+                    .save_working_directory(item_id, workspace_id, cwd)
+                    .await?;
+            }
+            if let Some(scrollback) = scrollback {
+                TERMINAL_DB
+                    .save_scrollback(item_id, workspace_id, scrollback)
+                    .await?;
+            }
+            Ok(())
+        }))
     }
 
     fn should_serialize(&self, _: &Self::Event) -> bool {
-        !self.cwd_serialized
+        !self.cwd_serialized || !self.scrollback_serialized
     }
 
     fn deserialize(
@@ -1364,6 +1512,18 @@ impl SerializableItem for TerminalView {
         cx: &mut App,
     ) -> Task<anyhow::Result<Entity<Self>>> {
         window.spawn(cx, async move |cx| {
+            let scrollback = cx
+                .update(|_window, cx| {
+                    if TerminalSettings::get_global(cx).max_persisted_scrollback_lines == 0 {
+                        return None;
+                    }
+                    TERMINAL_DB
+                        .get_scrollback(item_id, workspace_id)
+                        .log_err()
+                        .flatten()
+                })
+                .ok()
+                .flatten();
             let cwd = cx
                 .update(|_window, cx| {
                     let from_db = TERMINAL_DB
@@ -1387,6 +1547,11 @@ impl SerializableItem for TerminalView {
             let terminal = project
                 .update(cx, |project, cx| project.create_terminal_shell(cwd, cx))?
                 .await?;
+            if let Some(scrollback) = scrollback {
+                terminal.update(cx, |terminal, cx| {
+                    terminal.restore_scrollback(&scrollback, cx)
+                })?;
+            }
             cx.update(|window, cx| {
                 cx.new(|cx| {
                     TerminalView::new(
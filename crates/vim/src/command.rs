@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
 use command_palette_hooks::{CommandInterceptItem, CommandInterceptResult};
 use editor::{
@@ -8,15 +8,16 @@ use editor::{
 };
 use futures::AsyncWriteExt as _;
 use gpui::{
-    Action, App, AppContext as _, Context, Global, Keystroke, Task, WeakEntity, Window, actions,
+    Action, App, AppContext as _, Context, Entity, Global, Keystroke, Task, WeakEntity, Window,
+    actions,
 };
 use itertools::Itertools;
-use language::Point;
+use language::{Buffer, Point};
 use multi_buffer::MultiBufferRow;
 use project::ProjectPath;
 use regex::Regex;
 use schemars::JsonSchema;
-use search::{BufferSearchBar, SearchOptions};
+use search::{BufferSearchBar, ProjectSearchView, SearchOptions};
 use serde::Deserialize;
 use settings::{Settings, SettingsStore};
 use std::{
@@ -35,12 +36,13 @@ use util::{
     paths::PathStyle,
     rel_path::{RelPath, RelPathBuf},
 };
-use workspace::{Item, SaveIntent, Workspace, notifications::NotifyResultExt};
+use workspace::{Item, ItemHandle, Pane, SaveIntent, Workspace, notifications::NotifyResultExt};
 use workspace::{SplitDirection, notifications::DetachAndPromptErr};
 use zed_actions::{OpenDocs, RevealTarget};
 
 use crate::{
-    ToggleMarksView, ToggleRegistersView, Vim,
+    ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings,
+    helix::HelixPipe,
     motion::{EndOfDocument, Motion, MotionKind, StartOfDocument},
     normal::{
         JoinLines,
@@ -48,6 +50,7 @@ use crate::{
     },
     object::Object,
     state::{Mark, Mode},
+    vimrc,
     visual::VisualDeleteLine,
 };
 
@@ -82,12 +85,15 @@ pub struct WithCount {
     action: WrappedAction,
 }
 
-#[derive(Clone, Deserialize, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 pub enum VimOption {
     Wrap(bool),
     Number(bool),
     RelativeNumber(bool),
     IgnoreCase(bool),
+    SmartCase(bool),
+    ScrollOff(u32),
+    Clipboard(UseSystemClipboard),
 }
 
 impl VimOption {
@@ -109,10 +115,7 @@ impl VimOption {
                 options.push(possible);
 
                 CommandInterceptItem {
-                    string: format!(
-                        ":set {}",
-                        options.iter().map(|opt| opt.to_string()).join(" ")
-                    ),
+                    string: format!(":set {}", options.iter().join(" ")),
                     action: VimSet { options }.boxed_clone(),
                     positions: vec![],
                 }
@@ -134,13 +137,37 @@ impl VimOption {
             (None, VimOption::IgnoreCase(false)),
             (Some("ic"), VimOption::IgnoreCase(true)),
             (Some("noic"), VimOption::IgnoreCase(false)),
+            (None, VimOption::SmartCase(true)),
+            (None, VimOption::SmartCase(false)),
+            (Some("scs"), VimOption::SmartCase(true)),
+            (Some("noscs"), VimOption::SmartCase(false)),
         ]
         .into_iter()
-        .filter(move |(prefix, option)| prefix.unwrap_or(option.to_string()).starts_with(query))
+        .filter(move |(prefix, option)| {
+            prefix
+                .map(str::to_string)
+                .unwrap_or_else(|| option.to_string())
+                .starts_with(query)
+        })
         .map(|(_, option)| option)
     }
 
-    fn from(option: &str) -> Option<Self> {
+    pub(crate) fn from(option: &str) -> Option<Self> {
+        if let Some((name, value)) = option.split_once('=') {
+            return match name {
+                "scrolloff" | "so" => value.parse().ok().map(Self::ScrollOff),
+                // `unnamed` and `unnamedplus` make yanks and puts use the system clipboard.
+                "clipboard" | "cb" => Some(Self::Clipboard(
+                    if value.split(',').any(|value| value.starts_with("unnamed")) {
+                        UseSystemClipboard::Always
+                    } else {
+                        UseSystemClipboard::Never
+                    },
+                )),
+                _ => None,
+            };
+        }
+
         match option {
             "wrap" => Some(Self::Wrap(true)),
             "nowrap" => Some(Self::Wrap(false)),
@@ -160,20 +187,79 @@ impl VimOption {
             "noignorecase" => Some(Self::IgnoreCase(false)),
             "noic" => Some(Self::IgnoreCase(false)),
 
+            "smartcase" => Some(Self::SmartCase(true)),
+            "scs" => Some(Self::SmartCase(true)),
+            "nosmartcase" => Some(Self::SmartCase(false)),
+            "noscs" => Some(Self::SmartCase(false)),
+
             _ => None,
         }
     }
+}
 
-    fn to_string(&self) -> &'static str {
+impl std::fmt::Display for VimOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            VimOption::Wrap(true) => "wrap",
-            VimOption::Wrap(false) => "nowrap",
-            VimOption::Number(true) => "number",
-            VimOption::Number(false) => "nonumber",
-            VimOption::RelativeNumber(true) => "relativenumber",
-            VimOption::RelativeNumber(false) => "norelativenumber",
-            VimOption::IgnoreCase(true) => "ignorecase",
-            VimOption::IgnoreCase(false) => "noignorecase",
+            VimOption::Wrap(true) => write!(f, "wrap"),
+            VimOption::Wrap(false) => write!(f, "nowrap"),
+            VimOption::Number(true) => write!(f, "number"),
+            VimOption::Number(false) => write!(f, "nonumber"),
+            VimOption::RelativeNumber(true) => write!(f, "relativenumber"),
+            VimOption::RelativeNumber(false) => write!(f, "norelativenumber"),
+            VimOption::IgnoreCase(true) => write!(f, "ignorecase"),
+            VimOption::IgnoreCase(false) => write!(f, "noignorecase"),
+            VimOption::SmartCase(true) => write!(f, "smartcase"),
+            VimOption::SmartCase(false) => write!(f, "nosmartcase"),
+            VimOption::ScrollOff(lines) => write!(f, "scrolloff={lines}"),
+            VimOption::Clipboard(UseSystemClipboard::Never) => write!(f, "clipboard="),
+            VimOption::Clipboard(_) => write!(f, "clipboard=unnamedplus"),
+        }
+    }
+}
+
+/// Applies an option set with `:set` or in the vimrc to the editor, or globally for the options
+/// that Zed only has as settings.
+pub(crate) fn set_option(option: &VimOption, editor: &mut Editor, cx: &mut Context<Editor>) {
+    match option {
+        VimOption::Wrap(true) => {
+            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
+        }
+        VimOption::Wrap(false) => {
+            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
+        }
+        VimOption::Number(enabled) => {
+            editor.set_show_line_numbers(*enabled, cx);
+        }
+        VimOption::RelativeNumber(enabled) => {
+            editor.set_relative_line_number(Some(*enabled), cx);
+        }
+        VimOption::IgnoreCase(enabled) => {
+            let mut settings = EditorSettings::get_global(cx).clone();
+            settings.search.case_sensitive = !*enabled;
+            SettingsStore::update(cx, |store, _| {
+                store.override_global(settings);
+            });
+        }
+        VimOption::SmartCase(enabled) => {
+            let mut settings = EditorSettings::get_global(cx).clone();
+            settings.use_smartcase_search = *enabled;
+            SettingsStore::update(cx, |store, _| {
+                store.override_global(settings);
+            });
+        }
+        VimOption::ScrollOff(lines) => {
+            let mut settings = EditorSettings::get_global(cx).clone();
+            settings.vertical_scroll_margin = *lines as f64;
+            SettingsStore::update(cx, |store, _| {
+                store.override_global(settings);
+            });
+        }
+        VimOption::Clipboard(use_system_clipboard) => {
+            let mut settings = VimSettings::get_global(cx).clone();
+            settings.use_system_clipboard = *use_system_clipboard;
+            SettingsStore::update(cx, |store, _| {
+                store.override_global(settings);
+            });
         }
     }
 }
@@ -229,6 +315,21 @@ struct VimEdit {
     pub filename: String,
 }
 
+/// Loads the mappings and options of a vimrc file.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct SourceVimrc {
+    pub path: Option<String>,
+}
+
+/// Adds a key mapping, like a mapping command in a vimrc.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct VimMap {
+    pub command: String,
+    pub args: String,
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = vim, no_json, no_register)]
 struct VimNorm {
@@ -236,6 +337,80 @@ struct VimNorm {
     pub command: String,
 }
 
+impl VimNorm {
+    fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) -> Task<()> {
+        let keystrokes = self
+            .command
+            .chars()
+            .map(|c| Keystroke::parse(&c.to_string()).unwrap())
+            .collect();
+        vim.switch_mode(Mode::Normal, true, window, cx);
+        let initial_selections =
+            vim.update_editor(cx, |_, editor, _| editor.selections.disjoint_anchors_arc());
+        if let Some(range) = &self.range {
+            let result = vim.update_editor(cx, |vim, editor, cx| {
+                let range = range.buffer_range(vim, editor, window, cx)?;
+                editor.change_selections(
+                    SelectionEffects::no_scroll().nav_history(false),
+                    window,
+                    cx,
+                    |s| {
+                        s.select_ranges(
+                            (range.start.0..=range.end.0)
+                                .map(|line| Point::new(line, 0)..Point::new(line, 0)),
+                        );
+                    },
+                );
+                anyhow::Ok(())
+            });
+            if let Some(Err(err)) = result {
+                log::error!("Error selecting range: {}", err);
+                return Task::ready(());
+            }
+        };
+
+        let Some(workspace) = vim.workspace(window) else {
+            return Task::ready(());
+        };
+        let task = workspace.update(cx, |workspace, cx| {
+            workspace.send_keystrokes_impl(keystrokes, window, cx)
+        });
+        let had_range = self.range.is_some();
+
+        cx.spawn_in(window, async move |vim, cx| {
+            task.await;
+            vim.update_in(cx, |vim, window, cx| {
+                vim.update_editor(cx, |_, editor, cx| {
+                    if had_range {
+                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                            s.select_anchor_ranges([s.newest_anchor().range()]);
+                        })
+                    }
+                });
+                if matches!(vim.mode, Mode::Insert | Mode::Replace) {
+                    vim.normal_before(&Default::default(), window, cx);
+                } else {
+                    vim.switch_mode(Mode::Normal, true, window, cx);
+                }
+                vim.update_editor(cx, |_, editor, cx| {
+                    if let Some(first_sel) = initial_selections
+                        && let Some(tx_id) = editor
+                            .buffer()
+                            .update(cx, |multi, cx| multi.last_transaction_id(cx))
+                    {
+                        let last_sel = editor.selections.disjoint_anchors_arc();
+                        editor.modify_transaction_selection_history(tx_id, |old| {
+                            old.0 = first_sel;
+                            old.1 = Some(last_sel);
+                        });
+                    }
+                });
+            })
+            .ok();
+        })
+    }
+}
+
 #[derive(Debug)]
 struct WrappedAction(Box<dyn Action>);
 
@@ -262,30 +437,46 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     // Vim::action(editor, cx, |vim, action: &StartOfLine, window, cx| {
     Vim::action(editor, cx, |vim, action: &VimSet, _, cx| {
         for option in action.options.iter() {
-            vim.update_editor(cx, |_, editor, cx| match option {
-                VimOption::Wrap(true) => {
-                    editor
-                        .set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-                }
-                VimOption::Wrap(false) => {
-                    editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
-                }
-                VimOption::Number(enabled) => {
-                    editor.set_show_line_numbers(*enabled, cx);
-                }
-                VimOption::RelativeNumber(enabled) => {
-                    editor.set_relative_line_number(Some(*enabled), cx);
-                }
-                VimOption::IgnoreCase(enabled) => {
-                    let mut settings = EditorSettings::get_global(cx).clone();
-                    settings.search.case_sensitive = !*enabled;
-                    SettingsStore::update(cx, |store, _| {
-                        store.override_global(settings);
-                    });
-                }
+            vim.update_editor(cx, |_, editor, cx| set_option(option, editor, cx));
+        }
+    });
+    Vim::action(editor, cx, |vim, action: &SourceVimrc, window, cx| {
+        let path = if let Some(path) = &action.path {
+            let project_dir = vim.workspace(window).and_then(|workspace| {
+                let project = workspace.read(cx).project().read(cx);
+                let worktree = project.visible_worktrees(cx).next()?;
+                Some(worktree.read(cx).abs_path())
             });
+            vimrc::expand_path(path, project_dir.as_deref())
+        } else if let Some(path) = VimSettings::get_global(cx).vimrc.clone() {
+            vimrc::expand_path(&path, None)
+        } else {
+            Task::ready(Err::<(), _>(anyhow!(
+                "No vimrc is configured, set `vim.vimrc` in your settings"
+            )))
+            .detach_and_prompt_err("Failed to :source", window, cx, |_, _, _| None);
+            return;
+        };
+        vimrc::source_file(path, cx).detach_and_prompt_err(
+            "Failed to :source",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    });
+
+    Vim::action(editor, cx, |_, action: &VimMap, window, cx| {
+        let line = format!("{} {}", action.command, action.args);
+        if let Err(error) = vimrc::source_line(&line, cx) {
+            Task::ready(Err::<(), _>(error)).detach_and_prompt_err(
+                &format!("Failed to :{}", action.command),
+                window,
+                cx,
+                |_, _, _| None,
+            );
         }
     });
+
     Vim::action(editor, cx, |vim, _: &VisualCommand, window, cx| {
         let Some(workspace) = vim.workspace(window) else {
             return;
@@ -513,76 +704,7 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, action: &VimNorm, window, cx| {
-        let keystrokes = action
-            .command
-            .chars()
-            .map(|c| Keystroke::parse(&c.to_string()).unwrap())
-            .collect();
-        vim.switch_mode(Mode::Normal, true, window, cx);
-        let initial_selections =
-            vim.update_editor(cx, |_, editor, _| editor.selections.disjoint_anchors_arc());
-        if let Some(range) = &action.range {
-            let result = vim.update_editor(cx, |vim, editor, cx| {
-                let range = range.buffer_range(vim, editor, window, cx)?;
-                editor.change_selections(
-                    SelectionEffects::no_scroll().nav_history(false),
-                    window,
-                    cx,
-                    |s| {
-                        s.select_ranges(
-                            (range.start.0..=range.end.0)
-                                .map(|line| Point::new(line, 0)..Point::new(line, 0)),
-                        );
-                    },
-                );
-                anyhow::Ok(())
-            });
-            if let Some(Err(err)) = result {
-                log::error!("Error selecting range: {}", err);
-                return;
-            }
-        };
-
-        let Some(workspace) = vim.workspace(window) else {
-            return;
-        };
-        let task = workspace.update(cx, |workspace, cx| {
-            workspace.send_keystrokes_impl(keystrokes, window, cx)
-        });
-        let had_range = action.range.is_some();
-
-        cx.spawn_in(window, async move |vim, cx| {
-            task.await;
-            vim.update_in(cx, |vim, window, cx| {
-                vim.update_editor(cx, |_, editor, cx| {
-                    if had_range {
-                        editor.change_selections(SelectionEffects::default(), window, cx, |s| {
-                            s.select_anchor_ranges([s.newest_anchor().range()]);
-                        })
-                    }
-                });
-                if matches!(vim.mode, Mode::Insert | Mode::Replace) {
-                    vim.normal_before(&Default::default(), window, cx);
-                } else {
-                    vim.switch_mode(Mode::Normal, true, window, cx);
-                }
-                vim.update_editor(cx, |_, editor, cx| {
-                    if let Some(first_sel) = initial_selections
-                        && let Some(tx_id) = editor
-                            .buffer()
-                            .update(cx, |multi, cx| multi.last_transaction_id(cx))
-                    {
-                        let last_sel = editor.selections.disjoint_anchors_arc();
-                        editor.modify_transaction_selection_history(tx_id, |old| {
-                            old.0 = first_sel;
-                            old.1 = Some(last_sel);
-                        });
-                    }
-                });
-            })
-            .ok();
-        })
-        .detach();
+        action.run(vim, window, cx).detach()
     });
 
     Vim::action(editor, cx, |vim, _: &CountCommand, window, cx| {
@@ -706,6 +828,14 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 
     Vim::action(editor, cx, |vim, action: &OnMatchingLines, window, cx| {
+        action.run(vim, window, cx).detach()
+    });
+
+    Vim::action(editor, cx, |vim, action: &VimDo, window, cx| {
+        action.run(vim, window, cx)
+    });
+
+    Vim::action(editor, cx, |vim, action: &VimRead, window, cx| {
         action.run(vim, window, cx)
     });
 
@@ -1421,6 +1551,43 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
             .bang(DeleteMarks::AllLocal)
             .args(|_, args| Some(DeleteMarks::Marks(args).boxed_clone())),
         VimCommand::new(("sor", "t"), SortLinesCaseSensitive).range(select_range),
+        VimCommand::new(("r", "ead"), ArgumentRequired)
+            .filename(|_, filename| {
+                Some(
+                    VimRead {
+                        range: None,
+                        filename,
+                    }
+                    .boxed_clone(),
+                )
+            })
+            .range(|action, range| {
+                let mut action = action.as_any().downcast_ref::<VimRead>()?.clone();
+                action.range = Some(range.clone());
+                Some(action.boxed_clone())
+            }),
+        do_command(("bufd", "o"), DoTargets::Buffers),
+        do_command(("windo", ""), DoTargets::Panes),
+        do_command(("cdo", ""), DoTargets::Entries),
+        do_command(("cfd", "o"), DoTargets::Files),
+        VimCommand::new(("pipe", ""), ArgumentRequired).args(|_, command| {
+            Some(
+                HelixPipe {
+                    command,
+                    replace: true,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(("insert-output", ""), ArgumentRequired).args(|_, command| {
+            Some(
+                HelixPipe {
+                    command,
+                    replace: false,
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(("sort i", ""), SortLinesCaseInsensitive).range(select_range),
         VimCommand::str(("E", "xplore"), "project_panel::ToggleFocus"),
         VimCommand::str(("H", "explore"), "project_panel::ToggleFocus"),
@@ -1441,11 +1608,58 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("ex", ""), editor::actions::ReloadFile).bang(editor::actions::ReloadFile),
         VimCommand::new(("cpp", "link"), editor::actions::CopyPermalinkToLine).range(act_on_range),
         VimCommand::str(("opt", "ions"), "zed::OpenDefaultSettings"),
-        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap"),
+        VimCommand::str(("map", ""), "vim::OpenDefaultKeymap")
+            .bang(VimMap {
+                command: "map!".into(),
+                args: "".into(),
+            })
+            .args(map_args),
+        map_command(("no", "remap"), "noremap").bang(VimMap {
+            command: "noremap!".into(),
+            args: "".into(),
+        }),
+        map_command(("nm", "ap"), "nmap"),
+        map_command(("nn", "oremap"), "nnoremap"),
+        map_command(("vm", "ap"), "vmap"),
+        map_command(("vn", "oremap"), "vnoremap"),
+        map_command(("xm", "ap"), "xmap"),
+        map_command(("xn", "oremap"), "xnoremap"),
+        map_command(("om", "ap"), "omap"),
+        map_command(("ono", "remap"), "onoremap"),
+        map_command(("im", "ap"), "imap"),
+        map_command(("ino", "remap"), "inoremap"),
+        VimCommand::new(("so", "urce"), SourceVimrc { path: None })
+            .filename(|_, path| Some(SourceVimrc { path: Some(path) }.boxed_clone())),
         VimCommand::new(("h", "elp"), OpenDocs),
     ]
 }
 
+/// A command that runs the ex command given as its argument on each of the targets.
+fn do_command(pattern: (&'static str, &'static str), targets: DoTargets) -> VimCommand {
+    VimCommand::new(pattern, ArgumentRequired)
+        .args(move |_, command| Some(VimDo { targets, command }.boxed_clone()))
+}
+
+/// A mapping command, such as `:nnoremap`, which requires the keys and what they are mapped to.
+fn map_command(pattern: (&'static str, &'static str), command: &str) -> VimCommand {
+    VimCommand::new(
+        pattern,
+        VimMap {
+            command: command.into(),
+            args: "".into(),
+        },
+    )
+    .args(map_args)
+}
+
+fn map_args(action: Box<dyn Action>, args: String) -> Option<Box<dyn Action>> {
+    let command = action
+        .as_any()
+        .downcast_ref::<VimMap>()
+        .map_or("map".into(), |action| action.command.clone());
+    Some(VimMap { command, args }.boxed_clone())
+}
+
 struct VimCommands(Vec<VimCommand>);
 // safety: we only ever access this from the main thread (as ensured by the cx argument)
 // actions are not Sync so we can't otherwise use a OnceLock.
@@ -1790,21 +2004,21 @@ impl OnMatchingLines {
         Some((query_chars.collect::<String>(), range, search, invert))
     }
 
-    pub fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) {
+    pub fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) -> Task<()> {
         let result = vim.update_editor(cx, |vim, editor, cx| {
             self.range.buffer_range(vim, editor, window, cx)
         });
 
         let range = match result {
-            None => return,
+            None => return Task::ready(()),
             Some(e @ Err(_)) => {
                 let Some(workspace) = vim.workspace(window) else {
-                    return;
+                    return Task::ready(());
                 };
                 workspace.update(cx, |workspace, cx| {
                     e.notify_err(workspace, cx);
                 });
-                return;
+                return Task::ready(());
             }
             Some(Ok(result)) => result,
         };
@@ -1816,12 +2030,12 @@ impl OnMatchingLines {
             Ok(regex) => vec![(regex, !self.invert)],
             e @ Err(_) => {
                 let Some(workspace) = vim.workspace(window) else {
-                    return;
+                    return Task::ready(());
                 };
                 workspace.update(cx, |workspace, cx| {
                     e.notify_err(workspace, cx);
                 });
-                return;
+                return Task::ready(());
             }
         };
         while let Some(inner) = action
@@ -1921,8 +2135,358 @@ impl OnMatchingLines {
                     })
                     .ok();
             })
-            .detach();
+        })
+        .unwrap_or_else(|| Task::ready(()))
+    }
+}
+
+/// Runs an ex command in each buffer, pane or search result, like `:bufdo`, `:windo`, `:cdo`
+/// and `:cfdo`.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimDo {
+    targets: DoTargets,
+    command: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DoTargets {
+    /// Each open buffer.
+    Buffers,
+    /// The active item of each pane.
+    Panes,
+    /// Each project search match or diagnostic.
+    Entries,
+    /// Each file with project search matches or diagnostics.
+    Files,
+}
+
+enum DoTarget {
+    Item(Box<dyn ItemHandle>),
+    Pane(WeakEntity<Pane>),
+    Entry(Entity<Buffer>, language::Anchor),
+}
+
+impl VimDo {
+    pub fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        let targets = self.targets.resolve(workspace.read(cx), cx);
+        let command = command_interceptor(&self.command, workspace.downgrade(), cx);
+        let query = self.command.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let targets = targets?;
+            let Some(item) = command.await.results.into_iter().next() else {
+                anyhow::bail!("Not an editor command: {query}");
+            };
+            for target in targets {
+                let editor = cx.update(|window, cx| {
+                    workspace.update(cx, |workspace, cx| target.activate(workspace, window, cx))
+                })?;
+                let Some(vim) = editor.and_then(|editor| {
+                    cx.update(|_, cx| {
+                        editor
+                            .read(cx)
+                            .addon::<VimAddon>()
+                            .map(|addon| addon.entity.clone())
+                    })
+                    .ok()
+                    .flatten()
+                }) else {
+                    continue;
+                };
+                // Like in `Workspace::send_keystrokes_impl`, draw so that the action is dispatched
+                // to the newly focused editor.
+                let task = cx.update(|window, cx| {
+                    window.draw(cx).clear();
+                    vim.update(cx, |vim, cx| {
+                        vim.run_ex_command(item.action.boxed_clone(), window, cx)
+                    })
+                })?;
+                task.await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to run command", window, cx, |_, _, _| None);
+    }
+}
+
+impl DoTargets {
+    fn resolve(self, workspace: &Workspace, cx: &App) -> Result<Vec<DoTarget>> {
+        match self {
+            DoTargets::Buffers => {
+                let mut buffers = HashSet::default();
+                Ok(workspace
+                    .panes()
+                    .iter()
+                    .flat_map(|pane| pane.read(cx).items())
+                    .filter(|item| {
+                        item.act_as::<Editor>(cx)
+                            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+                            .is_some_and(|buffer| buffers.insert(buffer.entity_id()))
+                    })
+                    .map(|item| DoTarget::Item(item.boxed_clone()))
+                    .collect())
+            }
+            DoTargets::Panes => Ok(workspace
+                .panes()
+                .iter()
+                .filter(|pane| {
+                    pane.read(cx)
+                        .active_item()
+                        .is_some_and(|item| item.act_as::<Editor>(cx).is_some())
+                })
+                .map(|pane| DoTarget::Pane(pane.downgrade()))
+                .collect()),
+            DoTargets::Entries | DoTargets::Files => {
+                let entries = Self::quickfix_entries(workspace, cx)
+                    .context("No project search results or diagnostics are open")?;
+                let mut buffers = HashSet::default();
+                Ok(entries
+                    .into_iter()
+                    .filter(|(buffer, _)| {
+                        self == DoTargets::Entries || buffers.insert(buffer.entity_id())
+                    })
+                    .map(|(buffer, anchor)| DoTarget::Entry(buffer, anchor))
+                    .collect())
+            }
+        }
+    }
+
+    /// The equivalent of Vim's quickfix list: the matches of the project search, or the
+    /// diagnostics or other results shown in a multibuffer. The active item is preferred.
+    fn quickfix_entries(
+        workspace: &Workspace,
+        cx: &App,
+    ) -> Option<Vec<(Entity<Buffer>, language::Anchor)>> {
+        let active_item = workspace.active_item(cx);
+        let items = workspace
+            .panes()
+            .iter()
+            .flat_map(|pane| pane.read(cx).items())
+            .map(|item| item.boxed_clone());
+        active_item.into_iter().chain(items).find_map(|item| {
+            let editor = item.act_as::<Editor>(cx)?;
+            let multi_buffer = editor.read(cx).buffer().read(cx);
+            if multi_buffer.is_singleton() {
+                return None;
+            }
+            let entries = if let Some(search) = item.downcast::<ProjectSearchView>() {
+                search
+                    .read(cx)
+                    .get_matches(cx)
+                    .into_iter()
+                    .filter_map(|range| {
+                        let buffer = multi_buffer.buffer_for_anchor(range.start, cx)?;
+                        Some((buffer, range.start.text_anchor))
+                    })
+                    .collect()
+            } else {
+                multi_buffer
+                    .snapshot(cx)
+                    .excerpts()
+                    .filter_map(|(_, buffer, range)| {
+                        let buffer = multi_buffer.buffer(buffer.remote_id())?;
+                        Some((buffer, range.primary.start))
+                    })
+                    .collect()
+            };
+            Some(entries)
+        })
+    }
+}
+
+impl DoTarget {
+    /// Shows the target in the workspace and focuses it, returning its editor.
+    fn activate(
+        self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Option<Entity<Editor>> {
+        match self {
+            DoTarget::Item(item) => {
+                if !workspace.activate_item(item.as_ref(), true, true, window, cx) {
+                    return None;
+                }
+                item.act_as::<Editor>(cx)
+            }
+            DoTarget::Pane(pane) => {
+                let pane = pane.upgrade()?;
+                pane.update(cx, |pane, cx| pane.focus_active_item(window, cx));
+                pane.read(cx).active_item()?.act_as::<Editor>(cx)
+            }
+            DoTarget::Entry(buffer, anchor) => {
+                let point = buffer.read(cx).summary_for_anchor::<Point>(&anchor);
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Default::default(), window, cx, |s| {
+                        s.select_ranges([point..point])
+                    })
+                });
+                Some(editor)
+            }
+        }
+    }
+}
+
+impl Vim {
+    /// Runs an action parsed from an ex command, returning a task that completes when the
+    /// commands that finish asynchronously, such as `:s`, are done.
+    fn run_ex_command(
+        &mut self,
+        action: Box<dyn Action>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if let Some(action) = action.as_any().downcast_ref::<ReplaceCommand>() {
+            self.replace_command(action, window, cx)
+        } else if let Some(action) = action.as_any().downcast_ref::<VimNorm>() {
+            let task = action.run(self, window, cx);
+            cx.spawn(async move |_, _| {
+                task.await;
+                Ok(())
+            })
+        } else if let Some(action) = action.as_any().downcast_ref::<OnMatchingLines>() {
+            let task = action.run(self, window, cx);
+            cx.spawn(async move |_, _| {
+                task.await;
+                Ok(())
+            })
+        } else {
+            window.dispatch_action(action, cx);
+            Task::ready(Ok(()))
+        }
+    }
+}
+
+/// Inserts the contents of a file below the cursor, or below the given line.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub(crate) struct VimRead {
+    range: Option<CommandRange>,
+    filename: String,
+}
+
+impl VimRead {
+    pub fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) {
+        let Some(workspace) = vim.workspace(window) else {
+            return;
+        };
+        let project = workspace.read(cx).project().clone();
+        // Relative paths are resolved against the worktree of the edited buffer, which may not be
+        // the first one of the project.
+        let buffer_worktree_id = vim
+            .update_editor(cx, |_, editor, cx| {
+                let (_, buffer, _) = editor.active_excerpt(cx)?;
+                Some(buffer.read(cx).file()?.worktree_id(cx))
+            })
+            .flatten();
+        let project_dir = {
+            let project = project.read(cx);
+            buffer_worktree_id
+                .and_then(|worktree_id| project.worktree_for_id(worktree_id, cx))
+                .or_else(|| project.visible_worktrees(cx).next())
+                .and_then(|worktree| {
+                    let worktree = worktree.read(cx);
+                    let abs_path = worktree.abs_path();
+                    if worktree.root_entry().is_some_and(|entry| entry.is_file()) {
+                        abs_path.parent().map(Path::to_path_buf)
+                    } else {
+                        Some(abs_path.to_path_buf())
+                    }
+                })
+        };
+        let path = vimrc::expand_path(&self.filename, project_dir.as_deref());
+        // Read the file through the project, so that it comes from the remote host in remote
+        // projects.
+        let worktree = project.update(cx, |project, cx| {
+            project.find_or_create_worktree(&path, false, cx)
         });
+
+        // `:0r` inserts above the first line.
+        let above_first_line = matches!(
+            self.range,
+            Some(CommandRange {
+                start: Position::Line { row: 0, offset: 0 },
+                end: None,
+            })
+        );
+        let position = vim.update_editor(cx, |vim, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            if above_first_line {
+                return anyhow::Ok((snapshot.anchor_before(Point::zero()), false));
+            }
+            let row = if let Some(range) = &self.range {
+                range.buffer_range(vim, editor, window, cx)?.end.0
+            } else {
+                editor
+                    .selections
+                    .newest::<Point>(&editor.display_snapshot(cx))
+                    .head()
+                    .row
+            };
+            let row = row.min(snapshot.max_point().row);
+            let after_last_line = row == snapshot.max_point().row;
+            let point = if after_last_line {
+                snapshot.max_point()
+            } else {
+                Point::new(row + 1, 0)
+            };
+            anyhow::Ok((snapshot.anchor_before(point), after_last_line))
+        });
+        let Some(position) = position else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let (position, after_last_line) = position?;
+            let error_context = || format!("Can't open file {}", path.display());
+            let (worktree, path_in_worktree) = worktree.await.with_context(error_context)?;
+            let buffer = project
+                .update(cx, |project, cx| {
+                    let worktree_id = worktree.read(cx).id();
+                    project.open_buffer((worktree_id, path_in_worktree), cx)
+                })?
+                .await
+                .with_context(error_context)?;
+            let mut text = buffer
+                .read_with(cx, |buffer, _| {
+                    // Opening a missing file yields a new empty buffer instead of an error.
+                    let exists = buffer.file().is_some_and(|file| file.disk_state().exists());
+                    exists.then(|| buffer.text())
+                })?
+                .with_context(error_context)?;
+            if after_last_line {
+                text.insert(0, '\n');
+                if text.ends_with('\n') {
+                    text.pop();
+                }
+            } else if !text.ends_with('\n') {
+                text.push('\n');
+            }
+
+            vim.update_in(cx, |vim, window, cx| {
+                vim.update_editor(cx, |_, editor, cx| {
+                    editor.transact(window, cx, |editor, window, cx| {
+                        editor.edit([(position..position, text)], cx);
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let mut point = position.to_point(&snapshot);
+                        if after_last_line {
+                            point = Point::new(point.row + 1, 0);
+                        }
+                        editor.change_selections(Default::default(), window, cx, |s| {
+                            s.select_ranges([point..point]);
+                        })
+                    })
+                });
+            })
+        })
+        .detach_and_prompt_err("Failed to :read", window, cx, |_, _, _| None);
     }
 }
 
@@ -2897,4 +3461,106 @@ mod test {
             );
         });
     }
+
+    #[gpui::test]
+    async fn test_command_read(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/lines.txt"), b"one\ntwo\n".to_vec())
+            .await;
+
+        cx.set_state(
+            indoc! {"
+                ˇa
+                b"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": r space dir/lines.txt");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                a
+                ˇone
+                two
+                b"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": $ r space dir/lines.txt");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                a
+                one
+                two
+                b
+                ˇone
+                two"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": 0 r space dir/lines.txt");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                ˇone
+                two
+                a
+                one
+                two
+                b
+                one
+                two"},
+            Mode::Normal,
+        );
+
+        // Missing files are reported rather than read as empty.
+        cx.simulate_keystrokes(": r space dir/missing.txt");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                ˇone
+                two
+                a
+                one
+                two
+                b
+                one
+                two"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_bufdo(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let fs = cx.workspace(|workspace, _, cx| workspace.project().read(cx).fs().clone());
+        fs.as_fake()
+            .insert_file(path!("/root/dir/file2.rs"), b"two".to_vec())
+            .await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes(": e space dir/file2.rs");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, _, cx| assert_eq!(workspace.items(cx).count(), 2));
+
+        cx.simulate_keystrokes(": bufdo space norm space A !");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+
+        cx.workspace(|workspace, _, cx| {
+            let mut texts = workspace
+                .items_of_type::<Editor>(cx)
+                .map(|editor| editor.read(cx).text(cx))
+                .collect::<Vec<_>>();
+            texts.sort();
+            assert_eq!(texts, ["one!", "two!"]);
+        });
+    }
 }
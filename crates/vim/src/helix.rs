@@ -1,25 +1,31 @@
+mod align;
 mod boundary;
 mod duplicate;
+mod filter;
 mod object;
 mod paste;
+mod pipe;
+mod rotate;
 mod select;
 
 use editor::display_map::DisplaySnapshot;
 use editor::{
-    DisplayPoint, Editor, EditorSettings, HideMouseCursorOrigin, SelectionEffects, ToOffset,
-    ToPoint, movement,
+    Anchor, DisplayPoint, Editor, EditorSettings, HideMouseCursorOrigin, SelectionEffects,
+    ToOffset, ToPoint, movement,
 };
 use gpui::actions;
 use gpui::{Context, Window};
 use language::{CharClassifier, CharKind, Point};
 use search::{BufferSearchBar, SearchOptions};
 use settings::Settings;
-use text::{Bias, SelectionGoal};
+use text::{Bias, Selection, SelectionGoal};
 use workspace::searchable;
 use workspace::searchable::FilteredSearchRange;
 
+pub(crate) use pipe::HelixPipe;
+
 use crate::motion::{self, MotionKind};
-use crate::state::SearchState;
+use crate::state::{HelixRegexSearch, SearchState};
 use crate::{
     Vim,
     motion::{Motion, right},
@@ -41,6 +47,26 @@ actions!(
         HelixSelectLine,
         /// Select all matches of a given pattern within the current selection.
         HelixSelectRegex,
+        /// Splits the selections on matches of a given pattern.
+        HelixSplitRegex,
+        /// Keeps only the selections that match a given pattern.
+        HelixKeepMatching,
+        /// Removes the selections that match a given pattern.
+        HelixRemoveMatching,
+        /// Aligns the selections by inserting spaces before them.
+        HelixAlignSelections,
+        /// Makes the next selection the primary one.
+        HelixRotateSelectionsForward,
+        /// Makes the previous selection the primary one.
+        HelixRotateSelectionsBackward,
+        /// Moves the contents of each selection into the next selection.
+        HelixRotateSelectionContentsForward,
+        /// Moves the contents of each selection into the previous selection.
+        HelixRotateSelectionContentsBackward,
+        /// Prompts for a shell command to pipe each selection through.
+        HelixPipeSelections,
+        /// Prompts for a shell command whose output is inserted before each selection.
+        HelixInsertOutput,
         /// Removes all but the one selection that was created last.
         /// `Newest` can eventually be `Primary`.
         HelixKeepNewestSelection,
@@ -62,7 +88,52 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::helix_yank);
     Vim::action(editor, cx, Vim::helix_goto_last_modification);
     Vim::action(editor, cx, Vim::helix_paste);
-    Vim::action(editor, cx, Vim::helix_select_regex);
+    Vim::action(editor, cx, |vim, _: &HelixSelectRegex, window, cx| {
+        vim.helix_regex_prompt(HelixRegexSearch::Select, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &HelixSplitRegex, window, cx| {
+        vim.helix_regex_prompt(HelixRegexSearch::Split, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &HelixKeepMatching, window, cx| {
+        vim.helix_regex_prompt(HelixRegexSearch::Keep, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &HelixRemoveMatching, window, cx| {
+        vim.helix_regex_prompt(HelixRegexSearch::Remove, window, cx);
+    });
+    Vim::action(editor, cx, Vim::helix_align_selections);
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionsForward, window, cx| {
+            let count = Vim::take_count(cx).unwrap_or(1);
+            vim.helix_rotate_selections(count, false, window, cx);
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionsBackward, window, cx| {
+            let count = Vim::take_count(cx).unwrap_or(1);
+            vim.helix_rotate_selections(count, true, window, cx);
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionContentsForward, window, cx| {
+            let count = Vim::take_count(cx).unwrap_or(1);
+            vim.helix_rotate_selection_contents(count, false, window, cx);
+        },
+    );
+    Vim::action(
+        editor,
+        cx,
+        |vim, _: &HelixRotateSelectionContentsBackward, window, cx| {
+            let count = Vim::take_count(cx).unwrap_or(1);
+            vim.helix_rotate_selection_contents(count, true, window, cx);
+        },
+    );
+    pipe::register(editor, cx);
     Vim::action(editor, cx, Vim::helix_keep_newest_selection);
     Vim::action(editor, cx, |vim, _: &HelixDuplicateBelow, window, cx| {
         let times = Vim::take_count(cx);
@@ -400,9 +471,11 @@ impl Vim {
         self.switch_mode(Mode::Insert, false, window, cx);
     }
 
-    fn helix_select_regex(
+    /// Opens the search bar to prompt for a regex that is applied to the selections once the
+    /// search is submitted.
+    fn helix_regex_prompt(
         &mut self,
-        _: &HelixSelectRegex,
+        kind: HelixRegexSearch,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
@@ -432,7 +505,8 @@ impl Vim {
                         Some(FilteredSearchRange::Selection),
                         window,
                         cx,
-                    ) {
+                    ) && kind == HelixRegexSearch::Select
+                    {
                         cx.spawn_in(window, async move |search_bar, cx| {
                             if search.await.is_ok() {
                                 search_bar.update_in(cx, |search_bar, window, cx| {
@@ -450,7 +524,7 @@ impl Vim {
                         prior_selections,
                         prior_operator: self.operator_stack.last().cloned(),
                         prior_mode: self.mode,
-                        is_helix_regex_search: true,
+                        helix_regex_search: Some(kind),
                     }
                 });
             }
@@ -550,6 +624,50 @@ impl Vim {
         self.switch_mode(Mode::HelixNormal, true, window, cx);
     }
 
+    /// Replaces the text of each selection and selects the new text, keeping which selection is
+    /// the newest and which ones are reversed.
+    fn helix_replace_selections(
+        &mut self,
+        edits: Vec<(Selection<Anchor>, String)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(window, cx, |editor, window, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let mut edits = edits
+                    .into_iter()
+                    .map(|(selection, text)| {
+                        (selection.map(|anchor| anchor.to_offset(&snapshot)), text)
+                    })
+                    .collect::<Vec<_>>();
+                edits.sort_by_key(|(selection, _)| selection.start);
+
+                let mut delta = 0isize;
+                let mut new_selections = Vec::new();
+                for (selection, text) in &edits {
+                    let start = (selection.start as isize + delta) as usize;
+                    new_selections.push(Selection {
+                        start,
+                        end: start + text.len(),
+                        ..selection.clone()
+                    });
+                    delta += text.len() as isize - selection.range().len() as isize;
+                }
+
+                editor.edit(
+                    edits
+                        .into_iter()
+                        .map(|(selection, text)| (selection.range(), text)),
+                    cx,
+                );
+                editor.change_selections(Default::default(), window, cx, |s| {
+                    s.select(new_selections);
+                });
+            });
+        });
+    }
+
     pub fn helix_goto_last_modification(
         &mut self,
         _: &HelixGotoLastModification,
@@ -0,0 +1,170 @@
+use anyhow::anyhow;
+use editor::{MultiBufferSnapshot, SelectionEffects};
+use gpui::Context;
+use language::Point;
+use text::SelectionGoal;
+use ui::Window;
+use workspace::notifications::NotifyResultExt;
+
+use crate::{Vim, helix::HelixAlignSelections};
+
+impl Vim {
+    /// Inserts spaces before the selections so that the heads of the selections on each line
+    /// line up with those of the selections at the same position on the other lines.
+    /// Follows helix convention.
+    pub(super) fn helix_align_selections(
+        &mut self,
+        _: &HelixAlignSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        Vim::take_count(cx);
+        let result = self.update_editor(cx, |_, editor, cx| {
+            let display_map = editor.display_snapshot(cx);
+            let selections = editor.selections.all::<Point>(&display_map);
+            let snapshot = display_map.buffer_snapshot();
+
+            // The selections of each line, as (start, head column) pairs.
+            let mut lines: Vec<Vec<(Point, u32)>> = Vec::new();
+            let mut last_row = None;
+            for selection in &selections {
+                if selection.start.row != selection.end.row {
+                    return Err(anyhow!("align cannot work with multi line selections"));
+                }
+                if last_row != Some(selection.start.row) {
+                    lines.push(Vec::new());
+                    last_row = Some(selection.start.row);
+                }
+                let head = selection.head();
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .push((selection.start, char_column(snapshot, head)));
+            }
+
+            let columns = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+            let mut inserted = vec![0; lines.len()];
+            let mut edits = Vec::new();
+            for column in 0..columns {
+                let max_column = lines
+                    .iter()
+                    .zip(&inserted)
+                    .filter_map(|(line, inserted)| Some(line.get(column)?.1 + inserted))
+                    .max()
+                    .unwrap_or(0);
+                for (line, inserted) in lines.iter().zip(&mut inserted) {
+                    let Some((start, head_column)) = line.get(column) else {
+                        continue;
+                    };
+                    let count = max_column - (head_column + *inserted);
+                    if count > 0 {
+                        *inserted += count;
+                        edits.push((*start..*start, " ".repeat(count as usize)));
+                    }
+                }
+            }
+            if edits.is_empty() {
+                return Ok(());
+            }
+            edits.sort_by_key(|(range, _)| range.start);
+
+            // Keep the inserted spaces out of the selections.
+            let shift = |point: Point| {
+                edits
+                    .iter()
+                    .filter(|(range, _)| range.start.row == point.row && range.start <= point)
+                    .map(|(_, text)| text.len() as u32)
+                    .sum::<u32>()
+            };
+            let new_selections = selections
+                .iter()
+                .map(|selection| {
+                    let mut selection = selection.clone();
+                    selection.start.column += shift(selection.start);
+                    selection.end.column += shift(selection.end);
+                    selection.goal = SelectionGoal::None;
+                    selection
+                })
+                .collect::<Vec<_>>();
+
+            editor.transact(window, cx, |editor, window, cx| {
+                editor.edit(edits, cx);
+                editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                    s.select(new_selections);
+                });
+            });
+            Ok(())
+        });
+
+        if let Some(Err(error)) = result
+            && let Some(workspace) = self.workspace(window)
+        {
+            workspace.update(cx, |workspace, cx| {
+                Err::<(), _>(error).notify_err(workspace, cx);
+            });
+        }
+    }
+}
+
+fn char_column(snapshot: &MultiBufferSnapshot, point: Point) -> u32 {
+    snapshot
+        .text_for_range(Point::new(point.row, 0)..point)
+        .flat_map(|chunk| chunk.chars())
+        .count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_align_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state(
+            indoc! {"
+            let x «=ˇ» 1;
+            let long «=ˇ» 2;"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("&");
+        cx.assert_state(
+            indoc! {"
+            let x    «=ˇ» 1;
+            let long «=ˇ» 2;"},
+            Mode::HelixNormal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            a «=ˇ» 1 «;ˇ»
+            bbb «=ˇ» 22 «;ˇ»"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("&");
+        cx.assert_state(
+            indoc! {"
+            a   «=ˇ» 1  «;ˇ»
+            bbb «=ˇ» 22 «;ˇ»"},
+            Mode::HelixNormal,
+        );
+
+        // Multi-line selections can't be aligned.
+        cx.set_state(
+            indoc! {"
+            a «=
+            bbbˇ» = 2"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("&");
+        cx.assert_state(
+            indoc! {"
+            a «=
+            bbbˇ» = 2"},
+            Mode::HelixNormal,
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::mem;
+
+use editor::{SelectionEffects, ToOffset};
+use gpui::Context;
+use regex::RegexBuilder;
+use search::{BufferSearchBar, SearchOptions};
+use text::{Selection, SelectionGoal};
+use ui::Window;
+use workspace::notifications::NotifyResultExt;
+
+use crate::{Vim, state::HelixRegexSearch};
+
+impl Vim {
+    /// Applies the regex from the search bar to the selections the search was started from.
+    /// Follows helix convention: if no selection would remain, the selections are left unchanged.
+    pub(crate) fn helix_filter_selections(
+        &mut self,
+        kind: HelixRegexSearch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        let prior_selections = mem::take(&mut self.search.prior_selections);
+        self.search.helix_regex_search = None;
+
+        let Some((query, case_sensitive)) = pane.update(cx, |pane, cx| {
+            let search_bar = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()?;
+            Some(search_bar.update(cx, |search_bar, cx| {
+                let query = search_bar.query(cx);
+                let case_sensitive = search_bar.has_search_option(SearchOptions::CASE_SENSITIVE);
+                search_bar.dismiss(&Default::default(), window, cx);
+                (query, case_sensitive)
+            }))
+        }) else {
+            return;
+        };
+
+        let regex = if query.is_empty() {
+            None
+        } else {
+            let regex = RegexBuilder::new(&query)
+                .case_insensitive(!case_sensitive)
+                .build();
+            if let Err(error) = &regex
+                && let Some(workspace) = self.workspace(window)
+            {
+                workspace.update(cx, |workspace, cx| {
+                    Err::<(), _>(error.clone()).notify_err(workspace, cx);
+                });
+            }
+            regex.ok()
+        };
+
+        self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let prior_ranges = prior_selections
+                .iter()
+                .map(|range| {
+                    let tail = range.start.to_offset(&snapshot);
+                    let head = range.end.to_offset(&snapshot);
+                    (tail.min(head)..tail.max(head), head < tail)
+                })
+                .collect::<Vec<_>>();
+
+            let mut new_ranges = Vec::new();
+            if let Some(regex) = regex {
+                for (range, reversed) in &prior_ranges {
+                    let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                    match kind {
+                        HelixRegexSearch::Split => {
+                            if range.is_empty() {
+                                new_ranges.push((range.clone(), *reversed));
+                                continue;
+                            }
+                            let mut start = 0;
+                            for found in regex.find_iter(&text) {
+                                if found.start() > start {
+                                    new_ranges.push((
+                                        range.start + start..range.start + found.start(),
+                                        *reversed,
+                                    ));
+                                }
+                                start = found.end();
+                            }
+                            if start < text.len() {
+                                new_ranges.push((range.start + start..range.end, *reversed));
+                            }
+                        }
+                        HelixRegexSearch::Keep | HelixRegexSearch::Remove => {
+                            if regex.is_match(&text) == (kind == HelixRegexSearch::Keep) {
+                                new_ranges.push((range.clone(), *reversed));
+                            }
+                        }
+                        HelixRegexSearch::Select => {}
+                    }
+                }
+            }
+            if new_ranges.is_empty() {
+                new_ranges = prior_ranges;
+            }
+
+            editor.change_selections(SelectionEffects::default(), window, cx, |s| {
+                let selections = new_ranges
+                    .into_iter()
+                    .map(|(range, reversed)| Selection {
+                        id: s.new_selection_id(),
+                        start: range.start,
+                        end: range.end,
+                        reversed,
+                        goal: SelectionGoal::None,
+                    })
+                    .collect();
+                s.select(selections);
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_split_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("«one, two,three ˇ»four", Mode::HelixNormal);
+        cx.simulate_keystrokes("S , space *");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ», «twoˇ»,«three ˇ»four", Mode::HelixNormal);
+
+        cx.set_state(
+            indoc! {"
+            «one
+            two
+            threeˇ»"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("S \\ n");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state(
+            indoc! {"
+            «oneˇ»
+            «twoˇ»
+            «threeˇ»"},
+            Mode::HelixNormal,
+        );
+
+        // Splitting on a pattern that covers everything leaves the selections alone.
+        cx.set_state("«oneˇ» two", Mode::HelixNormal);
+        cx.simulate_keystrokes("S o n e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ» two", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_keep_and_remove_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("K t");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("one «twoˇ» «threeˇ»", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-k t");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ» two three", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("K ^ t w");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("one «twoˇ» three", Mode::HelixNormal);
+
+        // Removing every selection leaves the selections alone.
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-k .");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+    }
+}
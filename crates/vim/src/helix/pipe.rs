@@ -0,0 +1,318 @@
+use std::process::Stdio;
+
+use anyhow::{Result, anyhow};
+use editor::Editor;
+use futures::AsyncWriteExt as _;
+use gpui::{Action, App, Context, Entity, Task, Window};
+use project::Project;
+use text::Selection;
+use util::ResultExt as _;
+use workspace::notifications::DetachAndPromptErr;
+
+use crate::{
+    Vim,
+    helix::{HelixInsertOutput, HelixPipeSelections},
+};
+
+/// Pipes each selection through a shell command and replaces it with the output, or inserts the
+/// output of a shell command before each selection.
+#[derive(Clone, Debug, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+pub struct HelixPipe {
+    pub(crate) command: String,
+    pub(crate) replace: bool,
+}
+
+pub(super) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, _: &HelixPipeSelections, window, cx| {
+        vim.helix_prompt_shell_command("pipe ", window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &HelixInsertOutput, window, cx| {
+        vim.helix_prompt_shell_command("insert-output ", window, cx);
+    });
+    Vim::action(editor, cx, |vim, action: &HelixPipe, window, cx| {
+        action.run(vim, window, cx).detach_and_prompt_err(
+            &format!("Failed to run `{}`", action.command),
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    });
+}
+
+impl Vim {
+    fn helix_prompt_shell_command(
+        &mut self,
+        command: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            command_palette::CommandPalette::toggle(workspace, command, window, cx);
+        });
+    }
+}
+
+impl HelixPipe {
+    fn run(&self, vim: &mut Vim, window: &mut Window, cx: &mut Context<Vim>) -> Task<Result<()>> {
+        let Some(workspace) = vim.workspace(window) else {
+            return Task::ready(Ok(()));
+        };
+        let project = workspace.read(cx).project().clone();
+        let replace = self.replace;
+        let Some(selections) = vim.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            editor
+                .selections
+                .disjoint_anchors_arc()
+                .iter()
+                .map(|selection| {
+                    let input = replace.then(|| {
+                        snapshot
+                            .text_for_range(selection.start..selection.end)
+                            .collect::<String>()
+                    });
+                    (selection.clone(), input)
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return Task::ready(Ok(()));
+        };
+
+        // The output of a command without input is the same for every selection.
+        let outputs = if replace {
+            selections
+                .iter()
+                .map(|(_, input)| run_shell_command(&project, &self.command, input.clone(), cx))
+                .collect::<Vec<_>>()
+        } else {
+            vec![run_shell_command(&project, &self.command, None, cx)]
+        };
+
+        cx.spawn_in(window, async move |vim, cx| {
+            let outputs = futures::future::try_join_all(outputs).await?;
+            let edits = selections
+                .into_iter()
+                .enumerate()
+                .map(|(ix, (selection, input))| {
+                    let output = outputs.get(ix).or(outputs.first()).cloned();
+                    let output = trim_output(input.as_deref(), output.unwrap_or_default());
+                    if replace {
+                        (selection, output)
+                    } else {
+                        let start = selection.start;
+                        (
+                            Selection {
+                                start,
+                                end: start,
+                                ..selection
+                            },
+                            output,
+                        )
+                    }
+                })
+                .collect::<Vec<_>>();
+            vim.update_in(cx, |vim, window, cx| {
+                vim.helix_replace_selections(edits, window, cx);
+            })
+        })
+    }
+}
+
+fn run_shell_command(
+    project: &Entity<Project>,
+    command: &str,
+    input: Option<String>,
+    cx: &mut App,
+) -> Task<Result<String>> {
+    let process = project.update(cx, |project, cx| {
+        project.exec_in_shell(command.to_string(), cx)
+    });
+    cx.spawn(async move |cx| {
+        let mut process = process.await?;
+        process.stdout(Stdio::piped());
+        process.stderr(Stdio::piped());
+        if input.is_some() {
+            process.stdin(Stdio::piped());
+        } else {
+            process.stdin(Stdio::null());
+        }
+        let mut running = process.spawn()?;
+
+        if let Some(mut stdin) = running.stdin.take()
+            && let Some(input) = input
+        {
+            cx.background_spawn(async move {
+                if stdin.write_all(input.as_bytes()).await.log_err().is_some() {
+                    stdin.flush().await.log_err();
+                }
+            })
+            .detach();
+        }
+
+        let output = cx.background_spawn(running.output()).await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim_end()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+}
+
+/// Drops the trailing newline most commands print when the input didn't end with one, so that
+/// piping part of a line doesn't break it in two.
+fn trim_output(input: Option<&str>, mut output: String) -> String {
+    if input.is_some_and(|input| !input.ends_with('\n')) && output.ends_with('\n') {
+        output.pop();
+        if output.ends_with('\r') {
+            output.pop();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use command_palette::CommandPalette;
+    use editor::EditorEvent;
+    use gpui::Action as _;
+    use indoc::indoc;
+
+    use crate::{
+        command::command_interceptor,
+        helix::pipe::{HelixPipe, trim_output},
+        state::Mode,
+        test::VimTestContext,
+    };
+
+    #[gpui::test]
+    async fn test_pipe_prompt(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        for keystroke in ["|", "!"] {
+            cx.set_state("«oneˇ» two", Mode::HelixNormal);
+            cx.simulate_keystrokes(keystroke);
+            cx.run_until_parked();
+            assert!(cx.workspace(|workspace, _, cx| {
+                workspace.active_modal::<CommandPalette>(cx).is_some()
+            }));
+            cx.simulate_keystrokes("escape");
+            cx.run_until_parked();
+            assert!(cx.workspace(|workspace, _, cx| {
+                workspace.active_modal::<CommandPalette>(cx).is_none()
+            }));
+        }
+    }
+
+    #[gpui::test]
+    async fn test_pipe_commands(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        for (query, command, replace) in [
+            ("pipe sort -r", "sort -r", true),
+            ("insert-output date", "date", false),
+        ] {
+            let result = cx
+                .workspace(|_, _, cx| command_interceptor(query, cx.entity().downgrade(), cx))
+                .await;
+            let action = result.results.first().map(|item| item.action.boxed_clone());
+            assert_eq!(
+                action
+                    .as_ref()
+                    .and_then(|action| action.as_any().downcast_ref::<HelixPipe>()),
+                Some(&HelixPipe {
+                    command: command.into(),
+                    replace,
+                }),
+                "{query}"
+            );
+        }
+
+        assert_eq!(trim_output(Some("b a"), "a b\n".into()), "a b");
+        assert_eq!(trim_output(Some("b\na\n"), "a\nb\n".into()), "a\nb\n");
+        assert_eq!(trim_output(None, "today\n".into()), "today\n");
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_pipe_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+        // The commands are run by a real shell, so their output arrives in real time, and they
+        // can't run in the fake project directory.
+        cx.executor().allow_parking();
+        cx.workspace(|workspace, _, cx| {
+            workspace.project().update(cx, |project, cx| {
+                let worktree_ids = project
+                    .worktrees(cx)
+                    .map(|worktree| worktree.read(cx).id())
+                    .collect::<Vec<_>>();
+                for worktree_id in worktree_ids {
+                    project.remove_worktree(worktree_id, cx);
+                }
+            })
+        });
+
+        cx.set_state(
+            indoc! {"
+                «one
+                two
+                three
+                ˇ»--
+                «a
+                c
+                b
+                ˇ»"},
+            Mode::HelixNormal,
+        );
+        cx.dispatch_action(HelixPipe {
+            command: "sort -r".into(),
+            replace: true,
+        });
+        let editor = cx.editor.clone();
+        editor
+            .condition::<EditorEvent>(&cx, |editor, cx| editor.text(cx).starts_with("two"))
+            .await;
+        cx.assert_state(
+            indoc! {"
+                «two
+                three
+                one
+                ˇ»--
+                «c
+                b
+                a
+                ˇ»"},
+            Mode::HelixNormal,
+        );
+
+        cx.dispatch_action(HelixPipe {
+            command: "echo out".into(),
+            replace: false,
+        });
+        editor
+            .condition::<EditorEvent>(&cx, |editor, cx| editor.text(cx).starts_with("out"))
+            .await;
+        cx.assert_state(
+            indoc! {"
+                «out
+                ˇ»two
+                three
+                one
+                --
+                «out
+                ˇ»c
+                b
+                a
+                "},
+            Mode::HelixNormal,
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use gpui::Context;
+use ui::Window;
+
+use crate::Vim;
+
+impl Vim {
+    /// Makes the selection `count` places after (or before) the newest selection the newest one.
+    /// Follows helix convention, where the newest selection is the primary one.
+    pub(super) fn helix_rotate_selections(
+        &mut self,
+        count: usize,
+        backwards: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_editor(cx, |_, editor, cx| {
+            let mut selections = editor.selections.all::<usize>(&editor.display_snapshot(cx));
+            let len = selections.len();
+            if len < 2 {
+                return;
+            }
+            let Some(newest) = selections
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, selection)| selection.id)
+                .map(|(ix, _)| ix)
+            else {
+                return;
+            };
+            let count = count % len;
+            let primary = if backwards {
+                (newest + len - count) % len
+            } else {
+                (newest + count) % len
+            };
+            editor.change_selections(Default::default(), window, cx, |s| {
+                selections[primary].id = s.new_selection_id();
+                s.select(selections);
+            });
+        });
+    }
+
+    /// Moves the text of each selection `count` selections forwards (or backwards), wrapping
+    /// around at the last selection.
+    pub(super) fn helix_rotate_selection_contents(
+        &mut self,
+        count: usize,
+        backwards: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(edits) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let selections = editor.selections.disjoint_anchors_arc();
+            let mut texts = selections
+                .iter()
+                .map(|selection| {
+                    snapshot
+                        .text_for_range(selection.start..selection.end)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let count = count % texts.len().max(1);
+            if backwards {
+                texts.rotate_left(count);
+            } else {
+                texts.rotate_right(count);
+            }
+            selections.iter().cloned().zip(texts).collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        if edits.len() < 2 {
+            return;
+        }
+        self.helix_replace_selections(edits, window, cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_rotate_selections(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        // The newest selection is the last one, so `,` keeps whichever one was rotated to.
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes(") ,");
+        cx.assert_state("«oneˇ» two three", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("( ,");
+        cx.assert_state("one «twoˇ» three", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("2 ) ,");
+        cx.assert_state("one «twoˇ» three", Mode::HelixNormal);
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes(") ) ) ,");
+        cx.assert_state("one two «threeˇ»", Mode::HelixNormal);
+    }
+
+    #[gpui::test]
+    async fn test_rotate_selection_contents(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.enable_helix();
+
+        cx.set_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-)");
+        cx.assert_state("«threeˇ» «oneˇ» «twoˇ»", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("alt-(");
+        cx.assert_state("«oneˇ» «twoˇ» «threeˇ»", Mode::HelixNormal);
+
+        cx.simulate_keystrokes("2 alt-(");
+        cx.assert_state("«threeˇ» «oneˇ» «twoˇ»", Mode::HelixNormal);
+
+        cx.set_state(
+            indoc! {"
+            «ˇfn» a(«ˇb»: u8)
+            «letˇ» c = d;"},
+            Mode::HelixNormal,
+        );
+        cx.simulate_keystrokes("alt-)");
+        cx.assert_state(
+            indoc! {"
+            «ˇlet» a(«ˇfn»: u8)
+            «bˇ» c = d;"},
+            Mode::HelixNormal,
+        );
+
+        // The contents of the only selection stay put.
+        cx.set_state("«oneˇ» two", Mode::HelixNormal);
+        cx.simulate_keystrokes("alt-)");
+        cx.assert_state("«oneˇ» two", Mode::HelixNormal);
+    }
+}
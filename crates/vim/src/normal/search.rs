@@ -1,5 +1,6 @@
+use anyhow::Result;
 use editor::{Editor, EditorSettings, VimFlavor};
-use gpui::{Action, Context, Window, actions};
+use gpui::{Action, Context, Task, Window, actions};
 
 use language::Point;
 use schemars::JsonSchema;
@@ -11,10 +12,10 @@ use util::serde::default_true;
 use workspace::{notifications::NotifyResultExt, searchable::Direction};
 
 use crate::{
-    Vim,
+    Vim, VimSettings,
     command::CommandRange,
     motion::Motion,
-    state::{Mode, SearchState},
+    state::{HelixRegexSearch, HistoryKind, Mode, SearchState},
 };
 
 /// Moves to the next search match.
@@ -101,7 +102,10 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     Vim::action(editor, cx, Vim::search);
     Vim::action(editor, cx, Vim::search_deploy);
     Vim::action(editor, cx, Vim::find_command);
-    Vim::action(editor, cx, Vim::replace_command);
+    Vim::action(editor, cx, |vim, action: &ReplaceCommand, window, cx| {
+        vim.replace_command(action, window, cx)
+            .detach_and_log_err(cx)
+    });
 }
 
 impl Vim {
@@ -169,6 +173,8 @@ impl Vim {
                         return;
                     }
 
+                    search_bar
+                        .restore_search_history(Vim::globals(cx).search_history.iter().cloned());
                     search_bar.select_query(window, cx);
                     cx.focus_self(window);
 
@@ -196,7 +202,7 @@ impl Vim {
                         prior_selections,
                         prior_operator: self.operator_stack.last().cloned(),
                         prior_mode,
-                        is_helix_regex_search: false,
+                        helix_regex_search: None,
                     }
                 });
             }
@@ -217,10 +223,18 @@ impl Vim {
         let Some(pane) = self.pane(window, cx) else {
             return;
         };
+        if let Some(kind) = self
+            .search
+            .helix_regex_search
+            .filter(|kind| *kind != HelixRegexSearch::Select)
+        {
+            self.helix_filter_selections(kind, window, cx);
+            return;
+        }
         let new_selections = self.editor_selections(window, cx);
         let result = pane.update(cx, |pane, cx| {
             let search_bar = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()?;
-            if self.search.is_helix_regex_search {
+            if self.search.helix_regex_search.is_some() {
                 search_bar.update(cx, |search_bar, cx| {
                     search_bar.select_all_matches(&Default::default(), window, cx)
                 });
@@ -249,8 +263,13 @@ impl Vim {
                 let prior_mode = self.search.prior_mode;
                 let prior_operator = self.search.prior_operator.take();
 
-                let query = search_bar.query(cx).into();
-                Vim::globals(cx).registers.insert('/', query);
+                let query = search_bar.query(cx);
+                let max_history = VimSettings::get_global(cx).persistence.max_history;
+                let globals = Vim::globals(cx);
+                if !query.is_empty() {
+                    globals.push_history(HistoryKind::Search, &query, max_history);
+                }
+                globals.registers.insert('/', query.into());
                 Some((prior_selections, prior_mode, prior_operator))
             })
         });
@@ -458,19 +477,19 @@ impl Vim {
         })
     }
 
-    fn replace_command(
+    pub(crate) fn replace_command(
         &mut self,
         action: &ReplaceCommand,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
+    ) -> Task<Result<()>> {
         let replacement = action.replacement.clone();
         let Some(((pane, workspace), editor)) = self
             .pane(window, cx)
             .zip(self.workspace(window))
             .zip(self.editor())
         else {
-            return;
+            return Task::ready(Ok(()));
         };
         if let Some(result) = self.update_editor(cx, |vim, editor, cx| {
             let range = action.range.buffer_range(vim, editor, window, cx)?;
@@ -489,7 +508,7 @@ impl Vim {
         let Some(search_bar) = pane.update(cx, |pane, cx| {
             pane.toolbar().read(cx).item_of_type::<BufferSearchBar>()
         }) else {
-            return;
+            return Task::ready(Ok(()));
         };
         let mut options = SearchOptions::REGEX;
         let search = search_bar.update(cx, |search_bar, cx| {
@@ -533,9 +552,11 @@ impl Vim {
                 window,
                 cx,
             );
-            return;
+            return Task::ready(Ok(()));
         }
-        let Some(search) = search else { return };
+        let Some(search) = search else {
+            return Task::ready(Ok(()));
+        };
         let search_bar = search_bar.downgrade();
         cx.spawn_in(window, async move |vim, cx| {
             search.await?;
@@ -568,7 +589,6 @@ impl Vim {
                 search_bar.set_search_options(options, cx);
             })
         })
-        .detach_and_log_err(cx);
     }
 }
 
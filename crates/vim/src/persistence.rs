@@ -0,0 +1,708 @@
+//! Persists registers, recorded macros and the `:`/`/` history across sessions, like Vim's viminfo
+//! and Neovim's ShaDa files, which they can also be imported from.
+
+use crate::{
+    Vim, VimSettings,
+    state::{DB, HistoryKind, Register, ReplayableAction, VimGlobals},
+};
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use gpui::{Action, App, AppContext as _, Task};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, VimPersistenceImport, VimPersistenceScope};
+use std::{collections::VecDeque, ops::Range, path::PathBuf, sync::Arc};
+use util::ResultExt;
+use workspace::WorkspaceId;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PersistenceSettings {
+    pub enabled: bool,
+    pub registers: String,
+    pub macros: bool,
+    pub max_history: usize,
+    pub scope: VimPersistenceScope,
+    pub import_from: VimPersistenceImport,
+}
+
+impl From<settings::VimPersistenceSettingsContent> for PersistenceSettings {
+    fn from(content: settings::VimPersistenceSettingsContent) -> Self {
+        Self {
+            enabled: content.enabled.unwrap(),
+            registers: content.registers.unwrap(),
+            macros: content.macros.unwrap(),
+            max_history: content.max_history.unwrap(),
+            scope: content.scope.unwrap(),
+            import_from: content.import_from.unwrap(),
+        }
+    }
+}
+
+/// Where registers, macros and history are persisted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PersistenceScope {
+    Global,
+    Workspace(WorkspaceId),
+}
+
+impl PersistenceScope {
+    fn workspace_id(self) -> Option<WorkspaceId> {
+        match self {
+            Self::Global => None,
+            Self::Workspace(workspace_id) => Some(workspace_id),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct PersistedState {
+    #[serde(default)]
+    registers: BTreeMap<char, String>,
+    #[serde(default)]
+    recordings: BTreeMap<char, Vec<PersistedAction>>,
+    /// Oldest first.
+    #[serde(default)]
+    command_history: Vec<String>,
+    /// Oldest first.
+    #[serde(default)]
+    search_history: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PersistedAction {
+    Action {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+    },
+    Insertion {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl PersistedAction {
+    fn new(action: &ReplayableAction, cx: &App) -> Option<Self> {
+        match action {
+            ReplayableAction::Action(action) => {
+                let name = action.name();
+                if cx
+                    .build_action(name, None)
+                    .is_ok_and(|built| built.partial_eq(action.as_ref()))
+                {
+                    return Some(Self::Action {
+                        name: name.to_string(),
+                        input: None,
+                    });
+                }
+                // Actions with arguments can't be serialized, but the recorded ones were
+                // dispatched by a key binding whose input builds them again.
+                let input = cx
+                    .key_bindings()
+                    .borrow()
+                    .bindings()
+                    .filter(|binding| binding.action().partial_eq(action.as_ref()))
+                    .find_map(|binding| binding.action_input())?;
+                Some(Self::Action {
+                    name: name.to_string(),
+                    input: Some(serde_json::from_str(&input).ok()?),
+                })
+            }
+            ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(Self::Insertion {
+                text: text.to_string(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+
+    fn build(self, cx: &App) -> Option<ReplayableAction> {
+        match self {
+            Self::Action { name, input } => cx
+                .build_action(&name, input)
+                .log_err()
+                .map(ReplayableAction::Action),
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(ReplayableAction::Insertion {
+                text: Arc::from(text),
+                utf16_range_to_replace,
+            }),
+        }
+    }
+}
+
+impl PersistedState {
+    fn new(globals: &VimGlobals, settings: &PersistenceSettings, cx: &App) -> Self {
+        let registers = globals
+            .registers
+            .iter()
+            .filter(|(name, _)| settings.registers.contains(**name))
+            .map(|(name, register)| (*name, register.text.to_string()))
+            .collect();
+        // Macros with an action that can't be built again are left out rather than persisted
+        // partially.
+        let recordings = if settings.macros {
+            globals
+                .recordings
+                .iter()
+                .filter_map(|(name, actions)| {
+                    let actions = actions
+                        .iter()
+                        .map(|action| PersistedAction::new(action, cx))
+                        .collect::<Option<Vec<_>>>()?;
+                    Some((*name, actions))
+                })
+                .collect()
+        } else {
+            BTreeMap::default()
+        };
+        Self {
+            registers,
+            recordings,
+            command_history: globals.command_history.iter().cloned().collect(),
+            search_history: globals.search_history.iter().cloned().collect(),
+        }
+    }
+
+    fn apply(self, settings: &PersistenceSettings, cx: &mut App) {
+        let recordings = self
+            .recordings
+            .into_iter()
+            .filter_map(|(name, actions)| {
+                let actions = actions
+                    .into_iter()
+                    .map(|action| action.build(cx))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((name, actions))
+            })
+            .collect::<Vec<_>>();
+
+        let globals = Vim::globals(cx);
+        globals
+            .registers
+            .retain(|name, _| !settings.registers.contains(*name));
+        globals.registers.extend(
+            self.registers
+                .into_iter()
+                .filter(|(name, _)| settings.registers.contains(*name))
+                .map(|(name, text)| (name, Register::from(text))),
+        );
+        if settings.macros {
+            globals.recordings.clear();
+            globals.recordings.extend(recordings);
+        }
+        globals.command_history = history_tail(self.command_history, settings.max_history);
+        globals.search_history = history_tail(self.search_history, settings.max_history);
+    }
+}
+
+fn history_tail(history: Vec<String>, max_history: usize) -> VecDeque<String> {
+    let skip = history.len().saturating_sub(max_history);
+    history.into_iter().skip(skip).collect()
+}
+
+/// Loads the registers, macros and history of the workspace's scope, saving those of the
+/// previous scope first. Does nothing if they are already loaded.
+pub(crate) fn activate(workspace_id: Option<WorkspaceId>, cx: &mut App) {
+    let settings = VimSettings::get_global(cx).persistence.clone();
+    if !settings.enabled {
+        return;
+    }
+    let scope = match settings.scope {
+        VimPersistenceScope::Global => PersistenceScope::Global,
+        VimPersistenceScope::Workspace => match workspace_id {
+            Some(workspace_id) => PersistenceScope::Workspace(workspace_id),
+            None => return,
+        },
+    };
+    if Vim::globals(cx).persistence_scope == Some(scope) {
+        return;
+    }
+
+    let save = save(cx);
+    Vim::globals(cx).persistence_scope = Some(scope);
+    cx.spawn(async move |cx| {
+        save.await.log_err();
+        let import_from = settings.import_from;
+        let state = cx
+            .background_spawn(async move {
+                match DB.get_persisted_state(scope.workspace_id())? {
+                    Some(state) => Ok(serde_json::from_str(&state)?),
+                    None => import(import_from),
+                }
+            })
+            .await?;
+        cx.update(|cx| {
+            if Vim::globals(cx).persistence_scope == Some(scope) {
+                state.apply(&settings, cx);
+            }
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Saves the registers, macros and history to the scope they were loaded from.
+pub(crate) fn save(cx: &mut App) -> Task<Result<()>> {
+    let settings = &VimSettings::get_global(cx).persistence;
+    let globals = cx.global::<VimGlobals>();
+    let Some(scope) = globals.persistence_scope.filter(|_| settings.enabled) else {
+        return Task::ready(Ok(()));
+    };
+    let state = PersistedState::new(globals, settings, cx);
+    cx.background_spawn(async move {
+        DB.set_persisted_state(scope.workspace_id(), serde_json::to_string(&state)?)
+            .await
+    })
+}
+
+fn import(import_from: VimPersistenceImport) -> Result<PersistedState> {
+    let path = match import_from {
+        VimPersistenceImport::None => return Ok(PersistedState::default()),
+        VimPersistenceImport::Vim => util::paths::home_dir().join(".viminfo"),
+        VimPersistenceImport::Neovim => neovim_shada_path(),
+    };
+    let contents = match std::fs::read(&path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(PersistedState::default());
+        }
+        Err(error) => return Err(error).with_context(|| format!("reading {path:?}")),
+    };
+    match import_from {
+        VimPersistenceImport::Neovim => {
+            parse_shada(&contents).with_context(|| format!("parsing {path:?}"))
+        }
+        _ => Ok(parse_viminfo(&String::from_utf8_lossy(&contents))),
+    }
+}
+
+fn neovim_shada_path() -> PathBuf {
+    let state_dir = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA")
+            .map(|dir| PathBuf::from(dir).join("nvim-data"))
+            .unwrap_or_else(|| util::paths::home_dir().join("AppData/Local/nvim-data"))
+    } else {
+        std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| util::paths::home_dir().join(".local/state"))
+            .join("nvim")
+    };
+    state_dir.join("shada/main.shada")
+}
+
+/// Parses the registers and history of a `~/.viminfo` file.
+fn parse_viminfo(viminfo: &str) -> PersistedState {
+    enum Section {
+        CommandHistory,
+        SearchHistory,
+        Registers,
+        Other,
+    }
+
+    let mut state = PersistedState::default();
+    let mut section = Section::Other;
+    let mut register: Option<(char, bool, Vec<&str>)> = None;
+    let mut finish_register = |register: Option<(char, bool, Vec<&str>)>,
+                               state: &mut PersistedState| {
+        if let Some((name, linewise, lines)) = register {
+            let mut text = lines.join("\n");
+            if linewise {
+                text.push('\n');
+            }
+            state.registers.insert(name, text);
+        }
+    };
+
+    for line in viminfo.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((_, _, lines)) = register.as_mut() {
+                lines.push(content);
+            }
+            continue;
+        }
+        finish_register(register.take(), &mut state);
+
+        if let Some(header) = line.strip_prefix("# ") {
+            section = if header.starts_with("Command Line History") {
+                Section::CommandHistory
+            } else if header.starts_with("Search String History") {
+                Section::SearchHistory
+            } else if header.starts_with("Registers") {
+                Section::Registers
+            } else {
+                Section::Other
+            };
+            continue;
+        }
+
+        match section {
+            Section::CommandHistory => {
+                if let Some(command) = line.strip_prefix(':') {
+                    state.command_history.push(command.to_string());
+                }
+            }
+            Section::SearchHistory => {
+                // Search entries start with `?` and the separator they were searched with.
+                if let Some(search) = line.strip_prefix('?') {
+                    let search = search
+                        .strip_prefix(['/', '?', ' '])
+                        .unwrap_or(search)
+                        .to_string();
+                    state.search_history.push(search);
+                }
+            }
+            Section::Registers => {
+                // For example `""a\tLINE\t0`, where the second `"` marks the register that the
+                // unnamed register points to.
+                if let Some(header) = line.strip_prefix('"') {
+                    let header = header.strip_prefix('"').unwrap_or(header);
+                    let mut fields = header.split('\t');
+                    let name = fields.next().and_then(|name| name.chars().next());
+                    let linewise = fields.next() == Some("LINE");
+                    if let Some(name) = name {
+                        register = Some((name, linewise, Vec::new()));
+                    }
+                }
+            }
+            Section::Other => {}
+        }
+    }
+    finish_register(register.take(), &mut state);
+
+    // viminfo lists the history newest first.
+    state.command_history.reverse();
+    state.search_history.reverse();
+    state
+}
+
+/// Parses the registers and history of a Neovim ShaDa file, which is a sequence of MessagePack
+/// encoded entries.
+fn parse_shada(mut shada: &[u8]) -> Result<PersistedState> {
+    const HISTORY_ENTRY: u64 = 4;
+    const REGISTER: u64 = 5;
+
+    let mut state = PersistedState::default();
+    let mut history = Vec::new();
+    while !shada.is_empty() {
+        let entry_type = MsgPack::read(&mut shada)?
+            .as_u64()
+            .context("invalid entry type")?;
+        let timestamp = MsgPack::read(&mut shada)?
+            .as_u64()
+            .context("invalid timestamp")?;
+        let len = MsgPack::read(&mut shada)?
+            .as_u64()
+            .context("invalid entry length")? as usize;
+        anyhow::ensure!(len <= shada.len(), "truncated entry");
+        let (mut data, rest) = shada.split_at(len);
+        shada = rest;
+
+        match entry_type {
+            HISTORY_ENTRY => {
+                let MsgPack::Array(items) = MsgPack::read(&mut data)? else {
+                    continue;
+                };
+                let (Some(kind), Some(entry)) = (
+                    items.first().and_then(MsgPack::as_u64),
+                    items.get(1).and_then(MsgPack::as_string),
+                ) else {
+                    continue;
+                };
+                let kind = match kind {
+                    0 => HistoryKind::Command,
+                    1 => HistoryKind::Search,
+                    _ => continue,
+                };
+                history.push((timestamp, kind, entry));
+            }
+            REGISTER => {
+                let MsgPack::Map(fields) = MsgPack::read(&mut data)? else {
+                    continue;
+                };
+                let field = |key: &str| {
+                    fields
+                        .iter()
+                        .find(|(field, _)| field.as_string().as_deref() == Some(key))
+                        .map(|(_, value)| value)
+                };
+                let Some(name) = field("n")
+                    .and_then(MsgPack::as_u64)
+                    .and_then(|name| char::from_u32(name as u32))
+                else {
+                    continue;
+                };
+                let lines = match field("rc") {
+                    Some(MsgPack::Array(lines)) => lines
+                        .iter()
+                        .filter_map(MsgPack::as_string)
+                        .collect::<Vec<_>>(),
+                    _ => continue,
+                };
+                let mut text = lines.join("\n");
+                // Registers are characterwise when their type is left out.
+                if field("rt").and_then(MsgPack::as_u64) == Some(1) {
+                    text.push('\n');
+                }
+                state.registers.insert(name, text);
+            }
+            _ => {}
+        }
+    }
+
+    history.sort_by_key(|(timestamp, _, _)| *timestamp);
+    for (_, kind, entry) in history {
+        match kind {
+            HistoryKind::Command => state.command_history.push(entry),
+            HistoryKind::Search => state.search_history.push(entry),
+        }
+    }
+    Ok(state)
+}
+
+/// The subset of MessagePack that ShaDa files are made of.
+#[derive(Debug, PartialEq)]
+enum MsgPack {
+    Nil,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bytes(Vec<u8>),
+    Array(Vec<MsgPack>),
+    Map(Vec<(MsgPack, MsgPack)>),
+    Ext,
+}
+
+impl MsgPack {
+    fn read(input: &mut &[u8]) -> Result<Self> {
+        let marker = take(input, 1)?[0];
+        Ok(match marker {
+            0x00..=0x7f => Self::UInt(marker as u64),
+            0x80..=0x8f => Self::read_map(input, (marker & 0x0f) as usize)?,
+            0x90..=0x9f => Self::read_array(input, (marker & 0x0f) as usize)?,
+            0xa0..=0xbf => Self::Bytes(take(input, (marker & 0x1f) as usize)?.to_vec()),
+            0xc0 => Self::Nil,
+            0xc2 => Self::Bool(false),
+            0xc3 => Self::Bool(true),
+            0xc4 | 0xd9 => {
+                let len = read_uint(input, 1)? as usize;
+                Self::Bytes(take(input, len)?.to_vec())
+            }
+            0xc5 | 0xda => {
+                let len = read_uint(input, 2)? as usize;
+                Self::Bytes(take(input, len)?.to_vec())
+            }
+            0xc6 | 0xdb => {
+                let len = read_uint(input, 4)? as usize;
+                Self::Bytes(take(input, len)?.to_vec())
+            }
+            0xc7..=0xc9 => {
+                let len = read_uint(input, 1 << (marker - 0xc7))? as usize;
+                take(input, len + 1)?;
+                Self::Ext
+            }
+            0xca => Self::Float(f32::from_bits(read_uint(input, 4)? as u32) as f64),
+            0xcb => Self::Float(f64::from_bits(read_uint(input, 8)?)),
+            0xcc..=0xcf => Self::UInt(read_uint(input, 1 << (marker - 0xcc))?),
+            0xd0 => Self::Int(read_uint(input, 1)? as u8 as i8 as i64),
+            0xd1 => Self::Int(read_uint(input, 2)? as u16 as i16 as i64),
+            0xd2 => Self::Int(read_uint(input, 4)? as u32 as i32 as i64),
+            0xd3 => Self::Int(read_uint(input, 8)? as i64),
+            0xd4..=0xd8 => {
+                take(input, (1 << (marker - 0xd4)) + 1)?;
+                Self::Ext
+            }
+            0xdc => {
+                let len = read_uint(input, 2)? as usize;
+                Self::read_array(input, len)?
+            }
+            0xdd => {
+                let len = read_uint(input, 4)? as usize;
+                Self::read_array(input, len)?
+            }
+            0xde => {
+                let len = read_uint(input, 2)? as usize;
+                Self::read_map(input, len)?
+            }
+            0xdf => {
+                let len = read_uint(input, 4)? as usize;
+                Self::read_map(input, len)?
+            }
+            0xe0..=0xff => Self::Int(marker as i8 as i64),
+            0xc1 => anyhow::bail!("invalid MessagePack marker {marker:#x}"),
+        })
+    }
+
+    fn read_array(input: &mut &[u8], len: usize) -> Result<Self> {
+        let items = (0..len).map(|_| Self::read(input)).collect::<Result<_>>()?;
+        Ok(Self::Array(items))
+    }
+
+    fn read_map(input: &mut &[u8], len: usize) -> Result<Self> {
+        let entries = (0..len)
+            .map(|_| anyhow::Ok((Self::read(input)?, Self::read(input)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self::Map(entries))
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::UInt(value) => Some(*value),
+            Self::Int(value) => u64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_string(&self) -> Option<String> {
+        match self {
+            Self::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        }
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    anyhow::ensure!(input.len() >= len, "unexpected end of MessagePack data");
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Ok(taken)
+}
+
+fn read_uint(input: &mut &[u8], len: usize) -> Result<u64> {
+    Ok(take(input, len)?
+        .iter()
+        .fold(0, |value, byte| (value << 8) | *byte as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_viminfo() {
+        let viminfo = indoc! {"
+            # This viminfo file was generated by Vim 9.1.
+            # Value of 'encoding' when this file was written
+            *encoding=utf-8
+
+            # Command Line History (newest to oldest):
+            :wq
+            |2,0,1700000002,,\"wq\"
+            :s/foo/bar/g
+            |2,0,1700000001,,\"s/foo/bar/g\"
+
+            # Search String History (newest to oldest):
+            ?/needle
+            ? \\<word\\>
+
+            # Registers:
+            \"\"a\tLINE\t0
+            \tfirst line
+            \tsecond line
+            |3,1,0,1,2,0,1700000000,\"first line\",\"second line\"
+            \"b\tCHAR\t0
+            \tword
+
+            # File marks:
+            '0  1  0  ~/notes.txt
+        "};
+
+        assert_eq!(
+            parse_viminfo(viminfo),
+            PersistedState {
+                registers: BTreeMap::from_iter([
+                    ('a', "first line\nsecond line\n".to_string()),
+                    ('b', "word".to_string()),
+                ]),
+                recordings: BTreeMap::default(),
+                command_history: vec!["s/foo/bar/g".to_string(), "wq".to_string()],
+                search_history: vec!["\\<word\\>".to_string(), "needle".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_shada() {
+        fn entry(shada: &mut Vec<u8>, entry_type: u8, timestamp: u8, data: &[u8]) {
+            shada.extend([entry_type, timestamp, data.len() as u8]);
+            shada.extend(data);
+        }
+
+        let mut shada = Vec::new();
+        // A header entry, which is skipped.
+        entry(&mut shada, 1, 0, &[0x81, 0xa1, b'v', 0xa1, b'1']);
+        // Search history entries: [1, "new"] and [1, "old", "/"].
+        entry(&mut shada, 4, 20, &[0x92, 0x01, 0xa3, b'n', b'e', b'w']);
+        entry(
+            &mut shada,
+            4,
+            10,
+            &[0x93, 0x01, 0xa3, b'o', b'l', b'd', 0xa1, b'/'],
+        );
+        // Command history entry: [0, "w"].
+        entry(&mut shada, 4, 15, &[0x92, 0x00, 0xa1, b'w']);
+        // Register entry: {"n": 'q', "rc": ["ab", "c"], "rt": 1}.
+        entry(
+            &mut shada,
+            5,
+            30,
+            &[
+                0x83, 0xa1, b'n', b'q', 0xa2, b'r', b'c', 0x92, 0xc4, 0x02, b'a', b'b', 0xa1, b'c',
+                0xa2, b'r', b't', 0x01,
+            ],
+        );
+
+        assert_eq!(
+            parse_shada(&shada).unwrap(),
+            PersistedState {
+                registers: BTreeMap::from_iter([('q', "ab\nc\n".to_string())]),
+                recordings: BTreeMap::default(),
+                command_history: vec!["w".to_string()],
+                search_history: vec!["old".to_string(), "new".to_string()],
+            }
+        );
+        assert!(parse_shada(&shada[..shada.len() - 1]).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_persisted_state_round_trip(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("\" a y w");
+        cx.simulate_keystrokes("q w d w i x escape q");
+        cx.simulate_keystrokes("\" b y w");
+
+        let state = cx.update(|_, cx| {
+            let settings = PersistenceSettings {
+                registers: "a".to_string(),
+                ..VimSettings::get_global(cx).persistence.clone()
+            };
+            let state = PersistedState::new(cx.global::<VimGlobals>(), &settings, cx);
+            serde_json::to_string(&state).unwrap()
+        });
+        cx.update(|_, cx| {
+            let globals = Vim::globals(cx);
+            globals.registers.clear();
+            globals.recordings.clear();
+            let state: PersistedState = serde_json::from_str(&state).unwrap();
+            let settings = VimSettings::get_global(cx).persistence.clone();
+            state.apply(&settings, cx);
+            assert!(!Vim::globals(cx).registers.contains_key(&'b'));
+        });
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("\" a p");
+        cx.assert_state("ooneˇ ne two three", Mode::Normal);
+        cx.simulate_keystrokes("0 @ w");
+        cx.run_until_parked();
+        cx.assert_state("ˇxne two three", Mode::Normal);
+    }
+}
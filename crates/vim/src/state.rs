@@ -1,6 +1,7 @@
 use crate::command::command_interceptor;
 use crate::motion::MotionKind;
 use crate::normal::repeat::Replayer;
+use crate::persistence::{self, PersistenceScope};
 use crate::surrounds::SurroundsType;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
@@ -24,7 +25,7 @@ use project::{Project, ProjectItem, ProjectPath};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
 use std::{fmt::Display, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
@@ -212,6 +213,12 @@ impl From<String> for Register {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryKind {
+    Command,
+    Search,
+}
+
 #[derive(Default)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
@@ -240,6 +247,13 @@ pub struct VimGlobals {
     pub registers: HashMap<char, Register>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
 
+    /// The `:` commands that were run, oldest first.
+    pub command_history: VecDeque<String>,
+    /// The `/` and `?` searches that were submitted, oldest first.
+    pub search_history: VecDeque<String>,
+    /// The scope that registers, macros and history were loaded from, and are saved to.
+    pub persistence_scope: Option<PersistenceScope>,
+
     pub focused_vim: Option<WeakEntity<Vim>>,
 
     pub marks: HashMap<EntityId, Entity<MarksState>>,
@@ -709,6 +723,14 @@ impl VimGlobals {
         })
         .detach();
 
+        cx.on_app_quit(|cx| {
+            let save = persistence::save(cx);
+            async move {
+                save.await.log_err();
+            }
+        })
+        .detach();
+
         let mut was_enabled = None;
 
         cx.observe_global::<SettingsStore>(move |cx| {
@@ -723,6 +745,13 @@ impl VimGlobals {
                     filter.show_namespace(Vim::NAMESPACE);
                 });
                 GlobalCommandPaletteInterceptor::set(cx, command_interceptor);
+                GlobalCommandPaletteInterceptor::on_confirm(cx, |query, cx| {
+                    let max_history = VimSettings::get_global(cx).persistence.max_history;
+                    let command = query.trim_start_matches(':').trim();
+                    if !command.is_empty() {
+                        Vim::globals(cx).push_history(HistoryKind::Command, command, max_history);
+                    }
+                });
                 for window in cx.windows() {
                     if let Some(workspace) = window.downcast::<Workspace>() {
                         workspace
@@ -736,6 +765,7 @@ impl VimGlobals {
                 }
             } else {
                 KeyBinding::set_vim_mode(cx, false);
+                persistence::save(cx).detach_and_log_err(cx);
                 *Vim::globals(cx) = VimGlobals::default();
                 GlobalCommandPaletteInterceptor::clear(cx);
                 CommandPaletteFilter::update_global(cx, |filter, _| {
@@ -761,6 +791,19 @@ impl VimGlobals {
         .detach();
     }
 
+    /// Adds an entry to the `:` or `/` history, moving it to the end if it was already there.
+    pub(crate) fn push_history(&mut self, kind: HistoryKind, entry: &str, max_history: usize) {
+        let history = match kind {
+            HistoryKind::Command => &mut self.command_history,
+            HistoryKind::Search => &mut self.search_history,
+        };
+        history.retain(|existing| existing != entry);
+        history.push_back(entry.to_string());
+        while history.len() > max_history {
+            history.pop_front();
+        }
+    }
+
     pub(crate) fn write_registers(
         &mut self,
         content: Register,
@@ -854,7 +897,8 @@ impl VimGlobals {
         };
         let lower = register.to_lowercase().next().unwrap_or(register);
         match lower {
-            '_' | ':' | '.' | '#' | '=' => None,
+            ':' => self.command_history.back().cloned().map(Register::from),
+            '_' | '.' | '#' | '=' => None,
             '+' => cx.read_from_clipboard().map(|item| item.into()),
             '*' => {
                 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
@@ -994,7 +1038,20 @@ pub struct SearchState {
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
     pub prior_mode: Mode,
-    pub is_helix_regex_search: bool,
+    pub helix_regex_search: Option<HelixRegexSearch>,
+}
+
+/// The helix command that the search bar is prompting a regex for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HelixRegexSearch {
+    /// Select all matches within the selections.
+    Select,
+    /// Split the selections on matches.
+    Split,
+    /// Keep the selections that match.
+    Keep,
+    /// Remove the selections that match.
+    Remove,
 }
 
 impl Operator {
@@ -1718,6 +1775,12 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_persisted_state(
+                workspace_id INTEGER,
+                state TEXT NOT NULL
+            );
+        ),
     ];
 }
 
@@ -1836,4 +1899,36 @@ impl VimDb {
         })
         .await
     }
+
+    /// Returns the serialized registers, macros and history of the workspace, or the global ones
+    /// when `workspace_id` is `None`.
+    pub(crate) fn get_persisted_state(
+        &self,
+        workspace_id: Option<WorkspaceId>,
+    ) -> Result<Option<String>> {
+        self.select_row_bound(sql!(
+            SELECT state FROM vim_persisted_state
+                WHERE workspace_id IS ?
+        ))?(workspace_id)
+    }
+
+    pub(crate) async fn set_persisted_state(
+        &self,
+        workspace_id: Option<WorkspaceId>,
+        state: String,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_persisted_state
+                WHERE workspace_id IS ?
+            ))?(workspace_id)?;
+            conn.exec_bound(sql!(
+                INSERT INTO vim_persisted_state
+                    (workspace_id, state)
+                VALUES
+                    (?, ?)
+            ))?((workspace_id, state))
+        })
+        .await
+    }
 }
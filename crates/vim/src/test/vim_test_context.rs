@@ -31,6 +31,16 @@ impl VimTestContext {
             project::Project::init_settings(cx);
             theme::init(theme::LoadThemes::JustBase, cx);
             settings_ui::init(cx);
+            // Tests share a database, so registers persisted by one test would leak into others.
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |s| {
+                    s.vim
+                        .get_or_insert_default()
+                        .persistence
+                        .get_or_insert_default()
+                        .enabled = Some(false);
+                });
+            });
         });
     }
 
@@ -13,10 +13,12 @@ mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
 mod replace;
 mod rewrap;
 mod state;
 mod surrounds;
+mod vimrc;
 mod visual;
 
 use collections::HashMap;
@@ -50,6 +52,7 @@ use theme::ThemeSettings;
 use ui::{IntoElement, SharedString, px};
 use vim_mode_setting::HelixModeSetting;
 use vim_mode_setting::VimModeSetting;
+pub use vimrc::{Vimrc, vimrc_key_bindings};
 use workspace::{self, Pane, Workspace};
 
 use crate::{
@@ -271,6 +274,7 @@ pub fn init(cx: &mut App) {
     vim_mode_setting::init(cx);
     VimSettings::register(cx);
     VimGlobals::register(cx);
+    vimrc::init(cx);
 
     cx.observe_new(Vim::register).detach();
 
@@ -635,6 +639,9 @@ impl Vim {
         editor.register_addon(VimAddon {
             entity: vim.clone(),
         });
+        if editor.mode().is_full() {
+            vimrc::apply_options_to_editor(editor, cx);
+        }
 
         vim.update(cx, |_, cx| {
             Vim::action(editor, cx, |vim, _: &SwitchToNormalMode, window, cx| {
@@ -1441,6 +1448,11 @@ impl Vim {
             }
         }
         Vim::globals(cx).focused_vim = Some(cx.entity().downgrade());
+
+        let workspace_id = self
+            .workspace(window)
+            .and_then(|workspace| workspace.read(cx).database_id());
+        persistence::activate(workspace_id, cx);
     }
 
     fn blurred(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -1951,6 +1963,8 @@ struct VimSettings {
     pub custom_digraphs: HashMap<String, Arc<str>>,
     pub highlight_on_yank_duration: u64,
     pub cursor_shape: CursorShapeSettings,
+    pub persistence: persistence::PersistenceSettings,
+    pub vimrc: Option<String>,
 }
 
 /// The settings for cursor shape.
@@ -2005,6 +2019,8 @@ impl Settings for VimSettings {
             custom_digraphs: vim.custom_digraphs.unwrap(),
             highlight_on_yank_duration: vim.highlight_on_yank_duration.unwrap(),
             cursor_shape: vim.cursor_shape.unwrap().into(),
+            persistence: vim.persistence.unwrap().into(),
+            vimrc: vim.vimrc,
         }
     }
 }
@@ -0,0 +1,648 @@
+//! Loads the common subset of a vimrc: key mappings, `let mapleader` and `set` options.
+//!
+//! Mappings become key bindings in the vim mode contexts, which send the mapped keystrokes.
+
+use crate::{
+    Vim, VimAddon, VimSettings,
+    command::{VimOption, set_option},
+};
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use gpui::{App, AppContext as _, Global, KeyBinding, Keystroke, Task};
+use itertools::Itertools as _;
+use serde_json::json;
+use settings::{KeybindSource, KeymapFile, KeymapFileLoadResult, Settings, SettingsStore};
+use std::path::{Path, PathBuf};
+use workspace::{
+    Workspace,
+    notifications::{
+        NotificationId, show_app_notification, simple_message_notification::MessageNotification,
+    },
+};
+
+/// The number of unsupported lines listed in the notification shown after loading a vimrc.
+const MAX_REPORTED_LINES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MapMode {
+    Normal,
+    Visual,
+    OperatorPending,
+    Insert,
+}
+
+impl MapMode {
+    const ALL: [Self; 4] = [
+        Self::Normal,
+        Self::Visual,
+        Self::OperatorPending,
+        Self::Insert,
+    ];
+
+    fn context(self) -> &'static str {
+        match self {
+            Self::Normal => "vim_mode == normal && !menu",
+            Self::Visual => "vim_mode == visual && !menu",
+            Self::OperatorPending => "vim_mode == operator",
+            Self::Insert => "vim_mode == insert",
+        }
+    }
+
+    /// The modes that the mapping commands, such as `nnoremap`, apply to.
+    fn for_command(command: &str) -> Option<&'static [Self]> {
+        use MapMode::*;
+        match command {
+            "map" | "noremap" | "no" | "nor" => Some(&[Normal, Visual, OperatorPending]),
+            "nmap" | "nm" | "nnoremap" | "nn" | "nno" => Some(&[Normal]),
+            "vmap" | "vm" | "vnoremap" | "vn" | "vno" | "xmap" | "xm" | "xnoremap" | "xn"
+            | "xno" => Some(&[Visual]),
+            "omap" | "om" | "onoremap" | "ono" => Some(&[OperatorPending]),
+            "imap" | "im" | "inoremap" | "ino" | "map!" | "noremap!" | "no!" => Some(&[Insert]),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Mapping {
+    modes: Vec<MapMode>,
+    /// The mapped keystrokes, separated by spaces as in keymap files.
+    keystrokes: String,
+    /// The keystrokes sent when the mapping is triggered, or `None` for `<Nop>`.
+    rhs: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct UnsupportedLine {
+    line_number: usize,
+    line: String,
+    reason: String,
+}
+
+/// The mappings and options loaded from the vimrc, with `:source` and with `:map` commands.
+pub struct Vimrc {
+    /// The keystrokes that `<leader>` stands for.
+    leader: String,
+    /// The keystrokes that `<localleader>` stands for.
+    local_leader: String,
+    mappings: Vec<Mapping>,
+    options: Vec<VimOption>,
+}
+
+impl Default for Vimrc {
+    fn default() -> Self {
+        Self {
+            leader: "\\".to_string(),
+            local_leader: "\\".to_string(),
+            mappings: Vec::new(),
+            options: Vec::new(),
+        }
+    }
+}
+
+impl Global for Vimrc {}
+
+impl Vimrc {
+    fn source(&mut self, content: &str) -> Vec<UnsupportedLine> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(ix, line)| {
+                let reason = self.source_line(line).err()?;
+                Some(UnsupportedLine {
+                    line_number: ix + 1,
+                    line: line.trim().to_string(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    /// Runs a single line of the vimrc, returning why it isn't supported if it isn't.
+    fn source_line(&mut self, line: &str) -> Result<(), String> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        if line.is_empty() || line.starts_with('"') {
+            return Ok(());
+        }
+        let (command, args) = line
+            .split_once(char::is_whitespace)
+            .map(|(command, args)| (command, args.trim_start()))
+            .unwrap_or((line, ""));
+
+        match command {
+            "let" => self.let_variable(strip_comment(args)),
+            "set" | "se" | "setglobal" | "setg" => self.set_options(strip_comment(args)),
+            // Syntax highlighting and filetype detection are always on in Zed.
+            "syntax" | "syn" | "filetype" | "filet" => Ok(()),
+            _ => match MapMode::for_command(command) {
+                Some(modes) => self.map(modes, args),
+                None => Err(format!("`{command}` isn't supported")),
+            },
+        }
+    }
+
+    fn let_variable(&mut self, args: &str) -> Result<(), String> {
+        let (name, value) = args
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .ok_or_else(|| "expected `let {name} = {value}`".to_string())?;
+        let value = parse_string(value).ok_or_else(|| format!("invalid string {value}"))?;
+        match name {
+            "mapleader" | "g:mapleader" => self.leader = self.parse_keys(&value)?,
+            "maplocalleader" | "g:maplocalleader" => self.local_leader = self.parse_keys(&value)?,
+            _ => return Err(format!("the variable `{name}` isn't supported")),
+        }
+        Ok(())
+    }
+
+    fn set_options(&mut self, args: &str) -> Result<(), String> {
+        let mut unsupported = Vec::new();
+        for option in args.split_whitespace() {
+            match option {
+                // Zed is never compatible with vi.
+                "nocompatible" | "nocp" => {}
+                _ => match VimOption::from(option) {
+                    Some(option) => self.options.push(option),
+                    None => unsupported.push(option),
+                },
+            }
+        }
+        if unsupported.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "the option {} isn't supported",
+                unsupported
+                    .iter()
+                    .map(|option| format!("`{option}`"))
+                    .join(", ")
+            ))
+        }
+    }
+
+    fn map(&mut self, modes: &[MapMode], mut args: &str) -> Result<(), String> {
+        loop {
+            let lowercase = args.to_ascii_lowercase();
+            if let Some(attribute) = ["<silent>", "<nowait>", "<unique>", "<script>", "<special>"]
+                .into_iter()
+                .find(|attribute| lowercase.starts_with(attribute))
+            {
+                args = args[attribute.len()..].trim_start();
+            } else if let Some(attribute) = ["<buffer>", "<expr>"]
+                .into_iter()
+                .find(|attribute| lowercase.starts_with(attribute))
+            {
+                return Err(format!("{attribute} mappings aren't supported"));
+            } else {
+                break;
+            }
+        }
+
+        let Some((lhs, rhs)) = args
+            .split_once(char::is_whitespace)
+            .map(|(lhs, rhs)| (lhs, rhs.trim()))
+            .filter(|(_, rhs)| !rhs.is_empty())
+        else {
+            return Err("listing mappings isn't supported".to_string());
+        };
+        let keystrokes = self.parse_keys(lhs)?;
+        let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+            None
+        } else {
+            Some(self.parse_keys(rhs)?)
+        };
+
+        // Like in Vim, a mapping replaces the earlier mapping of the same keys in its modes.
+        for mapping in &mut self.mappings {
+            if mapping.keystrokes == keystrokes {
+                mapping.modes.retain(|mode| !modes.contains(mode));
+            }
+        }
+        self.mappings.retain(|mapping| !mapping.modes.is_empty());
+        self.mappings.push(Mapping {
+            modes: modes.to_vec(),
+            keystrokes,
+            rhs,
+        });
+        Ok(())
+    }
+
+    /// Converts keys in Vim's notation, such as `<leader>w` or `<C-w>j`, to keystrokes separated
+    /// by spaces, such as `space w` or `ctrl-w j`.
+    fn parse_keys(&self, keys: &str) -> Result<String, String> {
+        let mut keystrokes = Vec::new();
+        let mut rest = keys;
+        while let Some(char) = rest.chars().next() {
+            if char == '<'
+                && let Some(end) = rest.find('>')
+                && let Some(notation) = self.key_notation(&rest[1..end])?
+            {
+                keystrokes.push(notation);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            keystrokes.push(match char {
+                ' ' => "space".to_string(),
+                _ => char.to_string(),
+            });
+            rest = &rest[char.len_utf8()..];
+        }
+
+        for keystroke in keystrokes
+            .iter()
+            .flat_map(|keystrokes| keystrokes.split(' '))
+        {
+            Keystroke::parse(keystroke).map_err(|_| format!("invalid key `{keystroke}`"))?;
+        }
+        Ok(keystrokes.join(" "))
+    }
+
+    /// Converts the name between `<` and `>`, returning `None` when it isn't a key notation, in
+    /// which case the `<` is a key itself, as in Vim.
+    fn key_notation(&self, notation: &str) -> Result<Option<String>, String> {
+        let lowercase = notation.to_ascii_lowercase();
+        match lowercase.as_str() {
+            "leader" => return Ok(Some(self.leader.clone())),
+            "localleader" => return Ok(Some(self.local_leader.clone())),
+            // `<Cmd>` runs an ex command without changing modes, which the command palette does.
+            "cmd" => return Ok(Some(":".to_string())),
+            "plug" | "sid" | "snr" | "sfile" => {
+                return Err(format!("`<{notation}>` isn't supported"));
+            }
+            _ => {}
+        }
+
+        let mut modifiers = String::new();
+        let mut key = notation;
+        while key.len() > 2 && key.as_bytes()[1] == b'-' {
+            let modifier = match key.as_bytes()[0].to_ascii_lowercase() {
+                b'c' => "ctrl-",
+                b's' => "shift-",
+                b'a' | b'm' => "alt-",
+                b'd' => "cmd-",
+                _ => return Ok(None),
+            };
+            modifiers.push_str(modifier);
+            key = &key[2..];
+        }
+
+        let key = if key.chars().count() == 1 {
+            if modifiers.is_empty() {
+                key.to_string()
+            } else {
+                // `<C-A>` is the same as `<C-a>` in Vim.
+                key.to_ascii_lowercase()
+            }
+        } else {
+            let key = key.to_ascii_lowercase();
+            match key.as_str() {
+                "cr" | "enter" | "return" => "enter",
+                "esc" => "escape",
+                "space" => "space",
+                "tab" => "tab",
+                "bs" => "backspace",
+                "del" => "delete",
+                "up" | "down" | "left" | "right" | "home" | "end" | "pageup" | "pagedown"
+                | "insert" => key.as_str(),
+                "lt" => "<",
+                "bar" => "|",
+                "bslash" => "\\",
+                _ if key.starts_with('f') && key[1..].parse::<u8>().is_ok() => key.as_str(),
+                _ => return Ok(None),
+            }
+            .to_string()
+        };
+        Ok(Some(modifiers + &key))
+    }
+}
+
+/// Strips a trailing `" comment` from a `set` or `let` line.
+fn strip_comment(args: &str) -> &str {
+    match args.find(" \"") {
+        Some(ix) if args[ix + 2..].matches('"').count() % 2 == 0 => args[..ix].trim_end(),
+        _ => args,
+    }
+}
+
+/// Parses a single or double quoted Vim string. Escaped key notations like `"\<Space>"` are kept
+/// as key notations.
+fn parse_string(value: &str) -> Option<String> {
+    if let Some(value) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Some(value.replace("''", "'"));
+    }
+    let value = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char == '\\' {
+            result.push(chars.next()?);
+        } else {
+            result.push(char);
+        }
+    }
+    Some(result)
+}
+
+pub(crate) fn init(cx: &mut App) {
+    let mut loaded_path = None;
+    load_configured_vimrc(&mut loaded_path, cx);
+    cx.observe_global::<SettingsStore>(move |cx| load_configured_vimrc(&mut loaded_path, cx))
+        .detach();
+}
+
+/// Loads the vimrc from the `vim.vimrc` setting when it changes or vim mode is enabled.
+fn load_configured_vimrc(loaded_path: &mut Option<Option<String>>, cx: &mut App) {
+    let path = if Vim::enabled(cx) {
+        VimSettings::get_global(cx).vimrc.clone()
+    } else {
+        None
+    };
+    if loaded_path.as_ref() == Some(&path) {
+        return;
+    }
+    *loaded_path = Some(path.clone());
+
+    cx.set_global(Vimrc::default());
+    if let Some(path) = path {
+        source_file(expand_path(&path, None), cx).detach_and_log_err(cx);
+    }
+}
+
+/// Resolves a path given in the settings or to `:source`, relative to the project's directory.
+pub(crate) fn expand_path(path: &str, project_dir: Option<&Path>) -> PathBuf {
+    if path == "~" {
+        return util::paths::home_dir().clone();
+    }
+    if let Some(path) = path.strip_prefix("~/") {
+        return util::paths::home_dir().join(path);
+    }
+    match project_dir {
+        Some(project_dir) if Path::new(path).is_relative() => project_dir.join(path),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Reads the vimrc at the given path, adding its mappings and options to the ones loaded before.
+pub(crate) fn source_file(path: PathBuf, cx: &mut App) -> Task<Result<()>> {
+    cx.spawn(async move |cx| {
+        let content = cx
+            .background_spawn({
+                let path = path.clone();
+                async move { std::fs::read_to_string(path) }
+            })
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        cx.update(|cx| {
+            let unsupported = cx.default_global::<Vimrc>().source(&content);
+            apply_options(cx);
+            report_unsupported_lines(&path, unsupported, cx);
+        })
+    })
+}
+
+/// Runs a mapping command given in the command palette, such as `:nnoremap <leader>w :w<CR>`.
+pub(crate) fn source_line(line: &str, cx: &mut App) -> Result<()> {
+    cx.default_global::<Vimrc>()
+        .source_line(line)
+        .map_err(|reason| anyhow::anyhow!(reason))
+}
+
+/// The key bindings of the vimrc mappings, which are bound after the default vim key bindings
+/// and before the user's key bindings.
+pub fn vimrc_key_bindings(cx: &mut App) -> Vec<KeyBinding> {
+    if !Vim::enabled(cx) {
+        return Vec::new();
+    }
+    let Some(vimrc) = cx.try_global::<Vimrc>() else {
+        return Vec::new();
+    };
+
+    let sections = MapMode::ALL
+        .into_iter()
+        .filter_map(|mode| {
+            let bindings = vimrc
+                .mappings
+                .iter()
+                .filter(|mapping| mapping.modes.contains(&mode))
+                .map(|mapping| {
+                    let action = match &mapping.rhs {
+                        Some(rhs) => json!(["workspace::SendKeystrokes", rhs]),
+                        None => serde_json::Value::Null,
+                    };
+                    (mapping.keystrokes.clone(), action)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            (!bindings.is_empty())
+                .then(|| json!({ "context": mode.context(), "bindings": bindings }))
+        })
+        .collect::<Vec<_>>();
+    if sections.is_empty() {
+        return Vec::new();
+    }
+
+    let mut key_bindings = match KeymapFile::load(&serde_json::to_string(&sections).unwrap(), cx) {
+        KeymapFileLoadResult::Success { key_bindings } => key_bindings,
+        KeymapFileLoadResult::SomeFailedToLoad {
+            key_bindings,
+            error_message,
+        } => {
+            log::error!("failed to load vimrc mappings: {}", error_message.0);
+            key_bindings
+        }
+        KeymapFileLoadResult::JsonParseFailure { error } => {
+            log::error!("failed to load vimrc mappings: {error}");
+            Vec::new()
+        }
+    };
+    for key_binding in &mut key_bindings {
+        key_binding.set_meta(KeybindSource::User.meta());
+    }
+    key_bindings
+}
+
+/// Applies the options of the vimrc to the vim mode editor.
+pub(crate) fn apply_options_to_editor(editor: &mut Editor, cx: &mut gpui::Context<Editor>) {
+    let Some(vimrc) = cx.try_global::<Vimrc>() else {
+        return;
+    };
+    for option in vimrc.options.clone() {
+        set_option(&option, editor, cx);
+    }
+}
+
+fn apply_options(cx: &mut App) {
+    for window in cx.windows() {
+        let Some(workspace) = window.downcast::<Workspace>() else {
+            continue;
+        };
+        workspace
+            .update(cx, |workspace, _, cx| {
+                for editor in workspace.items_of_type::<Editor>(cx).collect::<Vec<_>>() {
+                    editor.update(cx, |editor, cx| {
+                        if editor.addon::<VimAddon>().is_some() {
+                            apply_options_to_editor(editor, cx);
+                        }
+                    });
+                }
+            })
+            .ok();
+    }
+}
+
+fn report_unsupported_lines(path: &Path, unsupported: Vec<UnsupportedLine>, cx: &mut App) {
+    struct VimrcNotification;
+
+    if unsupported.is_empty() {
+        return;
+    }
+    for line in &unsupported {
+        log::warn!(
+            "{}:{}: skipped `{}`: {}",
+            path.display(),
+            line.line_number,
+            line.line,
+            line.reason
+        );
+    }
+
+    let mut message = format!(
+        "Skipped {} unsupported lines of {}:",
+        unsupported.len(),
+        path.display()
+    );
+    for line in unsupported.iter().take(MAX_REPORTED_LINES) {
+        message.push_str(&format!("\nLine {}: {}", line.line_number, line.reason));
+    }
+    if unsupported.len() > MAX_REPORTED_LINES {
+        message.push_str("\nSee the log for the others.");
+    }
+    show_app_notification(
+        NotificationId::unique::<VimrcNotification>(),
+        cx,
+        move |cx| {
+            let message = message.clone();
+            cx.new(|cx| MessageNotification::new(message, cx))
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{state::Mode, test::VimTestContext};
+    use indoc::indoc;
+    use settings::UseSystemClipboard;
+
+    #[test]
+    fn test_parse_vimrc() {
+        let mut vimrc = Vimrc::default();
+        let unsupported = vimrc.source(indoc! {r#"
+            " Basics
+            set nocompatible
+            syntax on
+            set number relativenumber scrolloff=8 " keep some context
+            set clipboard=unnamedplus
+            let mapleader = "\<Space>"
+
+            nnoremap <silent> <leader>w :w<CR>
+            nmap <C-h> <C-w>h
+            inoremap jk <Esc>
+            vnoremap < <gv
+            map Q <Nop>
+            nnoremap <leader>w :wa<CR>
+            nnoremap <buffer> x y
+            nmap <Plug>(foo) x
+            set tabstop=4 number
+            call plug#begin()
+            nmap
+        "#});
+
+        assert_eq!(
+            vimrc.options,
+            vec![
+                VimOption::Number(true),
+                VimOption::RelativeNumber(true),
+                VimOption::ScrollOff(8),
+                VimOption::Clipboard(UseSystemClipboard::Always),
+                VimOption::Number(true),
+            ]
+        );
+        assert_eq!(vimrc.leader, "space");
+        assert_eq!(
+            vimrc.mappings,
+            vec![
+                Mapping {
+                    modes: vec![MapMode::Normal],
+                    keystrokes: "ctrl-h".to_string(),
+                    rhs: Some("ctrl-w h".to_string()),
+                },
+                Mapping {
+                    modes: vec![MapMode::Insert],
+                    keystrokes: "j k".to_string(),
+                    rhs: Some("escape".to_string()),
+                },
+                Mapping {
+                    modes: vec![MapMode::Visual],
+                    keystrokes: "<".to_string(),
+                    rhs: Some("< g v".to_string()),
+                },
+                Mapping {
+                    modes: vec![MapMode::Normal, MapMode::Visual, MapMode::OperatorPending],
+                    keystrokes: "Q".to_string(),
+                    rhs: None,
+                },
+                Mapping {
+                    modes: vec![MapMode::Normal],
+                    keystrokes: "space w".to_string(),
+                    rhs: Some(": w a enter".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            unsupported
+                .iter()
+                .map(|line| (line.line_number, line.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (14, "<buffer> mappings aren't supported"),
+                (15, "`<Plug>` isn't supported"),
+                (16, "the option `tabstop=4` isn't supported"),
+                (17, "`call` isn't supported"),
+                (18, "listing mappings isn't supported"),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_vimrc_mappings(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.update(|_, cx| {
+            let unsupported = cx.default_global::<Vimrc>().source(indoc! {r#"
+                let mapleader = ","
+                nnoremap <leader>d dd
+                inoremap jk <Esc>
+            "#});
+            assert_eq!(unsupported, Vec::new());
+            let key_bindings = vimrc_key_bindings(cx);
+            cx.bind_keys(key_bindings);
+        });
+
+        cx.set_state(
+            indoc! {"
+                ˇone
+                two"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(", d");
+        cx.run_until_parked();
+        cx.assert_state("ˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes("i j k");
+        cx.run_until_parked();
+        cx.assert_state("ˇtwo", Mode::Normal);
+    }
+}
@@ -20,7 +20,9 @@ use project::debugger::breakpoint_store::{BreakpointState, SourceBreakpoint};
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
-use remote::{RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions};
+use remote::{
+    DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
+};
 use sqlez::{
     bindable::{Bind, Column, StaticColumnCount},
     statement::Statement,
@@ -702,6 +704,9 @@ impl Domain for WorkspaceDb {
         sql!(
             DROP TABLE ssh_connections;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN devcontainer_path TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1115,6 +1120,7 @@ impl WorkspaceDb {
         let mut host = None;
         let mut port = None;
         let mut distro = None;
+        let mut devcontainer_path = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1127,8 +1133,24 @@ impl WorkspaceDb {
                 distro = Some(options.distro_name);
                 user = options.user;
             }
+            RemoteConnectionOptions::Docker(options) => {
+                kind = RemoteConnectionKind::Container(options.runtime);
+                host = Some(options.container);
+                user = options.user;
+                devcontainer_path = options
+                    .devcontainer
+                    .map(|path| path.to_string_lossy().into_owned());
+            }
         }
-        Self::get_or_create_remote_connection_query(this, kind, host, port, user, distro)
+        Self::get_or_create_remote_connection_query(
+            this,
+            kind,
+            host,
+            port,
+            user,
+            distro,
+            devcontainer_path,
+        )
     }
 
     fn get_or_create_remote_connection_query(
@@ -1138,6 +1160,7 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        devcontainer_path: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1147,7 +1170,8 @@ impl WorkspaceDb {
                 host IS ? AND
                 port IS ? AND
                 user IS ? AND
-                distro IS ?
+                distro IS ? AND
+                devcontainer_path IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1155,6 +1179,7 @@ impl WorkspaceDb {
             port,
             user.clone(),
             distro.clone(),
+            devcontainer_path.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1164,10 +1189,18 @@ impl WorkspaceDb {
                     host,
                     port,
                     user,
-                    distro
-                ) VALUES (?1, ?2, ?3, ?4, ?5)
+                    distro,
+                    devcontainer_path
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
                 RETURNING id
-            ))?((kind.serialize(), host, port, user, distro))?
+            ))?((
+                kind.serialize(),
+                host,
+                port,
+                user,
+                distro,
+                devcontainer_path,
+            ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
         }
@@ -1250,15 +1283,22 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro
+                id, kind, host, port, user, distro, devcontainer_path
             FROM
                 remote_connections
         ))?()?
         .into_iter()
-        .filter_map(|(id, kind, host, port, user, distro)| {
+        .filter_map(|(id, kind, host, port, user, distro, devcontainer_path)| {
             Some((
                 RemoteConnectionId(id),
-                Self::remote_connection_from_row(kind, host, port, user, distro)?,
+                Self::remote_connection_from_row(
+                    kind,
+                    host,
+                    port,
+                    user,
+                    distro,
+                    devcontainer_path,
+                )?,
             ))
         })
         .collect())
@@ -1268,13 +1308,13 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro) = self.select_row_bound(sql!(
-            SELECT kind, host, port, user, distro
+        let (kind, host, port, user, distro, devcontainer_path) = self.select_row_bound(sql!(
+            SELECT kind, host, port, user, distro, devcontainer_path
             FROM remote_connections
             WHERE id = ?
         ))?(id.0)?
         .context("no such remote connection")?;
-        Self::remote_connection_from_row(kind, host, port, user, distro)
+        Self::remote_connection_from_row(kind, host, port, user, distro, devcontainer_path)
             .context("invalid remote_connection row")
     }
 
@@ -1284,6 +1324,7 @@ impl WorkspaceDb {
         port: Option<u16>,
         user: Option<String>,
         distro: Option<String>,
+        devcontainer_path: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1296,6 +1337,14 @@ impl WorkspaceDb {
                 username: user,
                 ..Default::default()
             })),
+            RemoteConnectionKind::Container(runtime) => {
+                Some(RemoteConnectionOptions::Docker(DockerConnectionOptions {
+                    container: host?,
+                    runtime,
+                    user,
+                    devcontainer: devcontainer_path.map(PathBuf::from),
+                }))
+            }
         }
     }
 
@@ -2859,6 +2908,18 @@ mod tests {
             );
         }
 
+        let container = DockerConnectionOptions {
+            container: "zed-dev".into(),
+            runtime: remote::ContainerRuntime::Podman,
+            user: Some("vscode".into()),
+            devcontainer: Some(PathBuf::from("/src/zed/.devcontainer/devcontainer.json")),
+        };
+        ids.push(
+            db.get_or_create_remote_connection(RemoteConnectionOptions::Docker(container.clone()))
+                .await
+                .unwrap(),
+        );
+
         let stored_connections = db.remote_connections().unwrap();
         assert_eq!(
             stored_connections,
@@ -2890,6 +2951,7 @@ mod tests {
                         ..Default::default()
                     }),
                 ),
+                (ids[3], RemoteConnectionOptions::Docker(container)),
             ]
             .into_iter()
             .collect::<HashMap<_, _>>(),
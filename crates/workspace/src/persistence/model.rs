@@ -14,7 +14,7 @@ use gpui::{AsyncWindowContext, Entity, WeakEntity};
 
 use language::{Toolchain, ToolchainScope};
 use project::{Project, debugger::breakpoint_store::SourceBreakpoint};
-use remote::RemoteConnectionOptions;
+use remote::{ContainerRuntime, RemoteConnectionOptions};
 use std::{
     collections::BTreeMap,
     path::{Path, PathBuf},
@@ -32,6 +32,7 @@ pub(crate) struct RemoteConnectionId(pub u64);
 pub(crate) enum RemoteConnectionKind {
     Ssh,
     Wsl,
+    Container(ContainerRuntime),
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -75,6 +76,7 @@ impl RemoteConnectionKind {
         match self {
             RemoteConnectionKind::Ssh => "ssh",
             RemoteConnectionKind::Wsl => "wsl",
+            RemoteConnectionKind::Container(runtime) => runtime.serialize(),
         }
     }
 
@@ -82,7 +84,7 @@ impl RemoteConnectionKind {
         match text {
             "ssh" => Some(Self::Ssh),
             "wsl" => Some(Self::Wsl),
-            _ => None,
+            _ => ContainerRuntime::deserialize(text).map(Self::Container),
         }
     }
 }
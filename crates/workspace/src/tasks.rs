@@ -1,9 +1,10 @@
 use std::process::ExitStatus;
 
 use anyhow::Result;
+use futures::FutureExt as _;
 use gpui::{AppContext, Context, Entity, Task};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskExecutionGraph, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
 use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
 use ui::Window;
@@ -55,6 +56,8 @@ impl Workspace {
         cx: &mut Context<Workspace>,
     ) {
         let spawn_in_terminal = resolved_task.resolved.clone();
+        let dependent_task =
+            (!resolved_task.original_task().depends_on.is_empty()).then(|| resolved_task.clone());
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
@@ -65,12 +68,17 @@ impl Workspace {
                     project.task_store().read(cx).task_inventory().cloned()
                 {
                     task_inventory.update(cx, |inventory, _| {
-                        inventory.task_scheduled(task_source_kind, resolved_task);
+                        inventory.task_scheduled(task_source_kind.clone(), resolved_task);
                     })
                 }
             });
         }
 
+        if let Some(dependent_task) = dependent_task {
+            self.schedule_task_with_dependencies(task_source_kind, dependent_task, window, cx);
+            return;
+        }
+
         if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
@@ -98,6 +106,63 @@ impl Workspace {
         }
     }
 
+    /// Runs the tasks the given task depends on first, and the task itself only if all of them succeeded.
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(task_inventory) = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned()
+        else {
+            return;
+        };
+        let worktree_id = match &task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => None,
+        };
+        let graph = task_inventory.read(cx).task_execution_graph(
+            task_source_kind,
+            resolved_task.original_task().clone(),
+            worktree_id,
+            cx,
+        );
+        let task_context = resolved_task.task_context().clone();
+        let template_label = resolved_task.original_task().label.clone();
+        let label = resolved_task.display_label().to_string();
+
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let error = match graph.await {
+                Ok(graph) => run_task_execution_graph(graph, task_context, workspace.clone(), cx)
+                    .await
+                    // The task's own failure is reported by its terminal.
+                    .filter(|failed_task| *failed_task != template_label)
+                    .map(|failed_task| format!("`{failed_task}` failed")),
+                Err(e) => Some(format!("{e:#}")),
+            };
+            if let Some(error) = error {
+                log::error!("Task `{label}` was not run: {error}");
+                workspace
+                    .update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<TaskExecutionGraph>();
+                        workspace.show_toast(
+                            Toast::new(id, format!("Task `{label}` was not run: {error}")),
+                            cx,
+                        );
+                    })
+                    .ok();
+            }
+        });
+        self.scheduled_tasks.push(task);
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
@@ -132,3 +197,47 @@ impl Workspace {
         }
     }
 }
+
+/// Runs every task of the graph once all the tasks it waits for have succeeded.
+/// Returns the label of the task that failed, if the last task of the graph did not succeed.
+async fn run_task_execution_graph(
+    graph: TaskExecutionGraph,
+    task_context: TaskContext,
+    workspace: gpui::WeakEntity<Workspace>,
+    cx: &mut gpui::AsyncWindowContext,
+) -> Option<String> {
+    let mut outcomes = Vec::<futures::future::Shared<Task<Result<(), String>>>>::new();
+    for node in graph.tasks {
+        let prerequisites = node
+            .waits_for
+            .iter()
+            .map(|ix| outcomes[*ix].clone())
+            .collect::<Vec<_>>();
+        let label = node.template.label.clone();
+        let resolved_task = node
+            .template
+            .resolve_task(&node.source_kind.to_id_base(), &task_context);
+        let workspace = workspace.clone();
+        let outcome = cx.spawn(async move |cx| {
+            for prerequisite in prerequisites {
+                prerequisite.await?;
+            }
+            let resolved_task = resolved_task.ok_or_else(|| label.clone())?;
+            if resolved_task.original_task().command.trim().is_empty() {
+                return Ok(());
+            }
+            let status = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.spawn_in_terminal(resolved_task.resolved, window, cx)
+                })
+                .map_err(|_| label.clone())?
+                .await;
+            match status {
+                Some(Ok(status)) if status.success() => Ok(()),
+                _ => Err(label),
+            }
+        });
+        outcomes.push(outcome.shared());
+    }
+    outcomes.pop()?.await.err()
+}
@@ -164,7 +164,7 @@ pub fn main() {
     #[cfg(unix)]
     util::prevent_root_execution();
 
-    let args = Args::parse();
+    let mut args = Args::parse();
 
     // `zed --askpass` Makes zed operate in nc/netcat mode for use with askpass
     #[cfg(not(target_os = "windows"))]
@@ -232,6 +232,12 @@ pub fn main() {
         return;
     }
 
+    // `zed --agent` runs an agent thread headlessly, printing its events to stdout
+    if let Some(agent_args) = args.agent.take() {
+        agent_cli::main(agent_args);
+        return;
+    }
+
     zlog::init();
     if stdout_is_a_pty() {
         zlog::init_output_stdout();
@@ -1316,6 +1322,17 @@ struct Args {
     /// Output current environment variables as JSON to stdout
     #[arg(long, hide = true)]
     printenv: bool,
+
+    /// Runs an agent thread without any UI, printing its events to stdout. The arguments that
+    /// follow are the agent's, see `zed --agent --help`.
+    #[arg(
+        long,
+        num_args = 0..,
+        allow_hyphen_values = true,
+        value_name = "AGENT_ARGS",
+        conflicts_with = "paths_or_urls"
+    )]
+    agent: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -1512,3 +1529,25 @@ fn check_for_conpty_dll() {
         log::warn!("Failed to load conpty.dll. Terminal will work with reduced functionality.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_args() {
+        // A path named `agent` is still opened.
+        let args = Args::try_parse_from(["zed", "agent"]).unwrap();
+        assert_eq!(args.paths_or_urls, ["agent"]);
+        assert_eq!(args.agent, None);
+
+        let args = Args::try_parse_from(["zed", "--agent", "--prompt", "Fix it", "agent"]).unwrap();
+        assert!(args.paths_or_urls.is_empty());
+        assert_eq!(args.agent.unwrap(), ["--prompt", "Fix it", "agent"]);
+
+        let args = Args::try_parse_from(["zed", "--agent"]).unwrap();
+        assert_eq!(args.agent.unwrap(), Vec::<String>::new());
+
+        assert!(Args::try_parse_from(["zed", "file.rs", "--agent"]).is_err());
+    }
+}
@@ -1417,6 +1417,14 @@ pub fn handle_keymap_file_changes(
     let mut old_vim_enabled = VimModeSetting::get_global(cx).0;
     let mut old_helix_enabled = vim_mode_setting::HelixModeSetting::get_global(cx).0;
 
+    cx.observe_global::<vim::Vimrc>({
+        let base_keymap_tx = base_keymap_tx.clone();
+        move |_| {
+            base_keymap_tx.unbounded_send(()).ok();
+        }
+    })
+    .detach();
+
     cx.observe_global::<SettingsStore>(move |cx| {
         let new_base_keymap = *BaseKeymap::get_global(cx);
         let new_vim_enabled = VimModeSetting::get_global(cx).0;
@@ -1611,6 +1619,8 @@ fn show_markdown_app_notification<F>(
 fn reload_keymaps(cx: &mut App, mut user_key_bindings: Vec<KeyBinding>) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
+    let vimrc_key_bindings = vim::vimrc_key_bindings(cx);
+    cx.bind_keys(vimrc_key_bindings);
 
     for key_binding in &mut user_key_bindings {
         key_binding.set_meta(KeybindSource::User.meta());
@@ -215,6 +215,8 @@ pub mod git {
             Branch,
             /// Opens the git stash selector.
             ViewStash,
+            /// Opens the git tag selector.
+            Tags,
             /// Opens the git worktree selector.
             Worktree
         ]
@@ -408,6 +410,15 @@ pub struct OpenRemote {
     pub create_new_window: bool,
 }
 
+/// Reopens the current project inside the container described by its `devcontainer.json`.
+#[derive(PartialEq, Clone, Deserialize, Default, JsonSchema, Action)]
+#[action(namespace = projects)]
+#[serde(deny_unknown_fields)]
+pub struct OpenDevContainer {
+    #[serde(default)]
+    pub create_new_window: bool,
+}
+
 /// Where to spawn the task in the UI.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]